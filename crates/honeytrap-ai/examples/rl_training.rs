@@ -14,8 +14,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         epsilon: 1.0,
         epsilon_decay: 0.995,
         epsilon_min: 0.01,
+        replay_capacity: 10_000,
     };
     let mut agent = RLAgent::with_config(config);
+    let calculator = RewardCalculator::new();
 
     println!("📊 Initial Stats:");
     let stats = agent.get_stats();
@@ -33,6 +35,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             attack_type: (episode % 10) as u8,
             connection_intensity: ((episode / 10) % 10) as u8,
             source_reputation: ((episode / 100) % 10) as u8,
+            behavior: 0,
         };
 
         // Agent chooses action
@@ -47,13 +50,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             Action::Block => (0.5, 0.0, 0.1),
         };
 
-        let reward = RewardCalculator::calculate(&action, info_gained, time_wasted, resources);
+        let reward = calculator.calculate(&action, info_gained, time_wasted, resources);
 
         // Update Q-table
         let next_state = State {
             attack_type: state.attack_type,
             connection_intensity: (state.connection_intensity + 1) % 10,
             source_reputation: state.source_reputation,
+            behavior: 0,
         };
         agent.update(&state, &action, reward, &next_state);
 
@@ -85,10 +89,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🎭 Testing Learned Policy:\n");
     
     let test_states = vec![
-        State { attack_type: 1, connection_intensity: 2, source_reputation: 8 },
-        State { attack_type: 5, connection_intensity: 8, source_reputation: 2 },
-        State { attack_type: 9, connection_intensity: 9, source_reputation: 0 },
-        State { attack_type: 2, connection_intensity: 3, source_reputation: 5 },
+        State { attack_type: 1, connection_intensity: 2, source_reputation: 8, behavior: 0 },
+        State { attack_type: 5, connection_intensity: 8, source_reputation: 2, behavior: 0 },
+        State { attack_type: 9, connection_intensity: 9, source_reputation: 0, behavior: 0 },
+        State { attack_type: 2, connection_intensity: 3, source_reputation: 5, behavior: 0 },
     ];
 
     for (i, state) in test_states.iter().enumerate() {
@@ -2,7 +2,7 @@
 ///
 /// Zeigt wie man den AnomalyDetector mit RandomForest trainiert
 
-use honeytrap_ai::AnomalyDetector;
+use honeytrap_ai::{AnomalyDetector, SyntheticTrafficGenerator};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -16,8 +16,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("📊 Generating training dataset...");
 
-    // Training-Daten generieren
-    let training_data = generate_training_data();
+    // Training-Daten generieren (fester Seed = reproduzierbar)
+    let training_data = SyntheticTrafficGenerator::new(42).training_data();
 
     println!("   Generated {} samples", training_data.len());
     println!("   Features per sample: {}\n", training_data[0].0.len());
@@ -100,96 +100,3 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
-
-/// Training-Daten generieren
-/// Mix aus normalen und anomalen Samples
-fn generate_training_data() -> Vec<(Vec<f64>, bool)> {
-    let mut data = Vec::new();
-
-    // Normal Traffic (Label: false = 0)
-    for i in 0..100 {
-        let features = vec![
-            (1000 + i * 10) as f64,          // source_port
-            8443.0,                           // dest_port
-            10.0 + (i as f64 * 0.5),          // duration
-            0.1,                              // inter_packet_time
-            1000.0 + (i as f64 * 100.0),      // bytes_sent
-            2000.0 + (i as f64 * 150.0),      // bytes_received
-            10.0 + (i as f64 * 0.5),          // packets_sent
-            15.0 + (i as f64 * 0.5),          // packets_received
-            0.0,                              // failed_logins
-            1.0 + (i as f64 * 0.1),           // command_freq
-        ];
-        data.push((features, false)); // Normal
-    }
-
-    // Port Scanning (Label: true = 1)
-    for i in 0..30 {
-        let features = vec![
-            (50000 + i * 100) as f64,    // random high ports
-            8443.0,
-            0.1,                         // sehr kurz
-            0.001,                       // sehr schnell
-            100.0,                       // wenig data
-            50.0,
-            5.0,
-            2.0,
-            0.0,
-            50.0 + (i as f64 * 5.0),     // hohe command_freq
-        ];
-        data.push((features, true)); // Anomaly
-    }
-
-    // Brute Force (Label: true = 1)
-    for i in 0..30 {
-        let features = vec![
-            (40000 + i * 50) as f64,
-            8443.0,
-            5.0 + (i as f64 * 0.2),
-            0.1,
-            5000.0,
-            1000.0,
-            50.0,
-            10.0,
-            20.0 + (i as f64 * 2.0),     // viele failed_logins
-            10.0,
-        ];
-        data.push((features, true)); // Anomaly
-    }
-
-    // DDoS Pattern (Label: true = 1)
-    for i in 0..30 {
-        let features = vec![
-            (30000 + i * 200) as f64,
-            8443.0,
-            0.5,
-            0.0001,                      // extrem schnell
-            100000.0 + (i as f64 * 10000.0), // flood
-            0.0,                         // keine response
-            1000.0 + (i as f64 * 100.0), // viele packets
-            0.0,
-            0.0,
-            100.0 + (i as f64 * 50.0),   // hohe command_freq
-        ];
-        data.push((features, true)); // Anomaly
-    }
-
-    // Legitimate High-Volume Users (Label: false = 0)
-    for i in 0..20 {
-        let features = vec![
-            (2000 + i * 20) as f64,
-            8443.0,
-            120.0 + (i as f64 * 10.0),   // längere sessions
-            0.2,
-            50000.0 + (i as f64 * 5000.0), // viel data aber normal
-            45000.0 + (i as f64 * 4500.0),
-            100.0 + (i as f64 * 5.0),
-            95.0 + (i as f64 * 4.0),
-            0.0,                         // keine failed logins
-            5.0 + (i as f64 * 0.5),
-        ];
-        data.push((features, false)); // Normal
-    }
-
-    data
-}
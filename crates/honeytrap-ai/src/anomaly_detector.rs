@@ -1,21 +1,127 @@
-use crate::llm::{BehaviorAnalysis, LLMClient, SessionData};
+use crate::classifier::Classifier;
+use crate::drift::{DriftConfig, DriftMonitor, DriftReport};
+use crate::llm::{BehaviorAnalysis, LlmBackend, SessionData};
 use crate::random_forest::RandomForestModel;
+use crate::retrain::{RetrainBuffer, RetrainConfig};
+use crate::scaling::StandardScaler;
+use honeytrap_metrics::METRICS;
 use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Benigne Scores so knapp unter dem Schwellwert werden dem LLM ebenfalls
+/// vorgelegt, damit es übersehene Angriffe nachmelden kann
+const LLM_REVIEW_MARGIN: f64 = 0.15;
+
+/// Uneinigkeit zwischen ML-Model und LLM zu einer Session
+#[derive(Debug, Clone, PartialEq)]
+pub struct Disagreement {
+    pub source_ip: String,
+    pub destination_port: u16,
+    pub ml_score: f64,
+    pub llm_threat_score: f64,
+    pub attack_type: String,
+    /// Welche Seite die Session als bösartig einstuft ("ml" oder "llm")
+    pub malicious_by: &'static str,
+}
+
+/// Callback für jede Uneinigkeit (z.B. Weitergabe an die Capture-Pipeline)
+pub type DisagreementHook = Arc<dyn Fn(&Disagreement) + Send + Sync>;
 
-/// Anomalie-Detektor mit RandomForest ML + LLM
+/// Anomalie-Detektor mit austauschbarem ML-Backend (Default: RandomForest) + LLM
+///
+/// Analysen laufen über `&self`: Zähler sind atomar, das Sliding Window wird
+/// nur kurz (ohne `.await`) gesperrt. Damit genügt ein Read-Lock auf einem
+/// geteilten Detektor; Write-Locks braucht nur Training bzw. Model-Laden.
 pub struct AnomalyDetector {
     window_size: usize,
     anomaly_threshold: f64,
-    samples: VecDeque<Vec<f64>>,
-    anomalies_count: u64,
-    total_predictions: u64,
-    llm_client: Option<LLMClient>,
+    window: Mutex<Window>,
+    anomalies_count: AtomicU64,
+    total_predictions: AtomicU64,
+    /// Sessions, bei denen ML-Model und LLM uneinig waren
+    disagreements_count: AtomicU64,
+    disagreement_hook: Option<DisagreementHook>,
+    llm_client: Option<Box<dyn LlmBackend>>,
     
-    /// RandomForest ML Model
-    ml_model: Option<RandomForestModel>,
+    /// ML-Backend
+    ml_model: Box<dyn Classifier>,
     
     /// Verwende ML-Model für Predictions?
     use_ml_model: bool,
+
+    /// Gelabelte Samples seit dem letzten Training
+    retrain: Mutex<RetrainBuffer>,
+
+    /// Live-Features vs. Trainingsverteilung
+    drift: Mutex<DriftMonitor>,
+}
+
+/// Sliding Window über die letzten Samples und Scores
+#[derive(Debug, Default)]
+struct Window {
+    samples: VecDeque<Vec<f64>>,
+    /// Scores der letzten `window_size` Analysen
+    scores: VecDeque<f64>,
+}
+
+impl Window {
+    fn push_sample(&mut self, features: &[f64], window_size: usize) {
+        self.samples.push_back(features.to_vec());
+        if self.samples.len() > window_size {
+            self.samples.pop_front();
+        }
+    }
+
+    fn push_score(&mut self, score: f64, window_size: usize) {
+        self.scores.push_back(score);
+        if self.scores.len() > window_size {
+            self.scores.pop_front();
+        }
+    }
+
+    /// Heuristischer Score: Distanz zum Mittelwert des Windows
+    ///
+    /// Mit Scaler wird in Standardabweichungen gemessen, sonst in Rohwerten.
+    fn heuristic_score(&self, features: &[f64], scaler: Option<&StandardScaler>) -> f64 {
+        if self.samples.len() < 2 {
+            return 0.0;
+        }
+        let scale = |sample: &[f64]| match scaler {
+            Some(scaler) => scaler.transform(sample),
+            None => sample.to_vec(),
+        };
+        let features = scale(features);
+
+        // Durchschnitt der bisherigen Samples
+        let mut avg = vec![0.0; features.len()];
+        for sample in &self.samples {
+            for (i, &val) in scale(sample).iter().enumerate() {
+                if let Some(sum) = avg.get_mut(i) {
+                    *sum += val;
+                }
+            }
+        }
+        for val in &mut avg {
+            *val /= self.samples.len() as f64;
+        }
+
+        // Euklidische Distanz zum Durchschnitt
+        let mut distance = 0.0;
+        for (i, &feature) in features.iter().enumerate() {
+            let diff = feature - avg.get(i).copied().unwrap_or(0.0);
+            distance += diff * diff;
+        }
+        distance = distance.sqrt();
+
+        // Normalisieren (0.0 - 1.0), skaliert: 3 Sigma auf jedem Feature = 1.0
+        let max_distance = match scaler {
+            Some(_) => 3.0 * (features.len().max(1) as f64).sqrt(),
+            None => 100.0, // Heuristic
+        };
+        (distance / max_distance).min(1.0)
+    }
 }
 
 impl AnomalyDetector {
@@ -24,12 +130,19 @@ impl AnomalyDetector {
         Self {
             window_size,
             anomaly_threshold: 0.7,
-            samples: VecDeque::with_capacity(window_size),
-            anomalies_count: 0,
-            total_predictions: 0,
+            window: Mutex::new(Window {
+                samples: VecDeque::with_capacity(window_size),
+                scores: VecDeque::with_capacity(window_size),
+            }),
+            anomalies_count: AtomicU64::new(0),
+            total_predictions: AtomicU64::new(0),
+            disagreements_count: AtomicU64::new(0),
+            disagreement_hook: None,
             llm_client: None,
-            ml_model: Some(RandomForestModel::new()),
+            ml_model: Box::new(RandomForestModel::new()),
             use_ml_model: false, // Erst nach Training aktivieren
+            retrain: Mutex::new(RetrainBuffer::new()),
+            drift: Mutex::new(DriftMonitor::new()),
         }
     }
 
@@ -39,52 +152,108 @@ impl AnomalyDetector {
         self
     }
 
-    /// Mit LLM-Client
-    pub fn with_llm(mut self, llm_client: LLMClient) -> Self {
-        self.llm_client = Some(llm_client);
+    /// Mit LLM-Backend (`LLMClient` oder z.B. `MockLlmBackend` in Tests)
+    pub fn with_llm(mut self, llm_client: impl LlmBackend + 'static) -> Self {
+        self.llm_client = Some(Box::new(llm_client));
+        self
+    }
+
+    /// Uneinigkeiten zwischen ML-Model und LLM zusätzlich an `hook` melden
+    pub fn with_disagreement_hook(mut self, hook: DisagreementHook) -> Self {
+        self.disagreement_hook = Some(hook);
+        self
+    }
+
+    /// Mit eigenem ML-Backend statt RandomForest
+    pub fn with_classifier(mut self, classifier: Box<dyn Classifier>) -> Self {
+        self.use_ml_model = classifier.is_trained();
+        self.ml_model = classifier;
+        self
+    }
+
+    /// Mit eigenen Schwellwerten für inkrementelles Retraining
+    pub fn with_retrain_config(mut self, config: RetrainConfig) -> Self {
+        self.retrain = Mutex::new(RetrainBuffer::with_config(config));
+        self
+    }
+
+    /// Mit eigenen Parametern für die Drift-Erkennung
+    pub fn with_drift_config(mut self, config: DriftConfig) -> Self {
+        self.drift = Mutex::new(DriftMonitor::with_config(config));
         self
     }
 
     /// Feature-Vektor analysieren
     pub async fn analyze(
-        &mut self,
+        &self,
         features: &[f64],
     ) -> Result<(bool, f64), Box<dyn std::error::Error>> {
-        self.total_predictions += 1;
+        self.validate_feature_count(features)?;
 
-        // Sample hinzufügen
-        self.samples.push_back(features.to_vec());
-        if self.samples.len() > self.window_size {
-            self.samples.pop_front();
+        // Model-Prediction außerhalb des Window-Locks
+        let ml_score = self.predict_score(features);
+
+        self.drift.lock().unwrap().observe(features);
+
+        let score = {
+            let mut window = self.window.lock().unwrap();
+            window.push_sample(features, self.window_size);
+            ml_score.unwrap_or_else(|| window.heuristic_score(features, self.ml_model.scaler()))
+        };
+        self.total_predictions.fetch_add(1, Ordering::Relaxed);
+
+        Ok(self.record_score(score))
+    }
+
+    /// Mehrere Feature-Vektoren nacheinander analysieren (Replay, Evaluation)
+    ///
+    /// Bewusst ohne Batch-Pfad, siehe `batch` in `benches/detector.rs`.
+    pub async fn analyze_many(
+        &self,
+        samples: &[Vec<f64>],
+    ) -> Result<Vec<(bool, f64)>, Box<dyn std::error::Error>> {
+        let mut results = Vec::with_capacity(samples.len());
+        for features in samples {
+            results.push(self.analyze(features).await?);
         }
+        Ok(results)
+    }
 
-        // Anomalie-Score berechnen
-        let score = self.calculate_anomaly_score(features).await;
+    /// Score festhalten und gegen den Schwellwert prüfen
+    fn record_score(&self, score: f64) -> (bool, f64) {
+        self.window
+            .lock()
+            .unwrap()
+            .push_score(score, self.window_size);
 
         let is_anomaly = score > self.anomaly_threshold;
 
         if is_anomaly {
-            self.anomalies_count += 1;
+            self.anomalies_count.fetch_add(1, Ordering::Relaxed);
             tracing::debug!("🤖 Anomaly detected: score={:.3}", score);
         }
 
-        Ok((is_anomaly, score))
+        (is_anomaly, score)
     }
 
     /// Erweiterte Analyse mit LLM
     pub async fn analyze_with_llm(
-        &mut self,
+        &self,
         features: &[f64],
         session_data: SessionData,
     ) -> Result<(bool, f64, Option<BehaviorAnalysis>), Box<dyn std::error::Error>> {
         // Basis-Analyse
         let (is_anomaly, score) = self.analyze(features).await?;
 
-        // LLM-Analyse nur bei Anomalien
-        if is_anomaly && self.llm_client.is_some() {
+        // LLM-Analyse bei Anomalien und knapp benignen Scores
+        let near_threshold = score > self.anomaly_threshold - LLM_REVIEW_MARGIN;
+        if let Some(llm_client) = self
+            .llm_client
+            .as_ref()
+            .filter(|_| is_anomaly || near_threshold)
+        {
             tracing::info!("🧠 Running LLM behavior analysis...");
 
-            let llm_client = self.llm_client.as_ref().unwrap();
             match llm_client.analyze_behavior(&session_data).await {
                 Ok(analysis) => {
                     tracing::info!(
@@ -94,6 +263,10 @@ impl AnomalyDetector {
                         analysis.threat_score
                     );
 
+                    if analysis.is_malicious != is_anomaly {
+                        self.record_disagreement(score, &analysis, &session_data);
+                    }
+
                     // LLM-Score mit ML-Score kombinieren
                     let combined_score = (score + analysis.threat_score) / 2.0;
                     let is_malicious =
@@ -110,54 +283,75 @@ impl AnomalyDetector {
         Ok((is_anomaly, score, None))
     }
 
-    /// Anomalie-Score berechnen
-    async fn calculate_anomaly_score(&self, features: &[f64]) -> f64 {
-        // Wenn ML-Model trainiert ist, nutze es
-        if self.use_ml_model {
-            if let Some(ref model) = self.ml_model {
-                match model.predict(features) {
-                    Ok((prediction, probability)) => {
-                        // prediction: 0 = normal, 1 = anomaly
-                        if prediction == 1 {
-                            return probability;
-                        } else {
-                            return 1.0 - probability;
-                        }
-                    }
-                    Err(e) => {
-                        tracing::warn!("ML prediction failed: {}, falling back to heuristic", e);
-                    }
-                }
-            }
+    /// Uneinigkeit zwischen ML-Model und LLM als Metrik festhalten und an den
+    /// Hook melden (Kandidaten für Relabeling)
+    fn record_disagreement(
+        &self,
+        ml_score: f64,
+        analysis: &BehaviorAnalysis,
+        session_data: &SessionData,
+    ) {
+        self.disagreements_count.fetch_add(1, Ordering::Relaxed);
+        let malicious_by = if analysis.is_malicious { "llm" } else { "ml" };
+        METRICS
+            .ml
+            .llm_disagreement
+            .with_label_values(&[malicious_by])
+            .inc();
+
+        tracing::warn!(
+            event = "ml_llm_disagreement",
+            source_ip = %session_data.source_ip,
+            destination_port = session_data.destination_port,
+            ml_score,
+            llm_threat_score = analysis.threat_score,
+            llm_attack_type = %analysis.attack_type,
+            malicious_by,
+            "⚖️ ML model and LLM disagree: ml={:.3}, llm={:.3} ({})",
+            ml_score,
+            analysis.threat_score,
+            analysis.attack_type
+        );
+
+        if let Some(hook) = &self.disagreement_hook {
+            hook(&Disagreement {
+                source_ip: session_data.source_ip.clone(),
+                destination_port: session_data.destination_port,
+                ml_score,
+                llm_threat_score: analysis.threat_score,
+                attack_type: analysis.attack_type.clone(),
+                malicious_by,
+            });
         }
+    }
 
-        // Fallback: Heuristische Berechnung
-        if self.samples.len() < 2 {
-            return 0.0;
+    /// Feature-Vektor gegen die vom Model erwartete Länge prüfen
+    fn validate_feature_count(&self, features: &[f64]) -> Result<(), Box<dyn std::error::Error>> {
+        let expected = self.feature_count();
+        if expected != 0 && features.len() != expected {
+            return Err(format!(
+                "Feature vector length mismatch: model expects {} features, got {}",
+                expected,
+                features.len()
+            )
+            .into());
         }
+        Ok(())
+    }
 
-        // Durchschnitt der bisherigen Samples
-        let mut avg = vec![0.0; features.len()];
-        for sample in &self.samples {
-            for (i, &val) in sample.iter().enumerate() {
-                avg[i] += val;
-            }
-        }
-        for val in &mut avg {
-            *val /= self.samples.len() as f64;
+    /// Anomalie-Score des trainierten Models (None = Heuristik verwenden)
+    fn predict_score(&self, features: &[f64]) -> Option<f64> {
+        if !self.use_ml_model {
+            return None;
         }
 
-        // Euklidische Distanz zum Durchschnitt
-        let mut distance = 0.0;
-        for (i, &feature) in features.iter().enumerate() {
-            let diff = feature - avg.get(i).copied().unwrap_or(0.0);
-            distance += diff * diff;
+        match self.ml_model.predict(features) {
+            Ok((prediction, probability)) => Some(ml_score(prediction, probability)),
+            Err(e) => {
+                tracing::warn!("ML prediction failed: {}, falling back to heuristic", e);
+                None
+            }
         }
-        distance = distance.sqrt();
-
-        // Normalisieren (0.0 - 1.0)
-        let max_distance = 100.0; // Heuristic
-        (distance / max_distance).min(1.0)
     }
 
     /// Model trainieren
@@ -169,7 +363,32 @@ impl AnomalyDetector {
             return Err("Training data is empty".into());
         }
 
-        tracing::info!("🧠 Training RandomForest model with {} samples", training_data.len());
+        tracing::info!(
+            "🧠 Training {} model with {} samples",
+            self.ml_model.name(),
+            training_data.len()
+        );
+
+        // Alle Samples müssen dasselbe Feature-Layout haben
+        let n_features = training_data[0].0.len();
+        if let Some((i, (features, _))) = training_data
+            .iter()
+            .enumerate()
+            .find(|(_, (features, _))| features.len() != n_features)
+        {
+            return Err(format!(
+                "Inconsistent training data: sample {} has {} features, expected {}",
+                i,
+                features.len(),
+                n_features
+            )
+            .into());
+        }
+
+        // Neue Referenz für Drift-Erkennung, Puffer ist jetzt verarbeitet
+        self.retrain.get_mut().unwrap().set_baseline(&training_data);
+        let reference: Vec<Vec<f64>> = training_data.iter().map(|(x, _)| x.clone()).collect();
+        self.drift.get_mut().unwrap().set_reference(&reference);
 
         // Daten für smartcore vorbereiten
         let mut x_train = Vec::new();
@@ -181,11 +400,8 @@ impl AnomalyDetector {
         }
 
         // Model trainieren
-        let model = self.ml_model.as_mut()
-            .ok_or("ML model not initialized")?;
-        
-        let accuracy = model.train(x_train, y_train)?;
-        
+        let accuracy = self.ml_model.train(x_train, y_train)?;
+
         self.use_ml_model = true;
 
         tracing::info!("✅ Model trained with accuracy: {:.4}", accuracy);
@@ -193,40 +409,131 @@ impl AnomalyDetector {
         Ok(accuracy)
     }
 
+    /// Weicht die Live-Verteilung von den Trainingsdaten ab?
+    pub fn drift_detected(&self) -> bool {
+        self.drift.lock().unwrap().is_drifting()
+    }
+
+    /// Letzte Drift-Prüfung (PSI pro Feature)
+    pub fn drift_report(&self) -> Option<DriftReport> {
+        self.drift.lock().unwrap().last_report().cloned()
+    }
+
+    /// Gelabeltes Sample (z.B. aus Analysten-Feedback) für Retraining puffern
+    pub fn add_labeled_sample(&self, features: Vec<f64>, is_anomaly: bool) {
+        self.retrain.lock().unwrap().push(features, is_anomaly);
+    }
+
+    /// Anzahl gepufferter Samples seit dem letzten Training
+    pub fn pending_samples(&self) -> usize {
+        self.retrain.lock().unwrap().pending_samples()
+    }
+
+    /// Genug neue bzw. abweichende Samples für ein Retraining?
+    pub fn should_retrain(&self) -> bool {
+        self.retrain.lock().unwrap().should_retrain()
+    }
+
+    /// Partial Update: nur neu trainieren, wenn `should_retrain()` zutrifft
+    ///
+    /// Trainiert auf einer Stichprobe der bisherigen Trainingsdaten plus den
+    /// gepufferten Samples. `None`, wenn sich ein Retraining nicht lohnt.
+    pub async fn partial_update(&mut self) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+        let buffer = self.retrain.get_mut().unwrap();
+        if !buffer.should_retrain() {
+            return Ok(None);
+        }
+
+        tracing::info!(
+            "🔄 Retraining with {} new samples (drift {:.2})",
+            buffer.pending_samples(),
+            buffer.drift_score()
+        );
+        let training_data = buffer.training_set();
+        self.train(training_data).await.map(Some)
+    }
+
     /// Model speichern
     pub async fn save_model(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("💾 Saving model to {}", path);
-        
-        if let Some(ref model) = self.ml_model {
-            model.save(path)?;
-        } else {
-            return Err("No model to save".into());
-        }
-        
-        Ok(())
+        self.ml_model.save(Path::new(path))
     }
 
     /// Model laden
     pub async fn load_model(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("📂 Loading model from {}", path);
-        
-        let loaded = RandomForestModel::load(path)?;
-        self.ml_model = Some(loaded);
-        
+        self.ml_model.load(Path::new(path))
+    }
+
+    /// Zustand des Sliding Windows (Dashboards, Debugging)
+    pub fn window_stats(&self) -> WindowStats {
+        let window = self.window.lock().unwrap();
+        let dims = window.samples.iter().map(|s| s.len()).max().unwrap_or(0);
+        let mut feature_means = vec![0.0; dims];
+        let mut feature_stds = vec![0.0; dims];
+
+        for i in 0..dims {
+            let values: Vec<f64> =
+                window.samples.iter().filter_map(|s| s.get(i).copied()).collect();
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+            feature_means[i] = mean;
+            feature_stds[i] = variance.sqrt();
+        }
+
+        WindowStats {
+            sample_count: window.samples.len(),
+            window_size: self.window_size,
+            feature_means,
+            feature_stds,
+            scores: ScoreDistribution::from_scores(window.scores.iter().copied()),
+        }
+    }
+
+    /// Aktueller Anomalie-Schwellwert
+    pub fn anomaly_threshold(&self) -> f64 {
+        self.anomaly_threshold
+    }
+
+    /// Schwellwert zur Laufzeit ändern (z.B. während eines Scan-Sturms)
+    pub fn set_threshold(&mut self, threshold: f64) -> Result<(), Box<dyn std::error::Error>> {
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(format!(
+                "anomaly threshold must be within 0.0..=1.0, got {}",
+                threshold
+            )
+            .into());
+        }
+
+        tracing::info!(
+            "🎚️ Anomaly threshold changed: {:.3} -> {:.3}",
+            self.anomaly_threshold,
+            threshold
+        );
+        self.anomaly_threshold = threshold;
         Ok(())
     }
 
     /// Statistiken
     pub fn anomalies_detected(&self) -> u64 {
-        self.anomalies_count
+        self.anomalies_count.load(Ordering::Relaxed)
+    }
+
+    /// Anzahl analysierter Feature-Vektoren
+    pub fn total_predictions(&self) -> u64 {
+        self.total_predictions.load(Ordering::Relaxed)
+    }
+
+    /// Anzahl Uneinigkeiten zwischen ML-Model und LLM
+    pub fn disagreements(&self) -> u64 {
+        self.disagreements_count.load(Ordering::Relaxed)
     }
 
     pub fn model_accuracy(&self) -> f64 {
         // Nutze echte Accuracy wenn ML-Model trainiert
         if self.use_ml_model {
-            if let Some(ref model) = self.ml_model {
-                return model.accuracy();
-            }
+            return self.ml_model.accuracy();
         }
 
         // Fallback: Heuristic
@@ -238,13 +545,394 @@ impl AnomalyDetector {
         self.use_ml_model
     }
     
-    /// Feature-Anzahl
+    /// Feature-Anzahl, mit der das Model trainiert wurde (0 = untrainiert)
     pub fn feature_count(&self) -> usize {
-        if let Some(ref model) = self.ml_model {
-            if model.is_trained() {
-                return 10; // NetworkFeatures hat 10 Features
-            }
+        if self.ml_model.is_trained() {
+            return self.ml_model.n_features();
         }
         0
     }
+
+    /// Aktives ML-Backend
+    pub fn classifier(&self) -> &dyn Classifier {
+        self.ml_model.as_ref()
+    }
+}
+
+/// Momentaufnahme des Sliding Windows
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowStats {
+    pub sample_count: usize,
+    pub window_size: usize,
+    /// Mittelwert pro Feature
+    pub feature_means: Vec<f64>,
+    /// Standardabweichung pro Feature
+    pub feature_stds: Vec<f64>,
+    /// Verteilung der letzten Scores
+    pub scores: ScoreDistribution,
+}
+
+impl WindowStats {
+    /// Window vollständig gefüllt?
+    pub fn is_warmed_up(&self) -> bool {
+        self.sample_count >= self.window_size
+    }
+}
+
+/// Verteilung der letzten Anomalie-Scores
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreDistribution {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p95: f64,
+}
+
+impl ScoreDistribution {
+    fn from_scores(scores: impl Iterator<Item = f64>) -> Self {
+        let mut sorted: Vec<f64> = scores.collect();
+        if sorted.is_empty() {
+            return Self::default();
+        }
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let percentile = |p: f64| sorted[((sorted.len() - 1) as f64 * p).round() as usize];
+        Self {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: percentile(0.5),
+            p95: percentile(0.95),
+        }
+    }
+}
+
+/// Model-Prediction als Anomalie-Score (prediction: 0 = normal, 1 = anomaly)
+fn ml_score(prediction: usize, probability: f64) -> f64 {
+    if prediction == 1 {
+        probability
+    } else {
+        1.0 - probability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{LLMClient, LLMProvider, MockLlmBackend};
+
+    #[tokio::test]
+    async fn test_window_stats_reports_count_and_means() {
+        let detector = AnomalyDetector::new(4);
+        assert_eq!(detector.window_stats().sample_count, 0);
+        assert_eq!(detector.window_stats().scores, ScoreDistribution::default());
+
+        for sample in [[1.0, 10.0], [2.0, 10.0], [3.0, 10.0], [4.0, 10.0], [5.0, 10.0]] {
+            detector.analyze(&sample).await.unwrap();
+        }
+
+        // Ältestes Sample ist aus dem Window gefallen
+        let stats = detector.window_stats();
+        assert_eq!(stats.sample_count, 4);
+        assert!(stats.is_warmed_up());
+        assert_eq!(stats.feature_means, [3.5, 10.0]);
+        assert!((stats.feature_stds[0] - 1.25f64.sqrt()).abs() < 1e-12);
+        assert_eq!(stats.feature_stds[1], 0.0);
+
+        assert_eq!(stats.scores.count, 4);
+        assert!(stats.scores.min <= stats.scores.p50 && stats.scores.p50 <= stats.scores.max);
+        assert!(stats.scores.max <= 1.0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_parallel_analyses_under_read_lock_keep_exact_counts() {
+        const TASKS: usize = 16;
+        const PER_TASK: usize = 50;
+        let detector = std::sync::Arc::new(tokio::sync::RwLock::new(AnomalyDetector::new(32)));
+
+        let tasks: Vec<_> = (0..TASKS)
+            .map(|task| {
+                let detector = detector.clone();
+                tokio::spawn(async move {
+                    for i in 0..PER_TASK {
+                        let sample = [task as f64, i as f64];
+                        detector.read().await.analyze(&sample).await.unwrap();
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // Keine verlorenen Updates trotz paralleler `&self`-Analysen
+        let detector = detector.read().await;
+        assert_eq!(detector.total_predictions(), (TASKS * PER_TASK) as u64);
+        let stats = detector.window_stats();
+        assert_eq!(stats.sample_count, 32);
+        assert_eq!(stats.scores.count, 32);
+    }
+
+    #[tokio::test]
+    async fn test_high_ml_score_with_benign_llm_counts_disagreement() {
+        // Deaktivierter Provider liefert immer ein harmloses Urteil
+        let detector = AnomalyDetector::new(10)
+            .with_threshold(0.5)
+            .with_llm(LLMClient::new(LLMProvider::Disabled));
+        let session = SessionData {
+            source_ip: "203.0.113.9".to_string(),
+            destination_port: 22,
+            duration_secs: 1.0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            failed_login_attempts: 0,
+            commands: vec![],
+            user_agent: None,
+            request_pattern: None,
+        };
+        let counter = METRICS.ml.llm_disagreement.with_label_values(&["ml"]);
+        let before = counter.get();
+
+        for _ in 0..3 {
+            detector.analyze(&[1.0, 1.0]).await.unwrap();
+        }
+        let (_, _, analysis) = detector
+            .analyze_with_llm(&[500.0, 500.0], session)
+            .await
+            .unwrap();
+
+        assert!(!analysis.unwrap().is_malicious);
+        assert_eq!(detector.disagreements(), 1);
+        assert!(counter.get() > before);
+    }
+
+    #[tokio::test]
+    async fn test_near_threshold_benign_score_is_reviewed_by_llm() {
+        let baseline = AnomalyDetector::new(10).with_threshold(0.5);
+        let (_, ml_score, _) = analyze_outlier(&baseline).await;
+
+        // Knapp unter dem Schwellwert: ML benign, LLM meldet den Angriff nach
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let hook_reported = reported.clone();
+        let llm = mock_llm(0.9, true);
+        let detector = AnomalyDetector::new(10)
+            .with_threshold(ml_score + LLM_REVIEW_MARGIN / 2.0)
+            .with_llm(llm.clone())
+            .with_disagreement_hook(Arc::new(move |d: &Disagreement| {
+                hook_reported.lock().unwrap().push(d.clone())
+            }));
+        let (is_malicious, _, analysis) = analyze_outlier(&detector).await;
+
+        assert!(is_malicious);
+        assert!(analysis.is_some());
+        // Baseline-Samples liegen weit unter dem Schwellwert
+        assert_eq!(llm.calls(), 1);
+        assert_eq!(detector.disagreements(), 1);
+        let reported = reported.lock().unwrap();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].malicious_by, "llm");
+        assert_eq!(reported[0].source_ip, "203.0.113.7");
+        assert_eq!(reported[0].attack_type, "reconnaissance");
+        assert!((reported[0].ml_score - ml_score).abs() < 1e-12);
+    }
+
+    #[tokio::test]
+    async fn test_set_threshold_validates_and_applies_to_next_analysis() {
+        let mut detector = AnomalyDetector::new(10).with_threshold(0.0);
+        for _ in 0..3 {
+            detector.analyze(&[1.0, 1.0]).await.unwrap();
+        }
+        let (is_anomaly, score) = detector.analyze(&[50.0, 50.0]).await.unwrap();
+        assert!(is_anomaly && score < 1.0);
+
+        for invalid in [-0.1, 1.5, f64::NAN] {
+            assert!(detector.set_threshold(invalid).is_err());
+        }
+        assert_eq!(detector.anomaly_threshold(), 0.0);
+
+        detector.set_threshold(1.0).unwrap();
+        assert_eq!(detector.anomaly_threshold(), 1.0);
+        let (is_anomaly, _) = detector.analyze(&[50.0, 50.0]).await.unwrap();
+        assert!(!is_anomaly);
+    }
+
+    fn mock_llm(threat_score: f64, is_malicious: bool) -> MockLlmBackend {
+        MockLlmBackend::new(BehaviorAnalysis {
+            threat_score,
+            is_malicious,
+            attack_type: "reconnaissance".to_string(),
+            ..BehaviorAnalysis::default()
+        })
+    }
+
+    fn scanner_session() -> SessionData {
+        SessionData {
+            source_ip: "203.0.113.7".to_string(),
+            destination_port: 22,
+            duration_secs: 3.0,
+            bytes_sent: 128,
+            bytes_received: 64,
+            failed_login_attempts: 5,
+            commands: vec![],
+            user_agent: None,
+            request_pattern: None,
+        }
+    }
+
+    /// Normale Baseline, dann ein Ausreißer über `analyze_with_llm`
+    async fn analyze_outlier(detector: &AnomalyDetector) -> (bool, f64, Option<BehaviorAnalysis>) {
+        for _ in 0..3 {
+            detector.analyze(&[1.0, 1.0]).await.unwrap();
+        }
+        detector
+            .analyze_with_llm(&[500.0, 500.0], scanner_session())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_llm_score_is_averaged_and_verdict_ored_with_threshold() {
+        // ML-Score des Ausreißers ohne LLM
+        let baseline = AnomalyDetector::new(10).with_threshold(0.5);
+        let (_, ml_score, _) = analyze_outlier(&baseline).await;
+        assert!(ml_score > 0.5);
+        let combined = (ml_score + 0.2) / 2.0;
+
+        // Kombinierter Score unter dem Schwellwert: harmloses LLM-Urteil gewinnt ...
+        let threshold = (combined + ml_score) / 2.0;
+        let llm = mock_llm(0.2, false);
+        let detector = AnomalyDetector::new(10)
+            .with_threshold(threshold)
+            .with_llm(llm.clone());
+        let (is_malicious, score, analysis) = analyze_outlier(&detector).await;
+        assert!((score - combined).abs() < 1e-12);
+        assert!(!is_malicious);
+        assert_eq!(analysis.unwrap().attack_type, "reconnaissance");
+        // ... LLM nur für die Anomalie gefragt, nicht für die Baseline
+        assert_eq!(llm.calls(), 1);
+
+        // ... außer das LLM stuft die Session als bösartig ein
+        let detector = AnomalyDetector::new(10)
+            .with_threshold(threshold)
+            .with_llm(mock_llm(0.2, true));
+        let (is_malicious, score, _) = analyze_outlier(&detector).await;
+        assert!((score - combined).abs() < 1e-12);
+        assert!(is_malicious);
+
+        // Kombinierter Score über dem Schwellwert reicht auch bei harmlosem LLM
+        let detector = AnomalyDetector::new(10)
+            .with_threshold(combined - 0.05)
+            .with_llm(mock_llm(0.2, false));
+        let (is_malicious, _, _) = analyze_outlier(&detector).await;
+        assert!(is_malicious);
+    }
+
+    #[tokio::test]
+    async fn test_large_magnitude_features_are_scaled() {
+        // [source_port, failed_login_attempts]: Port ist Rauschen, Logins trennen
+        let training_data: Vec<(Vec<f64>, bool)> = (0..200)
+            .map(|i| {
+                let port = ((i * 7_919) % 64_511 + 1_024) as f64;
+                let is_anomaly = i % 2 == 1;
+                let failed = if is_anomaly { 20 + i % 30 } else { i % 4 };
+                (vec![port, failed as f64], is_anomaly)
+            })
+            .collect();
+
+        let mut detector = AnomalyDetector::new(10);
+        let accuracy = detector.train(training_data).await.unwrap();
+        assert!(accuracy > 0.9, "accuracy {}", accuracy);
+
+        let scaler = detector.classifier().scaler().expect("fitted scaler");
+        assert!(scaler.stds()[0] > 1_000.0);
+        assert!(scaler.stds()[1] < 50.0);
+    }
+
+    /// Minimal-Backend: Anomalie, wenn Feature 0 über dem gelernten Mittelwert liegt
+    #[derive(Default)]
+    struct MockClassifier {
+        calls: std::sync::Arc<std::sync::Mutex<Vec<&'static str>>>,
+        cutoff: Option<f64>,
+    }
+
+    impl Classifier for MockClassifier {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn train(
+            &mut self,
+            x_train: Vec<Vec<f64>>,
+            _y_train: Vec<usize>,
+        ) -> Result<f64, Box<dyn std::error::Error>> {
+            self.calls.lock().unwrap().push("train");
+            let sum: f64 = x_train.iter().map(|x| x[0]).sum();
+            self.cutoff = Some(sum / x_train.len() as f64);
+            Ok(1.0)
+        }
+
+        fn predict(&self, features: &[f64]) -> Result<(usize, f64), Box<dyn std::error::Error>> {
+            self.calls.lock().unwrap().push("predict");
+            let cutoff = self.cutoff.ok_or("untrained")?;
+            Ok((usize::from(features[0] > cutoff), 0.9))
+        }
+
+        fn predict_proba(&self, features: &[f64]) -> Result<f64, Box<dyn std::error::Error>> {
+            let (prediction, _) = self.predict(features)?;
+            Ok(prediction as f64)
+        }
+
+        fn save(&self, _path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn load(&mut self, _path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+            Ok(())
+        }
+
+        fn feature_importances(&self) -> Option<Vec<f64>> {
+            Some(vec![1.0, 0.0])
+        }
+
+        fn is_trained(&self) -> bool {
+            self.cutoff.is_some()
+        }
+
+        fn accuracy(&self) -> f64 {
+            1.0
+        }
+
+        fn n_features(&self) -> usize {
+            2
+        }
+    }
+
+    #[tokio::test]
+    async fn test_detector_drives_custom_classifier() {
+        let mock = MockClassifier::default();
+        let calls = mock.calls.clone();
+        let mut detector = AnomalyDetector::new(10)
+            .with_threshold(0.5)
+            .with_classifier(Box::new(mock));
+        assert!(!detector.is_ml_trained());
+
+        detector
+            .train(vec![(vec![0.0, 1.0], false), (vec![10.0, 1.0], true)])
+            .await
+            .unwrap();
+        assert!(detector.is_ml_trained());
+        assert_eq!(detector.model_accuracy(), 1.0);
+        assert_eq!(detector.classifier().name(), "mock");
+
+        assert_eq!(detector.analyze(&[9.0, 1.0]).await.unwrap(), (true, 0.9));
+        let (is_anomaly, score) = detector.analyze(&[1.0, 1.0]).await.unwrap();
+        assert!(!is_anomaly);
+        assert!((score - 0.1).abs() < 1e-12);
+
+        assert_eq!(*calls.lock().unwrap(), ["train", "predict", "predict"]);
+        assert!(detector.analyze(&[1.0]).await.is_err());
+    }
 }
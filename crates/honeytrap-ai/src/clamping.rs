@@ -0,0 +1,142 @@
+//! Outlier Clamping pro Feature
+//!
+//! Rohe Features wie `bytes_sent` liegen im Millionenbereich, während
+//! `failed_logins` einstellig bleiben. Extreme Werte (auch absichtlich
+//! erzeugte) werden vor Training und Prediction auf konfigurierte Grenzen
+//! gekappt: entweder absolut oder über Perzentile der Trainingsdaten.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Grenze für ein einzelnes Feature
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ClampRule {
+    /// Feste Unter-/Obergrenze
+    Absolute { min: f64, max: f64 },
+    /// Perzentile (0-100) der Trainingsdaten (Winsorizing)
+    Percentile { lower: f64, upper: f64 },
+}
+
+/// Per-Feature Clamping, wird mit dem Model gespeichert
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeatureClamp {
+    /// Feature-Index -> Regel
+    rules: BTreeMap<usize, ClampRule>,
+    /// Feature-Index -> wirksame Grenzen (nach `fit` auch für Perzentile)
+    bounds: BTreeMap<usize, (f64, f64)>,
+}
+
+impl FeatureClamp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Mit Regel für ein Feature
+    pub fn with_rule(mut self, index: usize, rule: ClampRule) -> Self {
+        match rule {
+            ClampRule::Absolute { min, max } => {
+                self.bounds.insert(index, (min.min(max), min.max(max)));
+            }
+            ClampRule::Percentile { .. } => {
+                self.bounds.remove(&index);
+            }
+        }
+        self.rules.insert(index, rule);
+        self
+    }
+
+    /// Mit fester Grenze für ein Feature
+    pub fn with_absolute(self, index: usize, min: f64, max: f64) -> Self {
+        self.with_rule(index, ClampRule::Absolute { min, max })
+    }
+
+    /// Mit Perzentil-Grenzen für ein Feature
+    pub fn with_percentile(self, index: usize, lower: f64, upper: f64) -> Self {
+        self.with_rule(index, ClampRule::Percentile { lower, upper })
+    }
+
+    /// Keine Regeln konfiguriert?
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Perzentil-Grenzen aus den Trainingsdaten bestimmen
+    pub fn fit(&mut self, samples: &[Vec<f64>]) {
+        for (&index, rule) in &self.rules {
+            let ClampRule::Percentile { lower, upper } = *rule else {
+                continue;
+            };
+
+            let mut column: Vec<f64> = samples
+                .iter()
+                .filter_map(|sample| sample.get(index).copied())
+                .filter(|value| value.is_finite())
+                .collect();
+            if column.is_empty() {
+                continue;
+            }
+            column.sort_by(f64::total_cmp);
+
+            let (lower, upper) = (lower.min(upper), lower.max(upper));
+            self.bounds.insert(
+                index,
+                (percentile(&column, lower), percentile(&column, upper)),
+            );
+        }
+    }
+
+    /// Wirksame Grenzen eines Features (None = nicht gekappt)
+    pub fn bounds(&self, index: usize) -> Option<(f64, f64)> {
+        self.bounds.get(&index).copied()
+    }
+
+    /// Features eines Samples auf die Grenzen kappen
+    pub fn apply(&self, features: &mut [f64]) {
+        for (&index, &(min, max)) in &self.bounds {
+            if let Some(value) = features.get_mut(index) {
+                // NaN landet an der Untergrenze statt im Classifier
+                *value = if value.is_nan() {
+                    min
+                } else {
+                    value.clamp(min, max)
+                };
+            }
+        }
+    }
+
+    /// Gekappte Kopie eines Samples
+    pub fn clamp(&self, features: &[f64]) -> Vec<f64> {
+        let mut clamped = features.to_vec();
+        self.apply(&mut clamped);
+        clamped
+    }
+}
+
+/// Perzentil (0-100) einer sortierten Spalte, linear interpoliert
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let (low, high) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[low] + (sorted[high] - sorted[low]) * (rank - low as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_absolute_and_percentile_bounds() {
+        let samples: Vec<Vec<f64>> = (0..=100).map(|i| vec![i as f64, i as f64]).collect();
+        let mut clamp = FeatureClamp::new()
+            .with_absolute(0, 0.0, 50.0)
+            .with_percentile(1, 5.0, 95.0);
+
+        // Perzentile greifen erst nach dem Fit
+        assert_eq!(clamp.clamp(&[1e9, 1e9]), vec![50.0, 1e9]);
+
+        clamp.fit(&samples);
+        assert_eq!(clamp.bounds(1), Some((5.0, 95.0)));
+        assert_eq!(clamp.clamp(&[-3.0, 1e9, 7.0]), vec![0.0, 95.0, 7.0]);
+        assert_eq!(clamp.clamp(&[f64::NAN, 1.0]), vec![0.0, 5.0]);
+    }
+}
@@ -0,0 +1,56 @@
+//! Model-Backend Abstraktion
+//!
+//! `AnomalyDetector` arbeitet nur gegen den `Classifier` Trait, damit
+//! alternative Modelle (Logistic Regression, Isolation Forest, ...) ohne
+//! Änderungen am Detektor eingesetzt werden können. Labels: 0 = normal,
+//! 1 = Anomalie.
+
+use crate::scaling::StandardScaler;
+use std::error::Error;
+use std::path::Path;
+
+/// Binärer Klassifikator für Feature-Vektoren
+pub trait Classifier: Send + Sync {
+    /// Kurzname des Backends (Logging)
+    fn name(&self) -> &'static str;
+
+    /// Trainieren, liefert Training-Accuracy
+    fn train(&mut self, x_train: Vec<Vec<f64>>, y_train: Vec<usize>)
+        -> Result<f64, Box<dyn Error>>;
+
+    /// Label + Konfidenz für ein Sample
+    fn predict(&self, features: &[f64]) -> Result<(usize, f64), Box<dyn Error>>;
+
+    /// Wahrscheinlichkeit für Label 1 (Anomalie)
+    fn predict_proba(&self, features: &[f64]) -> Result<f64, Box<dyn Error>>;
+
+    /// Label + Konfidenz für mehrere Samples
+    fn predict_many(&self, samples: &[Vec<f64>]) -> Result<Vec<(usize, f64)>, Box<dyn Error>> {
+        samples
+            .iter()
+            .map(|features| self.predict(features))
+            .collect()
+    }
+
+    /// Model speichern
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>>;
+
+    /// Gespeichertes Model in dieses Backend laden
+    fn load(&mut self, path: &Path) -> Result<(), Box<dyn Error>>;
+
+    /// Relative Wichtigkeit pro Feature (None = vom Backend nicht unterstützt)
+    fn feature_importances(&self) -> Option<Vec<f64>>;
+
+    fn is_trained(&self) -> bool;
+
+    /// Training-Accuracy (0.0 = untrainiert)
+    fn accuracy(&self) -> f64;
+
+    /// Feature-Anzahl aus dem Training (0 = untrainiert)
+    fn n_features(&self) -> usize;
+
+    /// Beim Training gefittete Skalierung (None = Backend skaliert nicht)
+    fn scaler(&self) -> Option<&StandardScaler> {
+        None
+    }
+}
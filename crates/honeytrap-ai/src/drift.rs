@@ -0,0 +1,279 @@
+//! Drift-Erkennung auf der Feature-Verteilung
+//!
+//! Angriffsmuster ändern sich; ein Model vom letzten Monat degradiert sonst
+//! unbemerkt. Der Monitor vergleicht pro Feature die Verteilung der letzten
+//! Samples mit der Trainingsverteilung über den Population Stability Index
+//! (PSI). Bins sind Quantile der Referenz, damit auch schiefe Features
+//! (Bytes, Dauer) sinnvoll aufgeteilt werden.
+//!
+//! Faustregel PSI: < 0.1 stabil, 0.1 - 0.25 leichte Verschiebung, > 0.25 Drift.
+
+use honeytrap_metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Untergrenze für Bin-Anteile (leere Bins würden ln(0) ergeben)
+const MIN_BIN_SHARE: f64 = 1e-4;
+
+/// Parameter des Drift-Monitors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftConfig {
+    /// Anzahl der letzten Samples, die mit der Referenz verglichen werden
+    pub window_size: usize,
+    /// Anzahl Quantil-Bins pro Feature
+    pub bins: usize,
+    /// PSI, ab dem ein Feature als gedriftet gilt
+    pub psi_threshold: f64,
+    /// Bei vollem Window nur alle N Samples prüfen
+    pub check_interval: usize,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self {
+            window_size: 500,
+            bins: 10,
+            psi_threshold: 0.25,
+            check_interval: 50,
+        }
+    }
+}
+
+/// Ergebnis einer Drift-Prüfung
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriftReport {
+    /// PSI pro Feature
+    pub psi: Vec<f64>,
+    /// Indizes der Features über dem Schwellwert
+    pub drifted_features: Vec<usize>,
+}
+
+impl DriftReport {
+    /// Mindestens ein Feature gedriftet?
+    pub fn is_drift(&self) -> bool {
+        !self.drifted_features.is_empty()
+    }
+
+    /// Größter PSI über alle Features
+    pub fn max_psi(&self) -> f64 {
+        self.psi.iter().copied().fold(0.0, f64::max)
+    }
+}
+
+/// Referenzverteilung eines Features
+#[derive(Debug, Clone)]
+struct FeatureReference {
+    /// Innere Bin-Grenzen (aufsteigend, ohne Duplikate)
+    edges: Vec<f64>,
+    /// Anteil der Referenz-Samples pro Bin
+    shares: Vec<f64>,
+}
+
+impl FeatureReference {
+    fn new(mut values: Vec<f64>, bins: usize) -> Self {
+        values.sort_by(f64::total_cmp);
+        let mut edges: Vec<f64> = (1..bins.max(1))
+            .map(|i| values[i * values.len() / bins])
+            .collect();
+        edges.dedup();
+
+        let shares = bin_shares(&edges, values.iter().copied(), values.len());
+        Self { edges, shares }
+    }
+
+    fn psi(&self, values: impl Iterator<Item = f64>, n: usize) -> f64 {
+        bin_shares(&self.edges, values, n)
+            .iter()
+            .zip(&self.shares)
+            .map(|(actual, expected)| (actual - expected) * (actual / expected).ln())
+            .sum()
+    }
+}
+
+/// Anteil der Werte pro Bin (Wert == Grenze zählt in den oberen Bin)
+fn bin_shares(edges: &[f64], values: impl Iterator<Item = f64>, n: usize) -> Vec<f64> {
+    let mut counts = vec![0usize; edges.len() + 1];
+    for value in values {
+        counts[edges.partition_point(|edge| *edge <= value)] += 1;
+    }
+    counts
+        .into_iter()
+        .map(|count| (count as f64 / n.max(1) as f64).max(MIN_BIN_SHARE))
+        .collect()
+}
+
+/// Vergleicht Live-Features fortlaufend mit der Trainingsverteilung
+#[derive(Debug, Clone, Default)]
+pub struct DriftMonitor {
+    config: DriftConfig,
+    reference: Vec<FeatureReference>,
+    recent: VecDeque<Vec<f64>>,
+    since_check: usize,
+    drifting: bool,
+    last_report: Option<DriftReport>,
+}
+
+impl DriftMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: DriftConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Trainingsdaten als Referenzverteilung setzen (setzt den Zustand zurück)
+    pub fn set_reference(&mut self, samples: &[Vec<f64>]) {
+        let n_features = samples.first().map_or(0, Vec::len);
+        self.reference = (0..n_features)
+            .map(|i| {
+                let values = samples.iter().filter_map(|s| s.get(i).copied()).collect();
+                FeatureReference::new(values, self.config.bins)
+            })
+            .collect();
+        self.recent.clear();
+        self.since_check = 0;
+        self.drifting = false;
+        self.last_report = None;
+    }
+
+    /// Referenz vorhanden?
+    pub fn has_reference(&self) -> bool {
+        !self.reference.is_empty()
+    }
+
+    /// Live-Sample aufnehmen
+    ///
+    /// Gibt einen Report zurück, sobald Drift neu erkannt wurde; dabei wird
+    /// `model_drift_detected` gezählt. Solange die Drift anhält, wird nicht
+    /// erneut gemeldet.
+    pub fn observe(&mut self, features: &[f64]) -> Option<DriftReport> {
+        if !self.has_reference() || features.len() != self.reference.len() {
+            return None;
+        }
+
+        self.recent.push_back(features.to_vec());
+        if self.recent.len() > self.config.window_size {
+            self.recent.pop_front();
+        }
+        if self.recent.len() < self.config.window_size {
+            return None;
+        }
+
+        self.since_check += 1;
+        if self.since_check < self.config.check_interval.max(1) && self.last_report.is_some() {
+            return None;
+        }
+        self.since_check = 0;
+
+        let report = self.check();
+        for (i, psi) in report.psi.iter().enumerate() {
+            METRICS
+                .ml
+                .feature_psi
+                .with_label_values(&[&i.to_string()])
+                .set(*psi);
+        }
+
+        let newly_drifted = report.is_drift() && !self.drifting;
+        self.drifting = report.is_drift();
+        self.last_report = Some(report.clone());
+
+        if !newly_drifted {
+            return None;
+        }
+        METRICS.ml.model_drift_detected.inc();
+        tracing::warn!(
+            event = "model_drift_detected",
+            max_psi = report.max_psi(),
+            "📉 Feature drift detected (max PSI {:.3}, features {:?}), consider retraining",
+            report.max_psi(),
+            report.drifted_features
+        );
+        Some(report)
+    }
+
+    /// PSI des aktuellen Windows gegen die Referenz
+    pub fn check(&self) -> DriftReport {
+        if self.recent.is_empty() {
+            return DriftReport::default();
+        }
+
+        let psi: Vec<f64> = self
+            .reference
+            .iter()
+            .enumerate()
+            .map(|(i, reference)| {
+                reference.psi(self.recent.iter().map(|s| s[i]), self.recent.len())
+            })
+            .collect();
+        let drifted_features = psi
+            .iter()
+            .enumerate()
+            .filter(|(_, psi)| **psi > self.config.psi_threshold)
+            .map(|(i, _)| i)
+            .collect();
+
+        DriftReport {
+            psi,
+            drifted_features,
+        }
+    }
+
+    /// Ist die Live-Verteilung aktuell gedriftet?
+    pub fn is_drifting(&self) -> bool {
+        self.drifting
+    }
+
+    /// Letzte Prüfung (None, bis das Window einmal voll war)
+    pub fn last_report(&self) -> Option<&DriftReport> {
+        self.last_report.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn samples(n: usize, offset: f64) -> Vec<Vec<f64>> {
+        (0..n)
+            .map(|i| vec![offset + (i % 20) as f64, ((i * 7) % 13) as f64])
+            .collect()
+    }
+
+    #[test]
+    fn test_shifted_distribution_triggers_drift() {
+        let config = DriftConfig {
+            window_size: 100,
+            check_interval: 10,
+            ..DriftConfig::default()
+        };
+        let before = METRICS.ml.model_drift_detected.get();
+
+        let mut stable = DriftMonitor::with_config(config.clone());
+        stable.set_reference(&samples(1000, 0.0));
+        let reports: Vec<_> = samples(300, 0.0)
+            .iter()
+            .filter_map(|s| stable.observe(s))
+            .collect();
+        assert!(reports.is_empty());
+        assert!(!stable.is_drifting());
+        assert!(stable.last_report().unwrap().max_psi() < 0.1);
+
+        let mut shifted = DriftMonitor::with_config(config);
+        shifted.set_reference(&samples(1000, 0.0));
+        let reports: Vec<_> = samples(300, 15.0)
+            .iter()
+            .filter_map(|s| shifted.observe(s))
+            .collect();
+
+        // Nur einmal gemeldet, obwohl die Drift anhält
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].drifted_features, [0]);
+        assert!(shifted.is_drifting());
+        assert!(METRICS.ml.model_drift_detected.get() > before);
+    }
+}
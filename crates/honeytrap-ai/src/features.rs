@@ -1,6 +1,22 @@
+use std::fmt;
 use std::net::SocketAddr;
 use std::time::Duration;
 
+/// Features mit NaN/Inf (z.B. Division durch 0 Pakete)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureError {
+    /// Namen der betroffenen Features
+    pub fields: Vec<&'static str>,
+}
+
+impl fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "non-finite feature values: {}", self.fields.join(", "))
+    }
+}
+
+impl std::error::Error for FeatureError {}
+
 /// Netzwerk-Features für ML
 #[derive(Debug, Clone)]
 pub struct NetworkFeatures {
@@ -40,6 +56,65 @@ impl NetworkFeatures {
         ]
     }
 
+    /// Alle Werte endlich? Sonst Fehler mit den betroffenen Feldern
+    pub fn validate(&self) -> Result<(), FeatureError> {
+        let fields: Vec<&'static str> = self
+            .float_fields()
+            .into_iter()
+            .filter(|(_, value)| !value.is_finite())
+            .map(|(name, _)| name)
+            .collect();
+
+        if fields.is_empty() {
+            Ok(())
+        } else {
+            Err(FeatureError { fields })
+        }
+    }
+
+    /// NaN/Inf durch 0.0 ersetzen, liefert die Anzahl ersetzter Werte
+    pub fn sanitize(&mut self) -> usize {
+        let mut replaced = 0;
+        for (_, value) in self.float_fields_mut() {
+            if !value.is_finite() {
+                *value = 0.0;
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+
+    fn float_fields(&self) -> [(&'static str, f64); 8] {
+        [
+            ("connection_duration", self.connection_duration),
+            ("inter_packet_time", self.inter_packet_time),
+            ("bytes_sent", self.bytes_sent),
+            ("bytes_received", self.bytes_received),
+            ("packets_sent", self.packets_sent),
+            ("packets_received", self.packets_received),
+            ("failed_login_attempts", self.failed_login_attempts),
+            ("command_frequency", self.command_frequency),
+        ]
+    }
+
+    fn float_fields_mut(&mut self) -> [(&'static str, &mut f64); 8] {
+        [
+            ("connection_duration", &mut self.connection_duration),
+            ("inter_packet_time", &mut self.inter_packet_time),
+            ("bytes_sent", &mut self.bytes_sent),
+            ("bytes_received", &mut self.bytes_received),
+            ("packets_sent", &mut self.packets_sent),
+            ("packets_received", &mut self.packets_received),
+            ("failed_login_attempts", &mut self.failed_login_attempts),
+            ("command_frequency", &mut self.command_frequency),
+        ]
+    }
+
+    /// Anzahl der Features (Länge von `as_vector`)
+    pub fn feature_count() -> usize {
+        Self::feature_names().len()
+    }
+
     /// Feature-Namen
     pub fn feature_names() -> Vec<&'static str> {
         vec![
@@ -74,28 +149,120 @@ impl Default for NetworkFeatures {
     }
 }
 
+/// Default HoneyTrap Port (wenn der Ziel-Port unbekannt ist)
+pub const DEFAULT_DESTINATION_PORT: u16 = 8443;
+
+/// Gezählter Traffic einer Verbindung, Bytes/Pakete aus Sicht des Peers
+#[derive(Debug, Clone)]
+pub struct ConnectionStats {
+    pub destination_port: u16,
+    pub duration: Duration,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    /// Mittlere Zeit zwischen zwei Paketen des Peers
+    pub inter_packet_time: Duration,
+    pub failed_login_attempts: u64,
+    pub commands: u64,
+}
+
+impl Default for ConnectionStats {
+    fn default() -> Self {
+        Self {
+            destination_port: DEFAULT_DESTINATION_PORT,
+            duration: Duration::ZERO,
+            bytes_sent: 0,
+            bytes_received: 0,
+            packets_sent: 0,
+            packets_received: 0,
+            inter_packet_time: Duration::ZERO,
+            failed_login_attempts: 0,
+            commands: 0,
+        }
+    }
+}
+
 /// Feature Extractor
 pub struct FeatureExtractor;
 
 impl FeatureExtractor {
-    /// Features aus Connection-Daten extrahieren
+    /// Features aus Connection-Daten extrahieren (ohne Paket-/Verhaltensdaten)
     pub fn extract(
         peer_addr: SocketAddr,
         duration: Duration,
         bytes_sent: u64,
         bytes_received: u64,
     ) -> NetworkFeatures {
+        Self::extract_stats(
+            peer_addr,
+            &ConnectionStats {
+                duration,
+                bytes_sent,
+                bytes_received,
+                ..ConnectionStats::default()
+            },
+        )
+    }
+
+    /// Vollständige Features aus dem gezählten Traffic
+    ///
+    /// `command_frequency` ist in Commands pro Minute.
+    pub fn extract_stats(peer_addr: SocketAddr, stats: &ConnectionStats) -> NetworkFeatures {
+        let minutes = stats.duration.as_secs_f64() / 60.0;
+        let command_frequency = if minutes > 0.0 {
+            stats.commands as f64 / minutes
+        } else {
+            0.0
+        };
+
         NetworkFeatures {
             source_port: peer_addr.port(),
-            destination_port: 8443, // Default HoneyTrap Port
-            connection_duration: duration.as_secs_f64(),
-            inter_packet_time: 0.0, // TODO: Calculate
-            bytes_sent: bytes_sent as f64,
-            bytes_received: bytes_received as f64,
-            packets_sent: 0.0,          // TODO: Count
-            packets_received: 0.0,      // TODO: Count
-            failed_login_attempts: 0.0, // TODO: Track
-            command_frequency: 0.0,     // TODO: Calculate
+            destination_port: stats.destination_port,
+            connection_duration: stats.duration.as_secs_f64(),
+            inter_packet_time: stats.inter_packet_time.as_secs_f64(),
+            bytes_sent: stats.bytes_sent as f64,
+            bytes_received: stats.bytes_received as f64,
+            packets_sent: stats.packets_sent as f64,
+            packets_received: stats.packets_received as f64,
+            failed_login_attempts: stats.failed_login_attempts as f64,
+            command_frequency,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_replaces_nan() {
+        let mut features = NetworkFeatures {
+            inter_packet_time: f64::NAN,
+            bytes_sent: 512.0,
+            ..NetworkFeatures::default()
+        };
+
+        assert_eq!(features.sanitize(), 1);
+        assert_eq!(features.inter_packet_time, 0.0);
+        assert_eq!(features.bytes_sent, 512.0);
+        assert!(features.validate().is_ok());
+        assert!(features.as_vector().iter().all(|v| v.is_finite()));
+    }
+
+    #[test]
+    fn test_validate_flags_infinite_fields() {
+        let features = NetworkFeatures {
+            command_frequency: f64::INFINITY,
+            packets_received: f64::NEG_INFINITY,
+            ..NetworkFeatures::default()
+        };
+
+        let error = features.validate().unwrap_err();
+        assert_eq!(error.fields, ["packets_received", "command_frequency"]);
+        assert_eq!(
+            error.to_string(),
+            "non-finite feature values: packets_received, command_frequency"
+        );
+    }
+}
@@ -0,0 +1,354 @@
+//! Isolation Forest für Anomalie-Erkennung ohne Labels
+//!
+//! Trainiert auf (ungelabeltem) Normal-Traffic. Jeder Baum teilt zufällige
+//! Sub-Samples mit zufälligen Splits auf; Ausreißer werden nach wenigen
+//! Splits isoliert. Aus der mittleren Pfadlänge ergibt sich der Score
+//! `s(x) = 2^(-E[h(x)] / c(n))` (Liu et al., 2008): ~1.0 = Anomalie,
+//! deutlich unter 0.5 = normal.
+
+use crate::classifier::Classifier;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Konfiguration des Isolation Forest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IsolationForestConfig {
+    pub n_trees: usize,
+    /// Sub-Sample pro Baum (Original-Paper: 256)
+    pub sample_size: usize,
+    /// Score ab dem ein Sample als Anomalie gilt
+    pub threshold: f64,
+    pub seed: u64,
+}
+
+impl Default for IsolationForestConfig {
+    fn default() -> Self {
+        Self {
+            n_trees: 100,
+            sample_size: 256,
+            threshold: 0.6,
+            seed: 42,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Node {
+    Split {
+        feature: usize,
+        value: f64,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+    Leaf {
+        size: usize,
+    },
+}
+
+impl Node {
+    fn build(samples: &[&[f64]], depth: usize, max_depth: usize, rng: &mut StdRng) -> Self {
+        if depth >= max_depth || samples.len() <= 1 {
+            return Node::Leaf {
+                size: samples.len(),
+            };
+        }
+
+        // Nur Features mit Streuung kommen für einen Split in Frage
+        let n_features = samples[0].len();
+        let ranges: Vec<(usize, f64, f64)> = (0..n_features)
+            .filter_map(|feature| {
+                let (min, max) = samples.iter().fold((f64::MAX, f64::MIN), |(min, max), s| {
+                    (min.min(s[feature]), max.max(s[feature]))
+                });
+                (max > min).then_some((feature, min, max))
+            })
+            .collect();
+        if ranges.is_empty() {
+            return Node::Leaf {
+                size: samples.len(),
+            };
+        }
+
+        let (feature, min, max) = ranges[rng.gen_range(0..ranges.len())];
+        let value = rng.gen_range(min..max);
+        let (left, right): (Vec<&[f64]>, Vec<&[f64]>) =
+            samples.iter().partition(|s| s[feature] < value);
+
+        Node::Split {
+            feature,
+            value,
+            left: Box::new(Node::build(&left, depth + 1, max_depth, rng)),
+            right: Box::new(Node::build(&right, depth + 1, max_depth, rng)),
+        }
+    }
+
+    fn path_length(&self, features: &[f64], depth: usize) -> f64 {
+        match self {
+            Node::Leaf { size } => depth as f64 + average_path_length(*size),
+            Node::Split {
+                feature,
+                value,
+                left,
+                right,
+            } => {
+                let next = if features[*feature] < *value {
+                    left
+                } else {
+                    right
+                };
+                next.path_length(features, depth + 1)
+            }
+        }
+    }
+
+    fn count_splits(&self, counts: &mut [f64]) {
+        if let Node::Split {
+            feature,
+            left,
+            right,
+            ..
+        } = self
+        {
+            counts[*feature] += 1.0;
+            left.count_splits(counts);
+            right.count_splits(counts);
+        }
+    }
+}
+
+/// Unüberwachtes Anomalie-Model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IsolationForestModel {
+    config: IsolationForestConfig,
+    trees: Vec<Node>,
+    /// Tatsächliche Sub-Sample-Größe (für die Normalisierung)
+    sample_size: usize,
+    n_features: usize,
+    accuracy: f64,
+}
+
+impl IsolationForestModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: IsolationForestConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Auf Normal-Traffic fitten (keine Labels nötig)
+    pub fn fit(&mut self, samples: &[Vec<f64>]) -> Result<(), Box<dyn Error>> {
+        let n_features = samples.first().ok_or("Training data is empty")?.len();
+        if samples.iter().any(|s| s.len() != n_features) {
+            return Err("Inconsistent training data: samples differ in feature count".into());
+        }
+
+        let sample_size = self.config.sample_size.clamp(1, samples.len());
+        let max_depth = (sample_size as f64).log2().ceil() as usize;
+        let mut rng = StdRng::seed_from_u64(self.config.seed);
+
+        self.trees = (0..self.config.n_trees.max(1))
+            .map(|_| {
+                let subset: Vec<&[f64]> = sample(&mut rng, samples.len(), sample_size)
+                    .into_iter()
+                    .map(|i| samples[i].as_slice())
+                    .collect();
+                Node::build(&subset, 0, max_depth, &mut rng)
+            })
+            .collect();
+        self.sample_size = sample_size;
+        self.n_features = n_features;
+
+        tracing::info!(
+            "🌲 Isolation Forest fitted: {} trees, {} samples/tree, {} features",
+            self.trees.len(),
+            sample_size,
+            n_features
+        );
+        Ok(())
+    }
+
+    /// Anomalie-Score in 0.0..=1.0
+    pub fn score(&self, features: &[f64]) -> Result<f64, Box<dyn Error>> {
+        if self.trees.is_empty() {
+            return Err("Model not trained yet".into());
+        }
+        if features.len() != self.n_features {
+            return Err(format!(
+                "Feature count mismatch: model expects {} features, got {}",
+                self.n_features,
+                features.len()
+            )
+            .into());
+        }
+
+        let mean_path = self
+            .trees
+            .iter()
+            .map(|tree| tree.path_length(features, 0))
+            .sum::<f64>()
+            / self.trees.len() as f64;
+        let normalizer = average_path_length(self.sample_size);
+        if normalizer == 0.0 {
+            return Ok(0.5);
+        }
+        Ok(2f64.powf(-mean_path / normalizer))
+    }
+
+    /// Schwellwert für `predict`
+    pub fn threshold(&self) -> f64 {
+        self.config.threshold
+    }
+}
+
+impl Classifier for IsolationForestModel {
+    fn name(&self) -> &'static str {
+        "isolation_forest"
+    }
+
+    /// Labels werden nur zur Accuracy-Berechnung genutzt, nicht zum Fitten
+    fn train(
+        &mut self,
+        x_train: Vec<Vec<f64>>,
+        y_train: Vec<usize>,
+    ) -> Result<f64, Box<dyn Error>> {
+        self.fit(&x_train)?;
+
+        let correct = x_train
+            .iter()
+            .zip(&y_train)
+            .filter(|(features, label)| {
+                self.predict(features)
+                    .is_ok_and(|(prediction, _)| prediction == **label)
+            })
+            .count();
+        self.accuracy = correct as f64 / x_train.len() as f64;
+        Ok(self.accuracy)
+    }
+
+    fn predict(&self, features: &[f64]) -> Result<(usize, f64), Box<dyn Error>> {
+        let score = self.score(features)?;
+        Ok(if score > self.config.threshold {
+            (1, score)
+        } else {
+            (0, 1.0 - score)
+        })
+    }
+
+    fn predict_proba(&self, features: &[f64]) -> Result<f64, Box<dyn Error>> {
+        self.score(features)
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if self.trees.is_empty() {
+            return Err("Cannot save untrained model".into());
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    fn load(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        *self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        Ok(())
+    }
+
+    /// Anteil der Splits pro Feature
+    fn feature_importances(&self) -> Option<Vec<f64>> {
+        if self.trees.is_empty() {
+            return None;
+        }
+        let mut counts = vec![0.0; self.n_features];
+        for tree in &self.trees {
+            tree.count_splits(&mut counts);
+        }
+        let total: f64 = counts.iter().sum();
+        if total > 0.0 {
+            counts.iter_mut().for_each(|count| *count /= total);
+        }
+        Some(counts)
+    }
+
+    fn is_trained(&self) -> bool {
+        !self.trees.is_empty()
+    }
+
+    fn accuracy(&self) -> f64 {
+        self.accuracy
+    }
+
+    fn n_features(&self) -> usize {
+        self.n_features
+    }
+}
+
+/// Mittlere Pfadlänge einer erfolglosen BST-Suche über `n` Elemente: c(n)
+fn average_path_length(n: usize) -> f64 {
+    match n {
+        0 | 1 => 0.0,
+        2 => 1.0,
+        n => {
+            let n = n as f64;
+            const EULER_GAMMA: f64 = 0.577_215_664_901_532_9;
+            2.0 * ((n - 1.0).ln() + EULER_GAMMA) - 2.0 * (n - 1.0) / n
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anomaly_detector::AnomalyDetector;
+
+    fn clustered_normal(n: usize) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(7);
+        (0..n)
+            .map(|_| {
+                vec![
+                    10.0 + rng.gen_range(-1.0..1.0),
+                    5.0 + rng.gen_range(-1.0..1.0),
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_distant_point_scores_as_anomalous() {
+        let mut model = IsolationForestModel::new();
+        model.fit(&clustered_normal(500)).unwrap();
+
+        let inlier = model.score(&[10.0, 5.0]).unwrap();
+        let outlier = model.score(&[60.0, -40.0]).unwrap();
+        assert!(inlier < 0.5, "inlier scored {}", inlier);
+        assert!(outlier > model.threshold(), "outlier scored {}", outlier);
+
+        assert_eq!(model.predict(&[60.0, -40.0]).unwrap().0, 1);
+        assert_eq!(model.predict(&[10.2, 4.9]).unwrap().0, 0);
+        let importances = model.feature_importances().unwrap();
+        assert!((importances.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_detector_uses_isolation_forest_without_labels() {
+        let model = IsolationForestModel::new();
+        let mut detector = AnomalyDetector::new(10)
+            .with_threshold(model.threshold())
+            .with_classifier(Box::new(model));
+        let unlabeled = clustered_normal(300)
+            .into_iter()
+            .map(|x| (x, false))
+            .collect();
+        detector.train(unlabeled).await.unwrap();
+
+        assert!(detector.is_ml_trained());
+        assert!(detector.analyze(&[60.0, -40.0]).await.unwrap().0);
+        assert!(!detector.analyze(&[10.0, 5.0]).await.unwrap().0);
+    }
+}
@@ -1,14 +1,39 @@
 pub mod anomaly_detector;
+pub mod clamping;
+pub mod classifier;
+pub mod drift;
 pub mod features;
+pub mod isolation_forest;
 pub mod llm;
 pub mod random_forest;
+pub mod reputation;
+pub mod retrain;
 pub mod rl_agent;
+pub mod scaling;
+pub mod synthetic;
 
-pub use anomaly_detector::AnomalyDetector;
-pub use features::NetworkFeatures;
-pub use llm::{BehaviorAnalysis, LLMClient, LLMProvider, SessionData};
+pub use anomaly_detector::{
+    AnomalyDetector, Disagreement, DisagreementHook, ScoreDistribution, WindowStats,
+};
+pub use clamping::{ClampRule, FeatureClamp};
+pub use classifier::Classifier;
+pub use drift::{DriftConfig, DriftMonitor, DriftReport};
+pub use features::{ConnectionStats, FeatureError, FeatureExtractor, NetworkFeatures};
+pub use isolation_forest::{IsolationForestConfig, IsolationForestModel};
+pub use llm::{
+    BehaviorAnalysis, LLMClient, LLMConfig, LLMConfigError, LLMProvider, LlmBackend,
+    MockLlmBackend, RetryPolicy, SessionData,
+    validate_prompt_template, DEFAULT_OLLAMA_URL, DEFAULT_PROMPT_TEMPLATE, LLM_API_KEY_ENV,
+};
 pub use random_forest::{ModelMetrics, RandomForestModel};
-pub use rl_agent::{Action, RLAgent, RLConfig, RLStats, RewardCalculator, State};
+pub use reputation::{Reputation, ReputationTracker};
+pub use retrain::{RetrainBuffer, RetrainConfig};
+pub use rl_agent::{
+    Action, RLAgent, RLConfig, RLStats, ReplayBuffer, RewardCalculator, RewardConfig, State,
+    Transition,
+};
+pub use scaling::StandardScaler;
+pub use synthetic::{LabeledSample, SyntheticTrafficGenerator, TrafficClass, TrafficMix};
 
 // Re-export scripting from honeytrap-scripting
 pub use honeytrap_scripting::{PythonScriptEngine, RhaiScriptEngine, ScriptEngine};
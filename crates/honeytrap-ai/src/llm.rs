@@ -1,4 +1,15 @@
+use async_trait::async_trait;
+use honeytrap_metrics::METRICS;
+use lru::LruCache;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// LLM Provider Configuration
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -11,14 +22,332 @@ pub enum LLMProvider {
         api_key: String,
         model: String,
     },
+    /// Lokales Modell via Ollama (Session-Daten verlassen das Netz nicht)
+    Ollama {
+        base_url: String,
+        model: String,
+    },
     #[default]
     Disabled,
 }
 
+/// Umgebungsvariable für den API Key, falls nicht in der Config gesetzt
+pub const LLM_API_KEY_ENV: &str = "HONEYTRAP_LLM_API_KEY";
+
+/// Standard-Prompt für die Session-Analyse
+///
+/// Platzhalter: `{source_ip}`, `{destination_port}`, `{duration}`, `{bytes_sent}`,
+/// `{bytes_received}`, `{failed_logins}`, `{commands}`, `{user_agent}`, `{request_pattern}`.
+/// Unbekannte `{...}` (z.B. das JSON-Schema) bleiben unverändert stehen.
+pub const DEFAULT_PROMPT_TEMPLATE: &str = r#"Analyze this network session for malicious behavior:
+
+Source IP: {source_ip}
+Destination Port: {destination_port}
+Connection Duration: {duration}s
+Bytes Sent: {bytes_sent}
+Bytes Received: {bytes_received}
+Failed Login Attempts: {failed_logins}
+Commands Executed: {commands}
+User Agent: {user_agent}
+Request Pattern: {request_pattern}
+
+Provide analysis in JSON format:
+{
+    "threat_level": "low|medium|high|critical",
+    "threat_score": 0.0-1.0,
+    "is_malicious": true|false,
+    "attack_type": "port_scan|brute_force|sql_injection|xss|ddos|reconnaissance|credential_stuffing|none",
+    "confidence": 0.0-1.0,
+    "indicators": ["list of suspicious indicators"],
+    "recommended_action": "block|honeypot|monitor|allow",
+    "reasoning": "brief explanation"
+}"#;
+
+/// Felder, die ein eigenes Prompt-Template für die JSON-Antwort anfordern muss
+const REQUIRED_RESPONSE_FIELDS: [&str; 4] =
+    ["threat_level", "threat_score", "is_malicious", "attack_type"];
+
+/// Standard-Endpoint einer lokalen Ollama-Instanz
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// LLM Konfiguration (`[llm]` Abschnitt)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LLMConfig {
+    pub enabled: bool,
+    pub provider: String, // "deepseek", "openai" or "ollama"
+    pub api_key: Option<String>,
+    pub model: String,
+    /// Endpoint für "ollama" (Default: `DEFAULT_OLLAMA_URL`)
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Gültigkeit gecachter Analysen in Sekunden (0 = kein Cache)
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    /// Max. Anzahl gecachter Analysen
+    #[serde(default = "default_cache_capacity")]
+    pub cache_capacity: usize,
+    /// Wiederholung bei 429/5xx und Timeouts (`[llm.retry]`)
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    /// Eigenes Prompt-Template (Default: `DEFAULT_PROMPT_TEMPLATE`)
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+}
+
+/// Obergrenze für `Retry-After`, damit ein Provider die Analyse nicht beliebig blockiert
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Retry-Policy für LLM API Calls (exponentielles Backoff mit Jitter)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Versuche inkl. dem ersten (1 = keine Wiederholung)
+    pub max_attempts: u32,
+    /// Wartezeit vor der ersten Wiederholung, verdoppelt sich pro Versuch
+    pub base_delay_ms: u64,
+    /// Zufällige Abweichung als Anteil der Wartezeit (0.2 = ±20%)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay_ms: 500,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wartezeit nach dem fehlgeschlagenen Versuch `attempt` (ab 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self.base_delay_ms as f64 * 2f64.powi(attempt.saturating_sub(1) as i32);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter)
+        } else {
+            1.0
+        };
+        Duration::from_secs_f64(exponential * factor / 1000.0)
+    }
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    300
+}
+
+fn default_cache_capacity() -> usize {
+    1024
+}
+
+impl LLMConfig {
+    /// TTL des Response-Caches (`None` = Cache aus)
+    pub fn cache_ttl(&self) -> Option<Duration> {
+        (self.cache_ttl_secs > 0 && self.cache_capacity > 0)
+            .then(|| Duration::from_secs(self.cache_ttl_secs))
+    }
+}
+
+impl Default for LLMConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: "deepseek".to_string(),
+            api_key: None,
+            model: "deepseek-chat".to_string(),
+            base_url: None,
+            cache_ttl_secs: default_cache_ttl_secs(),
+            cache_capacity: default_cache_capacity(),
+            retry: RetryPolicy::default(),
+            prompt_template: None,
+        }
+    }
+}
+
+/// Fehler beim Aufbau des Providers aus der Config
+#[derive(Debug, thiserror::Error)]
+pub enum LLMConfigError {
+    #[error("unknown LLM provider '{0}' (expected \"deepseek\", \"openai\" or \"ollama\")")]
+    UnknownProvider(String),
+    #[error("LLM provider '{0}' enabled but no API key (set api_key or {LLM_API_KEY_ENV})")]
+    MissingApiKey(String),
+    #[error("LLM prompt template must request a JSON answer ({0})")]
+    InvalidPromptTemplate(String),
+}
+
+/// Prüfen, ob ein Prompt-Template die JSON-Antwort mit allen ausgewerteten Feldern anfordert
+pub fn validate_prompt_template(template: &str) -> Result<(), LLMConfigError> {
+    if !template.to_lowercase().contains("json") {
+        return Err(LLMConfigError::InvalidPromptTemplate(
+            "no JSON output instruction".to_string(),
+        ));
+    }
+
+    let missing: Vec<_> = REQUIRED_RESPONSE_FIELDS
+        .iter()
+        .filter(|field| !template.contains(&format!("\"{}\"", field)))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(LLMConfigError::InvalidPromptTemplate(format!(
+            "missing fields {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(())
+}
+
+/// Platzhalter in einem Durchgang ersetzen
+///
+/// Eingesetzte Werte (z.B. Commands des Angreifers) werden nicht erneut expandiert.
+fn render_prompt(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut prompt = String::with_capacity(template.len() + 256);
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        prompt.push_str(&rest[..start]);
+        let candidate = &rest[start + 1..];
+        let replacement = candidate
+            .find('}')
+            .and_then(|end| value(&candidate[..end]).map(|v| (v, end)));
+
+        match replacement {
+            Some((replacement, end)) => {
+                prompt.push_str(&replacement);
+                rest = &candidate[end + 1..];
+            }
+            None => {
+                prompt.push('{');
+                rest = candidate;
+            }
+        }
+    }
+    prompt.push_str(rest);
+
+    prompt
+}
+
+impl LLMProvider {
+    /// Provider aus der Config bauen
+    ///
+    /// Ohne `api_key` in der Config wird `HONEYTRAP_LLM_API_KEY` gelesen,
+    /// damit Secrets nicht im Klartext in der Datei stehen müssen.
+    pub fn from_config(config: &LLMConfig) -> Result<Self, LLMConfigError> {
+        Self::from_config_with_key(
+            config,
+            config
+                .api_key
+                .clone()
+                .or_else(|| std::env::var(LLM_API_KEY_ENV).ok()),
+        )
+    }
+
+    fn from_config_with_key(
+        config: &LLMConfig,
+        api_key: Option<String>,
+    ) -> Result<Self, LLMConfigError> {
+        if !config.enabled {
+            return Ok(LLMProvider::Disabled);
+        }
+
+        let provider = config.provider.to_lowercase();
+        if !matches!(provider.as_str(), "deepseek" | "openai" | "ollama") {
+            return Err(LLMConfigError::UnknownProvider(config.provider.clone()));
+        }
+
+        // Lokales Modell braucht keinen API Key
+        if provider == "ollama" {
+            return Ok(LLMProvider::Ollama {
+                base_url: config
+                    .base_url
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string()),
+                model: config.model.clone(),
+            });
+        }
+
+        let api_key = api_key
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| LLMConfigError::MissingApiKey(config.provider.clone()))?;
+        let model = config.model.clone();
+
+        Ok(match provider.as_str() {
+            "openai" => LLMProvider::OpenAI { api_key, model },
+            _ => LLMProvider::DeepSeek { api_key, model },
+        })
+    }
+}
+
+/// LRU-Cache für Analysen nahezu identischer Sessions (z.B. wiederkehrende Scanner)
+struct ResponseCache {
+    entries: Mutex<LruCache<u64, (Instant, BehaviorAnalysis)>>,
+    ttl: Duration,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ResponseCache {
+    fn new(capacity: NonZeroUsize, ttl: Duration) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+            ttl,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Stabiler Key: Quell-IP, Ziel-Port, Command-Menge und Request Pattern
+    fn key(session_data: &SessionData) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        session_data.source_ip.hash(&mut hasher);
+        session_data.destination_port.hash(&mut hasher);
+        session_data
+            .commands
+            .iter()
+            .collect::<BTreeSet<_>>()
+            .hash(&mut hasher);
+        session_data.request_pattern.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn get(&self, key: u64) -> Option<BehaviorAnalysis> {
+        let mut entries = self.entries.lock().unwrap();
+        let cached = match entries.get(&key) {
+            Some((at, analysis)) if at.elapsed() < self.ttl => Some(analysis.clone()),
+            Some(_) => {
+                entries.pop(&key);
+                None
+            }
+            None => None,
+        };
+        drop(entries);
+
+        let (counter, result) = match cached {
+            Some(_) => (&self.hits, "hit"),
+            None => (&self.misses, "miss"),
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        METRICS.ml.llm_cache.with_label_values(&[result]).inc();
+        cached
+    }
+
+    fn insert(&self, key: u64, analysis: BehaviorAnalysis) {
+        self.entries
+            .lock()
+            .unwrap()
+            .put(key, (Instant::now(), analysis));
+    }
+}
+
 /// LLM Client für Verhaltensanalyse
 pub struct LLMClient {
     provider: LLMProvider,
     client: reqwest::Client,
+    cache: Option<ResponseCache>,
+    retry: RetryPolicy,
+    prompt_template: Option<String>,
 }
 
 impl LLMClient {
@@ -30,14 +359,85 @@ impl LLMClient {
                 .timeout(std::time::Duration::from_secs(30))
                 .build()
                 .unwrap(),
+            cache: None,
+            retry: RetryPolicy::default(),
+            prompt_template: None,
         }
     }
 
+    /// Mit eigenem Prompt-Template (Platzhalter siehe `DEFAULT_PROMPT_TEMPLATE`)
+    pub fn with_prompt_template(
+        mut self,
+        template: impl Into<String>,
+    ) -> Result<Self, LLMConfigError> {
+        let template = template.into();
+        validate_prompt_template(&template)?;
+        self.prompt_template = Some(template);
+        Ok(self)
+    }
+
+    /// Mit eigener Retry-Policy für 429/5xx und Timeouts
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Mit Response-Cache: identische Sessions innerhalb von `ttl` nur einmal analysieren
+    ///
+    /// Parallele Anfragen zur selben Session können beide das LLM erreichen.
+    pub fn with_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.cache = NonZeroUsize::new(capacity).map(|capacity| ResponseCache::new(capacity, ttl));
+        self
+    }
+
+    /// Treffer im Response-Cache
+    pub fn cache_hits(&self) -> u64 {
+        self.cache
+            .as_ref()
+            .map_or(0, |cache| cache.hits.load(Ordering::Relaxed))
+    }
+
+    /// Fehlgeschlagene Cache-Lookups (= LLM-Aufrufe)
+    pub fn cache_misses(&self) -> u64 {
+        self.cache
+            .as_ref()
+            .map_or(0, |cache| cache.misses.load(Ordering::Relaxed))
+    }
+
     /// Verhaltensanalyse via LLM
     pub async fn analyze_behavior(
         &self,
         session_data: &SessionData,
     ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>> {
+        if matches!(self.provider, LLMProvider::Disabled) {
+            return Ok(BehaviorAnalysis::default());
+        }
+
+        let Some(cache) = &self.cache else {
+            return Ok(self.request_analysis(session_data).await?.unwrap_or_default());
+        };
+
+        let key = ResponseCache::key(session_data);
+        if let Some(analysis) = cache.get(key) {
+            tracing::debug!("🧠 LLM cache hit for {}", session_data.source_ip);
+            return Ok(analysis);
+        }
+
+        // API-Fehler (None) werden nicht gecacht
+        match self.request_analysis(session_data).await? {
+            Some(analysis) => {
+                cache.insert(key, analysis.clone());
+                Ok(analysis)
+            }
+            None => Ok(BehaviorAnalysis::default()),
+        }
+    }
+
+    /// Analyse beim Provider anfragen (`None` = API-Fehler)
+    async fn request_analysis(
+        &self,
+        session_data: &SessionData,
+    ) -> Result<Option<BehaviorAnalysis>, Box<dyn std::error::Error>> {
         match &self.provider {
             LLMProvider::DeepSeek { api_key, model } => {
                 self.analyze_with_deepseek(api_key, model, session_data)
@@ -46,7 +446,10 @@ impl LLMClient {
             LLMProvider::OpenAI { api_key, model } => {
                 self.analyze_with_openai(api_key, model, session_data).await
             }
-            LLMProvider::Disabled => Ok(BehaviorAnalysis::default()),
+            LLMProvider::Ollama { base_url, model } => {
+                self.analyze_with_ollama(base_url, model, session_data).await
+            }
+            LLMProvider::Disabled => Ok(Some(BehaviorAnalysis::default())),
         }
     }
 
@@ -56,7 +459,7 @@ impl LLMClient {
         api_key: &str,
         model: &str,
         session_data: &SessionData,
-    ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>> {
+    ) -> Result<Option<BehaviorAnalysis>, Box<dyn std::error::Error>> {
         let prompt = self.build_analysis_prompt(session_data);
 
         let request = serde_json::json!({
@@ -77,23 +480,18 @@ impl LLMClient {
 
         tracing::debug!("🤖 Calling DeepSeek API...");
 
-        let response = self
+        let request = self
             .client
             .post("https://api.deepseek.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            tracing::error!("DeepSeek API error: {}", error_text);
-            return Ok(BehaviorAnalysis::default());
-        }
+            .json(&request);
+        let Some(response) = self.send_with_retry("DeepSeek", request).await? else {
+            return Ok(None);
+        };
 
         let result: DeepSeekResponse = response.json().await?;
-        self.parse_llm_response(&result.choices[0].message.content)
+        self.parse_llm_response(&result.choices[0].message.content).map(Some)
     }
 
     /// OpenAI API Call
@@ -102,7 +500,7 @@ impl LLMClient {
         api_key: &str,
         model: &str,
         session_data: &SessionData,
-    ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>> {
+    ) -> Result<Option<BehaviorAnalysis>, Box<dyn std::error::Error>> {
         let prompt = self.build_analysis_prompt(session_data);
 
         let request = serde_json::json!({
@@ -123,61 +521,133 @@ impl LLMClient {
 
         tracing::debug!("🤖 Calling OpenAI API...");
 
-        let response = self
+        let request = self
             .client
             .post("https://api.openai.com/v1/chat/completions")
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let error_text = response.text().await?;
-            tracing::error!("OpenAI API error: {}", error_text);
-            return Ok(BehaviorAnalysis::default());
-        }
+            .json(&request);
+        let Some(response) = self.send_with_retry("OpenAI", request).await? else {
+            return Ok(None);
+        };
 
         let result: OpenAIResponse = response.json().await?;
-        self.parse_llm_response(&result.choices[0].message.content)
+        self.parse_llm_response(&result.choices[0].message.content).map(Some)
+    }
+
+    /// Ollama API Call (lokales Modell)
+    async fn analyze_with_ollama(
+        &self,
+        base_url: &str,
+        model: &str,
+        session_data: &SessionData,
+    ) -> Result<Option<BehaviorAnalysis>, Box<dyn std::error::Error>> {
+        let prompt = self.build_analysis_prompt(session_data);
+
+        let request = serde_json::json!({
+            "model": model,
+            "messages": [
+                {
+                    "role": "system",
+                    "content": "You are a cybersecurity expert analyzing network traffic for malicious behavior. Respond with JSON only."
+                },
+                {
+                    "role": "user",
+                    "content": prompt
+                }
+            ],
+            "stream": false,
+            "format": "json",
+            "options": { "temperature": 0.3 }
+        });
+
+        tracing::debug!("🤖 Calling Ollama at {}...", base_url);
+
+        let request = self
+            .client
+            .post(format!("{}/api/chat", base_url.trim_end_matches('/')))
+            .header("Content-Type", "application/json")
+            .json(&request);
+        let Some(response) = self.send_with_retry("Ollama", request).await? else {
+            return Ok(None);
+        };
+
+        let result: OllamaResponse = response.json().await?;
+        self.parse_llm_response(&result.message.content).map(Some)
+    }
+
+    /// Request senden, bei 429/5xx und Timeouts laut Retry-Policy wiederholen
+    ///
+    /// `None`, wenn der Provider endgültig mit einem Fehlerstatus antwortet.
+    async fn send_with_retry(
+        &self,
+        provider: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Option<reqwest::Response>, Box<dyn std::error::Error>> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+
+        loop {
+            let pending = request
+                .try_clone()
+                .ok_or("LLM request body cannot be retried")?;
+            let (reason, retry_after) = match pending.send().await {
+                Ok(response) if response.status().is_success() => return Ok(Some(response)),
+                Ok(response) if is_retryable(response.status()) && attempt < max_attempts => {
+                    (response.status().to_string(), retry_after(&response))
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let error_text = response.text().await?;
+                    tracing::error!(
+                        "{} API error after {} attempt(s): {} {}",
+                        provider,
+                        attempt,
+                        status,
+                        error_text
+                    );
+                    return Ok(None);
+                }
+                Err(e) if e.is_timeout() && attempt < max_attempts => ("timeout".to_string(), None),
+                Err(e) => return Err(e.into()),
+            };
+
+            let delay = retry_after.unwrap_or_else(|| self.retry.backoff(attempt));
+            METRICS.ml.llm_retries.with_label_values(&[provider]).inc();
+            tracing::warn!(
+                "🔁 {} API {} - retrying in {:.1}s (attempt {}/{})",
+                provider,
+                reason,
+                delay.as_secs_f64(),
+                attempt + 1,
+                max_attempts
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     /// Prompt für LLM erstellen
     fn build_analysis_prompt(&self, session_data: &SessionData) -> String {
-        format!(
-            r#"Analyze this network session for malicious behavior:
-
-Source IP: {}
-Destination Port: {}
-Connection Duration: {:.2}s
-Bytes Sent: {}
-Bytes Received: {}
-Failed Login Attempts: {}
-Commands Executed: {:?}
-User Agent: {:?}
-Request Pattern: {:?}
+        let template = self
+            .prompt_template
+            .as_deref()
+            .unwrap_or(DEFAULT_PROMPT_TEMPLATE);
 
-Provide analysis in JSON format:
-{{
-    "threat_level": "low|medium|high|critical",
-    "threat_score": 0.0-1.0,
-    "is_malicious": true|false,
-    "attack_type": "port_scan|brute_force|sql_injection|xss|ddos|reconnaissance|credential_stuffing|none",
-    "confidence": 0.0-1.0,
-    "indicators": ["list of suspicious indicators"],
-    "recommended_action": "block|honeypot|monitor|allow",
-    "reasoning": "brief explanation"
-}}"#,
-            session_data.source_ip,
-            session_data.destination_port,
-            session_data.duration_secs,
-            session_data.bytes_sent,
-            session_data.bytes_received,
-            session_data.failed_login_attempts,
-            session_data.commands,
-            session_data.user_agent,
-            session_data.request_pattern,
-        )
+        render_prompt(template, |placeholder| {
+            Some(match placeholder {
+                "source_ip" => session_data.source_ip.clone(),
+                "destination_port" => session_data.destination_port.to_string(),
+                "duration" => format!("{:.2}", session_data.duration_secs),
+                "bytes_sent" => session_data.bytes_sent.to_string(),
+                "bytes_received" => session_data.bytes_received.to_string(),
+                "failed_logins" => session_data.failed_login_attempts.to_string(),
+                "commands" => format!("{:?}", session_data.commands),
+                "user_agent" => format!("{:?}", session_data.user_agent),
+                "request_pattern" => format!("{:?}", session_data.request_pattern),
+                _ => return None,
+            })
+        })
     }
 
     /// LLM Response parsen
@@ -213,6 +683,81 @@ Provide analysis in JSON format:
     }
 }
 
+/// Backend für die Verhaltensanalyse, gegen das `AnomalyDetector` arbeitet
+///
+/// Implementiert von `LLMClient`; `MockLlmBackend` ersetzt ihn in Tests.
+#[async_trait]
+pub trait LlmBackend: Send + Sync {
+    /// Session analysieren
+    async fn analyze_behavior(
+        &self,
+        session_data: &SessionData,
+    ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>>;
+}
+
+#[async_trait]
+impl LlmBackend for LLMClient {
+    async fn analyze_behavior(
+        &self,
+        session_data: &SessionData,
+    ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>> {
+        LLMClient::analyze_behavior(self, session_data).await
+    }
+}
+
+/// Backend mit fest vorgegebener Analyse für deterministische Tests ohne API
+///
+/// Klone teilen den Aufrufzähler.
+#[derive(Debug, Clone)]
+pub struct MockLlmBackend {
+    analysis: BehaviorAnalysis,
+    calls: Arc<AtomicU64>,
+}
+
+impl MockLlmBackend {
+    /// Backend, das jede Session mit `analysis` beantwortet
+    pub fn new(analysis: BehaviorAnalysis) -> Self {
+        Self {
+            analysis,
+            calls: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Anzahl bisheriger Analysen
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl LlmBackend for MockLlmBackend {
+    async fn analyze_behavior(
+        &self,
+        _session_data: &SessionData,
+    ) -> Result<BehaviorAnalysis, Box<dyn std::error::Error>> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        Ok(self.analysis.clone())
+    }
+}
+
+/// Rate Limit und Serverfehler gelten als vorübergehend
+fn is_retryable(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` in Sekunden (HTTP-Datum wird ignoriert), gedeckelt auf `MAX_RETRY_AFTER`
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|secs| Duration::from_secs(secs).min(MAX_RETRY_AFTER))
+}
+
 /// Session-Daten für LLM-Analyse
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionData {
@@ -266,6 +811,12 @@ struct OpenAIResponse {
     choices: Vec<Choice>,
 }
 
+/// Ollama `/api/chat` ohne Streaming: eine einzelne Message
+#[derive(Debug, Deserialize)]
+struct OllamaResponse {
+    message: Message,
+}
+
 #[derive(Debug, Deserialize)]
 struct Choice {
     message: Message,
@@ -275,3 +826,274 @@ struct Choice {
 struct Message {
     content: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(provider: &str) -> LLMConfig {
+        LLMConfig {
+            enabled: true,
+            provider: provider.to_string(),
+            api_key: Some("sk-test".to_string()),
+            model: "gpt-4o-mini".to_string(),
+            ..LLMConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_from_config_known_provider() {
+        match LLMProvider::from_config(&config("OpenAI")).unwrap() {
+            LLMProvider::OpenAI { api_key, model } => {
+                assert_eq!(api_key, "sk-test");
+                assert_eq!(model, "gpt-4o-mini");
+            }
+            other => panic!("unexpected provider: {:?}", other),
+        }
+
+        let disabled = LLMConfig {
+            enabled: false,
+            ..config("unknown")
+        };
+        assert!(matches!(
+            LLMProvider::from_config(&disabled).unwrap(),
+            LLMProvider::Disabled
+        ));
+    }
+
+    type Requests = std::sync::Arc<Mutex<Vec<String>>>;
+
+    /// Lokaler Ollama-Mock: beantwortet jede Verbindung mit einer Brute-Force-Analyse
+    ///
+    /// `failures` (Statuszeile plus optionale Header) werden vorher der Reihe nach geliefert.
+    async fn spawn_mock_ollama(failures: &[&str]) -> (String, Requests) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Requests::default();
+        let seen = requests.clone();
+        let mut failures: std::collections::VecDeque<String> =
+            failures.iter().map(|f| f.to_string()).collect();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut chunk = [0u8; 4096];
+                // Body endet mit der schließenden Klammer des JSON-Objekts
+                while !request.ends_with(b"}") {
+                    let read = socket.read(&mut chunk).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&chunk[..read]);
+                }
+                seen.lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&request).into_owned());
+
+                if let Some(failure) = failures.pop_front() {
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Length: 4\r\nConnection: close\r\n\r\nbusy",
+                        failure
+                    );
+                    socket.write_all(response.as_bytes()).await.unwrap();
+                    continue;
+                }
+
+                let content = serde_json::json!({
+                    "threat_level": "high",
+                    "threat_score": 0.9,
+                    "is_malicious": true,
+                    "attack_type": "brute_force",
+                    "indicators": ["42 failed logins"],
+                })
+                .to_string();
+                let body = serde_json::json!({
+                    "model": "llama3.1",
+                    "message": { "role": "assistant", "content": content },
+                    "done": true
+                })
+                .to_string();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (base_url, requests)
+    }
+
+    fn session_data(source_ip: &str, commands: &[&str]) -> SessionData {
+        SessionData {
+            source_ip: source_ip.to_string(),
+            destination_port: 22,
+            duration_secs: 12.5,
+            bytes_sent: 4096,
+            bytes_received: 512,
+            failed_login_attempts: 42,
+            commands: commands.iter().map(|c| c.to_string()).collect(),
+            user_agent: None,
+            request_pattern: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ollama_provider_posts_to_local_chat_endpoint() {
+        let ollama = LLMConfig {
+            api_key: None,
+            model: "llama3.1".to_string(),
+            ..config("ollama")
+        };
+        match LLMProvider::from_config_with_key(&ollama, None).unwrap() {
+            LLMProvider::Ollama { base_url, model } => {
+                assert_eq!(base_url, DEFAULT_OLLAMA_URL);
+                assert_eq!(model, "llama3.1");
+            }
+            other => panic!("unexpected provider: {:?}", other),
+        }
+
+        let (base_url, requests) = spawn_mock_ollama(&[]).await;
+        let client = LLMClient::new(LLMProvider::Ollama {
+            base_url,
+            model: "llama3.1".to_string(),
+        });
+        let analysis = client
+            .analyze_behavior(&session_data("203.0.113.5", &[]))
+            .await
+            .unwrap();
+
+        assert!(analysis.is_malicious);
+        assert_eq!(analysis.attack_type, "brute_force");
+        assert_eq!(analysis.indicators, ["42 failed logins"]);
+
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("POST /api/chat HTTP/1.1"));
+        assert!(!request.to_lowercase().contains("authorization"));
+        assert!(request.contains("\"stream\":false"));
+        assert!(request.contains("Failed Login Attempts: 42"));
+    }
+
+    #[tokio::test]
+    async fn test_identical_sessions_hit_cache_with_single_http_call() {
+        let (base_url, requests) = spawn_mock_ollama(&[]).await;
+        let client = LLMClient::new(LLMProvider::Ollama {
+            base_url,
+            model: "llama3.1".to_string(),
+        })
+        .with_cache(16, Duration::from_secs(60));
+
+        let first = client
+            .analyze_behavior(&session_data("203.0.113.5", &["uname -a", "id"]))
+            .await
+            .unwrap();
+        // Gleiche Command-Menge in anderer Reihenfolge
+        let second = client
+            .analyze_behavior(&session_data("203.0.113.5", &["id", "uname -a"]))
+            .await
+            .unwrap();
+
+        assert_eq!(requests.lock().unwrap().len(), 1);
+        assert_eq!(second.attack_type, first.attack_type);
+        assert_eq!((client.cache_hits(), client.cache_misses()), (1, 1));
+
+        // Andere Quelle -> eigener Eintrag
+        client
+            .analyze_behavior(&session_data("203.0.113.6", &["id"]))
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert_eq!(client.cache_misses(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_and_server_errors_are_retried() {
+        let retry = RetryPolicy {
+            max_attempts: 3,
+            base_delay_ms: 1,
+            jitter: 0.0,
+        };
+        let failures = ["429 Too Many Requests\r\nRetry-After: 0", "503 Service Unavailable"];
+
+        let (base_url, requests) = spawn_mock_ollama(&failures).await;
+        let client = LLMClient::new(LLMProvider::Ollama {
+            base_url,
+            model: "llama3.1".to_string(),
+        })
+        .with_retry_policy(retry.clone());
+        let analysis = client
+            .analyze_behavior(&session_data("203.0.113.5", &[]))
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 3);
+        assert_eq!(analysis.attack_type, "brute_force");
+
+        // Versuche erschöpft -> Default-Analyse statt Fehler
+        let (base_url, requests) = spawn_mock_ollama(&failures).await;
+        let client = LLMClient::new(LLMProvider::Ollama {
+            base_url,
+            model: "llama3.1".to_string(),
+        })
+        .with_retry_policy(RetryPolicy {
+            max_attempts: 2,
+            ..retry
+        });
+        let analysis = client
+            .analyze_behavior(&session_data("203.0.113.5", &[]))
+            .await
+            .unwrap();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+        assert!(!analysis.is_malicious);
+
+        let backoff = RetryPolicy::default();
+        assert_eq!(
+            RetryPolicy { jitter: 0.0, ..backoff.clone() }.backoff(3),
+            Duration::from_millis(2000)
+        );
+        let jittered = backoff.backoff(1);
+        assert!(jittered >= Duration::from_millis(400) && jittered <= Duration::from_millis(600));
+    }
+
+    #[test]
+    fn test_prompt_template_placeholders_and_validation() {
+        let client = LLMClient::new(LLMProvider::Disabled);
+        let session = session_data("203.0.113.5", &["cat {source_ip}"]);
+        let prompt = client.build_analysis_prompt(&session);
+        assert!(prompt.contains("\nSource IP: 203.0.113.5\nDestination Port: 22\n"));
+        assert!(prompt.contains("Connection Duration: 12.50s"));
+        assert!(prompt.contains("{\n    \"threat_level\""));
+
+        let template = "Sitzung von {source_ip}:{destination_port}, {duration}s: {commands} {x}\n\
+                        JSON: {\"threat_level\", \"threat_score\", \"is_malicious\", \
+                        \"attack_type\"}";
+        let client = client.with_prompt_template(template).unwrap();
+        let prompt = client.build_analysis_prompt(&session);
+        // Eingesetzte Commands werden nicht erneut expandiert
+        assert!(prompt.starts_with(
+            "Sitzung von 203.0.113.5:22, 12.50s: [\"cat {source_ip}\"] {x}\nJSON: {\"threat_level\""
+        ));
+
+        assert!(validate_prompt_template(DEFAULT_PROMPT_TEMPLATE).is_ok());
+        let rejected = LLMClient::new(LLMProvider::Disabled)
+            .with_prompt_template("Is {source_ip} malicious? Answer yes or no.");
+        assert!(matches!(
+            rejected,
+            Err(LLMConfigError::InvalidPromptTemplate(_))
+        ));
+        let err = validate_prompt_template("Reply in JSON with \"threat_score\"").unwrap_err();
+        assert!(err.to_string().contains("threat_level, is_malicious, attack_type"));
+    }
+
+    #[test]
+    fn test_from_config_unknown_provider_errors() {
+        let err = LLMProvider::from_config(&config("anthropics")).unwrap_err();
+        assert!(matches!(err, LLMConfigError::UnknownProvider(ref p) if p == "anthropics"));
+
+        let err = LLMProvider::from_config_with_key(&config("deepseek"), None).unwrap_err();
+        assert!(matches!(err, LLMConfigError::MissingApiKey(_)));
+    }
+}
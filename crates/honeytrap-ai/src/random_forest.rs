@@ -1,13 +1,32 @@
 //! RandomForest ML Model für Anomalie-Erkennung
 //!
-//! Implementiert einen Random Forest Klassifikator mit smartcore
-
+//! Implementiert einen Random Forest Klassifikator mit smartcore. Jeder Baum
+//! ist ein eigener smartcore-Forest mit `n_trees = 1` (eigener Seed, eigenes
+//! Bootstrap-Sample), da smartcore die Stimmen einzelner Bäume nicht
+//! herausgibt. So ist die Wahrscheinlichkeit einer Klasse der Anteil der
+//! Bäume, die für sie stimmen.
+//!
+//! smartcore setzt Split-Kandidaten nur an Labelwechsel in der sortierten
+//! Spalte. Identische Feature-Vektoren mit verschiedenen Labels verhindern
+//! dadurch Splits hinter ihnen. Pro Baum bekommen solche Gruppen deshalb das
+//! Label eines zufällig gezogenen Mitglieds (Anteil bleibt über die Bäume
+//! erhalten).
+
+use crate::clamping::FeatureClamp;
+use crate::classifier::Classifier;
+use crate::scaling::StandardScaler;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 use smartcore::ensemble::random_forest_classifier::RandomForestClassifier;
 use smartcore::linalg::basic::matrix::DenseMatrix;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 
+/// Ein trainierter Baum (smartcore-Forest mit einem Baum)
+type RfClassifier = RandomForestClassifier<f64, usize, DenseMatrix<f64>, Vec<usize>>;
+
 /// Configuration for RandomForest model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RandomForestConfig {
@@ -30,33 +49,63 @@ impl Default for RandomForestConfig {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RandomForestModel {
     #[serde(skip)]
-    classifier: Option<RandomForestClassifier<f64, usize, DenseMatrix<f64>, Vec<usize>>>,
+    trees: Vec<RfClassifier>,
     config: RandomForestConfig,
     accuracy: f64,
     is_trained: bool,
+    /// Anzahl Features, mit denen trainiert wurde
+    #[serde(default)]
+    n_features: usize,
+    /// Outlier Clamping, bei Training und Prediction identisch angewendet
+    #[serde(default)]
+    clamp: FeatureClamp,
+    /// Anzahl Klassen (größtes Label + 1)
+    #[serde(default = "default_n_classes")]
+    n_classes: usize,
+    /// Standardisierung nach dem Clamping, bei Training und Prediction identisch
+    #[serde(default)]
+    scaler: StandardScaler,
+}
+
+fn default_n_classes() -> usize {
+    2
 }
 
 impl RandomForestModel {
     /// Create new model with default configuration
     pub fn new() -> Self {
         Self {
-            classifier: None,
+            trees: Vec::new(),
             config: RandomForestConfig::default(),
             accuracy: 0.0,
             is_trained: false,
+            n_features: 0,
+            clamp: FeatureClamp::default(),
+            n_classes: default_n_classes(),
+            scaler: StandardScaler::default(),
         }
     }
 
     /// Create new model with custom configuration
     pub fn with_config(config: RandomForestConfig) -> Self {
         Self {
-            classifier: None,
+            trees: Vec::new(),
             config,
             accuracy: 0.0,
             is_trained: false,
+            n_features: 0,
+            clamp: FeatureClamp::default(),
+            n_classes: default_n_classes(),
+            scaler: StandardScaler::default(),
         }
     }
 
+    /// Mit Outlier Clamping pro Feature
+    pub fn with_clamping(mut self, clamp: FeatureClamp) -> Self {
+        self.clamp = clamp;
+        self
+    }
+
     /// Train the RandomForest model
     pub fn train(
         &mut self,
@@ -71,29 +120,49 @@ impl RandomForestModel {
 
         use smartcore::ensemble::random_forest_classifier::RandomForestClassifierParameters;
 
-        // SmartCore RandomForestClassifier Parameter
+        // SmartCore RandomForestClassifier Parameter (ein Baum pro Fit)
         let params = RandomForestClassifierParameters::default()
-            .with_n_trees(self.config.n_trees)
+            .with_n_trees(1)
             .with_max_depth(self.config.max_depth)
             .with_min_samples_split(self.config.min_samples_split);
 
+        // Perzentil-Grenzen aus den Rohdaten, dann identisch kappen wie bei Prediction
+        let mut x_train = x_train;
+        self.clamp.fit(&x_train);
+        for sample in &mut x_train {
+            self.clamp.apply(sample);
+        }
+        self.scaler = StandardScaler::fit(&x_train);
+        for sample in &mut x_train {
+            self.scaler.apply(sample);
+        }
+
         // Convert to smartcore format
         let x_dense = DenseMatrix::from_2d_vec(&x_train);
 
-        // Train model
-        let trained_classifier = RandomForestClassifier::fit(&x_dense, &y_train, params)?;
+        // Train model: Seed pro Baum, damit Bootstrap und Feature-Auswahl variieren
+        let ties = tie_groups(&x_train, &y_train);
+        let trees = (0..self.config.n_trees.max(1))
+            .map(|seed| {
+                let params = params.clone().with_seed(seed as u64);
+                let y_tree = resolve_ties(&y_train, &ties, seed as u64);
+                RandomForestClassifier::fit(&x_dense, &y_tree, params)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.trees = trees;
+        self.n_classes = y_train.iter().max().map_or(1, |max| max + 1).max(2);
 
         // Calculate accuracy on training data
-        let predictions = trained_classifier.predict(&x_dense)?;
+        let predictions = self.vote(&x_dense)?;
         let correct = predictions
             .iter()
             .zip(y_train.iter())
-            .filter(|(pred, actual)| pred == actual)
+            .filter(|(votes, actual)| argmax(votes) == **actual)
             .count();
 
         self.accuracy = correct as f64 / y_train.len() as f64;
-        self.classifier = Some(trained_classifier);
         self.is_trained = true;
+        self.n_features = x_train[0].len();
 
         tracing::info!("✅ Training accuracy: {:.4}", self.accuracy);
 
@@ -101,45 +170,81 @@ impl RandomForestModel {
     }
 
     /// Make prediction for single sample
+    ///
+    /// Liefert die Klasse mit den meisten Stimmen und den Anteil der Bäume,
+    /// die für sie gestimmt haben.
     pub fn predict(&self, features: &[f64]) -> Result<(usize, f64), Box<dyn Error>> {
-        if !self.is_trained {
-            return Err("Model not trained yet".into());
-        }
-
-        let classifier = self
-            .classifier
-            .as_ref()
-            .ok_or("Model not initialized")?;
-
-        // Convert to smartcore format (1 sample)
-        let x_vec = vec![features.to_vec()];
-        let x = DenseMatrix::from_2d_vec(&x_vec);
-
-        // Predict
-        let predictions = classifier.predict(&x)?;
-        let prediction = predictions[0];
+        let probabilities = self.predict_proba(features)?;
+        let prediction = argmax(&probabilities);
+        Ok((prediction, probabilities[prediction]))
+    }
 
-        // Get probability (simplified - smartcore doesn't expose predict_proba for all classifiers)
-        let probability = if prediction == 1 { 0.8 } else { 0.2 };
+    /// Wahrscheinlichkeit pro Klasse (Index = Label, Summe = 1)
+    pub fn predict_proba(&self, features: &[f64]) -> Result<Vec<f64>, Box<dyn Error>> {
+        let votes = self.votes_for(vec![features.to_vec()])?;
+        Ok(self.probabilities(&votes[0]))
+    }
 
-        Ok((prediction, probability))
+    /// Predictions inkl. Wahrscheinlichkeit für mehrere Samples
+    pub fn predict_many(
+        &self,
+        features: &[Vec<f64>],
+    ) -> Result<Vec<(usize, f64)>, Box<dyn Error>> {
+        let votes = self.votes_for(features.to_vec())?;
+        Ok(votes
+            .iter()
+            .map(|votes| {
+                let prediction = argmax(votes);
+                (prediction, self.probabilities(votes)[prediction])
+            })
+            .collect())
     }
 
     /// Make predictions for batch of samples
     pub fn predict_batch(&self, features: Vec<Vec<f64>>) -> Result<Vec<usize>, Box<dyn Error>> {
+        let votes = self.votes_for(features)?;
+        Ok(votes.iter().map(|votes| argmax(votes)).collect())
+    }
+
+    /// Features prüfen, kappen und skalieren, dann Stimmen pro Sample zählen
+    fn votes_for(&self, features: Vec<Vec<f64>>) -> Result<Vec<Vec<usize>>, Box<dyn Error>> {
         if !self.is_trained {
             return Err("Model not trained yet".into());
         }
+        if self.trees.is_empty() {
+            return Err("Model not initialized".into());
+        }
 
-        let classifier = self
-            .classifier
-            .as_ref()
-            .ok_or("Model not initialized")?;
+        let mut features = features;
+        for sample in &mut features {
+            self.check_feature_count(sample.len())?;
+            self.clamp.apply(sample);
+            self.scaler.apply(sample);
+        }
 
         let x_dense = DenseMatrix::from_2d_vec(&features);
-        let predictions = classifier.predict(&x_dense)?;
+        self.vote(&x_dense)
+    }
+
+    /// Stimmen pro Sample und Klasse über alle Bäume
+    fn vote(&self, x: &DenseMatrix<f64>) -> Result<Vec<Vec<usize>>, Box<dyn Error>> {
+        let mut votes: Vec<Vec<usize>> = Vec::new();
+        for tree in &self.trees {
+            let predictions = tree.predict(x)?;
+            votes.resize_with(predictions.len(), || vec![0; self.n_classes]);
+            for (sample_votes, prediction) in votes.iter_mut().zip(predictions) {
+                if prediction >= sample_votes.len() {
+                    sample_votes.resize(prediction + 1, 0);
+                }
+                sample_votes[prediction] += 1;
+            }
+        }
+        Ok(votes)
+    }
 
-        Ok(predictions)
+    fn probabilities(&self, votes: &[usize]) -> Vec<f64> {
+        let total = votes.iter().sum::<usize>().max(1) as f64;
+        votes.iter().map(|&count| count as f64 / total).collect()
     }
 
     /// Evaluate model on test set
@@ -148,17 +253,7 @@ impl RandomForestModel {
         x_test: Vec<Vec<f64>>,
         y_test: Vec<usize>,
     ) -> Result<ModelMetrics, Box<dyn Error>> {
-        if !self.is_trained {
-            return Err("Model not trained yet".into());
-        }
-
-        let classifier = self
-            .classifier
-            .as_ref()
-            .ok_or("Model not initialized")?;
-
-        let x_dense = DenseMatrix::from_2d_vec(&x_test);
-        let predictions = classifier.predict(&x_dense)?;
+        let predictions = self.predict_batch(x_test)?;
 
         // Calculate metrics
         let mut tp = 0; // True Positives
@@ -208,9 +303,14 @@ impl RandomForestModel {
         }
 
         let data = ModelData {
+            trees: Some(self.trees.as_slice()),
             config: self.config.clone(),
             accuracy: self.accuracy,
             is_trained: self.is_trained,
+            n_features: self.n_features,
+            clamp: self.clamp.clone(),
+            n_classes: self.n_classes,
+            scaler: self.scaler.clone(),
         };
 
         let json = serde_json::to_string_pretty(&data)?;
@@ -222,16 +322,39 @@ impl RandomForestModel {
     /// Load model from file
     pub fn load<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Box<dyn Error>> {
         let json = fs::read_to_string(path)?;
-        let data: ModelData = serde_json::from_str(&json)?;
+        let data: ModelData<Vec<RfClassifier>> = serde_json::from_str(&json)?;
+
+        // Ältere Dateien enthalten nur die Config: dann muss neu trainiert werden
+        let trees = data.trees.unwrap_or_default();
+        let is_trained = data.is_trained && !trees.is_empty();
+        if data.is_trained && !is_trained {
+            tracing::warn!("⚠️ Model file has no trees, retraining required");
+        }
 
         Ok(Self {
-            classifier: None, // Cannot serialize RandomForest, needs retraining
+            trees,
             config: data.config,
             accuracy: data.accuracy,
-            is_trained: false, // Mark as not trained since classifier is None
+            is_trained,
+            n_features: data.n_features,
+            clamp: data.clamp,
+            n_classes: data.n_classes,
+            scaler: data.scaler,
         })
     }
 
+    /// Prüfen, ob die Feature-Anzahl zum trainierten Model passt
+    fn check_feature_count(&self, actual: usize) -> Result<(), Box<dyn Error>> {
+        if self.n_features != 0 && actual != self.n_features {
+            return Err(format!(
+                "Feature count mismatch: model expects {} features, got {}",
+                self.n_features, actual
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     /// Get model accuracy
     pub fn accuracy(&self) -> f64 {
         self.accuracy
@@ -241,6 +364,21 @@ impl RandomForestModel {
     pub fn is_trained(&self) -> bool {
         self.is_trained
     }
+
+    /// Number of features the model was trained on (0 if untrained)
+    pub fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    /// Konfiguriertes (und gefittetes) Clamping
+    pub fn clamping(&self) -> &FeatureClamp {
+        &self.clamp
+    }
+
+    /// Beim Training gefittete Skalierung
+    pub fn scaler(&self) -> &StandardScaler {
+        &self.scaler
+    }
 }
 
 impl Default for RandomForestModel {
@@ -249,12 +387,116 @@ impl Default for RandomForestModel {
     }
 }
 
+impl Classifier for RandomForestModel {
+    fn name(&self) -> &'static str {
+        "random_forest"
+    }
+
+    fn train(
+        &mut self,
+        x_train: Vec<Vec<f64>>,
+        y_train: Vec<usize>,
+    ) -> Result<f64, Box<dyn Error>> {
+        RandomForestModel::train(self, x_train, y_train)
+    }
+
+    fn predict(&self, features: &[f64]) -> Result<(usize, f64), Box<dyn Error>> {
+        RandomForestModel::predict(self, features)
+    }
+
+    fn predict_proba(&self, features: &[f64]) -> Result<f64, Box<dyn Error>> {
+        let probabilities = RandomForestModel::predict_proba(self, features)?;
+        Ok(probabilities.get(1).copied().unwrap_or(0.0))
+    }
+
+    fn predict_many(&self, samples: &[Vec<f64>]) -> Result<Vec<(usize, f64)>, Box<dyn Error>> {
+        RandomForestModel::predict_many(self, samples)
+    }
+
+    fn save(&self, path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+        RandomForestModel::save(self, path)
+    }
+
+    fn load(&mut self, path: &std::path::Path) -> Result<(), Box<dyn Error>> {
+        *self = RandomForestModel::load(path)?;
+        Ok(())
+    }
+
+    /// smartcore stellt keine Feature Importances für Random Forests bereit
+    fn feature_importances(&self) -> Option<Vec<f64>> {
+        None
+    }
+
+    fn is_trained(&self) -> bool {
+        self.is_trained
+    }
+
+    fn accuracy(&self) -> f64 {
+        self.accuracy
+    }
+
+    fn n_features(&self) -> usize {
+        self.n_features
+    }
+
+    fn scaler(&self) -> Option<&StandardScaler> {
+        Some(&self.scaler).filter(|scaler| scaler.is_fitted())
+    }
+}
+
+/// Gruppen identischer Feature-Vektoren mit mehr als einem Label
+fn tie_groups(x: &[Vec<f64>], y: &[usize]) -> Vec<Vec<usize>> {
+    let mut groups: HashMap<Vec<u64>, Vec<usize>> = HashMap::new();
+    for (index, sample) in x.iter().enumerate() {
+        let key = sample.iter().map(|value| value.to_bits()).collect();
+        groups.entry(key).or_default().push(index);
+    }
+
+    let mut ties: Vec<Vec<usize>> = groups
+        .into_values()
+        .filter(|group| group.iter().any(|&index| y[index] != y[group[0]]))
+        .collect();
+    ties.sort();
+    ties
+}
+
+/// Labels für einen Baum: jede Gruppe bekommt das Label eines gezogenen Mitglieds
+fn resolve_ties(y: &[usize], ties: &[Vec<usize>], seed: u64) -> Vec<usize> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut labels = y.to_vec();
+    for group in ties {
+        let label = y[group[rng.gen_range(0..group.len())]];
+        for &index in group {
+            labels[index] = label;
+        }
+    }
+
+    // smartcore braucht mindestens zwei Klassen
+    if labels.iter().any(|&label| label != labels[0]) {
+        labels
+    } else {
+        y.to_vec()
+    }
+}
+
 /// Model data for serialization
+///
+/// Beim Speichern mit `&[RfClassifier]`, beim Laden mit `Vec<RfClassifier>` belegt.
 #[derive(Debug, Serialize, Deserialize)]
-struct ModelData {
+struct ModelData<T> {
+    /// Fehlt in älteren Dateien (`None`)
+    trees: Option<T>,
     config: RandomForestConfig,
     accuracy: f64,
     is_trained: bool,
+    #[serde(default)]
+    n_features: usize,
+    #[serde(default)]
+    clamp: FeatureClamp,
+    #[serde(default = "default_n_classes")]
+    n_classes: usize,
+    #[serde(default)]
+    scaler: StandardScaler,
 }
 
 /// Model evaluation metrics
@@ -266,6 +508,14 @@ pub struct ModelMetrics {
     pub f1_score: f64,
 }
 
+/// Klasse mit den meisten Stimmen (bei Gleichstand die kleinere)
+fn argmax<T: PartialOrd + Copy>(values: &[T]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, &value)| if value > values[best] { i } else { best })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,5 +544,110 @@ mod tests {
         assert!(model.is_trained());
         assert!(accuracy > 0.0);
         assert_eq!(model.accuracy(), accuracy);
+        assert_eq!(model.n_features(), 2);
+    }
+
+    #[test]
+    fn test_save_load_roundtrip_keeps_predictions() {
+        let x: Vec<Vec<f64>> = (0..40)
+            .map(|i| vec![i as f64, (i % 7) as f64, (40 - i) as f64])
+            .collect();
+        let y: Vec<usize> = (0..40).map(|i| usize::from(i >= 20)).collect();
+
+        let mut model = RandomForestModel::with_config(RandomForestConfig {
+            n_trees: 10,
+            ..RandomForestConfig::default()
+        });
+        model.train(x.clone(), y).unwrap();
+
+        let path = std::env::temp_dir().join("honeytrap_test_rf_model.json");
+        model.save(&path).unwrap();
+        let loaded = RandomForestModel::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(loaded.is_trained());
+        assert_eq!(loaded.accuracy(), model.accuracy());
+        assert_eq!(loaded.n_features(), 3);
+        assert_eq!(
+            loaded.predict_batch(x.clone()).unwrap(),
+            model.predict_batch(x.clone()).unwrap()
+        );
+        for sample in &x {
+            assert_eq!(loaded.predict(sample).unwrap(), model.predict(sample).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_probability_reflects_tree_votes() {
+        // Klasse 0 um 0..10, Klasse 1 um 30..40, bei 20 beide Klassen gleich oft
+        let mut x: Vec<Vec<f64>> = Vec::new();
+        let mut y = Vec::new();
+        for i in 0..10 {
+            x.push(vec![i as f64]);
+            y.push(0);
+            x.push(vec![30.0 + i as f64]);
+            y.push(1);
+        }
+        for label in [0, 1].repeat(10) {
+            x.push(vec![20.0]);
+            y.push(label);
+        }
+
+        let mut model = RandomForestModel::with_config(RandomForestConfig {
+            n_trees: 50,
+            ..RandomForestConfig::default()
+        });
+        model.train(x, y).unwrap();
+
+        let (prediction, confident) = model.predict(&[35.0]).unwrap();
+        assert_eq!(prediction, 1);
+        assert!(confident > 0.95, "in-class probability {}", confident);
+
+        let ambiguous = model.predict_proba(&[20.0]).unwrap();
+        assert_eq!(ambiguous.len(), 2);
+        assert!((ambiguous.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(
+            (0.2..=0.8).contains(&ambiguous[1]),
+            "ambiguous probability {:?}",
+            ambiguous
+        );
+        assert_eq!(
+            Classifier::predict_proba(&model, &[5.0]).unwrap(),
+            model.predict_proba(&[5.0]).unwrap()[1]
+        );
+    }
+
+    #[test]
+    fn test_predict_rejects_feature_count_mismatch() {
+        let mut model = RandomForestModel::new();
+        model
+            .train(vec![vec![1.0, 2.0], vec![3.0, 4.0]], vec![0, 1])
+            .unwrap();
+
+        let err = model.predict(&[1.0, 2.0, 3.0]).unwrap_err();
+        assert!(err.to_string().contains("expects 2 features, got 3"));
+    }
+
+    #[test]
+    fn test_extreme_feature_clamped_before_prediction() {
+        use crate::clamping::FeatureClamp;
+
+        // Feature 0 = bytes_sent (riesig), Feature 1 = failed_logins
+        let x: Vec<Vec<f64>> = (0..40)
+            .map(|i| vec![(i * 1_000) as f64, (i % 10) as f64])
+            .collect();
+        let y: Vec<usize> = (0..40).map(|i| usize::from(i >= 20)).collect();
+
+        let mut model = RandomForestModel::new()
+            .with_clamping(FeatureClamp::new().with_percentile(0, 0.0, 90.0));
+        model.train(x, y).unwrap();
+
+        let (_, upper) = model.clamping().bounds(0).unwrap();
+        assert!((upper - 35_100.0).abs() < 1e-6);
+        assert_eq!(model.clamping().clamp(&[5e12, 3.0]), vec![upper, 3.0]);
+        assert_eq!(
+            model.predict(&[5e12, 3.0]).unwrap(),
+            model.predict(&[upper, 3.0]).unwrap()
+        );
     }
 }
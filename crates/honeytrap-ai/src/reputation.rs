@@ -0,0 +1,152 @@
+//! Source Reputation mit exponentiellem Zerfall
+//!
+//! Scores liegen zwischen 0 (bösartig) und 10 (vertrauenswürdig) und
+//! zerfallen ohne neue Ereignisse mit konfigurierbarer Halbwertszeit
+//! Richtung neutral - IPs werden neu vergeben, alte Vorfälle verblassen.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+/// Schlechtester Score
+pub const MIN_REPUTATION: f64 = 0.0;
+/// Bester Score
+pub const MAX_REPUTATION: f64 = 10.0;
+/// Neutraler Score für unbekannte Quellen
+pub const NEUTRAL_REPUTATION: f64 = 5.0;
+
+/// Standard-Halbwertszeit (24h)
+const DEFAULT_HALF_LIFE: Duration = Duration::from_secs(24 * 3600);
+
+/// Reputation einer einzelnen Quelle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reputation {
+    score: f64,
+    updated_at: Instant,
+}
+
+impl Reputation {
+    fn new(now: Instant) -> Self {
+        Self {
+            score: NEUTRAL_REPUTATION,
+            updated_at: now,
+        }
+    }
+
+    /// Aktueller Score (0-10)
+    pub fn score(&self) -> f64 {
+        self.score
+    }
+
+    /// Score Richtung neutral zerfallen lassen
+    pub fn decay(&mut self, now: Instant, half_life: Duration) {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+        if half_life.is_zero() {
+            self.score = NEUTRAL_REPUTATION;
+        } else {
+            let factor = 0.5_f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64());
+            self.score = NEUTRAL_REPUTATION + (self.score - NEUTRAL_REPUTATION) * factor;
+        }
+        self.updated_at = self.updated_at.max(now);
+    }
+}
+
+/// Reputation Tracker pro Quell-IP
+#[derive(Debug, Clone)]
+pub struct ReputationTracker {
+    sources: HashMap<IpAddr, Reputation>,
+    half_life: Duration,
+}
+
+impl Default for ReputationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReputationTracker {
+    pub fn new() -> Self {
+        Self {
+            sources: HashMap::new(),
+            half_life: DEFAULT_HALF_LIFE,
+        }
+    }
+
+    /// Mit Halbwertszeit
+    pub fn with_half_life(mut self, half_life: Duration) -> Self {
+        self.half_life = half_life;
+        self
+    }
+
+    /// Ereignis verbuchen (negativ = schlechtes Verhalten)
+    pub fn record(&mut self, ip: IpAddr, delta: f64, now: Instant) -> f64 {
+        let half_life = self.half_life;
+        let reputation = self.sources.entry(ip).or_insert_with(|| Reputation::new(now));
+        reputation.decay(now, half_life);
+        reputation.score = (reputation.score + delta).clamp(MIN_REPUTATION, MAX_REPUTATION);
+        reputation.score
+    }
+
+    /// Score abfragen (Zerfall wird beim Lookup angewendet)
+    pub fn score(&mut self, ip: IpAddr, now: Instant) -> f64 {
+        let half_life = self.half_life;
+        match self.sources.get_mut(&ip) {
+            Some(reputation) => {
+                reputation.decay(now, half_life);
+                reputation.score
+            }
+            None => NEUTRAL_REPUTATION,
+        }
+    }
+
+    /// Score als Bucket (0-10) für den RL State
+    pub fn bucket(&mut self, ip: IpAddr, now: Instant) -> u8 {
+        self.score(ip, now).round() as u8
+    }
+
+    /// Quellen entfernen, die wieder (nahezu) neutral sind
+    pub fn prune(&mut self, now: Instant, epsilon: f64) -> usize {
+        let half_life = self.half_life;
+        let before = self.sources.len();
+        self.sources.retain(|_, reputation| {
+            reputation.decay(now, half_life);
+            (reputation.score - NEUTRAL_REPUTATION).abs() > epsilon
+        });
+        before - self.sources.len()
+    }
+
+    /// Anzahl bekannter Quellen
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bad_score_decays_toward_neutral() {
+        let mut tracker = ReputationTracker::new().with_half_life(Duration::from_secs(3600));
+        let ip: IpAddr = "203.0.113.9".parse().unwrap();
+        let start = Instant::now();
+
+        assert_eq!(tracker.record(ip, -5.0, start), MIN_REPUTATION);
+        assert_eq!(tracker.bucket(ip, start), 0);
+
+        // Nach einer Halbwertszeit: halber Abstand zu neutral
+        let score = tracker.score(ip, start + Duration::from_secs(3600));
+        assert!((score - 2.5).abs() < 1e-9);
+
+        // Nach vielen Halbwertszeiten: praktisch neutral
+        let later = start + Duration::from_secs(3600 * 20);
+        assert!((tracker.score(ip, later) - NEUTRAL_REPUTATION).abs() < 1e-3);
+        assert_eq!(tracker.bucket(ip, later), 5);
+        assert_eq!(tracker.prune(later, 0.01), 1);
+        assert!(tracker.is_empty());
+    }
+}
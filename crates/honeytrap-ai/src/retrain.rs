@@ -0,0 +1,230 @@
+//! Puffer für inkrementelles Retraining
+//!
+//! Gelabelte Samples aus dem Live-Betrieb werden gesammelt, statt das Model
+//! stur per Timer neu zu trainieren. Retraining lohnt sich erst, wenn genug
+//! neue Samples vorliegen oder deren Verteilung deutlich von den letzten
+//! Trainingsdaten abweicht (standardisierte Mittelwert-Verschiebung pro
+//! Feature). Ein Reservoir früherer Trainingsdaten verhindert, dass ein
+//! Partial Update das bisher Gelernte vergisst.
+
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Schwellwerte für `RetrainBuffer::should_retrain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetrainConfig {
+    /// Max. gepufferte neue Samples (älteste fallen heraus)
+    pub capacity: usize,
+    /// Ab so vielen neuen Samples wird immer neu trainiert
+    pub max_pending: usize,
+    /// Mindestanzahl neuer Samples für eine belastbare Drift-Schätzung
+    pub min_drift_samples: usize,
+    /// Mittelwert-Verschiebung in Standardabweichungen, ab der retrainiert wird
+    pub drift_threshold: f64,
+}
+
+impl Default for RetrainConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 10_000,
+            max_pending: 1_000,
+            min_drift_samples: 50,
+            drift_threshold: 0.5,
+        }
+    }
+}
+
+/// Mittelwert + Standardabweichung pro Feature
+#[derive(Debug, Clone, Default)]
+struct Baseline {
+    means: Vec<f64>,
+    stds: Vec<f64>,
+}
+
+impl Baseline {
+    fn from_samples<'a>(samples: impl Iterator<Item = &'a [f64]> + Clone) -> Option<Self> {
+        let n = samples.clone().count();
+        let n_features = samples.clone().next()?.len();
+
+        let mut means = vec![0.0; n_features];
+        for sample in samples.clone() {
+            for (mean, value) in means.iter_mut().zip(sample) {
+                *mean += value / n as f64;
+            }
+        }
+
+        let mut stds = vec![0.0; n_features];
+        for sample in samples {
+            for ((std, mean), value) in stds.iter_mut().zip(&means).zip(sample) {
+                *std += (value - mean).powi(2) / n as f64;
+            }
+        }
+        stds.iter_mut().for_each(|std| *std = std.sqrt());
+
+        Some(Self { means, stds })
+    }
+}
+
+/// Begrenzter Puffer gelabelter Samples seit dem letzten Training
+#[derive(Debug, Clone, Default)]
+pub struct RetrainBuffer {
+    config: RetrainConfig,
+    pending: VecDeque<(Vec<f64>, bool)>,
+    /// Stichprobe früherer Trainingsdaten (max. `capacity`)
+    history: Vec<(Vec<f64>, bool)>,
+    baseline: Option<Baseline>,
+}
+
+impl RetrainBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: RetrainConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    /// Gelabeltes Sample puffern
+    pub fn push(&mut self, features: Vec<f64>, is_anomaly: bool) {
+        if self.pending.len() >= self.config.capacity.max(1) {
+            self.pending.pop_front();
+        }
+        self.pending.push_back((features, is_anomaly));
+    }
+
+    /// Anzahl neuer Samples seit dem letzten Training
+    pub fn pending_samples(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Trainingsdaten als neue Referenz übernehmen (verwirft den Puffer)
+    pub fn set_baseline(&mut self, training_data: &[(Vec<f64>, bool)]) {
+        self.baseline = Baseline::from_samples(training_data.iter().map(|(x, _)| x.as_slice()));
+
+        let mut rng = rand::thread_rng();
+        self.history = training_data
+            .choose_multiple(&mut rng, self.config.capacity)
+            .cloned()
+            .collect();
+        self.pending.clear();
+    }
+
+    /// Größte Mittelwert-Verschiebung neuer Samples (in Standardabweichungen)
+    ///
+    /// 0.0 ohne Baseline oder ohne neue Samples. Features ohne Streuung in
+    /// der Baseline zählen ab jeder Abweichung voll.
+    pub fn drift_score(&self) -> f64 {
+        let Some(baseline) = &self.baseline else {
+            return 0.0;
+        };
+        let Some(recent) = Baseline::from_samples(self.pending.iter().map(|(x, _)| x.as_slice()))
+        else {
+            return 0.0;
+        };
+
+        baseline
+            .means
+            .iter()
+            .zip(&baseline.stds)
+            .zip(&recent.means)
+            .map(|((reference, std), mean)| {
+                let shift = (mean - reference).abs();
+                if *std > f64::EPSILON {
+                    shift / std
+                } else if shift > f64::EPSILON {
+                    f64::INFINITY
+                } else {
+                    0.0
+                }
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /// Lohnt sich ein Retraining?
+    ///
+    /// Ja bei `max_pending` neuen Samples oder, sobald `min_drift_samples`
+    /// vorliegen, bei einer Drift über `drift_threshold`.
+    pub fn should_retrain(&self) -> bool {
+        let pending = self.pending.len();
+        if pending >= self.config.max_pending {
+            return true;
+        }
+        pending >= self.config.min_drift_samples
+            && (self.baseline.is_none() || self.drift_score() >= self.config.drift_threshold)
+    }
+
+    /// Trainingsdaten für ein Partial Update: Reservoir + neue Samples
+    pub fn training_set(&self) -> Vec<(Vec<f64>, bool)> {
+        self.history
+            .iter()
+            .chain(&self.pending)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RetrainConfig {
+        RetrainConfig {
+            capacity: 100,
+            max_pending: 80,
+            min_drift_samples: 20,
+            drift_threshold: 0.5,
+        }
+    }
+
+    fn normal(n: usize, offset: f64) -> Vec<(Vec<f64>, bool)> {
+        (0..n)
+            .map(|i| (vec![offset + (i % 10) as f64, 5.0 + (i % 3) as f64], false))
+            .collect()
+    }
+
+    #[test]
+    fn test_buffer_fills_and_stays_bounded() {
+        let mut buffer = RetrainBuffer::with_config(config());
+        buffer.set_baseline(&normal(200, 0.0));
+        assert_eq!(buffer.pending_samples(), 0);
+
+        for (features, label) in normal(150, 0.0) {
+            buffer.push(features, label);
+        }
+        assert_eq!(buffer.pending_samples(), 100);
+        // Reservoir (max. capacity) + neue Samples
+        assert_eq!(buffer.training_set().len(), 200);
+
+        buffer.set_baseline(&buffer.training_set());
+        assert_eq!(buffer.pending_samples(), 0);
+    }
+
+    #[test]
+    fn test_should_retrain_flips_on_drift_or_sample_count() {
+        let mut buffer = RetrainBuffer::with_config(config());
+        buffer.set_baseline(&normal(200, 0.0));
+
+        // Gleiche Verteilung: kein Retraining bis max_pending
+        for (features, label) in normal(79, 0.0) {
+            buffer.push(features, label);
+        }
+        assert!(buffer.drift_score() < 0.5);
+        assert!(!buffer.should_retrain());
+        buffer.push(vec![4.0, 6.0], false);
+        assert!(buffer.should_retrain());
+
+        // Verschobene Verteilung: schon ab min_drift_samples
+        buffer.set_baseline(&normal(200, 0.0));
+        for (features, label) in normal(19, 10.0) {
+            buffer.push(features, label);
+        }
+        assert!(!buffer.should_retrain());
+        buffer.push(vec![14.0, 6.0], true);
+        assert!(buffer.drift_score() > 0.5);
+        assert!(buffer.should_retrain());
+    }
+}
@@ -2,11 +2,15 @@
 //!
 //! Implementiert einen Q-Learning Agenten, der optimale Antwortstrategien lernt
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::error::Error;
 use std::fs;
 
+/// Standard-Kapazität des Replay Buffers
+pub const DEFAULT_REPLAY_CAPACITY: usize = 10_000;
+
 /// State representation für RL Agent
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct State {
@@ -16,25 +20,29 @@ pub struct State {
     pub connection_intensity: u8,
     /// Source reputation score (0-10)
     pub source_reputation: u8,
+    /// Angreifer-Verhalten (`BehaviorClass::as_u8`: 0 unbekannt, 1 Bot, 2 Mensch)
+    #[serde(default)]
+    pub behavior: u8,
 }
 
 impl State {
     /// Convert state to string key for serialization
     fn to_key(&self) -> String {
         format!(
-            "{}-{}-{}",
-            self.attack_type, self.connection_intensity, self.source_reputation
+            "{}-{}-{}-{}",
+            self.attack_type, self.connection_intensity, self.source_reputation, self.behavior
         )
     }
 
-    /// Parse state from string key
+    /// Parse state from string key (ältere Keys ohne Verhalten = unbekannt)
     fn from_key(key: &str) -> Option<Self> {
         let parts: Vec<&str> = key.split('-').collect();
-        if parts.len() == 3 {
+        if parts.len() == 3 || parts.len() == 4 {
             Some(State {
                 attack_type: parts[0].parse().ok()?,
                 connection_intensity: parts[1].parse().ok()?,
                 source_reputation: parts[2].parse().ok()?,
+                behavior: parts.get(3).map_or(Ok(0), |b| b.parse()).ok()?,
             })
         } else {
             None
@@ -82,6 +90,13 @@ pub struct RLConfig {
     pub epsilon_decay: f64,
     /// Minimum epsilon
     pub epsilon_min: f64,
+    /// Max. gespeicherte Transitions für Experience Replay
+    #[serde(default = "default_replay_capacity")]
+    pub replay_capacity: usize,
+}
+
+fn default_replay_capacity() -> usize {
+    DEFAULT_REPLAY_CAPACITY
 }
 
 impl Default for RLConfig {
@@ -92,10 +107,70 @@ impl Default for RLConfig {
             epsilon: 1.0,
             epsilon_decay: 0.995,
             epsilon_min: 0.01,
+            replay_capacity: DEFAULT_REPLAY_CAPACITY,
         }
     }
 }
 
+/// Einzelner Schritt (s, a, r, s') für Experience Replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transition {
+    pub state: State,
+    pub action: Action,
+    pub reward: f64,
+    pub next_state: State,
+}
+
+/// Ring-Buffer der letzten Transitions (älteste fallen bei voller Kapazität heraus)
+#[derive(Debug, Clone)]
+pub struct ReplayBuffer {
+    transitions: VecDeque<Transition>,
+    capacity: usize,
+}
+
+impl ReplayBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            transitions: VecDeque::with_capacity(capacity.min(DEFAULT_REPLAY_CAPACITY)),
+            capacity,
+        }
+    }
+
+    /// Transition speichern (0 Kapazität = Replay aus)
+    pub fn push(&mut self, transition: Transition) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.transitions.len() >= self.capacity {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition);
+    }
+
+    /// Zufällige Minibatch (mit Zurücklegen) der Größe `batch_size`
+    pub fn sample(&self, batch_size: usize) -> Vec<Transition> {
+        if self.transitions.is_empty() {
+            return Vec::new();
+        }
+        let mut rng = rand::thread_rng();
+        (0..batch_size)
+            .map(|_| self.transitions[rng.gen_range(0..self.transitions.len())].clone())
+            .collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.transitions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+}
+
 /// Q-Learning Agent für adaptive Honeypot-Strategien
 #[derive(Debug)]
 pub struct RLAgent {
@@ -107,6 +182,8 @@ pub struct RLAgent {
     episodes_trained: usize,
     /// Current epsilon (exploration rate)
     current_epsilon: f64,
+    /// Gespeicherte Transitions für Experience Replay (nicht persistiert)
+    replay: ReplayBuffer,
 }
 
 /// Serializable version of RLAgent
@@ -126,17 +203,20 @@ impl RLAgent {
             config: RLConfig::default(),
             episodes_trained: 0,
             current_epsilon: 1.0,
+            replay: ReplayBuffer::new(DEFAULT_REPLAY_CAPACITY),
         }
     }
 
     /// Create agent with custom config
     pub fn with_config(config: RLConfig) -> Self {
         let epsilon = config.epsilon;
+        let replay = ReplayBuffer::new(config.replay_capacity);
         Self {
             q_table: HashMap::new(),
             config,
             episodes_trained: 0,
             current_epsilon: epsilon,
+            replay,
         }
     }
 
@@ -151,6 +231,8 @@ impl RLAgent {
 
     /// Update Q-value using Q-learning formula
     /// Q(s,a) = Q(s,a) + α[r + γ max Q(s',a') - Q(s,a)]
+    ///
+    /// Die Transition landet zusätzlich im Replay Buffer.
     pub fn update(
         &mut self,
         state: &State,
@@ -158,6 +240,37 @@ impl RLAgent {
         reward: f64,
         next_state: &State,
     ) {
+        self.apply_update(state, action, reward, next_state);
+        self.replay.push(Transition {
+            state: state.clone(),
+            action: *action,
+            reward,
+            next_state: next_state.clone(),
+        });
+    }
+
+    /// Q-Update aus zufälligen Minibatches gespeicherter Transitions
+    ///
+    /// Liefert die Anzahl angewendeter Updates (0 bei leerem Buffer).
+    pub fn train_from_replay(&mut self, batch_size: usize) -> usize {
+        let batch = self.replay.sample(batch_size);
+        for transition in &batch {
+            self.apply_update(
+                &transition.state,
+                &transition.action,
+                transition.reward,
+                &transition.next_state,
+            );
+        }
+        batch.len()
+    }
+
+    /// Replay Buffer (z.B. für Monitoring)
+    pub fn replay_buffer(&self) -> &ReplayBuffer {
+        &self.replay
+    }
+
+    fn apply_update(&mut self, state: &State, action: &Action, reward: f64, next_state: &State) {
         let current_q = self.get_q_value(state, action);
         let max_next_q = self.get_max_q_value(next_state);
 
@@ -282,6 +395,7 @@ impl RLAgent {
 
         Ok(RLAgent {
             q_table,
+            replay: ReplayBuffer::new(agent_serde.config.replay_capacity),
             config: agent_serde.config,
             episodes_trained: agent_serde.episodes_trained,
             current_epsilon: agent_serde.current_epsilon,
@@ -311,29 +425,94 @@ pub struct RLStats {
     pub avg_q_value: f64,
 }
 
+/// Reward-Gewichtung (Base Rewards pro Action + Outcome-Gewichte)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RewardConfig {
+    /// Missed opportunity
+    pub ignore: f64,
+    /// Low cost, some info
+    pub minimal_response: f64,
+    /// Good balance
+    pub standard_engagement: f64,
+    /// High info gain
+    pub deep_engagement: f64,
+    /// Safe but no intel
+    pub block: f64,
+    /// Gewicht pro Einheit gewonnener Information
+    pub info_weight: f64,
+    /// Gewicht pro Einheit verschwendeter Angreifer-Zeit
+    pub time_weight: f64,
+    /// Kosten pro Einheit verbrauchter Ressourcen
+    pub resource_weight: f64,
+}
+
+impl Default for RewardConfig {
+    fn default() -> Self {
+        Self {
+            ignore: -1.0,
+            minimal_response: 2.0,
+            standard_engagement: 5.0,
+            deep_engagement: 8.0,
+            block: 1.0,
+            info_weight: 2.0,
+            time_weight: 1.5,
+            resource_weight: 0.5,
+        }
+    }
+}
+
+impl RewardConfig {
+    /// Base Reward einer Action
+    pub fn base_reward(&self, action: &Action) -> f64 {
+        match action {
+            Action::Ignore => self.ignore,
+            Action::MinimalResponse => self.minimal_response,
+            Action::StandardEngagement => self.standard_engagement,
+            Action::DeepEngagement => self.deep_engagement,
+            Action::Block => self.block,
+        }
+    }
+}
+
 /// Reward calculator für Training
-pub struct RewardCalculator;
+#[derive(Debug, Clone, Default)]
+pub struct RewardCalculator {
+    config: RewardConfig,
+}
 
 impl RewardCalculator {
+    /// Calculator mit Default-Gewichtung
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calculator mit eigener Gewichtung (z.B. aus der Config)
+    pub fn with_config(config: RewardConfig) -> Self {
+        Self { config }
+    }
+
+    /// Aktive Gewichtung
+    pub fn config(&self) -> &RewardConfig {
+        &self.config
+    }
+
     /// Calculate reward based on outcome
     /// Positive rewards: Information gained, attacker time wasted
     /// Negative rewards: Resources consumed, false positives
     pub fn calculate(
+        &self,
         action: &Action,
         info_gained: f64,
         time_wasted: f64,
         resources_used: f64,
     ) -> f64 {
-        let base_reward = match action {
-            Action::Ignore => -1.0,              // Missed opportunity
-            Action::MinimalResponse => 2.0,      // Low cost, some info
-            Action::StandardEngagement => 5.0,   // Good balance
-            Action::DeepEngagement => 8.0,       // High info gain
-            Action::Block => 1.0,                // Safe but no intel
-        };
+        let config = &self.config;
 
         // Adjust based on actual outcome
-        base_reward + info_gained * 2.0 + time_wasted * 1.5 - resources_used * 0.5
+        config.base_reward(action) + info_gained * config.info_weight
+            + time_wasted * config.time_weight
+            - resources_used * config.resource_weight
     }
 }
 
@@ -355,11 +534,13 @@ mod tests {
             attack_type: 5,
             connection_intensity: 3,
             source_reputation: 2,
+            behavior: 0,
         };
         let next_state = State {
             attack_type: 5,
             connection_intensity: 4,
             source_reputation: 2,
+            behavior: 0,
         };
 
         agent.update(&state, &Action::StandardEngagement, 10.0, &next_state);
@@ -374,6 +555,7 @@ mod tests {
             attack_type: 3,
             connection_intensity: 2,
             source_reputation: 5,
+            behavior: 0,
         };
 
         // Train with high reward for deep engagement
@@ -387,6 +569,36 @@ mod tests {
         assert_eq!(action, Action::DeepEngagement);
     }
 
+    #[test]
+    fn test_replaying_high_reward_transition_raises_q_value() {
+        let mut agent = RLAgent::with_config(RLConfig {
+            replay_capacity: 2,
+            ..RLConfig::default()
+        });
+        let state = State {
+            attack_type: 9,
+            connection_intensity: 1,
+            source_reputation: 1,
+            behavior: 0,
+        };
+
+        agent.update(&state, &Action::DeepEngagement, 10.0, &state);
+        let live = agent.get_q_value(&state, &Action::DeepEngagement);
+        assert_eq!(agent.train_from_replay(16), 16);
+        assert!(agent.get_q_value(&state, &Action::DeepEngagement) > live);
+
+        // Ring-Buffer: älteste Transition fällt heraus, Replay schreibt nichts nach
+        agent.update(&state, &Action::Block, 1.0, &state);
+        agent.update(&state, &Action::Ignore, -1.0, &state);
+        assert_eq!(agent.replay_buffer().len(), 2);
+        assert!(agent
+            .replay_buffer()
+            .sample(32)
+            .iter()
+            .all(|t| t.action != Action::DeepEngagement));
+        assert_eq!(RLAgent::new().train_from_replay(16), 0);
+    }
+
     #[test]
     fn test_epsilon_decay() {
         let mut agent = RLAgent::new();
@@ -398,7 +610,7 @@ mod tests {
 
     #[test]
     fn test_reward_calculation() {
-        let reward = RewardCalculator::calculate(
+        let reward = RewardCalculator::new().calculate(
             &Action::DeepEngagement,
             5.0,  // info_gained
             3.0,  // time_wasted
@@ -408,6 +620,27 @@ mod tests {
         assert!((reward - 21.5).abs() < 0.01);
     }
 
+    #[test]
+    fn test_configured_info_weight_raises_info_rich_reward() {
+        // Nicht gesetzte Felder behalten ihre Defaults
+        let config: RewardConfig =
+            serde_json::from_str(r#"{"info_weight": 4.0, "block": 0.0}"#).unwrap();
+        assert_eq!(config.deep_engagement, 8.0);
+
+        let default = RewardCalculator::new();
+        let intel_focused = RewardCalculator::with_config(config);
+
+        // Info-reiche Action: 8 + 5*4 + 0 - 1 = 27 statt 17
+        let info_rich = |calculator: &RewardCalculator| {
+            calculator.calculate(&Action::DeepEngagement, 5.0, 0.0, 2.0)
+        };
+        assert!((info_rich(&default) - 17.0).abs() < 0.01);
+        assert!((info_rich(&intel_focused) - 27.0).abs() < 0.01);
+
+        // Ohne Info-Gewinn ändert sich nur die überschriebene Base
+        assert_eq!(intel_focused.calculate(&Action::Block, 0.0, 1.0, 0.0), 1.5);
+    }
+
     #[test]
     fn test_save_load() {
         let mut agent = RLAgent::new();
@@ -415,6 +648,7 @@ mod tests {
             attack_type: 1,
             connection_intensity: 2,
             source_reputation: 3,
+            behavior: 2,
         };
         agent.update(&state, &Action::StandardEngagement, 5.0, &state);
 
@@ -424,6 +658,12 @@ mod tests {
         let loaded = RLAgent::load(path).unwrap();
         assert_eq!(loaded.episodes_trained, agent.episodes_trained);
         assert_eq!(loaded.q_table.len(), agent.q_table.len());
+        assert!(loaded.q_table.contains_key(&state));
+
+        // Keys aus Versionen ohne Verhalten laden als "unbekannt"
+        let legacy = State::from_key("1-2-3").unwrap();
+        assert_eq!(legacy.behavior, 0);
+        assert_eq!(legacy.source_reputation, 3);
 
         std::fs::remove_file(path).ok();
     }
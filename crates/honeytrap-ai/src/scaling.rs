@@ -0,0 +1,97 @@
+//! Feature-Skalierung
+//!
+//! `source_port` (0-65535) und `failed_login_attempts` (0-50) liegen auf
+//! völlig verschiedenen Skalen. Der `StandardScaler` bringt jedes Feature auf
+//! Mittelwert 0 und Standardabweichung 1. Er wird beim Training gefittet und
+//! mit dem Model gespeichert, damit geladene Models identisch skalieren.
+
+use serde::{Deserialize, Serialize};
+
+/// Mittelwert/Standardabweichung pro Feature
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StandardScaler {
+    means: Vec<f64>,
+    stds: Vec<f64>,
+}
+
+impl StandardScaler {
+    /// Aus Trainingsdaten fitten (nicht-endliche Werte werden ignoriert)
+    pub fn fit(samples: &[Vec<f64>]) -> Self {
+        let n_features = samples.first().map_or(0, |sample| sample.len());
+        let mut means = Vec::with_capacity(n_features);
+        let mut stds = Vec::with_capacity(n_features);
+
+        for index in 0..n_features {
+            let column: Vec<f64> = samples
+                .iter()
+                .filter_map(|sample| sample.get(index).copied())
+                .filter(|value| value.is_finite())
+                .collect();
+            if column.is_empty() {
+                means.push(0.0);
+                stds.push(1.0);
+                continue;
+            }
+
+            let mean = column.iter().sum::<f64>() / column.len() as f64;
+            let squared: f64 = column.iter().map(|value| (value - mean).powi(2)).sum();
+            let variance = squared / column.len() as f64;
+            means.push(mean);
+            // Konstante Features nur zentrieren
+            stds.push(if variance > 0.0 { variance.sqrt() } else { 1.0 });
+        }
+
+        Self { means, stds }
+    }
+
+    /// Schon gefittet?
+    pub fn is_fitted(&self) -> bool {
+        !self.means.is_empty()
+    }
+
+    pub fn means(&self) -> &[f64] {
+        &self.means
+    }
+
+    pub fn stds(&self) -> &[f64] {
+        &self.stds
+    }
+
+    /// Sample in-place skalieren (ungefittet: unverändert)
+    pub fn apply(&self, features: &mut [f64]) {
+        for ((value, mean), std) in features.iter_mut().zip(&self.means).zip(&self.stds) {
+            *value = (*value - mean) / std;
+        }
+    }
+
+    /// Skalierte Kopie eines Samples
+    pub fn transform(&self, features: &[f64]) -> Vec<f64> {
+        let mut scaled = features.to_vec();
+        self.apply(&mut scaled);
+        scaled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_transform() {
+        let samples = vec![
+            vec![1_000.0, 1.0, 5.0],
+            vec![3_000.0, 3.0, 5.0],
+            vec![f64::NAN, 2.0, 5.0],
+        ];
+        let scaler = StandardScaler::fit(&samples);
+
+        assert_eq!(scaler.means(), [2_000.0, 2.0, 5.0]);
+        assert_eq!(scaler.stds()[0], 1_000.0);
+        // Konstantes Feature wird nur zentriert
+        assert_eq!(scaler.stds()[2], 1.0);
+        assert_eq!(scaler.transform(&[4_000.0, 2.0, 7.0]), vec![2.0, 0.0, 2.0]);
+
+        assert!(!StandardScaler::default().is_fitted());
+        assert_eq!(StandardScaler::default().transform(&[42.0]), vec![42.0]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Synthetischer Traffic-Generator
+//!
+//! Erzeugt gelabelte `NetworkFeatures` mit festem Seed - gleiche Seeds
+//! liefern identische Datasets für reproduzierbare Tests und Benchmarks.
+
+use crate::features::NetworkFeatures;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+
+/// Traffic-Klasse eines Samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrafficClass {
+    Benign,
+    PortScan,
+    BruteForce,
+    Ddos,
+}
+
+impl TrafficClass {
+    /// Label für das Training (true = Anomalie)
+    pub fn is_attack(&self) -> bool {
+        !matches!(self, TrafficClass::Benign)
+    }
+}
+
+impl fmt::Display for TrafficClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrafficClass::Benign => write!(f, "benign"),
+            TrafficClass::PortScan => write!(f, "port_scan"),
+            TrafficClass::BruteForce => write!(f, "brute_force"),
+            TrafficClass::Ddos => write!(f, "ddos"),
+        }
+    }
+}
+
+/// Anzahl Samples pro Traffic-Klasse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficMix {
+    pub benign: usize,
+    pub port_scan: usize,
+    pub brute_force: usize,
+    pub ddos: usize,
+}
+
+impl Default for TrafficMix {
+    /// Entspricht dem bisherigen Dataset aus dem `train_model` Example
+    fn default() -> Self {
+        Self {
+            benign: 120,
+            port_scan: 30,
+            brute_force: 30,
+            ddos: 30,
+        }
+    }
+}
+
+impl TrafficMix {
+    /// Gesamtzahl Samples
+    pub fn total(&self) -> usize {
+        self.benign + self.port_scan + self.brute_force + self.ddos
+    }
+
+    /// Anzahl Samples einer Klasse
+    pub fn count(&self, class: TrafficClass) -> usize {
+        match class {
+            TrafficClass::Benign => self.benign,
+            TrafficClass::PortScan => self.port_scan,
+            TrafficClass::BruteForce => self.brute_force,
+            TrafficClass::Ddos => self.ddos,
+        }
+    }
+}
+
+/// Gelabeltes Sample
+#[derive(Debug, Clone)]
+pub struct LabeledSample {
+    pub class: TrafficClass,
+    pub features: NetworkFeatures,
+}
+
+/// Seedbarer Generator für gelabelte Trainingsdaten
+#[derive(Debug, Clone)]
+pub struct SyntheticTrafficGenerator {
+    rng: StdRng,
+    mix: TrafficMix,
+}
+
+impl SyntheticTrafficGenerator {
+    /// Neuer Generator mit festem Seed
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            mix: TrafficMix::default(),
+        }
+    }
+
+    /// Mit eigenem Attack-Mix
+    pub fn with_mix(mut self, mix: TrafficMix) -> Self {
+        self.mix = mix;
+        self
+    }
+
+    /// Dataset gemäß Mix generieren (gemischte Reihenfolge)
+    pub fn generate(&mut self) -> Vec<LabeledSample> {
+        let classes = [
+            TrafficClass::Benign,
+            TrafficClass::PortScan,
+            TrafficClass::BruteForce,
+            TrafficClass::Ddos,
+        ];
+
+        let mut samples = Vec::with_capacity(self.mix.total());
+        for class in classes {
+            for _ in 0..self.mix.count(class) {
+                let features = self.sample(class);
+                samples.push(LabeledSample { class, features });
+            }
+        }
+        samples.shuffle(&mut self.rng);
+        samples
+    }
+
+    /// Dataset im Format von `AnomalyDetector::train`
+    pub fn training_data(&mut self) -> Vec<(Vec<f64>, bool)> {
+        self.generate()
+            .into_iter()
+            .map(|sample| (sample.features.as_vector(), sample.class.is_attack()))
+            .collect()
+    }
+
+    /// Einzelnes Sample einer Klasse
+    pub fn sample(&mut self, class: TrafficClass) -> NetworkFeatures {
+        let rng = &mut self.rng;
+        match class {
+            TrafficClass::Benign => NetworkFeatures {
+                source_port: rng.gen_range(1024..5000),
+                destination_port: 8443,
+                connection_duration: rng.gen_range(10.0..300.0),
+                inter_packet_time: rng.gen_range(0.05..0.5),
+                bytes_sent: rng.gen_range(1_000.0..100_000.0),
+                bytes_received: rng.gen_range(2_000.0..100_000.0),
+                packets_sent: rng.gen_range(10.0..200.0),
+                packets_received: rng.gen_range(15.0..200.0),
+                failed_login_attempts: rng.gen_range(0..2) as f64,
+                command_frequency: rng.gen_range(0.5..15.0),
+            },
+            TrafficClass::PortScan => NetworkFeatures {
+                source_port: rng.gen_range(50000..60000),
+                destination_port: rng.gen_range(1..1024),
+                connection_duration: rng.gen_range(0.01..0.5),
+                inter_packet_time: rng.gen_range(0.0001..0.005),
+                bytes_sent: rng.gen_range(40.0..200.0),
+                bytes_received: rng.gen_range(0.0..100.0),
+                packets_sent: rng.gen_range(1.0..10.0),
+                packets_received: rng.gen_range(0.0..3.0),
+                failed_login_attempts: 0.0,
+                command_frequency: rng.gen_range(50.0..200.0),
+            },
+            TrafficClass::BruteForce => NetworkFeatures {
+                source_port: rng.gen_range(40000..50000),
+                destination_port: 22,
+                connection_duration: rng.gen_range(5.0..15.0),
+                inter_packet_time: rng.gen_range(0.05..0.2),
+                bytes_sent: rng.gen_range(3_000.0..8_000.0),
+                bytes_received: rng.gen_range(500.0..2_000.0),
+                packets_sent: rng.gen_range(30.0..80.0),
+                packets_received: rng.gen_range(5.0..20.0),
+                failed_login_attempts: rng.gen_range(20..80) as f64,
+                command_frequency: rng.gen_range(5.0..20.0),
+            },
+            TrafficClass::Ddos => NetworkFeatures {
+                source_port: rng.gen_range(30000..40000),
+                destination_port: 8443,
+                connection_duration: rng.gen_range(0.1..1.0),
+                inter_packet_time: rng.gen_range(0.00001..0.0005),
+                bytes_sent: rng.gen_range(100_000.0..500_000.0),
+                bytes_received: 0.0,
+                packets_sent: rng.gen_range(1_000.0..5_000.0),
+                packets_received: 0.0,
+                failed_login_attempts: 0.0,
+                command_frequency: rng.gen_range(100.0..1_600.0),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_produces_identical_dataset() {
+        let mix = TrafficMix {
+            benign: 40,
+            port_scan: 10,
+            brute_force: 5,
+            ddos: 15,
+        };
+
+        let first = SyntheticTrafficGenerator::new(42).with_mix(mix).generate();
+        let second = SyntheticTrafficGenerator::new(42).with_mix(mix).generate();
+        let other = SyntheticTrafficGenerator::new(7).with_mix(mix).generate();
+
+        assert_eq!(first.len(), mix.total());
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.class, b.class);
+            assert_eq!(a.features.as_vector(), b.features.as_vector());
+        }
+        assert!(first
+            .iter()
+            .zip(other.iter())
+            .any(|(a, b)| a.features.as_vector() != b.features.as_vector()));
+
+        for class in [
+            TrafficClass::Benign,
+            TrafficClass::PortScan,
+            TrafficClass::BruteForce,
+            TrafficClass::Ddos,
+        ] {
+            let count = first.iter().filter(|s| s.class == class).count();
+            assert_eq!(count, mix.count(class), "{}", class);
+        }
+
+        let data = SyntheticTrafficGenerator::new(42).with_mix(mix).training_data();
+        assert_eq!(data.iter().filter(|(_, attack)| *attack).count(), 30);
+    }
+}
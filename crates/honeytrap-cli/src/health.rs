@@ -0,0 +1,117 @@
+//! Health Probe
+//!
+//! Fragt den `/health` Endpoint einer laufenden Instanz ab (für Docker
+//! `HEALTHCHECK` bzw. Kubernetes exec Probes). Exit-Code 0 = healthy.
+
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Ergebnis eines Health Checks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthStatus {
+    pub healthy: bool,
+    pub status_line: String,
+}
+
+/// `/health` abfragen (inkl. Connect, Request und Response innerhalb des Timeouts)
+pub async fn check(server: &str, probe_timeout: Duration) -> Result<HealthStatus, String> {
+    timeout(probe_timeout, probe(server))
+        .await
+        .map_err(|_| format!("timed out after {:?}", probe_timeout))?
+}
+
+async fn probe(server: &str) -> Result<HealthStatus, String> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .map_err(|e| format!("connect to {} failed: {}", server, e))?;
+
+    let request = format!(
+        "GET /health HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        server
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("reading response failed: {}", e))?;
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default().trim().to_string();
+    let healthy = status_line
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|code| code == "200");
+
+    Ok(HealthStatus {
+        healthy,
+        status_line,
+    })
+}
+
+/// Exit-Code für Orchestrierung
+pub fn exit_code(result: &Result<HealthStatus, String>) -> i32 {
+    match result {
+        Ok(status) if status.healthy => 0,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    async fn mock_endpoint(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 1024];
+            let _ = socket.read(&mut buffer).await;
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_healthy_endpoint_exits_zero() {
+        let addr = mock_endpoint(
+            "HTTP/1.1 200 OK\r\nContent-Length: 15\r\n\r\n{\"status\":\"ok\"}",
+        )
+        .await;
+
+        let result = check(&addr, Duration::from_secs(2)).await;
+        assert!(result.as_ref().unwrap().healthy);
+        assert_eq!(exit_code(&result), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_endpoint_exits_nonzero() {
+        let addr = mock_endpoint("HTTP/1.1 503 Service Unavailable\r\n\r\n").await;
+
+        let result = check(&addr, Duration::from_secs(2)).await;
+        assert_eq!(
+            result.as_ref().unwrap().status_line,
+            "HTTP/1.1 503 Service Unavailable"
+        );
+        assert_eq!(exit_code(&result), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_exits_nonzero() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let result = check(&addr, Duration::from_secs(2)).await;
+        assert!(result.is_err());
+        assert_eq!(exit_code(&result), 1);
+    }
+}
@@ -1,6 +1,12 @@
 use clap::{Parser, Subcommand};
 use honeytrap_core::{Config, HoneyTrap};
 
+mod health;
+mod repl;
+mod replay;
+mod stats;
+mod train;
+
 #[derive(Parser)]
 #[command(name = "honeytrap")]
 #[command(about = "🍯 HoneyTrap - AI-powered Zero Trust Network Access", long_about = None)]
@@ -34,14 +40,22 @@ enum Commands {
         service: String,
     },
 
-    /// Show statistics
+    /// Show statistics of a running server
     Stats {
-        /// Server address
-        #[arg(short, long, default_value = "127.0.0.1:8443")]
+        /// Metrics/stats endpoint address
+        #[arg(short, long, default_value = "127.0.0.1:9090")]
         server: String,
+
+        /// Print raw JSON instead of a table
+        #[arg(long)]
+        json: bool,
+
+        /// Timeout in seconds
+        #[arg(short, long, default_value_t = 5)]
+        timeout: u64,
     },
 
-    /// Train AI model
+    /// Train AI model from a labeled dataset
     Train {
         /// Training data path
         #[arg(short, long)]
@@ -50,6 +64,14 @@ enum Commands {
         /// Output model path
         #[arg(short, long)]
         output: String,
+
+        /// Dataset format (default: from file extension)
+        #[arg(short, long, value_enum)]
+        format: Option<train::DataFormat>,
+
+        /// Fraction of samples held out for evaluation
+        #[arg(long, default_value_t = 0.2)]
+        test_split: f64,
     },
 
     /// Connect as client
@@ -62,6 +84,35 @@ enum Commands {
         #[arg(short, long)]
         resource: String,
     },
+
+    /// Health probe for container orchestration (exit code 0 = healthy)
+    Health {
+        /// Metrics/health endpoint address
+        #[arg(short, long, default_value = "127.0.0.1:9090")]
+        server: String,
+
+        /// Timeout in seconds
+        #[arg(short, long, default_value_t = 3)]
+        timeout: u64,
+    },
+
+    /// Replay a labeled capture file through the anomaly detector
+    Replay {
+        /// Capture file (`label,feature1,feature2,...` per line)
+        #[arg(short, long)]
+        captures: String,
+
+        /// Model path
+        #[arg(short, long)]
+        model: String,
+    },
+
+    /// Interactive REPL against a local interaction handler (no network)
+    Repl {
+        /// Service type (ssh, http, mysql)
+        #[arg(short, long, default_value = "ssh")]
+        service: String,
+    },
 }
 
 #[tokio::main]
@@ -83,8 +134,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .init();
 
             // Load config
-            let config_str = tokio::fs::read_to_string(&config).await?;
-            let config: Config = toml::from_str(&config_str)?;
+            let config = Config::from_file(&config)?;
 
             // Start HoneyTrap
             let honeytrap = HoneyTrap::new(config).await?;
@@ -102,7 +152,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 env!("CARGO_PKG_VERSION")
             );
 
-            honeytrap.run().await?;
+            tokio::select! {
+                result = honeytrap.run() => result?,
+                _ = tokio::signal::ctrl_c() => println!("🛑 Shutting down..."),
+            }
+            honeytrap.shutdown().await?;
         }
 
         Commands::Deploy { port, service } => {
@@ -110,21 +164,54 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             // TODO: Implement
         }
 
-        Commands::Stats { server } => {
-            println!("📊 HoneyTrap Statistics for {}", server);
-            // TODO: Implement API call
+        Commands::Stats {
+            server,
+            json,
+            timeout,
+        } => {
+            let timeout = std::time::Duration::from_secs(timeout);
+            if json {
+                println!("{}", stats::fetch_raw(&server, timeout).await?);
+            } else {
+                let stats = stats::fetch(&server, timeout).await?;
+                print!("{}", stats::render_table(&server, &stats));
+            }
         }
 
-        Commands::Train { data, output } => {
+        Commands::Train {
+            data,
+            output,
+            format,
+            test_split,
+        } => {
             println!("🧠 Training model with data from {}", data);
-            println!("💾 Output: {}", output);
-            // TODO: Implement training
+            train::run(&data, &output, format, test_split).await?;
         }
 
         Commands::Connect { server, resource } => {
             println!("🔌 Connecting to {} → {}", server, resource);
             // TODO: Implement client
         }
+
+        Commands::Health { server, timeout } => {
+            let result = health::check(&server, std::time::Duration::from_secs(timeout)).await;
+            match &result {
+                Ok(status) if status.healthy => {
+                    println!("✅ {} healthy ({})", server, status.status_line)
+                }
+                Ok(status) => println!("❌ {} unhealthy ({})", server, status.status_line),
+                Err(e) => println!("❌ {} unreachable: {}", server, e),
+            }
+            std::process::exit(health::exit_code(&result));
+        }
+
+        Commands::Replay { captures, model } => {
+            replay::run(&captures, &model).await?;
+        }
+
+        Commands::Repl { service } => {
+            repl::run(&service).await?;
+        }
     }
 
     Ok(())
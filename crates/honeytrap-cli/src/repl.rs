@@ -0,0 +1,174 @@
+//! Interaktiver REPL-Modus
+//!
+//! Startet einen Interaction-Handler lokal (ohne Netzwerk) und verarbeitet
+//! Eingaben von stdin - zum schnellen Tunen der Honeypot-Antworten.
+
+use honeytrap_deception::{
+    HttpInteractionHandler, HttpMethod, HttpRequest, HttpResponse, MysqlInteractionHandler,
+    MysqlResponse, SshInteractionHandler,
+};
+use std::collections::HashMap;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+/// Lokaler Handler für den REPL
+pub enum ReplHandler {
+    Ssh(Box<SshInteractionHandler>),
+    Http(Box<HttpInteractionHandler>),
+    Mysql(MysqlInteractionHandler),
+}
+
+impl ReplHandler {
+    /// Handler für Service-Type erstellen (ssh, http, mysql)
+    pub fn new(service: &str) -> Result<Self, String> {
+        let session_id = "repl".to_string();
+        match service.to_lowercase().as_str() {
+            "ssh" => Ok(Self::Ssh(Box::new(SshInteractionHandler::new(session_id)))),
+            "http" => Ok(Self::Http(Box::new(HttpInteractionHandler::new(session_id)))),
+            "mysql" => Ok(Self::Mysql(MysqlInteractionHandler::new(session_id))),
+            other => Err(format!("Unsupported service for REPL: {}", other)),
+        }
+    }
+
+    /// Prompt für die nächste Eingabe
+    pub fn prompt(&self) -> String {
+        match self {
+            Self::Ssh(handler) => handler.get_prompt(),
+            Self::Http(_) => "http> ".to_string(),
+            Self::Mysql(_) => "mysql> ".to_string(),
+        }
+    }
+
+    /// Eine Eingabezeile verarbeiten und die Antwort als Text liefern
+    ///
+    /// HTTP-Eingaben haben die Form `METHOD /path [body]`.
+    pub async fn process_line(&mut self, line: &str) -> String {
+        match self {
+            Self::Ssh(handler) => handler.execute_command(line).await,
+            Self::Http(handler) => match parse_http_line(line) {
+                Ok(request) => format_http_response(&handler.handle_request(request).await),
+                Err(e) => format!("{}\n", e),
+            },
+            Self::Mysql(handler) => format_mysql_response(&handler.handle_query(line).await),
+        }
+    }
+}
+
+/// REPL auf stdin/stdout ausführen
+pub async fn run(service: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut handler = ReplHandler::new(service)?;
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    println!("🧪 HoneyTrap REPL ({}) - type 'quit' to exit", service);
+
+    loop {
+        stdout.write_all(handler.prompt().as_bytes()).await?;
+        stdout.flush().await?;
+
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let line = line.trim();
+        if line == "quit" {
+            break;
+        }
+
+        let output = handler.process_line(line).await;
+        stdout.write_all(output.as_bytes()).await?;
+
+        if matches!(&handler, ReplHandler::Ssh(ssh) if ssh.is_disconnected()) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// `METHOD /path [body]` in einen HttpRequest umwandeln
+fn parse_http_line(line: &str) -> Result<HttpRequest, String> {
+    let mut parts = line.splitn(3, ' ');
+    let method = parts.next().unwrap_or("");
+    let method =
+        HttpMethod::parse(method).ok_or_else(|| format!("Unknown HTTP method: {}", method))?;
+    let path = parts.next().unwrap_or("/").to_string();
+    let body = parts.next().map(|b| b.to_string());
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers: HashMap::new(),
+        body,
+    })
+}
+
+fn format_http_response(response: &HttpResponse) -> String {
+    let mut output = format!("HTTP/1.1 {} {}\n", response.status, response.status_text);
+    let mut headers: Vec<_> = response.headers.iter().collect();
+    headers.sort();
+    for (name, value) in headers {
+        output.push_str(&format!("{}: {}\n", name, value));
+    }
+    output.push('\n');
+    output.push_str(&response.body);
+    output.push('\n');
+    output
+}
+
+fn format_mysql_response(response: &MysqlResponse) -> String {
+    match response {
+        MysqlResponse::Ok { affected_rows } => {
+            format!("Query OK, {} rows affected\n", affected_rows)
+        }
+        MysqlResponse::Error { code, message } => format!("ERROR {}: {}\n", code, message),
+        MysqlResponse::ResultSet { columns, rows } => {
+            let mut output = format!("{}\n", columns.join("\t"));
+            for row in rows {
+                output.push_str(&format!("{}\n", row.join("\t")));
+            }
+            output.push_str(&format!("{} rows in set\n", rows.len()));
+            output
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ssh_repl_script() {
+        let mut handler = ReplHandler::new("ssh").unwrap();
+        let script = ["whoami", "cd /etc", "pwd", "foo"];
+
+        let mut outputs = Vec::new();
+        for line in script {
+            outputs.push(handler.process_line(line).await);
+        }
+
+        assert_eq!(outputs[0], "admin\n");
+        assert_eq!(outputs[1], "");
+        assert_eq!(outputs[2], "/etc\n");
+        assert_eq!(outputs[3], "foo: command not found\n");
+        assert!(handler.prompt().contains(":/etc$"));
+    }
+
+    #[tokio::test]
+    async fn test_http_and_mysql_repl_lines() {
+        let mut http = ReplHandler::new("http").unwrap();
+        let output = http.process_line("GET /").await;
+        assert!(output.starts_with("HTTP/1.1 200 OK\n"));
+        assert!(output.contains("Welcome"));
+        let output = http.process_line("FOO /").await;
+        assert_eq!(output, "Unknown HTTP method: FOO\n");
+
+        let mut mysql = ReplHandler::new("mysql").unwrap();
+        let output = mysql.process_line("SHOW DATABASES").await;
+        assert!(output.contains("corporate_db"));
+        assert!(output.ends_with("4 rows in set\n"));
+    }
+
+    #[test]
+    fn test_unknown_service() {
+        assert!(ReplHandler::new("ftp").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+//! Stats Abfrage
+//!
+//! Holt `HoneyTrapStats` vom `/stats` Endpoint einer laufenden Instanz und
+//! gibt sie als Tabelle oder JSON aus.
+
+use honeytrap_core::HoneyTrapStats;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+/// Antwort-Body von `/stats` (JSON)
+pub async fn fetch_raw(server: &str, request_timeout: Duration) -> Result<String, String> {
+    timeout(request_timeout, request(server))
+        .await
+        .map_err(|_| format!("timed out after {:?}", request_timeout))?
+}
+
+/// `/stats` abfragen und parsen
+pub async fn fetch(server: &str, request_timeout: Duration) -> Result<HoneyTrapStats, String> {
+    let body = fetch_raw(server, request_timeout).await?;
+    serde_json::from_str(&body).map_err(|e| format!("invalid stats response: {}", e))
+}
+
+async fn request(server: &str) -> Result<String, String> {
+    let mut stream = TcpStream::connect(server)
+        .await
+        .map_err(|e| format!("connect to {} failed: {}", server, e))?;
+
+    let request = format!(
+        "GET /stats HTTP/1.1\r\nHost: {}\r\nAccept: application/json\r\nConnection: close\r\n\r\n",
+        server
+    );
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(|e| format!("request failed: {}", e))?;
+
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .await
+        .map_err(|e| format!("reading response failed: {}", e))?;
+
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("malformed HTTP response")?;
+    let status_line = head.lines().next().unwrap_or_default().trim();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(format!("unexpected status: {}", status_line));
+    }
+
+    Ok(body.to_string())
+}
+
+/// Menschenlesbare Tabelle
+pub fn render_table(server: &str, stats: &HoneyTrapStats) -> String {
+    let rows = [
+        ("Total connections", stats.total_connections.to_string()),
+        ("Anomalies detected", stats.anomalies_detected.to_string()),
+        ("Active honeypots", stats.active_honeypots.to_string()),
+        ("Blocked IPs", stats.blocked_ips.to_string()),
+        ("Model accuracy", format!("{:.2}%", stats.model_accuracy * 100.0)),
+        ("Anomaly threshold", format!("{:.2}", stats.anomaly_threshold)),
+        ("Exfiltrated bytes", stats.exfil_bytes.to_string()),
+    ];
+
+    let mut table = format!("📊 HoneyTrap Statistics for {}\n", server);
+    for (label, value) in rows {
+        table.push_str(&format!("  {:<20} {:>12}\n", label, value));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_fetch_parses_stats_and_renders_table() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 1024];
+            let n = socket.read(&mut buffer).await.unwrap();
+            assert!(String::from_utf8_lossy(&buffer[..n]).starts_with("GET /stats "));
+            let body = r#"{"total_connections":42,"anomalies_detected":7,"active_honeypots":3,"blocked_ips":2,"model_accuracy":0.9375,"anomaly_threshold":0.85}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let stats = fetch(&addr, Duration::from_secs(2)).await.unwrap();
+        assert_eq!(
+            stats,
+            HoneyTrapStats {
+                total_connections: 42,
+                anomalies_detected: 7,
+                active_honeypots: 3,
+                blocked_ips: 2,
+                model_accuracy: 0.9375,
+                anomaly_threshold: 0.85,
+                exfil_bytes: 0,
+            }
+        );
+
+        let table = render_table(&addr, &stats);
+        assert!(table.contains("Total connections"));
+        assert!(table.contains("93.75%"));
+        assert!(table.contains("Anomaly threshold"));
+        assert!(table.lines().any(|line| line.contains("Blocked IPs") && line.ends_with(" 2")));
+    }
+}
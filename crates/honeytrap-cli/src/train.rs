@@ -0,0 +1,214 @@
+//! Model-Training
+//!
+//! Trainiert einen `AnomalyDetector` aus einer gelabelten Datei, bewertet
+//! das Model auf einem zurückgehaltenen Split und speichert es. Formate:
+//! CSV wie bei `replay` (`label,f1,f2,...`, `benign` = normal) oder JSON
+//! (`[{"features": [...], "anomaly": true}, ...]`).
+
+use crate::replay::parse_captures;
+use honeytrap_ai::{AnomalyDetector, ModelMetrics, RandomForestModel};
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// Format der Trainingsdatei
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum DataFormat {
+    Csv,
+    Json,
+}
+
+impl DataFormat {
+    /// Aus der Dateiendung ableiten (Default: CSV)
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => DataFormat::Json,
+            _ => DataFormat::Csv,
+        }
+    }
+}
+
+/// Gelabelte Samples: (Features, Anomalie?)
+pub type Dataset = Vec<(Vec<f64>, bool)>;
+
+/// Sample im JSON-Format
+#[derive(Debug, Deserialize)]
+struct JsonSample {
+    features: Vec<f64>,
+    anomaly: bool,
+}
+
+/// Trainingsdatei parsen
+pub fn parse_dataset(content: &str, format: DataFormat) -> Result<Dataset, String> {
+    let samples: Dataset = match format {
+        DataFormat::Csv => parse_captures(content)?
+            .into_iter()
+            .map(|sample| (sample.features, sample.attack_type.is_some()))
+            .collect(),
+        DataFormat::Json => serde_json::from_str::<Vec<JsonSample>>(content)
+            .map_err(|e| format!("invalid JSON dataset: {}", e))?
+            .into_iter()
+            .map(|sample| (sample.features, sample.anomaly))
+            .collect(),
+    };
+
+    if samples.is_empty() {
+        return Err("dataset contains no samples".to_string());
+    }
+    Ok(samples)
+}
+
+/// Stratifizierter Holdout-Split: pro Klasse gleichmäßig verteilt ca.
+/// `test_split` der Samples in den Test-Split (deterministisch)
+pub fn split(samples: Dataset, test_split: f64) -> (Dataset, Dataset) {
+    let mut train = Vec::new();
+    let mut test = Vec::new();
+    let mut seen = [0usize; 2];
+
+    for sample in samples {
+        let index = &mut seen[usize::from(sample.1)];
+        let picked =
+            ((*index + 1) as f64 * test_split).floor() > (*index as f64 * test_split).floor();
+        *index += 1;
+        if picked {
+            test.push(sample);
+        } else {
+            train.push(sample);
+        }
+    }
+    (train, test)
+}
+
+/// Ergebnis eines Trainingslaufs
+#[derive(Debug, Clone)]
+pub struct TrainReport {
+    pub train_samples: usize,
+    pub test_samples: usize,
+    pub training_accuracy: f64,
+    /// Metriken auf dem Test-Split (None ohne Test-Split)
+    pub metrics: Option<ModelMetrics>,
+}
+
+impl fmt::Display for TrainReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "🧠 Trained on {} samples, {} held out",
+            self.train_samples, self.test_samples
+        )?;
+        writeln!(f, "Training accuracy: {:.2}%", self.training_accuracy * 100.0)?;
+        if let Some(m) = &self.metrics {
+            writeln!(
+                f,
+                "Holdout  Accuracy: {:.2}%  Precision: {:.2}%  Recall: {:.2}%  F1: {:.3}",
+                m.accuracy * 100.0,
+                m.precision * 100.0,
+                m.recall * 100.0,
+                m.f1_score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Trainieren, speichern und das gespeicherte Model auf dem Test-Split bewerten
+pub async fn train(
+    samples: Dataset,
+    test_split: f64,
+    output: &str,
+) -> Result<TrainReport, Box<dyn std::error::Error>> {
+    if !(0.0..1.0).contains(&test_split) {
+        return Err(format!("--test-split must be in [0, 1), got {}", test_split).into());
+    }
+
+    let (train_set, test_set) = split(samples, test_split);
+    if train_set.is_empty() {
+        return Err("no samples left for training".into());
+    }
+    let train_samples = train_set.len();
+
+    let mut detector = AnomalyDetector::new(100);
+    let training_accuracy = detector.train(train_set).await?;
+    detector.save_model(output).await?;
+
+    // Gespeicherte Datei laden: bewertet genau das ausgelieferte Model
+    let metrics = if test_set.is_empty() {
+        None
+    } else {
+        let model = RandomForestModel::load(output)?;
+        let (x_test, y_test) = test_set
+            .iter()
+            .map(|(features, anomaly)| (features.clone(), usize::from(*anomaly)))
+            .unzip();
+        Some(model.evaluate(x_test, y_test)?)
+    };
+
+    Ok(TrainReport {
+        train_samples,
+        test_samples: test_set.len(),
+        training_accuracy,
+        metrics,
+    })
+}
+
+/// Datei laden, trainieren und Report ausgeben
+pub async fn run(
+    data: &str,
+    output: &str,
+    format: Option<DataFormat>,
+    test_split: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let format = format.unwrap_or_else(|| DataFormat::from_path(data));
+    let samples = parse_dataset(&std::fs::read_to_string(data)?, format)?;
+
+    let report = train(samples, test_split, output).await?;
+    print!("{}", report);
+    println!("💾 Model saved to {}", output);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_ai::SyntheticTrafficGenerator;
+
+    #[test]
+    fn test_parse_formats_and_stratified_split() {
+        let csv = parse_dataset("benign,1,2\nddos,900,3\n", DataFormat::Csv).unwrap();
+        let json = parse_dataset(
+            r#"[{"features": [1, 2], "anomaly": false}, {"features": [900, 3], "anomaly": true}]"#,
+            DataFormat::Json,
+        )
+        .unwrap();
+        assert_eq!(csv, json);
+        assert_eq!(DataFormat::from_path("attacks.JSON"), DataFormat::Json);
+        assert!(parse_dataset("[]", DataFormat::Json).is_err());
+
+        // 40 normal, 10 Angriffe (sortiert): 20% jeder Klasse im Test-Split
+        let samples: Dataset = (0..50).map(|i| (vec![i as f64], i >= 40)).collect();
+        let (train, test) = split(samples, 0.2);
+        assert_eq!(train.len(), 40);
+        assert_eq!(test.iter().filter(|(_, anomaly)| !anomaly).count(), 8);
+        assert_eq!(test.iter().filter(|(_, anomaly)| *anomaly).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_train_saves_loadable_model_with_holdout_metrics() {
+        let samples = SyntheticTrafficGenerator::new(7).training_data();
+        let output = std::env::temp_dir().join("honeytrap_cli_train_model.json");
+        let output = output.to_str().unwrap();
+
+        let report = train(samples, 0.2, output).await.unwrap();
+        let metrics = report.metrics.clone().expect("holdout metrics");
+        assert!(report.test_samples > 0);
+        assert!(metrics.accuracy > 0.9, "holdout accuracy {}", metrics.accuracy);
+        assert!(report.to_string().contains("Holdout  Accuracy"));
+
+        let mut detector = AnomalyDetector::new(10);
+        detector.load_model(output).await.unwrap();
+        assert!(detector.classifier().is_trained());
+        std::fs::remove_file(output).ok();
+
+        assert!(train(vec![(vec![1.0], false)], 1.0, output).await.is_err());
+    }
+}
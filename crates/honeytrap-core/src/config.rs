@@ -1,5 +1,15 @@
+use crate::error::{HoneyTrapError, Result};
+use crate::events::{EventOverflow, DEFAULT_EVENT_CAPACITY};
+use crate::scanners::{Cidr, KnownScanner};
+pub use honeytrap_deception::ExitBehavior;
+use honeytrap_deception::{HoneypotType, DEFAULT_CAPTURE_QUEUE, DEFAULT_MAX_INTEL_ENTRIES};
+pub use honeytrap_ai::{LLMConfig, RewardConfig};
+use honeytrap_ai::{validate_prompt_template, LLMConfigError, LLMProvider};
+use honeytrap_protocol::{TransportParams, DEFAULT_MIRROR_QUEUE};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -9,6 +19,285 @@ pub struct Config {
     pub security: SecurityConfig,
     #[serde(default)]
     pub llm: LLMConfig,
+    /// Bekannte Research-Scanner (minimale Engagement)
+    #[serde(default)]
+    pub known_scanners: Vec<KnownScanner>,
+    #[serde(default)]
+    pub engagement: EngagementConfig,
+    #[serde(default)]
+    pub events: EventsConfig,
+    #[serde(default)]
+    pub intel: IntelConfig,
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    #[serde(default)]
+    pub mirror: MirrorConfig,
+    #[serde(default)]
+    pub policy: PolicyConfig,
+}
+
+impl Config {
+    /// Config aus TOML parsen und prüfen
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Config-Datei laden
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            HoneyTrapError::Config(format!("failed to read {}: {}", path.display(), e))
+        })?;
+        Self::from_toml(&content)
+    }
+
+    /// Builder für programmatische Konfiguration (Library-Einbettung)
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::new()
+    }
+
+    /// Werte prüfen, die TOML-Parsing allein nicht abfängt
+    pub fn validate(&self) -> Result<()> {
+        if self.ai.window_size == 0 {
+            return Err(HoneyTrapError::Config("ai.window_size must be > 0".to_string()));
+        }
+        if !(0.0..=1.0).contains(&self.ai.anomaly_threshold) {
+            return Err(HoneyTrapError::Config(format!(
+                "ai.anomaly_threshold must be within 0.0..=1.0, got {}",
+                self.ai.anomaly_threshold
+            )));
+        }
+        if self.network.cert_path.is_some() != self.network.key_path.is_some() {
+            return Err(HoneyTrapError::Config(
+                "network.cert_path and network.key_path must be set together".to_string(),
+            ));
+        }
+        if self.events.capacity == 0 {
+            return Err(HoneyTrapError::Config("events.capacity must be > 0".to_string()));
+        }
+        if self.capture.queue_capacity == 0 {
+            return Err(HoneyTrapError::Config("capture.queue_capacity must be > 0".to_string()));
+        }
+        if self.mirror.file.is_some() && self.mirror.unix_socket.is_some() {
+            return Err(HoneyTrapError::Config(
+                "mirror.file and mirror.unix_socket are mutually exclusive".to_string(),
+            ));
+        }
+        if self.mirror.queue_capacity == 0 {
+            return Err(HoneyTrapError::Config("mirror.queue_capacity must be > 0".to_string()));
+        }
+        if self.intel.max_entries == 0 {
+            return Err(HoneyTrapError::Config("intel.max_entries must be > 0".to_string()));
+        }
+        if self.intel.retention_days == Some(0) {
+            return Err(HoneyTrapError::Config("intel.retention_days must be > 0".to_string()));
+        }
+        if self.engagement.max_engagement_secs == Some(0) {
+            return Err(HoneyTrapError::Config(
+                "engagement.max_engagement_secs must be > 0".to_string(),
+            ));
+        }
+
+        let mut ports = std::collections::HashSet::new();
+        for honeypot in &self.honeypots {
+            if !ports.insert(honeypot.port) {
+                return Err(HoneyTrapError::Config(format!(
+                    "duplicate honeypot port {}",
+                    honeypot.port
+                )));
+            }
+            honeypot.honeypot_type()?;
+            if !matches!(honeypot.interaction_level.as_str(), "low" | "medium" | "high") {
+                return Err(HoneyTrapError::Config(format!(
+                    "unknown interaction level '{}' on port {}",
+                    honeypot.interaction_level, honeypot.port
+                )));
+            }
+        }
+
+        if let Some(port) = self.network.default_honeypot_port {
+            if !self
+                .honeypots
+                .iter()
+                .any(|honeypot| honeypot.port == port && honeypot.service_type != "snmp")
+            {
+                return Err(HoneyTrapError::Config(format!(
+                    "network.default_honeypot_port {} has no TCP honeypot",
+                    port
+                )));
+            }
+        }
+
+        // Fehlender API Key kann noch aus der Umgebung kommen
+        if let Err(e @ LLMConfigError::UnknownProvider(_)) = LLMProvider::from_config(&self.llm) {
+            return Err(HoneyTrapError::config(e));
+        }
+        if let Some(template) = &self.llm.prompt_template {
+            validate_prompt_template(template).map_err(HoneyTrapError::config)?;
+        }
+
+        for scanner in &self.known_scanners {
+            for range in &scanner.ranges {
+                range.parse::<Cidr>().map_err(|e| {
+                    HoneyTrapError::Config(format!(
+                        "invalid range '{}' for scanner {}: {}",
+                        range, scanner.name, e
+                    ))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Fluent Builder für `Config`
+///
+/// Startet mit den Defaults, aber ohne Honeypots.
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            config: Config {
+                honeypots: Vec::new(),
+                ..Config::default()
+            },
+        }
+    }
+
+    /// Bind-Adresse des Transports
+    pub fn bind_addr(mut self, bind_addr: SocketAddr) -> Self {
+        self.config.network.bind_addr = bind_addr;
+        self
+    }
+
+    /// QUIC aktivieren/deaktivieren
+    pub fn enable_quic(mut self, enable_quic: bool) -> Self {
+        self.config.network.enable_quic = enable_quic;
+        self
+    }
+
+    /// Honeypot für Verbindungen auf dem Transport-Port
+    pub fn default_honeypot_port(mut self, port: u16) -> Self {
+        self.config.network.default_honeypot_port = Some(port);
+        self
+    }
+
+    /// Honeypot hinzufügen (wird automatisch deployed)
+    pub fn add_honeypot(mut self, port: u16, service_type: &str, interaction_level: &str) -> Self {
+        self.config.honeypots.push(HoneypotConfig {
+            port,
+            service_type: service_type.to_string(),
+            interaction_level: interaction_level.to_string(),
+            auto_deploy: true,
+        });
+        self
+    }
+
+    /// Anomalie-Schwellwert (0.0 - 1.0)
+    pub fn anomaly_threshold(mut self, anomaly_threshold: f64) -> Self {
+        self.config.ai.anomaly_threshold = anomaly_threshold;
+        self
+    }
+
+    /// Größe des Sliding Windows
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.config.ai.window_size = window_size;
+        self
+    }
+
+    /// Mit LLM-Konfiguration
+    pub fn with_llm(mut self, llm: LLMConfig) -> Self {
+        self.config.llm = llm;
+        self
+    }
+
+    /// Mit Security-Einstellungen
+    pub fn with_security(mut self, security: SecurityConfig) -> Self {
+        self.config.security = security;
+        self
+    }
+
+    /// Bekannten Research-Scanner hinzufügen
+    pub fn add_known_scanner(mut self, name: &str, ranges: &[&str]) -> Self {
+        self.config.known_scanners.push(KnownScanner {
+            name: name.to_string(),
+            ranges: ranges.iter().map(|range| range.to_string()).collect(),
+        });
+        self
+    }
+
+    /// Verhalten bei `exit`/`logout`
+    pub fn on_exit(mut self, on_exit: ExitBehavior) -> Self {
+        self.config.engagement.on_exit = on_exit;
+        self
+    }
+
+    /// Max. gesamte Engagement-Zeit pro Session
+    pub fn max_engagement(mut self, max_engagement: std::time::Duration) -> Self {
+        self.config.engagement.max_engagement_secs = Some(max_engagement.as_secs());
+        self
+    }
+
+    /// Fester Seed für konsistente Decoys pro Quell-IP
+    pub fn decoy_seed(mut self, seed: u64) -> Self {
+        self.config.engagement.decoy_seed = Some(seed);
+        self
+    }
+
+    /// Fester Seed für Banner/Header-Randomisierung dieser Instanz
+    pub fn personality_seed(mut self, seed: u64) -> Self {
+        self.config.engagement.personality_seed = Some(seed);
+        self
+    }
+
+    /// Service-Definitionen aus einem Verzeichnis deployen
+    pub fn profile_dir(mut self, path: &str) -> Self {
+        self.config.engagement.profile_dir = Some(path.to_string());
+        self
+    }
+
+    /// Dedup-Fenster der Capture-Pipeline (0 = keine Deduplizierung)
+    pub fn capture_dedup_window(mut self, window: Duration) -> Self {
+        self.config.capture.dedup_window_secs = window.as_secs();
+        self
+    }
+
+    /// Eingehende Session-Bytes in Datei spiegeln
+    pub fn mirror_file(mut self, path: &str) -> Self {
+        self.config.mirror.file = Some(path.to_string());
+        self
+    }
+
+    /// Policy-Datei laden (mehrfach aufrufbar)
+    pub fn policy_file(mut self, path: &str) -> Self {
+        self.config.policy.files.push(path.to_string());
+        self
+    }
+
+    /// Intel in SQLite-Datei persistieren
+    pub fn intel_db_path(mut self, path: &str) -> Self {
+        self.config.intel.db_path = Some(path.to_string());
+        self
+    }
+
+    /// Validierte Config erzeugen
+    pub fn build(self) -> Result<Config> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -17,6 +306,57 @@ pub struct NetworkConfig {
     pub enable_quic: bool,
     pub enable_nat_traversal: bool,
     pub stun_servers: Vec<String>,
+    #[serde(default = "default_max_idle_timeout_ms")]
+    pub max_idle_timeout_ms: u64, // 0 = unbegrenzt
+    #[serde(default = "default_max_concurrent_bidi_streams")]
+    pub max_concurrent_bidi_streams: u32,
+    #[serde(default)]
+    pub keep_alive_interval: Option<u64>, // milliseconds
+    #[serde(default)]
+    pub enable_0rtt: bool, // Early Data ist replaybar
+    /// TLS-Zertifikatskette (PEM), ohne cert/key selbst-signiert
+    #[serde(default)]
+    pub cert_path: Option<String>,
+    /// Private Key (PEM) zu `cert_path`
+    #[serde(default)]
+    pub key_path: Option<String>,
+    /// CA-Bundle (PEM) für mTLS - gesetzt = Client-Zertifikat erforderlich
+    #[serde(default)]
+    pub client_ca_path: Option<String>,
+    /// Honeypot-Port für Verbindungen auf dem Transport-Port (None = erster TCP-Honeypot)
+    #[serde(default)]
+    pub default_honeypot_port: Option<u16>,
+}
+
+fn default_max_idle_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_max_concurrent_bidi_streams() -> u32 {
+    100
+}
+
+impl NetworkConfig {
+    /// Standard-Honeypot: konfigurierter Port oder der erste Nicht-UDP-Honeypot
+    pub fn default_honeypot_port(&self, honeypots: &[HoneypotConfig]) -> Option<u16> {
+        self.default_honeypot_port.or_else(|| {
+            honeypots
+                .iter()
+                .find(|honeypot| honeypot.service_type != "snmp")
+                .map(|honeypot| honeypot.port)
+        })
+    }
+
+    /// QUIC Transport-Parameter aus der Config
+    pub fn transport_params(&self) -> TransportParams {
+        TransportParams {
+            max_idle_timeout: (self.max_idle_timeout_ms > 0)
+                .then(|| Duration::from_millis(self.max_idle_timeout_ms)),
+            max_concurrent_bidi_streams: self.max_concurrent_bidi_streams,
+            keep_alive_interval: self.keep_alive_interval.map(Duration::from_millis),
+            enable_0rtt: self.enable_0rtt,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -26,6 +366,28 @@ pub struct AIConfig {
     pub model_path: Option<String>,
     pub training_enabled: bool,
     pub auto_retrain_interval: u64, // seconds
+    #[serde(default)]
+    pub reevaluation_interval: u64, // seconds, 0 = nur beim Verbindungsaufbau
+    /// Reward-Gewichtung des RL-Agents
+    #[serde(default)]
+    pub rl_rewards: RewardConfig,
+    /// Q-Table des RL-Agents (None = nicht persistiert)
+    #[serde(default)]
+    pub rl_agent_path: Option<String>,
+    #[serde(default)]
+    pub rl_save_interval: u64, // seconds, 0 = nur beim Shutdown
+}
+
+impl AIConfig {
+    /// Intervall für die Neubewertung laufender Sessions
+    pub fn reevaluation_interval(&self) -> Option<Duration> {
+        (self.reevaluation_interval > 0).then(|| Duration::from_secs(self.reevaluation_interval))
+    }
+
+    /// Intervall für das periodische Speichern des RL-Agents
+    pub fn rl_save_interval(&self) -> Option<Duration> {
+        (self.rl_save_interval > 0).then(|| Duration::from_secs(self.rl_save_interval))
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -36,33 +398,210 @@ pub struct HoneypotConfig {
     pub auto_deploy: bool,
 }
 
+impl HoneypotConfig {
+    /// `service_type` als Honeypot-Typ (Fehler bei unbekanntem Typ)
+    pub fn honeypot_type(&self) -> Result<HoneypotType> {
+        self.service_type
+            .parse()
+            .map_err(|e| HoneyTrapError::Config(format!("{} on port {}", e, self.port)))
+    }
+}
+
+/// Verhalten der interaktiven Honeypots gegenüber dem Angreifer
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EngagementConfig {
+    /// Was nach `exit`/`logout` passiert (disconnect/relogin/subshell)
+    #[serde(default)]
+    pub on_exit: ExitBehavior,
+    /// Max. gesamte Engagement-Zeit pro Session in Sekunden (None = unbegrenzt)
+    #[serde(default)]
+    pub max_engagement_secs: Option<u64>,
+    /// Seed für pro Quell-IP konsistente Decoys (None = zufällig pro Prozess)
+    #[serde(default)]
+    pub decoy_seed: Option<u64>,
+    /// Seed für Banner-, Versions- und Fehlertext-Randomisierung (None = zufällig)
+    #[serde(default)]
+    pub personality_seed: Option<u64>,
+    /// Verzeichnis mit Service-Definitionen (`*.toml`), beim Start deployt
+    #[serde(default)]
+    pub profile_dir: Option<String>,
+}
+
+/// Session-Event-Queue für Consumer (Metrics, Logging, SIEM-Export)
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct SecurityConfig {
-    pub max_failed_attempts: u32,
-    pub block_duration: u64, // seconds
-    pub enable_tarpit: bool,
-    pub tarpit_delay: u64, // seconds
+pub struct EventsConfig {
+    /// Max. Anzahl wartender Events
+    #[serde(default = "default_event_capacity")]
+    pub capacity: usize,
+    /// Was bei voller Queue verworfen wird (drop_oldest/drop_newest)
+    #[serde(default)]
+    pub overflow: EventOverflow,
+}
+
+impl Default for EventsConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_event_capacity(),
+            overflow: EventOverflow::default(),
+        }
+    }
+}
+
+fn default_event_capacity() -> usize {
+    DEFAULT_EVENT_CAPACITY
 }
 
+/// Capture-Pipeline (Handshake-Fehler, ...) vor den Capture-Sinks
 #[derive(Debug, Clone, Deserialize, Serialize)]
-pub struct LLMConfig {
-    pub enabled: bool,
-    pub provider: String, // "deepseek" or "openai"
-    pub api_key: Option<String>,
-    pub model: String,
+pub struct CaptureConfig {
+    /// Identische Events innerhalb dieses Fensters zusammenfassen (0 = aus)
+    #[serde(default = "default_dedup_window_secs")]
+    pub dedup_window_secs: u64,
+    /// Max. wartende Events, darüber wird verworfen
+    #[serde(default = "default_capture_queue")]
+    pub queue_capacity: usize,
 }
 
-impl Default for LLMConfig {
+impl CaptureConfig {
+    pub fn dedup_window(&self) -> Duration {
+        Duration::from_secs(self.dedup_window_secs)
+    }
+}
+
+impl Default for CaptureConfig {
     fn default() -> Self {
         Self {
-            enabled: false,
-            provider: "deepseek".to_string(),
-            api_key: None,
-            model: "deepseek-chat".to_string(),
+            dedup_window_secs: default_dedup_window_secs(),
+            queue_capacity: default_capture_queue(),
         }
     }
 }
 
+fn default_dedup_window_secs() -> u64 {
+    60
+}
+
+fn default_capture_queue() -> usize {
+    DEFAULT_CAPTURE_QUEUE
+}
+
+/// Policy Engine vor dem AI-Routing
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PolicyConfig {
+    /// Policy-Dateien (TOML), leer = keine Policy Engine
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Bei BLOCK die Quell-IP zusätzlich für `security.block_duration` sperren
+    #[serde(default)]
+    pub block_ips: bool,
+    /// Dateien überwachen und bei Änderungen neu laden
+    #[serde(default = "default_policy_hot_reload")]
+    pub hot_reload: bool,
+}
+
+impl Default for PolicyConfig {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            block_ips: false,
+            hot_reload: default_policy_hot_reload(),
+        }
+    }
+}
+
+fn default_policy_hot_reload() -> bool {
+    true
+}
+
+/// Traffic-Mirror: eingehende Bytes der Honeypot-Sessions an einen Sink
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MirrorConfig {
+    /// Frames an diese Datei anhängen
+    #[serde(default)]
+    pub file: Option<String>,
+    /// Frames als Datagramme an diesen Unix Socket senden
+    #[serde(default)]
+    pub unix_socket: Option<String>,
+    /// Max. wartende Frames vor dem Writer, darüber wird verworfen
+    #[serde(default = "default_mirror_queue")]
+    pub queue_capacity: usize,
+}
+
+impl Default for MirrorConfig {
+    fn default() -> Self {
+        Self {
+            file: None,
+            unix_socket: None,
+            queue_capacity: default_mirror_queue(),
+        }
+    }
+}
+
+fn default_mirror_queue() -> usize {
+    DEFAULT_MIRROR_QUEUE
+}
+
+/// Intel-Store für Analysten-Abfragen (Commands, Angriffe, Sessions pro IP)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct IntelConfig {
+    /// SQLite-Datei (None = nur In-Memory, geht beim Neustart verloren)
+    #[serde(default)]
+    pub db_path: Option<String>,
+    /// Einträge älter als so viele Tage werden gelöscht (None = behalten)
+    #[serde(default)]
+    pub retention_days: Option<u64>,
+    /// Max. Einträge pro Tabelle, älteste werden verworfen
+    #[serde(default = "default_intel_max_entries")]
+    pub max_entries: usize,
+}
+
+impl Default for IntelConfig {
+    fn default() -> Self {
+        Self {
+            db_path: None,
+            retention_days: None,
+            max_entries: default_intel_max_entries(),
+        }
+    }
+}
+
+fn default_intel_max_entries() -> usize {
+    DEFAULT_MAX_INTEL_ENTRIES
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SecurityConfig {
+    pub max_failed_attempts: u32,
+    pub block_duration: u64, // seconds
+    pub enable_tarpit: bool,
+    pub tarpit_delay: u64, // seconds
+    #[serde(default = "default_connection_rate_limit")]
+    pub connection_rate_limit: f64, // Verbindungen/s pro IP, 0 = aus
+    #[serde(default = "default_connection_burst")]
+    pub connection_burst: u32,
+    #[serde(default = "default_session_idle_timeout")]
+    pub session_idle_timeout: u64, // seconds ohne Traffic, 0 = nie reapen
+}
+
+impl SecurityConfig {
+    /// Idle Timeout, nach dem Sessions ohne Traffic entfernt werden
+    pub fn session_idle_timeout(&self) -> Option<Duration> {
+        (self.session_idle_timeout > 0).then(|| Duration::from_secs(self.session_idle_timeout))
+    }
+}
+
+fn default_connection_rate_limit() -> f64 {
+    20.0
+}
+
+fn default_connection_burst() -> u32 {
+    40
+}
+
+fn default_session_idle_timeout() -> u64 {
+    600
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -71,6 +610,14 @@ impl Default for Config {
                 enable_quic: true,
                 enable_nat_traversal: true,
                 stun_servers: vec!["stun:stun.l.google.com:19302".to_string()],
+                max_idle_timeout_ms: default_max_idle_timeout_ms(),
+                max_concurrent_bidi_streams: default_max_concurrent_bidi_streams(),
+                keep_alive_interval: None,
+                enable_0rtt: false,
+                cert_path: None,
+                key_path: None,
+                client_ca_path: None,
+                default_honeypot_port: None,
             },
             ai: AIConfig {
                 window_size: 100,
@@ -78,6 +625,10 @@ impl Default for Config {
                 model_path: Some("./models/honeytrap.pkl".to_string()),
                 training_enabled: true,
                 auto_retrain_interval: 86400, // 24h
+                reevaluation_interval: 0,
+                rl_rewards: RewardConfig::default(),
+                rl_agent_path: None,
+                rl_save_interval: 0,
             },
             honeypots: vec![
                 HoneypotConfig {
@@ -98,8 +649,127 @@ impl Default for Config {
                 block_duration: 3600,
                 enable_tarpit: true,
                 tarpit_delay: 300,
+                connection_rate_limit: default_connection_rate_limit(),
+                connection_burst: default_connection_burst(),
+                session_idle_timeout: default_session_idle_timeout(),
             },
             llm: LLMConfig::default(),
+            known_scanners: Vec::new(),
+            engagement: EngagementConfig::default(),
+            events: EventsConfig::default(),
+            intel: IntelConfig::default(),
+            capture: CaptureConfig::default(),
+            mirror: MirrorConfig::default(),
+            policy: PolicyConfig::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_failure_is_config_error() {
+        let result = Config::from_toml("[network]\nbind_addr = \"not-an-address\"");
+        assert!(matches!(result, Err(HoneyTrapError::Config(_))));
+
+        let missing = Config::from_file("/nonexistent/honeytrap.toml");
+        assert!(
+            matches!(missing, Err(HoneyTrapError::Config(msg)) if msg.contains("failed to read"))
+        );
+
+        let config = Config::from_toml(include_str!("../../../config/honeytrap.toml")).unwrap();
+        assert_eq!(config.network.max_concurrent_bidi_streams, 100);
+        assert_eq!(config.engagement.on_exit, ExitBehavior::Disconnect);
+    }
+
+    #[test]
+    fn test_invalid_config_file_rejected() {
+        let mut content = include_str!("../../../config/honeytrap.toml").to_string();
+        content = content.replacen("anomaly_threshold = ", "anomaly_threshold = 4", 1);
+        let path =
+            std::env::temp_dir().join(format!("honeytrap-invalid-{}.toml", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+
+        let result = Config::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(result, Err(HoneyTrapError::Config(msg)) if msg.contains("anomaly_threshold"))
+        );
+    }
+
+    #[test]
+    fn test_builder_matches_toml_and_validates() {
+        let from_toml = Config::from_toml(
+            r#"
+            [network]
+            bind_addr = "127.0.0.1:9443"
+            enable_quic = true
+            enable_nat_traversal = true
+            stun_servers = ["stun:stun.l.google.com:19302"]
+
+            [ai]
+            window_size = 100
+            anomaly_threshold = 0.8
+            model_path = "./models/honeytrap.pkl"
+            training_enabled = true
+            auto_retrain_interval = 86400
+
+            [[honeypots]]
+            port = 2222
+            service_type = "ssh"
+            interaction_level = "high"
+            auto_deploy = true
+
+            [security]
+            max_failed_attempts = 5
+            block_duration = 3600
+            enable_tarpit = true
+            tarpit_delay = 300
+            "#,
+        )
+        .unwrap();
+
+        let built = Config::builder()
+            .bind_addr("127.0.0.1:9443".parse().unwrap())
+            .anomaly_threshold(0.8)
+            .add_honeypot(2222, "ssh", "high")
+            .build()
+            .unwrap();
+        assert_eq!(
+            toml::to_string(&built).unwrap(),
+            toml::to_string(&from_toml).unwrap()
+        );
+
+        let invalid = [
+            Config::builder().anomaly_threshold(1.5).build(),
+            Config::builder()
+                .add_honeypot(22, "ssh", "high")
+                .add_honeypot(22, "http", "low")
+                .build(),
+            Config::builder().add_honeypot(70, "gopher", "low").build(),
+            Config::builder()
+                .with_llm(LLMConfig {
+                    enabled: true,
+                    provider: "bard".to_string(),
+                    ..LLMConfig::default()
+                })
+                .build(),
+            Config::builder()
+                .with_llm(LLMConfig {
+                    prompt_template: Some("Is {source_ip} evil?".to_string()),
+                    ..LLMConfig::default()
+                })
+                .build(),
+            Config::builder().add_known_scanner("censys", &["not-a-cidr"]).build(),
+            Config::builder()
+                .add_honeypot(161, "snmp", "low")
+                .default_honeypot_port(161)
+                .build(),
+        ];
+        for result in invalid {
+            assert!(matches!(result, Err(HoneyTrapError::Config(_))));
         }
     }
 }
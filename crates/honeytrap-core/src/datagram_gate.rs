@@ -0,0 +1,124 @@
+//! Vorab-Prüfung für UDP-Honeypots (SNMP)
+//!
+//! UDP-Quellen sind fälschbar und jede Antwort geht an den angeblichen
+//! Absender. Ohne Blocklist und Rate Limit vor dem Handler wird der Honeypot
+//! zum Reflektor. Verworfene Pakete werden gezählt und pro Quell-IP höchstens
+//! einmal je `LOG_WINDOW` auf Debug-Level geloggt.
+
+use crate::rate_limit::RateLimiter;
+use async_trait::async_trait;
+use honeytrap_deception::{DatagramGate, DeceptionSystem};
+use honeytrap_metrics::METRICS;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Max. ein Log pro Quell-IP in diesem Fenster
+const LOG_WINDOW: Duration = Duration::from_secs(60);
+/// Max. gleichzeitig verfolgte Quell-IPs fürs Logging
+const MAX_LOG_SOURCES: usize = 4096;
+
+/// Blocklist + Token Bucket pro Quell-IP vor dem Datagramm-Handler
+pub struct SourceGate {
+    deception: Arc<DeceptionSystem>,
+    limiter: Option<RateLimiter>,
+    logged: Mutex<HashMap<IpAddr, Instant>>,
+}
+
+impl SourceGate {
+    /// Gate mit Blocklist des Deception-Systems und optionalem Rate Limit
+    pub fn new(deception: Arc<DeceptionSystem>, limiter: Option<RateLimiter>) -> Self {
+        Self {
+            deception,
+            limiter,
+            logged: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Verworfenes Datagramm zählen und (gedrosselt) loggen
+    fn drop_datagram(&self, peer_addr: SocketAddr, reason: &str, now: Instant) {
+        METRICS
+            .connections
+            .datagrams_dropped
+            .with_label_values(&[reason])
+            .inc();
+
+        if self.log_allowed(peer_addr.ip(), now) {
+            tracing::debug!(
+                "🚦 Dropping UDP datagrams from {} ({}, further drops not logged for {:?})",
+                peer_addr,
+                reason,
+                LOG_WINDOW
+            );
+        }
+    }
+
+    /// Darf für `ip` geloggt werden?
+    fn log_allowed(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut logged = self.logged.lock().unwrap();
+
+        if let Some(last) = logged.get_mut(&ip) {
+            if now.saturating_duration_since(*last) < LOG_WINDOW {
+                return false;
+            }
+            *last = now;
+            return true;
+        }
+
+        // Viele (gefälschte) Quellen: abgelaufene Fenster verwerfen, sonst nicht loggen
+        if logged.len() >= MAX_LOG_SOURCES {
+            logged.retain(|_, last| now.saturating_duration_since(*last) < LOG_WINDOW);
+            if logged.len() >= MAX_LOG_SOURCES {
+                return false;
+            }
+        }
+        logged.insert(ip, now);
+        true
+    }
+}
+
+#[async_trait]
+impl DatagramGate for SourceGate {
+    async fn admit(&self, peer_addr: SocketAddr) -> bool {
+        let now = Instant::now();
+
+        if self.deception.is_blocked(peer_addr.ip()).await {
+            self.drop_datagram(peer_addr, "blocked", now);
+            return false;
+        }
+        if let Some(limiter) = &self.limiter {
+            if !limiter.check(peer_addr.ip(), now) {
+                self.drop_datagram(peer_addr, "rate_limited", now);
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_blocked_and_flooding_sources_dropped() {
+        let deception = Arc::new(DeceptionSystem::new());
+        let gate = SourceGate::new(deception.clone(), Some(RateLimiter::new(1.0, 3)));
+        let spoofed: SocketAddr = "198.51.100.7:161".parse().unwrap();
+        let blocked: SocketAddr = "203.0.113.9:161".parse().unwrap();
+        deception.block_ip(blocked.ip()).await;
+
+        let mut admitted = 0;
+        for _ in 0..100 {
+            if gate.admit(spoofed).await {
+                admitted += 1;
+            }
+        }
+        assert_eq!(admitted, 3);
+        assert!(!gate.admit(blocked).await);
+
+        // Nur ein Log-Fenster pro Quelle
+        assert_eq!(gate.logged.lock().unwrap().len(), 2);
+    }
+}
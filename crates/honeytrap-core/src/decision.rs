@@ -0,0 +1,122 @@
+//! Decision Audit Trail
+//!
+//! Hält pro Session fest, warum sie so behandelt wurde (Anomalie-Score,
+//! Policy-Match, RL-Aktion, Block, Routing) - für Tuning und Post-Incident-Review.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::time::Instant;
+
+/// Einzelner Entscheidungspunkt mit Inputs und Ergebnis
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decision {
+    /// ML/Heuristik-Bewertung
+    AnomalyScore {
+        score: f64,
+        threshold: f64,
+        is_anomaly: bool,
+    },
+    /// Policy hat gematcht
+    PolicyMatch { policy: String, action: String },
+    /// Aktion des RL-Agenten
+    RlAction { state: String, action: String },
+    /// Quelle blockiert
+    Block { ip: IpAddr, reason: String },
+    /// Bekannter Research-Scanner erkannt
+    ResearchScanner { scanner: String },
+    /// Endgültiges Routing-Ziel
+    Route { target: String },
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::AnomalyScore {
+                score,
+                threshold,
+                is_anomaly,
+            } => write!(
+                f,
+                "anomaly_score score={:.3} threshold={:.3} anomaly={}",
+                score, threshold, is_anomaly
+            ),
+            Decision::PolicyMatch { policy, action } => {
+                write!(f, "policy_match policy={} action={}", policy, action)
+            }
+            Decision::RlAction { state, action } => {
+                write!(f, "rl_action state={} action={}", state, action)
+            }
+            Decision::Block { ip, reason } => write!(f, "block ip={} reason={}", ip, reason),
+            Decision::ResearchScanner { scanner } => {
+                write!(f, "research_scanner scanner={}", scanner)
+            }
+            Decision::Route { target } => write!(f, "route target={}", target),
+        }
+    }
+}
+
+/// Eintrag im Audit Trail
+#[derive(Debug, Clone)]
+pub struct DecisionEntry {
+    pub at: Instant,
+    pub decision: Decision,
+}
+
+/// Audit Trail aller Entscheidungen einer Session
+#[derive(Debug, Clone, Default)]
+pub struct DecisionTrace {
+    entries: Vec<DecisionEntry>,
+}
+
+impl DecisionTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Entscheidung festhalten
+    pub fn record(&mut self, decision: Decision) {
+        tracing::trace!("📝 Decision: {}", decision);
+        self.entries.push(DecisionEntry {
+            at: Instant::now(),
+            decision,
+        });
+    }
+
+    /// Alle Einträge in Reihenfolge
+    pub fn entries(&self) -> &[DecisionEntry] {
+        &self.entries
+    }
+
+    /// Name der gematchten Policy (falls vorhanden)
+    pub fn matched_policy(&self) -> Option<&str> {
+        self.entries.iter().find_map(|e| match &e.decision {
+            Decision::PolicyMatch { policy, .. } => Some(policy.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Zuletzt festgehaltener Anomalie-Score
+    pub fn anomaly_score(&self) -> Option<f64> {
+        self.entries.iter().rev().find_map(|e| match e.decision {
+            Decision::AnomalyScore { score, .. } => Some(score),
+            _ => None,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl fmt::Display for DecisionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, entry) in self.entries.iter().enumerate() {
+            writeln!(f, "  {}. {}", i + 1, entry.decision)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Einheitlicher Fehlertyp an den öffentlichen API-Grenzen
+//!
+//! Subsysteme liefern weiterhin `Box<dyn Error>`; hier wird die Fehlerart
+//! festgehalten, damit Aufrufer gezielt reagieren können.
+
+use honeytrap_policy::PolicyError;
+use std::fmt::Display;
+
+/// HoneyTrap Fehler
+#[derive(Debug, thiserror::Error)]
+pub enum HoneyTrapError {
+    /// Konfiguration ungültig oder nicht lesbar
+    #[error("configuration error: {0}")]
+    Config(String),
+
+    /// Transport (QUIC, UDP, ...) fehlgeschlagen
+    #[error("transport error: {0}")]
+    Transport(String),
+
+    /// Honeypot-Deployment oder -Handling fehlgeschlagen
+    #[error("honeypot error: {0}")]
+    Honeypot(String),
+
+    /// AI-Engine (Modell, LLM) fehlgeschlagen
+    #[error("AI error: {0}")]
+    Ai(String),
+
+    /// Policy-Laden oder -Auswertung fehlgeschlagen
+    #[error("policy error: {0}")]
+    Policy(#[from] PolicyError),
+}
+
+impl HoneyTrapError {
+    pub fn config(error: impl Display) -> Self {
+        Self::Config(error.to_string())
+    }
+
+    pub fn transport(error: impl Display) -> Self {
+        Self::Transport(error.to_string())
+    }
+
+    pub fn honeypot(error: impl Display) -> Self {
+        Self::Honeypot(error.to_string())
+    }
+
+    pub fn ai(error: impl Display) -> Self {
+        Self::Ai(error.to_string())
+    }
+}
+
+impl From<toml::de::Error> for HoneyTrapError {
+    fn from(error: toml::de::Error) -> Self {
+        Self::config(error)
+    }
+}
+
+/// Result mit `HoneyTrapError`
+pub type Result<T> = std::result::Result<T, HoneyTrapError>;
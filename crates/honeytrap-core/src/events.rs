@@ -0,0 +1,271 @@
+use crate::session::SessionEvent;
+use honeytrap_metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::sync::Notify;
+
+/// Standard-Kapazität der Session-Event-Queue
+pub const DEFAULT_EVENT_CAPACITY: usize = 1024;
+
+/// Was bei voller Event-Queue verworfen wird
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventOverflow {
+    /// Ältestes Event verwerfen, das neue einreihen
+    #[default]
+    DropOldest,
+    /// Neues Event verwerfen
+    DropNewest,
+}
+
+/// Consumer für Session-Events (Metrics, Logging, SIEM-Export, ...)
+///
+/// Wird im Dispatch-Task aufgerufen und sollte daher nicht blockieren.
+pub trait SessionEventSink: Send + Sync {
+    fn handle(&self, event: &SessionEvent);
+}
+
+/// Sink, der alle Events auf Debug-Level loggt
+#[derive(Debug, Default)]
+pub struct LoggingSink;
+
+impl SessionEventSink for LoggingSink {
+    fn handle(&self, event: &SessionEvent) {
+        match event {
+            SessionEvent::Created(session) => tracing::debug!(
+                "📨 Session created for {} (Session: {})",
+                session.peer_addr,
+                session.id
+            ),
+            SessionEvent::Updated(session) => {
+                tracing::debug!("📨 Session updated (Session: {})", session.id)
+            }
+            SessionEvent::Suspicious(session) => tracing::debug!(
+                "📨 Session suspicious, score {:.3} (Session: {})",
+                session.anomaly_score,
+                session.id
+            ),
+            SessionEvent::Closed(id) => tracing::debug!("📨 Session closed (Session: {})", id),
+        }
+    }
+}
+
+struct Shared {
+    queue: Mutex<VecDeque<SessionEvent>>,
+    capacity: usize,
+    overflow: EventOverflow,
+    dropped: AtomicU64,
+    senders: AtomicUsize,
+    notify: Notify,
+}
+
+/// Bounded Event-Queue mit konfigurierbarem Overflow-Verhalten
+pub fn channel(
+    capacity: usize,
+    overflow: EventOverflow,
+) -> (SessionEventSender, SessionEventReceiver) {
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(VecDeque::with_capacity(
+            capacity.min(DEFAULT_EVENT_CAPACITY),
+        )),
+        capacity: capacity.max(1),
+        overflow,
+        dropped: AtomicU64::new(0),
+        senders: AtomicUsize::new(1),
+        notify: Notify::new(),
+    });
+
+    (
+        SessionEventSender {
+            shared: shared.clone(),
+        },
+        SessionEventReceiver { shared },
+    )
+}
+
+/// Sende-Seite der Session-Event-Queue (blockiert nie)
+pub struct SessionEventSender {
+    shared: Arc<Shared>,
+}
+
+impl SessionEventSender {
+    /// Event einreihen - `false`, wenn dabei ein Event verworfen wurde
+    pub fn send(&self, event: SessionEvent) -> bool {
+        let mut queue = self.shared.queue.lock().unwrap();
+        let mut accepted = true;
+
+        if queue.len() >= self.shared.capacity {
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            METRICS.system.session_events_dropped.inc();
+            accepted = false;
+
+            match self.shared.overflow {
+                EventOverflow::DropOldest => {
+                    queue.pop_front();
+                    queue.push_back(event);
+                }
+                EventOverflow::DropNewest => {}
+            }
+        } else {
+            queue.push_back(event);
+        }
+        drop(queue);
+
+        self.shared.notify.notify_one();
+        accepted
+    }
+
+    /// Anzahl wegen voller Queue verworfener Events
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Clone for SessionEventSender {
+    fn clone(&self) -> Self {
+        self.shared.senders.fetch_add(1, Ordering::AcqRel);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for SessionEventSender {
+    fn drop(&mut self) {
+        if self.shared.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            // Wartenden Receiver wecken, damit `recv` `None` liefert
+            self.shared.notify.notify_one();
+        }
+    }
+}
+
+/// Empfangs-Seite der Session-Event-Queue
+pub struct SessionEventReceiver {
+    shared: Arc<Shared>,
+}
+
+impl SessionEventReceiver {
+    /// Nächstes Event - `None`, sobald alle Sender weg und die Queue leer ist
+    pub async fn recv(&mut self) -> Option<SessionEvent> {
+        loop {
+            match self.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Disconnected) => return None,
+                Err(TryRecvError::Empty) => self.shared.notify.notified().await,
+            }
+        }
+    }
+
+    /// Event ohne Warten abholen
+    pub fn try_recv(&mut self) -> Result<SessionEvent, TryRecvError> {
+        if let Some(event) = self.shared.queue.lock().unwrap().pop_front() {
+            return Ok(event);
+        }
+        if self.shared.senders.load(Ordering::Acquire) == 0 {
+            Err(TryRecvError::Disconnected)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+
+    /// Anzahl wartender Events
+    pub fn len(&self) -> usize {
+        self.shared.queue.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Anzahl wegen voller Queue verworfener Events
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Events in einem Hintergrund-Task an alle Sinks verteilen
+    pub fn spawn_dispatch(
+        mut self,
+        sinks: Vec<Arc<dyn SessionEventSink>>,
+    ) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            while let Some(event) = self.recv().await {
+                for sink in &sinks {
+                    sink.handle(&event);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn closed(id: &str) -> SessionEvent {
+        SessionEvent::Closed(id.to_string())
+    }
+
+    fn closed_id(event: SessionEvent) -> String {
+        match event {
+            SessionEvent::Closed(id) => id,
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_overflow_policy_and_dropped_counter() {
+        let (tx, mut rx) = channel(2, EventOverflow::DropOldest);
+        assert!(tx.send(closed("a")));
+        assert!(tx.send(closed("b")));
+        assert!(!tx.send(closed("c")));
+        assert_eq!(rx.dropped(), 1);
+        assert_eq!(closed_id(rx.try_recv().unwrap()), "b");
+        assert_eq!(closed_id(rx.try_recv().unwrap()), "c");
+
+        let (tx, mut rx) = channel(2, EventOverflow::DropNewest);
+        tx.send(closed("a"));
+        tx.send(closed("b"));
+        assert!(!tx.send(closed("c")));
+        assert_eq!(tx.dropped(), 1);
+        assert_eq!(closed_id(rx.try_recv().unwrap()), "a");
+        assert_eq!(closed_id(rx.try_recv().unwrap()), "b");
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+
+        drop(tx);
+        assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_forwards_events_to_sinks() {
+        struct Collect(Mutex<Vec<String>>);
+
+        impl SessionEventSink for Collect {
+            fn handle(&self, event: &SessionEvent) {
+                self.0.lock().unwrap().push(closed_id(event.clone()));
+            }
+        }
+
+        let sink = Arc::new(Collect(Mutex::new(Vec::new())));
+        let (tx, rx) = channel(8, EventOverflow::default());
+        let dispatch = rx.spawn_dispatch(vec![sink.clone(), Arc::new(LoggingSink)]);
+
+        let sender = tx.clone();
+        tokio::spawn(async move {
+            sender.send(closed("a"));
+            sender.send(closed("b"));
+        })
+        .await
+        .unwrap();
+        drop(tx);
+
+        // Dispatch endet, sobald alle Sender gedroppt sind
+        tokio::time::timeout(std::time::Duration::from_secs(1), dispatch)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(*sink.0.lock().unwrap(), vec!["a", "b"]);
+    }
+}
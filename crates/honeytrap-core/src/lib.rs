@@ -1,15 +1,40 @@
 pub mod config;
+pub mod datagram_gate;
+pub mod decision;
+pub mod error;
+pub mod events;
+pub mod rate_limit;
 pub mod router;
+pub mod scanners;
 pub mod session;
 
-pub use config::Config;
+pub use config::{Config, ConfigBuilder};
+pub use datagram_gate::SourceGate;
+pub use decision::{Decision, DecisionTrace};
+pub use error::HoneyTrapError;
+pub use events::{EventOverflow, LoggingSink, SessionEventReceiver, SessionEventSink};
+pub use rate_limit::{ConnectionRate, RateLimiter};
 pub use router::Router;
-pub use session::{Session, SessionManager};
+pub use scanners::{Cidr, KnownScanner, ScannerClassifier};
+pub use session::{Session, SessionEvent, SessionManager, SessionSummary};
 
-use honeytrap_ai::{AnomalyDetector, LLMClient, LLMProvider};
-use honeytrap_deception::DeceptionSystem;
-use honeytrap_protocol::SecureQuicTransport;
-use std::sync::Arc;
+use crate::config::{AIConfig, MirrorConfig, PolicyConfig};
+use honeytrap_ai::{
+    AnomalyDetector, Disagreement, DisagreementHook, LLMClient, LLMProvider, RLAgent,
+    RewardCalculator,
+};
+use honeytrap_deception::{
+    CaptureEvent, CaptureKind, CaptureSender, CaptureSink, CapturePipeline, CloseReason,
+    CompositeSink, DeceptionSystem, IntelStore, LogSink, SnmpHoneypot, UdpListener,
+};
+use honeytrap_metrics::METRICS;
+use honeytrap_policy::{PolicyEngine, PolicyWatcher};
+use honeytrap_protocol::{FileSink, MirrorSink, SecureQuicTransport, TrafficMirror, Transport};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
 /// HoneyTrap - Hauptsystem
@@ -17,78 +42,149 @@ pub struct HoneyTrap {
     /// AI-Engine für Anomalie-Erkennung
     pub ai_engine: Arc<RwLock<AnomalyDetector>>,
 
+    /// RL-Agent für Engagement-Entscheidungen (vom Router trainiert)
+    pub rl_agent: Arc<RwLock<RLAgent>>,
+
     /// Deception System (Honeypots)
     pub deception: Arc<DeceptionSystem>,
 
     /// Secure Transport
-    pub transport: Arc<SecureQuicTransport>,
+    pub transport: Arc<dyn Transport>,
 
     /// Router für Traffic-Handling
     pub router: Arc<Router>,
 
+    /// Per-IP Rate Limiter im Accept-Loop (`None` = deaktiviert)
+    pub rate_limiter: Option<RateLimiter>,
+
+    /// Policy Engine aus `policy.files` (`None` = nur AI-Routing)
+    pub policy: Option<Arc<PolicyEngine>>,
+
+    /// Hot-Reload der Policy-Dateien, lebt so lange wie das System
+    _policy_watcher: Option<PolicyWatcher>,
+
+    /// Capture-Pipeline für ML/LLM-Uneinigkeiten (gesetzt von `spawn_capture_pipeline`)
+    model_captures: Arc<OnceLock<CaptureSender>>,
+
     /// Konfiguration
     pub config: Config,
 }
 
 impl HoneyTrap {
-    /// Neues HoneyTrap System initialisieren
-    pub async fn new(config: Config) -> Result<Self, Box<dyn std::error::Error>> {
+    /// Neues HoneyTrap System mit QUIC Transport initialisieren
+    pub async fn new(config: Config) -> Result<Self, HoneyTrapError> {
+        // Vor dem Binden prüfen, auch für per Hand gebaute Configs
+        config.validate()?;
+        let network = &config.network;
+        let transport = match &network.client_ca_path {
+            Some(client_ca_path) => {
+                SecureQuicTransport::new_server_with_mtls(
+                    network.bind_addr,
+                    network.cert_path.as_deref(),
+                    network.key_path.as_deref(),
+                    client_ca_path,
+                    network.transport_params(),
+                )
+                .await
+            }
+            None => {
+                SecureQuicTransport::new_server_with_certs_and_params(
+                    network.bind_addr,
+                    network.cert_path.as_deref(),
+                    network.key_path.as_deref(),
+                    network.transport_params(),
+                )
+                .await
+            }
+        };
+        let transport = Arc::new(transport.map_err(HoneyTrapError::transport)?);
+
+        Self::build(config, transport).await
+    }
+
+    /// HoneyTrap System mit beliebigem Transport (z.B. `MemoryTransport`) initialisieren
+    pub async fn with_transport(
+        config: Config,
+        transport: Arc<dyn Transport>,
+    ) -> Result<Self, HoneyTrapError> {
+        config.validate()?;
+        Self::build(config, transport).await
+    }
+
+    /// Aufbau mit bereits validierter Config
+    async fn build(config: Config, transport: Arc<dyn Transport>) -> Result<Self, HoneyTrapError> {
         tracing::info!("🍯 Initializing HoneyTrap v{}", env!("CARGO_PKG_VERSION"));
 
         // AI Engine
-        let mut detector = AnomalyDetector::new(config.ai.window_size);
+        let model_captures = Arc::new(OnceLock::new());
+        let mut detector = AnomalyDetector::new(config.ai.window_size)
+            .with_threshold(config.ai.anomaly_threshold)
+            .with_disagreement_hook(capture_disagreements(model_captures.clone()));
 
         // LLM Integration
         if config.llm.enabled {
-            if let Some(api_key) = &config.llm.api_key {
-                let provider = match config.llm.provider.as_str() {
-                    "deepseek" => LLMProvider::DeepSeek {
-                        api_key: api_key.clone(),
-                        model: config.llm.model.clone(),
-                    },
-                    "openai" => LLMProvider::OpenAI {
-                        api_key: api_key.clone(),
-                        model: config.llm.model.clone(),
-                    },
-                    _ => {
-                        tracing::warn!(
-                            "Unknown LLM provider: {}, using DeepSeek",
-                            config.llm.provider
-                        );
-                        LLMProvider::DeepSeek {
-                            api_key: api_key.clone(),
-                            model: config.llm.model.clone(),
-                        }
-                    }
-                };
-
-                let llm_client = LLMClient::new(provider);
-                detector = detector.with_llm(llm_client);
-                tracing::info!(
+            let provider =
+                LLMProvider::from_config(&config.llm).map_err(HoneyTrapError::config)?;
+            match &provider {
+                LLMProvider::Ollama { base_url, model } => {
+                    tracing::info!("🧠 LLM enabled: local Ollama at {} ({})", base_url, model)
+                }
+                _ => tracing::info!(
                     "🧠 LLM enabled: {} ({})",
                     config.llm.provider,
                     config.llm.model
-                );
-            } else {
-                tracing::warn!("LLM enabled but no API key provided");
+                ),
+            }
+            let mut llm_client =
+                LLMClient::new(provider).with_retry_policy(config.llm.retry.clone());
+            if let Some(template) = &config.llm.prompt_template {
+                llm_client = llm_client
+                    .with_prompt_template(template.as_str())
+                    .map_err(HoneyTrapError::config)?;
+            }
+            if let Some(ttl) = config.llm.cache_ttl() {
+                llm_client = llm_client.with_cache(config.llm.cache_capacity, ttl);
             }
+            detector = detector.with_llm(llm_client);
         }
 
         let ai_engine = Arc::new(RwLock::new(detector));
+        let rl_agent = Arc::new(RwLock::new(load_rl_agent(&config.ai)?));
 
         // Deception System
-        let deception = Arc::new(DeceptionSystem::new());
+        let mut deception = DeceptionSystem::new()
+            .with_block_duration(Duration::from_secs(config.security.block_duration))
+            .with_exit_behavior(config.engagement.on_exit);
+        if let Some(secs) = config.engagement.max_engagement_secs {
+            deception = deception.with_max_engagement(Duration::from_secs(secs));
+        }
+        if let Some(seed) = config.engagement.decoy_seed {
+            deception = deception.with_decoy_seed(seed);
+        }
+        if let Some(seed) = config.engagement.personality_seed {
+            deception = deception.with_personality_seed(seed);
+        }
+        let intel = match &config.intel.db_path {
+            Some(path) => IntelStore::open(path, deception.credential_store())
+                .map_err(|e| HoneyTrapError::Config(format!("intel.db_path {}: {}", path, e)))?,
+            None => IntelStore::new(deception.credential_store()),
+        };
+        let mut intel = intel.with_max_entries(config.intel.max_entries);
+        if let Some(days) = config.intel.retention_days {
+            intel = intel.with_retention(Duration::from_secs(days * 24 * 3600));
+        }
+        deception = deception.with_intel_store(intel);
+        // QUIC-Verbindungen kommen alle auf dem Transport-Port an
+        if let Some(port) = config.network.default_honeypot_port(&config.honeypots) {
+            deception = deception.with_default_port(port);
+        }
+        let deception = Arc::new(deception);
 
         // Deploy configured honeypots
         for honeypot_config in &config.honeypots {
             let hp_config = honeytrap_deception::HoneypotConfig {
                 port: honeypot_config.port,
-                honeypot_type: match honeypot_config.service_type.as_str() {
-                    "ssh" => honeytrap_deception::HoneypotType::Ssh,
-                    "http" => honeytrap_deception::HoneypotType::Http,
-                    "mysql" => honeytrap_deception::HoneypotType::Mysql,
-                    _ => honeytrap_deception::HoneypotType::Ssh,
-                },
+                honeypot_type: honeypot_config.honeypot_type()?,
                 interaction_level: match honeypot_config.interaction_level.as_str() {
                     "low" => honeytrap_deception::InteractionLevel::Low,
                     "medium" => honeytrap_deception::InteractionLevel::Medium,
@@ -96,36 +192,109 @@ impl HoneyTrap {
                     _ => honeytrap_deception::InteractionLevel::Medium,
                 },
             };
-            deception.deploy_honeypot(hp_config).await?;
+            deception
+                .deploy_honeypot(hp_config)
+                .await
+                .map_err(HoneyTrapError::honeypot)?;
         }
 
-        // Transport
-        let transport = Arc::new(SecureQuicTransport::new_server(config.network.bind_addr).await?);
+        // Service-Definitionen ergänzen bzw. überschreiben die Honeypots oben
+        if let Some(dir) = &config.engagement.profile_dir {
+            deception
+                .deploy_from_dir(dir)
+                .await
+                .map_err(HoneyTrapError::honeypot)?;
+        }
 
         // Router
-        let router = Arc::new(Router::new(ai_engine.clone(), deception.clone()));
+        let mut router = Router::new(ai_engine.clone(), deception.clone())
+            .with_session_events(config.events.capacity, config.events.overflow)
+            .with_rl_agent(
+                rl_agent.clone(),
+                RewardCalculator::with_config(config.ai.rl_rewards.clone()),
+            );
+        if let Some(interval) = config.ai.reevaluation_interval() {
+            router = router.with_reevaluation_interval(interval);
+        }
+        let (policy, policy_watcher) = load_policy_engine(&config.policy)?;
+        if let Some(engine) = &policy {
+            router = router.with_policy_engine(engine.clone(), config.policy.block_ips);
+        }
+        if let Some(mirror) = build_traffic_mirror(&config.mirror)? {
+            router = router.with_traffic_mirror(mirror);
+        }
+        if !config.known_scanners.is_empty() {
+            router = router
+                .with_scanner_classifier(
+                    ScannerClassifier::from_config(&config.known_scanners)
+                        .map_err(HoneyTrapError::config)?,
+                );
+        }
+        let router = Arc::new(router);
+
+        let rate_limiter = (config.security.connection_rate_limit > 0.0).then(|| {
+            RateLimiter::new(
+                config.security.connection_rate_limit,
+                config.security.connection_burst,
+            )
+        });
 
         tracing::info!("✅ HoneyTrap initialized successfully");
 
         Ok(Self {
             ai_engine,
+            rl_agent,
             deception,
             transport,
             router,
+            rate_limiter,
+            policy,
+            _policy_watcher: policy_watcher,
+            model_captures,
             config,
         })
     }
 
     /// HoneyTrap starten
-    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+    pub async fn run(&self) -> Result<(), HoneyTrapError> {
         tracing::info!("🚀 Starting HoneyTrap on {}", self.config.network.bind_addr);
 
+        // Lang laufende Sessions periodisch neu bewerten
+        let _reevaluation = self.router.clone().spawn_reevaluation();
+        let _session_reaper = self
+            .config
+            .security
+            .session_idle_timeout()
+            .map(|idle_timeout| self.router.start_session_reaper(idle_timeout));
+        let _rl_persistence = self.spawn_rl_persistence();
+        // Session-Events loggen, falls kein Embedder den Receiver übernommen hat
+        let _event_dispatch = self
+            .router
+            .spawn_event_dispatch(vec![Arc::new(LoggingSink)]);
+        let _capture_pipeline = self.spawn_capture_pipeline(vec![Arc::new(LogSink)]);
+
+        self.spawn_udp_honeypots().await;
+
         loop {
             // Eingehende Verbindung
-            let (connection, peer_addr) = self.transport.accept().await?;
+            let (connection, peer_addr) = self
+                .transport
+                .accept()
+                .await
+                .map_err(HoneyTrapError::transport)?;
 
             tracing::debug!("📥 New connection from {}", peer_addr);
 
+            // Rate Limit vor jeder Honeypot-Logik
+            if let Some(limiter) = &self.rate_limiter {
+                if !limiter.check(peer_addr.ip(), Instant::now()) {
+                    METRICS.connections.rate_limited.inc();
+                    tracing::debug!("🚦 Rate limited connection from {}", peer_addr);
+                    connection.close_with(CloseReason::RateLimited).await;
+                    continue;
+                }
+            }
+
             // Router-Handler
             let router = self.router.clone();
             tokio::spawn(async move {
@@ -136,6 +305,88 @@ impl HoneyTrap {
         }
     }
 
+    /// Capture-Pipeline starten: Transport-Events -> Deduplicator -> Sinks
+    pub fn spawn_capture_pipeline(
+        &self,
+        sinks: Vec<Arc<dyn CaptureSink>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let sink = sinks
+            .into_iter()
+            .fold(CompositeSink::new(), |composite, sink| composite.with_sink(sink));
+        let pipeline = CapturePipeline::new(Arc::new(sink), self.config.capture.dedup_window());
+        let (captures, task) = pipeline
+            .with_capacity(self.config.capture.queue_capacity)
+            .spawn();
+        if self.model_captures.set(captures.clone()).is_err() {
+            tracing::warn!("⚠️ Model disagreements already forwarded to a capture pipeline");
+        }
+        self.transport.forward_captures(captures);
+        task
+    }
+
+    /// RL-Agent periodisch speichern (nur mit Pfad und Intervall)
+    fn spawn_rl_persistence(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let path = self.config.ai.rl_agent_path.clone()?;
+        let interval = self.config.ai.rl_save_interval()?;
+        let rl_agent = self.rl_agent.clone();
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // Erster Tick kommt sofort, der Agent ist gerade erst geladen
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = save_rl_agent(&rl_agent, &path).await {
+                    tracing::error!("Failed to save RL agent to {}: {}", path, e);
+                }
+            }
+        }))
+    }
+
+    /// Geordnet herunterfahren: RL-Agent sichern
+    pub async fn shutdown(&self) -> Result<(), HoneyTrapError> {
+        if let Some(path) = &self.config.ai.rl_agent_path {
+            save_rl_agent(&self.rl_agent, path).await?;
+        }
+        tracing::info!("👋 HoneyTrap shut down");
+        Ok(())
+    }
+
+    /// UDP Honeypots (SNMP) auf eigenen Ports starten
+    async fn spawn_udp_honeypots(&self) {
+        // Ein Gate für alle UDP-Listener: Quellen teilen sich ihr Budget
+        let security = &self.config.security;
+        let limiter = (security.connection_rate_limit > 0.0).then(|| {
+            RateLimiter::new(security.connection_rate_limit, security.connection_burst)
+        });
+        let gate = Arc::new(SourceGate::new(self.deception.clone(), limiter));
+
+        for honeypot in &self.config.honeypots {
+            if honeypot.service_type != "snmp" || !honeypot.auto_deploy {
+                continue;
+            }
+
+            let bind_addr = SocketAddr::new(self.config.network.bind_addr.ip(), honeypot.port);
+            let handler = Arc::new(
+                SnmpHoneypot::new(honeypot.port)
+                    .with_credential_store(self.deception.credential_store()),
+            );
+            let listener = match UdpListener::bind(bind_addr, handler).await {
+                Ok(listener) => listener.with_gate(gate.clone()),
+                Err(e) => {
+                    tracing::error!("Failed to bind SNMP honeypot on udp/{}: {}", bind_addr, e);
+                    continue;
+                }
+            };
+
+            tokio::spawn(async move {
+                if let Err(e) = listener.run().await {
+                    tracing::error!("UDP listener error: {}", e);
+                }
+            });
+        }
+    }
+
     /// Statistiken abrufen
     pub async fn stats(&self) -> HoneyTrapStats {
         let ai = self.ai_engine.read().await;
@@ -147,15 +398,443 @@ impl HoneyTrap {
             active_honeypots: deception.active_sessions(),
             blocked_ips: deception.blocked_count(),
             model_accuracy: ai.model_accuracy(),
+            anomaly_threshold: ai.anomaly_threshold(),
+            exfil_bytes: self.router.exfil_bytes(),
+        }
+    }
+
+    /// Anomalie-Schwellwert im laufenden Betrieb ändern (0.0..=1.0)
+    pub async fn set_anomaly_threshold(&self, threshold: f64) -> Result<(), HoneyTrapError> {
+        self.ai_engine
+            .write()
+            .await
+            .set_threshold(threshold)
+            .map_err(HoneyTrapError::ai)
+    }
+}
+
+/// RL-Agent vom konfigurierten Pfad laden (neuer Agent, wenn noch keiner gespeichert ist)
+fn load_rl_agent(config: &AIConfig) -> Result<RLAgent, HoneyTrapError> {
+    let Some(path) = &config.rl_agent_path else {
+        return Ok(RLAgent::new());
+    };
+    if !Path::new(path).exists() {
+        tracing::info!("🤖 No RL agent at {}, starting untrained", path);
+        return Ok(RLAgent::new());
+    }
+
+    let agent = RLAgent::load(path).map_err(HoneyTrapError::ai)?;
+    let stats = agent.get_stats();
+    tracing::info!(
+        "🤖 RL agent loaded from {} ({} episodes, {} states)",
+        path,
+        stats.episodes_trained,
+        stats.states_explored
+    );
+    Ok(agent)
+}
+
+/// Policy Engine aus den konfigurierten Dateien laden und ggf. überwachen
+fn load_policy_engine(
+    config: &PolicyConfig,
+) -> Result<(Option<Arc<PolicyEngine>>, Option<PolicyWatcher>), HoneyTrapError> {
+    if config.files.is_empty() {
+        return Ok((None, None));
+    }
+
+    // Kein Geo-Enricher: `country_in` würde nie matchen und wird abgewiesen
+    let engine = PolicyEngine::new().with_country_lookup(false);
+    engine.load_policies(&config.files)?;
+    let watcher = if config.hot_reload {
+        Some(engine.watch(config.files.clone())?)
+    } else {
+        None
+    };
+
+    Ok((Some(Arc::new(engine)), watcher))
+}
+
+/// Traffic-Mirror für den konfigurierten Sink (`None` = kein Mirroring)
+fn build_traffic_mirror(config: &MirrorConfig) -> Result<Option<TrafficMirror>, HoneyTrapError> {
+    let sink: Arc<dyn MirrorSink> = if let Some(path) = &config.file {
+        let sink = FileSink::open(path)
+            .map_err(|e| HoneyTrapError::Config(format!("mirror.file {}: {}", path, e)))?;
+        tracing::info!("🪞 Mirroring session traffic to {}", path);
+        Arc::new(sink)
+    } else if let Some(path) = &config.unix_socket {
+        #[cfg(unix)]
+        {
+            let sink = honeytrap_protocol::UnixSocketSink::connect(path).map_err(|e| {
+                HoneyTrapError::Config(format!("mirror.unix_socket {}: {}", path, e))
+            })?;
+            tracing::info!("🪞 Mirroring session traffic to socket {}", path);
+            Arc::new(sink)
         }
+        #[cfg(not(unix))]
+        return Err(HoneyTrapError::Config(format!(
+            "mirror.unix_socket {}: unix sockets not supported on this platform",
+            path
+        )));
+    } else {
+        return Ok(None);
+    };
+
+    TrafficMirror::with_capacity(sink, config.queue_capacity)
+        .map(Some)
+        .map_err(HoneyTrapError::transport)
+}
+
+/// ML/LLM-Uneinigkeiten als Capture-Events weiterreichen, sobald eine Pipeline läuft
+fn capture_disagreements(captures: Arc<OnceLock<CaptureSender>>) -> DisagreementHook {
+    Arc::new(move |disagreement: &Disagreement| {
+        let Some(captures) = captures.get() else {
+            return;
+        };
+        // `SessionData` kennt keine Session-ID
+        let mut event = CaptureEvent::new(
+            "",
+            CaptureKind::ModelDisagreement {
+                destination_port: disagreement.destination_port,
+                malicious_by: disagreement.malicious_by.to_string(),
+                attack_type: disagreement.attack_type.clone(),
+            },
+        );
+        if let Ok(ip) = disagreement.source_ip.parse() {
+            event = event.with_source_ip(ip);
+        }
+        captures.send(event);
+    })
+}
+
+/// Aktuellen Stand des RL-Agents speichern
+async fn save_rl_agent(rl_agent: &RwLock<RLAgent>, path: &str) -> Result<(), HoneyTrapError> {
+    if let Some(dir) = Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).map_err(HoneyTrapError::ai)?;
     }
+
+    let agent = rl_agent.read().await;
+    agent.save(path).map_err(HoneyTrapError::ai)?;
+    tracing::debug!(
+        "💾 RL agent saved to {} ({} episodes)",
+        path,
+        agent.get_stats().episodes_trained
+    );
+    Ok(())
 }
 
-#[derive(Debug, Clone)]
+/// Laufzeit-Statistiken (`/stats` Endpoint, `honeytrap stats`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HoneyTrapStats {
     pub total_connections: u64,
     pub anomalies_detected: u64,
     pub active_honeypots: usize,
     pub blocked_ips: usize,
     pub model_accuracy: f64,
+    /// Fehlt bei älteren Servern
+    #[serde(default)]
+    pub anomaly_threshold: f64,
+    /// Aus Honeypots exfiltrierte Bytes (fehlt bei älteren Servern)
+    #[serde(default)]
+    pub exfil_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_ai::{Action, State};
+    use honeytrap_protocol::MemoryNetwork;
+
+    #[tokio::test]
+    async fn test_rl_agent_loaded_at_startup_and_saved_on_shutdown() {
+        let path = std::env::temp_dir().join(format!("honeytrap-rl-{}.json", uuid::Uuid::new_v4()));
+        let state = State {
+            attack_type: 8,
+            connection_intensity: 1,
+            source_reputation: 5,
+            behavior: 0,
+        };
+        let mut trained = RLAgent::new();
+        trained.update(&state, &Action::DeepEngagement, 10.0, &state);
+        trained.finish_episode();
+        trained.save(path.to_str().unwrap()).unwrap();
+
+        let mut config = Config::default();
+        config.honeypots.clear();
+        config.ai.rl_agent_path = Some(path.to_string_lossy().into_owned());
+        let transport = MemoryNetwork::new()
+            .bind("127.0.0.1:4433".parse().unwrap())
+            .unwrap();
+        let honeytrap = HoneyTrap::with_transport(config, Arc::new(transport))
+            .await
+            .unwrap();
+
+        {
+            let mut agent = honeytrap.rl_agent.write().await;
+            assert_eq!(agent.get_stats().episodes_trained, 1);
+            assert_eq!(agent.get_best_action(&state), Action::DeepEngagement);
+            agent.finish_episode();
+        }
+        honeytrap.shutdown().await.unwrap();
+
+        let saved = RLAgent::load(path.to_str().unwrap()).unwrap();
+        assert_eq!(saved.get_stats().episodes_trained, 2);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_profile_dir_deployed_at_startup() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-startup-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("redis.toml"),
+            "port = 6380\nservice_type = \"redis\"\nbanner = \"redis_version:7.2.4\"\n",
+        )
+        .unwrap();
+
+        let config = Config::builder()
+            .add_honeypot(2222, "ssh", "medium")
+            .profile_dir(dir.to_str().unwrap())
+            .anomaly_threshold(0.95)
+            .build()
+            .unwrap();
+        let transport = MemoryNetwork::new()
+            .bind("127.0.0.1:4433".parse().unwrap())
+            .unwrap();
+        let honeytrap = HoneyTrap::with_transport(config, Arc::new(transport))
+            .await
+            .unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let deception = &honeytrap.deception;
+        assert_eq!(
+            deception.honeypot_type(6380).await,
+            Some(honeytrap_deception::HoneypotType::Redis)
+        );
+        assert_eq!(
+            deception.honeypot_type(2222).await,
+            Some(honeytrap_deception::HoneypotType::Ssh)
+        );
+        assert!(deception.service_definition(6380).await.is_some());
+        assert_eq!(honeytrap.stats().await.anomaly_threshold, 0.95);
+    }
+
+    #[tokio::test]
+    async fn test_policy_files_enforced_and_reloaded() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-policy-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("policies.toml");
+        let policy = |action: &str| {
+            format!(
+                "[[policies]]\nname = \"lab\"\naction = \"{}\"\n\
+                 [[policies.conditions.all]]\nsrc_ip_in_cidr = [\"192.0.2.0/24\"]\n",
+                action
+            )
+        };
+        std::fs::write(&path, policy("block")).unwrap();
+
+        let mut config = Config::builder()
+            .add_honeypot(2222, "ssh", "medium")
+            .policy_file(path.to_str().unwrap())
+            .build()
+            .unwrap();
+        config.policy.block_ips = true;
+        let transport = MemoryNetwork::new()
+            .bind("127.0.0.1:4433".parse().unwrap())
+            .unwrap();
+        let honeytrap = HoneyTrap::with_transport(config, Arc::new(transport))
+            .await
+            .unwrap();
+        let engine = honeytrap.policy.clone().expect("policy engine not built");
+        assert_eq!(engine.policies().len(), 1);
+
+        // BLOCK aus der Datei greift im Router
+        let connection = honeytrap_deception::Connection::new("192.0.2.10:40000".parse().unwrap())
+            .with_local_addr("10.0.0.1:2222".parse().unwrap());
+        honeytrap.router.handle_connection(connection).await.unwrap();
+        assert!(honeytrap.deception.is_blocked("192.0.2.10".parse().unwrap()).await);
+
+        // Watcher lädt Änderungen ohne Neustart
+        std::fs::write(&path, policy("deception")).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while engine.reload_count() == 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("policy file never reloaded");
+        assert_eq!(engine.policies()[0].action, honeytrap_policy::Decision::Deception);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_intel_db_path_survives_restart() {
+        use honeytrap_deception::IntelQuery;
+
+        let path = std::env::temp_dir()
+            .join(format!("honeytrap-intel-{}.db", uuid::Uuid::new_v4()));
+        let config = Config::builder()
+            .add_honeypot(2222, "ssh", "medium")
+            .intel_db_path(path.to_str().unwrap())
+            .build()
+            .unwrap();
+        let attacker: std::net::IpAddr = "203.0.113.90".parse().unwrap();
+
+        for _ in 0..2 {
+            let transport = MemoryNetwork::new()
+                .bind("127.0.0.1:4433".parse().unwrap())
+                .unwrap();
+            let honeytrap = HoneyTrap::with_transport(config.clone(), Arc::new(transport))
+                .await
+                .unwrap();
+            honeytrap.deception.intel_store().record_command(attacker, "uname -a");
+        }
+
+        let report = IntelStore::open(&path, Default::default())
+            .unwrap()
+            .query(&IntelQuery::ip(attacker));
+        assert_eq!(report.commands, ["uname -a", "uname -a"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_quic_session_reaches_default_honeypot() {
+        use honeytrap_deception::IntelQuery;
+
+        let mut config = Config::default();
+        config.honeypots = vec![config::HoneypotConfig {
+            port: 3306,
+            service_type: "mysql".to_string(),
+            interaction_level: "low".to_string(),
+            auto_deploy: true,
+        }];
+        let transport = SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let server_addr = transport.local_addr();
+        let honeytrap = HoneyTrap::with_transport(config, Arc::new(transport))
+            .await
+            .unwrap();
+
+        // Jede Verbindung ist eine Anomalie, der Agent engagiert ohne Exploration
+        *honeytrap.ai_engine.write().await = AnomalyDetector::new(10).with_threshold(-1.0);
+        *honeytrap.rl_agent.write().await = RLAgent::with_config(honeytrap_ai::RLConfig {
+            epsilon: 0.0,
+            epsilon_min: 0.0,
+            ..Default::default()
+        });
+        let honeytrap = Arc::new(honeytrap);
+        let server = tokio::spawn({
+            let honeytrap = honeytrap.clone();
+            async move { honeytrap.run().await }
+        });
+
+        let client = SecureQuicTransport::new_client().await.unwrap();
+        let _connection = client.connect(server_addr, "localhost").await.unwrap();
+
+        let query = IntelQuery::ip("127.0.0.1".parse().unwrap());
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while honeytrap.deception.query_intel(&query).total_sessions == 0 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await
+        .expect("QUIC session never reached a honeypot");
+        assert!(honeytrap.deception.query_intel(&query).services.contains("MySQL"));
+        server.abort();
+    }
+
+    #[tokio::test]
+    async fn test_handshake_failures_deduplicated_before_sinks() {
+        use honeytrap_deception::InMemorySink;
+
+        let config = Config::builder()
+            .add_honeypot(2222, "ssh", "medium")
+            .capture_dedup_window(Duration::from_secs(1))
+            .build()
+            .unwrap();
+        let transport = Arc::new(
+            SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+                .await
+                .unwrap(),
+        );
+        let honeytrap = HoneyTrap::with_transport(config, transport.clone())
+            .await
+            .unwrap();
+        let sink = Arc::new(InMemorySink::new());
+        let _pipeline = honeytrap.spawn_capture_pipeline(vec![sink.clone()]);
+
+        for port in 40000..40010 {
+            transport
+                .handshakes()
+                .record(([198, 51, 100, 20], port).into(), "timed_out", "probe");
+        }
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while sink.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("capture pipeline never flushed");
+        let events = sink.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].count, 10);
+    }
+
+    #[tokio::test]
+    async fn test_model_disagreements_reach_capture_sinks() {
+        use honeytrap_ai::SessionData;
+        use honeytrap_deception::InMemorySink;
+
+        let config = Config::builder()
+            .add_honeypot(2222, "ssh", "medium")
+            .capture_dedup_window(Duration::from_secs(1))
+            .build()
+            .unwrap();
+        let transport = SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let honeytrap = HoneyTrap::with_transport(config, Arc::new(transport))
+            .await
+            .unwrap();
+        let sink = Arc::new(InMemorySink::new());
+        let _pipeline = honeytrap.spawn_capture_pipeline(vec![sink.clone()]);
+
+        // Deaktivierter Provider urteilt immer harmlos, der Ausreißer ist eine Anomalie
+        *honeytrap.ai_engine.write().await = AnomalyDetector::new(10)
+            .with_threshold(0.5)
+            .with_llm(LLMClient::new(LLMProvider::Disabled))
+            .with_disagreement_hook(capture_disagreements(honeytrap.model_captures.clone()));
+        let ai = honeytrap.ai_engine.read().await;
+        for _ in 0..3 {
+            ai.analyze(&[1.0, 1.0]).await.unwrap();
+        }
+        let session = SessionData {
+            source_ip: "198.51.100.30".to_string(),
+            destination_port: 2222,
+            duration_secs: 1.0,
+            bytes_sent: 0,
+            bytes_received: 0,
+            failed_login_attempts: 0,
+            commands: vec![],
+            user_agent: None,
+            request_pattern: None,
+        };
+        ai.analyze_with_llm(&[500.0, 500.0], session).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while sink.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("disagreement never reached the sink");
+        let events = sink.events();
+        assert_eq!(events[0].source_ip, Some("198.51.100.30".parse().unwrap()));
+        assert!(matches!(
+            &events[0].kind,
+            CaptureKind::ModelDisagreement { destination_port: 2222, malicious_by, .. }
+                if malicious_by == "ml"
+        ));
+    }
 }
@@ -0,0 +1,183 @@
+//! Per-IP Connection Rate Limiting (Token Bucket)
+//!
+//! Greift im Accept-Loop vor jeder Honeypot-Logik und schützt das System
+//! vor einzelnen Quellen mit tausenden Verbindungen pro Sekunde.
+//! `ConnectionRate` misst die jüngste Verbindungsrate einer Quelle für den RL State.
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Ab dieser Anzahl Buckets werden volle (inaktive) Einträge entfernt
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Token Bucket einer Quelle
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// Token-Bucket Rate Limiter pro Quell-IP
+#[derive(Debug)]
+pub struct RateLimiter {
+    /// Nachfüllrate (Verbindungen pro Sekunde)
+    rate: f64,
+    /// Max. Burst
+    burst: f64,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Neuer Limiter mit Rate (pro Sekunde) und Burst
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: f64::from(burst.max(1)),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Verbindung erlauben? Verbraucht bei Erfolg ein Token.
+    pub fn check(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets.len() >= PRUNE_THRESHOLD {
+            let (rate, burst) = (self.rate, self.burst);
+            buckets.retain(|_, bucket| refill(bucket, now, rate, burst) < burst);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(TokenBucket {
+            tokens: self.burst,
+            updated_at: now,
+        });
+
+        if refill(bucket, now, self.rate, self.burst) >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Anzahl getrackter Quellen
+    pub fn tracked_sources(&self) -> usize {
+        self.buckets.lock().unwrap().len()
+    }
+}
+
+/// Zerfallender Verbindungszähler einer Quelle
+#[derive(Debug, Clone, Copy)]
+struct RecentCount {
+    count: f64,
+    updated_at: Instant,
+}
+
+/// Jüngste Verbindungen pro Quell-IP, exponentiell über `window` zerfallend
+#[derive(Debug)]
+pub struct ConnectionRate {
+    window: Duration,
+    sources: Mutex<HashMap<IpAddr, RecentCount>>,
+}
+
+impl ConnectionRate {
+    /// Neuer Zähler, Verbindungen verblassen mit Zeitkonstante `window`
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            sources: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Verbindung verbuchen, liefert die aktuelle Rate der Quelle
+    pub fn record(&self, ip: IpAddr, now: Instant) -> f64 {
+        let mut sources = self.sources.lock().unwrap();
+
+        if sources.len() >= PRUNE_THRESHOLD {
+            let window = self.window;
+            sources.retain(|_, recent| decay(recent, now, window) >= 0.01);
+        }
+
+        let recent = sources.entry(ip).or_insert(RecentCount {
+            count: 0.0,
+            updated_at: now,
+        });
+        decay(recent, now, self.window);
+        recent.count += 1.0;
+        recent.count
+    }
+
+    /// Aktuelle Rate der Quelle (≈ Verbindungen innerhalb von `window`)
+    pub fn rate(&self, ip: IpAddr, now: Instant) -> f64 {
+        let mut sources = self.sources.lock().unwrap();
+        sources
+            .get_mut(&ip)
+            .map_or(0.0, |recent| decay(recent, now, self.window))
+    }
+}
+
+/// Zähler auf `now` zerfallen lassen, liefert den neuen Stand
+fn decay(recent: &mut RecentCount, now: Instant, window: Duration) -> f64 {
+    let elapsed = now.saturating_duration_since(recent.updated_at);
+    recent.count = if window.is_zero() {
+        0.0
+    } else {
+        recent.count * (-elapsed.as_secs_f64() / window.as_secs_f64()).exp()
+    };
+    recent.updated_at = recent.updated_at.max(now);
+    recent.count
+}
+
+/// Bucket auf `now` nachfüllen, liefert den neuen Token-Stand
+fn refill(bucket: &mut TokenBucket, now: Instant, rate: f64, burst: f64) -> f64 {
+    let elapsed = now.saturating_duration_since(bucket.updated_at).as_secs_f64();
+    bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+    bucket.updated_at = bucket.updated_at.max(now);
+    bucket.tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burst_rejected_per_source_ip() {
+        let limiter = RateLimiter::new(2.0, 3);
+        let attacker: IpAddr = "198.51.100.66".parse().unwrap();
+        let other: IpAddr = "192.0.2.10".parse().unwrap();
+        let now = Instant::now();
+
+        let allowed = (0..10).filter(|_| limiter.check(attacker, now)).count();
+        assert_eq!(allowed, 3);
+
+        // Andere Quelle unbeeinflusst
+        assert!(limiter.check(other, now));
+
+        // Nach 1s sind 2 Tokens nachgefüllt
+        let later = now + Duration::from_secs(1);
+        assert!(limiter.check(attacker, later));
+        assert!(limiter.check(attacker, later));
+        assert!(!limiter.check(attacker, later));
+        assert_eq!(limiter.tracked_sources(), 2);
+    }
+
+    #[test]
+    fn test_connection_rate_is_per_source_and_decays() {
+        let rates = ConnectionRate::new(Duration::from_secs(60));
+        let attacker: IpAddr = "198.51.100.67".parse().unwrap();
+        let other: IpAddr = "192.0.2.11".parse().unwrap();
+        let now = Instant::now();
+
+        for _ in 0..5 {
+            rates.record(attacker, now);
+        }
+        assert_eq!(rates.record(other, now), 1.0);
+        assert_eq!(rates.rate(attacker, now), 5.0);
+
+        // Nach einer Zeitkonstante ist noch ~1/e übrig
+        let later = now + Duration::from_secs(60);
+        assert!((rates.rate(attacker, later) - 5.0 / std::f64::consts::E).abs() < 1e-9);
+        assert_eq!(rates.rate("203.0.113.1".parse().unwrap(), later), 0.0);
+    }
+}
@@ -1,33 +1,135 @@
+use crate::decision::Decision;
+use crate::events::{EventOverflow, SessionEventReceiver, SessionEventSink};
+use crate::rate_limit::ConnectionRate;
+use crate::scanners::ScannerClassifier;
 use crate::session::{Session, SessionManager};
-use honeytrap_ai::AnomalyDetector;
-use honeytrap_deception::{Connection, DeceptionSystem};
+use honeytrap_ai::features::DEFAULT_DESTINATION_PORT;
+use honeytrap_ai::{
+    Action, AnomalyDetector, ConnectionStats, FeatureExtractor, RLAgent, ReputationTracker,
+    RewardCalculator, State,
+};
+use honeytrap_deception::{CloseReason, Connection, DeceptionSystem, IntelQuery, ResponseStrategy};
+use honeytrap_metrics::METRICS;
+use honeytrap_policy::{Decision as PolicyDecision, EvaluationContext, PolicyEngine};
+use honeytrap_protocol::TrafficMirror;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 
+/// Zusätzliche Q-Updates aus dem Replay Buffer pro beendeter Session
+const RL_REPLAY_BATCH: usize = 32;
+
+/// Reputationsverlust einer Quelle pro Anomalie (skaliert mit dem Score)
+const REPUTATION_PENALTY: f64 = 2.0;
+
+/// Zeitfenster der Verbindungsrate pro Quelle (RL-Intensität)
+const CONNECTION_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Engagement-Strategie der Honeypots für eine RL-Aktion
+fn response_strategy(action: Option<Action>) -> ResponseStrategy {
+    match action {
+        Some(Action::MinimalResponse) => ResponseStrategy::Minimal,
+        Some(Action::DeepEngagement) => ResponseStrategy::Deep,
+        _ => ResponseStrategy::Standard,
+    }
+}
+
 /// Router - Leitet Traffic basierend auf AI-Analyse
 pub struct Router {
     ai_engine: Arc<RwLock<AnomalyDetector>>,
     deception: Arc<DeceptionSystem>,
     session_manager: Arc<SessionManager>,
+    /// Session-Events, bis ein Consumer sie übernimmt
+    event_rx: std::sync::Mutex<Option<SessionEventReceiver>>,
     total_connections: AtomicU64,
     anomalies_detected: AtomicU64,
+    /// Laufende Sessions periodisch neu bewerten (`None` = nur beim Verbindungsaufbau)
+    reevaluation_interval: Option<Duration>,
+    /// Erkennung bekannter Research-Scanner
+    scanners: ScannerClassifier,
+    research_scans: AtomicU64,
+    /// Exfiltrierte Bytes aller Honeypot-Sessions
+    exfil_bytes: AtomicU64,
+    /// RL-Agent + Reward-Gewichtung (`None` = kein Online-Learning)
+    rl: Option<(Arc<RwLock<RLAgent>>, RewardCalculator)>,
+    /// Reputation der Quell-IPs (fließt in den RL State ein)
+    reputation: std::sync::Mutex<ReputationTracker>,
+    /// Jüngste Verbindungen pro Quell-IP (Intensität im RL State)
+    connection_rates: ConnectionRate,
+    /// Policy Engine + ob BLOCK die IP auch sperrt
+    policy: Option<(Arc<PolicyEngine>, bool)>,
+    /// Eingehende Bytes der Honeypot-Sessions spiegeln
+    mirror: Option<TrafficMirror>,
 }
 
 impl Router {
     /// Neuer Router
     pub fn new(ai_engine: Arc<RwLock<AnomalyDetector>>, deception: Arc<DeceptionSystem>) -> Self {
-        let (session_manager, _event_rx) = SessionManager::new();
+        let (session_manager, event_rx) = SessionManager::new();
 
         Self {
             ai_engine,
             deception,
             session_manager: Arc::new(session_manager),
+            event_rx: std::sync::Mutex::new(Some(event_rx)),
             total_connections: AtomicU64::new(0),
             anomalies_detected: AtomicU64::new(0),
+            reevaluation_interval: None,
+            scanners: ScannerClassifier::new(),
+            research_scans: AtomicU64::new(0),
+            exfil_bytes: AtomicU64::new(0),
+            rl: None,
+            reputation: std::sync::Mutex::new(ReputationTracker::new()),
+            connection_rates: ConnectionRate::new(CONNECTION_RATE_WINDOW),
+            policy: None,
+            mirror: None,
         }
     }
 
+    /// Mit eigener Kapazität und Overflow-Verhalten der Session-Event-Queue
+    pub fn with_session_events(mut self, capacity: usize, overflow: EventOverflow) -> Self {
+        let (session_manager, event_rx) = SessionManager::with_event_capacity(capacity, overflow);
+        self.session_manager = Arc::new(session_manager);
+        self.event_rx = std::sync::Mutex::new(Some(event_rx));
+        self
+    }
+
+    /// Mit Policy Engine - eine passende Policy hat Vorrang vor dem AI-Routing
+    ///
+    /// `block_ips`: bei BLOCK die Quell-IP zusätzlich für die Blockdauer sperren.
+    pub fn with_policy_engine(mut self, engine: Arc<PolicyEngine>, block_ips: bool) -> Self {
+        self.policy = Some((engine, block_ips));
+        self
+    }
+
+    /// Mit Traffic-Mirror für alle an Honeypots umgeleiteten Sessions
+    pub fn with_traffic_mirror(mut self, mirror: TrafficMirror) -> Self {
+        self.mirror = Some(mirror);
+        self
+    }
+
+    /// Mit Research-Scanner Erkennung
+    pub fn with_scanner_classifier(mut self, scanners: ScannerClassifier) -> Self {
+        self.scanners = scanners;
+        self
+    }
+
+    /// Mit RL-Agent, der pro Honeypot-Session eine Aktion wählt und aus dem Ergebnis lernt
+    pub fn with_rl_agent(mut self, agent: Arc<RwLock<RLAgent>>, rewards: RewardCalculator) -> Self {
+        self.rl = Some((agent, rewards));
+        self
+    }
+
+    /// Mit periodischer Neubewertung laufender Sessions
+    ///
+    /// Unauffällige Sessions bleiben dann offen, solange der Peer sendet, und
+    /// werden bei einer späteren Anomalie an einen Honeypot übergeben.
+    pub fn with_reevaluation_interval(mut self, interval: Duration) -> Self {
+        self.reevaluation_interval = Some(interval);
+        self
+    }
+
     /// Verbindung verarbeiten
     pub async fn handle_connection(
         &self,
@@ -37,7 +139,9 @@ impl Router {
         self.total_connections.fetch_add(1, Ordering::SeqCst);
 
         // Session erstellen
-        let mut session = self.session_manager.register(connection.peer_addr).await;
+        let mut session = self.session_manager.register_connection(&connection).await;
+        self.connection_rates
+            .record(session.peer_addr.ip(), Instant::now());
 
         tracing::debug!(
             "📊 Session {} created for {}",
@@ -45,17 +149,50 @@ impl Router {
             session.peer_addr
         );
 
+        // Gesperrte IPs sofort abweisen
+        if self.deception.is_blocked(session.peer_addr.ip()).await {
+            tracing::debug!(
+                "🚫 Rejecting blocked IP {} (Session: {})",
+                session.peer_addr,
+                session.id
+            );
+            connection.close_with(CloseReason::Blocked).await;
+            self.session_manager.close(&session.id).await;
+            return Ok(());
+        }
+
+        // Research-Scanner nur minimal bedienen (keine Angreifer-Intel)
+        if let Some(scanner) = self.scanners.classify(session.peer_addr.ip()) {
+            let scanner = scanner.to_string();
+            return self.handle_research_scan(connection, session, scanner).await;
+        }
+
         // Features extrahieren
-        let features = self.extract_features(&connection, &session).await;
+        let features = self.extract_features(&session).await;
 
         // AI-Analyse
-        let mut ai = self.ai_engine.write().await;
+        let ai = self.ai_engine.read().await;
         let (is_anomaly, score) = ai.analyze(&features).await?;
+        let threshold = ai.anomaly_threshold();
         drop(ai);
 
+        session.record_decision(Decision::AnomalyScore {
+            score,
+            threshold,
+            is_anomaly,
+        });
+        session.mark_evaluated();
+
+        let policy = self.evaluate_policy(&connection, &mut session, score).await;
+
         if is_anomaly {
             self.anomalies_detected.fetch_add(1, Ordering::SeqCst);
             session.mark_suspicious(score);
+            self.reputation.lock().unwrap().record(
+                session.peer_addr.ip(),
+                -REPUTATION_PENALTY * score.clamp(0.0, 1.0),
+                Instant::now(),
+            );
 
             tracing::warn!(
                 "🚨 Anomaly detected! Session {} from {} (score: {:.2})",
@@ -63,42 +200,282 @@ impl Router {
                 session.peer_addr,
                 score
             );
+        }
 
-            // Zu Honeypot umleiten
-            self.redirect_to_honeypot(connection, session).await?;
-        } else {
-            tracing::debug!(
-                "✅ Normal traffic from {} (score: {:.2})",
+        match (policy, is_anomaly) {
+            (Some(PolicyDecision::Block), _) => {
+                let block_ip = matches!(self.policy, Some((_, true)));
+                self.block_connection(connection, session, "policy", block_ip)
+                    .await?
+            }
+            (Some(PolicyDecision::Deception), _) | (None, true) => {
+                // Zu Honeypot umleiten - Engagement-Tiefe wählt der RL-Agent
+                self.engage(connection, session, policy.is_some()).await?
+            }
+            (Some(PolicyDecision::Allow), _) | (None, false) => {
+                tracing::debug!(
+                    "✅ Normal traffic from {} (score: {:.2})",
+                    session.peer_addr,
+                    score
+                );
+
+                // Normale Weiterleitung (TODO: Backend), nur die AI-Einstufung
+                // wird bei laufender Neubewertung revidiert
+                let watch = policy.is_none();
+                self.forward_to_backend(connection, session, watch).await?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Passende Policy für die Verbindung (`None` = AI-Routing)
+    async fn evaluate_policy(
+        &self,
+        connection: &Connection,
+        session: &mut Session,
+        score: f64,
+    ) -> Option<PolicyDecision> {
+        let (engine, _) = self.policy.as_ref()?;
+
+        let protocol = match connection.dest_port() {
+            Some(port) => self.deception.honeypot_type(port).await,
+            None => None,
+        };
+        let context = EvaluationContext {
+            src_ip: session.peer_addr.ip().to_string(),
+            protocol: protocol.map(|t| t.to_string()).unwrap_or_default(),
+            risk_score: score.clamp(0.0, 1.0),
+            san: connection.client_sans.clone(),
+            mtls_verified: connection.mtls_verified,
+            // Kein Geo-Enricher - `country_in` wird beim Laden abgewiesen
+            ..EvaluationContext::default()
+        };
+
+        let policy = engine.matching_policy(&context)?;
+        tracing::info!(
+            "📜 Policy '{}' matched -> {} (Session: {})",
+            policy.name,
+            policy.action,
+            session.id
+        );
+        session.record_decision(Decision::PolicyMatch {
+            policy: policy.name,
+            action: policy.action.to_string().to_lowercase(),
+        });
+        Some(policy.action)
+    }
+
+    /// Verbindung abweisen (optional IP sperren)
+    async fn block_connection(
+        &self,
+        connection: Connection,
+        mut session: Session,
+        reason: &str,
+        block_ip: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let ip = session.peer_addr.ip();
+        if block_ip {
+            self.deception.block_ip(ip).await;
+        }
+
+        session.record_decision(Decision::Block {
+            ip,
+            reason: reason.to_string(),
+        });
+        METRICS
+            .connections
+            .by_result
+            .with_label_values(&["blocked"])
+            .inc();
+        tracing::debug!("{}", session.summary());
+
+        connection.close_with(CloseReason::Blocked).await;
+        self.session_manager.close(&session.id).await;
+
+        Ok(())
+    }
+
+    /// Laufende Session mit aktuellen Features neu bewerten
+    ///
+    /// Liefert `true`, wenn die Session dabei erstmals als Anomalie eingestuft wurde.
+    pub async fn reevaluate(&self, session_id: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let Some(mut session) = self.session_manager.get(session_id).await else {
+            return Ok(false);
+        };
+
+        let features = self.extract_features(&session).await;
+
+        let ai = self.ai_engine.read().await;
+        let (is_anomaly, score) = ai.analyze(&features).await?;
+        let threshold = ai.anomaly_threshold();
+        drop(ai);
+
+        session.record_decision(Decision::AnomalyScore {
+            score,
+            threshold,
+            is_anomaly,
+        });
+        session.mark_evaluated();
+
+        let reclassified = is_anomaly && !session.is_suspicious;
+        self.session_manager.update(session.clone()).await;
+
+        if reclassified {
+            self.anomalies_detected.fetch_add(1, Ordering::SeqCst);
+            self.session_manager.mark_suspicious(&session.id, score).await;
+
+            tracing::warn!(
+                "🚨 Session re-classified as anomalous after {:.1}s from {} (score: {:.2}, Session: {})",
+                session.duration().as_secs_f64(),
                 session.peer_addr,
-                score
+                score,
+                session.id
             );
+        }
+
+        Ok(reclassified)
+    }
+
+    /// Alle fälligen, noch unauffälligen Sessions neu bewerten
+    ///
+    /// Liefert die Anzahl neu als Anomalie eingestufter Sessions.
+    pub async fn reevaluate_sessions(&self) -> usize {
+        let Some(interval) = self.reevaluation_interval else {
+            return 0;
+        };
 
-            // Normale Weiterleitung (TODO: Backend)
-            self.forward_to_backend(connection, session).await?;
+        let mut reclassified = 0;
+        for session in self.session_manager.active_sessions().await {
+            if session.is_suspicious || session.elapsed_since_evaluation() < interval {
+                continue;
+            }
+
+            match self.reevaluate(&session.id).await {
+                Ok(true) => reclassified += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Re-evaluation failed: {} (Session: {})", e, session.id),
+            }
         }
 
-        Ok(())
+        reclassified
+    }
+
+    /// Hintergrund-Task, der Sessions ohne Traffic nach `idle_timeout` schließt
+    pub fn start_session_reaper(&self, idle_timeout: Duration) -> tokio::task::JoinHandle<()> {
+        self.session_manager.start_reaper(idle_timeout)
+    }
+
+    /// Receiver der Session-Events übernehmen (nur einmal möglich)
+    pub fn take_event_receiver(&self) -> Option<SessionEventReceiver> {
+        self.event_rx.lock().unwrap().take()
+    }
+
+    /// Session-Events in einem Hintergrund-Task an die Sinks verteilen
+    ///
+    /// `None`, wenn der Receiver bereits übernommen wurde.
+    pub fn spawn_event_dispatch(
+        &self,
+        sinks: Vec<Arc<dyn SessionEventSink>>,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        Some(self.take_event_receiver()?.spawn_dispatch(sinks))
+    }
+
+    /// Hintergrund-Task für die periodische Neubewertung starten
+    pub fn spawn_reevaluation(self: Arc<Self>) -> Option<tokio::task::JoinHandle<()>> {
+        let interval = self.reevaluation_interval?;
+
+        Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.reevaluate_sessions().await;
+            }
+        }))
     }
 
-    /// Features aus Connection extrahieren
-    async fn extract_features(&self, connection: &Connection, session: &Session) -> Vec<f64> {
-        // TODO: Echte Feature-Extraktion
-        // Für jetzt: Dummy-Features
-        vec![
-            connection.peer_addr.port() as f64,
-            session.duration().as_secs_f64(),
-            session.bytes_sent as f64,
-            session.bytes_received as f64,
-        ]
+    /// Features aus der Session extrahieren
+    ///
+    /// Liefert immer das vollständige Layout von `NetworkFeatures::as_vector`,
+    /// damit Training und Analyse dieselbe Feature-Reihenfolge verwenden.
+    async fn extract_features(&self, session: &Session) -> Vec<f64> {
+        let traffic = session.traffic.stats();
+        let stats = ConnectionStats {
+            destination_port: session.dest_port.unwrap_or(DEFAULT_DESTINATION_PORT),
+            duration: session.duration(),
+            bytes_sent: traffic.bytes_sent,
+            bytes_received: traffic.bytes_received,
+            packets_sent: traffic.packets_sent,
+            packets_received: traffic.packets_received,
+            inter_packet_time: traffic.inter_packet_time(),
+            failed_login_attempts: traffic.failed_logins,
+            commands: traffic.commands,
+        };
+        let mut features = FeatureExtractor::extract_stats(session.peer_addr, &stats);
+
+        // NaN/Inf nie an das Model weitergeben
+        if let Err(e) = features.validate() {
+            tracing::warn!("⚠️ {} - sanitizing (Session: {})", e, session.id);
+            features.sanitize();
+        }
+
+        features.as_vector()
+    }
+
+    /// Verdächtige Session gemäß RL-Aktion behandeln und aus dem Ergebnis lernen
+    ///
+    /// `forced`: Policy verlangt Deception - Ignore/Block werden dann zu Standard-Engagement.
+    async fn engage(
+        &self,
+        connection: Connection,
+        mut session: Session,
+        forced: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let rl_choice = self.choose_rl_action(&mut session).await;
+        let action = rl_choice.as_ref().map(|(_, action)| *action);
+
+        // Fehler erst nach dem RL-Update melden
+        let result = match action {
+            Some(Action::Ignore) if !forced => {
+                self.forward_to_backend(connection, session.clone(), false)
+                    .await
+            }
+            Some(Action::Block) if !forced => {
+                self.block_connection(connection, session.clone(), "rl", true)
+                    .await
+            }
+            action => {
+                let strategy = response_strategy(action);
+                self.redirect_to_honeypot(connection, session.clone(), strategy)
+                    .await
+            }
+        }
+        .map_err(|e| e.to_string());
+
+        if let Some((state, action)) = rl_choice {
+            self.learn_from_session(&session, state, action).await;
+        }
+
+        Ok(result?)
     }
 
     /// Zu Honeypot umleiten
     async fn redirect_to_honeypot(
         &self,
         connection: Connection,
-        session: Session,
+        mut session: Session,
+        strategy: ResponseStrategy,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        tracing::info!("🍯 Redirecting session {} to honeypot", session.id);
+        tracing::info!(
+            "🍯 Redirecting session {} to honeypot ({:?} engagement)",
+            session.id,
+            strategy
+        );
+
+        session.record_decision(Decision::Route {
+            target: "honeypot".to_string(),
+        });
+        self.session_manager.update(session.clone()).await;
 
         // Session als suspicious markieren
         self.session_manager
@@ -114,26 +491,178 @@ impl Router {
             bytes_received: session.bytes_received,
             is_suspicious: session.is_suspicious,
             anomaly_score: session.anomaly_score,
+            exfil: session.exfil.clone(),
+            strategy,
+            profile: None,
         };
 
-        // An Deception System übergeben
-        self.deception
+        if let Some(mirror) = &self.mirror {
+            connection
+                .traffic
+                .tap_inbound(Arc::new(mirror.for_session(&session.id)));
+        }
+
+        // An Deception System übergeben, Session danach in jedem Fall schließen
+        let result = self
+            .deception
             .handle_connection(connection, deception_session)
-            .await?;
+            .await
+            .map_err(|e| e.to_string());
+
+        let exfil_bytes = session.exfil.bytes();
+        if exfil_bytes > 0 {
+            self.exfil_bytes.fetch_add(exfil_bytes, Ordering::SeqCst);
+            tracing::warn!(
+                "📤 {} bytes exfiltrated from honeypot by {} (Session: {})",
+                exfil_bytes,
+                session.peer_addr,
+                session.id
+            );
+            self.session_manager.update(session.clone()).await;
+        }
+        tracing::debug!("{}", session.summary());
+        self.session_manager.close(&session.id).await;
+
+        Ok(result?)
+    }
+
+    /// RL-Aktion für die Session wählen und im Audit Trail festhalten
+    async fn choose_rl_action(&self, session: &mut Session) -> Option<(State, Action)> {
+        let (agent, _) = self.rl.as_ref()?;
+
+        let state = State {
+            attack_type: (session.anomaly_score.clamp(0.0, 1.0) * 10.0).round() as u8,
+            connection_intensity: self
+                .connection_rates
+                .rate(session.peer_addr.ip(), Instant::now())
+                .round()
+                .min(10.0) as u8,
+            source_reputation: self
+                .reputation
+                .lock()
+                .unwrap()
+                .bucket(session.peer_addr.ip(), Instant::now()),
+            behavior: session.traffic.stats().behavior.as_u8(),
+        };
+        let action = agent.read().await.choose_action(&state);
+
+        session.record_decision(Decision::RlAction {
+            state: format!(
+                "attack={} intensity={} reputation={} behavior={}",
+                state.attack_type,
+                state.connection_intensity,
+                state.source_reputation,
+                state.behavior
+            ),
+            action: format!("{:?}", action),
+        });
+        Some((state, action))
+    }
+
+    /// Reward aus der beendeten Session berechnen und den Agenten updaten
+    async fn learn_from_session(&self, session: &Session, state: State, action: Action) {
+        let Some((agent, rewards)) = &self.rl else {
+            return;
+        };
+
+        // Engagement-Dauer bindet Angreifer (Gewinn) und Ressourcen (Kosten)
+        let minutes = session.duration().as_secs_f64() / 60.0;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let since = now.saturating_sub(session.duration().as_secs());
+        let report = self
+            .deception
+            .query_intel(&IntelQuery::ip(session.peer_addr.ip()).between(since, now));
+        let info_gained = (report.commands.len() + report.distinct_credentials) as f64;
+        let reward = rewards.calculate(&action, info_gained, minutes, minutes);
+
+        // Folgezustand: Einstufung, die der Honeypot während der Session gelernt hat
+        let next_state = State {
+            behavior: session.traffic.stats().behavior.as_u8(),
+            ..state.clone()
+        };
+
+        let mut agent = agent.write().await;
+        agent.update(&state, &action, reward, &next_state);
+        agent.train_from_replay(RL_REPLAY_BATCH);
+        agent.finish_episode();
+
+        tracing::debug!(
+            "🤖 RL reward {:.2} for {:?} (Session: {})",
+            reward,
+            action,
+            session.id
+        );
+    }
+
+    /// Research-Scanner mit minimalem Engagement abfertigen
+    async fn handle_research_scan(
+        &self,
+        connection: Connection,
+        mut session: Session,
+        scanner: String,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.research_scans.fetch_add(1, Ordering::SeqCst);
+        METRICS
+            .connections
+            .research_scans
+            .with_label_values(&[&scanner])
+            .inc();
+
+        tracing::info!(
+            "🔭 Research scanner {} from {} - minimal engagement (Session: {})",
+            scanner,
+            session.peer_addr,
+            session.id
+        );
+
+        session.record_decision(Decision::ResearchScanner { scanner });
+        session.record_decision(Decision::Route {
+            target: "minimal".to_string(),
+        });
+        tracing::debug!("{}", session.summary());
+
+        connection.close_with(CloseReason::ResearchScanner).await;
+        self.session_manager.close(&session.id).await;
 
         Ok(())
     }
 
     /// Zu Backend weiterleiten
+    ///
+    /// `watch`: Bei aktiver Neubewertung bleibt die Session registriert, solange
+    /// der Peer sendet. Wird sie dabei als Anomalie eingestuft, übernimmt ein
+    /// Honeypot die Verbindung (bei QUIC ab dem nächsten Bi-Stream des Peers).
     async fn forward_to_backend(
         &self,
-        _connection: Connection,
-        session: Session,
+        connection: Connection,
+        mut session: Session,
+        watch: bool,
     ) -> Result<(), Box<dyn std::error::Error>> {
         tracing::debug!("➡️ Forwarding session {} to backend", session.id);
 
+        session.record_decision(Decision::Route {
+            target: "backend".to_string(),
+        });
+
+        if let (true, Some(interval)) = (watch, self.reevaluation_interval) {
+            self.session_manager.update(session.clone()).await;
+            let escalated = self
+                .watch_forwarded(&connection, &session.id, interval)
+                .await
+                .map_err(|e| e.to_string())?;
+            if let Some(session) = escalated {
+                return Box::pin(self.engage(connection, session, true)).await;
+            }
+            session = self.session_manager.get(&session.id).await.unwrap_or(session);
+        }
+        tracing::debug!("{}", session.summary());
+
         // TODO: Implementierung der Backend-Weiterleitung
-        // Für jetzt: Connection wird automatisch geschlossen (Drop)
+        // Für jetzt: Connection wird regulär geschlossen
+        connection.close().await;
 
         // Session schließen
         self.session_manager.close(&session.id).await;
@@ -141,7 +670,53 @@ impl Router {
         Ok(())
     }
 
+    /// Traffic einer weitergeleiteten Session mitzählen, bis der Peer schließt
+    ///
+    /// Liefert die Session, sobald die Neubewertung sie als Anomalie einstuft.
+    async fn watch_forwarded(
+        &self,
+        connection: &Connection,
+        session_id: &str,
+        interval: Duration,
+    ) -> Result<Option<Session>, Box<dyn std::error::Error>> {
+        use tokio::io::AsyncReadExt;
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            return Ok(None);
+        };
+
+        // Gelesene Bytes gehen (noch) an kein Backend, zählen aber als Traffic
+        let mut chunk = [0u8; 4096];
+        let mut ticker = tokio::time::interval(interval.max(Duration::from_millis(10)));
+        loop {
+            tokio::select! {
+                read = stream.read(&mut chunk) => {
+                    if read? == 0 {
+                        return Ok(None);
+                    }
+                }
+                _ = ticker.tick() => match self.session_manager.get(session_id).await {
+                    // Vom Reaper geschlossen
+                    None => return Ok(None),
+                    Some(session) if session.is_suspicious => {
+                        tracing::warn!(
+                            "🔀 Escalating forwarded session {} from {} to honeypot",
+                            session.id,
+                            session.peer_addr
+                        );
+                        return Ok(Some(session));
+                    }
+                    Some(_) => {}
+                },
+            }
+        }
+    }
+
     /// Statistiken
+    pub async fn active_sessions(&self) -> usize {
+        self.session_manager.count().await
+    }
+
     pub fn total_connections(&self) -> u64 {
         self.total_connections.load(Ordering::SeqCst)
     }
@@ -149,4 +724,560 @@ impl Router {
     pub fn anomalies_detected(&self) -> u64 {
         self.anomalies_detected.load(Ordering::SeqCst)
     }
+
+    pub fn research_scans(&self) -> u64 {
+        self.research_scans.load(Ordering::SeqCst)
+    }
+
+    pub fn exfil_bytes(&self) -> u64 {
+        self.exfil_bytes.load(Ordering::SeqCst)
+    }
+
+    pub fn dropped_session_events(&self) -> u64 {
+        self.session_manager.dropped_events()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_ai::NetworkFeatures;
+
+    /// Verdächtige Honeypot-Session (wie vom Router übergeben)
+    fn test_session(id: &str, peer_addr: std::net::SocketAddr) -> honeytrap_deception::Session {
+        honeytrap_deception::Session {
+            id: id.to_string(),
+            peer_addr,
+            started_at: Instant::now(),
+            bytes_sent: 0,
+            bytes_received: 0,
+            is_suspicious: true,
+            anomaly_score: 1.0,
+            exfil: Default::default(),
+            strategy: Default::default(),
+            profile: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extracted_features_match_feature_layout() {
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10))),
+            Arc::new(DeceptionSystem::new()),
+        );
+        let session = Session::new("192.168.1.50:40000".parse().unwrap());
+
+        let features = router.extract_features(&session).await;
+
+        assert_eq!(features.len(), NetworkFeatures::feature_names().len());
+        assert_eq!(features[0], 40000.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_logins_drive_login_feature() {
+        use honeytrap_deception::{HoneypotConfig, HoneypotType, InteractionLevel};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10))),
+            deception.clone(),
+        );
+        let peer_addr = "198.51.100.9:41000".parse().unwrap();
+        let (mut peer, server) = tokio::io::duplex(8192);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr("0.0.0.0:22".parse().unwrap());
+        let session = router.session_manager.register_connection(&connection).await;
+
+        // Login und Commands laufen durch den echten SSH-Handler
+        let input = b"root\ntoor\nuname -a\nid\nexit\n";
+        peer.write_all(input).await.unwrap();
+        deception
+            .handle_connection(connection, test_session(&session.id, peer_addr))
+            .await
+            .unwrap();
+        let mut output = Vec::new();
+        peer.read_to_end(&mut output).await.unwrap();
+
+        let features = router.extract_features(&session).await;
+        let names = NetworkFeatures::feature_names();
+        let feature = |name: &str| features[names.iter().position(|n| *n == name).unwrap()];
+
+        assert_eq!(feature("failed_login_attempts"), 1.0);
+        assert_eq!(feature("destination_port"), 22.0);
+        assert_eq!(feature("bytes_sent"), input.len() as f64);
+        assert_eq!(feature("bytes_received"), output.len() as f64);
+        assert!(feature("command_frequency") > 0.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_honeypot_sessions_are_mirrored() {
+        use honeytrap_deception::{HoneypotConfig, HoneypotType, InteractionLevel};
+        use honeytrap_protocol::MemorySink;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+        let sink = MemorySink::new();
+        let mirror = TrafficMirror::new(Arc::new(sink.clone())).unwrap();
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10).with_threshold(-1.0))),
+            deception,
+        )
+        .with_traffic_mirror(mirror.clone());
+
+        let (mut peer, server) = tokio::io::duplex(8192);
+        let connection = Connection::with_memory("198.51.100.10:41000".parse().unwrap(), server)
+            .with_local_addr("0.0.0.0:22".parse().unwrap());
+        let input = b"root\ntoor\nuname -a\nexit\n";
+        peer.write_all(input).await.unwrap();
+        peer.shutdown().await.unwrap();
+        router.handle_connection(connection).await.unwrap();
+        let mut output = Vec::new();
+        peer.read_to_end(&mut output).await.unwrap();
+
+        mirror.flush().await;
+        let frames = sink.frames();
+        assert!(!frames.is_empty());
+        let payload: Vec<u8> = frames.iter().flat_map(|f| f.payload.clone()).collect();
+        assert_eq!(payload, input);
+        let session_id = &frames[0].session_id;
+        assert!(frames.iter().all(|f| &f.session_id == session_id));
+        assert!(router.session_manager.get(session_id).await.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exfiltration_reported_on_router_session() {
+        use honeytrap_deception::{HoneypotConfig, HoneypotType, InteractionLevel};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10).with_threshold(-1.0))),
+            deception,
+        );
+        let mut events = router.take_event_receiver().unwrap();
+
+        let (mut peer, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory("198.51.100.11:41000".parse().unwrap(), server)
+            .with_local_addr("0.0.0.0:22".parse().unwrap());
+        peer.write_all(b"root\ntoor\ncat /etc/passwd\nexit\n")
+            .await
+            .unwrap();
+        peer.shutdown().await.unwrap();
+        router.handle_connection(connection).await.unwrap();
+        let mut output = Vec::new();
+        peer.read_to_end(&mut output).await.unwrap();
+        assert!(String::from_utf8_lossy(&output).contains("root:x:0:0"));
+
+        let mut reported = None;
+        while let Ok(event) = events.try_recv() {
+            if let crate::session::SessionEvent::Updated(session) = event {
+                reported = Some(session.summary());
+            }
+        }
+        let summary = reported.unwrap();
+        assert!(summary.exfil_bytes > 0);
+        assert_eq!(router.exfil_bytes(), summary.exfil_bytes);
+        assert!(summary
+            .to_string()
+            .contains(&format!("{} bytes exfiltrated", summary.exfil_bytes)));
+    }
+
+    #[tokio::test]
+    async fn test_growing_session_is_reclassified() {
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10))),
+            Arc::new(DeceptionSystem::new()),
+        )
+        .with_reevaluation_interval(Duration::ZERO);
+        let session = router
+            .session_manager
+            .register("198.51.100.20:40000".parse().unwrap())
+            .await;
+
+        // Unauffällig, solange kaum Traffic fließt
+        assert_eq!(router.reevaluate_sessions().await, 0);
+        assert_eq!(router.reevaluate_sessions().await, 0);
+
+        router
+            .session_manager
+            .record_traffic(&session.id, 500_000, 1_000)
+            .await;
+        assert_eq!(router.reevaluate_sessions().await, 1);
+        assert_eq!(router.anomalies_detected(), 1);
+
+        let session = router.session_manager.get(&session.id).await.unwrap();
+        assert!(session.is_suspicious);
+        assert_eq!(session.decisions.len(), 3);
+
+        // Bereits verdächtige Sessions werden nicht erneut gezählt
+        assert_eq!(router.reevaluate_sessions().await, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reclassified_forwarded_session_escalates_to_honeypot() {
+        use honeytrap_deception::{HoneypotConfig, HoneypotType, InteractionLevel};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+        let router = Arc::new(
+            Router::new(Arc::new(RwLock::new(AnomalyDetector::new(10))), deception)
+                .with_reevaluation_interval(Duration::ZERO),
+        );
+        let peer_addr: std::net::SocketAddr = "198.51.100.21:40000".parse().unwrap();
+        let (mut peer, server) = tokio::io::duplex(64 * 1024);
+        let connection =
+            Connection::with_memory(peer_addr, server).with_local_addr("0.0.0.0:22".parse().unwrap());
+        let handled = tokio::spawn({
+            let router = router.clone();
+            async move { router.handle_connection(connection).await.map_err(|e| e.to_string()) }
+        });
+
+        // Beim Verbindungsaufbau unauffällig: weitergeleitet, aber weiter registriert
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let session = router.session_manager.active_sessions().await.pop().unwrap();
+        assert!(!session.is_suspicious);
+        assert_eq!(router.reevaluate_sessions().await, 0);
+
+        // Wachsender Traffic auf der weitergeleiteten Verbindung
+        peer.write_all(&vec![b'x'; 500_000]).await.unwrap();
+        while session.traffic.stats().bytes_sent < 500_000 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(router.reevaluate_sessions().await, 1);
+
+        // Honeypot übernimmt die Verbindung
+        let mut output = Vec::new();
+        while !String::from_utf8_lossy(&output).contains("login: ") {
+            let mut chunk = [0u8; 1024];
+            let read = peer.read(&mut chunk).await.unwrap();
+            assert!(read > 0);
+            output.extend_from_slice(&chunk[..read]);
+        }
+        peer.write_all(b"root\ntoor\nexit\n").await.unwrap();
+        peer.shutdown().await.unwrap();
+        peer.read_to_end(&mut output).await.unwrap();
+        handled.await.unwrap().unwrap();
+
+        assert!(String::from_utf8_lossy(&output).starts_with("SSH-2.0-"));
+        assert_eq!(router.anomalies_detected(), 1);
+        let intel = router.deception.query_intel(&IntelQuery::ip(peer_addr.ip()));
+        assert_eq!(intel.total_sessions, 1);
+    }
+
+    #[tokio::test]
+    async fn test_known_scanner_gets_minimal_engagement() {
+        let scanners = ScannerClassifier::from_config(&[crate::scanners::KnownScanner {
+            name: "censys".to_string(),
+            ranges: vec!["198.51.100.0/24".to_string()],
+        }])
+        .unwrap();
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10))),
+            Arc::new(DeceptionSystem::new()),
+        )
+        .with_scanner_classifier(scanners);
+        let metric = METRICS
+            .connections
+            .research_scans
+            .with_label_values(&["censys"]);
+        let before = metric.get();
+        let closed = METRICS
+            .connections
+            .closed
+            .with_label_values(&["research_scanner"]);
+        let closed_before = closed.get();
+
+        router
+            .handle_connection(Connection::new("198.51.100.23:41000".parse().unwrap()))
+            .await
+            .unwrap();
+
+        assert_eq!(router.research_scans(), 1);
+        assert_eq!(metric.get(), before + 1);
+        assert_eq!(closed.get(), closed_before + 1);
+        assert_eq!(router.anomalies_detected(), 0);
+        assert_eq!(router.active_sessions().await, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_analyses_share_read_lock() {
+        const SESSIONS: usize = 32;
+        let ai_engine = Arc::new(RwLock::new(AnomalyDetector::new(100)));
+        let router = Arc::new(
+            Router::new(ai_engine.clone(), Arc::new(DeceptionSystem::new()))
+                .with_reevaluation_interval(Duration::ZERO),
+        );
+
+        let mut ids = Vec::new();
+        for i in 0..SESSIONS {
+            let addr = format!("198.51.100.{}:40000", i + 1).parse().unwrap();
+            ids.push(router.session_manager.register(addr).await.id);
+        }
+
+        // Ein dauerhaft gehaltener Read-Lock (z.B. Stats-Abfrage) würde jede
+        // Analyse blockieren, die einen Write-Lock braucht
+        let stats_reader = ai_engine.read().await;
+
+        let tasks: Vec<_> = ids
+            .into_iter()
+            .map(|id| {
+                let router = router.clone();
+                tokio::spawn(async move { router.reevaluate(&id).await.unwrap() })
+            })
+            .collect();
+        let all = async {
+            for task in tasks {
+                task.await.unwrap();
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), all)
+            .await
+            .expect("analyses serialized on a write lock");
+
+        assert_eq!(stats_reader.total_predictions(), SESSIONS as u64);
+    }
+
+    /// Router mit MySQL-Honeypot auf 3306 und einer einzelnen Policy
+    async fn policy_router(threshold: f64, policy: &str, block_ips: bool) -> Router {
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(honeytrap_deception::HoneypotConfig {
+                port: 3306,
+                honeypot_type: honeytrap_deception::HoneypotType::Mysql,
+                interaction_level: honeytrap_deception::InteractionLevel::Low,
+            })
+            .await
+            .unwrap();
+        let policies = PolicyEngine::parse_policies("test.toml", policy).unwrap();
+
+        Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10).with_threshold(threshold))),
+            deception,
+        )
+        .with_policy_engine(Arc::new(PolicyEngine::new().with_policies(policies)), block_ips)
+    }
+
+    fn mysql_connection(peer: &str) -> Connection {
+        Connection::new(peer.parse().unwrap()).with_local_addr("10.0.0.1:3306".parse().unwrap())
+    }
+
+    fn honeypot_sessions(router: &Router, peer: &str) -> usize {
+        let ip = peer.parse::<std::net::SocketAddr>().unwrap().ip();
+        router.deception.query_intel(&IntelQuery::ip(ip)).total_sessions
+    }
+
+    #[tokio::test]
+    async fn test_policy_block_closes_and_blocks_ip() {
+        let router = policy_router(
+            -1.0,
+            r#"
+            [[policies]]
+            name = "block-scanners"
+            action = "block"
+            [[policies.conditions.all]]
+            src_ip_in_cidr = ["198.51.100.0/24"]
+            "#,
+            true,
+        )
+        .await;
+        let peer = "198.51.100.9:40000";
+
+        router.handle_connection(mysql_connection(peer)).await.unwrap();
+        assert!(router.deception.is_blocked("198.51.100.9".parse().unwrap()).await);
+        assert_eq!(honeypot_sessions(&router, peer), 0);
+        assert_eq!(router.active_sessions().await, 0);
+
+        // Gesperrte IP wird vor der AI-Analyse abgewiesen
+        router.handle_connection(mysql_connection(peer)).await.unwrap();
+        assert_eq!(router.total_connections(), 2);
+        assert_eq!(router.anomalies_detected(), 1);
+        assert_eq!(router.active_sessions().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_policy_allow_overrides_anomaly() {
+        let router = policy_router(
+            -1.0,
+            r#"
+            [[policies]]
+            name = "allow-internal"
+            action = "allow"
+            [[policies.conditions.all]]
+            src_ip_in_cidr = ["10.0.0.0/8"]
+            "#,
+            true,
+        )
+        .await;
+
+        router.handle_connection(mysql_connection("10.1.2.3:40000")).await.unwrap();
+        assert_eq!(router.anomalies_detected(), 1);
+        assert_eq!(honeypot_sessions(&router, "10.1.2.3:40000"), 0);
+
+        // Ohne passende Policy entscheidet die AI-Analyse
+        router.handle_connection(mysql_connection("192.0.2.5:40000")).await.unwrap();
+        assert_eq!(honeypot_sessions(&router, "192.0.2.5:40000"), 1);
+        assert!(!router.deception.is_blocked("192.0.2.5".parse().unwrap()).await);
+    }
+
+    #[tokio::test]
+    async fn test_policy_deception_routes_normal_traffic_to_honeypot() {
+        let router = policy_router(
+            2.0,
+            r#"
+            [[policies]]
+            name = "deceive-mysql"
+            action = "deception"
+            [[policies.conditions.all]]
+            protocol = "mysql"
+            "#,
+            false,
+        )
+        .await;
+
+        router.handle_connection(mysql_connection("192.0.2.7:40000")).await.unwrap();
+        assert_eq!(router.anomalies_detected(), 0);
+        assert_eq!(honeypot_sessions(&router, "192.0.2.7:40000"), 1);
+
+        // Andere Protokolle matchen nicht und werden normal weitergeleitet
+        let http = Connection::new("192.0.2.8:40000".parse().unwrap())
+            .with_local_addr("10.0.0.1:80".parse().unwrap());
+        router.handle_connection(http).await.unwrap();
+        assert_eq!(honeypot_sessions(&router, "192.0.2.8:40000"), 0);
+    }
+
+    /// Greedy-Agent, der in jedem State `action` bevorzugt
+    fn rl_agent_preferring(action: Action) -> Arc<RwLock<RLAgent>> {
+        let mut agent = RLAgent::with_config(honeytrap_ai::RLConfig {
+            epsilon: 0.0,
+            epsilon_min: 0.0,
+            ..Default::default()
+        });
+        for attack_type in 0..=10 {
+            for connection_intensity in 0..=10 {
+                for source_reputation in 0..=10 {
+                    for behavior in 0..=2 {
+                        let state = State {
+                            attack_type,
+                            connection_intensity,
+                            source_reputation,
+                            behavior,
+                        };
+                        agent.update(&state, &action, 10.0, &state);
+                    }
+                }
+            }
+        }
+        Arc::new(RwLock::new(agent))
+    }
+
+    #[tokio::test]
+    async fn test_rl_action_drives_routing_and_learns_from_session() {
+        let policy = r#"
+            [[policies]]
+            name = "deceive-mysql"
+            action = "deception"
+            [[policies.conditions.all]]
+            protocol = "mysql"
+            "#;
+
+        // Block: IP gesperrt, kein Honeypot, Episode gelernt
+        let agent = rl_agent_preferring(Action::Block);
+        let router = policy_router(-1.0, "", false)
+            .await
+            .with_rl_agent(agent.clone(), RewardCalculator::new());
+        router.handle_connection(mysql_connection("192.0.2.20:40000")).await.unwrap();
+        assert!(router.deception.is_blocked("192.0.2.20".parse().unwrap()).await);
+        assert_eq!(honeypot_sessions(&router, "192.0.2.20:40000"), 0);
+        assert_eq!(agent.read().await.get_stats().episodes_trained, 1);
+
+        // Ignore: normale Weiterleitung statt Honeypot
+        let agent = rl_agent_preferring(Action::Ignore);
+        let router = policy_router(-1.0, "", false)
+            .await
+            .with_rl_agent(agent.clone(), RewardCalculator::new());
+        router.handle_connection(mysql_connection("192.0.2.21:40000")).await.unwrap();
+        assert!(!router.deception.is_blocked("192.0.2.21".parse().unwrap()).await);
+        assert_eq!(honeypot_sessions(&router, "192.0.2.21:40000"), 0);
+        assert_eq!(agent.read().await.get_stats().episodes_trained, 1);
+
+        // Policy erzwingt Deception - Block wird zu Standard-Engagement
+        let agent = rl_agent_preferring(Action::Block);
+        let router = policy_router(-1.0, policy, false)
+            .await
+            .with_rl_agent(agent.clone(), RewardCalculator::new());
+        router.handle_connection(mysql_connection("192.0.2.22:40000")).await.unwrap();
+        assert!(!router.deception.is_blocked("192.0.2.22".parse().unwrap()).await);
+        assert_eq!(honeypot_sessions(&router, "192.0.2.22:40000"), 1);
+
+        assert_eq!(response_strategy(Some(Action::DeepEngagement)), ResponseStrategy::Deep);
+        assert_eq!(response_strategy(Some(Action::MinimalResponse)), ResponseStrategy::Minimal);
+        assert_eq!(response_strategy(None), ResponseStrategy::Standard);
+    }
+
+    #[tokio::test]
+    async fn test_rl_intensity_counts_only_the_peer_and_sessions_are_closed() {
+        let router = policy_router(-1.0, "", false)
+            .await
+            .with_rl_agent(rl_agent_preferring(Action::StandardEngagement), RewardCalculator::new());
+
+        // Viele Sessions anderer Quellen heben die Intensität des Peers nicht an
+        for i in 0..20 {
+            let addr = format!("203.0.113.{}:40000", i + 1).parse().unwrap();
+            router.session_manager.register(addr).await;
+        }
+        for _ in 0..3 {
+            router.handle_connection(mysql_connection("192.0.2.30:40000")).await.unwrap();
+        }
+        assert_eq!(router.active_sessions().await, 20);
+
+        let mut session = Session::new("192.0.2.30:40001".parse().unwrap());
+        let (state, _) = router.choose_rl_action(&mut session).await.unwrap();
+        assert_eq!(state.connection_intensity, 3);
+        let mut session = Session::new("192.0.2.31:40000".parse().unwrap());
+        let (state, _) = router.choose_rl_action(&mut session).await.unwrap();
+        assert_eq!(state.connection_intensity, 0);
+
+        // Honeypot-Sessions werden geschlossen, auch wenn der Handler fehlschlägt
+        let unknown_port = Connection::new("192.0.2.32:40000".parse().unwrap())
+            .with_local_addr("10.0.0.1:9999".parse().unwrap());
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10).with_threshold(-1.0))),
+            Arc::new(DeceptionSystem::new()),
+        );
+        assert!(router.handle_connection(unknown_port).await.is_err());
+        assert_eq!(router.active_sessions().await, 0);
+    }
 }
@@ -0,0 +1,137 @@
+//! Research-Scanner Erkennung
+//!
+//! Bekannte Internet-Scanner (Shodan, Censys, akademische Scans) werden über
+//! konfigurierte IP-Ranges erkannt und nur minimal bedient, damit sie die
+//! Angreifer-Intel nicht verwässern.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+/// IP-Range in CIDR-Notation (z.B. `198.51.100.0/24`, `2001:db8::/32`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Liegt die IP in der Range?
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix_len)).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix_len)).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = Box<dyn std::error::Error>;
+
+    /// Ohne `/prefix` wird eine einzelne Adresse angenommen
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+
+        let network: IpAddr = addr.trim().parse()?;
+        let max_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix {
+            Some(prefix) => prefix.trim().parse::<u8>()?,
+            None => max_len,
+        };
+        if prefix_len > max_len {
+            return Err(format!("Invalid prefix length in {}", s).into());
+        }
+
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// Konfigurierter Scanner (Name + IP-Ranges)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct KnownScanner {
+    pub name: String,
+    pub ranges: Vec<String>,
+}
+
+/// Klassifiziert Quellen als bekannte Research-Scanner
+#[derive(Debug, Clone, Default)]
+pub struct ScannerClassifier {
+    scanners: Vec<(String, Vec<Cidr>)>,
+}
+
+impl ScannerClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Aus der Config-Liste aufbauen
+    pub fn from_config(scanners: &[KnownScanner]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut classifier = Self::new();
+        for scanner in scanners {
+            let ranges = scanner
+                .ranges
+                .iter()
+                .map(|range| range.parse())
+                .collect::<Result<Vec<Cidr>, _>>()?;
+            classifier.scanners.push((scanner.name.clone(), ranges));
+        }
+        Ok(classifier)
+    }
+
+    /// Name des Scanners, falls die IP zu einem bekannten Scanner gehört
+    pub fn classify(&self, ip: IpAddr) -> Option<&str> {
+        self.scanners
+            .iter()
+            .find(|(_, ranges)| ranges.iter().any(|range| range.contains(ip)))
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scanners.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cidr_matching() {
+        let v4: Cidr = "198.51.100.0/24".parse().unwrap();
+        assert!(v4.contains("198.51.100.200".parse().unwrap()));
+        assert!(!v4.contains("198.51.101.1".parse().unwrap()));
+        assert!(!v4.contains("2001:db8::1".parse().unwrap()));
+
+        let v6: Cidr = "2001:db8::/32".parse().unwrap();
+        assert!(v6.contains("2001:db8:ffff::1".parse().unwrap()));
+
+        let single: Cidr = "192.0.2.1".parse().unwrap();
+        assert_eq!(single.to_string(), "192.0.2.1/32");
+        assert!(!single.contains("192.0.2.2".parse().unwrap()));
+
+        let any: Cidr = "0.0.0.0/0".parse().unwrap();
+        assert!(any.contains("203.0.113.1".parse().unwrap()));
+
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+    }
+}
@@ -1,6 +1,12 @@
+use crate::decision::{Decision, DecisionTrace};
+use crate::events::{self, EventOverflow, SessionEventReceiver, SessionEventSender};
+use honeytrap_deception::{
+    Clock, CloseReason, Connection, ExfilCounter, SystemClock, TrafficCounter,
+};
+use std::fmt;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
 
 /// Session-Tracking für einzelne Verbindungen
 #[derive(Debug, Clone)]
@@ -12,19 +18,51 @@ pub struct Session {
     pub bytes_received: u64,
     pub is_suspicious: bool,
     pub anomaly_score: f64,
+    /// Audit Trail der Routing-Entscheidungen
+    pub decisions: DecisionTrace,
+    /// Zeitpunkt der letzten AI-Bewertung
+    pub last_evaluated_at: Instant,
+    /// Zeitpunkt des letzten Traffics (für das Idle-Reaping)
+    pub last_activity: Instant,
+    /// Ziel-Port der Verbindung (falls vom Transport bekannt)
+    pub dest_port: Option<u16>,
+    /// Live-Traffic der Connection (Pakete, Timing, Logins, Commands)
+    pub traffic: TrafficCounter,
+    /// Aus sensiblen Ressourcen exfiltrierte Bytes (mit dem Honeypot geteilt)
+    pub exfil: ExfilCounter,
+    /// Zugehörige Connection, damit der Reaper sie mit `Timeout` schließen kann
+    pub connection: Option<Connection>,
 }
 
 impl Session {
     /// Neue Session erstellen
     pub fn new(peer_addr: SocketAddr) -> Self {
+        let now = Instant::now();
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             peer_addr,
-            started_at: Instant::now(),
+            started_at: now,
             bytes_sent: 0,
             bytes_received: 0,
             is_suspicious: false,
             anomaly_score: 0.0,
+            decisions: DecisionTrace::new(),
+            last_evaluated_at: now,
+            last_activity: now,
+            dest_port: None,
+            traffic: TrafficCounter::new(),
+            exfil: ExfilCounter::new(),
+            connection: None,
+        }
+    }
+
+    /// Session für eine Connection, teilt deren Traffic-Zähler
+    pub fn for_connection(connection: &Connection) -> Self {
+        Self {
+            dest_port: connection.dest_port(),
+            traffic: connection.traffic.clone(),
+            connection: Some(connection.clone()),
+            ..Self::new(connection.peer_addr)
         }
     }
 
@@ -33,13 +71,42 @@ impl Session {
         self.started_at.elapsed()
     }
 
+    /// Zeit seit der letzten AI-Bewertung
+    pub fn elapsed_since_evaluation(&self) -> Duration {
+        self.last_evaluated_at.elapsed()
+    }
+
+    /// Bewertung als erfolgt markieren
+    pub fn mark_evaluated(&mut self) {
+        self.last_evaluated_at = Instant::now();
+    }
+
+    /// Zeit seit dem letzten Traffic (bzw. seit Session-Start)
+    pub fn idle_time(&self) -> Duration {
+        self.idle_time_at(Instant::now())
+    }
+
+    /// Wie `idle_time`, gemessen bis `now`
+    pub fn idle_time_at(&self, now: Instant) -> Duration {
+        let idle = now.saturating_duration_since(self.last_activity);
+        self.traffic.idle_time().map_or(idle, |stream| stream.min(idle))
+    }
+
     /// Bytes hinzufügen
     pub fn add_bytes_sent(&mut self, bytes: u64) {
         self.bytes_sent += bytes;
+        if bytes > 0 {
+            self.traffic.record_sent(bytes as usize);
+            self.last_activity = Instant::now();
+        }
     }
 
     pub fn add_bytes_received(&mut self, bytes: u64) {
         self.bytes_received += bytes;
+        if bytes > 0 {
+            self.traffic.record_received(bytes as usize);
+            self.last_activity = Instant::now();
+        }
     }
 
     /// Als verdächtig markieren
@@ -47,12 +114,69 @@ impl Session {
         self.is_suspicious = true;
         self.anomaly_score = score;
     }
+
+    /// Entscheidung im Audit Trail festhalten
+    pub fn record_decision(&mut self, decision: Decision) {
+        self.decisions.record(decision);
+    }
+
+    /// Zusammenfassung inkl. Decision Trace
+    pub fn summary(&self) -> SessionSummary {
+        SessionSummary {
+            id: self.id.clone(),
+            peer_addr: self.peer_addr,
+            duration: self.duration(),
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            is_suspicious: self.is_suspicious,
+            anomaly_score: self.anomaly_score,
+            exfil_bytes: self.exfil.bytes(),
+            decisions: self.decisions.clone(),
+        }
+    }
 }
 
+/// Session-Zusammenfassung für Logs und Review
+#[derive(Debug, Clone)]
+pub struct SessionSummary {
+    pub id: String,
+    pub peer_addr: SocketAddr,
+    pub duration: Duration,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub is_suspicious: bool,
+    pub anomaly_score: f64,
+    pub exfil_bytes: u64,
+    pub decisions: DecisionTrace,
+}
+
+impl fmt::Display for SessionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Session {} from {} ({:.2}s, {} bytes sent, {} bytes received, \
+             {} bytes exfiltrated, suspicious: {}, score: {:.3})",
+            self.id,
+            self.peer_addr,
+            self.duration.as_secs_f64(),
+            self.bytes_sent,
+            self.bytes_received,
+            self.exfil_bytes,
+            self.is_suspicious,
+            self.anomaly_score
+        )?;
+        write!(f, "{}", self.decisions)
+    }
+}
+
+type SessionMap = Arc<tokio::sync::RwLock<std::collections::HashMap<String, Session>>>;
+
 /// Session Manager für alle aktiven Sessions
 pub struct SessionManager {
-    sessions: tokio::sync::RwLock<std::collections::HashMap<String, Session>>,
-    event_tx: mpsc::UnboundedSender<SessionEvent>,
+    sessions: SessionMap,
+    event_tx: SessionEventSender,
+    /// Zeitquelle für Aktivität und Idle-Reaping
+    clock: Arc<dyn Clock>,
 }
 
 #[derive(Debug, Clone)]
@@ -64,27 +188,52 @@ pub enum SessionEvent {
 }
 
 impl SessionManager {
-    /// Neuer Session Manager
-    pub fn new() -> (Self, mpsc::UnboundedReceiver<SessionEvent>) {
-        let (tx, rx) = mpsc::unbounded_channel();
+    /// Neuer Session Manager mit Standard-Event-Queue
+    pub fn new() -> (Self, SessionEventReceiver) {
+        Self::with_event_capacity(events::DEFAULT_EVENT_CAPACITY, EventOverflow::default())
+    }
+
+    /// Neuer Session Manager mit begrenzter Event-Queue
+    ///
+    /// Ist die Queue voll, wird je nach `overflow` das älteste oder neue Event verworfen.
+    pub fn with_event_capacity(
+        capacity: usize,
+        overflow: EventOverflow,
+    ) -> (Self, SessionEventReceiver) {
+        let (tx, rx) = events::channel(capacity, overflow);
 
         (
             Self {
-                sessions: tokio::sync::RwLock::new(std::collections::HashMap::new()),
+                sessions: Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
                 event_tx: tx,
+                clock: Arc::new(SystemClock),
             },
             rx,
         )
     }
 
+    /// Zeitquelle für Aktivität und Idle-Reaping
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
     /// Neue Session registrieren
     pub async fn register(&self, peer_addr: SocketAddr) -> Session {
-        let session = Session::new(peer_addr);
+        self.insert(Session::new(peer_addr)).await
+    }
+
+    /// Neue Session für eine Connection registrieren (Traffic wird live mitgezählt)
+    pub async fn register_connection(&self, connection: &Connection) -> Session {
+        self.insert(Session::for_connection(connection)).await
+    }
 
+    async fn insert(&self, mut session: Session) -> Session {
+        session.last_activity = self.clock.now();
         let mut sessions = self.sessions.write().await;
         sessions.insert(session.id.clone(), session.clone());
 
-        let _ = self.event_tx.send(SessionEvent::Created(session.clone()));
+        self.event_tx.send(SessionEvent::Created(session.clone()));
 
         session
     }
@@ -94,7 +243,7 @@ impl SessionManager {
         let mut sessions = self.sessions.write().await;
         sessions.insert(session.id.clone(), session.clone());
 
-        let _ = self.event_tx.send(SessionEvent::Updated(session));
+        self.event_tx.send(SessionEvent::Updated(session));
     }
 
     /// Session schließen
@@ -102,19 +251,36 @@ impl SessionManager {
         let mut sessions = self.sessions.write().await;
         sessions.remove(session_id);
 
-        let _ = self
-            .event_tx
+        self.event_tx
             .send(SessionEvent::Closed(session_id.to_string()));
     }
 
+    /// Session abrufen
+    pub async fn get(&self, session_id: &str) -> Option<Session> {
+        let sessions = self.sessions.read().await;
+        sessions.get(session_id).cloned()
+    }
+
+    /// Übertragene Bytes einer Session aufaddieren
+    pub async fn record_traffic(&self, session_id: &str, bytes_sent: u64, bytes_received: u64) {
+        let mut sessions = self.sessions.write().await;
+
+        if let Some(session) = sessions.get_mut(session_id) {
+            session.add_bytes_sent(bytes_sent);
+            session.add_bytes_received(bytes_received);
+            if bytes_sent + bytes_received > 0 {
+                session.last_activity = self.clock.now();
+            }
+        }
+    }
+
     /// Session als verdächtig markieren
     pub async fn mark_suspicious(&self, session_id: &str, score: f64) {
         let mut sessions = self.sessions.write().await;
 
         if let Some(session) = sessions.get_mut(session_id) {
             session.mark_suspicious(score);
-            let _ = self
-                .event_tx
+            self.event_tx
                 .send(SessionEvent::Suspicious(session.clone()));
         }
     }
@@ -130,4 +296,179 @@ impl SessionManager {
         let sessions = self.sessions.read().await;
         sessions.len()
     }
+
+    /// Anzahl wegen voller Queue verworfener Session-Events
+    pub fn dropped_events(&self) -> u64 {
+        self.event_tx.dropped()
+    }
+
+    /// Sessions ohne Traffic seit `idle_timeout` schließen
+    ///
+    /// Ihre Connections werden mit `CloseReason::Timeout` geschlossen. Liefert
+    /// die IDs der entfernten Sessions.
+    pub async fn reap_idle(&self, idle_timeout: Duration) -> Vec<String> {
+        Self::reap(&self.sessions, &self.event_tx, self.clock.as_ref(), idle_timeout).await
+    }
+
+    /// Hintergrund-Task, der regelmäßig idle Sessions schließt
+    ///
+    /// Fängt Sessions ab, deren Handler abgestürzt ist oder nie `close` aufruft.
+    pub fn start_reaper(&self, idle_timeout: Duration) -> tokio::task::JoinHandle<()> {
+        let sessions = self.sessions.clone();
+        let event_tx = self.event_tx.clone();
+        let clock = self.clock.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(idle_timeout / 2);
+            loop {
+                ticker.tick().await;
+                Self::reap(&sessions, &event_tx, clock.as_ref(), idle_timeout).await;
+            }
+        })
+    }
+
+    async fn reap(
+        sessions: &SessionMap,
+        event_tx: &SessionEventSender,
+        clock: &dyn Clock,
+        idle_timeout: Duration,
+    ) -> Vec<String> {
+        let now = clock.now();
+        let reaped: Vec<Session> = {
+            let mut sessions = sessions.write().await;
+            let idle: Vec<String> = sessions
+                .values()
+                .filter(|session| session.idle_time_at(now) >= idle_timeout)
+                .map(|session| session.id.clone())
+                .collect();
+            idle.iter().filter_map(|id| sessions.remove(id)).collect()
+        };
+
+        // Connections erst nach dem Lock schließen (flusht In-Memory Streams)
+        for session in &reaped {
+            tracing::info!(
+                "⏱️ Reaping idle session from {} after {:.1}s (Session: {})",
+                session.peer_addr,
+                session.duration().as_secs_f64(),
+                session.id
+            );
+            if let Some(connection) = &session.connection {
+                connection.close_with(CloseReason::Timeout).await;
+            }
+            event_tx.send(SessionEvent::Closed(session.id.clone()));
+        }
+
+        reaped.into_iter().map(|session| session.id).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_policy_routed_session_trace() {
+        use crate::router::Router;
+        use honeytrap_ai::AnomalyDetector;
+        use honeytrap_deception::{DeceptionSystem, HoneypotConfig, HoneypotType, InteractionLevel};
+        use honeytrap_policy::PolicyEngine;
+        use tokio::sync::RwLock;
+
+        let deception = Arc::new(DeceptionSystem::new());
+        deception
+            .deploy_honeypot(HoneypotConfig {
+                port: 3306,
+                honeypot_type: HoneypotType::Mysql,
+                interaction_level: InteractionLevel::Low,
+            })
+            .await
+            .unwrap();
+        let policies = PolicyEngine::parse_policies(
+            "test.toml",
+            r#"
+            [[policies]]
+            name = "scanner-subnet"
+            action = "deception"
+            [[policies.conditions.all]]
+            src_ip_in_cidr = ["203.0.113.0/24"]
+            "#,
+        )
+        .unwrap();
+        // Schwelle > 1: ohne Policy würde die AI nie zum Honeypot routen
+        let router = Router::new(
+            Arc::new(RwLock::new(AnomalyDetector::new(10).with_threshold(2.0))),
+            deception,
+        )
+        .with_policy_engine(Arc::new(PolicyEngine::new().with_policies(policies)), false);
+        let mut events = router.take_event_receiver().unwrap();
+
+        let connection = Connection::new("203.0.113.7:51000".parse().unwrap())
+            .with_local_addr("10.0.0.1:3306".parse().unwrap());
+        router.handle_connection(connection).await.unwrap();
+
+        let mut routed = None;
+        while let Ok(event) = events.try_recv() {
+            if let SessionEvent::Updated(session) = event {
+                routed = Some(session);
+            }
+        }
+        let summary = routed.expect("session never routed").summary();
+        assert_eq!(summary.decisions.matched_policy(), Some("scanner-subnet"));
+        let score = summary.decisions.anomaly_score().unwrap();
+        assert!(score < 2.0);
+
+        let text = summary.to_string();
+        let policy_at = text
+            .find("policy_match policy=scanner-subnet action=deception")
+            .unwrap();
+        let score_at = text
+            .find(&format!("anomaly_score score={:.3}", score))
+            .unwrap();
+        let route_at = text.find("route target=honeypot").unwrap();
+        assert!(score_at < policy_at && policy_at < route_at);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reaper_closes_idle_sessions_only() {
+        let (manager, mut events) = SessionManager::new();
+        let manager = manager.with_clock(Arc::new(honeytrap_deception::TokioClock));
+        let idle = manager.register("203.0.113.20:40000".parse().unwrap()).await;
+        let active = manager.register("203.0.113.21:40000".parse().unwrap()).await;
+        while events.try_recv().is_ok() {}
+
+        let idle_timeout = Duration::from_millis(200);
+        let reaper = manager.start_reaper(idle_timeout);
+        for _ in 0..8 {
+            tokio::time::advance(Duration::from_millis(50)).await;
+            manager.record_traffic(&active.id, 10, 10).await;
+        }
+        // Reaper-Task laufen lassen
+        tokio::task::yield_now().await;
+
+        assert!(manager.get(&idle.id).await.is_none());
+        assert!(manager.get(&active.id).await.is_some());
+        assert!(matches!(events.try_recv(), Ok(SessionEvent::Closed(id)) if id == idle.id));
+        assert!(manager.reap_idle(idle_timeout).await.is_empty());
+        reaper.abort();
+    }
+
+    #[tokio::test]
+    async fn test_reaped_session_connection_closed_with_timeout() {
+        use honeytrap_metrics::METRICS;
+        use tokio::io::AsyncReadExt;
+
+        let (manager, _events) = SessionManager::new();
+        let (mut peer, server) = tokio::io::duplex(64);
+        let connection = Connection::with_memory("203.0.113.22:40000".parse().unwrap(), server);
+        let session = manager.register_connection(&connection).await;
+        let timeouts = METRICS.connections.closed.with_label_values(&["timeout"]);
+        let before = timeouts.get();
+
+        assert_eq!(manager.reap_idle(Duration::ZERO).await, [session.id]);
+
+        // Peer sieht EOF, Metrik zählt den Timeout
+        let mut buf = Vec::new();
+        assert_eq!(peer.read_to_end(&mut buf).await.unwrap(), 0);
+        assert_eq!(timeouts.get(), before + 1);
+    }
 }
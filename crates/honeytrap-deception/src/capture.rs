@@ -0,0 +1,97 @@
+//! Capture-Events
+//!
+//! Einheitliche Darstellung aller erfassten Angreifer-Aktivitäten
+//! (Credentials, Commands, Canaries, HTTP-Requests, LLM-Urteile, abgebrochene
+//! Handshakes) für Notifier, Metriken und Sinks.
+
+use crate::canary::CanaryEvent;
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Art eines Capture-Events
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CaptureKind {
+    Credential {
+        service: String,
+        username: String,
+        password: String,
+    },
+    Command {
+        command: String,
+        /// Vom `CommandParser` als bösartig erkannt
+        malicious: bool,
+    },
+    Canary {
+        token_id: String,
+        location: String,
+    },
+    HttpRequest {
+        method: String,
+        path: String,
+    },
+    LlmVerdict {
+        threat_level: String,
+        attack_type: String,
+    },
+    /// ML-Model und LLM sind uneinig (Kandidat für Relabeling)
+    ModelDisagreement {
+        destination_port: u16,
+        /// Welche Seite die Session als bösartig einstuft ("ml" oder "llm")
+        malicious_by: String,
+        attack_type: String,
+    },
+    /// TLS/QUIC Handshake, der nie zur `Connection` wurde (Scanner)
+    HandshakeFailure {
+        transport: String,
+        peer_addr: SocketAddr,
+        reason: String,
+    },
+}
+
+/// Erfasste Aktivität einer Session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CaptureEvent {
+    pub session_id: String,
+    pub source_ip: Option<IpAddr>,
+    /// Unix-Timestamp in Sekunden
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub kind: CaptureKind,
+    /// Anzahl zusammengefasster identischer Events (siehe `Deduplicator`)
+    pub count: u64,
+}
+
+impl CaptureEvent {
+    pub fn new(session_id: &str, kind: CaptureKind) -> Self {
+        Self {
+            session_id: session_id.to_string(),
+            source_ip: None,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            kind,
+            count: 1,
+        }
+    }
+
+    /// Mit Quell-IP
+    pub fn with_source_ip(mut self, source_ip: IpAddr) -> Self {
+        self.source_ip = Some(source_ip);
+        self
+    }
+}
+
+impl From<&CanaryEvent> for CaptureEvent {
+    fn from(event: &CanaryEvent) -> Self {
+        Self::new(
+            &event.session_id,
+            CaptureKind::Canary {
+                token_id: event.token_id.clone(),
+                location: event.location.clone(),
+            },
+        )
+    }
+}
@@ -0,0 +1,115 @@
+//! Austauschbare Zeitquelle
+//!
+//! Komponenten mit zeitabhängiger Logik (Block-Ablauf, Rate-Fenster,
+//! Timeouts) holen die aktuelle Zeit über `Clock`, damit Tests die Zeit
+//! deterministisch vorstellen können statt real zu schlafen. Handler
+//! schlafen analog über einen `Sleeper`.
+
+use async_trait::async_trait;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Zeitquelle
+pub trait Clock: Send + Sync + std::fmt::Debug {
+    /// Aktueller Zeitpunkt
+    fn now(&self) -> Instant;
+}
+
+/// Echte Systemzeit
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Tokio-Zeit - folgt `tokio::time::pause`/`advance` in Tests
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+}
+
+/// Manuell steuerbare Uhr für Tests
+#[derive(Debug)]
+pub struct MockClock {
+    base: Instant,
+    offset: Mutex<Duration>,
+}
+
+impl MockClock {
+    pub fn new() -> Self {
+        Self {
+            base: Instant::now(),
+            offset: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Zeit vorstellen
+    pub fn advance(&self, duration: Duration) {
+        *self.offset.lock().unwrap() += duration;
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.base + *self.offset.lock().unwrap()
+    }
+}
+
+/// Async Sleep der Handler (Response-Delays, Tarpit, Jitter)
+#[async_trait]
+pub trait Sleeper: Send + Sync + std::fmt::Debug {
+    async fn sleep(&self, duration: Duration);
+}
+
+/// `tokio::time::sleep` - respektiert `tokio::time::pause`/`advance` in Tests
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioSleeper;
+
+#[async_trait]
+impl Sleeper for TokioSleeper {
+    async fn sleep(&self, duration: Duration) {
+        tokio::time::sleep(duration).await;
+    }
+}
+
+/// Schläft nicht, sondern zeichnet die angeforderten Delays auf
+#[derive(Debug, Default)]
+pub struct RecordingSleeper {
+    delays: Mutex<Vec<Duration>>,
+}
+
+impl RecordingSleeper {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bisher angeforderte Delays
+    pub fn delays(&self) -> Vec<Duration> {
+        self.delays.lock().unwrap().clone()
+    }
+
+    /// Summe aller Delays
+    pub fn total(&self) -> Duration {
+        self.delays.lock().unwrap().iter().sum()
+    }
+}
+
+#[async_trait]
+impl Sleeper for RecordingSleeper {
+    async fn sleep(&self, duration: Duration) {
+        self.delays.lock().unwrap().push(duration);
+    }
+}
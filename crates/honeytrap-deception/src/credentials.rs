@@ -0,0 +1,125 @@
+//! Credential Intel Export
+//!
+//! Aggregiert erfasste Username/Passwort-Paare aller Handler und exportiert
+//! sie als CSV oder JSON (z.B. als Password-Spray-Blocklist für SOC-Teams).
+
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Export-Format für Credential-Feeds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialExportFormat {
+    Csv,
+    Json,
+}
+
+/// Aggregierter Eintrag pro Username/Passwort-Paar
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CredentialRecord {
+    pub username: String,
+    pub password: String,
+    pub count: u64,
+    /// Unix-Timestamp (Sekunden) der ersten Erfassung
+    pub first_seen: u64,
+    /// Unix-Timestamp (Sekunden) der letzten Erfassung
+    pub last_seen: u64,
+    pub source_ips: BTreeSet<IpAddr>,
+    pub services: BTreeSet<String>,
+}
+
+/// Thread-sicherer Credential Store, von allen Handlern geteilt
+#[derive(Debug, Default)]
+pub struct CredentialStore {
+    records: Mutex<BTreeMap<(String, String), CredentialRecord>>,
+}
+
+impl CredentialStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Erfasste Credentials festhalten
+    pub fn record(&self, source_ip: IpAddr, service: &str, username: &str, password: &str) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let mut records = self.records.lock().unwrap();
+        let record = records
+            .entry((username.to_string(), password.to_string()))
+            .or_insert_with(|| CredentialRecord {
+                username: username.to_string(),
+                password: password.to_string(),
+                count: 0,
+                first_seen: now,
+                last_seen: now,
+                source_ips: BTreeSet::new(),
+                services: BTreeSet::new(),
+            });
+
+        record.count += 1;
+        record.last_seen = now;
+        record.source_ips.insert(source_ip);
+        record.services.insert(service.to_string());
+    }
+
+    /// Alle aggregierten Einträge (sortiert nach Username, Passwort)
+    pub fn records(&self) -> Vec<CredentialRecord> {
+        self.records.lock().unwrap().values().cloned().collect()
+    }
+
+    /// In das gewünschte Format exportieren
+    pub fn export(
+        &self,
+        format: CredentialExportFormat,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let records = self.records();
+        match format {
+            CredentialExportFormat::Json => Ok(serde_json::to_string_pretty(&records)?),
+            CredentialExportFormat::Csv => Ok(to_csv(&records)),
+        }
+    }
+}
+
+impl fmt::Display for CredentialExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialExportFormat::Csv => write!(f, "csv"),
+            CredentialExportFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+fn to_csv(records: &[CredentialRecord]) -> String {
+    let mut output =
+        String::from("username,password,count,first_seen,last_seen,source_ips,services\n");
+    for record in records {
+        let source_ips: Vec<String> = record.source_ips.iter().map(|ip| ip.to_string()).collect();
+        let services: Vec<&str> = record.services.iter().map(String::as_str).collect();
+        output.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&record.username),
+            csv_field(&record.password),
+            record.count,
+            record.first_seen,
+            record.last_seen,
+            csv_field(&source_ips.join(";")),
+            csv_field(&services.join(";")),
+        ));
+    }
+    output
+}
+
+/// Feld nach RFC 4180 quoten, falls nötig
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
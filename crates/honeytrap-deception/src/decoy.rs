@@ -0,0 +1,173 @@
+//! Konsistente Decoys pro Angreifer
+//!
+//! Derselbe Angreifer soll bei jedem Reconnect dasselbe System sehen
+//! (Hostname, MAC, Uptime, PIDs). Die Details werden deterministisch aus
+//! Seed + Quell-IP abgeleitet. Ohne konfigurierten Seed wird pro Prozess ein
+//! zufälliger gewählt, damit Angreifer die Decoys nicht vorhersagen können.
+//!
+//! Die Ableitung nutzt SipHash-1-3 mit festen Keys statt `DefaultHasher`,
+//! dessen Algorithmus sich zwischen Rust-Releases ändern darf - sonst
+//! sähe ein Angreifer nach einem Toolchain-Update ein anderes System.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use siphasher::sip::SipHasher13;
+use std::hash::Hasher;
+use std::net::IpAddr;
+use std::time::Duration;
+
+const HOSTNAME_PREFIXES: &[&str] = &[
+    "web", "db", "app", "srv", "prod-web", "backup", "ubuntu", "node", "api", "mail",
+];
+/// OUIs typischer Hypervisoren (VirtualBox, VMware, QEMU/KVM)
+const VM_OUIS: &[[u8; 3]] = &[[0x08, 0x00, 0x27], [0x00, 0x50, 0x56], [0x52, 0x54, 0x00]];
+/// Nach spätestens ~400 Tagen "rebootet" der Decoy
+const UPTIME_PERIOD_SECS: u64 = 400 * 86_400;
+const MIN_UPTIME_SECS: u64 = 3 * 86_400;
+/// Zweiter SipHash-Key (Domain-Trennung, erster Key = Seed)
+const DECOY_HASH_KEY: u64 = 0x6465_636f_795f_7631;
+
+/// Seed für die Ableitung der Decoy-Details
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecoySeed(u64);
+
+impl DecoySeed {
+    /// Fester Seed: Decoys bleiben auch über Neustarts gleich
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Zufälliger Seed (Decoys nur innerhalb eines Prozesses stabil)
+    pub fn random() -> Self {
+        Self(rand::thread_rng().gen())
+    }
+
+    /// Decoy für eine Quell-IP
+    pub fn profile_for(&self, ip: IpAddr) -> DecoyProfile {
+        let mut hasher = SipHasher13::new_with_keys(self.0, DECOY_HASH_KEY);
+        match ip {
+            IpAddr::V4(ip) => {
+                hasher.write_u8(4);
+                hasher.write(&ip.octets());
+            }
+            IpAddr::V6(ip) => {
+                hasher.write_u8(6);
+                hasher.write(&ip.octets());
+            }
+        }
+        DecoyProfile::from_seed(hasher.finish())
+    }
+}
+
+/// Randomisierte, aber pro Angreifer stabile Decoy-Details
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecoyProfile {
+    pub hostname: String,
+    pub mac: [u8; 6],
+    /// Phase des Uptime-Zyklus (Sekunden)
+    uptime_phase: u64,
+    /// PID der Login-Shell
+    pub shell_pid: u32,
+}
+
+impl Default for DecoyProfile {
+    /// Feste Defaults ohne Seed
+    fn default() -> Self {
+        Self {
+            hostname: "ubuntu-server".to_string(),
+            mac: [0x08, 0x00, 0x27, 0x4e, 0x66, 0xa1],
+            uptime_phase: 42 * 86_400,
+            shell_pid: 1234,
+        }
+    }
+}
+
+impl DecoyProfile {
+    /// Details aus einem Seed ableiten
+    pub fn from_seed(seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let prefix = HOSTNAME_PREFIXES[rng.gen_range(0..HOSTNAME_PREFIXES.len())];
+        let hostname = format!("{}-{:02}", prefix, rng.gen_range(1..100));
+
+        let oui = VM_OUIS[rng.gen_range(0..VM_OUIS.len())];
+        let mac = [oui[0], oui[1], oui[2], rng.gen(), rng.gen(), rng.gen()];
+
+        Self {
+            hostname,
+            mac,
+            uptime_phase: rng.gen_range(0..UPTIME_PERIOD_SECS),
+            shell_pid: rng.gen_range(1_000..30_000),
+        }
+    }
+
+    /// MAC im `ifconfig`-Format
+    pub fn mac_string(&self) -> String {
+        self.mac
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+
+    /// IPv6 Link-Local Adresse aus der MAC (EUI-64)
+    pub fn link_local(&self) -> String {
+        let m = self.mac;
+        format!(
+            "fe80::{:x}:{:x}:{:x}:{:x}",
+            u16::from_be_bytes([m[0] ^ 0x02, m[1]]),
+            u16::from_be_bytes([m[2], 0xff]),
+            u16::from_be_bytes([0xfe, m[3]]),
+            u16::from_be_bytes([m[4], m[5]])
+        )
+    }
+
+    /// Uptime zu einem Unix-Timestamp
+    ///
+    /// Wächst mit der echten Zeit weiter, damit ein Reconnect Tage später
+    /// eine entsprechend höhere Uptime sieht.
+    pub fn uptime_at(&self, unix_secs: u64) -> Duration {
+        let cycle = UPTIME_PERIOD_SECS - MIN_UPTIME_SECS;
+        Duration::from_secs(MIN_UPTIME_SECS + (unix_secs + self.uptime_phase) % cycle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interactions::SshInteractionHandler;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_same_ip_sees_same_decoy_across_sessions() {
+        let seed = DecoySeed::new(0x5eed);
+        let attacker: IpAddr = "203.0.113.7".parse().unwrap();
+        let other: IpAddr = "198.51.100.23".parse().unwrap();
+
+        let mut first = SshInteractionHandler::new("s1".to_string())
+            .with_decoy(seed.profile_for(attacker));
+        let mut second = SshInteractionHandler::new("s2".to_string())
+            .with_decoy(seed.profile_for(attacker));
+        let mut third = SshInteractionHandler::new("s3".to_string())
+            .with_decoy(seed.profile_for(other));
+
+        let hostname = first.execute_command("hostname").await;
+        assert_eq!(second.execute_command("hostname").await, hostname);
+        assert_ne!(third.execute_command("hostname").await, hostname);
+        assert_eq!(
+            first.execute_command("ifconfig").await,
+            second.execute_command("ifconfig").await
+        );
+
+        let profile = seed.profile_for(attacker);
+        assert!(profile.uptime_at(1_000_600) > profile.uptime_at(1_000_000));
+        assert_ne!(DecoySeed::new(1).profile_for(attacker), profile);
+    }
+
+    #[test]
+    fn test_decoy_derivation_is_stable() {
+        // Fester Wert: ändert sich nur, wenn die Ableitung selbst geändert wird
+        let profile = DecoySeed::new(424242).profile_for("203.0.113.7".parse().unwrap());
+        assert_eq!(profile.hostname, "mail-62");
+        assert_eq!(profile.mac_string(), "00:50:56:2a:9b:4e");
+        assert_eq!(profile.shell_pid, 20779);
+    }
+}
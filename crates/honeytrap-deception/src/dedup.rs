@@ -0,0 +1,192 @@
+//! Deduplizierung von Capture-Events
+//!
+//! Bei Scan-Stürmen feuern identische Events (gleiche IP, gleicher Pfad,
+//! gleiche Signatur) hundertfach und fluten Logs und Webhooks. Der
+//! Deduplicator fasst identische Events innerhalb eines Zeitfensters zu einem
+//! Event mit `count` zusammen, bevor sie an Notifier und Sinks gehen.
+//!
+//! Identisch heißt: gleiche Quell-IP und gleiche `CaptureKind`. Session-ID
+//! und Timestamp zählen nicht, da Scanner meist pro Versuch neu verbinden;
+//! aus demselben Grund wird der Quellport bei Handshake-Fehlern ignoriert.
+
+use crate::capture::{CaptureEvent, CaptureKind};
+use crate::clock::{Clock, SystemClock};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Events mit gleichem Schlüssel werden zusammengefasst
+type DedupKey = (Option<IpAddr>, CaptureKind);
+
+fn dedup_key(event: &CaptureEvent) -> DedupKey {
+    let mut kind = event.kind.clone();
+    if let CaptureKind::HandshakeFailure { peer_addr, .. } = &mut kind {
+        peer_addr.set_port(0);
+    }
+    (event.source_ip, kind)
+}
+
+/// Offene Gruppe identischer Events
+#[derive(Debug)]
+struct Group {
+    /// Erstes Event der Gruppe (Session, Timestamp), `count` wird hochgezählt
+    event: CaptureEvent,
+    first_seen: Instant,
+}
+
+/// Fasst identische Events innerhalb eines Zeitfensters zusammen
+#[derive(Debug)]
+pub struct Deduplicator {
+    window: Duration,
+    clock: Arc<dyn Clock>,
+    groups: Mutex<HashMap<DedupKey, Group>>,
+}
+
+impl Deduplicator {
+    /// Deduplicator mit Zeitfenster (`Duration::ZERO` = keine Deduplizierung)
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            clock: Arc::new(SystemClock),
+            groups: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Mit eigener Zeitquelle (z.B. `MockClock` in Tests)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Zeitfenster
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Event aufnehmen
+    ///
+    /// Gibt alle Gruppen zurück, deren Fenster inzwischen abgelaufen ist -
+    /// diese sind fertig und können weitergereicht werden.
+    pub fn push(&self, event: CaptureEvent) -> Vec<CaptureEvent> {
+        if self.window.is_zero() {
+            return vec![event];
+        }
+
+        let now = self.clock.now();
+        let mut groups = self.groups.lock().unwrap();
+        let mut ready = self.drain_expired(&mut groups, now);
+
+        let key = dedup_key(&event);
+        match groups.get_mut(&key) {
+            Some(group) => group.event.count += event.count,
+            None => {
+                groups.insert(
+                    key,
+                    Group {
+                        event,
+                        first_seen: now,
+                    },
+                );
+            }
+        }
+
+        ready.sort_by_key(|event| event.timestamp);
+        ready
+    }
+
+    /// Gruppen mit abgelaufenem Fenster herausgeben (periodisch aufrufen)
+    pub fn flush_expired(&self) -> Vec<CaptureEvent> {
+        let now = self.clock.now();
+        let mut groups = self.groups.lock().unwrap();
+        let mut ready = self.drain_expired(&mut groups, now);
+        ready.sort_by_key(|event| event.timestamp);
+        ready
+    }
+
+    /// Alle offenen Gruppen herausgeben (z.B. beim Shutdown)
+    pub fn flush(&self) -> Vec<CaptureEvent> {
+        let mut ready: Vec<CaptureEvent> = self
+            .groups
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, group)| group.event)
+            .collect();
+        ready.sort_by_key(|event| event.timestamp);
+        ready
+    }
+
+    /// Anzahl offener Gruppen
+    pub fn pending(&self) -> usize {
+        self.groups.lock().unwrap().len()
+    }
+
+    fn drain_expired(
+        &self,
+        groups: &mut HashMap<DedupKey, Group>,
+        now: Instant,
+    ) -> Vec<CaptureEvent> {
+        let expired: Vec<DedupKey> = groups
+            .iter()
+            .filter(|(_, group)| now.saturating_duration_since(group.first_seen) >= self.window)
+            .map(|(key, _)| key.clone())
+            .collect();
+
+        expired
+            .into_iter()
+            .filter_map(|key| groups.remove(&key))
+            .map(|group| group.event)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+
+    fn probe(session_id: &str, ip: &str, path: &str) -> CaptureEvent {
+        CaptureEvent::new(
+            session_id,
+            CaptureKind::HttpRequest {
+                method: "GET".to_string(),
+                path: path.to_string(),
+            },
+        )
+        .with_source_ip(ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_identical_events_within_window_collapse_with_count() {
+        let clock = Arc::new(MockClock::new());
+        let dedup = Deduplicator::new(Duration::from_secs(60)).with_clock(clock.clone());
+
+        for i in 0..25 {
+            let session = format!("s{}", i);
+            assert!(dedup.push(probe(&session, "203.0.113.9", "/.env")).is_empty());
+            clock.advance(Duration::from_secs(1));
+        }
+        // Anderer Pfad bzw. andere IP bilden eigene Gruppen
+        dedup.push(probe("x", "203.0.113.9", "/wp-login.php"));
+        dedup.push(probe("y", "198.51.100.1", "/.env"));
+        assert_eq!(dedup.pending(), 3);
+        assert!(dedup.flush_expired().is_empty());
+
+        clock.advance(Duration::from_secs(60));
+        let next = probe("s99", "203.0.113.9", "/.env");
+        let ready = dedup.push(next);
+
+        assert_eq!(ready.len(), 3);
+        let env = ready
+            .iter()
+            .find(|e| e.session_id == "s0")
+            .expect("first occurrence is kept");
+        assert_eq!(env.count, 25);
+        assert!(ready.iter().filter(|e| e.session_id != "s0").all(|e| e.count == 1));
+
+        // Neues Fenster für das Event nach Ablauf
+        assert_eq!(dedup.pending(), 1);
+        assert_eq!(dedup.flush()[0].session_id, "s99");
+    }
+}
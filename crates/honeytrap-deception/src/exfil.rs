@@ -0,0 +1,121 @@
+//! Exfiltrations-Tracking
+//!
+//! Summiert die simulierten Bytes, die ein Angreifer aus sensiblen
+//! Ressourcen (Systemdateien, Secrets, DB-Dumps, Config-Endpoints) abzieht -
+//! das Volumen ist ein zentrales Severity-Signal.
+
+use honeytrap_metrics::METRICS;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Pfad-Fragmente, deren Inhalt als sensibel gilt
+const SENSITIVE_PATHS: &[&str] = &[
+    "/etc/passwd",
+    "/etc/shadow",
+    "/etc/hosts",
+    ".bash_history",
+    ".ssh/",
+    ".aws/",
+    ".env",
+    "id_rsa",
+    "credentials",
+    ".git/config",
+    ".kube/config",
+    ".docker/config.json",
+    "wp-config.php",
+    "/etc/ssh/sshd_config",
+];
+
+/// Ist der Pfad eine sensible Ressource?
+pub fn is_sensitive_path(path: &str) -> bool {
+    SENSITIVE_PATHS
+        .iter()
+        .any(|fragment| path.contains(fragment))
+}
+
+/// Exfiltrierte Bytes einer Session, von Session und Handlern geteilt
+#[derive(Debug, Clone, Default)]
+pub struct ExfilCounter {
+    bytes: Arc<AtomicU64>,
+}
+
+impl ExfilCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ausgelieferte Bytes einer sensiblen Ressource verbuchen
+    pub fn record(&self, service: &str, resource: &str, bytes: usize, session_id: &str) {
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        METRICS
+            .honeypots
+            .exfil_bytes
+            .with_label_values(&[service])
+            .inc_by(bytes as u64);
+
+        tracing::warn!(
+            "📤 Exfiltration: {} bytes from {} via {} (total: {}) (Session: {})",
+            bytes,
+            resource,
+            service,
+            self.bytes(),
+            session_id
+        );
+    }
+
+    /// Bisher exfiltrierte Bytes
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interactions::{
+        HttpInteractionHandler, HttpMethod, HttpRequest, SshInteractionHandler,
+    };
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_passwd_and_api_config_accumulate_into_session() {
+        let session =
+            crate::honeypots::test_session("exfil-test", "203.0.113.9:50000".parse().unwrap());
+
+        let mut ssh = SshInteractionHandler::new(session.id.clone())
+            .with_exfil_counter(session.exfil.clone());
+        let passwd = ssh.execute_command("cat /etc/passwd").await;
+        assert!(passwd.contains("root:x:0:0"));
+        assert_eq!(session.exfil.bytes(), passwd.len() as u64);
+
+        // Nicht-sensible Dateien zählen nicht
+        ssh.execute_command("cat .bashrc").await;
+        assert_eq!(ssh.exfil_bytes(), passwd.len() as u64);
+
+        let mut http = HttpInteractionHandler::new(session.id.clone())
+            .with_exfil_counter(session.exfil.clone());
+        let response = http
+            .handle_request(HttpRequest {
+                method: HttpMethod::GET,
+                path: "/api/config".to_string(),
+                headers: HashMap::new(),
+                body: None,
+            })
+            .await;
+        assert_eq!(response.status, 200);
+        assert_eq!(
+            session.exfil.bytes(),
+            (passwd.len() + response.body.len()) as u64
+        );
+        assert_eq!(http.get_stats().exfil_bytes, session.exfil.bytes());
+    }
+
+    #[test]
+    fn test_only_concrete_config_files_are_sensitive() {
+        assert!(is_sensitive_path("/root/.kube/config"));
+        assert!(is_sensitive_path("/var/www/html/wp-config.php"));
+        assert!(is_sensitive_path("/etc/ssh/sshd_config"));
+        assert!(!is_sensitive_path("/etc/ld.so.conf.d/config-notes"));
+        assert!(!is_sensitive_path("/usr/share/doc/configure.txt"));
+    }
+}
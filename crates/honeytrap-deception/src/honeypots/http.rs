@@ -1,35 +1,134 @@
 use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::limits::{within_limit, DEFAULT_MAX_HTTP_BODY};
+use crate::interactions::{HttpInteractionHandler, HttpRequest};
+use crate::personality::Personality;
 use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Max. Größe von Request-Line und Headern (Bytes)
+const MAX_HEAD_LEN: usize = 16 * 1024;
 
 /// HTTP Honeypot (High Interaction)
 pub struct HttpHoneypot {
     port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+    personality: Option<Personality>,
 }
 
 impl HttpHoneypot {
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            port,
+            credential_store: None,
+            personality: None,
+        }
+    }
+
+    /// Login-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+
+    /// `Server`/`X-Powered-By` und 404-Seite der Deployment-Persönlichkeit
+    pub fn with_personality(mut self, personality: Personality) -> Self {
+        self.personality = Some(personality);
+        self
+    }
+}
+
+/// Länge des ersten vollständigen Requests im Puffer (Header + Body)
+///
+/// `Err` mit der deklarierten Body-Länge, wenn sie das Limit überschreitet.
+fn request_len(buf: &[u8]) -> Option<Result<usize, usize>> {
+    let head_end = buf.windows(4).position(|w| w == b"\r\n\r\n")? + 4;
+    let head = String::from_utf8_lossy(&buf[..head_end]);
+    let body_len = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+
+    if body_len > DEFAULT_MAX_HTTP_BODY {
+        return Some(Err(body_len));
     }
+    (buf.len() >= head_end + body_len).then_some(Ok(head_end + body_len))
 }
 
 #[async_trait]
 impl Honeypot for HttpHoneypot {
     async fn handle(
         &self,
-        _connection: Connection,
+        connection: Connection,
         session: Session,
     ) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("🌐 HTTP Honeypot: Handling connection {}", session.id);
 
-        // HTTP Response senden
-        // TODO: Echte HTTP-Implementierung
-        tracing::debug!("📤 Sending HTTP 200 OK");
+        let mut handler = HttpInteractionHandler::new(session.id.clone())
+            .with_exfil_counter(session.exfil.clone());
+        if let Some(personality) = &self.personality {
+            handler = handler.with_personality(personality);
+        }
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("HTTP Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        'session: loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+
+            // Keep-Alive/Pipelining: alle vollständigen Requests beantworten
+            loop {
+                let len = match request_len(&buf) {
+                    Some(Ok(len)) => len,
+                    Some(Err(body_len)) => {
+                        // Loggt das oversized_input Event
+                        within_limit("http", &session.id, body_len, DEFAULT_MAX_HTTP_BODY);
+                        stream
+                            .write_all(
+                                b"HTTP/1.1 413 Payload Too Large\r\nConnection: close\r\n\r\n",
+                            )
+                            .await?;
+                        break 'session;
+                    }
+                    None if buf.len() > MAX_HEAD_LEN => break 'session,
+                    None => break,
+                };
 
-        // Fake Web Application
-        tracing::debug!("🖥️ Serving fake login page");
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                let raw: Vec<u8> = buf.drain(..len).collect();
+                let Some(request) = HttpRequest::parse(&String::from_utf8_lossy(&raw)) else {
+                    stream
+                        .write_all(b"HTTP/1.1 400 Bad Request\r\nConnection: close\r\n\r\n")
+                        .await?;
+                    break 'session;
+                };
+                let response = handler.handle_request(request).await;
+                stream.write_all(response.to_raw().as_bytes()).await?;
+                if handler.should_close() {
+                    break 'session;
+                }
+            }
+        }
 
-        tracing::info!("✅ HTTP Honeypot: Session {} completed", session.id);
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ HTTP Honeypot: Session {} completed ({} requests)",
+            session.id,
+            stats.request_count
+        );
 
         Ok(())
     }
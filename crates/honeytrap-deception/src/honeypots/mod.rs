@@ -1,14 +1,94 @@
 pub mod http;
+pub mod mqtt;
 pub mod mysql;
+pub mod postgres;
+pub mod redis;
+pub mod registry;
+pub mod smtp;
+pub mod snmp;
 pub mod ssh;
+pub mod telnet;
+pub mod udp;
 
 pub use http::HttpHoneypot;
+pub use mqtt::MqttHoneypot;
 pub use mysql::MysqlHoneypot;
+pub use postgres::PostgresHoneypot;
+pub use redis::RedisHoneypot;
+pub use registry::{HoneypotContext, HoneypotFactory, HoneypotRegistry};
+pub use smtp::SmtpHoneypot;
+pub use snmp::SnmpHoneypot;
 pub use ssh::SshHoneypot;
+pub use telnet::TelnetHoneypot;
+pub use udp::{DatagramGate, DatagramHandler, UdpListener};
 
+use crate::traffic::TrackedStream;
 use async_trait::async_trait;
+use honeytrap_metrics::METRICS;
+use serde::Deserialize;
 use std::fmt;
+use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// In-Memory Byte-Stream (z.B. `MemoryTransport` in Integrationstests)
+pub type MemoryStream = Arc<Mutex<DuplexStream>>;
+
+/// Byte-Stream zum Peer: In-Memory Stream oder vom Peer geöffneter QUIC Bi-Stream
+pub enum PeerStream {
+    Memory(OwnedMutexGuard<DuplexStream>),
+    #[cfg(feature = "quic")]
+    Quic(quinn::SendStream, quinn::RecvStream),
+}
+
+impl AsyncRead for PeerStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            PeerStream::Memory(stream) => Pin::new(&mut **stream).poll_read(cx, buf),
+            #[cfg(feature = "quic")]
+            PeerStream::Quic(_, recv) => Pin::new(recv).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for PeerStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.get_mut() {
+            PeerStream::Memory(stream) => Pin::new(&mut **stream).poll_write(cx, buf),
+            #[cfg(feature = "quic")]
+            PeerStream::Quic(send, _) => {
+                Pin::new(send).poll_write(cx, buf).map_err(std::io::Error::from)
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            PeerStream::Memory(stream) => Pin::new(&mut **stream).poll_flush(cx),
+            #[cfg(feature = "quic")]
+            PeerStream::Quic(send, _) => Pin::new(send).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            PeerStream::Memory(stream) => Pin::new(&mut **stream).poll_shutdown(cx),
+            #[cfg(feature = "quic")]
+            PeerStream::Quic(send, _) => Pin::new(send).poll_shutdown(cx),
+        }
+    }
+}
 
 /// Honeypot-Trait
 #[async_trait]
@@ -27,11 +107,18 @@ pub trait Honeypot: Send + Sync {
     fn service_type(&self) -> HoneypotType;
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum HoneypotType {
     Ssh,
     Http,
     Mysql,
+    Snmp,
+    Mqtt,
+    Telnet,
+    Smtp,
+    Redis,
+    Postgres,
 }
 
 impl fmt::Display for HoneypotType {
@@ -40,14 +127,99 @@ impl fmt::Display for HoneypotType {
             HoneypotType::Ssh => write!(f, "SSH"),
             HoneypotType::Http => write!(f, "HTTP"),
             HoneypotType::Mysql => write!(f, "MySQL"),
+            HoneypotType::Snmp => write!(f, "SNMP"),
+            HoneypotType::Mqtt => write!(f, "MQTT"),
+            HoneypotType::Telnet => write!(f, "Telnet"),
+            HoneypotType::Smtp => write!(f, "SMTP"),
+            HoneypotType::Redis => write!(f, "Redis"),
+            HoneypotType::Postgres => write!(f, "PostgreSQL"),
+        }
+    }
+}
+
+impl FromStr for HoneypotType {
+    type Err = String;
+
+    /// Name wie in `service_type` der Config (`ssh`, `postgres`, ...)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ssh" => Ok(HoneypotType::Ssh),
+            "http" => Ok(HoneypotType::Http),
+            "mysql" => Ok(HoneypotType::Mysql),
+            "snmp" => Ok(HoneypotType::Snmp),
+            "mqtt" => Ok(HoneypotType::Mqtt),
+            "telnet" => Ok(HoneypotType::Telnet),
+            "smtp" => Ok(HoneypotType::Smtp),
+            "redis" => Ok(HoneypotType::Redis),
+            "postgres" => Ok(HoneypotType::Postgres),
+            _ => Err(format!("unknown service type '{}'", s)),
         }
     }
 }
 
+/// Grund für das Schließen einer Connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseReason {
+    /// Session regulär beendet
+    Normal,
+    /// Quelle ist blockiert
+    Blocked,
+    /// Vom Rate Limiter abgewiesen
+    RateLimited,
+    /// Session-Timeout
+    Timeout,
+    /// Fehler im Handler
+    Error,
+    /// Max. Engagement-Zeit der Session aufgebraucht
+    EngagementBudgetExhausted,
+    /// Bekannter Research-Scanner, nur minimal bedient
+    ResearchScanner,
+}
+
+impl CloseReason {
+    /// QUIC Application Error Code
+    pub fn code(&self) -> u32 {
+        match self {
+            CloseReason::Normal => 0,
+            CloseReason::Blocked => 1,
+            CloseReason::RateLimited => 2,
+            CloseReason::Timeout => 3,
+            CloseReason::Error => 4,
+            CloseReason::EngagementBudgetExhausted => 5,
+            CloseReason::ResearchScanner => 6,
+        }
+    }
+
+    /// Label für `honeytrap_connections_closed_total`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::Normal => "normal",
+            CloseReason::Blocked => "blocked",
+            CloseReason::RateLimited => "rate_limited",
+            CloseReason::Timeout => "timeout",
+            CloseReason::Error => "error",
+            CloseReason::EngagementBudgetExhausted => "engagement_budget_exhausted",
+            CloseReason::ResearchScanner => "research_scanner",
+        }
+    }
+}
+
+impl fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Connection mit Quinn QUIC-Support
 #[derive(Debug, Clone)]
 pub struct Connection {
     pub peer_addr: std::net::SocketAddr,
+    /// Lokale Adresse, zu der der Angreifer verbunden hat (Ziel-Port)
+    pub local_addr: Option<std::net::SocketAddr>,
+    /// Client-Zertifikat per mTLS verifiziert
+    pub mtls_verified: bool,
+    /// SANs (DNS/URI) des verifizierten Client-Zertifikats
+    pub client_sans: Vec<String>,
     /// Optional Quinn QUIC Connection
     /// Wird nur gesetzt wenn QUIC verwendet wird
     #[cfg(feature = "quic")]
@@ -55,6 +227,12 @@ pub struct Connection {
     
     #[cfg(not(feature = "quic"))]
     pub quinn_connection: Option<Arc<()>>, // Placeholder wenn QUIC disabled
+
+    /// Optionaler In-Memory Stream (ohne UDP/QUIC)
+    pub memory_stream: Option<MemoryStream>,
+
+    /// Bytes, Pakete und Timing des Peers (von der Router-Session geteilt)
+    pub traffic: crate::traffic::TrafficCounter,
 }
 
 impl Connection {
@@ -62,7 +240,25 @@ impl Connection {
     pub fn new(peer_addr: std::net::SocketAddr) -> Self {
         Self {
             peer_addr,
+            local_addr: None,
+            mtls_verified: false,
+            client_sans: Vec::new(),
             quinn_connection: None,
+            memory_stream: None,
+            traffic: Default::default(),
+        }
+    }
+
+    /// Neue Connection über In-Memory Stream
+    pub fn with_memory(peer_addr: std::net::SocketAddr, stream: DuplexStream) -> Self {
+        Self {
+            peer_addr,
+            local_addr: None,
+            mtls_verified: false,
+            client_sans: Vec::new(),
+            quinn_connection: None,
+            memory_stream: Some(Arc::new(Mutex::new(stream))),
+            traffic: Default::default(),
         }
     }
 
@@ -71,15 +267,65 @@ impl Connection {
     pub fn with_quic(peer_addr: std::net::SocketAddr, quinn: Arc<quinn::Connection>) -> Self {
         Self {
             peer_addr,
+            local_addr: None,
+            mtls_verified: false,
+            client_sans: Vec::new(),
             quinn_connection: Some(quinn),
+            memory_stream: None,
+            traffic: Default::default(),
         }
     }
 
+    /// Mit lokaler Adresse (Ziel-Port für das Honeypot-Routing)
+    pub fn with_local_addr(mut self, local_addr: std::net::SocketAddr) -> Self {
+        self.local_addr = Some(local_addr);
+        self
+    }
+
+    /// Mit verifiziertem Client-Zertifikat (mTLS) und dessen SANs
+    pub fn with_client_identity(mut self, sans: Vec<String>) -> Self {
+        self.mtls_verified = true;
+        self.client_sans = sans;
+        self
+    }
+
+    /// Ziel-Port der Verbindung
+    pub fn dest_port(&self) -> Option<u16> {
+        self.local_addr.map(|addr| addr.port())
+    }
+
+    /// Byte-Stream zum Peer, Reads/Writes werden in `traffic` gezählt
+    ///
+    /// In-Memory Stream oder der erste Bi-Stream, den der Peer über QUIC
+    /// öffnet. `None`, wenn die Connection keinen Byte-Stream hat.
+    pub async fn peer_stream(
+        &self,
+    ) -> Result<Option<TrackedStream<PeerStream>>, Box<dyn std::error::Error>> {
+        if let Some(stream) = &self.memory_stream {
+            let guard = stream.clone().lock_owned().await;
+            return Ok(Some(self.traffic.track(PeerStream::Memory(guard))));
+        }
+
+        #[cfg(feature = "quic")]
+        if let Some(conn) = &self.quinn_connection {
+            let (send, recv) = conn.accept_bi().await?;
+            return Ok(Some(self.traffic.track(PeerStream::Quic(send, recv))));
+        }
+
+        Ok(None)
+    }
+
     /// QUIC Bi-Stream öffnen
     #[cfg(feature = "quic")]
-    pub async fn open_bi(&self) -> Result<(quinn::SendStream, quinn::RecvStream), Box<dyn std::error::Error>> {
+    pub async fn open_bi(
+        &self,
+    ) -> Result<
+        (TrackedStream<quinn::SendStream>, TrackedStream<quinn::RecvStream>),
+        Box<dyn std::error::Error>,
+    > {
         if let Some(ref conn) = self.quinn_connection {
-            Ok(conn.open_bi().await?)
+            let (send, recv) = conn.open_bi().await?;
+            Ok((self.traffic.track(send), self.traffic.track(recv)))
         } else {
             Err("No QUIC connection available".into())
         }
@@ -87,9 +333,11 @@ impl Connection {
 
     /// QUIC Uni-Stream öffnen
     #[cfg(feature = "quic")]
-    pub async fn open_uni(&self) -> Result<quinn::SendStream, Box<dyn std::error::Error>> {
+    pub async fn open_uni(
+        &self,
+    ) -> Result<TrackedStream<quinn::SendStream>, Box<dyn std::error::Error>> {
         if let Some(ref conn) = self.quinn_connection {
-            Ok(conn.open_uni().await?)
+            Ok(self.traffic.track(conn.open_uni().await?))
         } else {
             Err("No QUIC connection available".into())
         }
@@ -97,9 +345,15 @@ impl Connection {
 
     /// QUIC Bi-Stream akzeptieren
     #[cfg(feature = "quic")]
-    pub async fn accept_bi(&self) -> Result<(quinn::SendStream, quinn::RecvStream), Box<dyn std::error::Error>> {
+    pub async fn accept_bi(
+        &self,
+    ) -> Result<
+        (TrackedStream<quinn::SendStream>, TrackedStream<quinn::RecvStream>),
+        Box<dyn std::error::Error>,
+    > {
         if let Some(ref conn) = self.quinn_connection {
-            Ok(conn.accept_bi().await?)
+            let (send, recv) = conn.accept_bi().await?;
+            Ok((self.traffic.track(send), self.traffic.track(recv)))
         } else {
             Err("No QUIC connection available".into())
         }
@@ -107,20 +361,45 @@ impl Connection {
 
     /// QUIC Uni-Stream akzeptieren
     #[cfg(feature = "quic")]
-    pub async fn accept_uni(&self) -> Result<quinn::RecvStream, Box<dyn std::error::Error>> {
+    pub async fn accept_uni(
+        &self,
+    ) -> Result<TrackedStream<quinn::RecvStream>, Box<dyn std::error::Error>> {
         if let Some(ref conn) = self.quinn_connection {
-            Ok(conn.accept_uni().await?)
+            Ok(self.traffic.track(conn.accept_uni().await?))
         } else {
             Err("No QUIC connection available".into())
         }
     }
 
-    /// Connection schließen
+    /// Connection regulär schließen
     pub async fn close(&self) {
+        self.close_with(CloseReason::Normal).await;
+    }
+
+    /// Connection mit Grund schließen
+    ///
+    /// QUIC-Peers erhalten Code und Grund im Close Frame, In-Memory Streams
+    /// werden geflusht und sauber heruntergefahren (EOF beim Peer).
+    pub async fn close_with(&self, reason: CloseReason) {
         #[cfg(feature = "quic")]
         if let Some(ref conn) = self.quinn_connection {
-            conn.close(0u32.into(), b"connection closed");
+            conn.close(reason.code().into(), reason.as_str().as_bytes());
         }
+
+        if let Some(ref stream) = self.memory_stream {
+            use tokio::io::AsyncWriteExt;
+            let mut stream = stream.lock().await;
+            if let Err(e) = stream.shutdown().await {
+                tracing::debug!("In-memory stream shutdown failed for {}: {}", self.peer_addr, e);
+            }
+        }
+
+        METRICS
+            .connections
+            .closed
+            .with_label_values(&[reason.as_str()])
+            .inc();
+        tracing::debug!("🔒 Connection to {} closed ({})", self.peer_addr, reason);
     }
 }
 
@@ -134,4 +413,71 @@ pub struct Session {
     pub bytes_received: u64,
     pub is_suspicious: bool,
     pub anomaly_score: f64,
+    /// Exfiltrierte Bytes (von den Handlern geteilt)
+    pub exfil: crate::exfil::ExfilCounter,
+    /// Engagement-Tiefe (vom RL-Agent des Routers gewählt)
+    pub strategy: crate::ResponseStrategy,
+    /// Service-Profil des Ports (Snapshot beim Session-Start)
+    pub profile: Option<std::sync::Arc<crate::ServiceDefinition>>,
+}
+
+/// Verdächtige Session für Tests
+#[cfg(test)]
+pub(crate) fn test_session(id: &str, peer_addr: std::net::SocketAddr) -> Session {
+    Session {
+        id: id.to_string(),
+        peer_addr,
+        started_at: std::time::Instant::now(),
+        bytes_sent: 0,
+        bytes_received: 0,
+        is_suspicious: true,
+        anomaly_score: 0.9,
+        exfil: Default::default(),
+        strategy: Default::default(),
+        profile: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    #[tokio::test]
+    async fn test_close_with_blocked_records_reason() {
+        let closed = |reason: &str| {
+            METRICS
+                .connections
+                .closed
+                .with_label_values(&[reason])
+                .get()
+        };
+        let blocked_before = closed("blocked");
+
+        let (mut peer, server) = tokio::io::duplex(64);
+        let connection = Connection::with_memory("192.0.2.77:40000".parse().unwrap(), server);
+        connection.close_with(CloseReason::Blocked).await;
+
+        assert_eq!(closed("blocked"), blocked_before + 1);
+        assert_eq!(CloseReason::Blocked.code(), 1);
+
+        // Peer sieht ein sauberes EOF
+        let mut buf = [0u8; 8];
+        assert_eq!(peer.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[test]
+    fn test_honeypot_type_from_str_matches_config_names() {
+        for name in [
+            "ssh", "http", "mysql", "snmp", "mqtt", "telnet", "smtp", "redis", "postgres",
+        ] {
+            let parsed: HoneypotType = name.parse().unwrap();
+            let deserialized: HoneypotType = serde_json::from_value(name.into()).unwrap();
+            assert_eq!(parsed, deserialized);
+        }
+        assert_eq!(
+            "gopher".parse::<HoneypotType>(),
+            Err("unknown service type 'gopher'".to_string())
+        );
+    }
 }
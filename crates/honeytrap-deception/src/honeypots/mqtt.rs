@@ -0,0 +1,91 @@
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::MqttInteractionHandler;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// MQTT Honeypot (IoT-Broker, Credential- und Topic-Capture)
+pub struct MqttHoneypot {
+    port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+}
+
+impl MqttHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            credential_store: None,
+        }
+    }
+
+    /// CONNECT-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+}
+
+#[async_trait]
+impl Honeypot for MqttHoneypot {
+    async fn handle(
+        &self,
+        connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("📡 MQTT Honeypot: Handling connection {}", session.id);
+
+        let mut handler = MqttInteractionHandler::new(session.id.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("MQTT Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+
+            // Alle vollständigen Pakete im Puffer beantworten
+            loop {
+                match handler.handle_bytes(&buf) {
+                    Ok((response, consumed)) => {
+                        buf.drain(..consumed);
+                        if let Some(response) = response {
+                            stream.write_all(&response).await?;
+                        }
+                    }
+                    Err(e) if e.is_incomplete() => break,
+                    // Broker trennen bei Protokollfehlern die Verbindung
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ MQTT Honeypot: Session {} completed ({} packets, {} subscriptions, {} publications)",
+            session.id,
+            stats.packet_count,
+            stats.subscriptions,
+            stats.publications
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Mqtt
+    }
+}
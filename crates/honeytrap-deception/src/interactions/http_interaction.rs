@@ -2,12 +2,19 @@
 //!
 //! Erweiterte HTTP Honeypot-Interaktionen mit Fake Web Applications
 
+use super::limits::{within_limit, DEFAULT_MAX_HTTP_BODY, DEFAULT_MAX_HTTP_REQUESTS};
+use crate::canary::{CanaryAccess, CanaryKind, CanaryRegistry};
+use crate::clock::{Sleeper, TokioSleeper};
+use crate::credentials::CredentialStore;
+use crate::exfil::ExfilCounter;
+use crate::personality::Personality;
 use std::collections::HashMap;
-use std::time::Duration;
-use tokio::time::sleep;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// HTTP Method
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HttpMethod {
     GET,
     POST,
@@ -15,6 +22,36 @@ pub enum HttpMethod {
     DELETE,
     HEAD,
     OPTIONS,
+    CONNECT,
+}
+
+impl HttpMethod {
+    /// Methode aus Request-Line parsen (case-insensitive)
+    pub fn parse(method: &str) -> Option<Self> {
+        match method.to_ascii_uppercase().as_str() {
+            "GET" => Some(HttpMethod::GET),
+            "POST" => Some(HttpMethod::POST),
+            "PUT" => Some(HttpMethod::PUT),
+            "DELETE" => Some(HttpMethod::DELETE),
+            "HEAD" => Some(HttpMethod::HEAD),
+            "OPTIONS" => Some(HttpMethod::OPTIONS),
+            "CONNECT" => Some(HttpMethod::CONNECT),
+            _ => None,
+        }
+    }
+
+    /// Methode wie in der Request-Line
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::GET => "GET",
+            HttpMethod::POST => "POST",
+            HttpMethod::PUT => "PUT",
+            HttpMethod::DELETE => "DELETE",
+            HttpMethod::HEAD => "HEAD",
+            HttpMethod::OPTIONS => "OPTIONS",
+            HttpMethod::CONNECT => "CONNECT",
+        }
+    }
 }
 
 /// HTTP Request
@@ -26,6 +63,225 @@ pub struct HttpRequest {
     pub body: Option<String>,
 }
 
+impl HttpRequest {
+    /// Rohen HTTP/1.x Request parsen (Request-Line, Header, optionaler Body)
+    pub fn parse(raw: &str) -> Option<Self> {
+        let (head, body) = match raw.split_once("\r\n\r\n") {
+            Some((head, body)) => (head, body),
+            None => raw.split_once("\n\n").unwrap_or((raw, "")),
+        };
+
+        let mut lines = head.lines();
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = HttpMethod::parse(request_line.next()?)?;
+        let path = request_line.next()?.to_string();
+
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        Some(Self {
+            method,
+            path,
+            headers,
+            body: (!body.is_empty()).then(|| body.to_string()),
+        })
+    }
+}
+
+/// Standardmäßig geschwärzte Header
+pub const DEFAULT_REDACTED_HEADERS: &[&str] = &["Authorization", "Cookie", "Proxy-Authorization"];
+
+/// Vollständig erfasster Request (Replay, Signatur-Extraktion)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpCapture {
+    pub session_id: String,
+    /// Unix-Timestamp in Sekunden
+    pub timestamp: u64,
+    pub method: HttpMethod,
+    pub path: String,
+    /// Header sortiert nach Name, sensible Werte geschwärzt
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpCapture {
+    /// Request erfassen, Header aus `redacted` (case-insensitive) schwärzen
+    pub fn new(request: &HttpRequest, session_id: &str, redacted: &[String]) -> Self {
+        let mut headers: Vec<(String, String)> = request
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if redacted.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+                    "[REDACTED]".to_string()
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        headers.sort();
+
+        Self {
+            session_id: session_id.to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            method: request.method,
+            path: request.path.clone(),
+            headers,
+            body: request.body.clone(),
+        }
+    }
+
+    /// Request als HTTP/1.1 rekonstruieren (wieder mit `HttpRequest::parse` lesbar)
+    pub fn to_raw(&self) -> String {
+        let mut raw = format!("{} {} HTTP/1.1\r\n", self.method.as_str(), self.path);
+        for (name, value) in &self.headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        raw.push_str("\r\n");
+        raw.push_str(self.body.as_deref().unwrap_or_default());
+        raw
+    }
+}
+
+/// Einstellungen für die Request-Erfassung
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestCapture {
+    /// Erfasste Requests zusätzlich im Handler speichern
+    pub store: bool,
+    /// Zu schwärzende Header (case-insensitive)
+    pub redacted_headers: Vec<String>,
+}
+
+impl Default for RequestCapture {
+    fn default() -> Self {
+        Self {
+            store: false,
+            redacted_headers: DEFAULT_REDACTED_HEADERS.iter().map(|h| h.to_string()).collect(),
+        }
+    }
+}
+
+/// Antwort auf CONNECT Proxy-Probes (je nach Honeypot-Profil)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProxyProbeResponse {
+    /// Offenen Proxy vortäuschen (`200 Connection established`)
+    #[default]
+    Established,
+    /// Bewusst fehlschlagen lassen (`502 Bad Gateway`)
+    Refused,
+}
+
+/// Route, die einen Query-Parameter in die Antwort spiegelt (Suchseite etc.)
+///
+/// Der Wert wird HTML-escaped und genau einmal für `{{value}}` eingesetzt -
+/// Payloads werden erfasst, aber nie ausgeführt oder als Markup ausgeliefert.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReflectedRoute {
+    pub path: String,
+    pub param: String,
+    pub template: String,
+}
+
+impl ReflectedRoute {
+    pub fn new(path: &str, param: &str, template: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            param: param.to_string(),
+            template: template.to_string(),
+        }
+    }
+
+    /// Suchseite des Corporate Portals
+    pub fn search() -> Self {
+        let template = r#"<!DOCTYPE html>
+<html>
+<head>
+    <title>Search - Corporate Portal</title>
+</head>
+<body>
+    <h2>Search results for "{{value}}"</h2>
+    <p>No documents found.</p>
+</body>
+</html>"#;
+
+        Self::new("/search", "q", template)
+    }
+
+    /// Template mit escaptem Wert rendern
+    pub fn render(&self, value: &str) -> String {
+        self.template.replacen("{{value}}", &html_escape(value), 1)
+    }
+}
+
+/// Muster für Template-Injection (SSTI) und XSS in reflektierten Werten
+const INJECTION_PATTERNS: &[&str] = &[
+    "{{", "{%", "${", "#{", "<%", "<script", "javascript:", "onerror=", "onload=",
+];
+
+/// Sieht der Wert nach Template-/Script-Injection aus?
+fn looks_like_injection(value: &str) -> bool {
+    let lower = value.to_lowercase();
+    INJECTION_PATTERNS.iter().any(|pattern| lower.contains(pattern))
+}
+
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Query-Parameter aus dem Pfad lesen (URL-decoded)
+fn query_param(path: &str, name: &str) -> Option<String> {
+    let (_, query) = path.split_once('?')?;
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        (key == name).then(|| {
+            urlencoding::decode(&value.replace('+', " "))
+                .map(|v| v.into_owned())
+                .unwrap_or_else(|_| value.to_string())
+        })
+    })
+}
+
+/// Reaktion auf zu viele Requests über eine Connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestLimitAction {
+    /// Jede weitere Antwort zusätzlich verzögern
+    Tarpit(Duration),
+    /// `429` mit `Connection: close` und Verbindung beenden
+    Close,
+}
+
+/// Requests-pro-Connection Limit (Pipelining/Keep-Alive Missbrauch)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestLimit {
+    pub max_requests: usize,
+    pub action: RequestLimitAction,
+}
+
+impl Default for RequestLimit {
+    fn default() -> Self {
+        Self {
+            max_requests: DEFAULT_MAX_HTTP_REQUESTS,
+            action: RequestLimitAction::Tarpit(Duration::from_secs(5)),
+        }
+    }
+}
+
 /// HTTP Response
 #[derive(Debug, Clone)]
 pub struct HttpResponse {
@@ -35,11 +291,159 @@ pub struct HttpResponse {
     pub body: String,
 }
 
+impl HttpResponse {
+    /// Response als HTTP/1.1 serialisieren (mit `Content-Length`)
+    pub fn to_raw(&self) -> String {
+        let mut raw = format!("HTTP/1.1 {} {}\r\n", self.status, self.status_text);
+        for (name, value) in &self.headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        raw.push_str(&format!("Content-Length: {}\r\n\r\n", self.body.len()));
+        raw.push_str(&self.body);
+        raw
+    }
+}
+
+/// Einzelner Teil eines multipart/form-data Bodys
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultipartPart {
+    pub name: String,
+    pub filename: Option<String>,
+    pub content_type: Option<String>,
+    pub content: Vec<u8>,
+}
+
+impl MultipartPart {
+    /// Ist der Teil ein Datei-Upload?
+    pub fn is_file(&self) -> bool {
+        self.filename.is_some()
+    }
+
+    /// Sieht der Upload nach ausführbarem Code / Web-Shell aus?
+    pub fn is_executable(&self) -> bool {
+        const SCRIPT_CONTENT_TYPES: &[&str] = &[
+            "application/x-php",
+            "application/x-httpd-php",
+            "text/x-php",
+            "application/x-sh",
+            "text/x-shellscript",
+            "application/x-executable",
+            "application/x-msdownload",
+            "application/x-elf",
+            "text/x-python",
+            "application/x-perl",
+            "application/java-archive",
+        ];
+        const SCRIPT_EXTENSIONS: &[&str] = &[
+            "php", "phtml", "php5", "jsp", "jspx", "asp", "aspx", "sh", "py", "pl", "cgi", "exe",
+            "elf", "war", "jar",
+        ];
+
+        let by_type = self.content_type.as_deref().is_some_and(|ct| {
+            let ct = ct.to_lowercase();
+            SCRIPT_CONTENT_TYPES.iter().any(|t| ct.starts_with(t))
+        });
+        let by_extension = self.filename.as_deref().is_some_and(|name| {
+            name.rsplit_once('.')
+                .is_some_and(|(_, ext)| SCRIPT_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        });
+
+        by_type || by_extension
+    }
+}
+
+/// multipart/form-data Body parsen
+///
+/// Gibt `None` zurück, wenn der Content-Type kein multipart/form-data mit
+/// Boundary ist.
+pub fn parse_multipart(content_type: &str, body: &str) -> Option<Vec<MultipartPart>> {
+    if !content_type.to_lowercase().starts_with("multipart/form-data") {
+        return None;
+    }
+    let boundary = content_type.split(';').find_map(|param| {
+        param
+            .trim()
+            .strip_prefix("boundary=")
+            .map(|b| b.trim_matches('"').to_string())
+    })?;
+    let delimiter = format!("--{}", boundary);
+
+    let mut parts = Vec::new();
+    for raw in body.split(delimiter.as_str()).skip(1) {
+        if raw.starts_with("--") {
+            break; // Schluss-Boundary
+        }
+        let raw = raw.strip_prefix("\r\n").or_else(|| raw.strip_prefix('\n')).unwrap_or(raw);
+        let (head, content) = match raw.split_once("\r\n\r\n") {
+            Some(split) => split,
+            None => match raw.split_once("\n\n") {
+                Some(split) => split,
+                None => continue,
+            },
+        };
+        let content = content
+            .strip_suffix("\r\n")
+            .or_else(|| content.strip_suffix('\n'))
+            .unwrap_or(content);
+
+        let mut part = MultipartPart {
+            name: String::new(),
+            filename: None,
+            content_type: None,
+            content: content.as_bytes().to_vec(),
+        };
+        for line in head.lines() {
+            let Some((header, value)) = line.split_once(':') else {
+                continue;
+            };
+            match header.trim().to_lowercase().as_str() {
+                "content-disposition" => {
+                    for param in value.split(';').skip(1) {
+                        if let Some((key, val)) = param.trim().split_once('=') {
+                            let val = val.trim_matches('"').to_string();
+                            match key {
+                                "name" => part.name = val,
+                                "filename" => part.filename = Some(val),
+                                _ => {}
+                            }
+                        }
+                    }
+                }
+                "content-type" => part.content_type = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+        parts.push(part);
+    }
+
+    Some(parts)
+}
+
 /// HTTP Interaction Handler
 pub struct HttpInteractionHandler {
     session_id: String,
     request_count: usize,
     login_attempts: Vec<(String, String)>,
+    uploaded_files: Vec<MultipartPart>,
+    proxy_probes: Vec<String>,
+    proxy_response: ProxyProbeResponse,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    max_body_len: usize,
+    canaries: Option<Arc<CanaryRegistry>>,
+    config_api_key: String,
+    exfil: ExfilCounter,
+    sleeper: Arc<dyn Sleeper>,
+    request_limit: RequestLimit,
+    /// Requests jenseits des Limits
+    excess_requests: usize,
+    close_requested: bool,
+    reflected_routes: Vec<ReflectedRoute>,
+    /// Reflektierte Werte, die nach Injection aussehen
+    injection_payloads: Vec<String>,
+    request_capture: RequestCapture,
+    captured_requests: Vec<HttpCapture>,
+    /// `Server`, `X-Powered-By` und 404-Seite dieser Instanz
+    personality: Personality,
 }
 
 impl HttpInteractionHandler {
@@ -48,9 +452,93 @@ impl HttpInteractionHandler {
             session_id,
             request_count: 0,
             login_attempts: Vec::new(),
+            uploaded_files: Vec::new(),
+            proxy_probes: Vec::new(),
+            proxy_response: ProxyProbeResponse::default(),
+            credential_store: None,
+            max_body_len: DEFAULT_MAX_HTTP_BODY,
+            canaries: None,
+            config_api_key: "sk-fake-key-12345".to_string(),
+            exfil: ExfilCounter::new(),
+            sleeper: Arc::new(TokioSleeper),
+            request_limit: RequestLimit::default(),
+            excess_requests: 0,
+            close_requested: false,
+            reflected_routes: vec![ReflectedRoute::search()],
+            injection_payloads: Vec::new(),
+            request_capture: RequestCapture::default(),
+            captured_requests: Vec::new(),
+            personality: Personality::default(),
         }
     }
 
+    /// Header und Fehlerseiten aus der Deployment-Persönlichkeit übernehmen
+    pub fn with_personality(mut self, personality: &Personality) -> Self {
+        self.personality = personality.clone();
+        self
+    }
+
+    /// Decoy-API-Key in `/api/config` platzieren und Zugriffe überwachen
+    pub fn with_canary_registry(mut self, canaries: Arc<CanaryRegistry>) -> Self {
+        self.config_api_key = canaries.token(CanaryKind::ApiToken, "/api/config").value;
+        self.canaries = Some(canaries);
+        self
+    }
+
+    /// Mit max. Body-Länge (Bytes)
+    pub fn with_max_body_len(mut self, max_body_len: usize) -> Self {
+        self.max_body_len = max_body_len;
+        self
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Mit eigenem Sleeper (z.B. `RecordingSleeper` in Tests)
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.sleeper = sleeper;
+        self
+    }
+
+    /// Exfiltrierte Bytes in den Zähler der Session buchen
+    pub fn with_exfil_counter(mut self, exfil: ExfilCounter) -> Self {
+        self.exfil = exfil;
+        self
+    }
+
+    /// Mit eigenem Requests-pro-Connection Limit
+    pub fn with_request_limit(mut self, request_limit: RequestLimit) -> Self {
+        self.request_limit = request_limit;
+        self
+    }
+
+    /// Zusätzliche Route mit gespiegeltem Query-Parameter
+    pub fn with_reflected_route(mut self, route: ReflectedRoute) -> Self {
+        self.reflected_routes.retain(|r| r.path != route.path);
+        self.reflected_routes.push(route);
+        self
+    }
+
+    /// Mit eigenen Einstellungen für die Request-Erfassung
+    pub fn with_request_capture(mut self, request_capture: RequestCapture) -> Self {
+        self.request_capture = request_capture;
+        self
+    }
+
+    /// Soll die Connection nach der letzten Antwort geschlossen werden?
+    pub fn should_close(&self) -> bool {
+        self.close_requested
+    }
+
+    /// Verhalten bei CONNECT Proxy-Probes setzen
+    pub fn with_proxy_response(mut self, proxy_response: ProxyProbeResponse) -> Self {
+        self.proxy_response = proxy_response;
+        self
+    }
+
     /// Handle HTTP request
     pub async fn handle_request(&mut self, request: HttpRequest) -> HttpResponse {
         self.request_count += 1;
@@ -62,14 +550,48 @@ impl HttpInteractionHandler {
             self.session_id
         );
 
+        if self.request_count > self.request_limit.max_requests {
+            if let Some(response) = self.enforce_request_limit().await {
+                return response;
+            }
+        }
+
+        let body_len = request.body.as_ref().map_or(0, |b| b.len());
+        if !within_limit("http", &self.session_id, body_len, self.max_body_len) {
+            return HttpResponse {
+                status: 413,
+                status_text: "Payload Too Large".to_string(),
+                headers: self.default_headers("text/html"),
+                body: "<html><body><h1>413 Request Entity Too Large</h1></body></html>".to_string(),
+            };
+        }
+
+        self.capture_request(&request);
+
+        // Decoy-Secrets in eingehenden Requests = Exfiltration/Verwendung
+        if let Some(canaries) = &self.canaries {
+            let content = format!("{} {}", request.path, request.body.as_deref().unwrap_or(""));
+            canaries.check(&content, CanaryAccess::Exfiltration, &self.session_id);
+        }
+
         // Detect suspicious patterns
         self.detect_attacks(&request);
 
+        // Datei-Uploads (multipart/form-data) erfassen
+        self.capture_uploads(&request);
+
         // Simulate processing delay
-        sleep(Duration::from_millis(50)).await;
+        self.sleeper.sleep(Duration::from_millis(50)).await;
+
+        if request.method == HttpMethod::GET {
+            if let Some(response) = self.serve_reflected(&request.path) {
+                return response;
+            }
+        }
 
         // Route request
         match (request.method, request.path.as_str()) {
+            (HttpMethod::CONNECT, target) => self.handle_proxy_connect(target).await,
             (HttpMethod::GET, "/") => self.serve_homepage().await,
             (HttpMethod::GET, "/login") => self.serve_login_page().await,
             (HttpMethod::POST, "/login") => self.handle_login_post(request).await,
@@ -150,10 +672,13 @@ impl HttpInteractionHandler {
                 self.session_id
             );
 
+            if let Some((store, source_ip)) = &self.credential_store {
+                store.record(*source_ip, "HTTP", &username, &password);
+            }
             self.login_attempts.push((username.clone(), password.clone()));
 
             // Simulate auth delay
-            sleep(Duration::from_secs(1)).await;
+            self.sleeper.sleep(Duration::from_secs(1)).await;
         }
 
         let body = r#"<!DOCTYPE html>
@@ -199,22 +724,31 @@ impl HttpInteractionHandler {
     async fn serve_fake_config(&self) -> HttpResponse {
         tracing::warn!("🚨 Config file access attempt (Session: {})", self.session_id);
         
-        let body = r#"{
+        let body = format!(
+            r#"{{
     "version": "1.0.0",
-    "database": {
+    "database": {{
         "host": "localhost",
         "port": 3306,
         "name": "corporate_db"
-    },
-    "api_key": "sk-fake-key-12345",
+    }},
+    "api_key": "{}",
     "admin_email": "admin@corporate.com"
-}"#;
+}}"#,
+            self.config_api_key
+        );
+
+        if let Some(canaries) = &self.canaries {
+            canaries.check(&body, CanaryAccess::HttpFetch, &self.session_id);
+        }
+        self.exfil
+            .record("HTTP", "/api/config", body.len(), &self.session_id);
 
         HttpResponse {
             status: 200,
             status_text: "OK".to_string(),
             headers: self.default_headers("application/json"),
-            body: body.to_string(),
+            body,
         }
     }
 
@@ -240,20 +774,104 @@ impl HttpInteractionHandler {
         }
     }
 
+    async fn handle_proxy_connect(&mut self, target: &str) -> HttpResponse {
+        tracing::warn!(
+            "🚨 Open proxy probe: CONNECT {} (Session: {})",
+            target,
+            self.session_id
+        );
+        self.proxy_probes.push(target.to_string());
+
+        let (status, status_text) = match self.proxy_response {
+            ProxyProbeResponse::Established => (200, "Connection established"),
+            ProxyProbeResponse::Refused => (502, "Bad Gateway"),
+        };
+
+        let mut headers = HashMap::new();
+        headers.insert("Server".to_string(), self.personality.http_server.clone());
+
+        HttpResponse {
+            status,
+            status_text: status_text.to_string(),
+            headers,
+            body: String::new(),
+        }
+    }
+
+    /// Konfigurierte Route mit gespiegeltem Parameter beantworten
+    fn serve_reflected(&mut self, path: &str) -> Option<HttpResponse> {
+        let route_path = path.split('?').next().unwrap_or(path);
+        let route = self.reflected_routes.iter().find(|r| r.path == route_path)?;
+        let value = query_param(path, &route.param).unwrap_or_default();
+        let body = route.render(&value);
+
+        if looks_like_injection(&value) {
+            tracing::warn!(
+                "🚨 Injection payload in reflected parameter {}={} (Session: {})",
+                route.param,
+                value,
+                self.session_id
+            );
+            self.injection_payloads.push(value);
+        }
+
+        Some(HttpResponse {
+            status: 200,
+            status_text: "OK".to_string(),
+            headers: self.default_headers("text/html"),
+            body,
+        })
+    }
+
     async fn serve_404(&self) -> HttpResponse {
         HttpResponse {
             status: 404,
             status_text: "Not Found".to_string(),
             headers: self.default_headers("text/html"),
-            body: "<html><body><h1>404 Not Found</h1></body></html>".to_string(),
+            body: self.personality.not_found_body.clone(),
+        }
+    }
+
+    /// Limit überschritten: Tarpit-Delay anwenden oder Schließen erzwingen
+    async fn enforce_request_limit(&mut self) -> Option<HttpResponse> {
+        self.excess_requests += 1;
+        if self.excess_requests == 1 {
+            tracing::warn!(
+                "🚨 HTTP request rate abuse: more than {} requests on one connection ({:?}) \
+                 (Session: {})",
+                self.request_limit.max_requests,
+                self.request_limit.action,
+                self.session_id
+            );
+        }
+
+        match self.request_limit.action {
+            RequestLimitAction::Tarpit(delay) => {
+                self.sleeper.sleep(delay).await;
+                None
+            }
+            RequestLimitAction::Close => {
+                self.close_requested = true;
+                let mut headers = self.default_headers("text/html");
+                headers.insert("Connection".to_string(), "close".to_string());
+                Some(HttpResponse {
+                    status: 429,
+                    status_text: "Too Many Requests".to_string(),
+                    headers,
+                    body: "<html><body><h1>429 Too Many Requests</h1></body></html>".to_string(),
+                })
+            }
         }
     }
 
     fn default_headers(&self, content_type: &str) -> HashMap<String, String> {
         let mut headers = HashMap::new();
         headers.insert("Content-Type".to_string(), content_type.to_string());
-        headers.insert("Server".to_string(), "Apache/2.4.41 (Ubuntu)".to_string());
-        headers.insert("X-Powered-By".to_string(), "PHP/7.4.3".to_string());
+        headers.insert("Server".to_string(), self.personality.http_server.clone());
+        headers.insert(
+            "X-Powered-By".to_string(),
+            self.personality.http_powered_by.clone(),
+        );
         headers
     }
 
@@ -281,12 +899,75 @@ impl HttpInteractionHandler {
         }
     }
 
+    /// Vollständigen Request als Capture-Event ausgeben (und ggf. speichern)
+    fn capture_request(&mut self, request: &HttpRequest) {
+        let capture =
+            HttpCapture::new(request, &self.session_id, &self.request_capture.redacted_headers);
+        tracing::info!(
+            event = "http_request",
+            raw = %capture.to_raw(),
+            "📦 HTTP request captured: {} {} (Session: {})",
+            capture.method.as_str(),
+            capture.path,
+            self.session_id
+        );
+        if self.request_capture.store {
+            self.captured_requests.push(capture);
+        }
+    }
+
+    fn capture_uploads(&mut self, request: &HttpRequest) {
+        let Some(body) = &request.body else {
+            return;
+        };
+        let Some(content_type) = request
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+            .map(|(_, value)| value)
+        else {
+            return;
+        };
+        let Some(parts) = parse_multipart(content_type, body) else {
+            return;
+        };
+
+        for part in parts.into_iter().filter(|p| p.is_file()) {
+            let filename = part.filename.as_deref().unwrap_or_default();
+            if part.is_executable() {
+                tracing::warn!(
+                    "🚨 Web shell upload attempt: {} ({:?}, {} bytes) to {} (Session: {})",
+                    filename,
+                    part.content_type,
+                    part.content.len(),
+                    request.path,
+                    self.session_id
+                );
+            } else {
+                tracing::info!(
+                    "📎 File upload captured: {} ({:?}, {} bytes) (Session: {})",
+                    filename,
+                    part.content_type,
+                    part.content.len(),
+                    self.session_id
+                );
+            }
+            self.uploaded_files.push(part);
+        }
+    }
+
     /// Get statistics
     pub fn get_stats(&self) -> HttpStats {
         HttpStats {
             request_count: self.request_count,
             login_attempts: self.login_attempts.len(),
             captured_credentials: self.login_attempts.clone(),
+            uploaded_files: self.uploaded_files.clone(),
+            proxy_probes: self.proxy_probes.clone(),
+            exfil_bytes: self.exfil.bytes(),
+            excess_requests: self.excess_requests,
+            injection_payloads: self.injection_payloads.clone(),
+            captured_requests: self.captured_requests.clone(),
         }
     }
 }
@@ -297,6 +978,17 @@ pub struct HttpStats {
     pub request_count: usize,
     pub login_attempts: usize,
     pub captured_credentials: Vec<(String, String)>,
+    pub uploaded_files: Vec<MultipartPart>,
+    /// Angefragte Ziele (`host:port`) von CONNECT Proxy-Probes
+    pub proxy_probes: Vec<String>,
+    /// Ausgelieferte Bytes sensibler Ressourcen
+    pub exfil_bytes: u64,
+    /// Requests jenseits des Requests-pro-Connection Limits
+    pub excess_requests: usize,
+    /// Injection-verdächtige Werte aus reflektierten Parametern
+    pub injection_payloads: Vec<String>,
+    /// Vollständige Requests (nur mit `RequestCapture::store`)
+    pub captured_requests: Vec<HttpCapture>,
 }
 
 #[cfg(test)]
@@ -332,4 +1024,203 @@ mod tests {
         assert_eq!(response.status, 401);
         assert_eq!(handler.get_stats().login_attempts, 1);
     }
+
+    const MULTIPART_BODY: &str = "--XyZ\r\n\
+Content-Disposition: form-data; name=\"description\"\r\n\
+\r\n\
+my avatar\r\n\
+--XyZ\r\n\
+Content-Disposition: form-data; name=\"file\"; filename=\"shell.php\"\r\n\
+Content-Type: application/x-php\r\n\
+\r\n\
+<?php system($_GET['c']); ?>\r\n\
+--XyZ--\r\n";
+
+    #[test]
+    fn test_parse_multipart_field_and_file() {
+        let parts = parse_multipart("multipart/form-data; boundary=XyZ", MULTIPART_BODY).unwrap();
+        assert_eq!(parts.len(), 2);
+
+        assert_eq!(parts[0].name, "description");
+        assert!(!parts[0].is_file());
+        assert_eq!(parts[0].content, b"my avatar");
+
+        assert_eq!(parts[1].name, "file");
+        assert_eq!(parts[1].filename.as_deref(), Some("shell.php"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("application/x-php"));
+        assert_eq!(parts[1].content, b"<?php system($_GET['c']); ?>");
+        assert!(parts[1].is_executable());
+    }
+
+    #[tokio::test]
+    async fn test_upload_captured() {
+        let mut handler = HttpInteractionHandler::new("test".to_string());
+        let mut headers = HashMap::new();
+        headers.insert(
+            "content-type".to_string(),
+            "multipart/form-data; boundary=XyZ".to_string(),
+        );
+        let request = HttpRequest {
+            method: HttpMethod::POST,
+            path: "/upload".to_string(),
+            headers,
+            body: Some(MULTIPART_BODY.to_string()),
+        };
+
+        handler.handle_request(request).await;
+
+        let uploads = handler.get_stats().uploaded_files;
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].filename.as_deref(), Some("shell.php"));
+        assert!(String::from_utf8_lossy(&uploads[0].content).contains("system("));
+    }
+
+    #[tokio::test]
+    async fn test_connect_probe_captures_target() {
+        let raw = "CONNECT example.com:443 HTTP/1.1\r\nHost: example.com:443\r\n\r\n";
+        let request = HttpRequest::parse(raw).unwrap();
+        assert_eq!(request.method, HttpMethod::CONNECT);
+        assert_eq!(request.path, "example.com:443");
+        assert_eq!(request.headers.get("Host").map(String::as_str), Some("example.com:443"));
+
+        let mut handler = HttpInteractionHandler::new("test".to_string());
+        let response = handler.handle_request(request).await;
+        assert_eq!(response.status, 200);
+        assert_eq!(response.status_text, "Connection established");
+        assert_eq!(handler.get_stats().proxy_probes, vec!["example.com:443"]);
+    }
+
+    #[tokio::test]
+    async fn test_connect_probe_refused_by_profile() {
+        let mut handler = HttpInteractionHandler::new("test".to_string())
+            .with_proxy_response(ProxyProbeResponse::Refused);
+        let request = HttpRequest::parse("CONNECT 10.0.0.5:25 HTTP/1.1\r\n\r\n").unwrap();
+
+        let response = handler.handle_request(request).await;
+        assert_eq!(response.status, 502);
+        assert_eq!(handler.get_stats().proxy_probes, vec!["10.0.0.5:25"]);
+    }
+
+    #[tokio::test]
+    async fn test_config_fetch_fires_canary() {
+        let canaries = Arc::new(CanaryRegistry::new());
+        let mut handler =
+            HttpInteractionHandler::new("test".to_string()).with_canary_registry(canaries.clone());
+
+        let request = HttpRequest::parse("GET /api/config HTTP/1.1\r\n\r\n").unwrap();
+        let response = handler.handle_request(request).await;
+        let token = &canaries.tokens()[0];
+        assert!(response.body.contains(&token.value));
+
+        let events = canaries.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].access, CanaryAccess::HttpFetch);
+    }
+
+    #[tokio::test]
+    async fn test_request_limit_tarpits_then_closes() {
+        let get = || HttpRequest {
+            method: HttpMethod::GET,
+            path: "/".to_string(),
+            headers: HashMap::new(),
+            body: None,
+        };
+
+        let sleeper = Arc::new(crate::clock::RecordingSleeper::new());
+        let mut handler = HttpInteractionHandler::new("tarpit".to_string())
+            .with_sleeper(sleeper.clone())
+            .with_request_limit(RequestLimit {
+                max_requests: 2,
+                action: RequestLimitAction::Tarpit(Duration::from_secs(3)),
+            });
+        for _ in 0..3 {
+            assert_eq!(handler.handle_request(get()).await.status, 200);
+        }
+        // Nur der dritte Request wird gebremst
+        let tarpitted = sleeper
+            .delays()
+            .iter()
+            .filter(|d| **d == Duration::from_secs(3))
+            .count();
+        assert_eq!(tarpitted, 1);
+        assert_eq!(handler.get_stats().excess_requests, 1);
+
+        let mut handler = HttpInteractionHandler::new("close".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_request_limit(RequestLimit {
+                max_requests: 1,
+                action: RequestLimitAction::Close,
+            });
+        assert_eq!(handler.handle_request(get()).await.status, 200);
+        assert!(!handler.should_close());
+        let response = handler.handle_request(get()).await;
+        assert_eq!(response.status, 429);
+        assert_eq!(response.headers.get("Connection").unwrap(), "close");
+        assert!(handler.should_close());
+    }
+
+    #[tokio::test]
+    async fn test_search_query_reflected_and_ssti_captured() {
+        let get = |path: &str| HttpRequest {
+            method: HttpMethod::GET,
+            path: path.to_string(),
+            headers: HashMap::new(),
+            body: None,
+        };
+        let mut handler = HttpInteractionHandler::new("reflect".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()));
+
+        let response = handler.handle_request(get("/search?q=quarterly+report")).await;
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("Search results for \"quarterly report\""));
+        assert!(handler.get_stats().injection_payloads.is_empty());
+
+        // SSTI wird erfasst, aber nicht ausgewertet; Markup wird escaped
+        let response = handler.handle_request(get("/search?q=%7B%7B7*7%7D%7D")).await;
+        assert!(response.body.contains("{{7*7}}"));
+        assert!(!response.body.contains("49"));
+        let response = handler
+            .handle_request(get("/search?q=%3Cscript%3Ealert(1)%3C%2Fscript%3E"))
+            .await;
+        assert!(response.body.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        assert!(!response.body.contains("<script>"));
+        assert_eq!(
+            handler.get_stats().injection_payloads,
+            ["{{7*7}}", "<script>alert(1)</script>"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_full_request_captured_with_redaction() {
+        let raw = "POST /wp-login.php HTTP/1.1\r\n\
+                   Host: victim.example\r\n\
+                   Cookie: wordpress_test_cookie=WP+Cookie+check\r\n\
+                   User-Agent: Mozilla/5.0 (compatible; scanner)\r\n\r\n\
+                   log=admin&pwd=admin";
+        let mut handler = HttpInteractionHandler::new("capture".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_request_capture(RequestCapture {
+                store: true,
+                ..RequestCapture::default()
+            });
+
+        handler.handle_request(HttpRequest::parse(raw).unwrap()).await;
+
+        let captured = handler.get_stats().captured_requests;
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].session_id, "capture");
+        assert_eq!(
+            captured[0].to_raw(),
+            "POST /wp-login.php HTTP/1.1\r\n\
+             Cookie: [REDACTED]\r\n\
+             Host: victim.example\r\n\
+             User-Agent: Mozilla/5.0 (compatible; scanner)\r\n\r\n\
+             log=admin&pwd=admin"
+        );
+
+        // Rekonstruierter Request ist wieder parsebar (Replay)
+        let replay = HttpRequest::parse(&captured[0].to_raw()).unwrap();
+        assert_eq!(replay.path, "/wp-login.php");
+        assert_eq!(replay.body.as_deref(), Some("log=admin&pwd=admin"));
+    }
 }
@@ -0,0 +1,43 @@
+//! Input Size Limits
+//!
+//! Begrenzt Nachrichten-/Query-Größen pro Protokoll, damit überlange
+//! Payloads weder Logging noch Parser belasten (und Fuzzing auffällt).
+
+/// Max. Länge einer SSH-Kommandozeile (Bytes)
+pub const DEFAULT_MAX_SSH_COMMAND: usize = 4096;
+/// Max. Länge einer MySQL-Query (Bytes)
+pub const DEFAULT_MAX_MYSQL_QUERY: usize = 64 * 1024;
+/// Max. Länge eines HTTP-Bodys (Bytes)
+pub const DEFAULT_MAX_HTTP_BODY: usize = 1024 * 1024;
+/// Max. Länge eines MQTT-Pakets ohne Fixed Header (Bytes)
+pub const DEFAULT_MAX_MQTT_PACKET: usize = 256 * 1024;
+/// Max. Länge einer SMTP-Zeile (Bytes)
+pub const DEFAULT_MAX_SMTP_LINE: usize = 4096;
+/// Max. Größe einer SMTP-Nachricht (Bytes, wird per EHLO SIZE angekündigt)
+pub const DEFAULT_MAX_SMTP_MESSAGE: usize = 10 * 1024 * 1024;
+/// Max. Länge einer PostgreSQL-Nachricht (Bytes)
+pub const DEFAULT_MAX_POSTGRES_MESSAGE: usize = 64 * 1024;
+/// Max. Größe eines Redis-Requests (Bytes)
+pub const DEFAULT_MAX_REDIS_REQUEST: usize = 1024 * 1024;
+/// Max. Requests pro HTTP-Connection, bevor gebremst wird
+pub const DEFAULT_MAX_HTTP_REQUESTS: usize = 1000;
+
+/// Prüft die Eingabegröße und loggt ein `oversized_input` Event bei Überschreitung
+pub fn within_limit(protocol: &str, session_id: &str, len: usize, max: usize) -> bool {
+    if len <= max {
+        return true;
+    }
+
+    tracing::warn!(
+        event = "oversized_input",
+        protocol,
+        len,
+        max,
+        "🚨 Oversized {} input: {} bytes (limit {}) (Session: {})",
+        protocol,
+        len,
+        max,
+        session_id
+    );
+    false
+}
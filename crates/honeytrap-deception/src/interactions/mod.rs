@@ -3,15 +3,54 @@
 //! Realistische Protokoll-Implementierungen und intelligente Response-Strategien
 
 pub mod ssh_interaction;
+pub mod behavior;
 pub mod http_interaction;
+pub mod mqtt_interaction;
 pub mod mysql_interaction;
+pub mod postgres_interaction;
+pub mod redis_interaction;
+pub mod smtp_interaction;
+pub mod snmp_interaction;
+pub mod telnet_interaction;
 pub mod command_parser;
 pub mod fake_filesystem;
+pub mod limits;
+pub mod pager;
 pub mod response_generator;
+pub mod reverse_shell;
 
-pub use ssh_interaction::SshInteractionHandler;
-pub use http_interaction::{HttpInteractionHandler, HttpMethod, HttpRequest, HttpResponse, HttpStats};
-pub use mysql_interaction::{MysqlInteractionHandler, MysqlResponse, MysqlStats};
+pub use behavior::{BehaviorClass, BehaviorClassifier};
+pub use ssh_interaction::{
+    ExitBehavior, SshChannelRequests, SshInteractionHandler, UnknownCommandStrategy,
+};
+pub use http_interaction::{
+    parse_multipart, HttpCapture, HttpInteractionHandler, HttpMethod, HttpRequest, HttpResponse,
+    HttpStats, MultipartPart, ProxyProbeResponse, ReflectedRoute, RequestCapture, RequestLimit,
+    RequestLimitAction,
+};
+pub use mqtt_interaction::{
+    MqttConnect, MqttInteractionHandler, MqttPacket, MqttPacketType, MqttProtocolError,
+    MqttPublish, MqttStats,
+};
+pub use mysql_interaction::{
+    MysqlInteractionHandler, MysqlPacket, MysqlProtocolError, MysqlResponse, MysqlStats,
+};
+pub use postgres_interaction::{
+    PgResponse, PostgresAuthMethod, PostgresInteractionHandler, PostgresProtocolError,
+    PostgresStats,
+};
+pub use redis_interaction::{
+    RedisInteractionHandler, RedisProtocolError, RedisStats, RespValue,
+};
+pub use smtp_interaction::{CapturedMail, RelayAttempt, SmtpInteractionHandler, SmtpStats};
+pub use snmp_interaction::{PduType, SnmpInteractionHandler, SnmpMessage, SnmpValue};
+pub use telnet_interaction::{
+    TelnetCommand, TelnetInteractionHandler, TelnetParser, TelnetStats,
+};
 pub use command_parser::{Command, CommandParser};
 pub use fake_filesystem::{FakeFilesystem, FileEntry, FileType};
-pub use response_generator::{ResponseGenerator, ResponseStrategy};
+pub use pager::{Pager, TerminalSize};
+pub use reverse_shell::{detect_reverse_shell, ReverseShellSink, ReverseShellTarget};
+pub use response_generator::{
+    BannerDelay, CommandKind, DelayModel, ResponseGenerator, ResponseStrategy,
+};
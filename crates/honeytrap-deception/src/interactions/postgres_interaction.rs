@@ -0,0 +1,689 @@
+//! PostgreSQL Interaction Handler
+//!
+//! Spricht das Frontend/Backend-Protokoll v3: Startup-Paket (SSLRequest wird
+//! mit `N` abgelehnt), Passwort-Abfrage (Cleartext oder MD5) und Simple
+//! Queries. Jeder Login wird akzeptiert, Queries beantwortet eine kleine
+//! Fake-Instanz analog zum MySQL-Handler.
+
+use super::limits::{within_limit, DEFAULT_MAX_POSTGRES_MESSAGE};
+use super::mysql_interaction::detect_malicious_query;
+use crate::credentials::CredentialStore;
+use crate::exfil::ExfilCounter;
+use std::collections::HashMap;
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+const SERVER_VERSION: &str = "12.15 (Ubuntu 12.15-0ubuntu0.20.04.1)";
+const VERSION_STRING: &str = "PostgreSQL 12.15 (Ubuntu 12.15-0ubuntu0.20.04.1) on \
+     x86_64-pc-linux-gnu, compiled by gcc (Ubuntu 9.4.0-1ubuntu1~20.04.1) 9.4.0, 64-bit";
+/// Protokoll 3.0
+const PROTOCOL_V3: u32 = 196_608;
+const SSL_REQUEST: u32 = 80_877_103;
+const GSSENC_REQUEST: u32 = 80_877_104;
+const CANCEL_REQUEST: u32 = 80_877_102;
+/// OID von `text`
+const TEXT_OID: i32 = 25;
+
+/// Verletzung des PostgreSQL-Framings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostgresProtocolError {
+    /// Nachricht unvollständig (weitere Bytes abwarten)
+    Incomplete,
+    /// Längenfeld kleiner als das Längenfeld selbst
+    InvalidLength(i32),
+    /// Deklarierte Länge über dem Limit
+    MessageTooLarge { declared: usize, max: usize },
+    /// Unbekannte Protokollversion im Startup-Paket
+    UnsupportedProtocol(u32),
+    /// Nachricht im aktuellen Zustand nicht erlaubt
+    UnexpectedMessage(u8),
+}
+
+impl fmt::Display for PostgresProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostgresProtocolError::Incomplete => write!(f, "incomplete message"),
+            PostgresProtocolError::InvalidLength(len) => {
+                write!(f, "invalid message length {}", len)
+            }
+            PostgresProtocolError::MessageTooLarge { declared, max } => {
+                write!(f, "declared message length {} exceeds limit {}", declared, max)
+            }
+            PostgresProtocolError::UnsupportedProtocol(version) => write!(
+                f,
+                "unsupported frontend protocol {}.{}",
+                version >> 16,
+                version & 0xFFFF
+            ),
+            PostgresProtocolError::UnexpectedMessage(tag) => {
+                write!(f, "unexpected message type '{}'", tag.escape_ascii())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PostgresProtocolError {}
+
+impl PostgresProtocolError {
+    /// Nur unvollständig (weitere Bytes abwarten)?
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, PostgresProtocolError::Incomplete)
+    }
+
+    /// FATAL-ErrorResponse, die vor dem Schließen gesendet wird
+    pub fn to_error_response(&self) -> Vec<u8> {
+        let code = match self {
+            PostgresProtocolError::UnsupportedProtocol(_) => "0A000",
+            _ => "08P01",
+        };
+        encode_error("FATAL", code, &self.to_string())
+    }
+}
+
+/// Vom Honeypot angeforderte Passwort-Methode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostgresAuthMethod {
+    /// Passwort im Klartext (maximale Ausbeute)
+    Cleartext,
+    /// `md5(md5(password + user) + salt)`, nur der Hash wird erfasst
+    Md5,
+}
+
+/// Backend-Nachricht als Antwort auf eine Query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgResponse {
+    RowDescription { columns: Vec<String> },
+    DataRow { values: Vec<Option<String>> },
+    CommandComplete { tag: String },
+    ErrorResponse { code: String, message: String },
+}
+
+impl PgResponse {
+    fn error(code: &str, message: impl Into<String>) -> Self {
+        PgResponse::ErrorResponse {
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// In Wire-Format kodieren
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            PgResponse::RowDescription { columns } => {
+                let mut body = (columns.len() as i16).to_be_bytes().to_vec();
+                for column in columns {
+                    body.extend_from_slice(column.as_bytes());
+                    body.push(0);
+                    body.extend_from_slice(&0i32.to_be_bytes()); // Tabellen-OID
+                    body.extend_from_slice(&0i16.to_be_bytes()); // Spaltennummer
+                    body.extend_from_slice(&TEXT_OID.to_be_bytes());
+                    body.extend_from_slice(&(-1i16).to_be_bytes()); // typlen (varlena)
+                    body.extend_from_slice(&(-1i32).to_be_bytes()); // typmod
+                    body.extend_from_slice(&0i16.to_be_bytes()); // Textformat
+                }
+                message(b'T', &body)
+            }
+            PgResponse::DataRow { values } => {
+                let mut body = (values.len() as i16).to_be_bytes().to_vec();
+                for value in values {
+                    match value {
+                        Some(value) => {
+                            body.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                            body.extend_from_slice(value.as_bytes());
+                        }
+                        None => body.extend_from_slice(&(-1i32).to_be_bytes()),
+                    }
+                }
+                message(b'D', &body)
+            }
+            PgResponse::CommandComplete { tag } => message(b'C', &cstring(tag)),
+            PgResponse::ErrorResponse { code, message } => encode_error("ERROR", code, message),
+        }
+    }
+}
+
+/// Backend-Nachricht: Typ-Byte + Länge (inkl. sich selbst) + Body
+fn message(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(body.len() + 5);
+    out.push(tag);
+    out.extend_from_slice(&((body.len() + 4) as i32).to_be_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+fn cstring(value: &str) -> Vec<u8> {
+    let mut out = value.as_bytes().to_vec();
+    out.push(0);
+    out
+}
+
+fn encode_error(severity: &str, code: &str, text: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (field, value) in [(b'S', severity), (b'V', severity), (b'C', code), (b'M', text)] {
+        body.push(field);
+        body.extend_from_slice(&cstring(value));
+    }
+    body.push(0);
+    message(b'E', &body)
+}
+
+fn ready_for_query() -> Vec<u8> {
+    message(b'Z', b"I")
+}
+
+/// Nullterminierte Strings eines Bodys
+fn cstrings(body: &[u8]) -> impl Iterator<Item = String> + '_ {
+    body.split(|&b| b == 0)
+        .map(|s| String::from_utf8_lossy(s).into_owned())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Startup,
+    Password,
+    Ready,
+    Closed,
+}
+
+/// PostgreSQL Interaction Handler
+pub struct PostgresInteractionHandler {
+    session_id: String,
+    state: State,
+    authenticated: bool,
+    auth_method: PostgresAuthMethod,
+    salt: [u8; 4],
+    username: Option<String>,
+    database: Option<String>,
+    application_name: Option<String>,
+    query_count: usize,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    max_message_len: usize,
+    exfil: ExfilCounter,
+}
+
+impl PostgresInteractionHandler {
+    pub fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            state: State::Startup,
+            authenticated: false,
+            auth_method: PostgresAuthMethod::Cleartext,
+            salt: rand::random(),
+            username: None,
+            database: None,
+            application_name: None,
+            query_count: 0,
+            credential_store: None,
+            max_message_len: DEFAULT_MAX_POSTGRES_MESSAGE,
+            exfil: ExfilCounter::new(),
+        }
+    }
+
+    /// Mit anderer Passwort-Methode (Default: Cleartext)
+    pub fn with_auth_method(mut self, auth_method: PostgresAuthMethod) -> Self {
+        self.auth_method = auth_method;
+        self
+    }
+
+    /// Mit max. Nachrichtenlänge (Bytes)
+    pub fn with_max_message_len(mut self, max_message_len: usize) -> Self {
+        self.max_message_len = max_message_len;
+        self
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Exfiltrierte Bytes in den Zähler der Session buchen
+    pub fn with_exfil_counter(mut self, exfil: ExfilCounter) -> Self {
+        self.exfil = exfil;
+        self
+    }
+
+    /// Nachricht parsen und beantworten, liefert Antwort + verbrauchte Bytes
+    pub fn handle_bytes(
+        &mut self,
+        buf: &[u8],
+    ) -> Result<(Option<Vec<u8>>, usize), PostgresProtocolError> {
+        // Startup-Nachrichten haben kein Typ-Byte
+        let header = if self.state == State::Startup { 0 } else { 1 };
+        if buf.len() < header + 4 {
+            return Err(PostgresProtocolError::Incomplete);
+        }
+
+        let raw_len = i32::from_be_bytes([
+            buf[header],
+            buf[header + 1],
+            buf[header + 2],
+            buf[header + 3],
+        ]);
+        if raw_len < 4 {
+            return Err(PostgresProtocolError::InvalidLength(raw_len));
+        }
+        let declared = raw_len as usize;
+        if declared > self.max_message_len {
+            within_limit("postgres", &self.session_id, declared, self.max_message_len);
+            return Err(PostgresProtocolError::MessageTooLarge {
+                declared,
+                max: self.max_message_len,
+            });
+        }
+        let end = header + declared;
+        if buf.len() < end {
+            return Err(PostgresProtocolError::Incomplete);
+        }
+
+        let body = &buf[header + 4..end];
+        let response = match self.state {
+            State::Startup => self.handle_startup(body)?,
+            _ => self.handle_message(buf[0], body)?,
+        };
+        Ok((response, end))
+    }
+
+    fn handle_startup(&mut self, body: &[u8]) -> Result<Option<Vec<u8>>, PostgresProtocolError> {
+        if body.len() < 4 {
+            return Err(PostgresProtocolError::InvalidLength(body.len() as i32 + 4));
+        }
+        let code = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+
+        match code {
+            // Kein TLS/GSS, Client fährt unverschlüsselt fort
+            SSL_REQUEST | GSSENC_REQUEST => Ok(Some(b"N".to_vec())),
+            CANCEL_REQUEST => {
+                self.state = State::Closed;
+                Ok(None)
+            }
+            PROTOCOL_V3 => {
+                let params: HashMap<String, String> = {
+                    let mut strings = cstrings(&body[4..]).take_while(|s| !s.is_empty());
+                    std::iter::from_fn(|| Some((strings.next()?, strings.next()?))).collect()
+                };
+                self.username = params.get("user").cloned();
+                self.database = params
+                    .get("database")
+                    .or_else(|| params.get("user"))
+                    .cloned();
+                self.application_name = params.get("application_name").cloned();
+
+                tracing::info!(
+                    "🔑 PostgreSQL startup - User: {:?}, DB: {:?}, App: {:?} (Session: {})",
+                    self.username,
+                    self.database,
+                    self.application_name,
+                    self.session_id
+                );
+
+                self.state = State::Password;
+                let request = match self.auth_method {
+                    PostgresAuthMethod::Cleartext => 3i32.to_be_bytes().to_vec(),
+                    PostgresAuthMethod::Md5 => {
+                        let mut body = 5i32.to_be_bytes().to_vec();
+                        body.extend_from_slice(&self.salt);
+                        body
+                    }
+                };
+                Ok(Some(message(b'R', &request)))
+            }
+            other => Err(PostgresProtocolError::UnsupportedProtocol(other)),
+        }
+    }
+
+    fn handle_message(
+        &mut self,
+        tag: u8,
+        body: &[u8],
+    ) -> Result<Option<Vec<u8>>, PostgresProtocolError> {
+        match (self.state, tag) {
+            (State::Password, b'p') => {
+                let password = cstrings(body).next().unwrap_or_default();
+                Ok(Some(self.authenticate(&password)))
+            }
+            (State::Ready, b'Q') => {
+                let query = cstrings(body).next().unwrap_or_default();
+                let mut out = Vec::new();
+                for response in self.handle_query(&query) {
+                    out.extend_from_slice(&response.encode());
+                }
+                out.extend_from_slice(&ready_for_query());
+                Ok(Some(out))
+            }
+            // Extended Query Protocol wird nicht unterstützt, Sync schließt ab
+            (State::Ready, b'P' | b'B' | b'E' | b'D' | b'C' | b'H') => Ok(Some(
+                PgResponse::error("0A000", "extended query protocol not supported").encode(),
+            )),
+            (State::Ready, b'S') => Ok(Some(ready_for_query())),
+            (_, b'X') => {
+                self.state = State::Closed;
+                Ok(None)
+            }
+            _ => Err(PostgresProtocolError::UnexpectedMessage(tag)),
+        }
+    }
+
+    /// Passwort erfassen und Login immer akzeptieren
+    fn authenticate(&mut self, password: &str) -> Vec<u8> {
+        let username = self.username.clone().unwrap_or_default();
+        match self.auth_method {
+            PostgresAuthMethod::Cleartext => {
+                tracing::warn!("📝 Captured PostgreSQL credentials: {}:{}", username, password)
+            }
+            PostgresAuthMethod::Md5 => tracing::warn!(
+                "📝 Captured PostgreSQL MD5 hash: {}:{} (salt {:02x?})",
+                username,
+                password,
+                self.salt
+            ),
+        }
+        if let Some((store, source_ip)) = &self.credential_store {
+            store.record(*source_ip, "PostgreSQL", &username, password);
+        }
+
+        self.state = State::Ready;
+        self.authenticated = true;
+
+        let mut out = message(b'R', &0i32.to_be_bytes()); // AuthenticationOk
+        for (name, value) in [
+            ("application_name", self.application_name.as_deref().unwrap_or("")),
+            ("client_encoding", "UTF8"),
+            ("DateStyle", "ISO, MDY"),
+            ("integer_datetimes", "on"),
+            ("IntervalStyle", "postgres"),
+            ("is_superuser", "on"),
+            ("server_encoding", "UTF8"),
+            ("server_version", SERVER_VERSION),
+            ("session_authorization", &username),
+            ("standard_conforming_strings", "on"),
+            ("TimeZone", "Etc/UTC"),
+        ] {
+            let mut body = cstring(name);
+            body.extend_from_slice(&cstring(value));
+            out.extend_from_slice(&message(b'S', &body));
+        }
+        // BackendKeyData: Prozess-ID + Secret für CancelRequest
+        let pid = 1000 + rand::random::<u16>() as i32;
+        let mut key_data = pid.to_be_bytes().to_vec();
+        key_data.extend_from_slice(&rand::random::<[u8; 4]>());
+        out.extend_from_slice(&message(b'K', &key_data));
+        out.extend_from_slice(&ready_for_query());
+        out
+    }
+
+    /// Simple Query beantworten
+    pub fn handle_query(&mut self, query: &str) -> Vec<PgResponse> {
+        self.query_count += 1;
+
+        if !within_limit("postgres", &self.session_id, query.len(), self.max_message_len) {
+            return vec![PgResponse::error("54000", "query string is too long")];
+        }
+
+        tracing::info!("💾 PostgreSQL Query: {} (Session: {})", query, self.session_id);
+        detect_malicious_query(query, &self.session_id);
+
+        let query_lower = query.trim().trim_end_matches(';').trim().to_lowercase();
+        let first_word = query_lower.split_whitespace().next().unwrap_or("");
+
+        match first_word {
+            "" => vec![],
+            // psql-Metakommando, falls es roh gesendet wird
+            "\\l" => self.database_list(),
+            "select" => self.handle_select(&query_lower),
+            "insert" | "update" | "delete" => {
+                tracing::warn!(
+                    "🚨 Data modification attempt: {} (Session: {})",
+                    query,
+                    self.session_id
+                );
+                let tag = match first_word {
+                    "insert" => "INSERT 0 0",
+                    "update" => "UPDATE 0",
+                    _ => "DELETE 0",
+                };
+                vec![complete(tag)]
+            }
+            // COPY ... TO PROGRAM wird bereits von der Erkennung geloggt
+            "copy" => vec![complete("COPY 1")],
+            "create" | "drop" | "alter" => {
+                let object = query_lower.split_whitespace().nth(1).unwrap_or("").to_uppercase();
+                vec![complete(&format!("{} {}", first_word.to_uppercase(), object))]
+            }
+            "set" | "begin" | "commit" | "rollback" | "grant" => {
+                vec![complete(&first_word.to_uppercase())]
+            }
+            _ => {
+                let token = query.split_whitespace().next().unwrap_or("");
+                vec![PgResponse::error(
+                    "42601",
+                    format!("syntax error at or near \"{}\"", token),
+                )]
+            }
+        }
+    }
+
+    fn handle_select(&self, query: &str) -> Vec<PgResponse> {
+        if query.contains("version()") {
+            result_set(&["version"], vec![vec![VERSION_STRING.to_string()]])
+        } else if query.contains("current_user") || query.contains("session_user") {
+            let user = self.username.clone().unwrap_or_else(|| "postgres".to_string());
+            result_set(&["current_user"], vec![vec![user]])
+        } else if query.contains("current_database()") {
+            let db = self.database.clone().unwrap_or_else(|| "postgres".to_string());
+            result_set(&["current_database"], vec![vec![db]])
+        } else if query.contains("pg_database") {
+            self.database_list()
+        } else if query.contains("pg_tables") || query.contains("information_schema.tables") {
+            let rows = ["users", "sessions", "payments", "audit_log"]
+                .iter()
+                .map(|table| {
+                    ["public", table, "postgres", "", "t", "f", "f", "f"]
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect()
+                })
+                .collect();
+            result_set(
+                &[
+                    "schemaname",
+                    "tablename",
+                    "tableowner",
+                    "tablespace",
+                    "hasindexes",
+                    "hasrules",
+                    "hastriggers",
+                    "rowsecurity",
+                ],
+                rows,
+            )
+        } else if query.contains("from") {
+            tracing::warn!(
+                "🔍 Data extraction attempt: {} (Session: {})",
+                query,
+                self.session_id
+            );
+            let rows = vec![vec!["1".to_string(), "sample_data".to_string()]];
+            let bytes = rows.iter().flatten().map(|cell| cell.len()).sum();
+            self.exfil.record("PostgreSQL", query, bytes, &self.session_id);
+            result_set(&["id", "name"], rows)
+        } else {
+            result_set(&["?column?"], vec![vec!["1".to_string()]])
+        }
+    }
+
+    fn database_list(&self) -> Vec<PgResponse> {
+        let rows = ["postgres", "corporate_db", "template0", "template1"]
+            .iter()
+            .map(|name| {
+                [name, "postgres", "UTF8", "en_US.UTF-8", "en_US.UTF-8", ""]
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect()
+            })
+            .collect();
+        result_set(
+            &["Name", "Owner", "Encoding", "Collate", "Ctype", "Access privileges"],
+            rows,
+        )
+    }
+
+    /// Hat der Client Terminate gesendet?
+    pub fn is_closed(&self) -> bool {
+        self.state == State::Closed
+    }
+
+    /// Get statistics
+    pub fn get_stats(&self) -> PostgresStats {
+        PostgresStats {
+            query_count: self.query_count,
+            authenticated: self.authenticated,
+            username: self.username.clone(),
+            database: self.database.clone(),
+            application_name: self.application_name.clone(),
+            exfil_bytes: self.exfil.bytes(),
+        }
+    }
+}
+
+fn complete(tag: &str) -> PgResponse {
+    PgResponse::CommandComplete {
+        tag: tag.to_string(),
+    }
+}
+
+/// RowDescription + DataRows + `SELECT n`
+fn result_set(columns: &[&str], rows: Vec<Vec<String>>) -> Vec<PgResponse> {
+    let mut responses = vec![PgResponse::RowDescription {
+        columns: columns.iter().map(|c| c.to_string()).collect(),
+    }];
+    let count = rows.len();
+    responses.extend(rows.into_iter().map(|row| PgResponse::DataRow {
+        values: row.into_iter().map(Some).collect(),
+    }));
+    responses.push(complete(&format!("SELECT {}", count)));
+    responses
+}
+
+/// PostgreSQL Statistics
+#[derive(Debug, Clone)]
+pub struct PostgresStats {
+    pub query_count: usize,
+    pub authenticated: bool,
+    pub username: Option<String>,
+    pub database: Option<String>,
+    /// `application_name` aus dem Startup-Paket (z.B. `psql`)
+    pub application_name: Option<String>,
+    /// Ausgelieferte Bytes aus Daten-Abfragen
+    pub exfil_bytes: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn startup(params: &[(&str, &str)]) -> Vec<u8> {
+        let mut body = PROTOCOL_V3.to_be_bytes().to_vec();
+        for (key, value) in params {
+            body.extend_from_slice(&cstring(key));
+            body.extend_from_slice(&cstring(value));
+        }
+        body.push(0);
+        let mut out = ((body.len() + 4) as i32).to_be_bytes().to_vec();
+        out.extend_from_slice(&body);
+        out
+    }
+
+    fn tags(response: &[u8]) -> Vec<u8> {
+        let mut tags = Vec::new();
+        let mut pos = 0;
+        while pos < response.len() {
+            tags.push(response[pos]);
+            let len = i32::from_be_bytes(response[pos + 1..pos + 5].try_into().unwrap());
+            pos += 1 + len as usize;
+        }
+        tags
+    }
+
+    #[test]
+    fn test_login_captures_password_and_answers_queries() {
+        let store = Arc::new(CredentialStore::new());
+        let ip: IpAddr = "192.0.2.5".parse().unwrap();
+        let mut handler = PostgresInteractionHandler::new("pg-1".to_string())
+            .with_credential_store(store.clone(), ip);
+
+        // SSLRequest wird abgelehnt
+        let ssl = [0, 0, 0, 8, 0x04, 0xD2, 0x16, 0x2F];
+        assert_eq!(handler.handle_bytes(&ssl).unwrap(), (Some(b"N".to_vec()), 8));
+
+        let packet = startup(&[("user", "postgres"), ("database", "prod")]);
+        assert!(handler.handle_bytes(&packet[..6]).unwrap_err().is_incomplete());
+        let (response, consumed) = handler.handle_bytes(&packet).unwrap();
+        assert_eq!(consumed, packet.len());
+        assert_eq!(response.unwrap(), message(b'R', &3i32.to_be_bytes()));
+
+        let (response, _) = handler.handle_bytes(&message(b'p', b"s3cret\0")).unwrap();
+        let response = response.unwrap();
+        assert_eq!(&response[..9], &message(b'R', &0i32.to_be_bytes())[..]);
+        assert_eq!(tags(&response).last(), Some(&b'Z'));
+        assert_eq!(store.records()[0].username, "postgres");
+        assert_eq!(store.records()[0].password, "s3cret");
+
+        let (response, _) = handler.handle_bytes(&message(b'Q', b"SELECT version();\0")).unwrap();
+        let response = response.unwrap();
+        assert_eq!(tags(&response), b"TDCZ");
+        assert!(String::from_utf8_lossy(&response).contains("PostgreSQL 12.15"));
+
+        let tables = handler.handle_query("SELECT * FROM pg_catalog.pg_tables");
+        assert_eq!(tables.len(), 6);
+        assert_eq!(tables.last(), Some(&complete("SELECT 4")));
+        assert_eq!(handler.handle_query("\\l").len(), 6);
+        assert!(matches!(
+            handler.handle_query("SHOWX tables")[0],
+            PgResponse::ErrorResponse { ref code, .. } if code == "42601"
+        ));
+
+        handler.handle_bytes(&message(b'X', b"")).unwrap();
+        let stats = handler.get_stats();
+        assert!(handler.is_closed());
+        assert!(stats.authenticated);
+        assert_eq!(stats.database.as_deref(), Some("prod"));
+        assert_eq!(stats.query_count, 4);
+    }
+
+    #[test]
+    fn test_md5_auth_and_malformed_messages() {
+        let mut handler = PostgresInteractionHandler::new("pg-2".to_string())
+            .with_auth_method(PostgresAuthMethod::Md5)
+            .with_max_message_len(128);
+
+        assert_eq!(
+            handler.handle_bytes(&[0, 0, 0, 8, 0, 2, 0, 0]).unwrap_err(),
+            PostgresProtocolError::UnsupportedProtocol(131_072)
+        );
+        assert_eq!(
+            handler.handle_bytes(&[0, 0, 0, 2]).unwrap_err(),
+            PostgresProtocolError::InvalidLength(2)
+        );
+        assert!(matches!(
+            handler.handle_bytes(&[0, 0, 1, 0]),
+            Err(PostgresProtocolError::MessageTooLarge { declared: 256, max: 128 })
+        ));
+
+        let (response, _) = handler.handle_bytes(&startup(&[("user", "admin")])).unwrap();
+        let response = response.unwrap();
+        assert_eq!(&response[5..9], &5i32.to_be_bytes());
+        assert_eq!(&response[9..], &handler.salt);
+
+        // Queries vor dem Login sind ein Protokollfehler
+        assert_eq!(
+            handler.handle_bytes(&message(b'Q', b"SELECT 1\0")).unwrap_err(),
+            PostgresProtocolError::UnexpectedMessage(b'Q')
+        );
+        let hash = b"md5a3556571e93b0d20722ba62be61e8c2d\0";
+        handler.handle_bytes(&message(b'p', hash)).unwrap();
+        assert!(handler.get_stats().authenticated);
+        assert_eq!(handler.get_stats().database.as_deref(), Some("admin"));
+    }
+}
@@ -0,0 +1,782 @@
+//! Redis Interaction Handler
+//!
+//! Offene Redis-Instanzen werden massenhaft gescannt und für Cryptominer
+//! missbraucht. Der Handler spricht RESP (Arrays aus Bulk Strings sowie
+//! Inline-Commands) und spielt eine Redis 5 ohne Passwort.
+//!
+//! Besonders wichtig ist das klassische RCE-Muster: `CONFIG SET dir
+//! /root/.ssh`, `CONFIG SET dbfilename authorized_keys`, ein Key mit
+//! SSH-Public-Key und dann `SAVE`. Jede solche Sequenz wird geloggt und in
+//! `RedisStats` markiert; geschrieben wird natürlich nichts.
+
+use super::limits::DEFAULT_MAX_REDIS_REQUEST;
+use crate::credentials::CredentialStore;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Max. Elemente eines RESP-Arrays
+const MAX_MULTIBULK_LEN: usize = 1024 * 1024;
+/// Max. Anzahl Keys im Keyspace pro Session
+const DEFAULT_MAX_KEYS: usize = 10_000;
+/// Max. Summe aller Keys und Werte im Keyspace pro Session (Bytes)
+const DEFAULT_MAX_KEYSPACE_BYTES: usize = 8 * 1024 * 1024;
+/// Fehler bei überschrittener Quota (wie Redis mit erreichtem `maxmemory`)
+const OOM: &str = "OOM command not allowed when used memory > 'maxmemory'.";
+/// Scheinbare Laufzeit der Instanz beim Start des Handlers (~37 Tage)
+const BASE_UPTIME_SECS: u64 = 3_214_877;
+const DEFAULT_DIR: &str = "/var/lib/redis";
+const DEFAULT_DBFILENAME: &str = "dump.rdb";
+
+/// Fehler beim Parsen eines RESP-Requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisProtocolError {
+    /// Request unvollständig (weitere Bytes abwarten)
+    Incomplete,
+    /// `*<n>` ungültig oder zu groß
+    InvalidMultibulkLength,
+    /// `$<n>` ungültig
+    InvalidBulkLength,
+    /// Im Array wurde `$` erwartet
+    ExpectedBulk(u8),
+    /// Request über dem Limit
+    RequestTooLarge { len: usize, max: usize },
+}
+
+impl fmt::Display for RedisProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedisProtocolError::Incomplete => write!(f, "incomplete request"),
+            RedisProtocolError::InvalidMultibulkLength => write!(f, "invalid multibulk length"),
+            RedisProtocolError::InvalidBulkLength => write!(f, "invalid bulk length"),
+            RedisProtocolError::ExpectedBulk(byte) => {
+                write!(f, "expected '$', got '{}'", byte.escape_ascii())
+            }
+            RedisProtocolError::RequestTooLarge { len, max } => {
+                write!(f, "request of {} bytes exceeds limit {}", len, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RedisProtocolError {}
+
+impl RedisProtocolError {
+    /// Nur unvollständig (weitere Bytes abwarten)?
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, RedisProtocolError::Incomplete)
+    }
+
+    /// Fehlerantwort wie Redis sie vor dem Schließen sendet
+    pub fn to_resp(&self) -> Vec<u8> {
+        format!("-ERR Protocol error: {}\r\n", self).into_bytes()
+    }
+}
+
+/// RESP-Antwort
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RespValue {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Vec<RespValue>),
+}
+
+impl RespValue {
+    fn ok() -> Self {
+        RespValue::Simple("OK".to_string())
+    }
+
+    fn bulk(value: impl Into<Vec<u8>>) -> Self {
+        RespValue::Bulk(Some(value.into()))
+    }
+
+    /// In RESP-Bytes kodieren
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode_into(&mut out);
+        out
+    }
+
+    fn encode_into(&self, out: &mut Vec<u8>) {
+        match self {
+            RespValue::Simple(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+            RespValue::Error(e) => out.extend_from_slice(format!("-{}\r\n", e).as_bytes()),
+            RespValue::Integer(i) => out.extend_from_slice(format!(":{}\r\n", i).as_bytes()),
+            RespValue::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+            RespValue::Bulk(Some(data)) => {
+                out.extend_from_slice(format!("${}\r\n", data.len()).as_bytes());
+                out.extend_from_slice(data);
+                out.extend_from_slice(b"\r\n");
+            }
+            RespValue::Array(items) => {
+                out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                for item in items {
+                    item.encode_into(out);
+                }
+            }
+        }
+    }
+}
+
+/// Zeile bis CRLF ab `start` (ohne CRLF) und Position danach
+fn read_line(buf: &[u8], start: usize) -> Result<(&[u8], usize), RedisProtocolError> {
+    let end = buf[start..]
+        .windows(2)
+        .position(|w| w == b"\r\n")
+        .ok_or(RedisProtocolError::Incomplete)?;
+    Ok((&buf[start..start + end], start + end + 2))
+}
+
+fn parse_len(line: &[u8]) -> Option<i64> {
+    std::str::from_utf8(line).ok()?.parse().ok()
+}
+
+/// Request parsen: (Argumente, verbrauchte Bytes)
+///
+/// Leere Inline-Zeilen ergeben eine leere Argumentliste. Ist der Request
+/// unvollständig, aber der Puffer schon über `max_len`, kommt
+/// `RequestTooLarge` statt `Incomplete` (sonst puffert der Aufrufer endlos).
+pub fn parse_request(
+    buf: &[u8],
+    max_len: usize,
+) -> Result<(Vec<Vec<u8>>, usize), RedisProtocolError> {
+    match parse_frame(buf, max_len) {
+        Err(RedisProtocolError::Incomplete) if buf.len() > max_len => {
+            Err(RedisProtocolError::RequestTooLarge {
+                len: buf.len(),
+                max: max_len,
+            })
+        }
+        result => result,
+    }
+}
+
+fn parse_frame(buf: &[u8], max_len: usize) -> Result<(Vec<Vec<u8>>, usize), RedisProtocolError> {
+    if buf.is_empty() {
+        return Err(RedisProtocolError::Incomplete);
+    }
+
+    if buf[0] != b'*' {
+        // Inline-Command (z.B. `PING\r\n` via netcat)
+        let end = buf
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or(RedisProtocolError::Incomplete)?;
+        let line = String::from_utf8_lossy(&buf[..end]);
+        let args = line
+            .split_whitespace()
+            .map(|arg| arg.as_bytes().to_vec())
+            .collect();
+        return Ok((args, end + 1));
+    }
+
+    let (line, mut pos) = read_line(buf, 1)?;
+    let count = parse_len(line)
+        .filter(|n| (0..=MAX_MULTIBULK_LEN as i64).contains(n))
+        .ok_or(RedisProtocolError::InvalidMultibulkLength)?;
+
+    let mut args = Vec::with_capacity(count.min(64) as usize);
+    for _ in 0..count {
+        let marker = *buf.get(pos).ok_or(RedisProtocolError::Incomplete)?;
+        if marker != b'$' {
+            return Err(RedisProtocolError::ExpectedBulk(marker));
+        }
+        let (line, next) = read_line(buf, pos + 1)?;
+        let len = parse_len(line)
+            .filter(|n| *n >= 0)
+            .ok_or(RedisProtocolError::InvalidBulkLength)? as usize;
+        if next + len > max_len {
+            return Err(RedisProtocolError::RequestTooLarge {
+                len: next + len,
+                max: max_len,
+            });
+        }
+        if buf.len() < next + len + 2 {
+            return Err(RedisProtocolError::Incomplete);
+        }
+        args.push(buf[next..next + len].to_vec());
+        pos = next + len + 2;
+    }
+
+    Ok((args, pos))
+}
+
+/// Glob-Matching für KEYS / CONFIG GET (`*`, `?`)
+///
+/// Iterativ mit Backtracking nur zum letzten `*`, damit Muster wie
+/// `a*a*a*…b` linear bleiben statt exponentiell.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position nach dem letzten `*` und die dort gestartete Text-Position
+    let mut star: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                p += 1;
+                star = Some((p, t));
+            }
+            Some(b'?') => {
+                p += 1;
+                t += 1;
+            }
+            Some(c) if c.eq_ignore_ascii_case(&text[t]) => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p.min(pattern.len())..].iter().all(|&c| c == b'*')
+}
+
+/// Redis-Statistiken
+#[derive(Debug, Clone)]
+pub struct RedisStats {
+    pub session_id: String,
+    pub command_count: usize,
+    /// Aufrufe pro Command (Großbuchstaben)
+    pub commands: BTreeMap<String, usize>,
+    /// CONFIG SET dir/dbfilename + SAVE erkannt
+    pub rce_attempt_detected: bool,
+    /// Ziel des versuchten Dateischreibens (`dir/dbfilename`)
+    pub rce_target: Option<String>,
+}
+
+/// Redis Interaction Handler
+pub struct RedisInteractionHandler {
+    session_id: String,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    max_request_len: usize,
+    config: HashMap<String, String>,
+    keyspace: BTreeMap<Vec<u8>, Vec<u8>>,
+    /// Belegte Bytes aller Keys und Werte
+    keyspace_bytes: usize,
+    max_keys: usize,
+    max_keyspace_bytes: usize,
+    /// Unix-Timestamp des scheinbaren Server-Starts
+    started_at: u64,
+    closed: bool,
+    command_count: usize,
+    commands: BTreeMap<String, usize>,
+    rce_target: Option<String>,
+}
+
+impl RedisInteractionHandler {
+    pub fn new(session_id: String) -> Self {
+        let config = [
+            ("dir", DEFAULT_DIR),
+            ("dbfilename", DEFAULT_DBFILENAME),
+            ("requirepass", ""),
+            ("protected-mode", "no"),
+            ("bind", "0.0.0.0"),
+            ("port", "6379"),
+            ("maxmemory", "0"),
+            ("appendonly", "no"),
+            ("save", "900 1 300 10 60 10000"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        Self {
+            session_id,
+            credential_store: None,
+            max_request_len: DEFAULT_MAX_REDIS_REQUEST,
+            config,
+            keyspace: BTreeMap::new(),
+            keyspace_bytes: 0,
+            max_keys: DEFAULT_MAX_KEYS,
+            max_keyspace_bytes: DEFAULT_MAX_KEYSPACE_BYTES,
+            started_at: unix_now().saturating_sub(BASE_UPTIME_SECS),
+            closed: false,
+            command_count: 0,
+            commands: BTreeMap::new(),
+            rce_target: None,
+        }
+    }
+
+    /// AUTH-Passwörter zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Mit eigenem Request-Limit
+    pub fn with_max_request_len(mut self, max_request_len: usize) -> Self {
+        self.max_request_len = max_request_len;
+        self
+    }
+
+    /// Mit eigener Keyspace-Quota (max. Keys und Bytes insgesamt)
+    pub fn with_keyspace_quota(mut self, max_keys: usize, max_keyspace_bytes: usize) -> Self {
+        self.max_keys = max_keys;
+        self.max_keyspace_bytes = max_keyspace_bytes;
+        self
+    }
+
+    /// Request parsen und beantworten, liefert Antwort + verbrauchte Bytes
+    pub fn handle_bytes(
+        &mut self,
+        buf: &[u8],
+    ) -> Result<(Option<Vec<u8>>, usize), RedisProtocolError> {
+        let (args, consumed) = parse_request(buf, self.max_request_len)?;
+        if args.is_empty() {
+            return Ok((None, consumed));
+        }
+        Ok((Some(self.handle_command(&args).encode()), consumed))
+    }
+
+    /// Command ausführen
+    pub fn handle_command(&mut self, args: &[Vec<u8>]) -> RespValue {
+        let name = String::from_utf8_lossy(&args[0]).to_uppercase();
+        let rest = &args[1..];
+        self.command_count += 1;
+        *self.commands.entry(name.clone()).or_default() += 1;
+        tracing::debug!(
+            "Redis command {} ({} args) (Session: {})",
+            name,
+            rest.len(),
+            self.session_id
+        );
+
+        match name.as_str() {
+            "PING" => match rest.first() {
+                Some(message) => RespValue::bulk(message.clone()),
+                None => RespValue::Simple("PONG".to_string()),
+            },
+            "ECHO" if rest.len() == 1 => RespValue::bulk(rest[0].clone()),
+            "INFO" => RespValue::bulk(self.info()),
+            "CONFIG" => self.handle_config(rest),
+            "AUTH" => self.handle_auth(rest),
+            "SET" if rest.len() >= 2 => {
+                if !self.store_key(&rest[0], &rest[1]) {
+                    return RespValue::Error(OOM.to_string());
+                }
+                if contains_ssh_key(&rest[1]) {
+                    tracing::warn!(
+                        "🚨 Redis key {} holds an SSH public key (Session: {})",
+                        String::from_utf8_lossy(&rest[0]),
+                        self.session_id
+                    );
+                }
+                RespValue::ok()
+            }
+            "GET" if rest.len() == 1 => RespValue::Bulk(self.keyspace.get(&rest[0]).cloned()),
+            "DEL" if !rest.is_empty() => {
+                let removed = rest.iter().filter(|key| self.remove_key(key)).count();
+                RespValue::Integer(removed as i64)
+            }
+            "KEYS" if rest.len() == 1 => RespValue::Array(
+                self.keyspace
+                    .keys()
+                    .filter(|key| glob_match(&rest[0], key))
+                    .map(|key| RespValue::bulk(key.clone()))
+                    .collect(),
+            ),
+            "DBSIZE" => RespValue::Integer(self.keyspace.len() as i64),
+            "FLUSHALL" | "FLUSHDB" => {
+                self.keyspace.clear();
+                self.keyspace_bytes = 0;
+                RespValue::ok()
+            }
+            "SAVE" => {
+                self.detect_rce_write();
+                RespValue::ok()
+            }
+            "BGSAVE" => {
+                self.detect_rce_write();
+                RespValue::Simple("Background saving started".to_string())
+            }
+            "SLAVEOF" | "REPLICAOF" | "MODULE" => {
+                tracing::warn!(
+                    "🚨 Redis {} {} - possible rogue-master RCE (Session: {})",
+                    name,
+                    join_args(rest),
+                    self.session_id
+                );
+                RespValue::ok()
+            }
+            "COMMAND" => RespValue::Array(Vec::new()),
+            "SELECT" => RespValue::ok(),
+            "QUIT" => {
+                self.closed = true;
+                RespValue::ok()
+            }
+            "ECHO" | "SET" | "GET" | "DEL" | "KEYS" => RespValue::Error(format!(
+                "ERR wrong number of arguments for '{}' command",
+                name.to_lowercase()
+            )),
+            _ => RespValue::Error(format!(
+                "ERR unknown command `{}`, with args beginning with: {}",
+                String::from_utf8_lossy(&args[0]),
+                rest.iter()
+                    .map(|arg| format!("`{}`, ", String::from_utf8_lossy(arg)))
+                    .collect::<String>()
+            )),
+        }
+    }
+
+    /// Key setzen, sofern Key-Anzahl und Bytes die Quota nicht überschreiten
+    fn store_key(&mut self, key: &[u8], value: &[u8]) -> bool {
+        let old = self.keyspace.get(key).map(|old| key.len() + old.len());
+        let bytes = self.keyspace_bytes - old.unwrap_or(0) + key.len() + value.len();
+        if (old.is_none() && self.keyspace.len() >= self.max_keys)
+            || bytes > self.max_keyspace_bytes
+        {
+            tracing::debug!(
+                "Redis SET {} dropped (keyspace quota reached) (Session: {})",
+                String::from_utf8_lossy(key),
+                self.session_id
+            );
+            return false;
+        }
+        self.keyspace.insert(key.to_vec(), value.to_vec());
+        self.keyspace_bytes = bytes;
+        true
+    }
+
+    fn remove_key(&mut self, key: &[u8]) -> bool {
+        match self.keyspace.remove(key) {
+            Some(value) => {
+                self.keyspace_bytes -= key.len() + value.len();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Hat der Client QUIT gesendet?
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn get_stats(&self) -> RedisStats {
+        RedisStats {
+            session_id: self.session_id.clone(),
+            command_count: self.command_count,
+            commands: self.commands.clone(),
+            rce_attempt_detected: self.rce_target.is_some(),
+            rce_target: self.rce_target.clone(),
+        }
+    }
+
+    fn handle_config(&mut self, args: &[Vec<u8>]) -> RespValue {
+        let sub = args
+            .first()
+            .map(|s| String::from_utf8_lossy(s).to_uppercase())
+            .unwrap_or_default();
+
+        match (sub.as_str(), args.len()) {
+            ("GET", 2) => {
+                let mut matches: Vec<_> = self
+                    .config
+                    .iter()
+                    .filter(|(key, _)| glob_match(&args[1], key.as_bytes()))
+                    .collect();
+                matches.sort();
+                RespValue::Array(
+                    matches
+                        .into_iter()
+                        .flat_map(|(k, v)| {
+                            [RespValue::bulk(k.as_str()), RespValue::bulk(v.as_str())]
+                        })
+                        .collect(),
+                )
+            }
+            ("SET", 3) => {
+                let key = String::from_utf8_lossy(&args[1]).to_lowercase();
+                let value = String::from_utf8_lossy(&args[2]).to_string();
+                if matches!(key.as_str(), "dir" | "dbfilename") {
+                    tracing::warn!(
+                        "🚨 Redis CONFIG SET {} {} (Session: {})",
+                        key,
+                        value,
+                        self.session_id
+                    );
+                }
+                self.config.insert(key, value);
+                RespValue::ok()
+            }
+            ("RESETSTAT", 1) | ("REWRITE", 1) => RespValue::ok(),
+            _ => RespValue::Error(format!(
+                "ERR Unknown subcommand or wrong number of arguments for '{}'. Try CONFIG HELP.",
+                sub
+            )),
+        }
+    }
+
+    fn handle_auth(&mut self, args: &[Vec<u8>]) -> RespValue {
+        let (username, password) = match args {
+            [password] => ("default".to_string(), String::from_utf8_lossy(password).to_string()),
+            [username, password] => (
+                String::from_utf8_lossy(username).to_string(),
+                String::from_utf8_lossy(password).to_string(),
+            ),
+            _ => {
+                return RespValue::Error(
+                    "ERR wrong number of arguments for 'auth' command".to_string(),
+                )
+            }
+        };
+
+        tracing::warn!("📝 Captured credentials: {}:{}", username, password);
+        if let Some((store, source_ip)) = &self.credential_store {
+            store.record(*source_ip, "Redis", &username, &password);
+        }
+
+        // Ohne requirepass antwortet Redis 6+ genau so
+        RespValue::Error(
+            "ERR AUTH <password> called without any password configured for the default user. \
+             Are you sure your configuration is correct?"
+                .to_string(),
+        )
+    }
+
+    /// SAVE nach geändertem dir/dbfilename = Versuch, eine Datei zu schreiben
+    fn detect_rce_write(&mut self) {
+        let dir = self.config.get("dir").map(String::as_str).unwrap_or(DEFAULT_DIR);
+        let file = self
+            .config
+            .get("dbfilename")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_DBFILENAME);
+        if dir == DEFAULT_DIR && file == DEFAULT_DBFILENAME {
+            return;
+        }
+
+        let target = format!("{}/{}", dir.trim_end_matches('/'), file);
+        let ssh_key = self.keyspace.values().any(|value| contains_ssh_key(value));
+        tracing::warn!(
+            event = "redis_rce_attempt",
+            target = %target,
+            ssh_key,
+            "🚨 Redis RCE attempt: SAVE to {} (SSH key in keyspace: {}) (Session: {})",
+            target,
+            ssh_key,
+            self.session_id
+        );
+        self.rce_target = Some(target);
+    }
+
+    fn info(&self) -> String {
+        let uptime = unix_now().saturating_sub(self.started_at);
+        let used_memory = 866_184 + self.keyspace_bytes;
+        let mut info = format!(
+            "# Server\r\n\
+             redis_version:5.0.7\r\n\
+             redis_git_sha1:00000000\r\n\
+             redis_git_dirty:0\r\n\
+             redis_build_id:636cde3b5c7a3923\r\n\
+             redis_mode:standalone\r\n\
+             os:Linux 5.4.0-42-generic x86_64\r\n\
+             arch_bits:64\r\n\
+             multiplexing_api:epoll\r\n\
+             gcc_version:9.2.1\r\n\
+             process_id:812\r\n\
+             tcp_port:6379\r\n\
+             uptime_in_seconds:{}\r\n\
+             uptime_in_days:{}\r\n\
+             config_file:/etc/redis/redis.conf\r\n\
+             \r\n\
+             # Clients\r\n\
+             connected_clients:1\r\n\
+             blocked_clients:0\r\n\
+             \r\n\
+             # Memory\r\n\
+             used_memory:{}\r\n\
+             maxmemory:0\r\n\
+             maxmemory_policy:noeviction\r\n\
+             \r\n\
+             # Persistence\r\n\
+             rdb_bgsave_in_progress:0\r\n\
+             rdb_last_bgsave_status:ok\r\n\
+             \r\n\
+             # Replication\r\n\
+             role:master\r\n\
+             connected_slaves:0\r\n\
+             \r\n\
+             # Keyspace\r\n",
+            uptime,
+            uptime / 86_400,
+            used_memory
+        );
+        if !self.keyspace.is_empty() {
+            info.push_str(&format!(
+                "db0:keys={},expires=0,avg_ttl=0\r\n",
+                self.keyspace.len()
+            ));
+        }
+        info
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn contains_ssh_key(value: &[u8]) -> bool {
+    let value = String::from_utf8_lossy(value);
+    ["ssh-rsa ", "ssh-ed25519 ", "ecdsa-sha2-"]
+        .iter()
+        .any(|prefix| value.contains(prefix))
+}
+
+fn join_args(args: &[Vec<u8>]) -> String {
+    args.iter()
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resp(args: &[&str]) -> Vec<u8> {
+        let mut out = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            out.extend_from_slice(format!("${}\r\n{}\r\n", arg.len(), arg).as_bytes());
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_resp_and_inline_requests() {
+        let request = resp(&["SET", "k", "a\r\nb"]);
+        let (args, consumed) = parse_request(&request, 1024).unwrap();
+        assert_eq!(args, [b"SET".to_vec(), b"k".to_vec(), b"a\r\nb".to_vec()]);
+        assert_eq!(consumed, request.len());
+
+        for cut in 1..request.len() {
+            assert!(parse_request(&request[..cut], 1024).unwrap_err().is_incomplete());
+        }
+
+        let (args, consumed) = parse_request(b"PING hello\r\nGET", 1024).unwrap();
+        assert_eq!(args, [b"PING".to_vec(), b"hello".to_vec()]);
+        assert_eq!(consumed, 12);
+
+        assert_eq!(
+            parse_request(b"*1\r\n:5\r\n", 1024).unwrap_err(),
+            RedisProtocolError::ExpectedBulk(b':')
+        );
+        assert!(matches!(
+            parse_request(b"*1\r\n$5000\r\n", 1024),
+            Err(RedisProtocolError::RequestTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_oversized_headers_are_rejected_instead_of_buffered() {
+        let max = DEFAULT_MAX_REDIS_REQUEST;
+        let mut header = b"*".to_vec();
+        header.resize(max + 1, b'1');
+        assert!(matches!(
+            parse_request(&header, max),
+            Err(RedisProtocolError::RequestTooLarge { .. })
+        ));
+
+        let mut bulk_header = b"*1\r\n$".to_vec();
+        bulk_header.resize(max + 1, b'1');
+        assert!(matches!(
+            parse_request(&bulk_header, max),
+            Err(RedisProtocolError::RequestTooLarge { .. })
+        ));
+
+        let mut inline = b"PING ".to_vec();
+        inline.resize(max + 1, b'x');
+        assert!(matches!(
+            parse_request(&inline, max),
+            Err(RedisProtocolError::RequestTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"user:*", b"USER:42"));
+        assert!(glob_match(b"?b*d", b"abcd"));
+        assert!(glob_match(b"a*b*c", b"axxbyyc"));
+        assert!(!glob_match(b"a*b*c", b"axxbyy"));
+        assert!(!glob_match(b"a?", b"a"));
+
+        // Exponentiell beim rekursiven Matcher (CVE-2022-36021)
+        let pattern = format!("{}b", "a*".repeat(64));
+        let text = "a".repeat(4096);
+        assert!(!glob_match(pattern.as_bytes(), text.as_bytes()));
+        assert!(glob_match(pattern.as_bytes(), format!("{}b", text).as_bytes()));
+    }
+
+    #[test]
+    fn test_keyspace_quota_replies_oom() {
+        let mut handler =
+            RedisInteractionHandler::new("redis-quota".to_string()).with_keyspace_quota(2, 16);
+        let set = |handler: &mut RedisInteractionHandler, key: &str, value: &str| {
+            handler.handle_command(&[b"SET".to_vec(), key.into(), value.into()])
+        };
+        let oom = RespValue::Error(OOM.to_string());
+
+        assert_eq!(set(&mut handler, "a", "1234"), RespValue::ok());
+        assert_eq!(set(&mut handler, "b", "1234"), RespValue::ok());
+        assert_eq!(set(&mut handler, "c", "1"), oom);
+        // Überschreiben eines Keys zählt nur die Differenz
+        assert_eq!(set(&mut handler, "a", "123456789"), RespValue::ok());
+        assert_eq!(set(&mut handler, "a", "12345678901"), oom);
+
+        handler.handle_command(&[b"DEL".to_vec(), b"a".to_vec()]);
+        assert_eq!(set(&mut handler, "c", "1"), RespValue::ok());
+        handler.handle_command(&[b"FLUSHALL".to_vec()]);
+        assert_eq!(handler.keyspace_bytes, 0);
+        assert_eq!(set(&mut handler, "d", "123456789012345"), RespValue::ok());
+    }
+
+    #[test]
+    fn test_ssh_key_write_sequence_flags_rce() {
+        let mut handler = RedisInteractionHandler::new("redis-1".to_string());
+        let mut run = |args: &[&str]| {
+            let request = resp(args);
+            let (response, consumed) = handler.handle_bytes(&request).unwrap();
+            assert_eq!(consumed, request.len());
+            String::from_utf8(response.unwrap()).unwrap()
+        };
+
+        assert_eq!(run(&["PING"]), "+PONG\r\n");
+        let info = run(&["INFO"]);
+        assert!(info.contains("redis_version:5.0.7\r\n"));
+        assert!(info.contains("os:Linux"));
+        assert!(info.contains("uptime_in_days:37\r\n"));
+
+        assert_eq!(run(&["FLUSHALL"]), "+OK\r\n");
+        assert_eq!(run(&["SET", "x", "\n\nssh-rsa AAAAB3Nz attacker@box\n\n"]), "+OK\r\n");
+        assert_eq!(
+            run(&["CONFIG", "GET", "dir"]),
+            "*2\r\n$3\r\ndir\r\n$14\r\n/var/lib/redis\r\n"
+        );
+        run(&["CONFIG", "SET", "dir", "/root/.ssh/"]);
+        run(&["CONFIG", "SET", "dbfilename", "authorized_keys"]);
+        assert_eq!(run(&["KEYS", "*"]), "*1\r\n$1\r\nx\r\n");
+        assert!(run(&["AUTH", "foobared"]).starts_with("-ERR AUTH"));
+        assert_eq!(run(&["SAVE"]), "+OK\r\n");
+
+        let stats = handler.get_stats();
+        assert!(stats.rce_attempt_detected);
+        assert_eq!(stats.rce_target.as_deref(), Some("/root/.ssh/authorized_keys"));
+        assert_eq!(stats.commands["CONFIG"], 3);
+        assert_eq!(stats.command_count, 10);
+    }
+}
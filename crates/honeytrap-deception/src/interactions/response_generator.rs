@@ -2,14 +2,17 @@
 //!
 //! Intelligente Response-Strategien basierend auf Angreifer-Verhalten
 
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Response Strategy
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
 pub enum ResponseStrategy {
     /// Quick responses, minimal engagement
     Minimal,
     /// Standard realistic responses
+    #[default]
     Standard,
     /// Deep engagement, waste attacker time
     Deep,
@@ -17,11 +20,134 @@ pub enum ResponseStrategy {
     Adaptive,
 }
 
+/// Command-Kategorie für das Delay-Modell
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommandKind {
+    /// Shell-Builtins (pwd, echo, cd, ...)
+    Builtin,
+    /// Dateisystem-Zugriffe (ls, cat, find, ...)
+    Filesystem,
+    /// Prozess-/Systeminfos (ps, uname, id, ...)
+    System,
+    /// Netzwerk (wget, curl, ifconfig, ...)
+    Network,
+    /// Interaktive Tools mit "Denkzeit" (vim, top, sudo, ...)
+    Interactive,
+}
+
+impl CommandKind {
+    /// Command-Namen klassifizieren
+    pub fn classify(command: &str) -> Self {
+        match command {
+            "pwd" | "cd" | "echo" | "export" | "history" | "exit" | "logout" | "" => {
+                CommandKind::Builtin
+            }
+            "ls" | "cat" | "find" | "grep" | "head" | "tail" | "cp" | "mv" | "rm" | "chmod"
+            | "chown" | "touch" | "mkdir" => CommandKind::Filesystem,
+            "wget" | "curl" | "ifconfig" | "ip" | "ping" | "netstat" | "ss" | "nc" | "ssh" => {
+                CommandKind::Network
+            }
+            "vi" | "vim" | "nano" | "top" | "htop" | "less" | "more" | "sudo" | "su"
+            | "passwd" | "mysql" => CommandKind::Interactive,
+            _ => CommandKind::System,
+        }
+    }
+}
+
+/// Delay-Modell: Netzwerk + Verarbeitung, abhängig von Command-Typ und Output-Größe
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DelayModel {
+    /// Netzwerk-Roundtrip (ms)
+    pub network_ms: f64,
+    pub builtin_ms: f64,
+    pub filesystem_ms: f64,
+    pub system_ms: f64,
+    pub network_command_ms: f64,
+    /// Zusätzliche Denkzeit für interaktive Tools (ms)
+    pub think_time_ms: f64,
+    /// Übertragungszeit pro KB Output (ms)
+    pub per_kb_ms: f64,
+}
+
+impl Default for DelayModel {
+    fn default() -> Self {
+        Self {
+            network_ms: 20.0,
+            builtin_ms: 5.0,
+            filesystem_ms: 30.0,
+            system_ms: 50.0,
+            network_command_ms: 400.0,
+            think_time_ms: 800.0,
+            per_kb_ms: 15.0,
+        }
+    }
+}
+
+impl DelayModel {
+    /// Verarbeitungszeit für eine Command-Kategorie
+    fn processing_ms(&self, kind: CommandKind) -> f64 {
+        match kind {
+            CommandKind::Builtin => self.builtin_ms,
+            CommandKind::Filesystem => self.filesystem_ms,
+            CommandKind::System => self.system_ms,
+            CommandKind::Network => self.network_command_ms,
+            CommandKind::Interactive => self.system_ms + self.think_time_ms,
+        }
+    }
+
+    /// Modellierte Latenz für Command und Output-Länge
+    pub fn delay(&self, kind: CommandKind, output_len: usize) -> Duration {
+        let transfer_ms = output_len as f64 / 1024.0 * self.per_kb_ms;
+        Duration::from_secs_f64((self.network_ms + self.processing_ms(kind) + transfer_ms) / 1000.0)
+    }
+}
+
+/// Verzögerung vor Banner/Handshake (Key Exchange, Service-Startup)
+///
+/// Echte Services antworten nie exakt gleich schnell, daher wird um
+/// `base_ms` herum gleichverteilt mit `±jitter_ms` gestreut.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BannerDelay {
+    pub base_ms: f64,
+    #[serde(default)]
+    pub jitter_ms: f64,
+}
+
+impl BannerDelay {
+    /// SSH: Banner nach Verbindungsaufbau/Key-Vorbereitung
+    pub fn ssh() -> Self {
+        Self {
+            base_ms: 200.0,
+            jitter_ms: 80.0,
+        }
+    }
+
+    /// MySQL: Handshake-Latenz
+    pub fn mysql() -> Self {
+        Self {
+            base_ms: 100.0,
+            jitter_ms: 40.0,
+        }
+    }
+
+    /// Zufällige Verzögerung im Bereich `base_ms ± jitter_ms`
+    pub fn sample(&self) -> Duration {
+        let jitter = self.jitter_ms.abs();
+        let offset = if jitter > 0.0 {
+            rand::thread_rng().gen_range(-jitter..=jitter)
+        } else {
+            0.0
+        };
+        Duration::from_secs_f64((self.base_ms + offset).max(0.0) / 1000.0)
+    }
+}
+
 /// Response Generator
 pub struct ResponseGenerator {
     strategy: ResponseStrategy,
     engagement_level: f64,
     time_wasted: std::time::Duration,
+    delay_model: DelayModel,
 }
 
 impl ResponseGenerator {
@@ -30,9 +156,16 @@ impl ResponseGenerator {
             strategy,
             engagement_level: 0.5,
             time_wasted: std::time::Duration::from_secs(0),
+            delay_model: DelayModel::default(),
         }
     }
 
+    /// Mit eigenem Delay-Modell
+    pub fn with_delay_model(mut self, delay_model: DelayModel) -> Self {
+        self.delay_model = delay_model;
+        self
+    }
+
     /// Calculate response delay based on strategy
     pub fn calculate_delay(&self, command_complexity: f64) -> std::time::Duration {
         let base_delay = match self.strategy {
@@ -46,6 +179,20 @@ impl ResponseGenerator {
         std::time::Duration::from_millis(base_delay + complexity_factor)
     }
 
+    /// Modellierte Antwortzeit für ein Command mit gegebener Output-Länge
+    pub fn command_delay(&self, command: &str, output_len: usize) -> Duration {
+        let scale = match self.strategy {
+            ResponseStrategy::Minimal => 0.5,
+            ResponseStrategy::Standard => 1.0,
+            ResponseStrategy::Deep => 2.0,
+            ResponseStrategy::Adaptive => 0.5 + self.engagement_level,
+        };
+
+        self.delay_model
+            .delay(CommandKind::classify(command), output_len)
+            .mul_f64(scale)
+    }
+
     /// Decide if should provide detailed error
     pub fn should_provide_detailed_error(&self) -> bool {
         match self.strategy {
@@ -117,6 +264,17 @@ mod tests {
         assert!(delay > std::time::Duration::from_millis(1000));
     }
 
+    #[test]
+    fn test_large_output_yields_longer_delay() {
+        let gen = ResponseGenerator::new(ResponseStrategy::Standard);
+        let tiny = gen.command_delay("cat", 10);
+        let large = gen.command_delay("cat", 512 * 1024);
+        assert!(large > tiny);
+
+        // Interaktive Tools bekommen Denkzeit
+        assert!(gen.command_delay("vim", 10) > gen.command_delay("pwd", 10));
+    }
+
     #[test]
     fn test_engagement_update() {
         let mut gen = ResponseGenerator::new(ResponseStrategy::Adaptive);
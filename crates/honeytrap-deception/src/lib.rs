@@ -1,11 +1,53 @@
+pub mod canary;
+pub mod capture;
+pub mod clock;
+pub mod credentials;
 pub mod deception_system;
+pub mod decoy;
+pub mod dedup;
+pub mod exfil;
 pub mod honeypots;
+pub mod intel;
 pub mod interactions;
+pub mod personality;
+pub mod pipeline;
+pub mod service_definition;
+pub mod severity;
+pub mod sink;
+pub mod traffic;
 
-pub use deception_system::{DeceptionSystem, HoneypotConfig, InteractionLevel};
-pub use honeypots::{Connection, Honeypot, HoneypotType, Session};
+pub use canary::{CanaryAccess, CanaryEvent, CanaryKind, CanaryRegistry, CanaryToken};
+pub use capture::{CaptureEvent, CaptureKind};
+pub use clock::{
+    Clock, MockClock, RecordingSleeper, Sleeper, SystemClock, TokioClock, TokioSleeper,
+};
+pub use credentials::{CredentialExportFormat, CredentialRecord, CredentialStore};
+pub use deception_system::{
+    DeceptionSystem, DeployOutcome, DirectoryDeployment, HoneypotConfig, InteractionLevel,
+};
+pub use decoy::{DecoyProfile, DecoySeed};
+pub use dedup::Deduplicator;
+pub use exfil::ExfilCounter;
+pub use honeypots::{
+    CloseReason, Connection, DatagramGate, DatagramHandler, Honeypot, HoneypotContext,
+    HoneypotFactory, HoneypotRegistry, HoneypotType, MemoryStream, MqttHoneypot, PeerStream,
+    PostgresHoneypot, RedisHoneypot, Session, SmtpHoneypot, SnmpHoneypot, SshHoneypot,
+    TelnetHoneypot, UdpListener,
+};
+pub use intel::{IntelQuery, IntelReport, IntelStore, DEFAULT_MAX_INTEL_ENTRIES};
 pub use interactions::{
-    CommandParser, FakeFilesystem, HttpInteractionHandler, HttpMethod, HttpRequest,
-    HttpResponse, HttpStats, MysqlInteractionHandler, MysqlResponse, MysqlStats,
-    ResponseGenerator, ResponseStrategy, SshInteractionHandler,
+    BehaviorClass, BehaviorClassifier, CommandParser, ExitBehavior, FakeFilesystem, HttpCapture,
+    HttpInteractionHandler, HttpMethod, HttpRequest, HttpResponse, HttpStats, MqttConnect,
+    MqttInteractionHandler, MqttStats, MultipartPart, MysqlInteractionHandler, MysqlResponse,
+    MysqlStats, PgResponse, PostgresInteractionHandler, PostgresStats, ProxyProbeResponse,
+    RedisInteractionHandler, RedisStats, RequestCapture, ResponseGenerator, ResponseStrategy,
+    SmtpInteractionHandler, SmtpStats,
+    SnmpInteractionHandler, SnmpMessage, SshChannelRequests, SshInteractionHandler,
+    TelnetInteractionHandler, TelnetStats, UnknownCommandStrategy,
 };
+pub use personality::{Personality, PersonalityRandomizer};
+pub use pipeline::{CapturePipeline, CaptureSender, LogSink, DEFAULT_CAPTURE_QUEUE};
+pub use service_definition::{PlantedFile, ServiceDefinition};
+pub use severity::{severity, EventCategory, Severity, SeverityScorer};
+pub use sink::{CaptureSink, CompositeSink, InMemorySink, SinkError};
+pub use traffic::{InboundTap, TrackedStream, TrafficCounter, TrafficStats};
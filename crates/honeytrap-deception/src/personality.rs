@@ -0,0 +1,210 @@
+//! Randomisierte Deployment-"Persönlichkeit"
+//!
+//! Fingerprint-Datenbanken (Shodan & Co.) katalogisieren exakte Banner-,
+//! Header- und Fehlertexte. Damit zwei HoneyTrap-Instanzen nicht
+//! byte-identisch aussehen, werden unkritische Details beim Start aus einem
+//! Pool gewählt. Alles stammt aus derselben OS-Release (OpenSSH, Kernel,
+//! Apache und PHP passen zusammen), sonst wäre die Instanz an der
+//! Kombination erkennbar.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Release-Familie mit zusammenpassenden Paketversionen
+struct ReleaseFamily {
+    /// OpenSSH-Version, Paket-Suffix mit Platzhalter für die Patch-Nummer
+    openssh: &'static str,
+    openssh_package: &'static str,
+    openssh_patches: (u32, u32),
+    kernel: &'static str,
+    kernel_abis: (u32, u32),
+    kernel_build: &'static str,
+    apache: &'static str,
+    php: &'static str,
+}
+
+const RELEASES: &[ReleaseFamily] = &[
+    // Ubuntu 20.04 (focal)
+    ReleaseFamily {
+        openssh: "8.2p1",
+        openssh_package: "Ubuntu-4ubuntu0.{}",
+        openssh_patches: (1, 11),
+        kernel: "5.4.0-{}-generic",
+        kernel_abis: (42, 190),
+        kernel_build: "#{}-Ubuntu SMP Fri Jul 10 00:24:02 UTC 2020",
+        apache: "Apache/2.4.41 (Ubuntu)",
+        php: "PHP/7.4.3",
+    },
+    // Ubuntu 22.04 (jammy)
+    ReleaseFamily {
+        openssh: "8.9p1",
+        openssh_package: "Ubuntu-3ubuntu0.{}",
+        openssh_patches: (1, 10),
+        kernel: "5.15.0-{}-generic",
+        kernel_abis: (56, 119),
+        kernel_build: "#{}-Ubuntu SMP Tue Nov 14 14:59:59 UTC 2023",
+        apache: "Apache/2.4.52 (Ubuntu)",
+        php: "PHP/8.1.2-1ubuntu2.14",
+    },
+    // Debian 11 (bullseye)
+    ReleaseFamily {
+        openssh: "8.4p1",
+        openssh_package: "Debian-5+deb11u{}",
+        openssh_patches: (1, 3),
+        kernel: "5.10.0-{}-amd64",
+        kernel_abis: (20, 30),
+        kernel_build: "#1 SMP Debian 5.10.{}-1 (2023-12-09)",
+        apache: "Apache/2.4.56 (Debian)",
+        php: "PHP/7.4.33",
+    },
+];
+
+/// 404-Seiten in verschiedenen Formulierungen (`{}` = Server-Header)
+const NOT_FOUND_PAGES: &[&str] = &[
+    "<html><body><h1>404 Not Found</h1></body></html>",
+    "<!DOCTYPE HTML PUBLIC \"-//IETF//DTD HTML 2.0//EN\">\n<html><head>\n<title>404 Not Found</title>\n</head><body>\n<h1>Not Found</h1>\n<p>The requested URL was not found on this server.</p>\n<hr>\n<address>{}</address>\n</body></html>\n",
+    "<html><head><title>404 Not Found</title></head><body><h1>Not Found</h1><p>The requested resource could not be found.</p></body></html>",
+];
+
+/// Details, die eine Instanz nach außen zeigt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Personality {
+    /// SSH-Versionsstring (ohne CRLF)
+    pub ssh_banner: String,
+    /// Kernel-Release (`uname -r`)
+    pub kernel_release: String,
+    /// Kernel-Build (`uname -v`)
+    pub kernel_version: String,
+    /// HTTP `Server`-Header
+    pub http_server: String,
+    /// HTTP `X-Powered-By`-Header
+    pub http_powered_by: String,
+    /// Body der 404-Seite
+    pub not_found_body: String,
+}
+
+impl Default for Personality {
+    /// Bisherige feste Werte (Ubuntu 20.04)
+    fn default() -> Self {
+        Self {
+            ssh_banner: "SSH-2.0-OpenSSH_8.2p1 Ubuntu-4ubuntu0.5".to_string(),
+            kernel_release: "5.4.0-42-generic".to_string(),
+            kernel_version: "#46-Ubuntu SMP Fri Jul 10 00:24:02 UTC 2020".to_string(),
+            http_server: "Apache/2.4.41 (Ubuntu)".to_string(),
+            http_powered_by: "PHP/7.4.3".to_string(),
+            not_found_body: NOT_FOUND_PAGES[0].to_string(),
+        }
+    }
+}
+
+/// Würfelt die Persönlichkeit einmal beim Start aus
+#[derive(Debug, Clone)]
+pub struct PersonalityRandomizer {
+    seed: u64,
+    personality: Personality,
+}
+
+impl PersonalityRandomizer {
+    /// Fester Seed: gleiche Persönlichkeit auch über Neustarts
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            personality: Self::generate(seed),
+        }
+    }
+
+    /// Zufälliger Seed pro Prozess
+    pub fn random() -> Self {
+        Self::new(rand::thread_rng().gen())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Persönlichkeit dieser Instanz (immer dieselbe)
+    pub fn personality(&self) -> &Personality {
+        &self.personality
+    }
+
+    fn generate(seed: u64) -> Personality {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let release = &RELEASES[rng.gen_range(0..RELEASES.len())];
+
+        let (low, high) = release.openssh_patches;
+        let package = release
+            .openssh_package
+            .replace("{}", &rng.gen_range(low..=high).to_string());
+        let (low, high) = release.kernel_abis;
+        let abi = rng.gen_range(low..=high);
+        // Ubuntu: Build-Nummer liegt knapp über der ABI, Debian: Upstream-Patchlevel
+        let build = if release.kernel_build.starts_with("#{}") {
+            abi + rng.gen_range(1..=10)
+        } else {
+            rng.gen_range(150..=210)
+        };
+
+        let http_server = release.apache.to_string();
+        let not_found_body = NOT_FOUND_PAGES[rng.gen_range(0..NOT_FOUND_PAGES.len())]
+            .replace("{}", &format!("{} Server", http_server));
+
+        Personality {
+            ssh_banner: format!("SSH-2.0-OpenSSH_{} {}", release.openssh, package),
+            kernel_release: release.kernel.replace("{}", &abi.to_string()),
+            kernel_version: release.kernel_build.replace("{}", &build.to_string()),
+            http_server,
+            http_powered_by: release.php.to_string(),
+            not_found_body,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::RecordingSleeper;
+    use crate::interactions::{
+        HttpInteractionHandler, HttpMethod, HttpRequest, SshInteractionHandler,
+    };
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    async fn fingerprint(personality: &Personality) -> (String, String, String) {
+        let ssh = SshInteractionHandler::new("s".to_string())
+            .with_personality(personality)
+            .with_sleeper(Arc::new(RecordingSleeper::new()));
+        let mut http = HttpInteractionHandler::new("h".to_string()).with_personality(personality);
+        let response = http
+            .handle_request(HttpRequest {
+                method: HttpMethod::GET,
+                path: "/does-not-exist".to_string(),
+                headers: HashMap::new(),
+                body: None,
+            })
+            .await;
+        (
+            ssh.send_banner().await,
+            response.headers["Server"].clone(),
+            response.body,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_seeds_differ_while_instance_stays_consistent() {
+        let randomizer = PersonalityRandomizer::new(7);
+        let first = fingerprint(randomizer.personality()).await;
+        assert_eq!(fingerprint(randomizer.personality()).await, first);
+        assert_eq!(PersonalityRandomizer::new(7).personality(), randomizer.personality());
+
+        let other = fingerprint(PersonalityRandomizer::new(8).personality()).await;
+        assert_ne!(other.0, first.0);
+
+        // OpenSSH, Kernel und Apache stammen aus derselben Release
+        for seed in 0..32 {
+            let personality = PersonalityRandomizer::new(seed).personality().clone();
+            let debian = personality.ssh_banner.contains("Debian");
+            assert_eq!(personality.http_server.contains("Debian"), debian);
+            assert_eq!(personality.kernel_release.ends_with("-amd64"), debian);
+        }
+    }
+}
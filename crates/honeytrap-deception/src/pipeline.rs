@@ -0,0 +1,188 @@
+//! Capture-Pipeline
+//!
+//! Gemeinsamer Eingang für alle Capture-Emitter (Handshake-Monitor, ...).
+//! Events laufen über eine begrenzte Queue in einen Task, der sie durch den
+//! `Deduplicator` schickt und nur die zusammengefassten Events an die
+//! `CaptureSink`s ausliefert. Offene Gruppen werden periodisch und beim
+//! Schließen der Queue geflusht.
+
+use crate::capture::CaptureEvent;
+use crate::clock::Clock;
+use crate::dedup::Deduplicator;
+use crate::sink::{CaptureSink, SinkError};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Max. wartende Events in der Queue
+pub const DEFAULT_CAPTURE_QUEUE: usize = 4096;
+
+/// Eingang der Pipeline für (auch synchrone) Emitter
+#[derive(Debug, Clone)]
+pub struct CaptureSender {
+    tx: mpsc::Sender<CaptureEvent>,
+}
+
+impl CaptureSender {
+    /// Event einreihen, bei voller Queue verwerfen (Emitter blockieren nie)
+    pub fn send(&self, event: CaptureEvent) {
+        if let Err(e) = self.tx.try_send(event) {
+            tracing::debug!("⚠️ Capture event dropped: {}", e);
+        }
+    }
+}
+
+/// Deduplicator vor einer Sink-Kette
+pub struct CapturePipeline {
+    dedup: Deduplicator,
+    sink: Arc<dyn CaptureSink>,
+    capacity: usize,
+}
+
+impl CapturePipeline {
+    /// Pipeline mit Dedup-Fenster (`Duration::ZERO` = keine Deduplizierung)
+    pub fn new(sink: Arc<dyn CaptureSink>, dedup_window: Duration) -> Self {
+        Self {
+            dedup: Deduplicator::new(dedup_window),
+            sink,
+            capacity: DEFAULT_CAPTURE_QUEUE,
+        }
+    }
+
+    /// Mit Queue-Größe
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Mit eigener Zeitquelle für das Dedup-Fenster
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.dedup = self.dedup.with_clock(clock);
+        self
+    }
+
+    /// Event aufnehmen und abgeschlossene Gruppen ausliefern
+    pub async fn submit(&self, event: CaptureEvent) {
+        let ready = self.dedup.push(event);
+        self.deliver(ready).await;
+    }
+
+    /// Gruppen mit abgelaufenem Fenster ausliefern
+    pub async fn flush_expired(&self) {
+        let ready = self.dedup.flush_expired();
+        self.deliver(ready).await;
+    }
+
+    /// Alle offenen Gruppen ausliefern
+    pub async fn flush(&self) {
+        let ready = self.dedup.flush();
+        self.deliver(ready).await;
+    }
+
+    async fn deliver(&self, events: Vec<CaptureEvent>) {
+        for event in events {
+            if let Err(e) = self.sink.emit(&event).await {
+                tracing::warn!(
+                    "⚠️ Capture sink {} failed: {} (Session: {})",
+                    self.sink.name(),
+                    e,
+                    event.session_id
+                );
+            }
+        }
+    }
+
+    /// Queue und Task starten; der Task endet, wenn alle Sender gedroppt sind
+    pub fn spawn(self) -> (CaptureSender, tokio::task::JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::channel(self.capacity.max(1));
+        let tick = self.dedup.window().max(Duration::from_secs(1));
+
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(tick);
+            loop {
+                tokio::select! {
+                    event = rx.recv() => match event {
+                        Some(event) => self.submit(event).await,
+                        None => break,
+                    },
+                    _ = ticker.tick() => self.flush_expired().await,
+                }
+            }
+            self.flush().await;
+        });
+
+        (CaptureSender { tx }, task)
+    }
+}
+
+/// Loggt jedes (zusammengefasste) Event
+#[derive(Debug, Default)]
+pub struct LogSink;
+
+#[async_trait]
+impl CaptureSink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    async fn emit(&self, event: &CaptureEvent) -> Result<(), SinkError> {
+        tracing::info!(
+            event = "capture",
+            count = event.count,
+            "📥 Capture {:?} from {} x{} (Session: {})",
+            event.kind,
+            event
+                .source_ip
+                .map_or_else(|| "unknown".to_string(), |ip| ip.to_string()),
+            event.count,
+            event.session_id
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::CaptureKind;
+    use crate::sink::InMemorySink;
+
+    fn probe(path: &str) -> CaptureEvent {
+        CaptureEvent::new(
+            "scan",
+            CaptureKind::HttpRequest {
+                method: "GET".to_string(),
+                path: path.to_string(),
+            },
+        )
+        .with_source_ip("203.0.113.9".parse().unwrap())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sinks_receive_deduplicated_events() {
+        let sink = Arc::new(InMemorySink::new());
+        let (sender, task) = CapturePipeline::new(sink.clone(), Duration::from_secs(10))
+            .with_clock(Arc::new(crate::clock::TokioClock))
+            .spawn();
+
+        for _ in 0..50 {
+            sender.send(probe("/.env"));
+        }
+        sender.send(probe("/wp-login.php"));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert!(sink.is_empty());
+
+        // Fenster abgelaufen -> Ticker liefert die Gruppen aus
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        let mut counts: Vec<u64> = sink.events().iter().map(|e| e.count).collect();
+        counts.sort();
+        assert_eq!(counts, [1, 50]);
+
+        // Offene Gruppen gehen beim Schließen nicht verloren
+        sender.send(probe("/.env"));
+        drop(sender);
+        task.await.unwrap();
+        assert_eq!(sink.len(), 3);
+    }
+}
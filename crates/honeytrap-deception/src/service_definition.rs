@@ -0,0 +1,72 @@
+//! Service-Definitionen
+//!
+//! Ein Honeypot pro TOML-Datei (Port, Typ, Profil, Banner, Fake-Dateien),
+//! damit Operatoren Services einzeln statt in einer großen Config pflegen.
+
+use crate::deception_system::{HoneypotConfig, InteractionLevel};
+use crate::honeypots::HoneypotType;
+use crate::interactions::{BannerDelay, FakeFilesystem, UnknownCommandStrategy};
+use serde::Deserialize;
+
+/// Definition eines einzelnen Honeypot-Services
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceDefinition {
+    pub port: u16,
+    pub service_type: HoneypotType,
+    #[serde(default)]
+    pub interaction_level: InteractionLevel,
+    /// Persona/Profil (z.B. "ubuntu-web")
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Service-Banner (z.B. SSH-Versionsstring)
+    #[serde(default)]
+    pub banner: Option<String>,
+    /// Verzögerung vor dem Banner (Default: Service-spezifisch)
+    #[serde(default)]
+    pub banner_delay: Option<BannerDelay>,
+    /// Antwort auf unbekannte Commands (z.B. Restricted Shell)
+    #[serde(default)]
+    pub unknown_command: Option<UnknownCommandStrategy>,
+    /// Zusätzliche Dateien im Fake Filesystem
+    #[serde(default)]
+    pub filesystem: Vec<PlantedFile>,
+}
+
+/// Datei, die im Fake Filesystem platziert wird
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlantedFile {
+    pub path: String,
+    #[serde(default = "default_permissions")]
+    pub permissions: String,
+    #[serde(default)]
+    pub content: String,
+}
+
+fn default_permissions() -> String {
+    "-rw-r--r--".to_string()
+}
+
+impl ServiceDefinition {
+    /// Definition aus TOML-Inhalt parsen
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Deployment-Config für `DeceptionSystem::deploy_honeypot`
+    pub fn honeypot_config(&self) -> HoneypotConfig {
+        HoneypotConfig {
+            port: self.port,
+            honeypot_type: self.service_type,
+            interaction_level: self.interaction_level.clone(),
+        }
+    }
+
+    /// Fake Filesystem mit den definierten Dateien
+    pub fn build_filesystem(&self) -> FakeFilesystem {
+        let mut filesystem = FakeFilesystem::new();
+        for file in &self.filesystem {
+            filesystem.plant_file(&file.path, &file.permissions, &file.content);
+        }
+        filesystem
+    }
+}
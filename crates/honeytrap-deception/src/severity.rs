@@ -0,0 +1,219 @@
+//! Alert-Severity
+//!
+//! Bildet Capture-Events auf eine einheitliche `Severity` ab, damit Notifier
+//! und Metriken konsistent filtern. Jede Event-Kategorie hat ein Gewicht
+//! (0-100), das sich pro Deployment überschreiben lässt.
+
+use crate::capture::{CaptureEvent, CaptureKind};
+use honeytrap_metrics::METRICS;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Commands, die Daten oder das System zerstören
+const DESTRUCTIVE_PATTERNS: &[&str] = &[
+    "rm -rf",
+    "mkfs",
+    "dd if=",
+    "shred ",
+    ":(){",
+    "> /dev/sd",
+    "chmod -R 000",
+];
+
+/// Einheitliche Alert-Severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+impl Severity {
+    /// Gewicht (0-100) auf Severity abbilden
+    pub fn from_weight(weight: u8) -> Self {
+        match weight {
+            90.. => Severity::Critical,
+            70..=89 => Severity::High,
+            40..=69 => Severity::Medium,
+            20..=39 => Severity::Low,
+            _ => Severity::Info,
+        }
+    }
+
+    /// Label für `honeytrap_events_by_severity_total`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kategorie eines Events für die Gewichtung
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    Credential,
+    Command,
+    MaliciousCommand,
+    DestructiveCommand,
+    CanaryTrigger,
+    HttpRequest,
+    LlmVerdict,
+    LlmCritical,
+    ModelDisagreement,
+    HandshakeFailure,
+}
+
+impl EventCategory {
+    /// Kategorie eines Capture-Events
+    pub fn of(event: &CaptureEvent) -> Self {
+        match &event.kind {
+            CaptureKind::Credential { .. } => EventCategory::Credential,
+            CaptureKind::Command { command, .. }
+                if DESTRUCTIVE_PATTERNS.iter().any(|p| command.contains(p)) =>
+            {
+                EventCategory::DestructiveCommand
+            }
+            CaptureKind::Command {
+                malicious: true, ..
+            } => EventCategory::MaliciousCommand,
+            CaptureKind::Command { .. } => EventCategory::Command,
+            CaptureKind::Canary { .. } => EventCategory::CanaryTrigger,
+            CaptureKind::HttpRequest { .. } => EventCategory::HttpRequest,
+            CaptureKind::LlmVerdict { threat_level, .. }
+                if threat_level.eq_ignore_ascii_case("critical") =>
+            {
+                EventCategory::LlmCritical
+            }
+            CaptureKind::LlmVerdict { .. } => EventCategory::LlmVerdict,
+            CaptureKind::ModelDisagreement { .. } => EventCategory::ModelDisagreement,
+            CaptureKind::HandshakeFailure { .. } => EventCategory::HandshakeFailure,
+        }
+    }
+
+    /// Standardgewicht (0-100)
+    pub fn default_weight(&self) -> u8 {
+        match self {
+            EventCategory::Command => 0,
+            EventCategory::HandshakeFailure => 5,
+            EventCategory::HttpRequest => 10,
+            EventCategory::ModelDisagreement => 20,
+            EventCategory::LlmVerdict => 30,
+            EventCategory::MaliciousCommand => 45,
+            EventCategory::Credential => 50,
+            EventCategory::DestructiveCommand => 75,
+            EventCategory::LlmCritical => 95,
+            EventCategory::CanaryTrigger => 100,
+        }
+    }
+}
+
+/// Konfigurierbare Gewichtung Kategorie -> Severity
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeverityScorer {
+    /// Überschriebene Gewichte (sonst `EventCategory::default_weight`)
+    #[serde(default)]
+    weights: HashMap<EventCategory, u8>,
+}
+
+impl SeverityScorer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gewicht einer Kategorie überschreiben
+    pub fn with_weight(mut self, category: EventCategory, weight: u8) -> Self {
+        self.weights.insert(category, weight.min(100));
+        self
+    }
+
+    /// Gewicht (0-100) eines Events
+    pub fn weight(&self, event: &CaptureEvent) -> u8 {
+        let category = EventCategory::of(event);
+        self.weights
+            .get(&category)
+            .copied()
+            .unwrap_or_else(|| category.default_weight())
+    }
+
+    /// Severity eines Events
+    pub fn severity(&self, event: &CaptureEvent) -> Severity {
+        Severity::from_weight(self.weight(event))
+    }
+
+    /// Severity bestimmen und in `honeytrap_events_by_severity_total` zählen
+    pub fn record(&self, event: &CaptureEvent) -> Severity {
+        let severity = self.severity(event);
+        METRICS
+            .honeypots
+            .events_by_severity
+            .with_label_values(&[severity.as_str()])
+            .inc();
+        severity
+    }
+}
+
+/// Severity eines Events nach Standard-Gewichtung
+pub fn severity(event: &CaptureEvent) -> Severity {
+    SeverityScorer::default().severity(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_mapping_canary_critical_benign_command_info() {
+        let canary = CaptureEvent::new(
+            "sev",
+            CaptureKind::Canary {
+                token_id: "canary-1".to_string(),
+                location: "/var/www/.env".to_string(),
+            },
+        );
+        let benign = CaptureEvent::new(
+            "sev",
+            CaptureKind::Command {
+                command: "ls -la".to_string(),
+                malicious: false,
+            },
+        );
+        let wipe = CaptureEvent::new(
+            "sev",
+            CaptureKind::Command {
+                command: "rm -rf /var/log".to_string(),
+                malicious: true,
+            },
+        );
+
+        assert_eq!(severity(&canary), Severity::Critical);
+        assert_eq!(severity(&benign), Severity::Info);
+        assert_eq!(severity(&wipe), Severity::High);
+
+        // Gewichte pro Deployment überschreibbar
+        let scorer = SeverityScorer::new().with_weight(EventCategory::Command, 25);
+        assert_eq!(scorer.severity(&benign), Severity::Low);
+
+        let counter = METRICS
+            .honeypots
+            .events_by_severity
+            .with_label_values(&["critical"]);
+        let before = counter.get();
+        assert_eq!(scorer.record(&canary), Severity::Critical);
+        assert_eq!(counter.get(), before + 1);
+    }
+}
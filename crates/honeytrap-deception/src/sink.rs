@@ -0,0 +1,173 @@
+//! Capture-Sinks
+//!
+//! Ziele für `CaptureEvent`s (Datei, Webhook, Store, ...). `CompositeSink`
+//! verteilt ein Event an mehrere Sinks; ein ausgefallener Sink hält die
+//! übrigen nicht auf. `InMemorySink` sammelt Events für Assertions in Tests.
+
+use crate::capture::CaptureEvent;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Fehler eines Sinks (Send, da Sinks aus Tasks heraus beliefert werden)
+pub type SinkError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Ziel für Capture-Events
+#[async_trait]
+pub trait CaptureSink: Send + Sync {
+    /// Name für Logs
+    fn name(&self) -> &str;
+
+    /// Event ausliefern
+    async fn emit(&self, event: &CaptureEvent) -> Result<(), SinkError>;
+}
+
+/// Verteilt Events an mehrere Sinks
+#[derive(Default)]
+pub struct CompositeSink {
+    sinks: Vec<Arc<dyn CaptureSink>>,
+}
+
+impl CompositeSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Weiteren Sink anhängen
+    pub fn with_sink(mut self, sink: Arc<dyn CaptureSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Anzahl angehängter Sinks
+    pub fn len(&self) -> usize {
+        self.sinks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+}
+
+#[async_trait]
+impl CaptureSink for CompositeSink {
+    fn name(&self) -> &str {
+        "composite"
+    }
+
+    /// An alle Sinks ausliefern, auch wenn einzelne fehlschlagen
+    ///
+    /// Fehler werden geloggt und am Ende gesammelt zurückgegeben.
+    async fn emit(&self, event: &CaptureEvent) -> Result<(), SinkError> {
+        let mut failed = Vec::new();
+        for sink in &self.sinks {
+            if let Err(e) = sink.emit(event).await {
+                tracing::warn!(
+                    "⚠️ Capture sink {} failed: {} (Session: {})",
+                    sink.name(),
+                    e,
+                    event.session_id
+                );
+                failed.push(sink.name().to_string());
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("capture sinks failed: {}", failed.join(", ")).into())
+        }
+    }
+}
+
+/// Sammelt Events im Speicher (für Tests)
+#[derive(Debug, Default)]
+pub struct InMemorySink {
+    events: Mutex<Vec<CaptureEvent>>,
+}
+
+impl InMemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bisher empfangene Events (älteste zuerst)
+    pub fn events(&self) -> Vec<CaptureEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.lock().unwrap().is_empty()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+}
+
+#[async_trait]
+impl CaptureSink for InMemorySink {
+    fn name(&self) -> &str {
+        "memory"
+    }
+
+    async fn emit(&self, event: &CaptureEvent) -> Result<(), SinkError> {
+        self.events.lock().unwrap().push(event.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::CaptureKind;
+
+    struct FailingSink;
+
+    #[async_trait]
+    impl CaptureSink for FailingSink {
+        fn name(&self) -> &str {
+            "webhook"
+        }
+
+        async fn emit(&self, _event: &CaptureEvent) -> Result<(), SinkError> {
+            Err("connection refused".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_composite_delivers_to_all_despite_failing_sink() {
+        let first = Arc::new(InMemorySink::new());
+        let second = Arc::new(InMemorySink::new());
+        let event = CaptureEvent::new(
+            "s1",
+            CaptureKind::Command {
+                command: "cat /etc/shadow".to_string(),
+                malicious: true,
+            },
+        );
+
+        let composite = CompositeSink::new()
+            .with_sink(first.clone())
+            .with_sink(second.clone());
+        composite.emit(&event).await.unwrap();
+        assert_eq!(first.events(), vec![event.clone()]);
+        assert_eq!(second.events(), vec![event.clone()]);
+
+        // Fehlschlagender Sink in der Mitte
+        first.clear();
+        second.clear();
+        let composite = CompositeSink::new()
+            .with_sink(first.clone())
+            .with_sink(Arc::new(FailingSink))
+            .with_sink(second.clone());
+        let err = composite.emit(&event).await.unwrap_err();
+
+        assert!(err.to_string().contains("webhook"));
+        assert_eq!(first.len(), 1);
+        assert_eq!(second.len(), 1);
+    }
+}
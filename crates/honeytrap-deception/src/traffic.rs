@@ -0,0 +1,209 @@
+//! Traffic-Tracking pro Connection
+//!
+//! Zählt Bytes, Pakete (einzelne Reads/Writes), Inter-Arrival-Zeiten sowie
+//! Login-Versuche und Commands, während Daten über den Stream fließen -
+//! Grundlage für die Feature-Extraktion der AI. Alle Werte aus Sicht des Peers:
+//! `sent` = vom Angreifer gesendet, `received` = vom Honeypot an ihn.
+//! Eingehende Bytes können zusätzlich an einen `InboundTap` (z.B. den
+//! Traffic-Mirror) gehen.
+
+use crate::interactions::BehaviorClass;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Momentaufnahme der Traffic-Zähler
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrafficStats {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    /// Summe der Abstände zwischen aufeinanderfolgenden Paketen des Peers
+    pub total_gap: Duration,
+    /// Login-Versuche (der Honeypot akzeptiert nur zum Schein)
+    pub failed_logins: u64,
+    pub commands: u64,
+    pub last_packet_at: Option<Instant>,
+    /// Letzte Einstufung des Angreifers durch den Honeypot
+    pub behavior: BehaviorClass,
+}
+
+impl TrafficStats {
+    /// Mittlere Zeit zwischen zwei Paketen des Peers
+    pub fn inter_packet_time(&self) -> Duration {
+        match self.packets_sent {
+            0 | 1 => Duration::ZERO,
+            packets => self.total_gap.div_f64((packets - 1) as f64),
+        }
+    }
+}
+
+/// Beobachter für rohe eingehende Bytes eines Peers
+///
+/// Wird im Read-Pfad aufgerufen und darf daher nicht blockieren.
+pub trait InboundTap: fmt::Debug + Send + Sync {
+    fn inbound(&self, bytes: &[u8]);
+}
+
+/// Traffic einer Connection, von Connection, Session und Handlern geteilt
+#[derive(Debug, Clone, Default)]
+pub struct TrafficCounter {
+    stats: Arc<Mutex<TrafficStats>>,
+    tap: Arc<OnceLock<Arc<dyn InboundTap>>>,
+}
+
+impl TrafficCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paket vom Peer verbuchen
+    pub fn record_sent(&self, bytes: usize) {
+        let now = Instant::now();
+        let mut stats = self.stats.lock().unwrap();
+        if stats.packets_sent > 0 {
+            if let Some(last) = stats.last_packet_at {
+                stats.total_gap += now.duration_since(last);
+            }
+        }
+        stats.bytes_sent += bytes as u64;
+        stats.packets_sent += 1;
+        stats.last_packet_at = Some(now);
+    }
+
+    /// Paket an den Peer verbuchen
+    pub fn record_received(&self, bytes: usize) {
+        let mut stats = self.stats.lock().unwrap();
+        stats.bytes_received += bytes as u64;
+        stats.packets_received += 1;
+    }
+
+    /// Login-Versuch des Peers verbuchen
+    pub fn record_failed_login(&self) {
+        self.stats.lock().unwrap().failed_logins += 1;
+    }
+
+    /// Ausgeführtes Command verbuchen
+    pub fn record_command(&self) {
+        self.stats.lock().unwrap().commands += 1;
+    }
+
+    /// Neue Einstufung des Angreifers verbuchen
+    pub fn record_behavior(&self, behavior: BehaviorClass) {
+        self.stats.lock().unwrap().behavior = behavior;
+    }
+
+    /// Eingehende Bytes zusätzlich an `tap` geben (nur einmal setzbar)
+    pub fn tap_inbound(&self, tap: Arc<dyn InboundTap>) {
+        if self.tap.set(tap).is_err() {
+            tracing::warn!("⚠️ Inbound tap already set, ignoring");
+        }
+    }
+
+    /// Aktuelle Zählerstände
+    pub fn stats(&self) -> TrafficStats {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// Zeit seit dem letzten Paket des Peers (`None` = noch keins)
+    pub fn idle_time(&self) -> Option<Duration> {
+        self.stats.lock().unwrap().last_packet_at.map(|at| at.elapsed())
+    }
+
+    /// Stream so wrappen, dass alle Reads/Writes gezählt werden
+    pub fn track<S>(&self, inner: S) -> TrackedStream<S> {
+        TrackedStream {
+            inner,
+            traffic: self.clone(),
+        }
+    }
+}
+
+/// Stream-Wrapper, der Reads als vom Peer gesendet und Writes als an ihn empfangen zählt
+pub struct TrackedStream<S> {
+    inner: S,
+    traffic: TrafficCounter,
+}
+
+impl<S> TrackedStream<S> {
+    /// Gewrappter Stream (z.B. für `quinn::SendStream::finish`)
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TrackedStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let before = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        let read = buf.filled().len() - before;
+        if read > 0 {
+            self.traffic.record_sent(read);
+            if let Some(tap) = self.traffic.tap.get() {
+                tap.inbound(&buf.filled()[before..]);
+            }
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TrackedStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            if written > 0 {
+                self.traffic.record_received(written);
+            }
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_tracked_stream_counts_bytes_packets_and_gaps() {
+        let traffic = TrafficCounter::new();
+        let (mut peer, server) = tokio::io::duplex(1024);
+        let mut stream = traffic.track(server);
+
+        let mut buf = [0u8; 64];
+        peer.write_all(b"USER root\r\n").await.unwrap();
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 11);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        peer.write_all(b"PASS x\r\n").await.unwrap();
+        assert_eq!(stream.read(&mut buf).await.unwrap(), 8);
+        stream.write_all(b"530 Login incorrect\r\n").await.unwrap();
+
+        let stats = traffic.stats();
+        assert_eq!(stats.bytes_sent, 19);
+        assert_eq!(stats.packets_sent, 2);
+        assert_eq!(stats.bytes_received, 21);
+        assert_eq!(stats.packets_received, 1);
+        assert!(stats.inter_packet_time() >= Duration::from_millis(20));
+        assert!(traffic.idle_time().is_some());
+    }
+}
@@ -0,0 +1,27 @@
+//! Git-Hash für `honeytrap_build_info` ermitteln
+//!
+//! `HONEYTRAP_GIT_HASH` aus der Umgebung hat Vorrang (z.B. in CI/Docker-Builds
+//! ohne `.git`), sonst `git rev-parse --short HEAD`, sonst "unknown".
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=HONEYTRAP_GIT_HASH");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+
+    let git_hash = std::env::var("HONEYTRAP_GIT_HASH")
+        .ok()
+        .filter(|hash| !hash.is_empty())
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|hash| hash.trim().to_string())
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=HONEYTRAP_GIT_HASH={}", git_hash);
+}
@@ -68,6 +68,27 @@ pub struct ConnectionMetrics {
     
     /// Bytes transferred
     pub bytes_total: CounterVec,
+
+    /// Connections rejected by the per-IP rate limiter
+    pub rate_limited: IntCounter,
+
+    /// Connections from known research scanners (by scanner name)
+    pub research_scans: IntCounterVec,
+
+    /// Closed connections by close reason (normal/blocked/rate_limited/...)
+    pub closed: IntCounterVec,
+
+    /// Failed/incomplete TLS/QUIC handshakes by failure reason
+    pub handshake_failures: IntCounterVec,
+
+    /// UDP datagrams dropped before the honeypot handler (blocked/rate_limited)
+    pub datagrams_dropped: IntCounterVec,
+
+    /// QUIC 0-RTT packets received
+    pub zero_rtt_packets: IntCounter,
+
+    /// QUIC 0-RTT attempts (once per source IP and minute)
+    pub zero_rtt_attempts: IntCounter,
 }
 
 impl ConnectionMetrics {
@@ -114,12 +135,80 @@ impl ConnectionMetrics {
         .unwrap();
         registry.register(Box::new(bytes_total.clone())).unwrap();
 
+        let rate_limited = IntCounter::with_opts(Opts::new(
+            "honeytrap_rate_limited_total",
+            "Connections rejected by the per-source-IP rate limiter",
+        ))
+        .unwrap();
+        registry.register(Box::new(rate_limited.clone())).unwrap();
+
+        let research_scans = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_research_scans_total",
+                "Connections from known research scanners",
+            ),
+            &["scanner"],
+        )
+        .unwrap();
+        registry.register(Box::new(research_scans.clone())).unwrap();
+
+        let closed = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_connections_closed_total",
+                "Closed connections by close reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+        registry.register(Box::new(closed.clone())).unwrap();
+
+        let handshake_failures = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_handshake_failures_total",
+                "Failed or incomplete TLS/QUIC handshakes by failure reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+        registry.register(Box::new(handshake_failures.clone())).unwrap();
+
+        let datagrams_dropped = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_datagrams_dropped_total",
+                "UDP datagrams dropped before the honeypot handler by reason",
+            ),
+            &["reason"],
+        )
+        .unwrap();
+        registry.register(Box::new(datagrams_dropped.clone())).unwrap();
+
+        let zero_rtt_packets = IntCounter::with_opts(Opts::new(
+            "honeytrap_zero_rtt_packets_total",
+            "QUIC 0-RTT packets received",
+        ))
+        .unwrap();
+        registry.register(Box::new(zero_rtt_packets.clone())).unwrap();
+
+        let zero_rtt_attempts = IntCounter::with_opts(Opts::new(
+            "honeytrap_zero_rtt_attempts_total",
+            "QUIC 0-RTT attempts, counted once per source IP and minute",
+        ))
+        .unwrap();
+        registry.register(Box::new(zero_rtt_attempts.clone())).unwrap();
+
         Self {
             total,
             active,
             by_result,
             duration,
             bytes_total,
+            rate_limited,
+            research_scans,
+            closed,
+            handshake_failures,
+            datagrams_dropped,
+            zero_rtt_packets,
+            zero_rtt_attempts,
         }
     }
 }
@@ -143,6 +232,12 @@ pub struct HoneypotMetrics {
     
     /// Session duration
     pub session_duration: HistogramVec,
+
+    /// Simulated bytes exfiltrated from sensitive resources
+    pub exfil_bytes: IntCounterVec,
+
+    /// Capture events by alert severity
+    pub events_by_severity: IntCounterVec,
 }
 
 impl HoneypotMetrics {
@@ -214,6 +309,26 @@ impl HoneypotMetrics {
         .unwrap();
         registry.register(Box::new(session_duration.clone())).unwrap();
 
+        let exfil_bytes = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_exfil_bytes_total",
+                "Simulated bytes exfiltrated from sensitive resources",
+            ),
+            &["type"],
+        )
+        .unwrap();
+        registry.register(Box::new(exfil_bytes.clone())).unwrap();
+
+        let events_by_severity = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_events_by_severity_total",
+                "Capture events by alert severity",
+            ),
+            &["severity"],
+        )
+        .unwrap();
+        registry.register(Box::new(events_by_severity.clone())).unwrap();
+
         Self {
             sessions_by_type,
             active_sessions,
@@ -221,6 +336,8 @@ impl HoneypotMetrics {
             commands_executed,
             malicious_commands,
             session_duration,
+            exfil_bytes,
+            events_by_severity,
         }
     }
 }
@@ -241,6 +358,21 @@ pub struct MlMetrics {
     
     /// RL agent Q-values
     pub rl_q_values: GaugeVec,
+
+    /// ML model and LLM disagreed on a session (by which side said malicious)
+    pub llm_disagreement: IntCounterVec,
+
+    /// Live feature distribution diverged from the training distribution
+    pub model_drift_detected: IntCounter,
+
+    /// Population stability index per feature (live vs. training)
+    pub feature_psi: GaugeVec,
+
+    /// LLM response cache lookups by result ("hit" / "miss")
+    pub llm_cache: IntCounterVec,
+
+    /// LLM API retries after 429/5xx/timeouts by provider
+    pub llm_retries: IntCounterVec,
 }
 
 impl MlMetrics {
@@ -292,12 +424,66 @@ impl MlMetrics {
         .unwrap();
         registry.register(Box::new(rl_q_values.clone())).unwrap();
 
+        let llm_disagreement = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_ml_llm_disagreement_total",
+                "Sessions where ML model and LLM verdicts disagreed",
+            ),
+            &["malicious_by"],
+        )
+        .unwrap();
+        registry.register(Box::new(llm_disagreement.clone())).unwrap();
+
+        let model_drift_detected = IntCounter::new(
+            "honeytrap_model_drift_detected_total",
+            "Times the live feature distribution drifted from the training distribution",
+        )
+        .unwrap();
+        registry
+            .register(Box::new(model_drift_detected.clone()))
+            .unwrap();
+
+        let feature_psi = GaugeVec::new(
+            Opts::new(
+                "honeytrap_ml_feature_psi",
+                "Population stability index per feature (live vs. training)",
+            ),
+            &["feature"],
+        )
+        .unwrap();
+        registry.register(Box::new(feature_psi.clone())).unwrap();
+
+        let llm_cache = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_llm_cache_total",
+                "LLM response cache lookups by result",
+            ),
+            &["result"],
+        )
+        .unwrap();
+        registry.register(Box::new(llm_cache.clone())).unwrap();
+
+        let llm_retries = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_llm_retries_total",
+                "LLM API retries after rate limits, server errors or timeouts",
+            ),
+            &["provider"],
+        )
+        .unwrap();
+        registry.register(Box::new(llm_retries.clone())).unwrap();
+
         Self {
             predictions,
             inference_duration,
             anomaly_scores,
             rl_actions,
             rl_q_values,
+            llm_disagreement,
+            model_drift_detected,
+            feature_psi,
+            llm_cache,
+            llm_retries,
         }
     }
 }
@@ -315,6 +501,15 @@ pub struct SystemMetrics {
     
     /// Active goroutines/tasks
     pub active_tasks: IntGauge,
+
+    /// Build/config info (always 1, labels carry the data)
+    pub build_info: IntGaugeVec,
+
+    /// Policy reloads by result ("success" / "failure")
+    pub policy_reloads: IntCounterVec,
+
+    /// Session events dropped because the event queue was full
+    pub session_events_dropped: IntCounter,
 }
 
 impl SystemMetrics {
@@ -347,15 +542,64 @@ impl SystemMetrics {
         .unwrap();
         registry.register(Box::new(active_tasks.clone())).unwrap();
 
-        Self {
+        let build_info = IntGaugeVec::new(
+            Opts::new(
+                "honeytrap_build_info",
+                "Build and configuration info (constant 1)",
+            ),
+            &BUILD_INFO_LABELS,
+        )
+        .unwrap();
+        registry.register(Box::new(build_info.clone())).unwrap();
+
+        let policy_reloads = IntCounterVec::new(
+            Opts::new(
+                "honeytrap_policy_reloads_total",
+                "Policy file reloads by result",
+            ),
+            &["result"],
+        )
+        .unwrap();
+        registry.register(Box::new(policy_reloads.clone())).unwrap();
+
+        let session_events_dropped = IntCounter::with_opts(Opts::new(
+            "honeytrap_session_events_dropped_total",
+            "Session events dropped because the event queue was full",
+        ))
+        .unwrap();
+        registry.register(Box::new(session_events_dropped.clone())).unwrap();
+
+        let metrics = Self {
             uptime_seconds,
             memory_bytes,
             cpu_usage,
             active_tasks,
-        }
+            build_info,
+            policy_reloads,
+            session_events_dropped,
+        };
+        metrics.set_build_info(false, false, 0);
+        metrics
+    }
+
+    /// Set build info labels from the running configuration
+    pub fn set_build_info(&self, quic_enabled: bool, llm_enabled: bool, honeypot_count: usize) {
+        self.build_info.reset();
+        self.build_info
+            .with_label_values(&[
+                env!("CARGO_PKG_VERSION"),
+                env!("HONEYTRAP_GIT_HASH"),
+                &quic_enabled.to_string(),
+                &llm_enabled.to_string(),
+                &honeypot_count.to_string(),
+            ])
+            .set(1);
     }
 }
 
+/// Labels of `honeytrap_build_info`
+const BUILD_INFO_LABELS: [&str; 5] = ["version", "git_hash", "quic", "llm", "honeypots"];
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,6 +638,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_build_info_labels() {
+        let metrics = Metrics::new();
+        metrics.system.set_build_info(true, false, 3);
+
+        let families = metrics.registry().gather();
+        let info = families
+            .iter()
+            .find(|family| family.get_name() == "honeytrap_build_info")
+            .expect("build info registered");
+        assert_eq!(info.get_metric().len(), 1);
+
+        let metric = &info.get_metric()[0];
+        let labels: Vec<(&str, &str)> = metric
+            .get_label()
+            .iter()
+            .map(|label| (label.get_name(), label.get_value()))
+            .collect();
+        let names: Vec<&str> = labels.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["git_hash", "honeypots", "llm", "quic", "version"]);
+        assert!(labels.contains(&("version", env!("CARGO_PKG_VERSION"))));
+        assert!(labels.contains(&("quic", "true")));
+        assert!(labels.contains(&("llm", "false")));
+        assert!(labels.contains(&("honeypots", "3")));
+        assert_eq!(metric.get_gauge().get_value(), 1.0);
+    }
+
     #[test]
     fn test_ml_metrics() {
         let metrics = Metrics::new();
@@ -1,20 +1,130 @@
 //! Metrics Exporter
 //!
-//! HTTP endpoint for Prometheus scraping
+//! HTTP endpoint for Prometheus scraping and Pushgateway client
 
 use prometheus::{Encoder, TextEncoder};
+use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::net::TcpListener;
 
+/// Maximale Größe des Request-Headers
+const MAX_REQUEST_HEADER: usize = 8 * 1024;
+
+/// Timeout für das Lesen des Requests
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout pro Push zum Pushgateway
+const PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Liefert den JSON-Body für `GET /stats`
+pub type StatsProvider =
+    Arc<dyn Fn() -> Pin<Box<dyn Future<Output = String> + Send>> + Send + Sync>;
+
+/// Setzt den Anomalie-Schwellwert für `PUT /threshold?value=<0.0..=1.0>` (Fehlertext bei `Err`)
+pub type ThresholdControl =
+    Arc<dyn Fn(f64) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send>> + Send + Sync>;
+
 /// Metrics HTTP exporter
 pub struct MetricsExporter {
     addr: SocketAddr,
+    stats: Option<StatsProvider>,
+    threshold: Option<ThresholdControl>,
 }
 
 impl MetricsExporter {
     /// Create new exporter
     pub fn new(addr: SocketAddr) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            stats: None,
+            threshold: None,
+        }
+    }
+
+    /// Mit `/stats` Endpoint (JSON, z.B. `honeytrap stats`)
+    pub fn with_stats(mut self, provider: StatsProvider) -> Self {
+        self.stats = Some(provider);
+        self
+    }
+
+    /// Mit `PUT /threshold` Control-Endpoint
+    ///
+    /// Ohne Authentifizierung - nur auf einem eigenen Loopback-Listener aktivieren,
+    /// nicht auf dem scrapebaren Metrics-Port.
+    pub fn with_threshold_control(mut self, control: ThresholdControl) -> Self {
+        self.threshold = Some(control);
+        self
+    }
+
+    /// Metrics periodisch an ein Pushgateway senden (für nicht scrapebare Instanzen)
+    ///
+    /// Fehler und Nicht-2xx-Antworten werden geloggt, der nächste Versuch
+    /// folgt im nächsten Intervall.
+    pub fn push_to_gateway(
+        &self,
+        url: &str,
+        job: &str,
+        interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let instance = std::env::var("HOSTNAME").unwrap_or_else(|_| self.addr.to_string());
+        let push_url = Self::gateway_url(url, job, &instance);
+        tracing::info!("📊 Pushing metrics to {} every {:?}", push_url, interval);
+
+        tokio::spawn(async move {
+            let client = match reqwest::Client::builder().timeout(PUSH_TIMEOUT).build() {
+                Ok(client) => client,
+                Err(e) => {
+                    tracing::error!("📊 Failed to create Pushgateway client: {}", e);
+                    return;
+                }
+            };
+
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = Self::push_once(&client, &push_url).await {
+                    tracing::warn!("📊 Pushgateway push to {} failed: {}", push_url, e);
+                }
+            }
+        })
+    }
+
+    /// `http://gw:9091` + Job/Instance -> `http://gw:9091/metrics/job/<job>/instance/<instance>`
+    fn gateway_url(url: &str, job: &str, instance: &str) -> String {
+        format!(
+            "{}/metrics/job/{}/instance/{}",
+            url.trim_end_matches('/'),
+            job,
+            instance
+        )
+    }
+
+    /// Einzelner Push der aktuellen Registry
+    async fn push_once(client: &reqwest::Client, push_url: &str) -> Result<(), String> {
+        let encoder = TextEncoder::new();
+        let mut body = Vec::new();
+        encoder
+            .encode(&crate::METRICS.registry.gather(), &mut body)
+            .map_err(|e| e.to_string())?;
+
+        let response = client
+            .post(push_url)
+            .header(reqwest::header::CONTENT_TYPE, encoder.format_type())
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("unexpected status {}", response.status()));
+        }
+
+        tracing::debug!("📊 Pushed metrics to {}", push_url);
+        Ok(())
     }
 
     /// Start metrics HTTP server
@@ -26,27 +136,116 @@ impl MetricsExporter {
             let (socket, addr) = listener.accept().await?;
             tracing::debug!("📊 Metrics request from {}", addr);
 
+            let stats = self.stats.clone();
+            let threshold = self.threshold.clone();
             tokio::spawn(async move {
-                let mut buffer = [0; 1024];
-                if let Ok(n) = socket.try_read(&mut buffer) {
-                    let request = String::from_utf8_lossy(&buffer[..n]);
-                    
-                    if request.contains("GET /metrics") {
-                        if let Ok(response) = Self::generate_metrics_response() {
-                            let _ = socket.try_write(response.as_bytes());
-                        }
-                    } else if request.contains("GET /") || request.contains("GET /health") {
-                        let response = Self::health_response();
-                        let _ = socket.try_write(response.as_bytes());
-                    } else {
-                        let response = Self::not_found_response();
-                        let _ = socket.try_write(response.as_bytes());
-                    }
+                if let Err(e) = Self::handle_connection(socket, stats, threshold).await {
+                    tracing::debug!("📊 Metrics request from {} failed: {}", addr, e);
                 }
             });
         }
     }
 
+    /// Einzelne HTTP-Verbindung bedienen
+    async fn handle_connection<S>(
+        mut socket: S,
+        stats: Option<StatsProvider>,
+        threshold: Option<ThresholdControl>,
+    ) -> std::io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let request_line =
+            match tokio::time::timeout(READ_TIMEOUT, Self::read_request_line(&mut socket)).await {
+                Ok(result) => result?,
+                Err(_) => return Ok(()),
+            };
+
+        let route = request_line.as_deref().and_then(Self::parse_request_line);
+        let response = match (route, &stats) {
+            (Some(("GET", "/metrics")), _) => match Self::generate_metrics_response() {
+                Ok(response) => response,
+                Err(e) => {
+                    tracing::warn!("📊 Failed to encode metrics: {}", e);
+                    Self::error_response()
+                }
+            },
+            (Some(("GET", "/stats")), Some(provider)) => Self::json_response(&provider().await),
+            (Some(("PUT", "/threshold")), _) => match &threshold {
+                Some(control) => Self::threshold_response(control, request_line.as_deref()).await,
+                None => Self::not_found_response(),
+            },
+            (Some(("GET", "/" | "/health")), _) => Self::health_response(),
+            _ => Self::not_found_response(),
+        };
+
+        socket.write_all(response.as_bytes()).await?;
+        socket.flush().await?;
+        socket.shutdown().await
+    }
+
+    /// Lesen bis die Request-Zeile vollständig ist (`None` bei EOF/zu lang)
+    async fn read_request_line<S>(socket: &mut S) -> std::io::Result<Option<String>>
+    where
+        S: AsyncRead + Unpin,
+    {
+        let mut buffer = Vec::with_capacity(1024);
+        let mut chunk = [0u8; 1024];
+
+        loop {
+            if let Some(pos) = buffer.windows(2).position(|w| w == b"\r\n") {
+                return Ok(Some(String::from_utf8_lossy(&buffer[..pos]).into_owned()));
+            }
+            if buffer.len() >= MAX_REQUEST_HEADER {
+                return Ok(None);
+            }
+
+            let n = socket.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(None);
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// `GET /metrics?x=1 HTTP/1.1` -> `("GET", "/metrics")`
+    fn parse_request_line(line: &str) -> Option<(&str, &str)> {
+        let mut parts = line.split_whitespace();
+        let method = parts.next()?;
+        let target = parts.next()?;
+        let path = target.split('?').next().unwrap_or(target);
+        Some((method, path))
+    }
+
+    /// `PUT /threshold?value=<x>` an den Control-Hook weiterreichen
+    async fn threshold_response(control: &ThresholdControl, request_line: Option<&str>) -> String {
+        let value = request_line
+            .and_then(|line| Self::query_param(line, "value"))
+            .and_then(|value| value.parse::<f64>().ok());
+        let Some(value) = value else {
+            return Self::bad_request_response("expected ?value=<0.0..=1.0>");
+        };
+
+        match control(value).await {
+            Ok(()) => {
+                tracing::info!("📊 Anomaly threshold set to {} via control endpoint", value);
+                Self::json_response(&format!("{{\"anomaly_threshold\":{}}}", value))
+            }
+            Err(e) => Self::bad_request_response(&e),
+        }
+    }
+
+    /// Query-Parameter aus der Request-Zeile (`PUT /threshold?value=0.9 HTTP/1.1`)
+    fn query_param<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+        let target = line.split_whitespace().nth(1)?;
+        let (_, query) = target.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
     /// Generate Prometheus metrics response
     fn generate_metrics_response() -> Result<String, Box<dyn std::error::Error>> {
         let encoder = TextEncoder::new();
@@ -78,6 +277,42 @@ impl MetricsExporter {
             .to_string()
     }
 
+    /// JSON response (z.B. `/stats`)
+    fn json_response(body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             \r\n\
+             {}",
+            body.len(),
+            body
+        )
+    }
+
+    /// 500 response
+    fn error_response() -> String {
+        "HTTP/1.1 500 Internal Server Error\r\n\
+         Content-Type: text/plain\r\n\
+         Content-Length: 21\r\n\
+         \r\n\
+         Internal Server Error"
+            .to_string()
+    }
+
+    /// 400 response mit Fehlertext
+    fn bad_request_response(message: &str) -> String {
+        format!(
+            "HTTP/1.1 400 Bad Request\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Length: {}\r\n\
+             \r\n\
+             {}",
+            message.len(),
+            message
+        )
+    }
+
     /// 404 response
     fn not_found_response() -> String {
         "HTTP/1.1 404 Not Found\r\n\
@@ -112,4 +347,161 @@ mod tests {
         let response = MetricsExporter::generate_metrics_response();
         assert!(response.is_ok());
     }
+
+    async fn roundtrip(chunks: &[&str], stats: Option<StatsProvider>) -> String {
+        roundtrip_with_control(chunks, stats, None).await
+    }
+
+    async fn roundtrip_with_control(
+        chunks: &[&str],
+        stats: Option<StatsProvider>,
+        threshold: Option<ThresholdControl>,
+    ) -> String {
+        let (mut client, server) = tokio::io::duplex(64);
+        let handle = tokio::spawn(MetricsExporter::handle_connection(server, stats, threshold));
+
+        for chunk in chunks {
+            client.write_all(chunk.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let mut response = String::new();
+        client.read_to_string(&mut response).await.unwrap();
+        handle.await.unwrap().unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_split_request_gets_full_body() {
+        // Request in Teilen, Body größer als der Duplex-Puffer
+        let body = "x".repeat(10_000);
+        let provider: StatsProvider = Arc::new(move || {
+            let body = body.clone();
+            Box::pin(async move { body })
+        });
+
+        let response = roundtrip(&["GET /st", "ats HTTP/1.1\r\n", "\r\n"], Some(provider)).await;
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        assert!(head.contains("Content-Length: 10000"));
+        assert_eq!(body.len(), 10_000);
+    }
+
+    #[test]
+    fn test_gateway_url() {
+        assert_eq!(
+            MetricsExporter::gateway_url("http://gw:9091/", "honeytrap", "edge-1"),
+            "http://gw:9091/metrics/job/honeytrap/instance/edge-1"
+        );
+    }
+
+    /// Header plus Body (laut Content-Length) lesen
+    async fn read_http_request(socket: &mut tokio::net::TcpStream) -> Vec<u8> {
+        let mut request = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            request.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&request);
+            if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                let content_length = head
+                    .lines()
+                    .find_map(|line| {
+                        line.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap())
+                    })
+                    .unwrap_or(0);
+                if body.len() >= content_length {
+                    return request;
+                }
+            }
+            if n == 0 {
+                return request;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_retries_after_error_status() {
+        // Mock-Pushgateway: erst 500, danach 200
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let gateway = listener.local_addr().unwrap();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for status in ["500 Internal Server Error", "200 OK"] {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request = read_http_request(&mut socket).await;
+                let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+                socket.write_all(response.as_bytes()).await.unwrap();
+                tx.send(String::from_utf8_lossy(&request).into_owned())
+                    .unwrap();
+            }
+        });
+
+        crate::METRICS.connections.total.inc();
+        let exporter = MetricsExporter::new("127.0.0.1:9090".parse().unwrap());
+        let task = exporter.push_to_gateway(
+            &format!("http://{}", gateway),
+            "honeytrap",
+            Duration::from_millis(20),
+        );
+
+        let first = rx.recv().await.unwrap();
+        let second = rx.recv().await.unwrap();
+        task.abort();
+
+        assert!(first.starts_with("POST /metrics/job/honeytrap/instance/"));
+        assert!(second.contains("honeytrap_connections_total"));
+    }
+
+    #[tokio::test]
+    async fn test_routes() {
+        let health = roundtrip(&["GET /health HTTP/1.1\r\n\r\n"], None).await;
+        assert!(health.ends_with("{\"status\":\"ok\"}"));
+
+        let stats = roundtrip(&["GET /stats HTTP/1.1\r\n\r\n"], None).await;
+        assert!(stats.starts_with("HTTP/1.1 404"));
+
+        let unknown = roundtrip(&["GET /nope HTTP/1.1\r\n\r\n"], None).await;
+        assert!(unknown.starts_with("HTTP/1.1 404"));
+
+        let metrics = roundtrip(&["GET /metrics?x=1 HTTP/1.1\r\n\r\n"], None).await;
+        assert!(metrics.starts_with("HTTP/1.1 200 OK"));
+    }
+
+    #[tokio::test]
+    async fn test_threshold_control_route() {
+        let current = Arc::new(std::sync::Mutex::new(0.7));
+        let state = current.clone();
+        let control: ThresholdControl = Arc::new(move |value| {
+            let state = state.clone();
+            Box::pin(async move {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(format!("out of range: {}", value));
+                }
+                *state.lock().unwrap() = value;
+                Ok(())
+            })
+        });
+        let put = |line: &'static str| {
+            let control = control.clone();
+            async move { roundtrip_with_control(&[line], None, Some(control)).await }
+        };
+
+        let ok = put("PUT /threshold?value=0.9 HTTP/1.1\r\n\r\n").await;
+        assert!(ok.starts_with("HTTP/1.1 200 OK"));
+        assert!(ok.ends_with("{\"anomaly_threshold\":0.9}"));
+        assert_eq!(*current.lock().unwrap(), 0.9);
+
+        let invalid = put("PUT /threshold?value=1.5 HTTP/1.1\r\n\r\n").await;
+        assert!(invalid.starts_with("HTTP/1.1 400") && invalid.ends_with("out of range: 1.5"));
+        let missing = put("PUT /threshold HTTP/1.1\r\n\r\n").await;
+        assert!(missing.starts_with("HTTP/1.1 400"));
+        assert_eq!(*current.lock().unwrap(), 0.9);
+
+        // Ohne Control-Hook gibt es den Endpoint nicht
+        let disabled = roundtrip(&["PUT /threshold?value=0.1 HTTP/1.1\r\n\r\n"], None).await;
+        assert!(disabled.starts_with("HTTP/1.1 404"));
+    }
 }
@@ -5,9 +5,11 @@
 pub mod collectors;
 pub mod exporter;
 pub mod registry;
+pub mod resources;
 
 pub use collectors::{
     ConnectionMetrics, HoneypotMetrics, MlMetrics, SystemMetrics, METRICS,
 };
-pub use exporter::MetricsExporter;
+pub use exporter::{MetricsExporter, StatsProvider, ThresholdControl};
 pub use registry::MetricsRegistry;
+pub use resources::ResourceMonitor;
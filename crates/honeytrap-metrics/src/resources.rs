@@ -0,0 +1,114 @@
+//! Resource Monitor
+//!
+//! Periodically populates `SystemMetrics` (uptime, RSS, CPU, active tasks).
+//! Process stats are read from `/proc/self` and only available on Linux.
+
+use crate::collectors::SystemMetrics;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Clock ticks per second used by `/proc/<pid>/stat` (USER_HZ)
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// Background updater for system metrics
+pub struct ResourceMonitor {
+    started_at: Instant,
+    reported_uptime: u64,
+    last_cpu: Option<(Instant, f64)>,
+}
+
+impl ResourceMonitor {
+    /// Create monitor, measuring uptime from `started_at`
+    pub fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            reported_uptime: 0,
+            last_cpu: None,
+        }
+    }
+
+    /// Run one update cycle
+    pub fn update(&mut self, metrics: &SystemMetrics, active_tasks: i64) {
+        let uptime = self.started_at.elapsed().as_secs();
+        if uptime > self.reported_uptime {
+            metrics.uptime_seconds.inc_by(uptime - self.reported_uptime);
+            self.reported_uptime = uptime;
+        }
+
+        if let Some(rss) = read_rss_bytes() {
+            metrics.memory_bytes.set(rss);
+        }
+
+        if let Some(cpu_seconds) = read_cpu_seconds() {
+            let now = Instant::now();
+            if let Some((last_at, last_cpu)) = self.last_cpu {
+                let wall = now.duration_since(last_at).as_secs_f64();
+                if wall > 0.0 {
+                    metrics.cpu_usage.set((cpu_seconds - last_cpu) / wall * 100.0);
+                }
+            }
+            self.last_cpu = Some((now, cpu_seconds));
+        }
+
+        metrics.active_tasks.set(active_tasks);
+    }
+
+    /// Spawn periodic updates of the global metrics
+    pub fn spawn<F, Fut>(mut self, interval: Duration, active_tasks: F) -> JoinHandle<()>
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = i64> + Send,
+    {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let active = active_tasks().await;
+                self.update(&crate::METRICS.system, active);
+            }
+        })
+    }
+}
+
+/// Resident set size from `/proc/self/status`
+fn read_rss_bytes() -> Option<i64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: i64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// User + system CPU time from `/proc/self/stat`
+fn read_cpu_seconds() -> Option<f64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    // Felder nach dem Prozessnamen (der Leerzeichen enthalten kann)
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let utime: f64 = fields.get(11)?.parse().ok()?;
+    let stime: f64 = fields.get(12)?.parse().ok()?;
+    Some((utime + stime) / CLOCK_TICKS_PER_SEC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collectors::Metrics;
+
+    #[test]
+    fn test_update_populates_uptime_and_memory() {
+        let metrics = Metrics::new();
+        let mut monitor = ResourceMonitor::new(Instant::now() - Duration::from_secs(5));
+
+        monitor.update(&metrics.system, 3);
+
+        assert!(metrics.system.uptime_seconds.get() >= 5);
+        assert_eq!(metrics.system.active_tasks.get(), 3);
+        if cfg!(target_os = "linux") {
+            assert!(metrics.system.memory_bytes.get() > 0);
+        }
+
+        // Uptime wird nicht doppelt gezählt
+        monitor.update(&metrics.system, 0);
+        assert!(metrics.system.uptime_seconds.get() < 10);
+    }
+}
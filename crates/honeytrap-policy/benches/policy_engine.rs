@@ -0,0 +1,94 @@
+//! Benchmarks für die Policy-Auswertung
+//!
+//! `cargo bench -p honeytrap-policy` - realistisches Regelwerk mit
+//! mTLS-Allowlist, Geo-Blocking, Zeitfenstern und Risk-Score-Deception.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use honeytrap_policy::{EvaluationContext, PolicyEngine};
+
+const POLICIES: &str = r#"
+[[policies]]
+name = "allow-internal-mtls"
+priority = 100
+action = "allow"
+[[policies.conditions.all]]
+mtls_verified = true
+[[policies.conditions.all]]
+san_contains = ".corp.internal"
+
+[[policies]]
+name = "block-sanctioned-countries"
+priority = 90
+action = "block"
+[[policies.conditions.all]]
+country_in = ["KP", "IR", "SY", "CU"]
+
+[[policies]]
+name = "deceive-risky-ssh"
+priority = 50
+action = "deception"
+[[policies.conditions.all]]
+protocol = "ssh"
+[[policies.conditions.any]]
+min_risk_score = 0.7
+[[policies.conditions.any]]
+sni_contains = "admin"
+
+[[policies]]
+name = "deceive-after-hours-admin"
+priority = 40
+action = "deception"
+[[policies.conditions.all]]
+sni_contains = "admin"
+[policies.conditions.all.time_window]
+start = "18:00"
+end = "08:00"
+[[policies.conditions.not]]
+mtls_verified = true
+
+[[policies]]
+name = "allow-office-https"
+priority = 20
+action = "allow"
+[[policies.conditions.all]]
+protocol = "https"
+src_ip_in_cidr = ["10.0.0.0/8", "192.168.0.0/16"]
+
+[[policies]]
+name = "block-everything-else"
+action = "block"
+"#;
+
+fn bench_evaluate(c: &mut Criterion) {
+    let engine = PolicyEngine::new()
+        .with_policies(PolicyEngine::parse_policies("bench.toml", POLICIES).unwrap());
+
+    let internal = EvaluationContext {
+        src_ip: "10.1.2.3".to_string(),
+        protocol: "https".to_string(),
+        san: vec!["build01.corp.internal".to_string()],
+        mtls_verified: true,
+        ..EvaluationContext::default()
+    };
+    // Fällt durch alle Regeln bis zum Default-Block
+    let attacker = EvaluationContext {
+        src_ip: "203.0.113.50".to_string(),
+        protocol: "ssh".to_string(),
+        sni: Some("db.example.com".to_string()),
+        risk_score: 0.3,
+        country: Some("NL".to_string()),
+        ..EvaluationContext::default()
+    };
+
+    let mut group = c.benchmark_group("policy_engine");
+    group.bench_function("first_rule_match", |b| {
+        b.iter(|| engine.evaluate(black_box(&internal)))
+    });
+    group.bench_function("fallthrough", |b| {
+        b.iter(|| engine.evaluate(black_box(&attacker)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_evaluate);
+criterion_main!(benches);
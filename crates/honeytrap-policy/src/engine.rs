@@ -0,0 +1,1043 @@
+//! Policy Engine
+//!
+//! Wertet Policies in Prioritäts-Reihenfolge aus - die erste passende
+//! Policy entscheidet, sonst greift die Default-Entscheidung.
+
+use crate::error::PolicyError;
+use crate::model::{Condition, Decision, EvaluationContext, Policy, PolicyConditions};
+use honeytrap_metrics::METRICS;
+use notify::{RecursiveMode, Watcher};
+use serde::Deserialize;
+use std::fmt;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, RwLock};
+use std::time::Duration;
+
+/// Events eines Speichervorgangs zusammenfassen
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Callback nach jedem Hot-Reload (Anzahl Policies oder Fehler)
+pub type ReloadHook = Arc<dyn Fn(&Result<usize, PolicyError>) + Send + Sync>;
+
+/// Inhalt einer Policy-Datei
+#[derive(Debug, Deserialize)]
+struct PolicyFile {
+    #[serde(default)]
+    policies: Vec<Policy>,
+}
+
+/// Policy Engine
+pub struct PolicyEngine {
+    policies: Arc<RwLock<Vec<Policy>>>,
+    default_decision: Decision,
+    /// Erfolgreiche Hot-Reloads
+    reload_count: Arc<AtomicU64>,
+    reload_hook: Option<ReloadHook>,
+    /// Befüllt der Aufrufer `EvaluationContext::country`?
+    country_lookup: bool,
+}
+
+impl Default for PolicyEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PolicyEngine {
+    /// Neue Engine ohne Policies (Default: Allow)
+    pub fn new() -> Self {
+        Self {
+            policies: Arc::new(RwLock::new(Vec::new())),
+            default_decision: Decision::Allow,
+            reload_count: Arc::new(AtomicU64::new(0)),
+            reload_hook: None,
+            country_lookup: true,
+        }
+    }
+
+    /// Mit Entscheidung, falls keine Policy matcht
+    pub fn with_default_decision(mut self, decision: Decision) -> Self {
+        self.default_decision = decision;
+        self
+    }
+
+    /// Mit Policies
+    pub fn with_policies(self, policies: Vec<Policy>) -> Self {
+        self.set_policies(policies);
+        self
+    }
+
+    /// Ob der Aufrufer das Herkunftsland (Geo-Lookup) liefert
+    ///
+    /// Ohne Geo-Lookup würde `country_in` nie matchen - solche Policies werden
+    /// dann beim Laden und Hot-Reload abgewiesen.
+    pub fn with_country_lookup(mut self, available: bool) -> Self {
+        self.country_lookup = available;
+        self
+    }
+
+    /// Mit Callback nach jedem Hot-Reload (z.B. Alerting bei Fehlern)
+    pub fn with_reload_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Result<usize, PolicyError>) + Send + Sync + 'static,
+    {
+        self.reload_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Policies aus TOML-Dateien laden und atomar ersetzen
+    pub fn load_policies(&self, paths: &[String]) -> Result<usize, PolicyError> {
+        let policies = read_policy_files(paths, self.country_lookup)?;
+        let count = policies.len();
+        self.set_policies(policies);
+        tracing::info!("📜 Loaded {} policies from {} files", count, paths.len());
+
+        Ok(count)
+    }
+
+    /// Policy-Dateien überwachen und bei Änderungen neu laden
+    ///
+    /// Bei Fehlern bleiben die bisherigen Policies aktiv. Überwacht wird, bis
+    /// der zurückgegebene `PolicyWatcher` gedroppt wird.
+    pub fn watch(&self, paths: Vec<String>) -> Result<PolicyWatcher, PolicyError> {
+        let watch_error = |path: &Path, message: String| PolicyError::Watch {
+            path: path.display().to_string(),
+            message,
+        };
+        let all_files = PathBuf::from(paths.join(", "));
+
+        // Verzeichnisse überwachen - Editoren ersetzen Dateien per Rename
+        let mut files = Vec::new();
+        for path in &paths {
+            let path = Path::new(path);
+            let file = path
+                .canonicalize()
+                .map_err(|e| watch_error(path, e.to_string()))?;
+            files.push(file);
+        }
+        let mut dirs: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|file| file.parent().map(Path::to_path_buf))
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)
+            .map_err(|e| watch_error(&all_files, e.to_string()))?;
+        for dir in &dirs {
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|e| watch_error(dir, e.to_string()))?;
+        }
+
+        tracing::info!("📜 Watching {} policy files for changes", files.len());
+        let reloader = Reloader {
+            policies: self.policies.clone(),
+            reload_count: self.reload_count.clone(),
+            hook: self.reload_hook.clone(),
+            country_lookup: self.country_lookup,
+        };
+        std::thread::Builder::new()
+            .name("policy-watcher".to_string())
+            .spawn(move || {
+                while let Ok(event) = rx.recv() {
+                    let changed = match event {
+                        Ok(event) => event.paths.iter().any(|path| files.contains(path)),
+                        Err(e) => {
+                            tracing::warn!("📜 Policy watcher error: {}", e);
+                            false
+                        }
+                    };
+                    if !changed {
+                        continue;
+                    }
+                    while rx.recv_timeout(RELOAD_DEBOUNCE).is_ok() {}
+                    reloader.reload(&paths);
+                }
+            })
+            .map_err(|e| watch_error(&all_files, e.to_string()))?;
+
+        Ok(PolicyWatcher { _watcher: watcher })
+    }
+
+    /// Anzahl erfolgreicher Hot-Reloads
+    pub fn reload_count(&self) -> u64 {
+        self.reload_count.load(Ordering::Relaxed)
+    }
+
+    /// Policies aus TOML-Inhalt parsen
+    pub fn parse_policies(path: &str, content: &str) -> Result<Vec<Policy>, PolicyError> {
+        let file: PolicyFile = toml::from_str(content).map_err(|e| PolicyError::Parse {
+            path: path.to_string(),
+            message: e.to_string(),
+        })?;
+
+        for policy in &file.policies {
+            validate_policy(policy)?;
+        }
+        Ok(file.policies)
+    }
+
+    /// Policies ersetzen (nach Priorität sortiert, stabil)
+    pub fn set_policies(&self, policies: Vec<Policy>) {
+        *self.policies.write().unwrap() = by_priority(policies);
+    }
+
+    /// Aktuelle Policies in Auswertungs-Reihenfolge
+    pub fn policies(&self) -> Vec<Policy> {
+        self.policies.read().unwrap().clone()
+    }
+
+    /// Kontext auswerten
+    pub fn evaluate(&self, context: &EvaluationContext) -> Decision {
+        self.matching_policy(context)
+            .map(|policy| policy.action)
+            .unwrap_or(self.default_decision)
+    }
+
+    /// Erste passende Policy
+    pub fn matching_policy(&self, context: &EvaluationContext) -> Option<Policy> {
+        let policies = self.policies.read().unwrap();
+        let policy = policies
+            .iter()
+            .find(|policy| matches_conditions(&policy.conditions, context))
+            .cloned();
+
+        if let Some(policy) = &policy {
+            tracing::debug!("📜 Policy '{}' matched -> {}", policy.name, policy.action);
+        }
+        policy
+    }
+
+    /// Erklären, welche Policies (nicht) gematcht haben und warum
+    pub fn explain(&self, context: &EvaluationContext) -> PolicyExplanation {
+        let policies = self.policies.read().unwrap();
+
+        let traces: Vec<PolicyTrace> = policies
+            .iter()
+            .map(|policy| explain_policy(policy, context))
+            .collect();
+        let matched = traces.iter().find(|trace| trace.matched);
+
+        PolicyExplanation {
+            matched_policy: matched.map(|trace| trace.name.clone()),
+            decision: matched
+                .map(|trace| trace.action)
+                .unwrap_or(self.default_decision),
+            policies: traces,
+        }
+    }
+}
+
+/// Handle für `PolicyEngine::watch` - Drop beendet die Überwachung
+pub struct PolicyWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Lädt Policies im Watcher-Thread neu
+struct Reloader {
+    policies: Arc<RwLock<Vec<Policy>>>,
+    reload_count: Arc<AtomicU64>,
+    hook: Option<ReloadHook>,
+    country_lookup: bool,
+}
+
+impl Reloader {
+    fn reload(&self, paths: &[String]) {
+        let result = read_policy_files(paths, self.country_lookup).map(|policies| {
+            let count = policies.len();
+            *self.policies.write().unwrap() = by_priority(policies);
+            count
+        });
+
+        match &result {
+            Ok(count) => {
+                self.reload_count.fetch_add(1, Ordering::Relaxed);
+                METRICS
+                    .system
+                    .policy_reloads
+                    .with_label_values(&["success"])
+                    .inc();
+                tracing::info!("📜 Reloaded {} policies", count);
+            }
+            Err(e) => {
+                METRICS
+                    .system
+                    .policy_reloads
+                    .with_label_values(&["failure"])
+                    .inc();
+                tracing::error!("📜 Policy reload failed, keeping previous policies: {}", e);
+            }
+        }
+
+        if let Some(hook) = &self.hook {
+            hook(&result);
+        }
+    }
+}
+
+/// Nach Priorität sortieren (stabil)
+fn by_priority(mut policies: Vec<Policy>) -> Vec<Policy> {
+    policies.sort_by_key(|policy| std::cmp::Reverse(policy.priority));
+    policies
+}
+
+/// Alle Policy-Dateien lesen und parsen (alles oder nichts)
+fn read_policy_files(paths: &[String], country_lookup: bool) -> Result<Vec<Policy>, PolicyError> {
+    let mut policies = Vec::new();
+    for path in paths {
+        let content = std::fs::read_to_string(path).map_err(|source| PolicyError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        policies.extend(PolicyEngine::parse_policies(path, &content)?);
+    }
+
+    if !country_lookup {
+        if let Some(policy) = policies.iter().find(|policy| uses_country(policy)) {
+            return Err(PolicyError::Invalid {
+                policy: policy.name.clone(),
+                message: "country_in needs a geo lookup, but none is configured".to_string(),
+            });
+        }
+    }
+    Ok(policies)
+}
+
+/// Nutzt die Policy `country_in`?
+fn uses_country(policy: &Policy) -> bool {
+    let conditions = &policy.conditions;
+    conditions
+        .all
+        .iter()
+        .chain(&conditions.any)
+        .chain(&conditions.not)
+        .any(|condition| condition.country_in.is_some())
+}
+
+/// Ergebnis einer einzelnen Prüfung
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionCheck {
+    /// Bedingungsgruppe ("all" / "any" / "not")
+    pub group: &'static str,
+    /// Index der Bedingung in der Gruppe
+    pub index: usize,
+    /// Geprüftes Feld
+    pub field: &'static str,
+    /// Erwartung vs. tatsächlicher Wert
+    pub detail: String,
+    pub passed: bool,
+}
+
+impl fmt::Display for ConditionCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}] {}[{}].{}: {}",
+            if self.passed { "pass" } else { "FAIL" },
+            self.group,
+            self.index,
+            self.field,
+            self.detail
+        )
+    }
+}
+
+/// Auswertung einer Policy
+#[derive(Debug, Clone)]
+pub struct PolicyTrace {
+    pub name: String,
+    pub priority: i32,
+    pub action: Decision,
+    pub matched: bool,
+    pub checks: Vec<ConditionCheck>,
+}
+
+impl PolicyTrace {
+    /// Fehlgeschlagene Prüfungen
+    pub fn failed_checks(&self) -> impl Iterator<Item = &ConditionCheck> {
+        self.checks.iter().filter(|check| !check.passed)
+    }
+}
+
+/// Erklärung der Policy-Auswertung für einen Kontext
+#[derive(Debug, Clone)]
+pub struct PolicyExplanation {
+    /// Alle Policies in Prioritäts-Reihenfolge
+    pub policies: Vec<PolicyTrace>,
+    /// Erste passende Policy
+    pub matched_policy: Option<String>,
+    /// Resultierende Entscheidung
+    pub decision: Decision,
+}
+
+impl PolicyExplanation {
+    /// Trace einer Policy nach Name
+    pub fn policy(&self, name: &str) -> Option<&PolicyTrace> {
+        self.policies.iter().find(|trace| trace.name == name)
+    }
+}
+
+impl fmt::Display for PolicyExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Decision: {} (matched: {})",
+            self.decision,
+            self.matched_policy.as_deref().unwrap_or("<default>")
+        )?;
+        for trace in &self.policies {
+            writeln!(
+                f,
+                "  {} '{}' (priority {}, action {})",
+                if trace.matched { "✓" } else { "✗" },
+                trace.name,
+                trace.priority,
+                trace.action
+            )?;
+            for check in &trace.checks {
+                writeln!(f, "      {}", check)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bedingungen beim Laden prüfen, damit Tippfehler nicht still nie matchen
+fn validate_policy(policy: &Policy) -> Result<(), PolicyError> {
+    let conditions = &policy.conditions;
+    for condition in conditions
+        .all
+        .iter()
+        .chain(&conditions.any)
+        .chain(&conditions.not)
+    {
+        let invalid = |message| PolicyError::Invalid {
+            policy: policy.name.clone(),
+            message,
+        };
+        if let Some(window) = &condition.time_window {
+            window.validate().map_err(invalid)?;
+        }
+    }
+    Ok(())
+}
+
+fn matches_conditions(conditions: &PolicyConditions, context: &EvaluationContext) -> bool {
+    conditions
+        .all
+        .iter()
+        .all(|condition| matches_condition(condition, context))
+        && (conditions.any.is_empty()
+            || conditions
+                .any
+                .iter()
+                .any(|condition| matches_condition(condition, context)))
+        && !conditions
+            .not
+            .iter()
+            .any(|condition| matches_condition(condition, context))
+}
+
+/// Bedingung prüfen - Hot Path ohne Allokationen, Diagnose nur über `explain`
+fn matches_condition(condition: &Condition, context: &EvaluationContext) -> bool {
+    let src_ip = || context.src_ip.parse::<IpAddr>().ok();
+
+    condition
+        .protocol
+        .as_ref()
+        .is_none_or(|protocol| protocol.eq_ignore_ascii_case(&context.protocol))
+        && condition.src_ip_in_cidr.as_ref().is_none_or(|cidrs| {
+            src_ip().is_some_and(|ip| cidrs.iter().any(|cidr| cidr.contains(&ip)))
+        })
+        && condition.sni_contains.as_ref().is_none_or(|needle| {
+            context
+                .sni
+                .as_deref()
+                .unwrap_or("")
+                .contains(needle.as_str())
+        })
+        && condition
+            .san_contains
+            .as_ref()
+            .is_none_or(|needle| context.san.iter().any(|san| san.contains(needle.as_str())))
+        && condition
+            .min_risk_score
+            .is_none_or(|min| context.risk_score >= min)
+        && condition
+            .mtls_verified
+            .is_none_or(|expected| context.mtls_verified == expected)
+        && condition
+            .time_window
+            .as_ref()
+            .is_none_or(|window| window.contains(context.now).unwrap_or(false))
+        && condition.country_in.as_ref().is_none_or(|countries| {
+            let country = context.country.as_deref().unwrap_or("unknown");
+            countries
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(country))
+        })
+}
+
+fn explain_policy(policy: &Policy, context: &EvaluationContext) -> PolicyTrace {
+    let mut checks = Vec::new();
+    for (index, condition) in policy.conditions.all.iter().enumerate() {
+        checks.extend(condition_checks(condition, context, "all", index));
+    }
+    for (index, condition) in policy.conditions.any.iter().enumerate() {
+        checks.extend(condition_checks(condition, context, "any", index));
+    }
+    for (index, condition) in policy.conditions.not.iter().enumerate() {
+        // Ausschluss greift nur, wenn die ganze Bedingung zutrifft
+        let excluded = matches_condition(condition, context);
+        checks.extend(
+            condition_checks(condition, context, "not", index)
+                .into_iter()
+                .map(|check| ConditionCheck {
+                    detail: format!("not ({})", check.detail),
+                    passed: !excluded,
+                    ..check
+                }),
+        );
+    }
+
+    PolicyTrace {
+        name: policy.name.clone(),
+        priority: policy.priority,
+        action: policy.action,
+        matched: matches_conditions(&policy.conditions, context),
+        checks,
+    }
+}
+
+/// Einzelprüfungen aller gesetzten Felder einer Bedingung
+fn condition_checks(
+    condition: &Condition,
+    context: &EvaluationContext,
+    group: &'static str,
+    index: usize,
+) -> Vec<ConditionCheck> {
+    let mut checks = Vec::new();
+    let mut check = |field: &'static str, passed: bool, detail: String| {
+        checks.push(ConditionCheck {
+            group,
+            index,
+            field,
+            detail,
+            passed,
+        });
+    };
+
+    if let Some(protocol) = &condition.protocol {
+        check(
+            "protocol",
+            protocol.eq_ignore_ascii_case(&context.protocol),
+            format!("expected {}, got {}", protocol, context.protocol),
+        );
+    }
+
+    if let Some(cidrs) = &condition.src_ip_in_cidr {
+        match context.src_ip.parse::<IpAddr>() {
+            Ok(ip) => {
+                let ranges: Vec<String> = cidrs.iter().map(|cidr| cidr.to_string()).collect();
+                check(
+                    "src_ip_in_cidr",
+                    cidrs.iter().any(|cidr| cidr.contains(&ip)),
+                    format!("{} in [{}]", ip, ranges.join(", ")),
+                )
+            }
+            Err(_) => check(
+                "src_ip_in_cidr",
+                false,
+                format!("invalid source IP '{}'", context.src_ip),
+            ),
+        }
+    }
+
+    if let Some(needle) = &condition.sni_contains {
+        let sni = context.sni.as_deref().unwrap_or("");
+        check(
+            "sni_contains",
+            sni.contains(needle.as_str()),
+            format!("'{}' in '{}'", needle, sni),
+        );
+    }
+
+    if let Some(needle) = &condition.san_contains {
+        check(
+            "san_contains",
+            context.san.iter().any(|san| san.contains(needle.as_str())),
+            format!("'{}' in {:?}", needle, context.san),
+        );
+    }
+
+    if let Some(min) = condition.min_risk_score {
+        check(
+            "min_risk_score",
+            context.risk_score >= min,
+            format!("{:.2} >= {:.2}", context.risk_score, min),
+        );
+    }
+
+    if let Some(expected) = condition.mtls_verified {
+        check(
+            "mtls_verified",
+            context.mtls_verified == expected,
+            format!("expected {}, got {}", expected, context.mtls_verified),
+        );
+    }
+
+    if let Some(window) = &condition.time_window {
+        match window.contains(context.now) {
+            Ok(inside) => check("time_window", inside, format!("now within {}", window)),
+            Err(e) => check("time_window", false, e),
+        }
+    }
+
+    if let Some(countries) = &condition.country_in {
+        let country = context.country.as_deref().unwrap_or("unknown");
+        check(
+            "country_in",
+            countries
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(country)),
+            format!("{} in {:?}", country, countries),
+        );
+    }
+
+    checks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLICIES: &str = r#"
+        [[policies]]
+        name = "allow-internal-mtls"
+        priority = 100
+        action = "allow"
+        [[policies.conditions.all]]
+        mtls_verified = true
+        [[policies.conditions.all]]
+        san_contains = ".corp.internal"
+
+        [[policies]]
+        name = "deceive-risky-ssh"
+        priority = 50
+        action = "deception"
+        [[policies.conditions.all]]
+        protocol = "ssh"
+        [[policies.conditions.any]]
+        min_risk_score = 0.7
+        [[policies.conditions.any]]
+        sni_contains = "admin"
+
+        [[policies]]
+        name = "block-everything-else"
+        action = "block"
+    "#;
+
+    fn engine() -> PolicyEngine {
+        PolicyEngine::new()
+            .with_policies(PolicyEngine::parse_policies("test.toml", POLICIES).unwrap())
+    }
+
+    #[test]
+    fn test_explain_identifies_match_and_failing_condition() {
+        let engine = engine();
+        let context = EvaluationContext {
+            src_ip: "203.0.113.5".to_string(),
+            protocol: "SSH".to_string(),
+            risk_score: 0.85,
+            ..EvaluationContext::default()
+        };
+
+        let explanation = engine.explain(&context);
+        assert_eq!(
+            explanation.matched_policy.as_deref(),
+            Some("deceive-risky-ssh")
+        );
+        assert_eq!(explanation.decision, Decision::Deception);
+        assert_eq!(engine.evaluate(&context), Decision::Deception);
+
+        // Prioritäts-Reihenfolge
+        let names: Vec<&str> = explanation
+            .policies
+            .iter()
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "allow-internal-mtls",
+                "deceive-risky-ssh",
+                "block-everything-else"
+            ]
+        );
+
+        let mtls = explanation.policy("allow-internal-mtls").unwrap();
+        assert!(!mtls.matched);
+        let failed: Vec<&ConditionCheck> = mtls.failed_checks().collect();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].field, "mtls_verified");
+        assert_eq!(failed[0].detail, "expected true, got false");
+
+        let ssh = explanation.policy("deceive-risky-ssh").unwrap();
+        assert!(ssh.matched);
+        // any-Gruppe: SNI schlägt fehl, Risk Score reicht
+        assert_eq!(
+            ssh.failed_checks().map(|c| c.field).collect::<Vec<_>>(),
+            ["sni_contains"]
+        );
+
+        assert!(explanation
+            .to_string()
+            .contains("FAIL] all[0].mtls_verified"));
+    }
+
+    #[test]
+    fn test_default_decision_without_match() {
+        let engine = PolicyEngine::new()
+            .with_default_decision(Decision::Deception)
+            .with_policies(
+                PolicyEngine::parse_policies("test.toml", POLICIES).unwrap()[..2].to_vec(),
+            );
+        let context = EvaluationContext {
+            protocol: "https".to_string(),
+            ..EvaluationContext::default()
+        };
+
+        let explanation = engine.explain(&context);
+        assert_eq!(explanation.matched_policy, None);
+        assert_eq!(explanation.decision, Decision::Deception);
+        assert_eq!(
+            explanation
+                .policy("deceive-risky-ssh")
+                .unwrap()
+                .failed_checks()
+                .next()
+                .unwrap()
+                .detail,
+            "expected ssh, got https"
+        );
+
+        assert!(PolicyEngine::parse_policies("broken.toml", "[[policies]]\nname = 1").is_err());
+    }
+
+    #[test]
+    fn test_not_condition_excludes_match() {
+        let policies = PolicyEngine::parse_policies(
+            "test.toml",
+            r#"
+            [[policies]]
+            name = "deceive-non-https"
+            action = "deception"
+            [[policies.conditions.all]]
+            min_risk_score = 0.5
+            [[policies.conditions.not]]
+            protocol = "https"
+            [[policies.conditions.not]]
+            san_contains = "trusted.example"
+            "#,
+        )
+        .unwrap();
+        let engine = PolicyEngine::new().with_policies(policies);
+
+        // Nicht ausgeschlossen -> Match
+        let ssh = EvaluationContext {
+            protocol: "ssh".to_string(),
+            risk_score: 0.9,
+            san: vec!["client.other.example".to_string()],
+            ..EvaluationContext::default()
+        };
+        assert_eq!(engine.evaluate(&ssh), Decision::Deception);
+
+        // Ausgeschlossenes Protokoll blockiert den Match
+        let https = EvaluationContext {
+            protocol: "HTTPS".to_string(),
+            ..ssh.clone()
+        };
+        assert_eq!(engine.evaluate(&https), Decision::Allow);
+        let explanation = engine.explain(&https);
+        let failed: Vec<String> = explanation
+            .policy("deceive-non-https")
+            .unwrap()
+            .failed_checks()
+            .map(|check| check.to_string())
+            .collect();
+        assert_eq!(
+            failed,
+            ["[FAIL] not[0].protocol: not (expected https, got HTTPS)"]
+        );
+
+        // Ausschluss per SAN
+        let trusted = EvaluationContext {
+            san: vec!["api.trusted.example".to_string()],
+            ..ssh
+        };
+        assert_eq!(engine.evaluate(&trusted), Decision::Allow);
+    }
+
+    /// 2024-01-15 (Montag) um HH:MM UTC
+    fn monday_at(hour: u64, minute: u64) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH
+            + std::time::Duration::from_secs(1_705_276_800 + hour * 3600 + minute * 60)
+    }
+
+    #[test]
+    fn test_time_window_and_country_conditions() {
+        let policies = PolicyEngine::parse_policies(
+            "test.toml",
+            r#"
+            [[policies]]
+            name = "block-admin-off-hours"
+            priority = 10
+            action = "block"
+            [[policies.conditions.all]]
+            sni_contains = "admin"
+            [policies.conditions.all.time_window]
+            start = "18:00"
+            end = "08:00"
+
+            [[policies]]
+            name = "deep-engage-regions"
+            action = "deception"
+            [[policies.conditions.all]]
+            country_in = ["KP", "ir"]
+            "#,
+        )
+        .unwrap();
+        let engine = PolicyEngine::new().with_policies(policies);
+
+        let admin = EvaluationContext {
+            sni: Some("admin.corp.example".to_string()),
+            now: monday_at(22, 30),
+            ..EvaluationContext::default()
+        };
+        assert_eq!(engine.evaluate(&admin), Decision::Block);
+
+        let business_hours = EvaluationContext {
+            now: monday_at(10, 0),
+            ..admin
+        };
+        assert_eq!(engine.evaluate(&business_hours), Decision::Allow);
+
+        let from_ir = EvaluationContext {
+            country: Some("IR".to_string()),
+            ..business_hours.clone()
+        };
+        assert_eq!(engine.evaluate(&from_ir), Decision::Deception);
+
+        let from_de = EvaluationContext {
+            country: Some("DE".to_string()),
+            ..business_hours
+        };
+        assert_eq!(engine.evaluate(&from_de), Decision::Allow);
+        let explanation = engine.explain(&from_de);
+        let check = explanation
+            .policy("deep-engage-regions")
+            .unwrap()
+            .failed_checks()
+            .next()
+            .unwrap();
+        assert_eq!(check.field, "country_in");
+
+        // Wochentag-Einschränkung + ungültige Uhrzeit
+        let window = crate::model::TimeWindow {
+            start: "00:00".to_string(),
+            end: "00:00".to_string(),
+            days: vec!["sat".to_string(), "sun".to_string()],
+        };
+        assert_eq!(window.contains(monday_at(12, 0)), Ok(false));
+        assert!(PolicyEngine::parse_policies(
+            "broken.toml",
+            "[[policies]]\nname = \"x\"\naction = \"block\"\n[[policies.conditions.all]]\ntime_window = { start = \"25:00\", end = \"08:00\" }"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_overnight_window_days_refer_to_window_start() {
+        let window = crate::model::TimeWindow {
+            start: "22:00".to_string(),
+            end: "08:00".to_string(),
+            days: vec!["fri".to_string()],
+        };
+        let friday_at = |hour, minute| {
+            monday_at(hour, minute) + std::time::Duration::from_secs(4 * 86_400)
+        };
+        let saturday_at = |hour, minute| {
+            monday_at(hour, minute) + std::time::Duration::from_secs(5 * 86_400)
+        };
+
+        assert_eq!(window.contains(friday_at(23, 0)), Ok(true));
+        assert_eq!(window.contains(saturday_at(0, 0)), Ok(true));
+        assert_eq!(window.contains(saturday_at(7, 59)), Ok(true));
+        assert_eq!(window.contains(saturday_at(8, 0)), Ok(false));
+        assert_eq!(window.contains(saturday_at(23, 0)), Ok(false));
+        // Freitagmorgen gehört zum Fenster von Donnerstag
+        assert_eq!(window.contains(friday_at(3, 0)), Ok(false));
+    }
+
+    #[test]
+    fn test_country_in_rejected_without_geo_lookup() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-geo-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("policies.toml");
+        std::fs::write(
+            &path,
+            "[[policies]]\nname = \"geo\"\naction = \"block\"\n\
+             [[policies.conditions.not]]\ncountry_in = [\"DE\"]\n",
+        )
+        .unwrap();
+        let paths = vec![path.display().to_string()];
+
+        let err = PolicyEngine::new()
+            .with_country_lookup(false)
+            .load_policies(&paths)
+            .unwrap_err();
+        assert!(matches!(err, PolicyError::Invalid { ref policy, .. } if policy == "geo"));
+        assert!(err.to_string().contains("geo lookup"));
+        assert_eq!(PolicyEngine::new().load_policies(&paths).unwrap(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fast_matcher_agrees_with_explain_checks() {
+        let condition = Condition {
+            protocol: Some("ssh".to_string()),
+            src_ip_in_cidr: Some(vec!["10.0.0.0/8".parse().unwrap()]),
+            sni_contains: Some("admin".to_string()),
+            san_contains: Some(".corp".to_string()),
+            min_risk_score: Some(0.5),
+            mtls_verified: Some(true),
+            time_window: Some(crate::model::TimeWindow {
+                start: "00:00".to_string(),
+                end: "00:00".to_string(),
+                days: Vec::new(),
+            }),
+            country_in: Some(vec!["de".to_string()]),
+        };
+        let matching = EvaluationContext {
+            src_ip: "10.1.2.3".to_string(),
+            protocol: "SSH".to_string(),
+            sni: Some("admin.example".to_string()),
+            san: vec!["host.corp".to_string()],
+            risk_score: 0.9,
+            mtls_verified: true,
+            country: Some("DE".to_string()),
+            ..EvaluationContext::default()
+        };
+        let with = |change: fn(&mut EvaluationContext)| {
+            let mut context = matching.clone();
+            change(&mut context);
+            context
+        };
+        let variants = [
+            matching.clone(),
+            with(|c| c.protocol = "http".to_string()),
+            with(|c| c.src_ip = "192.168.1.1".to_string()),
+            with(|c| c.src_ip = "not-an-ip".to_string()),
+            with(|c| c.sni = None),
+            with(|c| c.san.clear()),
+            with(|c| c.risk_score = 0.1),
+            with(|c| c.mtls_verified = false),
+            with(|c| c.country = None),
+        ];
+
+        for (i, context) in variants.iter().enumerate() {
+            let explained = condition_checks(&condition, context, "all", 0)
+                .iter()
+                .all(|check| check.passed);
+            assert_eq!(matches_condition(&condition, context), explained);
+            assert_eq!(explained, i == 0);
+        }
+    }
+
+    #[test]
+    fn test_src_ip_in_cidr_condition() {
+        let policies = PolicyEngine::parse_policies(
+            "test.toml",
+            r#"
+            [[policies]]
+            name = "allow-internal"
+            action = "allow"
+            [[policies.conditions.all]]
+            src_ip_in_cidr = ["10.0.0.0/8", "2001:db8::/32", "198.51.100.7"]
+            "#,
+        )
+        .unwrap();
+
+        // Beim Laden geparst, einzelne Adresse als Host-Route
+        let cidrs = policies[0].conditions.all[0].src_ip_in_cidr.as_ref().unwrap();
+        assert_eq!(cidrs[2], "198.51.100.7/32".parse::<ipnet::IpNet>().unwrap());
+
+        let engine = PolicyEngine::new()
+            .with_default_decision(Decision::Block)
+            .with_policies(policies);
+        let from = |ip: &str| EvaluationContext {
+            src_ip: ip.to_string(),
+            ..EvaluationContext::default()
+        };
+
+        assert_eq!(engine.evaluate(&from("10.1.2.3")), Decision::Allow);
+        assert_eq!(engine.evaluate(&from("192.168.1.1")), Decision::Block);
+        assert_eq!(engine.evaluate(&from("2001:db8::42")), Decision::Allow);
+        assert_eq!(engine.evaluate(&from("2001:db9::1")), Decision::Block);
+        assert_eq!(engine.evaluate(&from("198.51.100.7")), Decision::Allow);
+        assert_eq!(engine.evaluate(&from("not-an-ip")), Decision::Block);
+
+        let explanation = engine.explain(&from("192.168.1.1"));
+        let check = explanation.policies[0].failed_checks().next().unwrap();
+        assert_eq!(check.field, "src_ip_in_cidr");
+
+        // Ungültige CIDR ist ein Ladefehler
+        let err = PolicyEngine::parse_policies(
+            "broken.toml",
+            "[[policies]]\nname = \"x\"\naction = \"block\"\n[[policies.conditions.all]]\nsrc_ip_in_cidr = [\"10.0.0.0/33\"]",
+        )
+        .unwrap_err();
+        assert!(matches!(err, PolicyError::Parse { .. }));
+        assert!(err.to_string().contains("10.0.0.0/33"));
+    }
+
+    #[test]
+    fn test_watch_reloads_and_keeps_policies_on_parse_error() {
+        let dir = std::env::temp_dir().join(format!("honeytrap-policies-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("policies.toml");
+        let policy = |action: &str| {
+            format!("[[policies]]\nname = \"all\"\naction = \"{}\"\n", action)
+        };
+        std::fs::write(&path, policy("block")).unwrap();
+
+        let (tx, rx) = mpsc::channel();
+        let engine = PolicyEngine::new().with_reload_hook(move |result| {
+            tx.send(result.as_ref().map_err(|e| e.to_string()).cloned())
+                .unwrap();
+        });
+        let paths = vec![path.display().to_string()];
+        engine.load_policies(&paths).unwrap();
+        let _watcher = engine.watch(paths).unwrap();
+        let context = EvaluationContext::default();
+        assert_eq!(engine.evaluate(&context), Decision::Block);
+
+        let timeout = Duration::from_secs(5);
+        std::fs::write(&path, policy("deception")).unwrap();
+        assert_eq!(rx.recv_timeout(timeout).unwrap(), Ok(1));
+        assert_eq!(engine.evaluate(&context), Decision::Deception);
+        assert_eq!(engine.reload_count(), 1);
+
+        // Kaputte Datei: Fehler melden, bisherige Policies behalten
+        std::fs::write(&path, "[[policies]\nname =").unwrap();
+        assert!(rx.recv_timeout(timeout).unwrap().is_err());
+        assert_eq!(engine.evaluate(&context), Decision::Deception);
+        assert_eq!(engine.reload_count(), 1);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Fehler beim Laden/Validieren von Policies
+#[derive(Debug)]
+pub enum PolicyError {
+    /// Policy-Datei nicht lesbar
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    /// Policy-Datei nicht parsebar
+    Parse { path: String, message: String },
+    /// Policy inhaltlich ungültig
+    Invalid { policy: String, message: String },
+    /// Policy-Datei kann nicht überwacht werden
+    Watch { path: String, message: String },
+}
+
+impl fmt::Display for PolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolicyError::Io { path, source } => {
+                write!(f, "Failed to read policy file {}: {}", path, source)
+            }
+            PolicyError::Parse { path, message } => {
+                write!(f, "Failed to parse policy file {}: {}", path, message)
+            }
+            PolicyError::Invalid { policy, message } => {
+                write!(f, "Invalid policy '{}': {}", policy, message)
+            }
+            PolicyError::Watch { path, message } => {
+                write!(f, "Failed to watch policy file {}: {}", path, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PolicyError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
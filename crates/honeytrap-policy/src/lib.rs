@@ -0,0 +1,14 @@
+//! HoneyTrap Policy Engine
+//!
+//! Regelbasierte Entscheidungen (Allow/Block/Deception) anhand von
+//! Verbindungs-Kontext, ergänzend zur AI-Analyse
+
+pub mod engine;
+pub mod error;
+pub mod model;
+
+pub use engine::{
+    ConditionCheck, PolicyEngine, PolicyExplanation, PolicyTrace, PolicyWatcher, ReloadHook,
+};
+pub use error::PolicyError;
+pub use model::{Condition, Decision, EvaluationContext, Policy, PolicyConditions, TimeWindow};
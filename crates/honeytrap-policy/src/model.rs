@@ -0,0 +1,226 @@
+//! Policy-Modell (aus TOML-Dateien ladbar)
+
+use ipnet::IpNet;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Wochentage, Index 0 = Montag
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Ergebnis einer Policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Allow,
+    Block,
+    Deception,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Allow => write!(f, "ALLOW"),
+            Decision::Block => write!(f, "BLOCK"),
+            Decision::Deception => write!(f, "DECEPTION"),
+        }
+    }
+}
+
+/// Einzelne Policy
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Policy {
+    pub name: String,
+    /// Höhere Priorität wird zuerst ausgewertet
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub conditions: PolicyConditions,
+    pub action: Decision,
+}
+
+/// Bedingungsgruppen einer Policy
+///
+/// Alle `all`-Bedingungen, (falls vorhanden) mindestens eine `any`-Bedingung
+/// und keine `not`-Bedingung müssen zutreffen. Ohne Bedingungen matcht die
+/// Policy immer.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct PolicyConditions {
+    #[serde(default)]
+    pub all: Vec<Condition>,
+    #[serde(default)]
+    pub any: Vec<Condition>,
+    /// Ausschlüsse - keine darf zutreffen
+    #[serde(default)]
+    pub not: Vec<Condition>,
+}
+
+/// Bedingung - alle gesetzten Felder müssen zutreffen
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Condition {
+    /// Protokoll (case-insensitive, z.B. "ssh", "https")
+    pub protocol: Option<String>,
+    /// Quell-IP in einer der Ranges (beim Laden geparst)
+    #[serde(
+        default,
+        deserialize_with = "deserialize_cidrs",
+        serialize_with = "serialize_cidrs"
+    )]
+    pub src_ip_in_cidr: Option<Vec<IpNet>>,
+    /// SNI enthält Substring
+    pub sni_contains: Option<String>,
+    /// Ein SAN des Client-Zertifikats enthält Substring
+    pub san_contains: Option<String>,
+    /// Risk Score mindestens
+    pub min_risk_score: Option<f64>,
+    /// mTLS verifiziert
+    pub mtls_verified: Option<bool>,
+    /// Uhrzeit-Fenster (UTC)
+    pub time_window: Option<TimeWindow>,
+    /// Herkunftsland (ISO 3166-1 alpha-2, case-insensitive)
+    pub country_in: Option<Vec<String>>,
+}
+
+/// CIDR-Strings beim Laden parsen - ungültige Ranges sind ein Ladefehler
+fn deserialize_cidrs<'de, D>(deserializer: D) -> Result<Option<Vec<IpNet>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<Vec<String>>::deserialize(deserializer)?
+        .map(|cidrs| cidrs.iter().map(|cidr| parse_cidr(cidr)).collect())
+        .transpose()
+        .map_err(serde::de::Error::custom)
+}
+
+fn serialize_cidrs<S>(cidrs: &Option<Vec<IpNet>>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match cidrs {
+        Some(cidrs) => serializer.collect_seq(cidrs.iter().map(|cidr| cidr.to_string())),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// "10.0.0.0/8", "2001:db8::/32" oder einzelne Adresse
+fn parse_cidr(value: &str) -> Result<IpNet, String> {
+    let value = value.trim();
+    value
+        .parse::<IpNet>()
+        .or_else(|_| value.parse::<IpAddr>().map(IpNet::from))
+        .map_err(|_| format!("Invalid CIDR: {}", value))
+}
+
+/// Uhrzeit-Fenster in UTC, z.B. `start = "18:00"`, `end = "08:00"`
+///
+/// `start > end` läuft über Mitternacht, `start == end` deckt den ganzen Tag ab.
+/// Ohne `days` gilt das Fenster an allen Wochentagen, sonst zählt der Tag,
+/// an dem das Fenster beginnt (`fri` 22:00-08:00 umfasst Samstagmorgen).
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TimeWindow {
+    pub start: String,
+    pub end: String,
+    /// Wochentage ("mon" .. "sun")
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+impl TimeWindow {
+    /// Format prüfen (beim Laden der Policies)
+    pub fn validate(&self) -> Result<(), String> {
+        parse_hhmm(&self.start)?;
+        parse_hhmm(&self.end)?;
+        if let Some(day) = self
+            .days
+            .iter()
+            .find(|day| !WEEKDAYS.contains(&day.to_lowercase().as_str()))
+        {
+            return Err(format!("Invalid weekday: {}", day));
+        }
+        Ok(())
+    }
+
+    /// Liegt der Zeitpunkt im Fenster?
+    pub fn contains(&self, now: SystemTime) -> Result<bool, String> {
+        let start = parse_hhmm(&self.start)?;
+        let end = parse_hhmm(&self.end)?;
+
+        let secs = now
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let minute = ((secs % 86_400) / 60) as u32;
+        let day = secs / 86_400;
+
+        // Über Mitternacht: `days` gilt für den Tag, an dem das Fenster begann
+        let started_on = match start.cmp(&end) {
+            std::cmp::Ordering::Less if minute >= start && minute < end => day,
+            std::cmp::Ordering::Greater if minute >= start => day,
+            // Vortag (modulo 7)
+            std::cmp::Ordering::Greater if minute < end => day + 6,
+            std::cmp::Ordering::Equal => day,
+            _ => return Ok(false),
+        };
+        // 1970-01-01 war ein Donnerstag
+        let weekday = WEEKDAYS[((started_on + 3) % 7) as usize];
+
+        Ok(self.days.is_empty()
+            || self
+                .days
+                .iter()
+                .any(|day| day.eq_ignore_ascii_case(weekday)))
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{} UTC", self.start, self.end)?;
+        if !self.days.is_empty() {
+            write!(f, " on {}", self.days.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// "HH:MM" in Minuten seit Mitternacht
+fn parse_hhmm(value: &str) -> Result<u32, String> {
+    let invalid = || format!("Invalid time '{}', expected HH:MM", value);
+    let (hours, minutes) = value.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+/// Verbindungs-Kontext für die Auswertung
+#[derive(Debug, Clone)]
+pub struct EvaluationContext {
+    pub src_ip: String,
+    pub protocol: String,
+    pub sni: Option<String>,
+    pub san: Vec<String>,
+    pub risk_score: f64,
+    pub mtls_verified: bool,
+    /// Auswertungszeitpunkt
+    pub now: SystemTime,
+    /// Herkunftsland (ISO 3166-1 alpha-2), falls Geo-Daten vorliegen
+    pub country: Option<String>,
+}
+
+impl Default for EvaluationContext {
+    fn default() -> Self {
+        Self {
+            src_ip: String::new(),
+            protocol: String::new(),
+            sni: None,
+            san: Vec::new(),
+            risk_score: 0.0,
+            mtls_verified: false,
+            now: SystemTime::now(),
+            country: None,
+        }
+    }
+}
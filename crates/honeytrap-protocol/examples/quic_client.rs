@@ -4,6 +4,7 @@
 
 use honeytrap_protocol::SecureQuicTransport;
 use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -24,12 +25,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Sende Test-Nachricht
     send.write_all(b"Hello from QUIC client!\n").await?;
-    send.finish()?;
+    send.get_mut().finish()?;
 
     println!("📤 Sent message");
 
     // Empfange Antwort
-    let response = recv.read_to_end(1024).await?;
+    let mut response = Vec::new();
+    recv.read_to_end(&mut response).await?;
     let response_str = String::from_utf8_lossy(&response);
 
     println!("📥 Received: {}", response_str);
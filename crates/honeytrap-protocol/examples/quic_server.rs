@@ -4,6 +4,7 @@
 
 use honeytrap_protocol::SecureQuicTransport;
 use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -40,7 +41,8 @@ async fn handle_connection(
     let (mut send, mut recv) = connection.accept_bi().await?;
 
     // Lese eingehende Nachricht
-    let message = recv.read_to_end(1024).await?;
+    let mut message = Vec::new();
+    recv.read_to_end(&mut message).await?;
     let message_str = String::from_utf8_lossy(&message);
 
     println!("📥 Received: {}", message_str);
@@ -48,7 +50,7 @@ async fn handle_connection(
     // Sende Echo-Antwort
     let response = format!("Echo: {}", message_str);
     send.write_all(response.as_bytes()).await?;
-    send.finish()?;
+    send.get_mut().finish()?;
 
     println!("📤 Sent echo response");
 
@@ -0,0 +1,319 @@
+//! 0-RTT / Early Data Beobachtung
+//!
+//! Quinn/rustls geben serverseitig nicht preis, ob ein Client 0-RTT versucht
+//! hat. Deshalb wird der UDP-Socket gewrappt und eingehende Datagramme auf
+//! 0-RTT Long-Header-Pakete geprüft - ein nützliches Fingerprinting-Signal,
+//! auch (bzw. gerade) wenn 0-RTT serverseitig deaktiviert ist.
+//!
+//! Pakete mit unbekannter QUIC-Version beantwortet Quinn nur mit Version
+//! Negotiation, ohne dass je eine `Incoming` entsteht; sie werden hier als
+//! Handshake-Fehler an den `HandshakeMonitor` gemeldet.
+//!
+//! Quell-Adressen sind fälschbar: ein Versuch wird pro Quell-IP höchstens
+//! einmal je `ATTEMPT_WINDOW` gezählt und geloggt, verfolgt werden maximal
+//! `MAX_TRACKED_SOURCES` IPs.
+
+use crate::handshake::HandshakeMonitor;
+use honeytrap_metrics::METRICS;
+use quinn::udp::{RecvMeta, Transmit};
+use quinn::{AsyncUdpSocket, UdpPoller};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, IoSliceMut};
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+const QUIC_V1: u32 = 0x0000_0001;
+const QUIC_V2: u32 = 0x6b33_43cf;
+
+/// Ein Versuch pro Quell-IP in diesem Fenster
+const ATTEMPT_WINDOW: Duration = Duration::from_secs(60);
+/// Max. gleichzeitig verfolgte Quell-IPs
+const MAX_TRACKED_SOURCES: usize = 4096;
+
+/// Zähler/Logging für 0-RTT Versuche
+#[derive(Debug, Default)]
+pub struct EarlyDataMonitor {
+    enabled: bool,
+    packets: AtomicU64,
+    attempts: AtomicU64,
+    windows: Mutex<HashMap<IpAddr, Instant>>,
+}
+
+impl EarlyDataMonitor {
+    /// Monitor für Server mit aktiviertem/deaktiviertem 0-RTT
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            ..Self::default()
+        }
+    }
+
+    /// Anzahl empfangener 0-RTT Pakete
+    pub fn packets(&self) -> u64 {
+        self.packets.load(Ordering::Relaxed)
+    }
+
+    /// Anzahl 0-RTT Versuche (pro Quell-IP und Fenster)
+    pub fn attempts(&self) -> u64 {
+        self.attempts.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, peer: SocketAddr) {
+        self.packets.fetch_add(1, Ordering::Relaxed);
+        METRICS.connections.zero_rtt_packets.inc();
+        if !self.new_attempt(peer.ip(), Instant::now()) {
+            return;
+        }
+        self.attempts.fetch_add(1, Ordering::Relaxed);
+        METRICS.connections.zero_rtt_attempts.inc();
+
+        if self.enabled {
+            tracing::warn!(
+                event = "zero_rtt_early_data",
+                "⚡ 0-RTT early data from {} (replayable - handlers must stay idempotent)",
+                peer
+            );
+        } else {
+            tracing::info!(
+                event = "zero_rtt_rejected",
+                "🔍 0-RTT attempt from {} rejected (0-RTT disabled)",
+                peer
+            );
+        }
+    }
+
+    /// Neues Fenster für `ip`? Volle Tabelle: abgelaufene verwerfen, sonst ignorieren
+    fn new_attempt(&self, ip: IpAddr, now: Instant) -> bool {
+        let mut windows = self.windows.lock().unwrap();
+
+        if let Some(started) = windows.get_mut(&ip) {
+            if now.saturating_duration_since(*started) < ATTEMPT_WINDOW {
+                return false;
+            }
+            *started = now;
+            return true;
+        }
+
+        if windows.len() >= MAX_TRACKED_SOURCES {
+            windows.retain(|_, started| now.saturating_duration_since(*started) < ATTEMPT_WINDOW);
+            if windows.len() >= MAX_TRACKED_SOURCES {
+                return false;
+            }
+        }
+        windows.insert(ip, now);
+        true
+    }
+}
+
+/// UDP-Socket Wrapper, der eingehende 0-RTT Pakete und unbekannte
+/// QUIC-Versionen meldet
+#[derive(Debug)]
+pub struct EarlyDataSocket {
+    inner: Arc<dyn AsyncUdpSocket>,
+    monitor: Arc<EarlyDataMonitor>,
+    handshakes: Option<Arc<HandshakeMonitor>>,
+}
+
+impl EarlyDataSocket {
+    pub fn new(inner: Arc<dyn AsyncUdpSocket>, monitor: Arc<EarlyDataMonitor>) -> Self {
+        Self {
+            inner,
+            monitor,
+            handshakes: None,
+        }
+    }
+
+    /// Mit Handshake-Monitor für Pakete mit unbekannter Version
+    pub fn with_handshake_monitor(mut self, handshakes: Arc<HandshakeMonitor>) -> Self {
+        self.handshakes = Some(handshakes);
+        self
+    }
+}
+
+impl AsyncUdpSocket for EarlyDataSocket {
+    fn create_io_poller(self: Arc<Self>) -> Pin<Box<dyn UdpPoller>> {
+        self.inner.clone().create_io_poller()
+    }
+
+    fn try_send(&self, transmit: &Transmit) -> io::Result<()> {
+        self.inner.try_send(transmit)
+    }
+
+    fn poll_recv(
+        &self,
+        cx: &mut Context,
+        bufs: &mut [IoSliceMut<'_>],
+        meta: &mut [RecvMeta],
+    ) -> Poll<io::Result<usize>> {
+        let result = self.inner.poll_recv(cx, bufs, meta);
+        if let Poll::Ready(Ok(count)) = &result {
+            for (buf, meta) in bufs.iter().zip(meta.iter()).take(*count) {
+                let stride = meta.stride.max(1);
+                if buf[..meta.len].chunks(stride).any(contains_zero_rtt) {
+                    self.monitor.record(meta.addr);
+                }
+                if let Some(handshakes) = &self.handshakes {
+                    let version = buf[..meta.len].chunks(stride).find_map(unsupported_version);
+                    if let Some(version) = version {
+                        handshakes.record(
+                            meta.addr,
+                            "version_mismatch",
+                            &format!("unsupported version {:#010x}", version),
+                        );
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.local_addr()
+    }
+
+    fn max_transmit_segments(&self) -> usize {
+        self.inner.max_transmit_segments()
+    }
+
+    fn max_receive_segments(&self) -> usize {
+        self.inner.max_receive_segments()
+    }
+
+    fn may_fragment(&self) -> bool {
+        self.inner.may_fragment()
+    }
+}
+
+impl fmt::Display for EarlyDataMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0-RTT {}: {} packets from {} peers",
+            if self.enabled { "enabled" } else { "disabled" },
+            self.packets(),
+            self.attempts()
+        )
+    }
+}
+
+/// Enthält das (ggf. coalesced) Datagramm ein 0-RTT Paket?
+pub fn contains_zero_rtt(datagram: &[u8]) -> bool {
+    let mut rest = datagram;
+
+    while rest.len() >= 7 && rest[0] & 0x80 != 0 {
+        let version = u32::from_be_bytes([rest[1], rest[2], rest[3], rest[4]]);
+        let packet_type = (rest[0] >> 4) & 0x03;
+        let (initial, zero_rtt, retry) = match version {
+            QUIC_V1 => (0, 1, 3),
+            QUIC_V2 => (1, 2, 0),
+            _ => return false,
+        };
+
+        if packet_type == zero_rtt {
+            return true;
+        }
+        if packet_type == retry {
+            return false;
+        }
+
+        let Some(next) = next_long_header_packet(rest, packet_type == initial) else {
+            return false;
+        };
+        rest = next;
+    }
+
+    false
+}
+
+/// QUIC-Version eines Long-Header-Pakets, das weder v1 noch v2 ist
+///
+/// Version 0 (Version Negotiation) senden nur Server und zählt nicht.
+pub fn unsupported_version(datagram: &[u8]) -> Option<u32> {
+    if datagram.len() < 5 || datagram[0] & 0x80 == 0 {
+        return None;
+    }
+    let version = u32::from_be_bytes([datagram[1], datagram[2], datagram[3], datagram[4]]);
+    (!matches!(version, 0 | QUIC_V1 | QUIC_V2)).then_some(version)
+}
+
+/// Rest des Datagramms hinter dem aktuellen Long-Header-Paket
+fn next_long_header_packet(packet: &[u8], is_initial: bool) -> Option<&[u8]> {
+    let mut pos = 5;
+    let dcid_len = *packet.get(pos)? as usize;
+    pos += 1 + dcid_len;
+    let scid_len = *packet.get(pos)? as usize;
+    pos += 1 + scid_len;
+
+    if is_initial {
+        let (token_len, size) = read_varint(packet.get(pos..)?)?;
+        pos += size + token_len as usize;
+    }
+
+    let (length, size) = read_varint(packet.get(pos..)?)?;
+    pos += size + length as usize;
+    packet.get(pos..)
+}
+
+/// QUIC Variable-Length Integer lesen (Wert, Länge)
+fn read_varint(buf: &[u8]) -> Option<(u64, usize)> {
+    let first = *buf.first()?;
+    let size = 1 << (first >> 6);
+    let bytes = buf.get(..size)?;
+    let value = bytes[1..]
+        .iter()
+        .fold(u64::from(first & 0x3f), |acc, b| (acc << 8) | u64::from(*b));
+    Some((value, size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_header(first: u8, body_len: u8, initial: bool) -> Vec<u8> {
+        let mut packet = vec![first, 0, 0, 0, 1, 4, 1, 2, 3, 4, 0];
+        if initial {
+            packet.push(0); // token length
+        }
+        packet.push(body_len);
+        packet.resize(packet.len() + body_len as usize, 0xaa);
+        packet
+    }
+
+    #[test]
+    fn test_detects_coalesced_zero_rtt_packet() {
+        let initial = long_header(0xc0, 20, true);
+        let zero_rtt = long_header(0xd0, 10, false);
+
+        assert!(!contains_zero_rtt(&initial));
+        assert!(contains_zero_rtt(&zero_rtt));
+
+        let coalesced: Vec<u8> = initial.iter().chain(zero_rtt.iter()).copied().collect();
+        assert!(contains_zero_rtt(&coalesced));
+
+        // Short Header (1-RTT) Pakete sind kein 0-RTT
+        assert!(!contains_zero_rtt(&[0x40, 1, 2, 3, 4, 5, 6, 7]));
+    }
+
+    #[test]
+    fn test_spoofed_sources_stay_bounded() {
+        let monitor = EarlyDataMonitor::new(false);
+
+        // Gleiche IP, wechselnde Ports: ein Versuch
+        for port in 1000..1100 {
+            monitor.record(SocketAddr::from(([198, 51, 100, 7], port)));
+        }
+        assert_eq!(monitor.packets(), 100);
+        assert_eq!(monitor.attempts(), 1);
+
+        for i in 0..(MAX_TRACKED_SOURCES as u32 + 100) {
+            monitor.record(SocketAddr::from((i.to_be_bytes(), 443)));
+        }
+        assert_eq!(monitor.windows.lock().unwrap().len(), MAX_TRACKED_SOURCES);
+        assert_eq!(monitor.attempts(), MAX_TRACKED_SOURCES as u64);
+    }
+}
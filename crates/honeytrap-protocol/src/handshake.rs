@@ -0,0 +1,269 @@
+//! Handshake-Fehler als Scan-Intel
+//!
+//! Massenscanner proben QUIC/TLS oft, ohne den Handshake abzuschließen. Solche
+//! Versuche werden nie zur `Connection` und wären sonst unsichtbar. Der
+//! Monitor zählt sie pro Grund, loggt sie, bewertet ihre Severity, hält die
+//! letzten Events als `CaptureEvent` vor und reicht sie an die
+//! Capture-Pipeline weiter.
+//!
+//! Fehler entstehen pro UDP-Paket (z.B. unbekannte QUIC-Version) und die
+//! Quell-IP ist fälschbar. Geloggt wird deshalb nur auf Debug-Level und pro
+//! Quell-IP höchstens einmal je `LOG_WINDOW`, weitere Fehler werden gezählt
+//! und beim nächsten Log zusammengefasst.
+
+use honeytrap_deception::{
+    CaptureEvent, CaptureKind, CaptureSender, Clock, SeverityScorer, SystemClock,
+};
+use honeytrap_metrics::METRICS;
+use std::collections::{HashMap, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Anzahl vorgehaltener Events
+const DEFAULT_CAPACITY: usize = 1024;
+/// Max. ein Log pro Quell-IP in diesem Fenster
+const LOG_WINDOW: Duration = Duration::from_secs(60);
+/// Max. gleichzeitig verfolgte Quell-IPs fürs Logging
+const MAX_LOG_SOURCES: usize = 4096;
+
+/// Log-Fenster einer Quell-IP
+#[derive(Debug)]
+struct LogWindow {
+    started: Instant,
+    suppressed: u64,
+}
+
+/// Zähler und letzte Events fehlgeschlagener Handshakes
+#[derive(Debug)]
+pub struct HandshakeMonitor {
+    transport: &'static str,
+    failures: AtomicU64,
+    recent: Mutex<VecDeque<CaptureEvent>>,
+    capacity: usize,
+    scorer: SeverityScorer,
+    captures: OnceLock<CaptureSender>,
+    log_windows: Mutex<HashMap<IpAddr, LogWindow>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl HandshakeMonitor {
+    /// Monitor für einen Transport ("quic", "tls")
+    pub fn new(transport: &'static str) -> Self {
+        Self {
+            transport,
+            failures: AtomicU64::new(0),
+            recent: Mutex::new(VecDeque::new()),
+            capacity: DEFAULT_CAPACITY,
+            scorer: SeverityScorer::default(),
+            captures: OnceLock::new(),
+            log_windows: Mutex::new(HashMap::new()),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Zeitquelle für das Log-Fenster
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Mit eigener Severity-Gewichtung
+    pub fn with_scorer(mut self, scorer: SeverityScorer) -> Self {
+        self.scorer = scorer;
+        self
+    }
+
+    /// Events zusätzlich an die Capture-Pipeline geben (nur einmal setzbar)
+    pub fn forward_to(&self, captures: CaptureSender) {
+        if self.captures.set(captures).is_err() {
+            tracing::warn!("⚠️ {} handshake monitor already forwards captures", self.transport);
+        }
+    }
+
+    /// Fehlgeschlagenen Handshake festhalten
+    pub fn record(&self, peer_addr: SocketAddr, reason: &str, detail: &str) -> CaptureEvent {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+        METRICS
+            .connections
+            .handshake_failures
+            .with_label_values(&[reason])
+            .inc();
+
+        // Keine Session: der Handshake wurde nie zur Connection
+        let event = CaptureEvent::new(
+            "",
+            CaptureKind::HandshakeFailure {
+                transport: self.transport.to_string(),
+                peer_addr,
+                reason: reason.to_string(),
+            },
+        )
+        .with_source_ip(peer_addr.ip());
+        let severity = self.scorer.record(&event);
+
+        if let Some(suppressed) = self.log_allowed(peer_addr.ip()) {
+            tracing::debug!(
+                event = "handshake_failure",
+                transport = self.transport,
+                peer = %peer_addr,
+                reason,
+                %severity,
+                suppressed,
+                "🤝 {} handshake from {} failed: {} ({}, {} more from this IP not logged)",
+                self.transport,
+                peer_addr,
+                reason,
+                detail,
+                suppressed
+            );
+        }
+
+        let mut recent = self.recent.lock().unwrap();
+        if recent.len() >= self.capacity {
+            recent.pop_front();
+        }
+        recent.push_back(event.clone());
+        if let Some(captures) = self.captures.get() {
+            captures.send(event.clone());
+        }
+        event
+    }
+
+    /// Darf für `ip` geloggt werden? Liefert die seit dem letzten Log unterdrückten Fehler
+    fn log_allowed(&self, ip: IpAddr) -> Option<u64> {
+        let now = self.clock.now();
+        let mut windows = self.log_windows.lock().unwrap();
+
+        if let Some(window) = windows.get_mut(&ip) {
+            if now.saturating_duration_since(window.started) < LOG_WINDOW {
+                window.suppressed += 1;
+                return None;
+            }
+            let suppressed = window.suppressed;
+            *window = LogWindow {
+                started: now,
+                suppressed: 0,
+            };
+            return Some(suppressed);
+        }
+
+        // Viele (gefälschte) Quellen: abgelaufene Fenster verwerfen, sonst nicht loggen
+        if windows.len() >= MAX_LOG_SOURCES {
+            windows.retain(|_, window| now.saturating_duration_since(window.started) < LOG_WINDOW);
+            if windows.len() >= MAX_LOG_SOURCES {
+                return None;
+            }
+        }
+        windows.insert(
+            ip,
+            LogWindow {
+                started: now,
+                suppressed: 0,
+            },
+        );
+        Some(0)
+    }
+
+    /// Anzahl fehlgeschlagener Handshakes
+    pub fn failures(&self) -> u64 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Letzte Handshake-Fehler (älteste zuerst)
+    pub fn recent(&self) -> Vec<CaptureEvent> {
+        self.recent.lock().unwrap().iter().cloned().collect()
+    }
+}
+
+/// Kurzer Grund für Metrik-Label und Capture-Event
+pub fn quic_failure_reason(error: &quinn::ConnectionError) -> &'static str {
+    use quinn::ConnectionError;
+
+    match error {
+        ConnectionError::VersionMismatch => "version_mismatch",
+        ConnectionError::TransportError(_) => "transport_error",
+        ConnectionError::ConnectionClosed(_) => "peer_closed",
+        ConnectionError::ApplicationClosed(_) => "application_closed",
+        ConnectionError::Reset => "reset",
+        ConnectionError::TimedOut => "timed_out",
+        ConnectionError::LocallyClosed => "locally_closed",
+        ConnectionError::CidsExhausted => "cids_exhausted",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_deception::EventCategory;
+
+    #[test]
+    fn test_failures_are_scored_when_recorded() {
+        let monitor = HandshakeMonitor::new("quic")
+            .with_scorer(SeverityScorer::new().with_weight(EventCategory::HandshakeFailure, 100));
+        let counter = METRICS
+            .honeypots
+            .events_by_severity
+            .with_label_values(&["critical"]);
+        let before = counter.get();
+
+        let event = monitor.record("198.51.100.9:4433".parse().unwrap(), "timed_out", "idle");
+        assert_eq!(counter.get(), before + 1);
+        assert_eq!(monitor.recent(), [event]);
+    }
+
+    #[test]
+    fn test_logging_is_rate_limited_per_source_ip() {
+        let clock = Arc::new(honeytrap_deception::MockClock::new());
+        let monitor = HandshakeMonitor::new("quic").with_clock(clock.clone());
+        let flood: IpAddr = "198.51.100.20".parse().unwrap();
+
+        // Flut mit unbekannter Version: einmal loggen, Rest nur zählen
+        assert_eq!(monitor.log_allowed(flood), Some(0));
+        for _ in 0..1000 {
+            assert_eq!(monitor.log_allowed(flood), None);
+        }
+        assert_eq!(monitor.log_allowed("198.51.100.21".parse().unwrap()), Some(0));
+
+        clock.advance(LOG_WINDOW);
+        assert_eq!(monitor.log_allowed(flood), Some(1000));
+        assert_eq!(monitor.log_allowed(flood), None);
+
+        // Gefälschte Quellen füllen die Tabelle nicht unbegrenzt
+        for i in 0..2 * MAX_LOG_SOURCES as u32 {
+            monitor.log_allowed(IpAddr::from((0x0a00_0000 + i).to_be_bytes()));
+        }
+        assert!(monitor.log_windows.lock().unwrap().len() <= MAX_LOG_SOURCES);
+
+        // Metriken und Events zählen trotzdem jeden Fehler
+        for _ in 0..3 {
+            monitor.record(SocketAddr::new(flood, 4433), "version_mismatch", "0xbabababa");
+        }
+        assert_eq!(monitor.failures(), 3);
+        assert_eq!(monitor.recent().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failures_forwarded_to_capture_pipeline() {
+        use honeytrap_deception::{CapturePipeline, InMemorySink};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        let sink = Arc::new(InMemorySink::new());
+        let (captures, task) = CapturePipeline::new(sink.clone(), Duration::from_secs(60)).spawn();
+        let monitor = HandshakeMonitor::new("quic");
+        monitor.forward_to(captures);
+
+        // Scanner verbindet pro Versuch von neuem Quellport
+        for port in 40000..40020 {
+            monitor.record(([198, 51, 100, 10], port).into(), "timed_out", "idle");
+        }
+        drop(monitor);
+        task.await.unwrap();
+
+        let events = sink.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].count, 20);
+    }
+}
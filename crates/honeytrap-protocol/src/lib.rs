@@ -1,9 +1,24 @@
+pub mod early_data;
+pub mod handshake;
+pub mod memory;
+pub mod mirror;
 pub mod quic;
 pub mod stream;
+pub mod transport;
 
 // Connection wird von honeytrap-deception bereitgestellt
+pub use early_data::EarlyDataMonitor;
+pub use handshake::HandshakeMonitor;
 pub use honeytrap_deception::Connection;
-pub use quic::SecureQuicTransport;
+pub use memory::{MemoryNetwork, MemoryTransport};
+#[cfg(unix)]
+pub use mirror::UnixSocketSink;
+pub use mirror::{
+    FileSink, MemorySink, MirrorFrame, MirrorSink, SessionMirror, TrafficMirror,
+    DEFAULT_MIRROR_QUEUE,
+};
+pub use quic::{SecureQuicTransport, TransportParams};
+pub use transport::Transport;
 
 #[cfg(feature = "quic")]
 pub use stream::{QuicLineReader, QuicStream};
@@ -0,0 +1,190 @@
+//! In-Memory Transport
+//!
+//! Implementiert die `Transport` Schnittstelle über In-Process Channels und
+//! `tokio::io::duplex` Streams - für schnelle, deterministische
+//! Integrationstests ohne UDP/QUIC.
+
+use crate::transport::Transport;
+use async_trait::async_trait;
+use honeytrap_deception::Connection;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// Puffergröße der Duplex-Streams
+const STREAM_BUFFER: usize = 64 * 1024;
+
+/// Simuliertes Netzwerk, in dem sich `MemoryTransport`s finden
+#[derive(Clone, Default)]
+pub struct MemoryNetwork {
+    listeners: Arc<Mutex<HashMap<SocketAddr, mpsc::UnboundedSender<Connection>>>>,
+}
+
+impl MemoryNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transport an Adresse binden
+    pub fn bind(&self, addr: SocketAddr) -> Result<MemoryTransport, Box<dyn std::error::Error>> {
+        let mut listeners = self.listeners.lock().unwrap();
+        if listeners.contains_key(&addr) {
+            return Err(format!("Address already in use: {}", addr).into());
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        listeners.insert(addr, tx);
+
+        Ok(MemoryTransport {
+            network: self.clone(),
+            local_addr: addr,
+            incoming: tokio::sync::Mutex::new(rx),
+        })
+    }
+}
+
+/// In-Memory Transport
+pub struct MemoryTransport {
+    network: MemoryNetwork,
+    local_addr: SocketAddr,
+    incoming: tokio::sync::Mutex<mpsc::UnboundedReceiver<Connection>>,
+}
+
+#[async_trait]
+impl Transport for MemoryTransport {
+    async fn accept(&self) -> Result<(Connection, SocketAddr), Box<dyn std::error::Error>> {
+        let connection = self
+            .incoming
+            .lock()
+            .await
+            .recv()
+            .await
+            .ok_or("Endpoint closed")?;
+        let peer_addr = connection.peer_addr;
+
+        tracing::debug!("📥 Accepting in-memory connection from {}", peer_addr);
+
+        Ok((connection, peer_addr))
+    }
+
+    async fn connect(
+        &self,
+        addr: SocketAddr,
+        server_name: &str,
+    ) -> Result<Connection, Box<dyn std::error::Error>> {
+        tracing::debug!("🔌 Connecting (in-memory) to {}@{}", server_name, addr);
+
+        let listener = self
+            .network
+            .listeners
+            .lock()
+            .unwrap()
+            .get(&addr)
+            .cloned()
+            .ok_or_else(|| format!("Connection refused: {}", addr))?;
+
+        let (client, server) = tokio::io::duplex(STREAM_BUFFER);
+        listener
+            .send(Connection::with_memory(self.local_addr, server).with_local_addr(addr))
+            .map_err(|_| format!("Connection refused: {}", addr))?;
+
+        Ok(Connection::with_memory(addr, client).with_local_addr(self.local_addr))
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    async fn close(&self) {
+        self.network.listeners.lock().unwrap().remove(&self.local_addr);
+        self.incoming.lock().await.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_deception::{Honeypot, HoneypotType, Session};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// Verdächtige Session für den Honeypot-Handler
+    fn test_session(id: &str, peer_addr: SocketAddr) -> Session {
+        Session {
+            id: id.to_string(),
+            peer_addr,
+            started_at: Instant::now(),
+            bytes_sent: 0,
+            bytes_received: 0,
+            is_suspicious: true,
+            anomaly_score: 1.0,
+            exfil: Default::default(),
+            strategy: Default::default(),
+            profile: None,
+        }
+    }
+
+    /// Minimaler Honeypot, der ein Banner über den Stream schickt
+    struct BannerHoneypot;
+
+    #[async_trait]
+    impl Honeypot for BannerHoneypot {
+        async fn handle(
+            &self,
+            connection: Connection,
+            session: Session,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            let stream = connection.memory_stream.ok_or("no memory stream")?;
+            let mut stream = stream.lock().await;
+            let banner = format!("SSH-2.0-OpenSSH_8.2p1 {}\r\n", session.peer_addr);
+            stream.write_all(banner.as_bytes()).await?;
+            Ok(())
+        }
+
+        fn port(&self) -> u16 {
+            22
+        }
+
+        fn service_type(&self) -> HoneypotType {
+            HoneypotType::Ssh
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_reaches_honeypot_handler() {
+        let network = MemoryNetwork::new();
+        let server_addr: SocketAddr = "10.0.0.1:22".parse().unwrap();
+        let client_addr: SocketAddr = "203.0.113.5:40000".parse().unwrap();
+        let server = network.bind(server_addr).unwrap();
+        let client = network.bind(client_addr).unwrap();
+
+        let handler = tokio::spawn(async move {
+            let (connection, peer_addr) = server.accept().await.unwrap();
+            assert_eq!(connection.dest_port(), Some(22));
+            let session = test_session("memory-test", peer_addr);
+            BannerHoneypot.handle(connection, session).await.unwrap();
+            peer_addr
+        });
+
+        let connection = client.connect(server_addr, "localhost").await.unwrap();
+        assert_eq!(connection.peer_addr, server_addr);
+
+        let mut banner = vec![0; 64];
+        let stream = connection.memory_stream.unwrap();
+        let n = stream.lock().await.read(&mut banner).await.unwrap();
+        let banner = String::from_utf8_lossy(&banner[..n]);
+
+        assert_eq!(handler.await.unwrap(), client_addr);
+        assert!(banner.starts_with("SSH-2.0-OpenSSH_8.2p1"));
+        assert!(banner.contains("203.0.113.5:40000"));
+    }
+
+    #[tokio::test]
+    async fn test_connect_to_unbound_address_refused() {
+        let network = MemoryNetwork::new();
+        let client = network.bind("127.0.0.1:1".parse().unwrap()).unwrap();
+        let result = client.connect("127.0.0.1:2".parse().unwrap(), "localhost").await;
+        assert!(result.is_err());
+    }
+}
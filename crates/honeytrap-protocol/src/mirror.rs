@@ -0,0 +1,366 @@
+//! Traffic Mirroring
+//!
+//! Spiegelt rohe eingehende Bytes pro Session an eine externe Analyse-Pipeline
+//! (IDS, Offline-ML). Jeder Chunk wird mit einem kleinen Header gerahmt:
+//!
+//! ```text
+//! u16 session_id_len | session_id | u64 timestamp_micros | u32 payload_len | payload
+//! ```
+//!
+//! Alle Integer sind Big-Endian. Der Mirror hängt als `InboundTap` am
+//! `TrackedStream` der Session. Frames gehen über eine begrenzte Queue an
+//! einen eigenen Writer-Thread, damit blockierende Sinks (Datei, Socket) nie
+//! den Read-Pfad aufhalten. Bei voller Queue und bei Sink-Fehlern wird
+//! verworfen bzw. geloggt, die Honeypot-Session läuft immer weiter.
+
+use honeytrap_deception::InboundTap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::{mpsc, oneshot};
+
+/// Max. wartende Frames vor dem Writer-Thread
+pub const DEFAULT_MIRROR_QUEUE: usize = 1024;
+
+/// Gerahmter Chunk eingehender Bytes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorFrame {
+    pub session_id: String,
+    /// Unix-Timestamp in Mikrosekunden
+    pub timestamp_micros: u64,
+    pub payload: Vec<u8>,
+}
+
+impl MirrorFrame {
+    /// Frame serialisieren
+    pub fn encode(&self) -> Vec<u8> {
+        let session_id = self.session_id.as_bytes();
+        let session_len = session_id.len().min(u16::MAX as usize);
+
+        let mut buf = Vec::with_capacity(2 + session_len + 8 + 4 + self.payload.len());
+        buf.extend_from_slice(&(session_len as u16).to_be_bytes());
+        buf.extend_from_slice(&session_id[..session_len]);
+        buf.extend_from_slice(&self.timestamp_micros.to_be_bytes());
+        buf.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&self.payload);
+        buf
+    }
+
+    /// Frame vom Anfang des Puffers lesen, liefert Frame + verbrauchte Bytes
+    pub fn decode(buf: &[u8]) -> Option<(Self, usize)> {
+        let session_len = u16::from_be_bytes(buf.get(..2)?.try_into().ok()?) as usize;
+        let mut offset = 2;
+
+        let session_id = buf.get(offset..offset + session_len)?;
+        offset += session_len;
+        let timestamp_micros = u64::from_be_bytes(buf.get(offset..offset + 8)?.try_into().ok()?);
+        offset += 8;
+        let payload_len =
+            u32::from_be_bytes(buf.get(offset..offset + 4)?.try_into().ok()?) as usize;
+        offset += 4;
+        let payload = buf.get(offset..offset + payload_len)?;
+        offset += payload_len;
+
+        Some((
+            Self {
+                session_id: String::from_utf8_lossy(session_id).into_owned(),
+                timestamp_micros,
+                payload: payload.to_vec(),
+            },
+            offset,
+        ))
+    }
+}
+
+/// Ziel für gespiegelte Frames
+pub trait MirrorSink: Send + Sync {
+    /// Kodierten Frame weitergeben
+    fn send(&self, frame: &[u8]) -> io::Result<()>;
+}
+
+/// In-Memory Sink (Tests, Debugging)
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    frames: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bisher empfangene Frames (dekodiert)
+    pub fn frames(&self) -> Vec<MirrorFrame> {
+        self.frames
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|raw| MirrorFrame::decode(raw).map(|(frame, _)| frame))
+            .collect()
+    }
+}
+
+impl MirrorSink for MemorySink {
+    fn send(&self, frame: &[u8]) -> io::Result<()> {
+        self.frames.lock().unwrap().push(frame.to_vec());
+        Ok(())
+    }
+}
+
+/// Hängt Frames an eine Datei an
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl MirrorSink for FileSink {
+    fn send(&self, frame: &[u8]) -> io::Result<()> {
+        self.file.lock().unwrap().write_all(frame)
+    }
+}
+
+/// Sendet jeden Frame als Datagramm an einen Unix Socket
+#[cfg(unix)]
+pub struct UnixSocketSink {
+    socket: std::os::unix::net::UnixDatagram,
+}
+
+#[cfg(unix)]
+impl UnixSocketSink {
+    pub fn connect(path: impl AsRef<Path>) -> io::Result<Self> {
+        let socket = std::os::unix::net::UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket })
+    }
+}
+
+#[cfg(unix)]
+impl MirrorSink for UnixSocketSink {
+    fn send(&self, frame: &[u8]) -> io::Result<()> {
+        self.socket.send(frame).map(|_| ())
+    }
+}
+
+/// Auftrag an den Writer-Thread
+enum MirrorCommand {
+    Frame {
+        session_id: String,
+        frame: Vec<u8>,
+        payload_len: usize,
+    },
+    Flush(oneshot::Sender<()>),
+}
+
+/// Spiegelt rohe Bytes an einen Sink
+#[derive(Debug, Clone)]
+pub struct TrafficMirror {
+    tx: mpsc::Sender<MirrorCommand>,
+    mirrored_bytes: Arc<AtomicU64>,
+    dropped_frames: Arc<AtomicU64>,
+}
+
+impl TrafficMirror {
+    /// Mirror mit eigenem Writer-Thread für `sink`
+    pub fn new(sink: Arc<dyn MirrorSink>) -> io::Result<Self> {
+        Self::with_capacity(sink, DEFAULT_MIRROR_QUEUE)
+    }
+
+    /// Mirror mit max. `capacity` wartenden Frames
+    pub fn with_capacity(sink: Arc<dyn MirrorSink>, capacity: usize) -> io::Result<Self> {
+        let (tx, mut rx) = mpsc::channel(capacity.max(1));
+        let mirrored_bytes = Arc::new(AtomicU64::new(0));
+
+        let written = mirrored_bytes.clone();
+        std::thread::Builder::new()
+            .name("traffic-mirror".to_string())
+            .spawn(move || {
+                while let Some(command) = rx.blocking_recv() {
+                    match command {
+                        MirrorCommand::Frame {
+                            session_id,
+                            frame,
+                            payload_len,
+                        } => match sink.send(&frame) {
+                            Ok(()) => {
+                                written.fetch_add(payload_len as u64, Ordering::Relaxed);
+                            }
+                            Err(e) => tracing::warn!(
+                                "⚠️ Traffic mirror failed ({} bytes): {} (Session: {})",
+                                payload_len,
+                                e,
+                                session_id
+                            ),
+                        },
+                        MirrorCommand::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            tx,
+            mirrored_bytes,
+            dropped_frames: Arc::new(AtomicU64::new(0)),
+        })
+    }
+
+    /// Eingehende Bytes einer Session spiegeln (blockiert nie)
+    pub fn mirror(&self, session_id: &str, payload: &[u8]) {
+        if payload.is_empty() {
+            return;
+        }
+
+        let frame = MirrorFrame {
+            session_id: session_id.to_string(),
+            timestamp_micros: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_micros() as u64)
+                .unwrap_or_default(),
+            payload: payload.to_vec(),
+        };
+        let command = MirrorCommand::Frame {
+            session_id: frame.session_id.clone(),
+            frame: frame.encode(),
+            payload_len: payload.len(),
+        };
+
+        if self.tx.try_send(command).is_err() {
+            self.dropped_frames.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!(
+                "⚠️ Traffic mirror queue full, dropped {} bytes (Session: {})",
+                payload.len(),
+                session_id
+            );
+        }
+    }
+
+    /// Warten, bis alle bisher gespiegelten Frames beim Sink sind
+    pub async fn flush(&self) {
+        let (done, flushed) = oneshot::channel();
+        if self.tx.send(MirrorCommand::Flush(done)).await.is_ok() {
+            let _ = flushed.await;
+        }
+    }
+
+    /// Tap für die eingehenden Bytes einer Session
+    pub fn for_session(&self, session_id: &str) -> SessionMirror {
+        SessionMirror {
+            mirror: self.clone(),
+            session_id: session_id.to_string(),
+        }
+    }
+
+    /// Erfolgreich gespiegelte Payload-Bytes
+    pub fn mirrored_bytes(&self) -> u64 {
+        self.mirrored_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Wegen voller Queue verworfene Frames
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped_frames.load(Ordering::Relaxed)
+    }
+}
+
+/// `TrafficMirror` gebunden an eine Session
+#[derive(Debug, Clone)]
+pub struct SessionMirror {
+    mirror: TrafficMirror,
+    session_id: String,
+}
+
+impl InboundTap for SessionMirror {
+    fn inbound(&self, bytes: &[u8]) {
+        self.mirror.mirror(&self.session_id, bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_deception::TrafficCounter;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn test_inbound_bytes_mirrored_with_framing() {
+        let sink = MemorySink::new();
+        let mirror = TrafficMirror::new(Arc::new(sink.clone())).unwrap();
+
+        let traffic = TrafficCounter::new();
+        traffic.tap_inbound(Arc::new(mirror.for_session("session-42")));
+        let (mut attacker, server) = tokio::io::duplex(1024);
+        let mut stream = traffic.track(server);
+
+        attacker.write_all(b"SSH-2.0-libssh\r\n").await.unwrap();
+        let mut buf = [0u8; 64];
+        let n = stream.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"SSH-2.0-libssh\r\n");
+
+        attacker.write_all(b"\x00\x01\xff").await.unwrap();
+        let n2 = stream.read(&mut buf).await.unwrap();
+
+        // Ausgehende Bytes werden gezählt, aber nicht gespiegelt
+        stream
+            .write_all(b"SSH-2.0-OpenSSH_8.2p1\r\n")
+            .await
+            .unwrap();
+        assert_eq!(traffic.stats().bytes_sent, (n + n2) as u64);
+        assert_eq!(traffic.stats().bytes_received, 23);
+
+        mirror.flush().await;
+        let frames = sink.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].session_id, "session-42");
+        assert_eq!(frames[0].payload, b"SSH-2.0-libssh\r\n");
+        assert_eq!(frames[1].payload, b"\x00\x01\xff");
+        assert!(frames[0].timestamp_micros > 0);
+        assert!(frames[0].timestamp_micros <= frames[1].timestamp_micros);
+        assert_eq!(mirror.mirrored_bytes(), (n + n2) as u64);
+
+        // Header-Layout: u16 len | id | u64 ts | u32 len | payload
+        let raw = frames[1].encode();
+        assert_eq!(&raw[..2], &10u16.to_be_bytes());
+        assert_eq!(&raw[2..12], b"session-42");
+        assert_eq!(&raw[20..24], &3u32.to_be_bytes());
+        assert_eq!(MirrorFrame::decode(&raw[..raw.len() - 1]), None);
+    }
+
+    /// Sink, der bis zur Freigabe blockiert
+    struct StalledSink(Mutex<std::sync::mpsc::Receiver<()>>);
+
+    impl MirrorSink for StalledSink {
+        fn send(&self, _frame: &[u8]) -> io::Result<()> {
+            let _ = self.0.lock().unwrap().recv();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocked_sink_never_stalls_reads() {
+        let (release, stalled) = std::sync::mpsc::channel();
+        let mirror =
+            TrafficMirror::with_capacity(Arc::new(StalledSink(Mutex::new(stalled))), 2).unwrap();
+
+        // Writer hängt im ersten Frame, Queue fasst zwei weitere
+        for _ in 0..10 {
+            mirror.mirror("stalled", b"payload");
+        }
+        assert!(mirror.dropped_frames() >= 7);
+
+        drop(release);
+        mirror.flush().await;
+        assert!(mirror.mirrored_bytes() <= 3 * 7);
+    }
+}
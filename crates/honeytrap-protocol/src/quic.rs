@@ -1,62 +1,257 @@
-use honeytrap_deception::Connection;
-use quinn::{Endpoint, ServerConfig};
-use rustls::pki_types::{CertificateDer, PrivatePkcs8KeyDer};
+use crate::early_data::{EarlyDataMonitor, EarlyDataSocket};
+use crate::handshake::{quic_failure_reason, HandshakeMonitor};
+use crate::transport::Transport;
+use async_trait::async_trait;
+use honeytrap_deception::{CaptureSender, Connection};
+use quinn::{Endpoint, EndpointConfig, IdleTimeout, ServerConfig, TransportConfig, VarInt};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::server::danger::ClientCertVerifier;
+use rustls::server::WebPkiClientVerifier;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+
+/// Max. etablierte, noch nicht per `accept` abgeholte Connections
+const ACCEPT_QUEUE: usize = 64;
+
+/// Etablierte Connection aus einem Handshake-Task
+type Established = (quinn::Connection, SocketAddr);
+
+/// QUIC Transport-Parameter (Idle Timeout, Stream-Limits, Keep-Alive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransportParams {
+    /// Idle Timeout (`None` = unbegrenzt)
+    pub max_idle_timeout: Option<Duration>,
+    /// Max. gleichzeitige bidirektionale Streams pro Connection
+    pub max_concurrent_bidi_streams: u32,
+    /// Keep-Alive Intervall (`None` = deaktiviert)
+    pub keep_alive_interval: Option<Duration>,
+    /// 0-RTT Early Data annehmen (replaybar!)
+    pub enable_0rtt: bool,
+}
+
+impl Default for TransportParams {
+    /// Entspricht den Quinn-Defaults
+    fn default() -> Self {
+        Self {
+            max_idle_timeout: Some(Duration::from_secs(30)),
+            max_concurrent_bidi_streams: 100,
+            keep_alive_interval: None,
+            enable_0rtt: false,
+        }
+    }
+}
+
+impl TransportParams {
+    /// In Quinn TransportConfig umwandeln
+    fn to_transport_config(self) -> Result<TransportConfig, Box<dyn std::error::Error>> {
+        let mut transport = TransportConfig::default();
+        let idle_timeout = self
+            .max_idle_timeout
+            .map(IdleTimeout::try_from)
+            .transpose()?;
+        transport
+            .max_idle_timeout(idle_timeout)
+            .max_concurrent_bidi_streams(VarInt::from_u32(self.max_concurrent_bidi_streams))
+            .keep_alive_interval(self.keep_alive_interval);
+        Ok(transport)
+    }
+}
 
 /// Secure QUIC Transport mit Quinn
 pub struct SecureQuicTransport {
     endpoint: Endpoint,
     bind_addr: SocketAddr,
+    early_data: Arc<EarlyDataMonitor>,
+    handshakes: Arc<HandshakeMonitor>,
+    /// Handshake-Tasks liefern hierüber etablierte Connections an `accept`
+    established_tx: mpsc::Sender<Established>,
+    established: Mutex<mpsc::Receiver<Established>>,
 }
 
 impl SecureQuicTransport {
     /// Neuer QUIC Server mit selbst-signiertem Zertifikat
     pub async fn new_server(bind_addr: SocketAddr) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new_server_with_params(bind_addr, TransportParams::default()).await
+    }
+
+    /// Neuer QUIC Server mit eigenen Transport-Parametern
+    pub async fn new_server_with_params(
+        bind_addr: SocketAddr,
+        params: TransportParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new_server_with_certs_and_params(bind_addr, None, None, params).await
+    }
+
+    /// Neuer QUIC Server mit Zertifikat aus PEM-Dateien
+    ///
+    /// Ohne Pfade wird ein selbst-signiertes Zertifikat generiert.
+    pub async fn new_server_with_certs(
+        bind_addr: SocketAddr,
+        cert_path: Option<&str>,
+        key_path: Option<&str>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::new_server_with_certs_and_params(
+            bind_addr,
+            cert_path,
+            key_path,
+            TransportParams::default(),
+        )
+        .await
+    }
+
+    /// Neuer QUIC Server mit Zertifikat aus PEM-Dateien und eigenen Transport-Parametern
+    pub async fn new_server_with_certs_and_params(
+        bind_addr: SocketAddr,
+        cert_path: Option<&str>,
+        key_path: Option<&str>,
+        params: TransportParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::start_server(bind_addr, cert_path, key_path, None, params).await
+    }
+
+    /// Neuer QUIC Server mit mTLS - Clients brauchen ein Zertifikat der CA aus `client_ca_path`
+    ///
+    /// SANs des Client-Zertifikats landen in `Connection::client_sans`.
+    pub async fn new_server_with_mtls(
+        bind_addr: SocketAddr,
+        cert_path: Option<&str>,
+        key_path: Option<&str>,
+        client_ca_path: &str,
+        params: TransportParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        install_crypto_provider();
+        let verifier = client_verifier(client_ca_path)?;
+        Self::start_server(bind_addr, cert_path, key_path, Some(verifier), params).await
+    }
+
+    async fn start_server(
+        bind_addr: SocketAddr,
+        cert_path: Option<&str>,
+        key_path: Option<&str>,
+        client_verifier: Option<Arc<dyn ClientCertVerifier>>,
+        params: TransportParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         tracing::info!("🔐 Initializing QUIC server on {}", bind_addr);
+        install_crypto_provider();
 
-        // Selbst-signiertes Zertifikat generieren
-        let (cert, key) = generate_self_signed_cert()?;
+        let (certs, key) = server_identity(cert_path, key_path)?;
 
         // Server-Konfiguration
-        let server_config = configure_server(cert, key)?;
-
-        // QUIC Endpoint erstellen
-        let endpoint = Endpoint::server(server_config, bind_addr)?;
+        let server_config = configure_server(certs, key, client_verifier, params)?;
+
+        // QUIC Endpoint erstellen - Socket gewrappt, um 0-RTT Versuche zu sehen
+        let early_data = Arc::new(EarlyDataMonitor::new(params.enable_0rtt));
+        let handshakes = Arc::new(HandshakeMonitor::new("quic"));
+        let runtime = quinn::default_runtime().ok_or("No async runtime found")?;
+        let socket = Arc::new(
+            EarlyDataSocket::new(
+                runtime.wrap_udp_socket(std::net::UdpSocket::bind(bind_addr)?)?,
+                early_data.clone(),
+            )
+            .with_handshake_monitor(handshakes.clone()),
+        );
+        let endpoint = Endpoint::new_with_abstract_socket(
+            EndpointConfig::default(),
+            Some(server_config),
+            socket,
+            runtime,
+        )?;
+
+        tracing::info!(
+            "✅ QUIC endpoint ready on {} (0-RTT {})",
+            bind_addr,
+            if params.enable_0rtt { "enabled" } else { "disabled" }
+        );
 
-        tracing::info!("✅ QUIC endpoint ready on {}", bind_addr);
+        Ok(Self::from_endpoint(endpoint, bind_addr, early_data, handshakes))
+    }
 
-        Ok(Self {
+    fn from_endpoint(
+        endpoint: Endpoint,
+        bind_addr: SocketAddr,
+        early_data: Arc<EarlyDataMonitor>,
+        handshakes: Arc<HandshakeMonitor>,
+    ) -> Self {
+        let (established_tx, established) = mpsc::channel(ACCEPT_QUEUE);
+        Self {
             endpoint,
             bind_addr,
-        })
+            early_data,
+            handshakes,
+            established_tx,
+            established: Mutex::new(established),
+        }
     }
 
-    /// Connection akzeptieren
-    pub async fn accept(&self) -> Result<(Connection, SocketAddr), Box<dyn std::error::Error>> {
-        // Warte auf eingehende QUIC-Verbindung
-        let incoming = self.endpoint.accept().await.ok_or("Endpoint closed")?;
+    /// 0-RTT Beobachtung (Versuche auch bei deaktiviertem 0-RTT)
+    pub fn early_data(&self) -> &EarlyDataMonitor {
+        &self.early_data
+    }
 
-        let peer_addr = incoming.remote_address();
-        tracing::debug!("📥 Accepting QUIC connection from {}", peer_addr);
+    /// Fehlgeschlagene/abgebrochene Handshakes (Scanner)
+    pub fn handshakes(&self) -> &HandshakeMonitor {
+        &self.handshakes
+    }
 
-        // Connection etablieren
-        let quinn_connection = incoming.await?;
+    /// Connection akzeptieren
+    ///
+    /// Jeder Handshake läuft in einem eigenen Task, ein hängender Client
+    /// blockiert also keine weiteren. Fehlgeschlagene Handshakes werden erfasst
+    /// und übersprungen, statt den Accept-Loop abzubrechen.
+    pub async fn accept(&self) -> Result<(Connection, SocketAddr), Box<dyn std::error::Error>> {
+        let mut established = self.established.lock().await;
+        let (quinn_connection, peer_addr) = loop {
+            tokio::select! {
+                Some(connection) = established.recv() => break connection,
+                // Warte auf eingehende QUIC-Verbindung
+                incoming = self.endpoint.accept() => {
+                    self.spawn_handshake(incoming.ok_or("Endpoint closed")?);
+                }
+            }
+        };
+        drop(established);
 
         tracing::info!("✅ QUIC connection established with {}", peer_addr);
 
         // In unsere Connection-Struktur konvertieren
-        let connection = Connection {
-            peer_addr,
-            quinn_connection: Some(Arc::new(quinn_connection)),
-        };
+        let client_sans = client_sans(&quinn_connection);
+        let mut connection = Connection::with_quic(peer_addr, Arc::new(quinn_connection))
+            .with_local_addr(self.endpoint.local_addr()?);
+        if let Some(sans) = client_sans {
+            tracing::info!("🪪 mTLS client {} presented SANs {:?}", peer_addr, sans);
+            connection = connection.with_client_identity(sans);
+        }
 
         Ok((connection, peer_addr))
     }
 
+    /// Handshake im Hintergrund abschließen, Ergebnis an `accept` bzw. den Monitor
+    fn spawn_handshake(&self, incoming: quinn::Incoming) {
+        let peer_addr = incoming.remote_address();
+        tracing::debug!("📥 Accepting QUIC connection from {}", peer_addr);
+
+        let established = self.established_tx.clone();
+        let handshakes = self.handshakes.clone();
+        tokio::spawn(async move {
+            match incoming.await {
+                Ok(connection) => {
+                    // Schlägt nur fehl, wenn der Transport schon weg ist
+                    let _ = established.send((connection, peer_addr)).await;
+                }
+                Err(quinn::ConnectionError::LocallyClosed) => {}
+                Err(e) => {
+                    handshakes.record(peer_addr, quic_failure_reason(&e), &e.to_string());
+                }
+            }
+        });
+    }
+
     /// Client-Endpoint erstellen (für ausgehende Verbindungen)
     pub async fn new_client() -> Result<Self, Box<dyn std::error::Error>> {
         tracing::info!("🔐 Initializing QUIC client");
+        install_crypto_provider();
 
         let mut endpoint = Endpoint::client("0.0.0.0:0".parse()?)?;
 
@@ -72,10 +267,12 @@ impl SecureQuicTransport {
 
         endpoint.set_default_client_config(client_config);
 
-        Ok(Self {
+        Ok(Self::from_endpoint(
             endpoint,
-            bind_addr: "0.0.0.0:0".parse()?,
-        })
+            "0.0.0.0:0".parse()?,
+            Arc::new(EarlyDataMonitor::default()),
+            Arc::new(HandshakeMonitor::new("quic")),
+        ))
     }
 
     /// Mit Remote-Server verbinden
@@ -90,17 +287,14 @@ impl SecureQuicTransport {
 
         tracing::info!("✅ Connected to {}", addr);
 
-        let connection = Connection {
-            peer_addr: addr,
-            quinn_connection: Some(Arc::new(quinn_connection)),
-        };
+        let connection = Connection::with_quic(addr, Arc::new(quinn_connection));
 
         Ok(connection)
     }
 
-    /// Lokale Adresse abrufen
+    /// Lokale Adresse abrufen (tatsächlich gebundener Port, auch bei Port 0)
     pub fn local_addr(&self) -> SocketAddr {
-        self.bind_addr
+        self.endpoint.local_addr().unwrap_or(self.bind_addr)
     }
 
     /// Endpoint graceful shutdown
@@ -124,20 +318,115 @@ fn generate_self_signed_cert() -> Result<(CertificateDer<'static>, PrivatePkcs8K
     Ok((cert_der, key))
 }
 
+/// rustls-Provider prozessweit setzen (mehrere Provider über Feature-Unification aktiv)
+fn install_crypto_provider() {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+}
+
+/// Zertifikatskette + Key: aus PEM-Dateien oder selbst-signiert
+fn server_identity(
+    cert_path: Option<&str>,
+    key_path: Option<&str>,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), Box<dyn std::error::Error>> {
+    match (cert_path, key_path) {
+        (Some(cert_path), Some(key_path)) => load_certs(cert_path, key_path),
+        (None, None) => {
+            let (cert, key) = generate_self_signed_cert()?;
+            Ok((vec![cert], key.into()))
+        }
+        _ => Err("TLS cert_path and key_path must be configured together".into()),
+    }
+}
+
+/// PEM-Zertifikatskette und Private Key laden
+fn load_certs(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), Box<dyn std::error::Error>> {
+    tracing::debug!("🔑 Loading TLS certificate from {}", cert_path);
+
+    let open = |path: &str, what: &str| {
+        std::fs::File::open(path)
+            .map(std::io::BufReader::new)
+            .map_err(|e| format!("Failed to read TLS {} {}: {}", what, path, e))
+    };
+
+    let certs = rustls_pemfile::certs(&mut open(cert_path, "certificate")?)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Invalid TLS certificate {}: {}", cert_path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", cert_path).into());
+    }
+
+    let key = rustls_pemfile::private_key(&mut open(key_path, "private key")?)
+        .map_err(|e| format!("Invalid TLS private key {}: {}", key_path, e))?
+        .ok_or_else(|| format!("No private key found in {}", key_path))?;
+
+    tracing::info!("🔑 Loaded {} TLS certificates from {}", certs.len(), cert_path);
+
+    Ok((certs, key))
+}
+
+/// Verifier für Client-Zertifikate gegen ein PEM CA-Bundle
+fn client_verifier(
+    client_ca_path: &str,
+) -> Result<Arc<dyn ClientCertVerifier>, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(client_ca_path)
+        .map_err(|e| format!("Failed to read TLS client CA {}: {}", client_ca_path, e))?;
+
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in rustls_pemfile::certs(&mut std::io::BufReader::new(file)) {
+        let cert = cert.map_err(|e| format!("Invalid TLS client CA {}: {}", client_ca_path, e))?;
+        roots.add(cert)?;
+    }
+    if roots.is_empty() {
+        return Err(format!("No CA certificates found in {}", client_ca_path).into());
+    }
+
+    tracing::info!("🪪 mTLS enabled with {} client CAs from {}", roots.len(), client_ca_path);
+
+    Ok(WebPkiClientVerifier::builder(Arc::new(roots)).build()?)
+}
+
+/// SANs des verifizierten Client-Zertifikats (`None` ohne mTLS)
+fn client_sans(connection: &quinn::Connection) -> Option<Vec<String>> {
+    let certs = connection
+        .peer_identity()?
+        .downcast::<Vec<CertificateDer<'static>>>()
+        .ok()?;
+    let cert = webpki::EndEntityCert::try_from(certs.first()?).ok()?;
+
+    Some(
+        cert.valid_dns_names()
+            .chain(cert.valid_uri_names())
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
 /// Server-Konfiguration mit TLS
 fn configure_server(
-    cert: CertificateDer<'static>,
-    key: PrivatePkcs8KeyDer<'static>,
+    certs: Vec<CertificateDer<'static>>,
+    key: PrivateKeyDer<'static>,
+    client_verifier: Option<Arc<dyn ClientCertVerifier>>,
+    params: TransportParams,
 ) -> Result<ServerConfig, Box<dyn std::error::Error>> {
     tracing::debug!("⚙️  Configuring QUIC server");
 
-    let crypto = rustls::ServerConfig::builder()
-        .with_no_client_auth()
-        .with_single_cert(vec![cert], key.into())?;
-
-    let server_config = ServerConfig::with_crypto(Arc::new(
+    let builder = rustls::ServerConfig::builder();
+    let builder = match client_verifier {
+        Some(verifier) => builder.with_client_cert_verifier(verifier),
+        None => builder.with_no_client_auth(),
+    };
+    let mut crypto = builder.with_single_cert(certs, key)?;
+    // QUIC erlaubt nur 0 oder u32::MAX. Tickets sind stateful und single-use
+    // (rustls Session Cache), ein Replay desselben Tickets wird abgelehnt.
+    crypto.max_early_data_size = if params.enable_0rtt { u32::MAX } else { 0 };
+
+    let mut server_config = ServerConfig::with_crypto(Arc::new(
         quinn::crypto::rustls::QuicServerConfig::try_from(crypto)?
     ));
+    server_config.transport_config(Arc::new(params.to_transport_config()?));
 
     tracing::debug!("✅ Server configuration ready");
 
@@ -187,3 +476,433 @@ impl rustls::client::danger::ServerCertVerifier for SkipServerVerification {
         ]
     }
 }
+
+#[async_trait]
+impl Transport for SecureQuicTransport {
+    async fn accept(&self) -> Result<(Connection, SocketAddr), Box<dyn std::error::Error>> {
+        SecureQuicTransport::accept(self).await
+    }
+
+    async fn connect(
+        &self,
+        addr: SocketAddr,
+        server_name: &str,
+    ) -> Result<Connection, Box<dyn std::error::Error>> {
+        SecureQuicTransport::connect(self, addr, server_name).await
+    }
+
+    fn local_addr(&self) -> SocketAddr {
+        SecureQuicTransport::local_addr(self)
+    }
+
+    async fn close(&self) {
+        SecureQuicTransport::close(self).await
+    }
+
+    fn forward_captures(&self, captures: CaptureSender) {
+        self.handshakes.forward_to(captures);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use honeytrap_deception::{CaptureEvent, CaptureKind, Session};
+
+    #[test]
+    fn test_idle_timeout_applied_to_transport_config() {
+        // rustls ist mit aws-lc-rs und ring gebaut -> Provider explizit wählen
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let (cert, key) = generate_self_signed_cert().unwrap();
+        let params = TransportParams {
+            max_idle_timeout: Some(Duration::from_millis(120_000)),
+            max_concurrent_bidi_streams: 8,
+            keep_alive_interval: Some(Duration::from_secs(15)),
+            enable_0rtt: false,
+        };
+
+        let server_config = configure_server(vec![cert], key.into(), None, params).unwrap();
+        let transport = format!("{:?}", server_config.transport);
+        assert!(transport.contains("max_idle_timeout: Some(120000)"));
+        assert!(transport.contains("max_concurrent_bidi_streams: 8"));
+        assert!(transport.contains("keep_alive_interval: Some(15s)"));
+    }
+
+    /// Verdächtige Session für den Honeypot-Handler
+    fn test_session(id: &str, peer_addr: SocketAddr) -> Session {
+        Session {
+            id: id.to_string(),
+            peer_addr,
+            started_at: std::time::Instant::now(),
+            bytes_sent: 0,
+            bytes_received: 0,
+            is_suspicious: true,
+            anomaly_score: 1.0,
+            exfil: Default::default(),
+            strategy: Default::default(),
+            profile: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ssh_honeypot_over_quic_stream_counts_traffic() {
+        use honeytrap_deception::{Honeypot, SshHoneypot};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let server = SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+        let server_addr = server.local_addr();
+        let honeypot = tokio::spawn(async move {
+            let (connection, peer_addr) = server.accept().await.unwrap();
+            let session = test_session("quic-ssh", peer_addr);
+            SshHoneypot::new(22)
+                .handle(connection.clone(), session)
+                .await
+                .unwrap();
+            (connection, server)
+        });
+
+        let client = SecureQuicTransport::new_client().await.unwrap();
+        let connection = client.connect(server_addr, "localhost").await.unwrap();
+        let (mut send, mut recv) = connection.open_bi().await.unwrap();
+        let input = b"root\ntoor\nid\nexit\n";
+        send.write_all(input).await.unwrap();
+        send.get_mut().finish().unwrap();
+        let mut output = Vec::new();
+        recv.read_to_end(&mut output).await.unwrap();
+
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.starts_with("SSH-2.0-"));
+        assert!(output.contains("login: "));
+        assert!(output.contains("uid="));
+
+        let (server_connection, _server) = honeypot.await.unwrap();
+        let stats = server_connection.traffic.stats();
+        assert_eq!(stats.bytes_sent, input.len() as u64);
+        assert_eq!(stats.bytes_received, output.len() as u64);
+        assert_eq!(stats.failed_logins, 1);
+        assert_eq!(stats.commands, 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_with_certs_from_files() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let dir = std::env::temp_dir().join(format!("honeytrap-certs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert = rcgen::generate_simple_self_signed(vec!["honeytrap.local".to_string()]).unwrap();
+        let cert_path = dir.join("cert.pem").display().to_string();
+        let key_path = dir.join("key.pem").display().to_string();
+        std::fs::write(&cert_path, cert.cert.pem()).unwrap();
+        std::fs::write(&key_path, cert.key_pair.serialize_pem()).unwrap();
+
+        let (certs, _) = load_certs(&cert_path, &key_path).unwrap();
+        assert_eq!(certs[0].as_ref(), cert.cert.der().as_ref());
+        SecureQuicTransport::new_server_with_certs(
+            "127.0.0.1:0".parse().unwrap(),
+            Some(&cert_path),
+            Some(&key_path),
+        )
+        .await
+        .unwrap();
+
+        // Fehlender Key und Zertifikat statt Key
+        let missing = dir.join("missing.pem").display().to_string();
+        let err = load_certs(&cert_path, &missing).unwrap_err().to_string();
+        assert!(err.contains("Failed to read TLS private key"));
+        assert!(err.contains("missing.pem"));
+        let err = load_certs(&cert_path, &cert_path).unwrap_err().to_string();
+        assert!(err.starts_with("No private key found in"));
+        assert!(server_identity(Some(&cert_path), None).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Echo-Server für einen einzelnen Stream
+    async fn echo_once(endpoint: Endpoint) -> quinn::Connection {
+        let connection = endpoint.accept().await.unwrap().await.unwrap();
+        let (mut send, mut recv) = connection.accept_bi().await.unwrap();
+        let data = recv.read_to_end(1024).await.unwrap();
+        send.write_all(&data).await.unwrap();
+        send.finish().unwrap();
+        connection
+    }
+
+    async fn round_trip(connection: &quinn::Connection, payload: &[u8]) -> Vec<u8> {
+        let (mut send, mut recv) = connection.open_bi().await.unwrap();
+        send.write_all(payload).await.unwrap();
+        send.finish().unwrap();
+        recv.read_to_end(1024).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_zero_rtt_attempt_rejected_when_disabled() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let bind_addr: SocketAddr = "127.0.0.1:0".parse().unwrap();
+
+        // Server A mit 0-RTT liefert dem Client ein Early-Data-fähiges Ticket
+        let enabled = SecureQuicTransport::new_server_with_params(
+            bind_addr,
+            TransportParams {
+                enable_0rtt: true,
+                ..TransportParams::default()
+            },
+        )
+        .await
+        .unwrap();
+        let disabled = SecureQuicTransport::new_server(bind_addr).await.unwrap();
+
+        let mut crypto = rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification))
+            .with_no_client_auth();
+        crypto.enable_early_data = true;
+        let mut client = Endpoint::client(bind_addr).unwrap();
+        client.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(crypto).unwrap(),
+        )));
+
+        let server = tokio::spawn(echo_once(enabled.endpoint.clone()));
+        let connection = client
+            .connect(enabled.endpoint.local_addr().unwrap(), "localhost")
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(round_trip(&connection, b"ticket").await, b"ticket");
+        connection.close(0u32.into(), b"done");
+        server.await.unwrap();
+
+        // Server B (0-RTT deaktiviert): Client versucht trotzdem Early Data
+        let server = tokio::spawn(echo_once(disabled.endpoint.clone()));
+        let (connection, accepted) = client
+            .connect(disabled.endpoint.local_addr().unwrap(), "localhost")
+            .unwrap()
+            .into_0rtt()
+            .unwrap_or_else(|_| panic!("client has no 0-RTT ticket"));
+        let (mut early, _) = connection.open_bi().await.unwrap();
+        let _ = early.write_all(b"early").await;
+
+        assert!(!accepted.await, "early data must not be accepted");
+        assert_eq!(round_trip(&connection, b"1-rtt").await, b"1-rtt");
+        server.await.unwrap();
+
+        assert_eq!(disabled.early_data().attempts(), 1);
+        assert!(disabled.early_data().packets() >= 1);
+    }
+
+    /// Wartet, bis der Monitor `count` Handshake-Fehler gesehen hat
+    async fn wait_for_failures(transport: &SecureQuicTransport, count: u64) -> Vec<CaptureEvent> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while transport.handshakes().failures() < count {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("no handshake failure recorded");
+        transport.handshakes().recent()
+    }
+
+    #[tokio::test]
+    async fn test_invalid_initial_recorded_as_handshake_failure() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let server = SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+            .await
+            .unwrap();
+
+        // Long-Header Initial mit unbekannter Version, wie von QUIC-Scannern
+        let mut initial = vec![0xc0, 0x1a, 0x2a, 0x3a, 0x4a, 8, 1, 2, 3, 4, 5, 6, 7, 8, 0];
+        initial.resize(1200, 0);
+        let probe = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        probe
+            .send_to(&initial, server.endpoint.local_addr().unwrap())
+            .await
+            .unwrap();
+
+        let events = wait_for_failures(&server, 1).await;
+        let probe_addr = probe.local_addr().unwrap();
+        assert_eq!(events[0].source_ip, Some(probe_addr.ip()));
+        assert_eq!(
+            events[0].kind,
+            CaptureKind::HandshakeFailure {
+                transport: "quic".to_string(),
+                peer_addr: probe_addr,
+                reason: "version_mismatch".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aborted_handshake_does_not_stop_accept_loop() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let server = Arc::new(
+            SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+                .await
+                .unwrap(),
+        );
+        let server_addr = server.endpoint.local_addr().unwrap();
+        let accepting = tokio::spawn({
+            let server = server.clone();
+            async move { server.accept().await.map(|(_, peer)| peer).unwrap() }
+        });
+
+        // Client ohne Trust Anchors bricht den TLS-Handshake ab
+        let strict = rustls::ClientConfig::builder()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let mut scanner = Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        scanner.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(strict).unwrap(),
+        )));
+        assert!(scanner
+            .connect(server_addr, "localhost")
+            .unwrap()
+            .await
+            .is_err());
+
+        let events = wait_for_failures(&server, 1).await;
+        assert!(matches!(
+            &events[0].kind,
+            CaptureKind::HandshakeFailure { peer_addr, reason, .. }
+                if *peer_addr == scanner.local_addr().unwrap() && reason == "peer_closed"
+        ));
+
+        // Nächster regulärer Client wird weiterhin angenommen
+        let client = SecureQuicTransport::new_client().await.unwrap();
+        let _connection = client.connect(server_addr, "localhost").await.unwrap();
+        assert_eq!(
+            accepting.await.unwrap().port(),
+            client.endpoint.local_addr().unwrap().port()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stalled_handshake_does_not_delay_next_client() {
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let server = Arc::new(
+            SecureQuicTransport::new_server("127.0.0.1:0".parse().unwrap())
+                .await
+                .unwrap(),
+        );
+        let server_addr = server.endpoint.local_addr().unwrap();
+
+        // Relay reicht nur das erste Initial des Clients weiter, danach kommt
+        // der Handshake nie zum Abschluss (bis zum Idle Timeout)
+        let relay = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let relay_addr = relay.local_addr().unwrap();
+        let stalled = SecureQuicTransport::new_client().await.unwrap();
+        let _stalled = tokio::spawn(async move {
+            let _ = stalled.connect(relay_addr, "localhost").await;
+        });
+        let mut initial = vec![0; 2048];
+        let (len, _) = relay.recv_from(&mut initial).await.unwrap();
+        relay.send_to(&initial[..len], server_addr).await.unwrap();
+
+        let accepting = tokio::spawn({
+            let server = server.clone();
+            async move { server.accept().await.map(|(_, peer)| peer).unwrap() }
+        });
+        // Erst wenn der Server am hängenden Handshake sitzt, kommt der zweite Client
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let client = SecureQuicTransport::new_client().await.unwrap();
+        let _connection = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.connect(server_addr, "localhost"),
+        )
+        .await
+        .expect("second client stuck behind stalled handshake")
+        .unwrap();
+
+        let peer = tokio::time::timeout(Duration::from_secs(5), accepting)
+            .await
+            .expect("accept stuck behind stalled handshake")
+            .unwrap();
+        assert_eq!(peer.port(), client.endpoint.local_addr().unwrap().port());
+        assert_ne!(peer, relay_addr);
+    }
+
+    /// Client-Endpoint, der optional ein Client-Zertifikat vorlegt
+    fn mtls_client(identity: Option<(&rcgen::Certificate, &rcgen::KeyPair)>) -> Endpoint {
+        let builder = rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(SkipServerVerification));
+        let crypto = match identity {
+            Some((cert, key)) => builder
+                .with_client_auth_cert(
+                    vec![cert.der().clone()],
+                    PrivatePkcs8KeyDer::from(key.serialize_der()).into(),
+                )
+                .unwrap(),
+            None => builder.with_no_client_auth(),
+        };
+        let mut endpoint = Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+        endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(
+            quinn::crypto::rustls::QuicClientConfig::try_from(crypto).unwrap(),
+        )));
+        endpoint
+    }
+
+    #[tokio::test]
+    async fn test_mtls_accepts_ca_signed_client_and_rejects_others() {
+        use rcgen::{
+            BasicConstraints, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+        };
+
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        let ca_key = KeyPair::generate().unwrap();
+        let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let ca = ca_params.self_signed(&ca_key).unwrap();
+
+        let issue = |san: &str, issuer: Option<(&rcgen::Certificate, &KeyPair)>| {
+            let key = KeyPair::generate().unwrap();
+            let mut params = CertificateParams::new(vec![san.to_string()]).unwrap();
+            params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+            let cert = match issuer {
+                Some((ca, ca_key)) => params.signed_by(&key, ca, ca_key).unwrap(),
+                None => params.self_signed(&key).unwrap(),
+            };
+            (cert, key)
+        };
+        let (trusted, trusted_key) = issue("agent.corp.internal", Some((&ca, &ca_key)));
+        let (rogue, rogue_key) = issue("agent.corp.internal", None);
+
+        let ca_path = std::env::temp_dir().join(format!("honeytrap-ca-{}.pem", std::process::id()));
+        std::fs::write(&ca_path, ca.pem()).unwrap();
+        let server = Arc::new(
+            SecureQuicTransport::new_server_with_mtls(
+                "127.0.0.1:0".parse().unwrap(),
+                None,
+                None,
+                &ca_path.display().to_string(),
+                TransportParams::default(),
+            )
+            .await
+            .unwrap(),
+        );
+        std::fs::remove_file(&ca_path).unwrap();
+        let server_addr = server.endpoint.local_addr().unwrap();
+        let accepting = tokio::spawn({
+            let server = server.clone();
+            async move { server.accept().await.map(|(connection, _)| connection).unwrap() }
+        });
+
+        // Ohne bzw. mit fremdem Zertifikat schlägt der Handshake serverseitig fehl
+        for client in [mtls_client(None), mtls_client(Some((&rogue, &rogue_key)))] {
+            if let Ok(connection) = client.connect(server_addr, "localhost").unwrap().await {
+                let _ = connection.closed().await;
+            }
+        }
+        wait_for_failures(&server, 2).await;
+        assert!(!accepting.is_finished());
+
+        let client = mtls_client(Some((&trusted, &trusted_key)));
+        let _connection = client
+            .connect(server_addr, "localhost")
+            .unwrap()
+            .await
+            .unwrap();
+        let connection = accepting.await.unwrap();
+        assert!(connection.mtls_verified);
+        assert_eq!(connection.client_sans, vec!["agent.corp.internal".to_string()]);
+    }
+}
@@ -88,6 +88,14 @@ impl QuicLineReader {
 
     /// Line lesen (bis \n)
     pub async fn read_line(&mut self) -> io::Result<String> {
+        let line_buf = self.read_line_bytes().await?;
+
+        String::from_utf8(line_buf)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Line als Roh-Bytes lesen (bis \n) - verliert keine Binär-Payloads
+    pub async fn read_line_bytes(&mut self) -> io::Result<Vec<u8>> {
         let mut line_buf = Vec::new();
 
         loop {
@@ -118,8 +126,7 @@ impl QuicLineReader {
             }
         }
 
-        String::from_utf8(line_buf)
-            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        Ok(line_buf)
     }
 
     /// Exakt N bytes lesen
@@ -0,0 +1,31 @@
+//! Transport-Abstraktion
+//!
+//! Gemeinsame `accept`/`connect` Schnittstelle für QUIC und In-Memory Transport,
+//! damit `HoneyTrap` mit beiden betrieben werden kann.
+
+use async_trait::async_trait;
+use honeytrap_deception::{CaptureSender, Connection};
+use std::net::SocketAddr;
+
+/// Transport für eingehende und ausgehende Connections
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Nächste eingehende Connection akzeptieren
+    async fn accept(&self) -> Result<(Connection, SocketAddr), Box<dyn std::error::Error>>;
+
+    /// Mit Remote-Endpoint verbinden
+    async fn connect(
+        &self,
+        addr: SocketAddr,
+        server_name: &str,
+    ) -> Result<Connection, Box<dyn std::error::Error>>;
+
+    /// Lokale Adresse
+    fn local_addr(&self) -> SocketAddr;
+
+    /// Transport schließen
+    async fn close(&self);
+
+    /// Capture-Events des Transports (z.B. abgebrochene Handshakes) weiterreichen
+    fn forward_captures(&self, _captures: CaptureSender) {}
+}
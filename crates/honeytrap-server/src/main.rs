@@ -1,16 +1,49 @@
 use anyhow::{Context, Result};
 use honeytrap_core::{Config, HoneyTrap};
+use honeytrap_metrics::{MetricsExporter, ResourceMonitor, StatsProvider, ThresholdControl, METRICS};
 use signal_hook::consts::signal::*;
 use signal_hook_tokio::Signals;
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
+/// Intervall für System-Metriken
+const RESOURCE_UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+/// `/metrics`, `/health` und `/stats`
+const DEFAULT_METRICS_ADDR: &str = "0.0.0.0:9090";
+/// `PUT /threshold` - eigener Listener, nur Loopback
+const DEFAULT_CONTROL_ADDR: &str = "127.0.0.1:9091";
+
 /// Server-Konfiguration
 #[derive(Debug)]
 struct ServerConfig {
     config_path: PathBuf,
     enable_json_logs: bool,
+    metrics_addr: SocketAddr,
+    /// `PUT /threshold` auf `control_addr` (ohne Auth, daher opt-in)
+    enable_control: bool,
+    /// Listener für den Control-Endpoint, muss Loopback sein
+    control_addr: SocketAddr,
+}
+
+impl ServerConfig {
+    /// Adresse des Control-Listeners, falls aktiviert und auf Loopback
+    fn control_listener(&self) -> Option<SocketAddr> {
+        if !self.enable_control {
+            return None;
+        }
+        if !self.control_addr.ip().is_loopback() {
+            error!(
+                "🎚️ Control endpoint disabled: {} is not a loopback address",
+                self.control_addr
+            );
+            return None;
+        }
+        Some(self.control_addr)
+    }
 }
 
 impl Default for ServerConfig {
@@ -18,6 +51,9 @@ impl Default for ServerConfig {
         Self {
             config_path: PathBuf::from("honeytrap.toml"),
             enable_json_logs: false,
+            metrics_addr: DEFAULT_METRICS_ADDR.parse().unwrap(),
+            enable_control: false,
+            control_addr: DEFAULT_CONTROL_ADDR.parse().unwrap(),
         }
     }
 }
@@ -35,6 +71,8 @@ async fn main() -> Result<()> {
         env!("CARGO_PKG_VERSION")
     );
 
+    let started_at = Instant::now();
+
     // Config laden
     let config = load_config(&server_config.config_path).await?;
     info!(
@@ -42,21 +80,76 @@ async fn main() -> Result<()> {
         server_config.config_path
     );
 
+    METRICS.system.set_build_info(
+        config.network.enable_quic,
+        config.llm.enabled,
+        config.honeypots.len(),
+    );
+
     // HoneyTrap System initialisieren
-    let honeytrap = HoneyTrap::new(config)
-        .await
-        .map_err(|e| anyhow::anyhow!("Failed to initialize HoneyTrap: {}", e))?;
+    let honeytrap = Arc::new(
+        HoneyTrap::new(config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to initialize HoneyTrap: {}", e))?,
+    );
 
     info!("✅ HoneyTrap system initialized");
 
+    // System-Metriken periodisch aktualisieren
+    let router = honeytrap.router.clone();
+    ResourceMonitor::new(started_at).spawn(RESOURCE_UPDATE_INTERVAL, move || {
+        let router = router.clone();
+        async move { router.active_sessions().await as i64 }
+    });
+
+    // Metrics/Health/Stats Endpoint
+    let stats_source = honeytrap.clone();
+    let stats: StatsProvider = Arc::new(move || {
+        let honeytrap = stats_source.clone();
+        Box::pin(async move {
+            serde_json::to_string(&honeytrap.stats().await).unwrap_or_else(|_| "{}".to_string())
+        })
+    });
+    let exporter = MetricsExporter::new(server_config.metrics_addr).with_stats(stats);
+    tokio::spawn(async move {
+        if let Err(e) = exporter.start().await {
+            error!("Metrics endpoint error: {}", e);
+        }
+    });
+
+    // Control-Endpoint nie auf dem (öffentlichen) Metrics-Port
+    if let Some(control_addr) = server_config.control_listener() {
+        warn!(
+            "🎚️ Control endpoint enabled: PUT http://{}/threshold?value=<0.0..=1.0>",
+            control_addr
+        );
+        let control_target = honeytrap.clone();
+        let control: ThresholdControl = Arc::new(move |threshold| {
+            let honeytrap = control_target.clone();
+            Box::pin(async move {
+                honeytrap
+                    .set_anomaly_threshold(threshold)
+                    .await
+                    .map_err(|e| e.to_string())
+            })
+        });
+        let exporter = MetricsExporter::new(control_addr).with_threshold_control(control);
+        tokio::spawn(async move {
+            if let Err(e) = exporter.start().await {
+                error!("Control endpoint error: {}", e);
+            }
+        });
+    }
+
     // Signal Handler Setup
-    let signals =
-        Signals::new([SIGTERM, SIGINT, SIGQUIT]).context("Failed to register signal handlers")?;
+    let signals = Signals::new([SIGTERM, SIGINT, SIGQUIT, SIGHUP])
+        .context("Failed to register signal handlers")?;
     let handle = signals.handle();
 
     // Server starten
+    let server = honeytrap.clone();
     let server_handle = tokio::spawn(async move {
-        if let Err(e) = honeytrap.run().await {
+        if let Err(e) = server.run().await {
             error!("Server error: {:#}", e);
             std::process::exit(1);
         }
@@ -64,7 +157,7 @@ async fn main() -> Result<()> {
 
     // Auf Shutdown-Signal warten
     tokio::select! {
-        _ = wait_for_shutdown_signal(signals) => {
+        _ = wait_for_shutdown_signal(signals, honeytrap.clone()) => {
             info!("🛑 Shutdown signal received, stopping server...");
         }
         _ = server_handle => {
@@ -74,6 +167,9 @@ async fn main() -> Result<()> {
 
     // Cleanup
     handle.close();
+    if let Err(e) = honeytrap.shutdown().await {
+        error!("Shutdown error: {:#}", e);
+    }
     info!("👋 HoneyTrap Server stopped");
 
     Ok(())
@@ -88,6 +184,17 @@ fn parse_env() -> ServerConfig {
         enable_json_logs: std::env::var("HONEYTRAP_JSON_LOGS")
             .map(|v| v == "1" || v.to_lowercase() == "true")
             .unwrap_or(false),
+        metrics_addr: std::env::var("HONEYTRAP_METRICS_ADDR")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| DEFAULT_METRICS_ADDR.parse().unwrap()),
+        enable_control: std::env::var("HONEYTRAP_ENABLE_CONTROL")
+            .map(|v| v == "1" || v.to_lowercase() == "true")
+            .unwrap_or(false),
+        control_addr: std::env::var("HONEYTRAP_CONTROL_ADDR")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_else(|| DEFAULT_CONTROL_ADDR.parse().unwrap()),
     }
 }
 
@@ -118,14 +225,14 @@ async fn load_config(path: &PathBuf) -> Result<Config> {
         .await
         .with_context(|| format!("Failed to read config file: {:?}", path))?;
 
-    let config: Config = toml::from_str(&content)
+    let config = Config::from_toml(&content)
         .with_context(|| format!("Failed to parse config file: {:?}", path))?;
 
     Ok(config)
 }
 
-/// Auf Shutdown-Signal warten
-async fn wait_for_shutdown_signal(mut signals: Signals) {
+/// Auf Shutdown-Signal warten, SIGHUP lädt die Service-Profile neu
+async fn wait_for_shutdown_signal(mut signals: Signals, honeytrap: Arc<HoneyTrap>) {
     use futures::StreamExt;
 
     while let Some(signal) = signals.next().await {
@@ -134,6 +241,16 @@ async fn wait_for_shutdown_signal(mut signals: Signals) {
                 info!("Received signal: {}", signal);
                 break;
             }
+            SIGHUP => {
+                // Eigener Task: SIGTERM/SIGINT bleiben während des Reloads behandelbar
+                info!("Received SIGHUP, reloading service profiles");
+                let honeytrap = honeytrap.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = honeytrap.deception.reload_profiles().await {
+                        warn!("⚠️ Profile reload failed: {}", e);
+                    }
+                });
+            }
             _ => {}
         }
     }
@@ -148,6 +265,22 @@ mod tests {
         let config = ServerConfig::default();
         assert_eq!(config.config_path, PathBuf::from("honeytrap.toml"));
         assert!(!config.enable_json_logs);
+        assert_eq!(config.metrics_addr.port(), 9090);
+        assert!(!config.enable_control);
+        assert_eq!(config.control_listener(), None);
+    }
+
+    #[test]
+    fn test_control_listener_loopback_only() {
+        let mut config = ServerConfig {
+            enable_control: true,
+            ..ServerConfig::default()
+        };
+        assert_eq!(config.control_listener(), Some(config.control_addr));
+        assert!(config.control_addr.ip().is_loopback());
+
+        config.control_addr = "0.0.0.0:9091".parse().unwrap();
+        assert_eq!(config.control_listener(), None);
     }
 
     #[test]